@@ -1,6 +1,9 @@
+use std::collections::HashSet;
+
 use async_tungstenite::tungstenite::{handshake, Message};
 use futures::channel::oneshot::Sender;
 use guilderia_database::events::client::ReadyPayloadFields;
+use guilderia_models::v0::MessageExtension;
 use guilderia_result::{create_error, Result};
 use serde::{Deserialize, Serialize};
 
@@ -11,12 +14,18 @@ pub enum ProtocolFormat {
     Msgpack,
 }
 
+/// Number of servers sent per packet when Ready is chunked
+const READY_CHUNK_SIZE: usize = 50;
+
 /// User-provided protocol configuration
 #[derive(Debug)]
 pub struct ProtocolConfiguration {
     protocol_version: i32,
     format: ProtocolFormat,
     session_token: Option<String>,
+    intents: Option<u32>,
+    chunked_ready: bool,
+    capabilities: HashSet<MessageExtension>,
 }
 
 impl ProtocolConfiguration {
@@ -30,6 +39,9 @@ impl ProtocolConfiguration {
             protocol_version,
             format,
             session_token,
+            intents: None,
+            chunked_ready: false,
+            capabilities: HashSet::new(),
         }
     }
 
@@ -85,6 +97,25 @@ impl ProtocolConfiguration {
         &self.format
     }
 
+    /// Get the intents requested for this connection, if any
+    pub fn get_intents(&self) -> Option<u32> {
+        self.intents
+    }
+
+    /// Get the message extensions supported by the client
+    pub fn get_capabilities(&self) -> &HashSet<MessageExtension> {
+        &self.capabilities
+    }
+
+    /// Get the number of servers to send per packet, if Ready should be chunked
+    pub fn get_ready_chunk_size(&self) -> Option<usize> {
+        if self.chunked_ready {
+            Some(READY_CHUNK_SIZE)
+        } else {
+            None
+        }
+    }
+
     /// Get ready payload fields
     pub fn get_ready_payload_fields(&self) -> Vec<ReadyPayloadFields> {
         vec![
@@ -93,6 +124,8 @@ impl ProtocolConfiguration {
             ReadyPayloadFields::Channels,
             ReadyPayloadFields::Members,
             ReadyPayloadFields::Emoji,
+            ReadyPayloadFields::Bookmarks,
+            ReadyPayloadFields::Experiments,
         ]
     }
 }
@@ -124,6 +157,9 @@ impl handshake::server::Callback for WebsocketHandshakeCallback {
         let mut protocol_version = 1;
         let mut format = ProtocolFormat::Json;
         let mut session_token = None;
+        let mut intents = None;
+        let mut chunked_ready = false;
+        let mut capabilities = HashSet::new();
 
         // Parse and map parameters from key-value to known variables.
         for (key, value) in params {
@@ -139,6 +175,24 @@ impl handshake::server::Callback for WebsocketHandshakeCallback {
                     _ => {}
                 },
                 "token" => session_token = Some(value.into()),
+                "intents" => {
+                    if let Ok(value) = value.parse() {
+                        intents = Some(value);
+                    }
+                }
+                "ready" => chunked_ready = value == "chunked",
+                "capabilities" => {
+                    capabilities = value
+                        .split(',')
+                        .filter_map(|name| match name {
+                            "spoiler" => Some(MessageExtension::Spoiler),
+                            "timestamp" => Some(MessageExtension::Timestamp),
+                            "subtext" => Some(MessageExtension::Subtext),
+                            "effect" => Some(MessageExtension::Effect),
+                            _ => None,
+                        })
+                        .collect();
+                }
                 _ => {}
             }
         }
@@ -151,6 +205,9 @@ impl handshake::server::Callback for WebsocketHandshakeCallback {
                 protocol_version,
                 format,
                 session_token,
+                intents,
+                chunked_ready,
+                capabilities,
             })
             .is_ok()
         {
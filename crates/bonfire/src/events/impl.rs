@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use futures::future::join_all;
+use guilderia_config::config;
 use guilderia_database::{
     events::client::{EventV1, ReadyPayloadFields},
     util::permissions::DatabasePermissionQuery,
@@ -58,6 +59,18 @@ impl Cache {
         viewable_channels
     }
 
+    /// Shape an event for the current connection
+    ///
+    /// Returns false if nothing is left worth sending.
+    pub fn shape_event(&self, event: &mut EventV1) -> bool {
+        self.recipient.shape(event, |channel| {
+            matches!(
+                self.channels.get(channel),
+                Some(Channel::DirectMessage { .. } | Channel::Group { .. })
+            )
+        })
+    }
+
     /// Check whether we can subscribe to another user
     pub fn can_subscribe_to_user(&self, user_id: &str) -> bool {
         if let Some(user) = self.users.get(&self.user_id) {
@@ -199,6 +212,20 @@ impl State {
             None
         };
 
+        // Fetch bookmarks
+        let bookmarks = if fields.contains(&ReadyPayloadFields::Bookmarks) {
+            Some(db.fetch_bookmarks(&user.id).await?)
+        } else {
+            None
+        };
+
+        // Find experiments enabled for the user
+        let experiments = if fields.contains(&ReadyPayloadFields::Experiments) {
+            Some(config().await.experiments_for(&user.id))
+        } else {
+            None
+        };
+
         // Copy data into local state cache.
         self.cache.users = users.iter().cloned().map(|x| (x.id.clone(), x)).collect();
         self.cache
@@ -211,8 +238,13 @@ impl State {
             .collect();
 
         // Make all users appear from our perspective.
-        let mut users: Vec<v0::User> = join_all(users.into_iter().map(|other_user| async {
-            let is_online = online_ids.contains(&other_user.id);
+        let presence = self.cache.recipient.has_intent(v0::BotIntents::Presence);
+        let mut users: Vec<v0::User> = join_all(users.into_iter().map(|mut other_user| async {
+            let is_online = presence && online_ids.contains(&other_user.id);
+            if !presence {
+                other_user.status = None;
+            }
+
             other_user.into_known(&user, is_online).await
         }))
         .await;
@@ -231,7 +263,7 @@ impl State {
         for server in &servers {
             self.insert_subscription(server.id.clone()).await;
 
-            if self.cache.is_bot {
+            if self.cache.is_bot && self.cache.recipient.has_intent(v0::BotIntents::Members) {
                 self.insert_subscription(format!("{}u", server.id)).await;
             }
         }
@@ -265,11 +297,79 @@ impl State {
 
             user_settings,
             channel_unreads: channel_unreads.map(|vec| vec.into_iter().map(Into::into).collect()),
+            bookmarks: bookmarks.map(|vec| vec.into_iter().map(Into::into).collect()),
+            experiments,
 
             policy_changes,
         })
     }
 
+    /// Split a Ready packet so that servers are sent in batches
+    ///
+    /// Ready keeps users, direct messages and the first batch of servers,
+    /// the remaining servers follow in supplements and a final packet
+    /// marks synchronisation as complete.
+    pub fn chunk_ready_payload(ready: EventV1, chunk_size: usize) -> Vec<EventV1> {
+        let EventV1::Ready {
+            users,
+            servers: Some(mut servers),
+            mut channels,
+            mut members,
+            mut emojis,
+            user_settings,
+            channel_unreads,
+            bookmarks,
+            experiments,
+            policy_changes,
+        } = ready
+        else {
+            return vec![ready, EventV1::ReadyComplete];
+        };
+
+        let remaining = servers.split_off(servers.len().min(chunk_size));
+        let mut supplements = vec![];
+        for batch in remaining.chunks(chunk_size) {
+            let ids: HashSet<&str> = batch.iter().map(|server| server.id.as_str()).collect();
+
+            supplements.push(EventV1::ReadySupplement {
+                channels: channels.as_mut().map(|channels| {
+                    take_matching(channels, |channel| match channel {
+                        v0::Channel::TextChannel { server, .. }
+                        | v0::Channel::VoiceChannel { server, .. } => ids.contains(server.as_str()),
+                        _ => false,
+                    })
+                }),
+                members: members.as_mut().map(|members| {
+                    take_matching(members, |member| ids.contains(member.id.server.as_str()))
+                }),
+                emojis: emojis.as_mut().map(|emojis| {
+                    take_matching(emojis, |emoji| match &emoji.parent {
+                        v0::EmojiParent::Server { id } => ids.contains(id.as_str()),
+                        v0::EmojiParent::Detached => false,
+                    })
+                }),
+                servers: Some(batch.to_vec()),
+            });
+        }
+
+        let mut payloads = vec![EventV1::Ready {
+            users,
+            servers: Some(servers),
+            channels,
+            members,
+            emojis,
+            user_settings,
+            channel_unreads,
+            bookmarks,
+            experiments,
+            policy_changes,
+        }];
+
+        payloads.append(&mut supplements);
+        payloads.push(EventV1::ReadyComplete);
+        payloads
+    }
+
     /// Re-determine the currently accessible server channels
     pub async fn recalculate_server(&mut self, db: &Database, id: &str, event: &mut EventV1) {
         if let Some(server) = self.cache.servers.get(id) {
@@ -345,7 +445,7 @@ impl State {
     }
 
     /// Push presence change to the user and all associated server topics
-    pub async fn broadcast_presence_change(&self, target: bool) {
+    pub async fn broadcast_presence_change(&self, db: &Database, target: bool) {
         if if let Some(status) = &self.cache.users.get(&self.cache.user_id).unwrap().status {
             status.presence != Some(Presence::Invisible)
         } else {
@@ -366,6 +466,11 @@ impl State {
             }
 
             event.p(self.cache.user_id.clone()).await;
+
+            let servers: Vec<String> = self.cache.servers.keys().cloned().collect();
+            db.adjust_server_counts(&servers, 0, if target { 1 } else { -1 })
+                .await
+                .ok();
         }
     }
 
@@ -439,6 +544,45 @@ impl State {
                     }
                 }
             }
+            EventV1::ChannelRolePermissionUpdate {
+                id, role_id, diff, ..
+            } => {
+                let could_view: bool = if let Some(channel) = self.cache.channels.get(id) {
+                    self.cache.can_view_channel(db, channel).await
+                } else {
+                    false
+                };
+
+                if let Some(
+                    Channel::TextChannel {
+                        role_permissions,
+                        synced,
+                        ..
+                    }
+                    | Channel::VoiceChannel {
+                        role_permissions,
+                        synced,
+                        ..
+                    },
+                ) = self.cache.channels.get_mut(id)
+                {
+                    role_permissions.insert(role_id.clone(), diff.after.clone().into());
+                    *synced = false;
+                }
+
+                if let Some(channel) = self.cache.channels.get(id) {
+                    let can_view = self.cache.can_view_channel(db, channel).await;
+                    if could_view != can_view {
+                        if can_view {
+                            queue_add = Some(id.clone());
+                            *event = EventV1::ChannelCreate(channel.clone().into());
+                        } else {
+                            queue_remove = Some(id.clone());
+                            *event = EventV1::ChannelDelete { id: id.clone() };
+                        }
+                    }
+                }
+            }
             EventV1::ChannelDelete { id } => {
                 self.remove_subscription(id).await;
                 self.cache.channels.remove(id);
@@ -462,7 +606,7 @@ impl State {
             } => {
                 self.insert_subscription(id.clone()).await;
 
-                if self.cache.is_bot {
+                if self.cache.is_bot && self.cache.recipient.has_intent(v0::BotIntents::Members) {
                     self.insert_subscription(format!("{}u", id)).await;
                 }
 
@@ -566,6 +710,25 @@ impl State {
                     }
                 }
             }
+            EventV1::ServerRoleRanksUpdate { id, ranks } => {
+                if let Some(server) = self.cache.servers.get_mut(id) {
+                    for (role_id, rank) in ranks {
+                        if let Some(role) = server.roles.get_mut(role_id) {
+                            role.rank = *rank;
+                        }
+                    }
+                }
+
+                if let Some(member) = self.cache.members.get(id) {
+                    if member
+                        .roles
+                        .iter()
+                        .any(|role_id| ranks.contains_key(role_id))
+                    {
+                        queue_server = Some(id.clone());
+                    }
+                }
+            }
             EventV1::ServerRoleDelete { id, role_id } => {
                 if let Some(server) = self.cache.servers.get_mut(id) {
                     server.roles.remove(role_id);
@@ -577,6 +740,16 @@ impl State {
                     }
                 }
             }
+            EventV1::ServerBadgeUpdate { id, badge_id, data } => {
+                if let Some(server) = self.cache.servers.get_mut(id) {
+                    server.badges.insert(badge_id.clone(), data.clone().into());
+                }
+            }
+            EventV1::ServerBadgeDelete { id, badge_id } => {
+                if let Some(server) = self.cache.servers.get_mut(id) {
+                    server.badges.remove(badge_id);
+                }
+            }
 
             EventV1::UserUpdate { event_id, .. } => {
                 if let Some(id) = event_id {
@@ -590,6 +763,12 @@ impl State {
                 *event_id = None;
             }
             EventV1::UserRelationship { id, user, .. } => {
+                if user.relationship == v0::RelationshipStatus::Blocked {
+                    self.cache.recipient.blocked.insert(id.clone());
+                } else {
+                    self.cache.recipient.blocked.remove(id);
+                }
+
                 self.cache.users.insert(id.clone(), user.clone().into());
 
                 if self.cache.can_subscribe_to_user(id) {
@@ -616,6 +795,11 @@ impl State {
             _ => {}
         }
 
+        // Tailor the event to this connection.
+        if !self.cache.shape_event(event) {
+            return false;
+        }
+
         // Calculate server permissions if requested.
         if let Some(server_id) = queue_server {
             self.recalculate_server(db, &server_id, event).await;
@@ -633,3 +817,10 @@ impl State {
         true
     }
 }
+
+/// Remove and return all items matching a predicate
+fn take_matching<T>(items: &mut Vec<T>, predicate: impl Fn(&T) -> bool) -> Vec<T> {
+    let (taken, kept) = std::mem::take(items).into_iter().partition(predicate);
+    *items = kept;
+    taken
+}
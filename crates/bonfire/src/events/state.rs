@@ -7,7 +7,9 @@ use std::{
 use async_std::sync::{Mutex, RwLock};
 use lru::LruCache;
 use lru_time_cache::{LruCache as LruTimeCache, TimedEntry};
-use guilderia_database::{Channel, Member, Server, User};
+use guilderia_database::{
+    events::projection::Recipient, Channel, Member, RelationshipStatus, Server, User,
+};
 
 /// Enumeration representing some change in subscriptions
 pub enum SubscriptionStateChange {
@@ -37,6 +39,7 @@ pub enum SubscriptionStateChange {
 pub struct Cache {
     pub user_id: String,
     pub is_bot: bool,
+    pub recipient: Recipient,
 
     pub users: HashMap<String, User>,
     pub channels: HashMap<String, Channel>,
@@ -51,6 +54,7 @@ impl Default for Cache {
         Cache {
             user_id: Default::default(),
             is_bot: false,
+            recipient: Recipient::new(Default::default()),
 
             users: Default::default(),
             channels: Default::default(),
@@ -84,9 +88,18 @@ impl State {
 
         let mut cache: Cache = Cache {
             user_id: user.id.clone(),
+            recipient: Recipient::new(user.id.clone()),
             ..Default::default()
         };
 
+        cache.recipient.blocked = user
+            .relations
+            .iter()
+            .flatten()
+            .filter(|relationship| relationship.status == RelationshipStatus::Blocked)
+            .map(|relationship| relationship.id.clone())
+            .collect();
+
         cache.users.insert(user.id.clone(), user);
 
         State {
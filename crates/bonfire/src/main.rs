@@ -1,7 +1,10 @@
 use std::env;
 
 use async_std::net::TcpListener;
+use futures::{pin_mut, select, FutureExt};
+use guilderia_config::config;
 use guilderia_presence::clear_region;
+use tokio::signal::unix::{signal, SignalKind};
 
 #[macro_use]
 extern crate log;
@@ -10,6 +13,7 @@ pub mod config;
 pub mod events;
 
 mod database;
+mod metrics;
 mod websocket;
 
 #[async_std::main]
@@ -31,12 +35,61 @@ async fn main() {
     let try_socket = TcpListener::bind(bind).await;
     let listener = try_socket.expect("Failed to bind");
 
+    // Expose gateway metrics for scraping if an address was given.
+    if let Ok(metrics_bind) = env::var("METRICS_HOST") {
+        async_std::task::spawn(metrics::serve(metrics_bind));
+    }
+
     // Start accepting new connections and spawn a client for each connection.
-    while let Ok((stream, addr)) = listener.accept().await {
-        async_std::task::spawn(async move {
-            info!("User connected from {addr:?}");
-            websocket::client(database::get_db(), stream, addr).await;
-            info!("User disconnected from {addr:?}");
-        });
+    let shutdown = shutdown_signal().fuse();
+    pin_mut!(shutdown);
+
+    loop {
+        select! {
+            _ = shutdown => break,
+            connection = listener.accept().fuse() => {
+                let Ok((stream, addr)) = connection else {
+                    break;
+                };
+
+                async_std::task::spawn(async move {
+                    info!("User connected from {addr:?}");
+                    let _connection = metrics::Gauge::raise(&metrics::CONNECTIONS);
+                    websocket::client(database::get_db(), stream, addr).await;
+                    info!("User disconnected from {addr:?}");
+                });
+            }
+        }
+    }
+
+    // Stop accepting connections and clean up, bounded by the shutdown deadline.
+    // Clients which are still connected will reconnect to another node.
+    info!("Shutting down");
+    drop(listener);
+
+    let deadline = config().await.shutdown.deadline();
+    let cleanup = async {
+        if !no_clear_region {
+            clear_region(None).await;
+        }
+
+        database::get_db().clone().shutdown().await;
+    };
+
+    if async_std::future::timeout(deadline, cleanup).await.is_err() {
+        warn!("Shutdown deadline elapsed before cleanup finished");
+    }
+}
+
+/// Wait until the process is asked to stop
+async fn shutdown_signal() {
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+    let interrupt = tokio::signal::ctrl_c().fuse();
+    let terminate = terminate.recv().fuse();
+    pin_mut!(interrupt, terminate);
+
+    select! {
+        _ = interrupt => {},
+        _ = terminate => {},
     }
 }
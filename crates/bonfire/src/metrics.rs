@@ -0,0 +1,104 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use async_std::{
+    io::{ReadExt, WriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+/// WebSocket connections currently open
+pub static CONNECTIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Authenticated sessions currently open
+pub static SESSIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Sessions refused for going over the per-account limit
+pub static SESSIONS_REFUSED: AtomicU64 = AtomicU64::new(0);
+
+/// Connections dropped for going silent
+pub static ZOMBIES_REAPED: AtomicU64 = AtomicU64::new(0);
+
+/// Keeps a gauge raised for as long as it is held
+pub struct Gauge(&'static AtomicU64);
+
+impl Gauge {
+    pub fn raise(gauge: &'static AtomicU64) -> Gauge {
+        gauge.fetch_add(1, Ordering::Relaxed);
+        Gauge(gauge)
+    }
+}
+
+impl Drop for Gauge {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Render current values in the Prometheus text format
+fn render() -> String {
+    [
+        (
+            "gateway_connections",
+            "gauge",
+            "WebSocket connections currently open",
+            &CONNECTIONS,
+        ),
+        (
+            "gateway_sessions",
+            "gauge",
+            "Authenticated sessions currently open",
+            &SESSIONS,
+        ),
+        (
+            "gateway_sessions_refused_total",
+            "counter",
+            "Sessions refused for going over the per-account limit",
+            &SESSIONS_REFUSED,
+        ),
+        (
+            "gateway_zombies_reaped_total",
+            "counter",
+            "Connections dropped after missing heartbeats",
+            &ZOMBIES_REAPED,
+        ),
+    ]
+    .iter()
+    .map(|(name, kind, help, value)| {
+        format!(
+            "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {}\n",
+            value.load(Ordering::Relaxed)
+        )
+    })
+    .collect()
+}
+
+/// Answer a scrape, whatever was requested
+async fn respond(mut stream: TcpStream) {
+    let mut buf = [0; 1024];
+    if stream.read(&mut buf).await.is_err() {
+        return;
+    }
+
+    let body = render();
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+
+    stream.write_all(response.as_bytes()).await.ok();
+}
+
+/// Serve metrics for scraping on the given address
+pub async fn serve(bind: String) {
+    let listener = match TcpListener::bind(&bind).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            error!("Failed to bind metrics listener on {bind}: {err:?}");
+            return;
+        }
+    };
+
+    info!("Serving metrics on host {bind}");
+    while let Ok((stream, _)) = listener.accept().await {
+        async_std::task::spawn(respond(stream));
+    }
+}
@@ -1,4 +1,10 @@
-use std::{collections::HashSet, net::SocketAddr, sync::Arc};
+use std::{
+    collections::HashSet,
+    future::Future,
+    net::SocketAddr,
+    sync::{atomic::Ordering, Arc},
+    time::Duration,
+};
 
 use async_tungstenite::WebSocketStream;
 use authifier::AuthifierEvent;
@@ -19,7 +25,7 @@ use guilderia_database::{
     events::{client::EventV1, server::ClientMessage},
     Database, User, UserHint,
 };
-use guilderia_presence::{create_session, delete_session};
+use guilderia_presence::{count_sessions, create_session, delete_session};
 
 use async_std::{
     net::TcpStream,
@@ -31,10 +37,25 @@ use sentry::Level;
 
 use crate::config::{ProtocolConfiguration, WebsocketHandshakeCallback};
 use crate::events::state::{State, SubscriptionStateChange};
+use crate::metrics::{self, Gauge};
 
 type WsReader = SplitStream<WebSocketStream<TcpStream>>;
 type WsWriter = SplitSink<WebSocketStream<TcpStream>, async_tungstenite::tungstenite::Message>;
 
+/// Wait on the connection, giving up if it stays stuck for longer than the heartbeat timeout
+async fn within<T>(timeout: Option<Duration>, future: impl Future<Output = T>) -> Option<T> {
+    match timeout {
+        Some(timeout) => async_std::future::timeout(timeout, future).await.ok(),
+        None => Some(future.await),
+    }
+}
+
+/// Record that a connection was dropped for going silent
+fn reap(addr: SocketAddr) {
+    info!("User {addr:?} missed their heartbeats, dropping connection");
+    metrics::ZOMBIES_REAPED.fetch_add(1, Ordering::Relaxed);
+}
+
 /// Start a new WebSocket client worker given access to the database,
 /// the relevant TCP stream and the remote address of the client.
 pub async fn client(db: &'static Database, stream: TcpStream, addr: SocketAddr) {
@@ -65,10 +86,12 @@ pub async fn client(db: &'static Database, stream: TcpStream, addr: SocketAddr)
 
     // Split the socket for simultaneously read and write.
     let (mut write, mut read) = ws.split();
+    let gateway = guilderia_config::config().await.gateway;
+    let heartbeat_timeout = gateway.heartbeat_timeout();
 
     // If the user has not provided authentication, request information.
     if config.get_session_token().is_none() {
-        while let Ok(Some(message)) = read.try_next().await {
+        while let Some(Ok(Some(message))) = within(heartbeat_timeout, read.try_next()).await {
             if let Ok(ClientMessage::Authenticate { token }) = config.decode(&message) {
                 config.set_session_token(token);
                 break;
@@ -100,8 +123,43 @@ pub async fn client(db: &'static Database, stream: TcpStream, addr: SocketAddr)
 
     info!("User {addr:?} authenticated as @{}", user.username);
 
+    // Refuse connections beyond the account's session limit.
+    if gateway.max_sessions_per_user > 0
+        && count_sessions(&user.id).await as usize >= gateway.max_sessions_per_user
+    {
+        info!("User {addr:?} has too many sessions open, refusing connection");
+        metrics::SESSIONS_REFUSED.fetch_add(1, Ordering::Relaxed);
+        write
+            .send(config.encode(&EventV1::Error {
+                data: create_error!(TooManySessions {
+                    max: gateway.max_sessions_per_user
+                }),
+            }))
+            .await
+            .ok();
+        return;
+    }
+
+    // Resolve which events a bot has declared and is allowed to receive.
+    let intents = if user.bot.is_some() {
+        match db.fetch_bot(&user.id).await {
+            Ok(bot) => bot.effective_intents() & config.get_intents().unwrap_or(u32::MAX),
+            Err(err) => {
+                write
+                    .send(config.encode(&EventV1::Error { data: err }))
+                    .await
+                    .ok();
+                return;
+            }
+        }
+    } else {
+        u32::MAX
+    };
+
     // Create local state.
     let mut state = State::from(user, session_id);
+    state.cache.recipient.intents = intents;
+    state.cache.recipient.capabilities = config.get_capabilities().clone();
     let user_id = state.cache.user_id.clone();
 
     // Notify socket we have authenticated.
@@ -119,16 +177,24 @@ pub async fn client(db: &'static Database, stream: TcpStream, addr: SocketAddr)
         Err(_) => return,
     };
 
-    if report_internal_error!(write.send(config.encode(&ready_payload)).await).is_err() {
-        return;
+    let payloads = match config.get_ready_chunk_size() {
+        Some(chunk_size) => State::chunk_ready_payload(ready_payload, chunk_size),
+        None => vec![ready_payload],
+    };
+
+    for payload in payloads {
+        if report_internal_error!(write.send(config.encode(&payload)).await).is_err() {
+            return;
+        }
     }
 
     // Create presence session.
     let (first_session, session_id) = create_session(&user_id, 0).await;
+    let session_gauge = Gauge::raise(&metrics::SESSIONS);
 
     // If this was the first session, notify other users that we just went online.
     if first_session {
-        state.broadcast_presence_change(true).await;
+        state.broadcast_presence_change(db, true).await;
     }
 
     {
@@ -149,6 +215,7 @@ pub async fn client(db: &'static Database, stream: TcpStream, addr: SocketAddr)
             &mut state,
             addr,
             &config,
+            heartbeat_timeout,
             topic_signal_r,
             kill_signal_1_r,
             &write,
@@ -162,6 +229,7 @@ pub async fn client(db: &'static Database, stream: TcpStream, addr: SocketAddr)
             active_servers,
             user_id.clone(),
             &config,
+            heartbeat_timeout,
             topic_signal_s,
             kill_signal_2_r,
             read,
@@ -171,12 +239,14 @@ pub async fn client(db: &'static Database, stream: TcpStream, addr: SocketAddr)
 
         join!(listener, worker);
     }
+    drop(session_gauge);
+
     // Clean up presence session.
     let last_session = delete_session(&user_id, session_id).await;
 
     // If this was the last session, notify other users that we just went offline.
     if last_session {
-        state.broadcast_presence_change(false).await;
+        state.broadcast_presence_change(db, false).await;
     }
 }
 
@@ -186,6 +256,7 @@ async fn listener_with_kill_signal(
     state: &mut State,
     addr: SocketAddr,
     config: &ProtocolConfiguration,
+    heartbeat_timeout: Option<Duration>,
     topic_signal_r: async_channel::Receiver<()>,
     kill_signal_r: async_channel::Receiver<()>,
     write: &Mutex<WsWriter>,
@@ -196,6 +267,7 @@ async fn listener_with_kill_signal(
         state,
         addr,
         config,
+        heartbeat_timeout,
         topic_signal_r,
         kill_signal_r,
         write,
@@ -204,11 +276,13 @@ async fn listener_with_kill_signal(
     kill_signal_s.send(()).await.ok();
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn listener(
     db: &'static Database,
     state: &mut State,
     addr: SocketAddr,
     config: &ProtocolConfiguration,
+    heartbeat_timeout: Option<Duration>,
     topic_signal_r: async_channel::Receiver<()>,
     kill_signal_r: async_channel::Receiver<()>,
     write: &Mutex<WsWriter>,
@@ -354,7 +428,13 @@ async fn listener(
                     }
                 }
 
-                let result = write.lock().await.send(config.encode(&event)).await;
+                // Clients which stop reading would otherwise hold up the listener forever
+                let send = async { write.lock().await.send(config.encode(&event)).await };
+                let Some(result) = within(heartbeat_timeout, send).await else {
+                    reap(addr);
+                    break 'out;
+                };
+
                 if let Err(e) = result {
                     use async_tungstenite::tungstenite::Error;
                     if !matches!(e, Error::AlreadyClosed | Error::ConnectionClosed) {
@@ -384,6 +464,7 @@ async fn worker_with_kill_signal(
     active_servers: Arc<Mutex<lru_time_cache::LruCache<String, ()>>>,
     user_id: String,
     config: &ProtocolConfiguration,
+    heartbeat_timeout: Option<Duration>,
     topic_signal_s: async_channel::Sender<()>,
     kill_signal_r: async_channel::Receiver<()>,
     read: WsReader,
@@ -396,6 +477,7 @@ async fn worker_with_kill_signal(
         active_servers,
         user_id,
         config,
+        heartbeat_timeout,
         topic_signal_s,
         kill_signal_r,
         read,
@@ -412,13 +494,15 @@ async fn worker(
     active_servers: Arc<Mutex<lru_time_cache::LruCache<String, ()>>>,
     user_id: String,
     config: &ProtocolConfiguration,
+    heartbeat_timeout: Option<Duration>,
     topic_signal_s: async_channel::Sender<()>,
     kill_signal_r: async_channel::Receiver<()>,
     mut read: WsReader,
     write: &Mutex<WsWriter>,
 ) {
     loop {
-        let t1 = read.try_next().fuse();
+        // Any message from the client, including pings, counts as a heartbeat
+        let t1 = within(heartbeat_timeout, read.try_next()).fuse();
         let t2 = kill_signal_r.recv().fuse();
 
         pin_mut!(t1, t2);
@@ -428,6 +512,11 @@ async fn worker(
                 return;
             },
             result = t1 => {
+                let Some(result) = result else {
+                    reap(addr);
+                    return;
+                };
+
                 let msg = match result {
                     Ok(Some(msg)) => msg,
                     Ok(None) => {
@@ -0,0 +1,584 @@
+//! Configuration doctor
+//!
+//! Every service binary accepts `--check`, which loads the configuration,
+//! checks everything the service needs up front and prints what is wrong
+//! instead of starting, so mistakes surface before the first request
+//! rather than as a panic deep inside it.
+use std::{
+    fmt,
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use crate::{proxy::IpRange, read, CdnSigning, SearchEngine, Settings};
+
+/// How long to wait for a dependency to accept a connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Service being checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Service {
+    /// API server (delta)
+    Api,
+    /// File server (autumn)
+    Files,
+    /// Embed proxy (january)
+    Proxy,
+    /// Scheduled tasks (crond)
+    Crond,
+}
+
+impl Service {
+    /// Port this service listens on
+    fn port(&self) -> Option<u16> {
+        match self {
+            Service::Api => Some(14702),
+            Service::Files => Some(14704),
+            Service::Proxy => Some(14705),
+            Service::Crond => None,
+        }
+    }
+}
+
+/// How serious a problem is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The service will not work
+    Error,
+    /// Some functionality will be unavailable
+    Warning,
+}
+
+/// Problem found with the configuration or environment
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Configuration key or dependency the problem is with
+    pub subject: String,
+    /// What is wrong and how to fix it
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn error(subject: &str, message: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            subject: subject.to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(subject: &str, message: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Warning,
+            subject: subject.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self.severity {
+            Severity::Error => "\x1b[31merror\x1b[0m",
+            Severity::Warning => "\x1b[33mwarning\x1b[0m",
+        };
+
+        write!(f, "{label} [{}]: {}", self.subject, self.message)
+    }
+}
+
+/// Whether the binary was started with `--check`
+pub fn requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--check")
+}
+
+/// Decode base64 in either the standard or URL-safe alphabet, padded or not
+fn decode_base64(value: &str) -> Option<Vec<u8>> {
+    let value = value.trim_end_matches('=');
+    let mut bytes = Vec::with_capacity(value.len() * 3 / 4);
+    let mut buffer = 0_u32;
+    let mut bits = 0;
+
+    for c in value.chars() {
+        let digit = match c {
+            'A'..='Z' => c as u32 - 'A' as u32,
+            'a'..='z' => c as u32 - 'a' as u32 + 26,
+            '0'..='9' => c as u32 - '0' as u32 + 52,
+            '+' | '-' => 62,
+            '/' | '_' => 63,
+            _ => return None,
+        };
+
+        buffer = (buffer << 6) | digit;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    if bits >= 6 {
+        return None;
+    }
+
+    Some(bytes)
+}
+
+/// Extract the first `host:port` from a connection string
+fn socket_address(uri: &str, default_port: u16) -> Option<String> {
+    let (_, rest) = uri.split_once("://")?;
+    let authority = rest.split(['/', '?']).next()?;
+    let host = authority.rsplit('@').next()?.split(',').next()?;
+    if host.is_empty() {
+        return None;
+    }
+
+    if host
+        .rsplit_once(':')
+        .is_some_and(|(_, port)| port.parse::<u16>().is_ok())
+    {
+        Some(host.to_string())
+    } else {
+        Some(format!("{host}:{default_port}"))
+    }
+}
+
+/// Check a dependency accepts TCP connections
+fn check_reachable(diagnostics: &mut Vec<Diagnostic>, subject: &str, address: &str) {
+    let reachable = address.to_socket_addrs().ok().is_some_and(|mut addrs| {
+        addrs.any(|addr| TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).is_ok())
+    });
+
+    if !reachable {
+        diagnostics.push(Diagnostic::error(
+            subject,
+            format!("could not connect to {address}, check the service is running and reachable from here"),
+        ));
+    }
+}
+
+/// Check a setting is not empty
+fn check_present(diagnostics: &mut Vec<Diagnostic>, subject: &str, value: &str) -> bool {
+    if value.is_empty() {
+        diagnostics.push(Diagnostic::error(subject, "must be set"));
+        false
+    } else {
+        true
+    }
+}
+
+/// Check a base64 encoded key decodes to the expected number of bytes
+fn check_key(diagnostics: &mut Vec<Diagnostic>, subject: &str, value: &str, length: usize) {
+    match decode_base64(value) {
+        Some(bytes) if bytes.len() == length => {}
+        Some(bytes) => diagnostics.push(Diagnostic::error(
+            subject,
+            format!(
+                "must be a base64 encoded {length} byte key, got {} bytes",
+                bytes.len()
+            ),
+        )),
+        None => diagnostics.push(Diagnostic::error(subject, "is not valid base64")),
+    }
+}
+
+impl Settings {
+    /// Check the configuration is complete and well formed for a service
+    pub fn check(&self, service: Service) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+
+        check_present(&mut diagnostics, "database.mongodb", &self.database.mongodb);
+        if matches!(service, Service::Api | Service::Files) {
+            check_present(&mut diagnostics, "database.redis", &self.database.redis);
+        }
+
+        for (index, range) in self.proxy.trusted_ranges.iter().enumerate() {
+            if IpRange::parse(range).is_none() {
+                diagnostics.push(Diagnostic::error(
+                    &format!("proxy.trusted_ranges[{index}]"),
+                    "must be an address or a range in CIDR notation",
+                ));
+            }
+        }
+
+        if service == Service::Api {
+            check_present(&mut diagnostics, "rabbit.host", &self.rabbit.host);
+            for (subject, value) in [
+                ("hosts.app", &self.hosts.app),
+                ("hosts.api", &self.hosts.api),
+                ("hosts.events", &self.hosts.events),
+                ("hosts.autumn", &self.hosts.autumn),
+            ] {
+                check_present(&mut diagnostics, subject, value);
+            }
+
+            if self.hosts.january.is_empty() {
+                diagnostics.push(Diagnostic::warning(
+                    "hosts.january",
+                    "not set, links will not be embedded",
+                ));
+            }
+
+            if self.api.smtp.host.is_empty() {
+                diagnostics.push(Diagnostic::warning(
+                    "api.smtp.host",
+                    "not set, emails such as verification and password resets will not be sent",
+                ));
+            }
+
+            if self.api.security.captcha.hcaptcha_key.is_empty() {
+                diagnostics.push(Diagnostic::warning(
+                    "api.security.captcha.hcaptcha_key",
+                    "not set, captchas will not be required",
+                ));
+            }
+
+            if self.api.provisioning.oidc.disable_password_login
+                && self.api.provisioning.oidc.issuer.is_empty()
+            {
+                diagnostics.push(Diagnostic::error(
+                    "api.provisioning.oidc.issuer",
+                    "password login is disabled but no OIDC issuer is configured, nobody will be able to sign in",
+                ));
+            }
+
+            if !self.api.security.interactions_signing_key.is_empty() {
+                check_key(
+                    &mut diagnostics,
+                    "api.security.interactions_signing_key",
+                    &self.api.security.interactions_signing_key,
+                    32,
+                );
+            }
+
+            self.check_vapid(&mut diagnostics);
+            self.check_federation(&mut diagnostics);
+
+            if self.database.search.engine != SearchEngine::Database {
+                check_present(
+                    &mut diagnostics,
+                    "database.search.url",
+                    &self.database.search.url,
+                );
+                check_present(
+                    &mut diagnostics,
+                    "database.search.index",
+                    &self.database.search.index,
+                );
+            }
+        }
+
+        if matches!(service, Service::Files | Service::Crond) {
+            for (subject, value) in [
+                ("files.s3.endpoint", &self.files.s3.endpoint),
+                ("files.s3.access_key_id", &self.files.s3.access_key_id),
+                (
+                    "files.s3.secret_access_key",
+                    &self.files.s3.secret_access_key,
+                ),
+                ("files.s3.default_bucket", &self.files.s3.default_bucket),
+            ] {
+                check_present(&mut diagnostics, subject, value);
+            }
+
+            if check_present(
+                &mut diagnostics,
+                "files.encryption_key",
+                &self.files.encryption_key,
+            ) {
+                check_key(
+                    &mut diagnostics,
+                    "files.encryption_key",
+                    &self.files.encryption_key,
+                    32,
+                );
+            }
+        }
+
+        if service == Service::Files {
+            self.check_cdn(&mut diagnostics);
+            self.check_hash_matching(&mut diagnostics);
+        }
+
+        if service == Service::Proxy && self.api.security.january_key.is_empty() {
+            diagnostics.push(Diagnostic::warning(
+                "api.security.january_key",
+                "not set, anyone who can reach January can use it to fetch embeds",
+            ));
+        }
+
+        diagnostics
+    }
+
+    /// Check web push keys are a matching pair in the expected format
+    fn check_vapid(&self, diagnostics: &mut Vec<Diagnostic>) {
+        let vapid = &self.pushd.vapid;
+        match (vapid.private_key.is_empty(), vapid.public_key.is_empty()) {
+            (true, true) => {
+                diagnostics.push(Diagnostic::warning(
+                    "pushd.vapid",
+                    "no keys set, web push notifications are disabled",
+                ));
+                return;
+            }
+            (true, false) | (false, true) => {
+                diagnostics.push(Diagnostic::error(
+                    "pushd.vapid",
+                    "both private_key and public_key must be set",
+                ));
+                return;
+            }
+            (false, false) => {}
+        }
+
+        match decode_base64(&vapid.private_key).map(String::from_utf8) {
+            Some(Ok(pem)) if pem.contains("-----BEGIN") && pem.contains("PRIVATE KEY-----") => {}
+            _ => diagnostics.push(Diagnostic::error(
+                "pushd.vapid.private_key",
+                "must be a URL-safe base64 encoded PEM private key, generate one with `openssl ecparam -name prime256v1 -genkey | base64 -w0 | tr '+/' '-_' | tr -d '='`",
+            )),
+        }
+
+        match decode_base64(&vapid.public_key) {
+            Some(bytes) if bytes.len() == 65 && bytes[0] == 4 => {}
+            _ => diagnostics.push(Diagnostic::error(
+                "pushd.vapid.public_key",
+                "must be a URL-safe base64 encoded uncompressed P-256 public key (65 bytes)",
+            )),
+        }
+    }
+
+    /// Check CDN credentials can be signed
+    fn check_cdn(&self, diagnostics: &mut Vec<Diagnostic>) {
+        let cdn = &self.files.cdn;
+        if cdn.signing != CdnSigning::None {
+            check_present(diagnostics, "files.cdn.url", &cdn.url);
+        }
+
+        match cdn.signing {
+            CdnSigning::None => {
+                if !cdn.url.is_empty() && cdn.origin_secret.is_empty() {
+                    diagnostics.push(Diagnostic::warning(
+                        "files.cdn.origin_secret",
+                        "not set, files can be fetched from autumn directly without going through the CDN",
+                    ));
+                }
+            }
+            CdnSigning::Cloudfront => {
+                check_present(
+                    diagnostics,
+                    "files.cdn.cloudfront_key_pair_id",
+                    &cdn.cloudfront_key_pair_id,
+                );
+
+                if !cdn.cloudfront_private_key.contains("PRIVATE KEY-----") {
+                    diagnostics.push(Diagnostic::error(
+                        "files.cdn.cloudfront_private_key",
+                        "must be the PEM encoded private key of the CloudFront key pair",
+                    ));
+                }
+            }
+            CdnSigning::Cloudflare => {
+                if cdn.cloudflare_secret.len() < 32 {
+                    diagnostics.push(Diagnostic::error(
+                        "files.cdn.cloudflare_secret",
+                        "HMAC secrets must be at least 32 characters long",
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Check hash matching has a list to match against and somewhere to report to
+    fn check_hash_matching(&self, diagnostics: &mut Vec<Diagnostic>) {
+        let hash_matching = &self.files.hash_matching;
+        if !hash_matching.enabled {
+            return;
+        }
+
+        if check_present(
+            diagnostics,
+            "files.hash_matching.hash_list",
+            &hash_matching.hash_list,
+        ) && !std::path::Path::new(&hash_matching.hash_list).is_file()
+        {
+            diagnostics.push(Diagnostic::error(
+                "files.hash_matching.hash_list",
+                "file does not exist",
+            ));
+        }
+
+        if hash_matching.hooks.is_empty() {
+            diagnostics.push(Diagnostic::warning(
+                "files.hash_matching.hooks",
+                "none set, matches will only be reported to the moderation team",
+            ));
+        }
+
+        for (index, hook) in hash_matching.hooks.iter().enumerate() {
+            if hook.secret.is_empty() {
+                diagnostics.push(Diagnostic::warning(
+                    &format!("files.hash_matching.hooks[{index}].secret"),
+                    "not set, reports will not be signed",
+                ));
+            }
+        }
+    }
+
+    /// Check federation peers can sign requests
+    fn check_federation(&self, diagnostics: &mut Vec<Diagnostic>) {
+        if !self.federation.enabled {
+            return;
+        }
+
+        check_present(diagnostics, "federation.host", &self.federation.host);
+        for peer in &self.federation.peers {
+            let subject = format!("federation.peers[{}].secret", peer.host);
+            if peer.secret.len() < 32 {
+                diagnostics.push(Diagnostic::error(
+                    &subject,
+                    "HMAC secrets must be at least 32 characters long",
+                ));
+            }
+        }
+    }
+
+    /// Check dependencies declared in the configuration can be reached
+    pub fn check_connectivity(&self, service: Service) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+
+        if self.database.mongodb.starts_with("mongodb+srv://") {
+            diagnostics.push(Diagnostic::warning(
+                "database.mongodb",
+                "SRV connection strings cannot be checked",
+            ));
+        } else if let Some(address) = socket_address(&self.database.mongodb, 27017) {
+            check_reachable(&mut diagnostics, "database.mongodb", &address);
+        }
+
+        if matches!(service, Service::Api | Service::Files) {
+            if let Some(address) = socket_address(&self.database.redis, 6379) {
+                check_reachable(&mut diagnostics, "database.redis", &address);
+            }
+        }
+
+        if service == Service::Api {
+            let default_port = match self.database.search.engine {
+                SearchEngine::Database => None,
+                SearchEngine::Meilisearch => Some(7700),
+                SearchEngine::Elasticsearch => Some(9200),
+            };
+
+            if let Some(address) =
+                default_port.and_then(|port| socket_address(&self.database.search.url, port))
+            {
+                check_reachable(&mut diagnostics, "database.search.url", &address);
+            }
+        }
+
+        if service == Service::Api && !self.rabbit.host.is_empty() {
+            check_reachable(
+                &mut diagnostics,
+                "rabbit",
+                &format!("{}:{}", self.rabbit.host, self.rabbit.port),
+            );
+        }
+
+        if matches!(service, Service::Files | Service::Crond) {
+            if let Some(address) = socket_address(&self.files.s3.endpoint, 443) {
+                check_reachable(&mut diagnostics, "files.s3.endpoint", &address);
+            }
+        }
+
+        if service == Service::Files && !self.files.clamd_host.is_empty() {
+            check_reachable(&mut diagnostics, "files.clamd_host", &self.files.clamd_host);
+        }
+
+        diagnostics
+    }
+}
+
+/// Check the port a service listens on is free
+fn check_port(service: Service) -> Vec<Diagnostic> {
+    let Some(port) = service.port() else {
+        return vec![];
+    };
+
+    match TcpListener::bind(("0.0.0.0", port)) {
+        Ok(_) => vec![],
+        Err(err) => vec![Diagnostic::error(
+            "port",
+            format!("cannot listen on port {port} ({err}), stop whatever is already using it"),
+        )],
+    }
+}
+
+/// Run every check for a service, print the results and return an exit code
+pub async fn run(service: Service) -> i32 {
+    println!(":: Checking configuration for {service:?} ::");
+
+    let settings = match read().await.try_deserialize::<Settings>() {
+        Ok(settings) => settings,
+        Err(err) => {
+            println!(
+                "{}",
+                Diagnostic::error("config", format!("could not be loaded: {err}"))
+            );
+            return 1;
+        }
+    };
+
+    let mut diagnostics = settings.check(service);
+    diagnostics.append(&mut settings.check_connectivity(service));
+    diagnostics.append(&mut check_port(service));
+
+    for diagnostic in &diagnostics {
+        println!("{diagnostic}");
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .count();
+
+    if errors == 0 {
+        println!("\x1b[32mok\x1b[0m: {} warning(s)", diagnostics.len());
+        0
+    } else {
+        println!("\x1b[31mfailed\x1b[0m: {errors} error(s)");
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_base64, socket_address};
+
+    #[test]
+    fn decodes_base64() {
+        assert_eq!(decode_base64("aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(decode_base64("aGVsbG8").unwrap(), b"hello");
+        assert_eq!(decode_base64("-_8").unwrap(), vec![0xfb, 0xff]);
+        assert!(decode_base64("not base64!").is_none());
+        assert!(decode_base64("a").is_none());
+    }
+
+    #[test]
+    fn extracts_socket_address() {
+        assert_eq!(
+            socket_address("mongodb://user:pass@db:27018,db2/guilderia?tls=true", 27017).unwrap(),
+            "db:27018"
+        );
+        assert_eq!(
+            socket_address("redis://localhost/", 6379).unwrap(),
+            "localhost:6379"
+        );
+        assert_eq!(
+            socket_address("https://s3.example.com", 443).unwrap(),
+            "s3.example.com:443"
+        );
+        assert!(socket_address("localhost", 80).is_none());
+    }
+}
@@ -6,6 +6,9 @@ use futures_locks::RwLock;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 
+pub mod doctor;
+pub mod proxy;
+
 pub use sentry::{capture_error, capture_message, Level};
 pub use sentry_anyhow::capture_anyhow;
 
@@ -85,10 +88,68 @@ static CONFIG_BUILDER: Lazy<RwLock<Config>> = Lazy::new(|| {
     })
 });
 
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct DatabaseBatching {
+    pub enabled: bool,
+    pub flush_interval_ms: u64,
+    pub max_batch_size: usize,
+}
+
+impl Default for DatabaseBatching {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            flush_interval_ms: 10,
+            max_batch_size: 100,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchEngine {
+    /// Use the database's own text search
+    #[default]
+    Database,
+    /// Index messages in MeiliSearch
+    Meilisearch,
+    /// Index messages in Elasticsearch or OpenSearch
+    Elasticsearch,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct DatabaseSearch {
+    #[serde(default)]
+    pub engine: SearchEngine,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub api_key: String,
+    #[serde(default)]
+    pub index: String,
+}
+
+impl Default for DatabaseSearch {
+    fn default() -> Self {
+        Self {
+            engine: SearchEngine::Database,
+            url: String::new(),
+            api_key: String::new(),
+            index: "messages".to_string(),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct Database {
     pub mongodb: String,
     pub redis: String,
+
+    #[serde(default)]
+    pub batching: DatabaseBatching,
+    #[serde(default)]
+    pub search: DatabaseSearch,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -168,6 +229,11 @@ pub struct ApiSecurityCaptcha {
 pub struct ApiSecurity {
     pub authifier_shield_key: String,
     pub voso_legacy_token: String,
+    /// Key used to authenticate with January's internal API
+    pub january_key: String,
+    /// Base64 encoded Ed25519 secret key used to sign interactions sent to bots over HTTP
+    #[serde(default)]
+    pub interactions_signing_key: String,
     pub captcha: ApiSecurityCaptcha,
     pub trust_cloudflare: bool,
     pub easypwned: String,
@@ -183,6 +249,116 @@ pub struct ApiUsers {
     pub early_adopter_cutoff: Option<u64>,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+pub struct ApiBodyLimits {
+    pub default: usize,
+    pub messages: usize,
+    pub settings: usize,
+    pub webhooks: usize,
+}
+
+impl Default for ApiBodyLimits {
+    fn default() -> Self {
+        Self {
+            default: 1_048_576,
+            messages: 262_144,
+            settings: 1_048_576,
+            webhooks: 1_048_576,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ApiJanuary {
+    /// How long to wait for January to generate an embed
+    pub timeout_seconds: u64,
+    /// Maximum number of times a single request is retried
+    pub max_retries: usize,
+    /// Fraction of requests which may be retries
+    pub retry_budget: f64,
+    /// Consecutive failures after which requests to January are paused
+    pub failure_threshold: usize,
+    /// How long requests are paused for once January is considered down
+    pub cooldown_seconds: u64,
+}
+
+impl Default for ApiJanuary {
+    fn default() -> Self {
+        Self {
+            timeout_seconds: 10,
+            max_retries: 2,
+            retry_budget: 0.1,
+            failure_threshold: 5,
+            cooldown_seconds: 30,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ApiInteractions {
+    /// How long to wait for a bot to accept an interaction
+    pub timeout_seconds: u64,
+    /// Maximum number of times delivery of an interaction is attempted
+    pub max_attempts: usize,
+}
+
+impl Default for ApiInteractions {
+    fn default() -> Self {
+        Self {
+            timeout_seconds: 5,
+            max_attempts: 3,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ApiProvisioningLdap {
+    /// LDAP server URL, LDAP login is disabled if empty
+    pub url: String,
+    /// DN to bind as, `{username}` is replaced with the escaped login name
+    pub bind_dn: String,
+    /// Domain used to build the email address of each directory user
+    pub email_domain: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct OidcRoleMapping {
+    /// Group as found in the groups claim
+    pub group: String,
+    /// Server the role belongs to
+    pub server: String,
+    /// Role granted to members of the group
+    pub role: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ApiProvisioningOidc {
+    /// Issuer URL, OIDC login is disabled if empty
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// Where the identity provider sends the user back to with a code
+    pub redirect_uri: String,
+    pub scopes: String,
+    /// Claim listing the groups the user belongs to
+    pub groups_claim: String,
+    /// Only allow signing in through the identity provider
+    pub disable_password_login: bool,
+    #[serde(default)]
+    pub role_mappings: Vec<OidcRoleMapping>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ApiProvisioning {
+    /// Bearer token for the SCIM API, provisioning is disabled if empty
+    #[serde(default)]
+    pub scim_token: String,
+    #[serde(default)]
+    pub ldap: ApiProvisioningLdap,
+    #[serde(default)]
+    pub oidc: ApiProvisioningOidc,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct Api {
     pub registration: ApiRegistration,
@@ -190,6 +366,14 @@ pub struct Api {
     pub security: ApiSecurity,
     pub workers: ApiWorkers,
     pub users: ApiUsers,
+    #[serde(default)]
+    pub body_limits: ApiBodyLimits,
+    #[serde(default)]
+    pub provisioning: ApiProvisioning,
+    #[serde(default)]
+    pub january: ApiJanuary,
+    #[serde(default)]
+    pub interactions: ApiInteractions,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -252,6 +436,84 @@ pub struct FilesLimit {
     pub max_pixel_side: usize,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+pub struct FilesArchive {
+    pub max_entries: usize,
+    pub max_uncompressed_size: usize,
+    pub max_compression_ratio: usize,
+    pub listing_limit: usize,
+    pub scan_contents: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FilesTextPreview {
+    pub max_file_size: usize,
+    pub max_lines: usize,
+    pub max_length: usize,
+}
+
+/// Processing step applied to uploaded files
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilesProcessor {
+    /// Probe file type specific metadata such as image dimensions
+    Classify,
+    /// Strip metadata according to the EXIF policy of the tag
+    ExifStrip,
+    /// Serve resized previews of images
+    Resize,
+    /// Virus scan files using ClamAV
+    Scan,
+    /// Match images against the hash list in `files.hash_matching`
+    HashMatch,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FilesPipeline {
+    #[serde(default)]
+    pub allowed_mime_types: Vec<String>,
+    #[serde(default)]
+    pub max_size: usize,
+    pub processors: Vec<FilesProcessor>,
+}
+
+impl Default for FilesPipeline {
+    fn default() -> Self {
+        Self {
+            allowed_mime_types: vec![],
+            max_size: 0,
+            processors: vec![
+                FilesProcessor::Classify,
+                FilesProcessor::ExifStrip,
+                FilesProcessor::Resize,
+                FilesProcessor::Scan,
+                FilesProcessor::HashMatch,
+            ],
+        }
+    }
+}
+
+impl FilesPipeline {
+    /// Whether the given processor should run
+    pub fn runs(&self, processor: FilesProcessor) -> bool {
+        self.processors.contains(&processor)
+    }
+
+    /// Whether the given mime type may be uploaded, supports `type/*` wildcards
+    pub fn allows_mime_type(&self, mime_type: &str) -> bool {
+        self.allowed_mime_types.is_empty()
+            || self
+                .allowed_mime_types
+                .iter()
+                .any(|allowed| match allowed.strip_suffix("/*") {
+                    Some(prefix) => mime_type
+                        .split_once('/')
+                        .is_some_and(|(kind, _)| kind == prefix),
+                    None => allowed == mime_type,
+                })
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct FilesS3 {
     pub endpoint: String,
@@ -269,10 +531,185 @@ pub struct Files {
     pub blocked_mime_types: Vec<String>,
     pub clamd_host: String,
     pub scan_mime_types: Vec<String>,
+    pub rescan_signature_threshold: u32,
 
     pub limit: FilesLimit,
+    pub archive: FilesArchive,
+    pub text_preview: FilesTextPreview,
     pub preview: HashMap<String, [usize; 2]>,
+    #[serde(default)]
+    pub exif: HashMap<String, ExifPolicy>,
+    #[serde(default)]
+    pub pipeline: HashMap<String, FilesPipeline>,
     pub s3: FilesS3,
+    #[serde(default)]
+    pub cdn: FilesCdn,
+    #[serde(default)]
+    pub hash_matching: FilesHashMatching,
+}
+
+/// How credentials for a CDN in front of autumn are signed
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CdnSigning {
+    /// Files are served to anyone who can reach the CDN
+    #[default]
+    None,
+    /// CloudFront signed cookies using a custom policy
+    Cloudfront,
+    /// Cloudflare HMAC token authentication
+    Cloudflare,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FilesCdn {
+    /// Public base URL of the CDN
+    #[serde(default)]
+    pub url: String,
+    /// Header the CDN adds to requests it forwards to autumn
+    #[serde(default)]
+    pub origin_header: String,
+    /// Expected value of the origin header, requests without it are rejected if set
+    #[serde(default)]
+    pub origin_secret: String,
+    /// Cache-Control header sent for each tag
+    #[serde(default)]
+    pub cache_control: HashMap<String, String>,
+    /// How CDN credentials are signed
+    #[serde(default)]
+    pub signing: CdnSigning,
+    /// How long CDN credentials remain valid
+    #[serde(default)]
+    pub credentials_ttl_seconds: u64,
+    /// Domain signed cookies are set on
+    #[serde(default)]
+    pub cookie_domain: String,
+    /// Id of the CloudFront public key
+    #[serde(default)]
+    pub cloudfront_key_pair_id: String,
+    /// CloudFront private key, PEM encoded
+    #[serde(default)]
+    pub cloudfront_private_key: String,
+    /// Secret shared with the Cloudflare token authentication rule
+    #[serde(default)]
+    pub cloudflare_secret: String,
+}
+
+impl Default for FilesCdn {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            origin_header: "x-origin-auth".to_string(),
+            origin_secret: String::new(),
+            cache_control: HashMap::new(),
+            signing: CdnSigning::None,
+            credentials_ttl_seconds: 3600,
+            cookie_domain: String::new(),
+            cloudfront_key_pair_id: String::new(),
+            cloudfront_private_key: String::new(),
+            cloudflare_secret: String::new(),
+        }
+    }
+}
+
+impl FilesCdn {
+    /// Get the Cache-Control header for a given tag
+    pub fn cache_control(&self, tag: &str) -> String {
+        self.cache_control
+            .get(tag)
+            .cloned()
+            .unwrap_or_else(|| "public, max-age=604800, must-revalidate".to_string())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FilesHashMatching {
+    /// Whether images are matched against the hash list
+    #[serde(default)]
+    pub enabled: bool,
+    /// Path to the hash list, one hex encoded 256-bit hash per line optionally followed by a label
+    #[serde(default)]
+    pub hash_list: String,
+    /// Maximum number of bits by which an image may differ from a hash to match
+    #[serde(default)]
+    pub max_distance: u32,
+    /// Bucket evidence of matches is kept in, the default bucket is used if empty
+    #[serde(default)]
+    pub evidence_bucket: String,
+    /// Header a trusted proxy sets to the uploader's country code
+    #[serde(default)]
+    pub country_header: String,
+    /// Jurisdiction assumed when the uploader's country is not known
+    #[serde(default)]
+    pub default_jurisdiction: String,
+    /// Hooks matches are reported to
+    #[serde(default)]
+    pub hooks: Vec<FilesHashMatchingHook>,
+}
+
+impl Default for FilesHashMatching {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hash_list: String::new(),
+            max_distance: 31,
+            evidence_bucket: String::new(),
+            country_header: String::new(),
+            default_jurisdiction: String::new(),
+            hooks: vec![],
+        }
+    }
+}
+
+impl FilesHashMatching {
+    /// Get the hooks matches made in a jurisdiction are reported to
+    ///
+    /// Hooks for the `*` jurisdiction receive every match.
+    pub fn hooks_for<'a>(
+        &'a self,
+        jurisdiction: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a FilesHashMatchingHook> {
+        self.hooks.iter().filter(move |hook| {
+            hook.jurisdiction == "*"
+                || jurisdiction.is_some_and(|x| hook.jurisdiction.eq_ignore_ascii_case(x))
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FilesHashMatchingHook {
+    /// Country code of the jurisdiction this hook reports to, or `*` for all
+    pub jurisdiction: String,
+    /// URL matches are posted to
+    pub url: String,
+    /// Secret the request body is signed with
+    #[serde(default)]
+    pub secret: String,
+}
+
+/// How image metadata is handled on upload
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ExifPolicy {
+    /// Remove all metadata, including orientation
+    StripAll,
+    /// Remove all metadata after applying orientation to the image
+    #[default]
+    KeepOrientation,
+    /// Same as keep orientation but also keep the colour profile
+    KeepColorProfile,
+}
+
+impl Files {
+    /// Get the metadata policy for a given tag
+    pub fn exif_policy(&self, tag: &str) -> ExifPolicy {
+        self.exif.get(tag).copied().unwrap_or_default()
+    }
+
+    /// Get the upload pipeline for a given tag
+    pub fn pipeline(&self, tag: &str) -> FilesPipeline {
+        self.pipeline.get(tag).cloned().unwrap_or_default()
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -298,6 +735,8 @@ pub struct FeaturesLimits {
     pub message_length: usize,
     pub message_attachments: usize,
     pub servers: usize,
+    pub server_badges: usize,
+    pub burst_reactions: usize,
 
     pub file_upload_size_limit: HashMap<String, usize>,
 }
@@ -327,12 +766,142 @@ impl Default for FeaturesAdvanced {
     }
 }
 
+#[derive(Deserialize, Debug, Clone)]
+pub struct FeaturesAltAccountAlerts {
+    #[serde(default)]
+    pub account_age_hours: u64,
+}
+
+impl Default for FeaturesAltAccountAlerts {
+    fn default() -> Self {
+        Self {
+            account_age_hours: 168,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FeaturesHighlights {
+    #[serde(default)]
+    pub max_keywords: usize,
+    #[serde(default)]
+    pub max_keyword_length: usize,
+    #[serde(default)]
+    pub cooldown_seconds: u64,
+}
+
+impl Default for FeaturesHighlights {
+    fn default() -> Self {
+        Self {
+            max_keywords: 25,
+            max_keyword_length: 32,
+            cooldown_seconds: 300,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FeaturesAccountRecovery {
+    #[serde(default)]
+    pub max_contacts: usize,
+    #[serde(default)]
+    pub delay_hours: u64,
+    #[serde(default)]
+    pub expiry_hours: u64,
+    #[serde(default)]
+    pub attempts_per_day: usize,
+    #[serde(default)]
+    pub backup_code_regenerations_per_day: usize,
+}
+
+impl Default for FeaturesAccountRecovery {
+    fn default() -> Self {
+        Self {
+            max_contacts: 3,
+            delay_hours: 72,
+            expiry_hours: 168,
+            attempts_per_day: 3,
+            backup_code_regenerations_per_day: 5,
+        }
+    }
+}
+
+/// Whether users are told when an admin impersonates them
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ImpersonationNotice {
+    /// Always notify the user
+    #[default]
+    Always,
+    /// Let the admin decide for each impersonation
+    Optional,
+    /// Never notify the user
+    Never,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FeaturesImpersonation {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub max_duration_minutes: u64,
+    #[serde(default)]
+    pub notify_user: ImpersonationNotice,
+}
+
+impl Default for FeaturesImpersonation {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_duration_minutes: 60,
+            notify_user: ImpersonationNotice::Always,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FeaturesEmojiImport {
+    #[serde(default)]
+    pub background_threshold: usize,
+}
+
+impl Default for FeaturesEmojiImport {
+    fn default() -> Self {
+        Self {
+            background_threshold: 20,
+        }
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct Features {
     pub limits: FeaturesLimitsCollection,
     pub webhooks_enabled: bool,
     pub mass_mentions_send_notifications: bool,
     pub mass_mentions_enabled: bool,
+    #[serde(default)]
+    pub require_emoji_approval: bool,
+    #[serde(default)]
+    pub mass_mention_confirmation_threshold: usize,
+    #[serde(default)]
+    pub screening_response_retention_days: u64,
+    #[serde(default)]
+    pub read_receipts_enabled: bool,
+
+    #[serde(default)]
+    pub alt_account_alerts: FeaturesAltAccountAlerts,
+
+    #[serde(default)]
+    pub highlights: FeaturesHighlights,
+
+    #[serde(default)]
+    pub account_recovery: FeaturesAccountRecovery,
+
+    #[serde(default)]
+    pub impersonation: FeaturesImpersonation,
+
+    #[serde(default)]
+    pub emoji_import: FeaturesEmojiImport,
 
     #[serde(default)]
     pub advanced: FeaturesAdvanced,
@@ -348,6 +917,181 @@ pub struct Sentry {
     pub crond: String,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+pub struct Shutdown {
+    pub deadline_seconds: u64,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self {
+            deadline_seconds: 30,
+        }
+    }
+}
+
+impl Shutdown {
+    /// Maximum time a service may spend shutting down
+    pub fn deadline(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.deadline_seconds)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Gateway {
+    /// Most connections an account may hold open at once, 0 for no limit
+    pub max_sessions_per_user: usize,
+    /// Seconds a connection may go without being heard from before it is dropped, 0 to never drop
+    pub heartbeat_timeout_seconds: u64,
+}
+
+impl Default for Gateway {
+    fn default() -> Self {
+        Self {
+            max_sessions_per_user: 16,
+            heartbeat_timeout_seconds: 90,
+        }
+    }
+}
+
+impl Gateway {
+    /// How long a connection may stay silent before it is considered dead
+    pub fn heartbeat_timeout(&self) -> Option<std::time::Duration> {
+        (self.heartbeat_timeout_seconds > 0)
+            .then(|| std::time::Duration::from_secs(self.heartbeat_timeout_seconds))
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FederationPeer {
+    /// Name the peer identifies itself with
+    pub host: String,
+    /// Base URL of the peer's API
+    pub url: String,
+    /// Secret shared with the peer, used to sign requests in both directions
+    pub secret: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Federation {
+    pub enabled: bool,
+    /// Name this instance identifies itself with
+    pub host: String,
+    /// How long resolved invites and profiles are cached for
+    pub cache_ttl_seconds: u64,
+    /// Instances allowed to resolve from and be resolved by this instance
+    #[serde(default)]
+    pub peers: Vec<FederationPeer>,
+}
+
+impl Federation {
+    /// Find an allowed peer by its name
+    pub fn peer(&self, host: &str) -> Option<&FederationPeer> {
+        self.peers.iter().find(|peer| peer.host == host)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct EmbedsX {
+    pub enabled: bool,
+    /// Bearer token for the X API, the fallback API is used if empty
+    pub bearer_token: String,
+    /// Base URL of an FxTwitter compatible API
+    pub fallback_api: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct EmbedsBluesky {
+    pub enabled: bool,
+    /// Base URL of the public Bluesky AppView API
+    pub api: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Embeds {
+    /// Maximum number of bytes of a web page to download and parse
+    pub max_document_size: usize,
+    #[serde(default)]
+    pub x: EmbedsX,
+    #[serde(default)]
+    pub bluesky: EmbedsBluesky,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Localization {
+    /// Locale used when the user has not picked one or it has no translation
+    pub default_locale: String,
+    /// Locales to try before a locale's parent, keyed by locale
+    #[serde(default)]
+    pub fallbacks: HashMap<String, Vec<String>>,
+    /// Translations keyed by locale and then by message key
+    #[serde(default)]
+    pub catalogs: HashMap<String, HashMap<String, String>>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Experiment {
+    /// Percentage of users the experiment is enabled for
+    #[serde(default)]
+    pub rollout: u8,
+    /// Users the experiment is always enabled for
+    #[serde(default)]
+    pub users: Vec<String>,
+    /// Routes only available to users in the experiment, such as
+    /// `POST /channels/<target>/messages/<msg>/thread`
+    #[serde(default)]
+    pub routes: Vec<String>,
+}
+
+/// Place a user into one of a hundred buckets for an experiment
+///
+/// Uses FNV-1a so buckets stay the same across builds and nodes.
+fn experiment_bucket(name: &str, user: &str) -> u8 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in name.bytes().chain([0]).chain(user.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    (hash % 100) as u8
+}
+
+impl Experiment {
+    /// Check whether the experiment is enabled for a user
+    ///
+    /// Raising the rollout only ever adds users, as each user keeps the
+    /// same bucket for a given experiment.
+    pub fn enabled_for(&self, name: &str, user: &str) -> bool {
+        self.users.iter().any(|id| id == user) || experiment_bucket(name, user) < self.rollout
+    }
+
+    /// Check whether a route is only available to users in the experiment
+    ///
+    /// Path segments written as `<name>` match any segment.
+    pub fn gates(&self, method: &str, path: &str) -> bool {
+        self.routes.iter().any(|route| {
+            let Some((route_method, route_path)) = route.split_once(' ') else {
+                return false;
+            };
+
+            let pattern: Vec<&str> = route_path.trim_matches('/').split('/').collect();
+            let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+            route_method.eq_ignore_ascii_case(method)
+                && pattern.len() == segments.len()
+                && pattern.iter().zip(segments).all(|(pattern, segment)| {
+                    *pattern == segment || (pattern.starts_with('<') && pattern.ends_with('>'))
+                })
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Proxy {
+    /// Address ranges of reverse proxies, in CIDR notation
+    #[serde(default)]
+    pub trusted_ranges: Vec<String>,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct Settings {
     pub database: Database,
@@ -358,6 +1102,20 @@ pub struct Settings {
     pub files: Files,
     pub features: Features,
     pub sentry: Sentry,
+    #[serde(default)]
+    pub shutdown: Shutdown,
+    #[serde(default)]
+    pub gateway: Gateway,
+    #[serde(default)]
+    pub federation: Federation,
+    #[serde(default)]
+    pub embeds: Embeds,
+    #[serde(default)]
+    pub localization: Localization,
+    #[serde(default)]
+    pub experiments: HashMap<String, Experiment>,
+    #[serde(default)]
+    pub proxy: Proxy,
     pub production: bool,
 }
 
@@ -370,6 +1128,35 @@ impl Settings {
         if self.api.security.captcha.hcaptcha_key.is_empty() {
             log::warn!("No Captcha key specified! Remember to add hCaptcha key.");
         }
+
+        if self.api.provisioning.oidc.disable_password_login
+            && self.api.provisioning.oidc.issuer.is_empty()
+        {
+            log::warn!("Password login is disabled but no OIDC issuer is configured!");
+        }
+    }
+
+    /// Check whether an experiment is enabled for a user
+    ///
+    /// Experiments which are not configured are enabled for everyone, so a
+    /// feature becomes generally available once its experiment is removed.
+    pub fn experiment_enabled(&self, name: &str, user: &str) -> bool {
+        self.experiments
+            .get(name)
+            .is_none_or(|experiment| experiment.enabled_for(name, user))
+    }
+
+    /// Find the experiments enabled for a user
+    pub fn experiments_for(&self, user: &str) -> Vec<String> {
+        let mut experiments: Vec<String> = self
+            .experiments
+            .iter()
+            .filter(|(name, experiment)| experiment.enabled_for(name, user))
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        experiments.sort();
+        experiments
     }
 }
 
@@ -443,10 +1230,79 @@ macro_rules! configure {
 #[cfg(feature = "test")]
 #[cfg(test)]
 mod tests {
-    use crate::init;
+    use crate::{init, Experiment, FilesHashMatching, FilesHashMatchingHook};
 
     #[async_std::test]
     async fn it_works() {
         init().await;
     }
+
+    #[test]
+    fn experiment_rollout() {
+        let mut experiment = Experiment {
+            users: vec!["tester".to_string()],
+            routes: vec!["POST /channels/<target>/messages/<msg>/thread".to_string()],
+            ..Default::default()
+        };
+
+        assert!(experiment.enabled_for("threads", "tester"));
+        assert!(!experiment.enabled_for("threads", "someone"));
+
+        let users: Vec<String> = (0..1000).map(|i| format!("user{i}")).collect();
+        experiment.rollout = 25;
+        let enrolled: Vec<&String> = users
+            .iter()
+            .filter(|user| experiment.enabled_for("threads", user))
+            .collect();
+        assert!((150..350).contains(&enrolled.len()));
+
+        experiment.rollout = 50;
+        assert!(enrolled
+            .iter()
+            .all(|user| experiment.enabled_for("threads", user)));
+
+        experiment.rollout = 100;
+        assert!(users
+            .iter()
+            .all(|user| experiment.enabled_for("threads", user)));
+    }
+
+    #[test]
+    fn experiment_routes() {
+        let experiment = Experiment {
+            routes: vec!["POST /channels/<target>/messages/<msg>/thread".to_string()],
+            ..Default::default()
+        };
+
+        assert!(experiment.gates("POST", "/channels/channel/messages/message/thread"));
+        assert!(!experiment.gates("GET", "/channels/channel/messages/message/thread"));
+        assert!(!experiment.gates("POST", "/channels/channel/messages"));
+    }
+
+    #[test]
+    fn hash_matching_hooks() {
+        let hook = |jurisdiction: &str| FilesHashMatchingHook {
+            jurisdiction: jurisdiction.to_string(),
+            url: format!("https://{jurisdiction}.example"),
+            secret: String::new(),
+        };
+
+        let config = FilesHashMatching {
+            hooks: vec![hook("US"), hook("GB"), hook("*")],
+            ..Default::default()
+        };
+
+        let urls = |jurisdiction| -> Vec<&str> {
+            config
+                .hooks_for(jurisdiction)
+                .map(|hook| hook.url.as_str())
+                .collect()
+        };
+
+        assert_eq!(
+            urls(Some("us")),
+            vec!["https://US.example", "https://*.example"]
+        );
+        assert_eq!(urls(None), vec!["https://*.example"]);
+    }
 }
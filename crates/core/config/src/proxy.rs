@@ -0,0 +1,275 @@
+//! Client addresses behind reverse proxies
+//!
+//! Services usually sit behind one or more reverse proxies, so the address a
+//! request arrives from is that of the last proxy. Proxies pass the client's
+//! address on in headers, which are only believed on requests coming from a
+//! trusted proxy as anyone can set them otherwise.
+use std::net::{IpAddr, SocketAddr};
+
+use crate::Settings;
+
+/// Standard header listing the hops a request took (RFC 7239)
+pub const FORWARDED: &str = "Forwarded";
+
+/// Header listing the addresses a request was forwarded for
+pub const X_FORWARDED_FOR: &str = "X-Forwarded-For";
+
+/// Header carrying the client's address set by Cloudflare
+pub const CF_CONNECTING_IP: &str = "CF-Connecting-IP";
+
+/// Range of addresses in CIDR notation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpRange {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl IpRange {
+    /// Parse a range such as `10.0.0.0/8`, a bare address is a range of one
+    pub fn parse(range: &str) -> Option<IpRange> {
+        let (network, prefix) = match range.trim().split_once('/') {
+            Some((network, prefix)) => (network.parse().ok()?, Some(prefix.parse().ok()?)),
+            None => (range.trim().parse().ok()?, None),
+        };
+
+        let bits = match network {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+
+        let prefix = prefix.unwrap_or(bits);
+        (prefix <= bits).then_some(IpRange { network, prefix })
+    }
+
+    /// Check whether an address falls within this range
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Headers a reverse proxy may pass the client's address on in
+#[derive(Debug, Default)]
+pub struct ForwardingHeaders<'a> {
+    pub forwarded: Option<&'a str>,
+    pub x_forwarded_for: Option<&'a str>,
+    pub cf_connecting_ip: Option<&'a str>,
+}
+
+impl<'a> ForwardingHeaders<'a> {
+    /// Read the headers using a lookup by header name
+    pub fn from_fn(get: impl Fn(&'static str) -> Option<&'a str>) -> ForwardingHeaders<'a> {
+        ForwardingHeaders {
+            forwarded: get(FORWARDED),
+            x_forwarded_for: get(X_FORWARDED_FOR),
+            cf_connecting_ip: get(CF_CONNECTING_IP),
+        }
+    }
+
+    /// Addresses the request was forwarded for, from the client to the last proxy
+    ///
+    /// Entries which are not addresses, such as obfuscated identifiers, are None.
+    fn hops(&self) -> Vec<Option<IpAddr>> {
+        if let Some(forwarded) = self.forwarded {
+            forwarded
+                .split(',')
+                .map(|element| {
+                    element
+                        .split(';')
+                        .filter_map(|pair| pair.split_once('='))
+                        .find(|(key, _)| key.trim().eq_ignore_ascii_case("for"))
+                        .and_then(|(_, value)| parse_node(value))
+                })
+                .collect()
+        } else if let Some(forwarded_for) = self.x_forwarded_for {
+            forwarded_for.split(',').map(parse_node).collect()
+        } else {
+            vec![]
+        }
+    }
+}
+
+/// Parse an address which may be quoted, bracketed or carry a port
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    node.parse::<IpAddr>()
+        .ok()
+        .or_else(|| node.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+        .or_else(|| {
+            node.strip_prefix('[')
+                .and_then(|node| node.split_once(']'))
+                .and_then(|(ip, _)| ip.parse().ok())
+        })
+}
+
+impl Settings {
+    /// Ranges reverse proxies are trusted from
+    pub fn trusted_proxies(&self) -> Vec<IpRange> {
+        self.proxy
+            .trusted_ranges
+            .iter()
+            .filter_map(|range| IpRange::parse(range))
+            .collect()
+    }
+
+    /// Work out the client's address given the address a request came from
+    ///
+    /// Forwarded addresses are walked back from the closest proxy until
+    /// one that is not a trusted proxy is found.
+    pub fn client_ip(&self, peer: IpAddr, headers: &ForwardingHeaders) -> IpAddr {
+        let trusted = self.trusted_proxies();
+        let is_trusted = |ip: IpAddr| trusted.iter().any(|range| range.contains(ip));
+        let cloudflare = || {
+            headers
+                .cf_connecting_ip
+                .and_then(parse_node)
+                .filter(|_| self.api.security.trust_cloudflare)
+        };
+
+        if !is_trusted(peer) {
+            // Cloudflare was trusted from anywhere before ranges could be configured
+            return if trusted.is_empty() {
+                cloudflare().unwrap_or(peer)
+            } else {
+                peer
+            };
+        }
+
+        if let Some(ip) = cloudflare() {
+            return ip;
+        }
+
+        let mut client = peer;
+        for hop in headers.hops().into_iter().rev() {
+            let Some(ip) = hop else {
+                break;
+            };
+
+            client = ip;
+            if !is_trusted(ip) {
+                break;
+            }
+        }
+
+        client
+    }
+}
+
+#[cfg(feature = "axum")]
+mod axum_impl {
+    use std::net::{IpAddr, SocketAddr};
+
+    use axum::{
+        extract::{ConnectInfo, Request},
+        middleware::Next,
+        response::Response,
+    };
+
+    use super::ForwardingHeaders;
+    use crate::config;
+
+    /// Address of the client making a request
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ClientIp(pub IpAddr);
+
+    /// Middleware resolving the client's address into a [`ClientIp`] extension
+    ///
+    /// The service must be served with connect info for `SocketAddr`.
+    pub async fn resolve_client_ip(
+        ConnectInfo(peer): ConnectInfo<SocketAddr>,
+        mut request: Request,
+        next: Next,
+    ) -> Response {
+        let ip = config().await.client_ip(
+            peer.ip(),
+            &ForwardingHeaders::from_fn(|name| {
+                request
+                    .headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+            }),
+        );
+
+        request.extensions_mut().insert(ClientIp(ip));
+        next.run(request).await
+    }
+}
+
+#[cfg(feature = "axum")]
+pub use axum_impl::*;
+
+#[cfg(feature = "test")]
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use super::{ForwardingHeaders, IpRange};
+    use crate::config;
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    #[test]
+    fn matches_ranges() {
+        let range = IpRange::parse("10.0.0.0/8").unwrap();
+        assert!(range.contains(ip("10.1.2.3")));
+        assert!(range.contains(ip("::ffff:10.1.2.3")));
+        assert!(!range.contains(ip("11.0.0.1")));
+
+        assert!(IpRange::parse("0.0.0.0/0").unwrap().contains(ip("1.2.3.4")));
+        assert!(IpRange::parse("fd00::/8").unwrap().contains(ip("fd12::1")));
+        assert!(IpRange::parse("127.0.0.1")
+            .unwrap()
+            .contains(ip("127.0.0.1")));
+        assert_eq!(IpRange::parse("10.0.0.0/33"), None);
+        assert_eq!(IpRange::parse("proxy"), None);
+    }
+
+    #[async_std::test]
+    async fn resolves_client_ips() {
+        let mut config = config().await;
+        config.proxy.trusted_ranges = vec!["10.0.0.0/8".to_string()];
+
+        let headers = ForwardingHeaders {
+            x_forwarded_for: Some("203.0.113.1, 198.51.100.7, 10.0.0.2"),
+            ..Default::default()
+        };
+
+        // Headers are ignored unless the request comes from a trusted proxy
+        assert_eq!(config.client_ip(ip("192.0.2.1"), &headers), ip("192.0.2.1"));
+
+        // Walk back until the first address which is not a proxy
+        assert_eq!(
+            config.client_ip(ip("10.0.0.1"), &headers),
+            ip("198.51.100.7")
+        );
+
+        let headers = ForwardingHeaders {
+            forwarded: Some(r#"for=192.0.2.60;proto=http, for="[2001:db8::1]:4711""#),
+            x_forwarded_for: Some("198.51.100.7"),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.client_ip(ip("10.0.0.1"), &headers),
+            ip("2001:db8::1")
+        );
+
+        let headers = ForwardingHeaders {
+            forwarded: Some("for=192.0.2.60, for=_hidden"),
+            ..Default::default()
+        };
+
+        assert_eq!(config.client_ip(ip("10.0.0.1"), &headers), ip("10.0.0.1"));
+    }
+}
@@ -12,7 +12,6 @@ use serde_json::to_string;
 
 #[derive(Clone)]
 pub struct AMQP {
-    #[allow(unused)]
     connection: Connection,
     channel: Channel,
 }
@@ -25,6 +24,12 @@ impl AMQP {
         }
     }
 
+    /// Close the channel and connection to the broker
+    pub async fn close(self) -> Result<(), AMQPError> {
+        self.channel.close().await?;
+        self.connection.close().await
+    }
+
     pub async fn friend_request_accepted(
         &self,
         accepted_request_user: &User,
@@ -4,7 +4,6 @@ mod reference;
 use authifier::config::Captcha;
 use authifier::config::EmailVerificationConfig;
 use authifier::config::PasswordScanning;
-use authifier::config::ResolveIp;
 use authifier::config::SMTPSettings;
 use authifier::config::Shield;
 use authifier::config::Template;
@@ -98,6 +97,13 @@ impl DatabaseInfo {
 }
 
 impl Database {
+    /// Close the database connection once outstanding operations finish
+    pub async fn shutdown(self) {
+        if let Database::MongoDb(MongoDb(client, _)) = self {
+            client.shutdown().await;
+        }
+    }
+
     /// Create an Authifier reference
     pub async fn to_authifier(self) -> Authifier {
         let config = config().await;
@@ -212,10 +218,6 @@ impl Database {
             };
         }
 
-        if config.api.security.trust_cloudflare {
-            auth_config.resolve_ip = ResolveIp::Cloudflare;
-        }
-
         Authifier {
             database: match self {
                 Database::Reference(_) => Default::default(),
@@ -3,32 +3,58 @@ use std::{collections::HashMap, sync::Arc};
 use futures::lock::Mutex;
 
 use crate::{
-    Bot, Channel, ChannelCompositeKey, ChannelUnread, Emoji, File, FileHash, Invite, Member,
-    MemberCompositeKey, Message, PolicyChange, RatelimitEvent, Report, Server, ServerBan, Snapshot,
-    User, UserSettings, Webhook,
+    ApiToken, AuditLogEntry, BackupCodes, BanSubscription, Bookmark, Bot, Channel,
+    ChannelCompositeKey, ChannelUnread, DelayedNotification, Device, Emoji, File, FileHash,
+    FilterWord, HashMatch, Impersonation, Interaction, Invite, Member, MemberCompositeKey, Message,
+    NotificationSummary, PendingAction, PolicyChange, PurgeSchedule, RatelimitEvent, ReadReceipt,
+    RecoveryContact, RecoveryRequest, Report, ScreeningResponse, Server, ServerApplication,
+    ServerBan, Snapshot, StarboardEntry, Thread, User, UserApp, UserAppCompositeKey, UserSettings,
+    Webhook,
 };
 
 database_derived!(
     /// Reference implementation
     #[derive(Default)]
     pub struct ReferenceDb {
+        pub api_tokens: Arc<Mutex<HashMap<String, ApiToken>>>,
+        pub audit_log: Arc<Mutex<HashMap<String, AuditLogEntry>>>,
+        pub backup_codes: Arc<Mutex<HashMap<String, BackupCodes>>>,
+        pub ban_subscriptions: Arc<Mutex<HashMap<String, BanSubscription>>>,
+        pub bookmarks: Arc<Mutex<HashMap<String, Bookmark>>>,
         pub bots: Arc<Mutex<HashMap<String, Bot>>>,
         pub channels: Arc<Mutex<HashMap<String, Channel>>>,
         pub channel_invites: Arc<Mutex<HashMap<String, Invite>>>,
         pub channel_unreads: Arc<Mutex<HashMap<ChannelCompositeKey, ChannelUnread>>>,
         pub channel_webhooks: Arc<Mutex<HashMap<String, Webhook>>>,
+        pub delayed_notifications: Arc<Mutex<HashMap<String, DelayedNotification>>>,
+        pub devices: Arc<Mutex<HashMap<String, Device>>>,
         pub emojis: Arc<Mutex<HashMap<String, Emoji>>>,
         pub file_hashes: Arc<Mutex<HashMap<String, FileHash>>>,
         pub files: Arc<Mutex<HashMap<String, File>>>,
+        pub filter_words: Arc<Mutex<HashMap<String, FilterWord>>>,
+        pub hash_matches: Arc<Mutex<HashMap<String, HashMatch>>>,
+        pub impersonations: Arc<Mutex<HashMap<String, Impersonation>>>,
+        pub interactions: Arc<Mutex<HashMap<String, Interaction>>>,
         pub messages: Arc<Mutex<HashMap<String, Message>>>,
+        pub notification_summaries: Arc<Mutex<HashMap<String, NotificationSummary>>>,
+        pub pending_actions: Arc<Mutex<HashMap<String, PendingAction>>>,
         pub policy_changes: Arc<Mutex<HashMap<String, PolicyChange>>>,
+        pub purge_schedules: Arc<Mutex<HashMap<String, PurgeSchedule>>>,
         pub ratelimit_events: Arc<Mutex<HashMap<String, RatelimitEvent>>>,
+        pub read_receipts: Arc<Mutex<HashMap<ChannelCompositeKey, ReadReceipt>>>,
+        pub recovery_contacts: Arc<Mutex<HashMap<String, RecoveryContact>>>,
+        pub recovery_requests: Arc<Mutex<HashMap<String, RecoveryRequest>>>,
+        pub user_apps: Arc<Mutex<HashMap<UserAppCompositeKey, UserApp>>>,
         pub user_settings: Arc<Mutex<HashMap<String, UserSettings>>>,
         pub users: Arc<Mutex<HashMap<String, User>>>,
+        pub server_applications: Arc<Mutex<HashMap<String, ServerApplication>>>,
         pub server_bans: Arc<Mutex<HashMap<MemberCompositeKey, ServerBan>>>,
         pub server_members: Arc<Mutex<HashMap<MemberCompositeKey, Member>>>,
         pub servers: Arc<Mutex<HashMap<String, Server>>>,
+        pub starboard_entries: Arc<Mutex<HashMap<String, StarboardEntry>>>,
+        pub threads: Arc<Mutex<HashMap<String, Thread>>>,
         pub safety_reports: Arc<Mutex<HashMap<String, Report>>>,
         pub safety_snapshots: Arc<Mutex<HashMap<String, Snapshot>>>,
+        pub screening_responses: Arc<Mutex<HashMap<MemberCompositeKey, ScreeningResponse>>>,
     }
 );
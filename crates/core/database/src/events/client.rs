@@ -1,12 +1,16 @@
+use std::collections::HashMap;
+
 use authifier::AuthifierEvent;
 use guilderia_result::Error;
 use serde::{Deserialize, Serialize};
 
 use guilderia_models::v0::{
-    AppendMessage, Channel, ChannelUnread, Emoji, FieldsChannel, FieldsMember, FieldsMessage,
-    FieldsRole, FieldsServer, FieldsUser, FieldsWebhook, Member, MemberCompositeKey, Message,
-    PartialChannel, PartialMember, PartialMessage, PartialRole, PartialServer, PartialUser,
-    PartialWebhook, PolicyChange, RemovalIntention, Report, Server, User, UserSettings, Webhook,
+    AppendMessage, Bookmark, Channel, ChannelUnread, Device, Emoji, EmojiImportFailure,
+    FieldsChannel, FieldsMember, FieldsMessage, FieldsRole, FieldsServer, FieldsUser,
+    FieldsWebhook, File, Interaction, Member, MemberCompositeKey, Message, PartialChannel,
+    PartialMember, PartialMessage, PartialRole, PartialServer, PartialThread, PartialUser,
+    PartialWebhook, PermissionDiff, PolicyChange, RecoveryRequest, RemovalIntention, Report,
+    Server, ServerBadge, Thread, User, UserSettings, Webhook,
 };
 
 use crate::Database;
@@ -30,6 +34,8 @@ pub enum ReadyPayloadFields {
 
     UserSettings(Vec<String>),
     ChannelUnreads,
+    Bookmarks,
+    Experiments,
 }
 
 /// Protocol Events
@@ -62,9 +68,26 @@ pub enum EventV1 {
         user_settings: Option<UserSettings>,
         #[serde(skip_serializing_if = "Option::is_none")]
         channel_unreads: Option<Vec<ChannelUnread>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        bookmarks: Option<Vec<Bookmark>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        experiments: Option<Vec<String>>,
 
         policy_changes: Vec<PolicyChange>,
     },
+    /// Further servers to cache, following a chunked Ready
+    ReadySupplement {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        servers: Option<Vec<Server>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        channels: Option<Vec<Channel>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        members: Option<Vec<Member>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        emojis: Option<Vec<Emoji>>,
+    },
+    /// All data following a chunked Ready has been sent
+    ReadyComplete,
 
     /// Ping response
     Pong { data: Ping },
@@ -96,6 +119,8 @@ pub enum EventV1 {
         channel_id: String,
         user_id: String,
         emoji_id: String,
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        burst: bool,
     },
 
     /// Remove user's reaction from message
@@ -116,6 +141,19 @@ pub enum EventV1 {
     /// Bulk delete messages
     BulkMessageDelete { channel: String, ids: Vec<String> },
 
+    /// New thread
+    ThreadCreate(Thread),
+
+    /// Update existing thread
+    ThreadUpdate {
+        id: String,
+        channel: String,
+        data: PartialThread,
+    },
+
+    /// Delete thread
+    ThreadDelete { id: String, channel: String },
+
     /// New server
     ServerCreate {
         id: String,
@@ -162,9 +200,43 @@ pub enum EventV1 {
         clear: Vec<FieldsRole>,
     },
 
+    /// Ranks of several server roles changed at once
+    ServerRoleRanksUpdate {
+        id: String,
+        ranks: HashMap<String, i64>,
+    },
+
     /// Server role deleted
     ServerRoleDelete { id: String, role_id: String },
 
+    /// Server badge created or updated
+    ServerBadgeUpdate {
+        id: String,
+        badge_id: String,
+        data: ServerBadge,
+    },
+
+    /// Server badge deleted
+    ServerBadgeDelete { id: String, badge_id: String },
+
+    /// Progress update for a background server job
+    ServerJobProgress {
+        id: String,
+        server: String,
+        processed: usize,
+        failed: usize,
+        total: usize,
+    },
+
+    /// Background server export finished
+    ///
+    /// Only sent to the user who started the export.
+    ServerExportComplete {
+        id: String,
+        server: String,
+        file: File,
+    },
+
     /// Update existing user
     UserUpdate {
         id: String,
@@ -195,6 +267,21 @@ pub enum EventV1 {
     /// Delete emoji
     EmojiDelete { id: String },
 
+    /// Pending emoji approved
+    EmojiApprove { id: String },
+
+    /// Pending emoji rejected
+    EmojiReject { id: String },
+
+    /// Background emoji pack import finished
+    ///
+    /// Only sent to the user who started the import.
+    EmojiImportComplete {
+        id: String,
+        server: String,
+        failed: Vec<EmojiImportFailure>,
+    },
+
     /// New report
     ReportCreate(Report),
     /// New channel
@@ -208,6 +295,16 @@ pub enum EventV1 {
         clear: Vec<FieldsChannel>,
     },
 
+    /// Role permission override changed in a channel
+    ///
+    /// Overriding a permission also stops the channel syncing with its category.
+    ChannelRolePermissionUpdate {
+        id: String,
+        role_id: String,
+        diff: PermissionDiff,
+        by: String,
+    },
+
     /// Delete channel
     ChannelDelete { id: String },
 
@@ -230,6 +327,20 @@ pub enum EventV1 {
         message_id: String,
     },
 
+    /// User has seen a message in a direct message or group
+    ///
+    /// Sent to the other recipients to show read receipts.
+    MessageSeen {
+        id: String,
+        user: String,
+        message_id: String,
+    },
+
+    /// Interaction started with a bot
+    ///
+    /// Only sent to the bot, along with the token it responds with.
+    InteractionCreate { token: String, data: Interaction },
+
     /// New webhook
     WebhookCreate(Webhook),
 
@@ -243,6 +354,29 @@ pub enum EventV1 {
     /// Delete webhook
     WebhookDelete { id: String },
 
+    /// Session on a new device is waiting for approval
+    ///
+    /// Only sent to the user's own sessions.
+    DeviceApprovalRequest(Device),
+
+    /// File uploaded by the user was quarantined after failing a virus scan
+    FileQuarantine(File),
+
+    /// Account recovery was started or approved
+    ///
+    /// Sent to the user being recovered and their recovery contact.
+    RecoveryRequest(RecoveryRequest),
+
+    /// Bookmark created or moved
+    ///
+    /// Only sent to the user's own sessions.
+    BookmarkUpdate(Bookmark),
+
+    /// Bookmark deleted
+    ///
+    /// Only sent to the user's own sessions.
+    BookmarkDelete { id: String, channel: String },
+
     /// Auth events
     Auth(AuthifierEvent),
 }
@@ -1,3 +1,5 @@
 pub mod client;
+pub mod projection;
 pub mod rabbit;
+pub mod redaction;
 pub mod server;
@@ -0,0 +1,338 @@
+//! Tailoring of events to the recipients they are delivered to
+//!
+//! Facts about each recipient are computed ahead of time, which places the
+//! recipient into a class for any given event. Each connection then shapes
+//! its own copy of the event for that class.
+//!
+//! Events are not projected once per class and shared between connections:
+//! every connection already deserialises its own copy from Redis and adjusts
+//! it with its own state (relationships, permissions) before it is shaped,
+//! so a shared projection would have to be copied again regardless.
+use std::collections::HashSet;
+
+use guilderia_models::v0;
+
+use super::client::EventV1;
+
+/// Class of recipients an event is shaped for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RecipientClass {
+    /// Deliver the event as-is
+    Full,
+    /// Strip message content, attachments and embeds
+    Redacted,
+    /// Replace a message with a tombstone carrying only its metadata
+    Tombstone,
+    /// Strip presence information about other users
+    Private,
+    /// Replace message content with its plain text fallback
+    Fallback,
+    /// Do not deliver the event
+    Omitted,
+}
+
+/// Pre-computed facts about a recipient
+#[derive(Clone, Debug)]
+pub struct Recipient {
+    /// Id of the recipient
+    pub user_id: String,
+    /// Bitfield of intents the recipient has
+    pub intents: u32,
+    /// Users the recipient has blocked
+    pub blocked: HashSet<String>,
+    /// Message extensions the recipient supports
+    pub capabilities: HashSet<v0::MessageExtension>,
+}
+
+impl Recipient {
+    /// Create facts for a recipient with every intent, nobody blocked and
+    /// no support for message extensions
+    pub fn new(user_id: String) -> Recipient {
+        Recipient {
+            user_id,
+            intents: u32::MAX,
+            blocked: HashSet::new(),
+            capabilities: HashSet::new(),
+        }
+    }
+
+    /// Check whether this recipient has a given intent
+    pub fn has_intent(&self, intent: v0::BotIntents) -> bool {
+        self.intents & intent as u32 != 0
+    }
+
+    /// Check whether this recipient supports every extension a message uses
+    fn supports(&self, extensions: Option<&[v0::MessageExtension]>) -> bool {
+        extensions
+            .unwrap_or_default()
+            .iter()
+            .all(|extension| self.capabilities.contains(extension))
+    }
+
+    /// Check whether a message is addressed to this recipient
+    fn is_addressed(
+        &self,
+        author: Option<&str>,
+        mentions: Option<&[String]>,
+        direct_channel: bool,
+    ) -> bool {
+        direct_channel
+            || author == Some(self.user_id.as_str())
+            || mentions.is_some_and(|mentions| mentions.contains(&self.user_id))
+    }
+
+    /// Work out which class this recipient falls into for an event
+    ///
+    /// `is_direct_channel` tells whether every message in a channel is
+    /// addressed to the recipient, as is the case in DMs and groups.
+    pub fn classify(
+        &self,
+        event: &EventV1,
+        is_direct_channel: impl Fn(&str) -> bool,
+    ) -> RecipientClass {
+        let content = self.has_intent(v0::BotIntents::MessageContent);
+        match event {
+            EventV1::Message(message) => {
+                if self.blocked.contains(&message.author) {
+                    RecipientClass::Tombstone
+                } else if !content
+                    && !self.is_addressed(
+                        Some(&message.author),
+                        message.mentions.as_deref(),
+                        is_direct_channel(&message.channel),
+                    )
+                {
+                    RecipientClass::Redacted
+                } else if self.supports(message.extensions.as_deref()) {
+                    RecipientClass::Full
+                } else {
+                    RecipientClass::Fallback
+                }
+            }
+            EventV1::MessageUpdate { channel, data, .. } => {
+                if !content
+                    && !self.is_addressed(
+                        None,
+                        data.mentions.as_deref(),
+                        is_direct_channel(channel),
+                    )
+                {
+                    RecipientClass::Redacted
+                } else if self.supports(data.extensions.as_deref()) {
+                    RecipientClass::Full
+                } else {
+                    RecipientClass::Fallback
+                }
+            }
+            EventV1::MessageAppend { channel, .. } => {
+                if content || is_direct_channel(channel) {
+                    RecipientClass::Full
+                } else {
+                    RecipientClass::Omitted
+                }
+            }
+            EventV1::ServerMemberJoin { user, .. } | EventV1::ServerMemberLeave { user, .. } => {
+                if self.has_intent(v0::BotIntents::Members) || user == &self.user_id {
+                    RecipientClass::Full
+                } else {
+                    RecipientClass::Omitted
+                }
+            }
+            EventV1::ServerMemberUpdate { id, .. } => {
+                if self.has_intent(v0::BotIntents::Members) || id.user == self.user_id {
+                    RecipientClass::Full
+                } else {
+                    RecipientClass::Omitted
+                }
+            }
+            EventV1::UserUpdate { id, .. } => {
+                if self.has_intent(v0::BotIntents::Presence) || id == &self.user_id {
+                    RecipientClass::Full
+                } else {
+                    RecipientClass::Private
+                }
+            }
+            _ => RecipientClass::Full,
+        }
+    }
+
+    /// Shape an event for this recipient in place
+    ///
+    /// Returns false if the event should not be delivered.
+    pub fn shape(&self, event: &mut EventV1, is_direct_channel: impl Fn(&str) -> bool) -> bool {
+        self.shape_inner(event, &is_direct_channel)
+    }
+
+    fn shape_inner(&self, event: &mut EventV1, is_direct_channel: &dyn Fn(&str) -> bool) -> bool {
+        if let EventV1::Bulk { v } = event {
+            v.retain_mut(|event| self.shape_inner(event, is_direct_channel));
+            return !v.is_empty();
+        }
+
+        let class = self.classify(event, is_direct_channel);
+        event.apply_class(class)
+    }
+}
+
+impl EventV1 {
+    /// Shape this event in place for a class of recipients
+    ///
+    /// Returns false if nothing is left worth delivering.
+    pub fn apply_class(&mut self, class: RecipientClass) -> bool {
+        match class {
+            RecipientClass::Full => true,
+            RecipientClass::Redacted => self.redact_message_content(),
+            RecipientClass::Tombstone => {
+                if let EventV1::Message(message) = self {
+                    message.content = None;
+                    message.fallback = None;
+                    message.attachments = None;
+                    message.embeds = None;
+                    message.masquerade = None;
+                    message.user = None;
+                    message.member = None;
+                }
+
+                true
+            }
+            RecipientClass::Private => {
+                if let EventV1::UserUpdate { data, clear, .. } = self {
+                    data.online = None;
+                    data.status = None;
+                    clear.retain(|field| {
+                        !matches!(
+                            field,
+                            v0::FieldsUser::StatusText
+                                | v0::FieldsUser::StatusPresence
+                                | v0::FieldsUser::StatusActivity
+                        )
+                    });
+
+                    data != &v0::PartialUser::default() || !clear.is_empty()
+                } else {
+                    true
+                }
+            }
+            RecipientClass::Fallback => {
+                match self {
+                    EventV1::Message(message) => {
+                        if let Some(fallback) = message.fallback.take() {
+                            message.content = Some(fallback);
+                        }
+
+                        message.effect = None;
+                        message.extensions = None;
+                    }
+                    EventV1::MessageUpdate { data, .. } => {
+                        if let Some(fallback) = data.fallback.take() {
+                            data.content = Some(fallback);
+                        }
+
+                        data.effect = None;
+                        data.extensions = None;
+                    }
+                    _ => {}
+                }
+
+                true
+            }
+            RecipientClass::Omitted => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use guilderia_models::v0;
+
+    use super::{Recipient, RecipientClass};
+    use crate::events::client::EventV1;
+
+    fn message(author: &str, mentions: Option<Vec<String>>) -> EventV1 {
+        EventV1::Message(
+            serde_json::from_value(serde_json::json!({
+                "_id": "message",
+                "channel": "channel",
+                "author": author,
+                "content": "hello",
+                "mentions": mentions,
+            }))
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn classifies_messages() {
+        let mut recipient = Recipient::new("bot".to_string());
+        recipient.intents = v0::BotIntents::Members as u32;
+        recipient.blocked.insert("blocked".to_string());
+
+        let not_direct = |_: &str| false;
+        assert_eq!(
+            recipient.classify(&message("user", None), not_direct),
+            RecipientClass::Redacted
+        );
+        assert_eq!(
+            recipient.classify(&message("user", Some(vec!["bot".to_string()])), not_direct),
+            RecipientClass::Full
+        );
+        assert_eq!(
+            recipient.classify(&message("user", None), |_: &str| true),
+            RecipientClass::Full
+        );
+        assert_eq!(
+            recipient.classify(&message("blocked", None), not_direct),
+            RecipientClass::Tombstone
+        );
+    }
+
+    #[test]
+    fn applies_classes() {
+        let mut redacted = message("user", None);
+        assert!(redacted.apply_class(RecipientClass::Redacted));
+
+        let EventV1::Message(redacted) = redacted else {
+            panic!("expected a message");
+        };
+
+        assert!(redacted.content.is_none());
+        assert_eq!(redacted.author, "user");
+        assert!(!message("user", None).apply_class(RecipientClass::Omitted));
+    }
+
+    #[test]
+    fn falls_back_for_unsupported_extensions() {
+        let event = EventV1::Message(
+            serde_json::from_value(serde_json::json!({
+                "_id": "message",
+                "channel": "channel",
+                "author": "user",
+                "content": "||secret||",
+                "extensions": ["Spoiler"],
+                "fallback": "[spoiler]",
+            }))
+            .unwrap(),
+        );
+
+        let mut recipient = Recipient::new("reader".to_string());
+        assert_eq!(
+            recipient.classify(&event, |_: &str| false),
+            RecipientClass::Fallback
+        );
+
+        let mut fallback = event.clone();
+        assert!(fallback.apply_class(RecipientClass::Fallback));
+        let EventV1::Message(message) = fallback else {
+            panic!("expected a message");
+        };
+
+        assert_eq!(message.content.as_deref(), Some("[spoiler]"));
+        assert!(message.fallback.is_none());
+
+        recipient.capabilities.insert(v0::MessageExtension::Spoiler);
+        assert_eq!(
+            recipient.classify(&event, |_: &str| false),
+            RecipientClass::Full
+        );
+    }
+}
@@ -0,0 +1,29 @@
+//! Redaction of event data recipients have not asked for
+use super::client::EventV1;
+
+impl EventV1 {
+    /// Strip message content, attachments and embeds from this event
+    ///
+    /// Message metadata such as the author, channel and mentions is kept
+    /// intact. Returns false if nothing is left worth delivering.
+    pub fn redact_message_content(&mut self) -> bool {
+        match self {
+            EventV1::Message(message) => {
+                message.content = None;
+                message.fallback = None;
+                message.attachments = None;
+                message.embeds = None;
+                true
+            }
+            EventV1::MessageUpdate { data, .. } => {
+                data.content = None;
+                data.fallback = None;
+                data.attachments = None;
+                data.embeds = None;
+                true
+            }
+            EventV1::MessageAppend { .. } => false,
+            _ => true,
+        }
+    }
+}
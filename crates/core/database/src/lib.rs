@@ -103,6 +103,7 @@ pub mod util;
 pub use models::*;
 
 pub mod events;
+pub mod search;
 pub mod tasks;
 
 mod amqp;
@@ -0,0 +1,330 @@
+use std::time::Duration;
+
+use authifier::{
+    config::{EmailVerificationConfig, Template},
+    Authifier,
+};
+use guilderia_config::config;
+use guilderia_result::{ErrorType, Result};
+use iso8601_timestamp::Timestamp;
+use serde_json::json;
+use ulid::Ulid;
+
+use crate::{events::client::EventV1, Database, RatelimitEvent, RatelimitEventType, User};
+
+auto_derived!(
+    /// Account nominated to help recover another account
+    pub struct RecoveryContact {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the user who may be recovered
+        pub user: String,
+        /// Id of the user who can approve a recovery
+        pub contact: String,
+        /// Whether the contact has agreed to help
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub accepted: bool,
+        /// Time at which the contact was nominated
+        pub created_at: Timestamp,
+    }
+
+    /// Request to recover an account
+    ///
+    /// Recoveries must be approved by a recovery contact and can only be
+    /// completed once a delay has passed, giving the owner time to notice
+    /// and cancel a recovery they did not start.
+    pub struct RecoveryRequest {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the user being recovered
+        pub user: String,
+        /// Id of the recovery contact asked to approve this request
+        pub contact: String,
+        /// Secret token required to complete the recovery
+        pub token: String,
+        /// Time at which the contact approved this request
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub approved_at: Option<Timestamp>,
+        /// Time after which this request may be completed
+        pub available_at: Timestamp,
+        /// Time after which this request is discarded
+        pub expires_at: Timestamp,
+        /// Time at which this request was started
+        pub created_at: Timestamp,
+    }
+
+    /// Backup code generation history of an account
+    pub struct BackupCodes {
+        /// Id of the user
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Number of codes issued when they were last generated
+        pub issued: usize,
+        /// Number of times backup codes have been regenerated
+        pub regenerations: u32,
+        /// Time at which backup codes were last generated
+        pub generated_at: Timestamp,
+    }
+);
+
+impl RecoveryContact {
+    /// Nominate a friend as a recovery contact
+    pub async fn create(db: &Database, user: &User, contact: &User) -> Result<RecoveryContact> {
+        if user.id == contact.id || contact.bot.is_some() || !user.is_friends_with(&contact.id) {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        let contacts = db.fetch_recovery_contacts(&user.id).await?;
+        let existing: Vec<_> = contacts
+            .iter()
+            .filter(|entry| entry.user == user.id)
+            .collect();
+
+        if existing.iter().any(|entry| entry.contact == contact.id) {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        let max_contacts = config().await.features.account_recovery.max_contacts;
+        if existing.len() >= max_contacts {
+            return Err(create_error!(TooManyRecoveryContacts { max: max_contacts }));
+        }
+
+        let contact = RecoveryContact {
+            id: Ulid::new().to_string(),
+            user: user.id.to_string(),
+            contact: contact.id.to_string(),
+            accepted: false,
+            created_at: Timestamp::now_utc(),
+        };
+
+        db.insert_recovery_contact(&contact).await?;
+        Ok(contact)
+    }
+
+    /// Agree to help recover the nominating account
+    pub async fn accept(&mut self, db: &Database) -> Result<()> {
+        db.accept_recovery_contact(&self.id).await?;
+        self.accepted = true;
+        Ok(())
+    }
+}
+
+impl RecoveryRequest {
+    /// Count an attempt to start recovering an account, failing if there were too many
+    ///
+    /// Attempts are counted before credentials are checked so this also
+    /// limits guessing the account password.
+    pub async fn take_attempt(db: &Database, user: &str) -> Result<()> {
+        if db
+            .has_ratelimited(
+                user,
+                RatelimitEventType::AccountRecovery,
+                Duration::from_secs(60 * 60 * 24),
+                config().await.features.account_recovery.attempts_per_day,
+            )
+            .await?
+        {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        RatelimitEvent::create(db, user.to_string(), RatelimitEventType::AccountRecovery).await
+    }
+
+    /// Start recovering an account through one of its recovery contacts
+    ///
+    /// Only one recovery may be in progress per account at a time.
+    pub async fn create(db: &Database, contact: &RecoveryContact) -> Result<RecoveryRequest> {
+        if !contact.accepted {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        if db
+            .fetch_recovery_requests(&contact.user)
+            .await?
+            .iter()
+            .any(|request| request.user == contact.user && !request.is_expired())
+        {
+            return Err(create_error!(RecoveryInProgress));
+        }
+
+        let settings = config().await.features.account_recovery;
+        let now = Timestamp::now_utc();
+        let request = RecoveryRequest {
+            id: Ulid::new().to_string(),
+            user: contact.user.to_string(),
+            contact: contact.contact.to_string(),
+            token: nanoid::nanoid!(64),
+            approved_at: None,
+            available_at: now
+                .checked_add(iso8601_timestamp::Duration::hours(
+                    settings.delay_hours as i64,
+                ))
+                .expect("failed to compute recovery delay"),
+            expires_at: now
+                .checked_add(iso8601_timestamp::Duration::hours(
+                    settings.expiry_hours as i64,
+                ))
+                .expect("failed to compute recovery expiry"),
+            created_at: now,
+        };
+
+        db.insert_recovery_request(&request).await?;
+
+        EventV1::RecoveryRequest(request.clone().into())
+            .private(request.user.to_string())
+            .await;
+        EventV1::RecoveryRequest(request.clone().into())
+            .private(request.contact.to_string())
+            .await;
+
+        Ok(request)
+    }
+
+    /// Whether this request can no longer be completed
+    pub fn is_expired(&self) -> bool {
+        Timestamp::now_utc() >= self.expires_at
+    }
+
+    /// Whether this request has been approved and its delay has passed
+    pub fn can_complete(&self) -> bool {
+        self.approved_at.is_some()
+            && Timestamp::now_utc() >= self.available_at
+            && !self.is_expired()
+    }
+
+    /// Approve this request as the recovery contact
+    pub async fn approve(&mut self, db: &Database) -> Result<()> {
+        if self.approved_at.is_some() || self.is_expired() {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        let now = Timestamp::now_utc();
+        db.approve_recovery_request(&self.id, now).await?;
+        self.approved_at = Some(now);
+
+        EventV1::RecoveryRequest(self.clone().into())
+            .private(self.user.to_string())
+            .await;
+
+        Ok(())
+    }
+
+    /// Warn the owner of the account that a recovery was started
+    pub async fn send_notice_email(&self, authifier: &Authifier) -> Result<()> {
+        let EmailVerificationConfig::Enabled { smtp, .. } = &authifier.config.email_verification
+        else {
+            return Ok(());
+        };
+
+        let account = authifier
+            .database
+            .find_account(&self.user)
+            .await
+            .map_err(|_| create_error!(InternalError))?;
+
+        smtp.send_email(
+            account.email.clone(),
+            &Template {
+                title: "Someone is recovering your account.".to_string(),
+                html: None,
+                text: include_str!("../../../templates/account-recovery.txt").to_owned(),
+                url: Default::default(),
+            },
+            json!({
+                "email": account.email,
+                "available_at": self.available_at,
+                "url": format!("{}/settings/account", config().await.hosts.app),
+            }),
+        )
+        .map_err(|_| create_error!(InternalError))
+    }
+}
+
+impl BackupCodes {
+    /// Check whether backup codes may be regenerated, failing if there were too many
+    pub async fn check_regeneration(db: &Database, user: &str) -> Result<()> {
+        if db
+            .has_ratelimited(
+                user,
+                RatelimitEventType::BackupCodeRegeneration,
+                Duration::from_secs(60 * 60 * 24),
+                config()
+                    .await
+                    .features
+                    .account_recovery
+                    .backup_code_regenerations_per_day,
+            )
+            .await?
+        {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        Ok(())
+    }
+
+    /// Record that a new set of backup codes was generated
+    pub async fn record(db: &Database, user: &str, issued: usize) -> Result<BackupCodes> {
+        RatelimitEvent::create(
+            db,
+            user.to_string(),
+            RatelimitEventType::BackupCodeRegeneration,
+        )
+        .await?;
+
+        let regenerations = match db.fetch_backup_codes(user).await {
+            Ok(codes) => codes.regenerations + 1,
+            Err(err) if matches!(err.error_type, ErrorType::NotFound) => 0,
+            Err(err) => return Err(err),
+        };
+
+        let codes = BackupCodes {
+            id: user.to_string(),
+            issued,
+            regenerations,
+            generated_at: Timestamp::now_utc(),
+        };
+
+        db.save_backup_codes(&codes).await?;
+        Ok(codes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use guilderia_result::ErrorType;
+    use iso8601_timestamp::Timestamp;
+
+    use crate::{RecoveryContact, RecoveryRequest};
+
+    #[async_std::test]
+    async fn recovery_requires_approval_and_delay() {
+        database_test!(|db| async move {
+            let mut contact = RecoveryContact {
+                id: "contact".to_string(),
+                user: "user".to_string(),
+                contact: "friend".to_string(),
+                accepted: false,
+                created_at: Timestamp::now_utc(),
+            };
+
+            db.insert_recovery_contact(&contact).await.unwrap();
+            assert!(RecoveryRequest::create(&db, &contact).await.is_err());
+
+            contact.accept(&db).await.unwrap();
+            let mut request = RecoveryRequest::create(&db, &contact).await.unwrap();
+            assert!(!request.can_complete());
+
+            let err = RecoveryRequest::create(&db, &contact).await.unwrap_err();
+            assert!(matches!(err.error_type, ErrorType::RecoveryInProgress));
+
+            request.approve(&db).await.unwrap();
+            assert!(request.approve(&db).await.is_err());
+
+            let request = db.fetch_recovery_request(&request.id).await.unwrap();
+            assert!(request.approved_at.is_some());
+            assert!(!request.can_complete());
+        });
+    }
+}
@@ -0,0 +1,49 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+
+use crate::{BackupCodes, RecoveryContact, RecoveryRequest};
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractAccountRecovery: Sync + Send {
+    /// Insert new recovery contact into database
+    async fn insert_recovery_contact(&self, contact: &RecoveryContact) -> Result<()>;
+
+    /// Fetch a recovery contact by its id
+    async fn fetch_recovery_contact(&self, id: &str) -> Result<RecoveryContact>;
+
+    /// Fetch all recovery contacts nominated by or nominating a user
+    async fn fetch_recovery_contacts(&self, user_id: &str) -> Result<Vec<RecoveryContact>>;
+
+    /// Mark a recovery contact as accepted
+    async fn accept_recovery_contact(&self, id: &str) -> Result<()>;
+
+    /// Delete a recovery contact
+    async fn delete_recovery_contact(&self, id: &str) -> Result<()>;
+
+    /// Insert new recovery request into database
+    async fn insert_recovery_request(&self, request: &RecoveryRequest) -> Result<()>;
+
+    /// Fetch a recovery request by its id
+    async fn fetch_recovery_request(&self, id: &str) -> Result<RecoveryRequest>;
+
+    /// Fetch all recovery requests for or approvable by a user
+    async fn fetch_recovery_requests(&self, user_id: &str) -> Result<Vec<RecoveryRequest>>;
+
+    /// Mark a recovery request as approved
+    async fn approve_recovery_request(&self, id: &str, approved_at: Timestamp) -> Result<()>;
+
+    /// Delete a recovery request
+    async fn delete_recovery_request(&self, id: &str) -> Result<()>;
+
+    /// Fetch backup code history of a user
+    async fn fetch_backup_codes(&self, user_id: &str) -> Result<BackupCodes>;
+
+    /// Insert or replace backup code history of a user
+    async fn save_backup_codes(&self, codes: &BackupCodes) -> Result<()>;
+
+    /// Delete all recovery contacts, requests and backup code history involving a user
+    async fn delete_account_recovery(&self, user_id: &str) -> Result<()>;
+}
@@ -0,0 +1,155 @@
+use bson::{to_bson, Document};
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+use mongodb::options::ReplaceOptions;
+
+use crate::MongoDb;
+use crate::{BackupCodes, RecoveryContact, RecoveryRequest};
+
+use super::AbstractAccountRecovery;
+
+static CONTACTS: &str = "recovery_contacts";
+static REQUESTS: &str = "recovery_requests";
+static BACKUP_CODES: &str = "backup_codes";
+
+#[async_trait]
+impl AbstractAccountRecovery for MongoDb {
+    /// Insert new recovery contact into database
+    async fn insert_recovery_contact(&self, contact: &RecoveryContact) -> Result<()> {
+        query!(self, insert_one, CONTACTS, &contact).map(|_| ())
+    }
+
+    /// Fetch a recovery contact by its id
+    async fn fetch_recovery_contact(&self, id: &str) -> Result<RecoveryContact> {
+        query!(self, find_one_by_id, CONTACTS, id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all recovery contacts nominated by or nominating a user
+    async fn fetch_recovery_contacts(&self, user_id: &str) -> Result<Vec<RecoveryContact>> {
+        query!(
+            self,
+            find,
+            CONTACTS,
+            doc! {
+                "$or": [
+                    { "user": user_id },
+                    { "contact": user_id }
+                ]
+            }
+        )
+    }
+
+    /// Mark a recovery contact as accepted
+    async fn accept_recovery_contact(&self, id: &str) -> Result<()> {
+        self.col::<Document>(CONTACTS)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$set": {
+                        "accepted": true
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", CONTACTS))
+    }
+
+    /// Delete a recovery contact
+    async fn delete_recovery_contact(&self, id: &str) -> Result<()> {
+        query!(self, delete_one_by_id, CONTACTS, id).map(|_| ())
+    }
+
+    /// Insert new recovery request into database
+    async fn insert_recovery_request(&self, request: &RecoveryRequest) -> Result<()> {
+        query!(self, insert_one, REQUESTS, &request).map(|_| ())
+    }
+
+    /// Fetch a recovery request by its id
+    async fn fetch_recovery_request(&self, id: &str) -> Result<RecoveryRequest> {
+        query!(self, find_one_by_id, REQUESTS, id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all recovery requests for or approvable by a user
+    async fn fetch_recovery_requests(&self, user_id: &str) -> Result<Vec<RecoveryRequest>> {
+        query!(
+            self,
+            find,
+            REQUESTS,
+            doc! {
+                "$or": [
+                    { "user": user_id },
+                    { "contact": user_id }
+                ]
+            }
+        )
+    }
+
+    /// Mark a recovery request as approved
+    async fn approve_recovery_request(&self, id: &str, approved_at: Timestamp) -> Result<()> {
+        self.col::<Document>(REQUESTS)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$set": {
+                        "approved_at": to_bson(&approved_at)
+                            .map_err(|_| create_database_error!("to_bson", "approved_at"))?
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", REQUESTS))
+    }
+
+    /// Delete a recovery request
+    async fn delete_recovery_request(&self, id: &str) -> Result<()> {
+        query!(self, delete_one_by_id, REQUESTS, id).map(|_| ())
+    }
+
+    /// Fetch backup code history of a user
+    async fn fetch_backup_codes(&self, user_id: &str) -> Result<BackupCodes> {
+        query!(self, find_one_by_id, BACKUP_CODES, user_id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Insert or replace backup code history of a user
+    async fn save_backup_codes(&self, codes: &BackupCodes) -> Result<()> {
+        self.col::<BackupCodes>(BACKUP_CODES)
+            .replace_one(
+                doc! {
+                    "_id": &codes.id
+                },
+                codes,
+            )
+            .with_options(ReplaceOptions::builder().upsert(true).build())
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("replace_one", BACKUP_CODES))
+    }
+
+    /// Delete all recovery contacts, requests and backup code history involving a user
+    async fn delete_account_recovery(&self, user_id: &str) -> Result<()> {
+        let filter = doc! {
+            "$or": [
+                { "user": user_id },
+                { "contact": user_id }
+            ]
+        };
+
+        self.col::<Document>(CONTACTS)
+            .delete_many(filter.clone())
+            .await
+            .map_err(|_| create_database_error!("delete_many", CONTACTS))?;
+
+        self.col::<Document>(REQUESTS)
+            .delete_many(filter)
+            .await
+            .map_err(|_| create_database_error!("delete_many", REQUESTS))?;
+
+        query!(self, delete_one_by_id, BACKUP_CODES, user_id).map(|_| ())
+    }
+}
@@ -0,0 +1,142 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+
+use crate::ReferenceDb;
+use crate::{BackupCodes, RecoveryContact, RecoveryRequest};
+
+use super::AbstractAccountRecovery;
+
+#[async_trait]
+impl AbstractAccountRecovery for ReferenceDb {
+    /// Insert new recovery contact into database
+    async fn insert_recovery_contact(&self, contact: &RecoveryContact) -> Result<()> {
+        let mut contacts = self.recovery_contacts.lock().await;
+        if contacts.contains_key(&contact.id) {
+            Err(create_database_error!("insert", "recovery_contact"))
+        } else {
+            contacts.insert(contact.id.to_string(), contact.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a recovery contact by its id
+    async fn fetch_recovery_contact(&self, id: &str) -> Result<RecoveryContact> {
+        let contacts = self.recovery_contacts.lock().await;
+        contacts
+            .get(id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all recovery contacts nominated by or nominating a user
+    async fn fetch_recovery_contacts(&self, user_id: &str) -> Result<Vec<RecoveryContact>> {
+        let contacts = self.recovery_contacts.lock().await;
+        Ok(contacts
+            .values()
+            .filter(|contact| contact.user == user_id || contact.contact == user_id)
+            .cloned()
+            .collect())
+    }
+
+    /// Mark a recovery contact as accepted
+    async fn accept_recovery_contact(&self, id: &str) -> Result<()> {
+        let mut contacts = self.recovery_contacts.lock().await;
+        if let Some(contact) = contacts.get_mut(id) {
+            contact.accepted = true;
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Delete a recovery contact
+    async fn delete_recovery_contact(&self, id: &str) -> Result<()> {
+        let mut contacts = self.recovery_contacts.lock().await;
+        if contacts.remove(id).is_some() {
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Insert new recovery request into database
+    async fn insert_recovery_request(&self, request: &RecoveryRequest) -> Result<()> {
+        let mut requests = self.recovery_requests.lock().await;
+        if requests.contains_key(&request.id) {
+            Err(create_database_error!("insert", "recovery_request"))
+        } else {
+            requests.insert(request.id.to_string(), request.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a recovery request by its id
+    async fn fetch_recovery_request(&self, id: &str) -> Result<RecoveryRequest> {
+        let requests = self.recovery_requests.lock().await;
+        requests
+            .get(id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all recovery requests for or approvable by a user
+    async fn fetch_recovery_requests(&self, user_id: &str) -> Result<Vec<RecoveryRequest>> {
+        let requests = self.recovery_requests.lock().await;
+        Ok(requests
+            .values()
+            .filter(|request| request.user == user_id || request.contact == user_id)
+            .cloned()
+            .collect())
+    }
+
+    /// Mark a recovery request as approved
+    async fn approve_recovery_request(&self, id: &str, approved_at: Timestamp) -> Result<()> {
+        let mut requests = self.recovery_requests.lock().await;
+        if let Some(request) = requests.get_mut(id) {
+            request.approved_at = Some(approved_at);
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Delete a recovery request
+    async fn delete_recovery_request(&self, id: &str) -> Result<()> {
+        let mut requests = self.recovery_requests.lock().await;
+        if requests.remove(id).is_some() {
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Fetch backup code history of a user
+    async fn fetch_backup_codes(&self, user_id: &str) -> Result<BackupCodes> {
+        let backup_codes = self.backup_codes.lock().await;
+        backup_codes
+            .get(user_id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Insert or replace backup code history of a user
+    async fn save_backup_codes(&self, codes: &BackupCodes) -> Result<()> {
+        let mut backup_codes = self.backup_codes.lock().await;
+        backup_codes.insert(codes.id.to_string(), codes.clone());
+        Ok(())
+    }
+
+    /// Delete all recovery contacts, requests and backup code history involving a user
+    async fn delete_account_recovery(&self, user_id: &str) -> Result<()> {
+        self.recovery_contacts
+            .lock()
+            .await
+            .retain(|_, contact| contact.user != user_id && contact.contact != user_id);
+        self.recovery_requests
+            .lock()
+            .await
+            .retain(|_, request| request.user != user_id && request.contact != user_id);
+        self.backup_codes.lock().await.remove(user_id);
+        Ok(())
+    }
+}
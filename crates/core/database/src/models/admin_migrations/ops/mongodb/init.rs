@@ -48,6 +48,30 @@ pub async fn create_database(db: &MongoDb) {
         .await
         .expect("Failed to create channel_webhooks collection.");
 
+    db.create_collection("interactions")
+        .await
+        .expect("Failed to create interactions collection.");
+
+    db.create_collection("user_apps")
+        .await
+        .expect("Failed to create user_apps collection.");
+
+    db.create_collection("screening_responses")
+        .await
+        .expect("Failed to create screening_responses collection.");
+
+    db.create_collection("starboard_entries")
+        .await
+        .expect("Failed to create starboard_entries collection.");
+
+    db.create_collection("api_tokens")
+        .await
+        .expect("Failed to create api_tokens collection.");
+
+    db.create_collection("audit_log")
+        .await
+        .expect("Failed to create audit_log collection.");
+
     db.create_collection("migrations")
         .await
         .expect("Failed to create migrations collection.");
@@ -64,6 +88,70 @@ pub async fn create_database(db: &MongoDb) {
         .await
         .expect("Failed to create user_settings collection.");
 
+    db.create_collection("notification_summaries")
+        .await
+        .expect("Failed to create notification_summaries collection.");
+
+    db.create_collection("delayed_notifications")
+        .await
+        .expect("Failed to create delayed_notifications collection.");
+
+    db.create_collection("pending_actions")
+        .await
+        .expect("Failed to create pending_actions collection.");
+
+    db.create_collection("ban_subscriptions")
+        .await
+        .expect("Failed to create ban_subscriptions collection.");
+
+    db.create_collection("filter_words")
+        .await
+        .expect("Failed to create filter_words collection.");
+
+    db.create_collection("server_applications")
+        .await
+        .expect("Failed to create server_applications collection.");
+
+    db.create_collection("purge_schedules")
+        .await
+        .expect("Failed to create purge_schedules collection.");
+
+    db.create_collection("threads")
+        .await
+        .expect("Failed to create threads collection.");
+
+    db.create_collection("bookmarks")
+        .await
+        .expect("Failed to create bookmarks collection.");
+
+    db.create_collection("hash_matches")
+        .await
+        .expect("Failed to create hash_matches collection.");
+
+    db.create_collection("read_receipts")
+        .await
+        .expect("Failed to create read_receipts collection.");
+
+    db.create_collection("devices")
+        .await
+        .expect("Failed to create devices collection.");
+
+    db.create_collection("recovery_contacts")
+        .await
+        .expect("Failed to create recovery_contacts collection.");
+
+    db.create_collection("recovery_requests")
+        .await
+        .expect("Failed to create recovery_requests collection.");
+
+    db.create_collection("backup_codes")
+        .await
+        .expect("Failed to create backup_codes collection.");
+
+    db.create_collection("impersonations")
+        .await
+        .expect("Failed to create impersonations collection.");
+
     db.create_collection("policy_changes")
         .await
         .expect("Failed to create policy_changes collection.");
@@ -158,6 +246,21 @@ pub async fn create_database(db: &MongoDb) {
                 },
                 "name": "channel_pinned_compound"
             },
+            {
+                "key": {
+                    "thread": 1_i32,
+                    "_id": 1_i32
+                },
+                "name": "thread_id_compound",
+                "sparse": true
+            },
+            {
+                "key": {
+                    "expires_at": 1_i32
+                },
+                "name": "expires_at",
+                "sparse": true
+            },
         ]
     })
     .await
@@ -199,12 +302,62 @@ pub async fn create_database(db: &MongoDb) {
                     "_id.user": 1_i32,
                 },
                 "name": "user_id"
+            },
+            {
+                "key": {
+                    "role_expiries.expires_at": 1_i32,
+                },
+                "name": "role_expiries"
+            },
+            {
+                "key": {
+                    "_id.server": 1_i32,
+                    "nickname": 1_i32,
+                },
+                "name": "server_nickname"
             }
         ]
     })
     .await
     .expect("Failed to create server_members index.");
 
+    db.run_command(doc! {
+        "createIndexes": "channels",
+        "indexes": [
+            {
+                "key": {
+                    "server": 1_i32,
+                    "name": 1_i32,
+                },
+                "name": "server_name"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create channels index.");
+
+    db.run_command(doc! {
+        "createIndexes": "user_settings",
+        "indexes": [
+            {
+                "key": {
+                    "highlights": 1_i32
+                },
+                "name": "highlights",
+                "sparse": true
+            },
+            {
+                "key": {
+                    "data_minimisation": 1_i32
+                },
+                "name": "data_minimisation",
+                "sparse": true
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create user_settings index.");
+
     db.run_command(doc! {
         "createIndexes": "attachments",
         "indexes": [
@@ -263,5 +416,318 @@ pub async fn create_database(db: &MongoDb) {
     .await
     .expect("Failed to create ratelimit_events index.");
 
+    db.run_command(doc! {
+        "createIndexes": "servers",
+        "indexes": [
+            {
+                "key": {
+                    "tag": 1_i32
+                },
+                "name": "tag",
+                "unique": true,
+                "partialFilterExpression": {
+                    "tag": {
+                        "$exists": true
+                    }
+                }
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create servers index.");
+
+    db.run_command(doc! {
+        "createIndexes": "user_apps",
+        "indexes": [
+            {
+                "key": {
+                    "_id.user": 1_i32
+                },
+                "name": "user"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create user_apps index.");
+
+    db.run_command(doc! {
+        "createIndexes": "screening_responses",
+        "indexes": [
+            {
+                "key": {
+                    "left_at": 1_i32
+                },
+                "name": "left_at"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create screening_responses index.");
+
+    db.run_command(doc! {
+        "createIndexes": "api_tokens",
+        "indexes": [
+            {
+                "key": {
+                    "token_hash": 1_i32
+                },
+                "name": "token_hash",
+                "unique": true
+            },
+            {
+                "key": {
+                    "user": 1_i32
+                },
+                "name": "user"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create api_tokens index.");
+
+    db.run_command(doc! {
+        "createIndexes": "audit_log",
+        "indexes": [
+            {
+                "key": {
+                    "server": 1_i32,
+                    "_id": -1_i32
+                },
+                "name": "server"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create audit_log index.");
+
+    db.run_command(doc! {
+        "createIndexes": "pending_actions",
+        "indexes": [
+            {
+                "key": {
+                    "server": 1_i32
+                },
+                "name": "server"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create pending_actions index.");
+
+    db.run_command(doc! {
+        "createIndexes": "server_applications",
+        "indexes": [
+            {
+                "key": {
+                    "server": 1_i32
+                },
+                "name": "server"
+            },
+            {
+                "key": {
+                    "status": 1_i32
+                },
+                "name": "status"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create server_applications index.");
+
+    db.run_command(doc! {
+        "createIndexes": "purge_schedules",
+        "indexes": [
+            {
+                "key": {
+                    "next_run": 1_i32
+                },
+                "name": "next_run"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create purge_schedules index.");
+
+    db.run_command(doc! {
+        "createIndexes": "threads",
+        "indexes": [
+            {
+                "key": {
+                    "channel": 1_i32
+                },
+                "name": "channel"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create threads index.");
+
+    db.run_command(doc! {
+        "createIndexes": "bookmarks",
+        "indexes": [
+            {
+                "key": {
+                    "user": 1_i32
+                },
+                "name": "user"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create bookmarks index.");
+
+    db.run_command(doc! {
+        "createIndexes": "hash_matches",
+        "indexes": [
+            {
+                "key": {
+                    "user": 1_i32
+                },
+                "name": "user"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create hash_matches index.");
+
+    db.run_command(doc! {
+        "createIndexes": "read_receipts",
+        "indexes": [
+            {
+                "key": {
+                    "_id.channel": 1_i32,
+                    "_id.user": 1_i32,
+                },
+                "name": "compound_id"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create read_receipts index.");
+
+    db.run_command(doc! {
+        "createIndexes": "ban_subscriptions",
+        "indexes": [
+            {
+                "key": {
+                    "server": 1_i32
+                },
+                "name": "server"
+            },
+            {
+                "key": {
+                    "source": 1_i32
+                },
+                "name": "source"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create ban_subscriptions index.");
+
+    db.run_command(doc! {
+        "createIndexes": "interactions",
+        "indexes": [
+            {
+                "key": {
+                    "channel": 1_i32
+                },
+                "name": "channel"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create interactions index.");
+
+    db.run_command(doc! {
+        "createIndexes": "devices",
+        "indexes": [
+            {
+                "key": {
+                    "user": 1_i32
+                },
+                "name": "user"
+            },
+            {
+                "key": {
+                    "approval_token": 1_i32
+                },
+                "name": "approval_token",
+                "sparse": true
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create devices index.");
+
+    db.run_command(doc! {
+        "createIndexes": "recovery_contacts",
+        "indexes": [
+            {
+                "key": {
+                    "user": 1_i32
+                },
+                "name": "user"
+            },
+            {
+                "key": {
+                    "contact": 1_i32
+                },
+                "name": "contact"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create recovery_contacts index.");
+
+    db.run_command(doc! {
+        "createIndexes": "recovery_requests",
+        "indexes": [
+            {
+                "key": {
+                    "user": 1_i32
+                },
+                "name": "user"
+            },
+            {
+                "key": {
+                    "contact": 1_i32
+                },
+                "name": "contact"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create recovery_requests index.");
+
+    db.run_command(doc! {
+        "createIndexes": "impersonations",
+        "indexes": [
+            {
+                "key": {
+                    "token": 1_i32
+                },
+                "name": "token",
+                "unique": true
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create impersonations index.");
+
+    db.run_command(doc! {
+        "createIndexes": "delayed_notifications",
+        "indexes": [
+            {
+                "key": {
+                    "deliver_at": 1_i32
+                },
+                "name": "deliver_at"
+            }
+        ]
+    })
+    .await
+    .expect("Failed to create delayed_notifications index.");
+
     info!("Created database.");
 }
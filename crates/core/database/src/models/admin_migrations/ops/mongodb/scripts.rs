@@ -22,7 +22,7 @@ struct MigrationInfo {
     revision: i32,
 }
 
-pub const LATEST_REVISION: i32 = 41; // MUST BE +1 to last migration
+pub const LATEST_REVISION: i32 = 69; // MUST BE +1 to last migration
 
 pub async fn migrate_database(db: &MongoDb) {
     let migrations = db.col::<Document>("migrations");
@@ -1165,6 +1165,653 @@ pub async fn run_migrations(db: &MongoDb, revision: i32) -> i32 {
             .expect("failed to update users");
     }
 
+    if revision <= 41 {
+        info!("Running migration [revision 41 / 15-10-2026]: Add unique index on server tags.");
+
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "servers",
+                "indexes": [
+                    {
+                        "key": {
+                            "tag": 1_i32
+                        },
+                        "name": "tag",
+                        "unique": true,
+                        "partialFilterExpression": {
+                            "tag": {
+                                "$exists": true
+                            }
+                        }
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create servers index.");
+    }
+
+    if revision <= 42 {
+        info!("Running migration [revision 42 / 15-10-2026]: Add collection `interactions` if not exists.");
+
+        db.db().create_collection("interactions").await.ok();
+    }
+
+    if revision <= 43 {
+        info!("Running migration [revision 43 / 15-10-2026]: Add collection `user_apps` if not exists.");
+
+        db.db().create_collection("user_apps").await.ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "user_apps",
+                "indexes": [
+                    {
+                        "key": {
+                            "_id.user": 1_i32
+                        },
+                        "name": "user"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create user_apps index.");
+    }
+
+    if revision <= 44 {
+        info!("Running migration [revision 44 / 15-10-2026]: Add index for member role expiries.");
+
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "server_members",
+                "indexes": [
+                    {
+                        "key": {
+                            "role_expiries.expires_at": 1_i32
+                        },
+                        "name": "role_expiries"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create server_members index.");
+    }
+
+    if revision <= 45 {
+        info!("Running migration [revision 45 / 15-10-2026]: Add collection `screening_responses` if not exists.");
+
+        db.db().create_collection("screening_responses").await.ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "screening_responses",
+                "indexes": [
+                    {
+                        "key": {
+                            "left_at": 1_i32
+                        },
+                        "name": "left_at"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create screening_responses index.");
+    }
+
+    if revision <= 46 {
+        info!("Running migration [revision 46 / 15-10-2026]: Add collection `starboard_entries` if not exists.");
+
+        db.db().create_collection("starboard_entries").await.ok();
+    }
+
+    if revision <= 47 {
+        info!("Running migration [revision 47 / 15-10-2026]: Add collection `api_tokens` if not exists.");
+
+        db.db().create_collection("api_tokens").await.ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "api_tokens",
+                "indexes": [
+                    {
+                        "key": {
+                            "token": 1_i32
+                        },
+                        "name": "token",
+                        "unique": true
+                    },
+                    {
+                        "key": {
+                            "user": 1_i32
+                        },
+                        "name": "user"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create api_tokens index.");
+    }
+
+    if revision <= 48 {
+        info!("Running migration [revision 48 / 15-10-2026]: Add prefix indexes for mention autocomplete.");
+
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "server_members",
+                "indexes": [
+                    {
+                        "key": {
+                            "_id.server": 1_i32,
+                            "nickname": 1_i32
+                        },
+                        "name": "server_nickname"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create server_members index.");
+
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "channels",
+                "indexes": [
+                    {
+                        "key": {
+                            "server": 1_i32,
+                            "name": 1_i32
+                        },
+                        "name": "server_name"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create channels index.");
+    }
+
+    if revision <= 49 {
+        info!("Running migration [revision 49 / 15-10-2026]: Add index for highlight keywords.");
+
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "user_settings",
+                "indexes": [
+                    {
+                        "key": {
+                            "highlights": 1_i32
+                        },
+                        "name": "highlights",
+                        "sparse": true
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create user_settings index.");
+    }
+
+    if revision <= 50 {
+        info!("Running migration [revision 50 / 15-10-2026]: Add collection `audit_log` if not exists.");
+
+        db.db().create_collection("audit_log").await.ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "audit_log",
+                "indexes": [
+                    {
+                        "key": {
+                            "server": 1_i32,
+                            "_id": -1_i32
+                        },
+                        "name": "server"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create audit_log index.");
+    }
+
+    if revision <= 51 {
+        info!("Running migration [revision 51 / 15-10-2026]: Add collection `pending_actions` if not exists.");
+
+        db.db().create_collection("pending_actions").await.ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "pending_actions",
+                "indexes": [
+                    {
+                        "key": {
+                            "server": 1_i32
+                        },
+                        "name": "server"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create pending_actions index.");
+    }
+
+    if revision <= 52 {
+        info!("Running migration [revision 52 / 15-10-2026]: Add index for data minimisation settings.");
+
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "user_settings",
+                "indexes": [
+                    {
+                        "key": {
+                            "data_minimisation": 1_i32
+                        },
+                        "name": "data_minimisation",
+                        "sparse": true
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create user_settings index.");
+    }
+
+    if revision <= 53 {
+        info!("Running migration [revision 53 / 15-10-2026]: Add collection `devices` if not exists.");
+
+        db.db().create_collection("devices").await.ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "devices",
+                "indexes": [
+                    {
+                        "key": {
+                            "user": 1_i32
+                        },
+                        "name": "user"
+                    },
+                    {
+                        "key": {
+                            "approval_token": 1_i32
+                        },
+                        "name": "approval_token",
+                        "sparse": true
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create devices index.");
+    }
+
+    if revision <= 54 {
+        info!("Running migration [revision 54 / 15-10-2026]: Add account recovery collections if not exists.");
+
+        db.db().create_collection("recovery_contacts").await.ok();
+        db.db().create_collection("recovery_requests").await.ok();
+        db.db().create_collection("backup_codes").await.ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "recovery_contacts",
+                "indexes": [
+                    {
+                        "key": {
+                            "user": 1_i32
+                        },
+                        "name": "user"
+                    },
+                    {
+                        "key": {
+                            "contact": 1_i32
+                        },
+                        "name": "contact"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create recovery_contacts index.");
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "recovery_requests",
+                "indexes": [
+                    {
+                        "key": {
+                            "user": 1_i32
+                        },
+                        "name": "user"
+                    },
+                    {
+                        "key": {
+                            "contact": 1_i32
+                        },
+                        "name": "contact"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create recovery_requests index.");
+    }
+
+    if revision <= 55 {
+        info!("Running migration [revision 55 / 15-10-2026]: Add collection `impersonations` if not exists.");
+
+        db.db().create_collection("impersonations").await.ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "impersonations",
+                "indexes": [
+                    {
+                        "key": {
+                            "token": 1_i32
+                        },
+                        "name": "token",
+                        "unique": true
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create impersonations index.");
+    }
+
+    if revision <= 56 {
+        info!("Running migration [revision 56 / 15-10-2026]: Quarantine attachments which failed a virus scan.");
+
+        db.col::<Document>("attachments")
+            .update_many(
+                doc! {
+                    "scan.signature": {
+                        "$exists": true
+                    },
+                    "deleted": {
+                        "$ne": true
+                    }
+                },
+                doc! {
+                    "$set": {
+                        "quarantined": true
+                    }
+                },
+            )
+            .await
+            .expect("Failed to quarantine attachments.");
+    }
+
+    if revision <= 57 {
+        info!("Running migration [revision 57 / 15-10-2026]: Add collection `ban_subscriptions` if not exists.");
+
+        db.db().create_collection("ban_subscriptions").await.ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "ban_subscriptions",
+                "indexes": [
+                    {
+                        "key": {
+                            "server": 1_i32
+                        },
+                        "name": "server"
+                    },
+                    {
+                        "key": {
+                            "source": 1_i32
+                        },
+                        "name": "source"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create ban_subscriptions index.");
+    }
+
+    if revision <= 58 {
+        info!("Running migration [revision 58 / 15-10-2026]: Add channel index to `interactions`.");
+
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "interactions",
+                "indexes": [
+                    {
+                        "key": {
+                            "channel": 1_i32
+                        },
+                        "name": "channel"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create interactions index.");
+    }
+
+    if revision <= 59 {
+        info!("Running migration [revision 59 / 15-10-2026]: Add collection `filter_words` if not exists.");
+
+        db.db().create_collection("filter_words").await.ok();
+    }
+
+    if revision <= 60 {
+        info!("Running migration [revision 60 / 15-10-2026]: Add collection `server_applications` if not exists.");
+
+        db.db().create_collection("server_applications").await.ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "server_applications",
+                "indexes": [
+                    {
+                        "key": {
+                            "server": 1_i32
+                        },
+                        "name": "server"
+                    },
+                    {
+                        "key": {
+                            "status": 1_i32
+                        },
+                        "name": "status"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create server_applications index.");
+    }
+
+    if revision <= 61 {
+        info!("Running migration [revision 61 / 15-10-2026]: Add collection `purge_schedules` if not exists.");
+
+        db.db().create_collection("purge_schedules").await.ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "purge_schedules",
+                "indexes": [
+                    {
+                        "key": {
+                            "next_run": 1_i32
+                        },
+                        "name": "next_run"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create purge_schedules index.");
+    }
+
+    if revision <= 62 {
+        info!("Running migration [revision 62 / 15-10-2026]: Add collection `threads` if not exists.");
+
+        db.db().create_collection("threads").await.ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "threads",
+                "indexes": [
+                    {
+                        "key": {
+                            "channel": 1_i32
+                        },
+                        "name": "channel"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create threads index.");
+
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "messages",
+                "indexes": [
+                    {
+                        "key": {
+                            "thread": 1_i32,
+                            "_id": 1_i32
+                        },
+                        "name": "thread_id_compound",
+                        "sparse": true
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create messages index.");
+    }
+
+    if revision <= 63 {
+        info!("Running migration [revision 63 / 15-10-2026]: Add collection `bookmarks` if not exists.");
+
+        db.db().create_collection("bookmarks").await.ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "bookmarks",
+                "indexes": [
+                    {
+                        "key": {
+                            "user": 1_i32
+                        },
+                        "name": "user"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create bookmarks index.");
+    }
+
+    if revision <= 64 {
+        info!("Running migration [revision 64 / 15-10-2026]: Add collection `hash_matches` if not exists.");
+
+        db.db().create_collection("hash_matches").await.ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "hash_matches",
+                "indexes": [
+                    {
+                        "key": {
+                            "user": 1_i32
+                        },
+                        "name": "user"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create hash_matches index.");
+    }
+
+    if revision <= 65 {
+        info!("Running migration [revision 65 / 15-10-2026]: Add collection `read_receipts` if not exists.");
+
+        db.db().create_collection("read_receipts").await.ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "read_receipts",
+                "indexes": [
+                    {
+                        "key": {
+                            "_id.channel": 1_i32,
+                            "_id.user": 1_i32,
+                        },
+                        "name": "compound_id"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create read_receipts index.");
+    }
+
+    if revision <= 66 {
+        info!("Running migration [revision 66 / 15-10-2026]: Add index on message expiry.");
+
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "messages",
+                "indexes": [
+                    {
+                        "key": {
+                            "expires_at": 1_i32
+                        },
+                        "name": "expires_at",
+                        "sparse": true
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create message index.");
+    }
+
+    if revision <= 67 {
+        info!("Running migration [revision 67 / 15-10-2026]: Store hashes of API tokens.");
+
+        let api_tokens = db.col::<Document>("api_tokens");
+        let mut cursor = api_tokens
+            .find(doc! { "token": { "$exists": true } })
+            .await
+            .unwrap();
+
+        while let Some(Ok(document)) = cursor.next().await {
+            let id = document.get_str("_id").unwrap().to_string();
+            let token = document.get_str("token").unwrap();
+
+            api_tokens
+                .update_one(
+                    doc! { "_id": &id },
+                    doc! {
+                        "$set": { "token_hash": crate::ApiToken::hash_secret(token) },
+                        "$unset": { "token": 1_i32 }
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        db.db()
+            .run_command(doc! {
+                "dropIndexes": "api_tokens",
+                "index": "token"
+            })
+            .await
+            .ok();
+
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "api_tokens",
+                "indexes": [
+                    {
+                        "key": {
+                            "token_hash": 1_i32
+                        },
+                        "name": "token_hash",
+                        "unique": true
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create api_tokens index.");
+    }
+
+    if revision <= 68 {
+        info!("Running migration [revision 68 / 15-10-2026]: Add collection `delayed_notifications` if not exists.");
+
+        db.db()
+            .create_collection("delayed_notifications")
+            .await
+            .ok();
+        db.db()
+            .run_command(doc! {
+                "createIndexes": "delayed_notifications",
+                "indexes": [
+                    {
+                        "key": {
+                            "deliver_at": 1_i32
+                        },
+                        "name": "deliver_at"
+                    }
+                ]
+            })
+            .await
+            .expect("Failed to create delayed_notifications index.");
+    }
+
     // Reminder to update LATEST_REVISION when adding new migrations.
     LATEST_REVISION.max(revision)
 }
@@ -0,0 +1,250 @@
+use std::time::Duration;
+
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+use sha2::{Digest, Sha256};
+use ulid::Ulid;
+
+use crate::Database;
+
+/// Maximum number of API tokens a user may hold
+pub const MAX_API_TOKENS: usize = 25;
+
+/// How stale the last used time may get before it is written again
+static LAST_USED_GRANULARITY: Duration = Duration::from_secs(60);
+
+auto_derived!(
+    /// Personal access token which lets scripts act as a user
+    pub struct ApiToken {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the user this token acts as
+        pub user: String,
+        /// Name given to this token
+        pub name: String,
+        /// SHA-256 hash of the secret token
+        pub token_hash: String,
+        /// What this token may be used for
+        pub scope: ApiTokenScope,
+        /// Time at which this token was last used
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub last_used: Option<Timestamp>,
+    }
+
+    /// What an API token may be used for
+    pub enum ApiTokenScope {
+        /// Only fetch data
+        ReadOnly,
+        /// Fetch data and send messages
+        Messaging,
+        /// Fetch data and set the user's activity
+        Activity,
+        /// Anything a session may do, apart from managing the account (`/auth` routes)
+        Admin,
+    }
+);
+
+#[allow(clippy::disallowed_methods)]
+impl ApiToken {
+    /// Create a new API token for a user, returning it along with its secret
+    ///
+    /// Only a hash of the secret is stored, so it can't be recovered later.
+    pub async fn create(
+        db: &Database,
+        user: &str,
+        name: String,
+        scope: ApiTokenScope,
+    ) -> Result<(ApiToken, String)> {
+        if db.fetch_api_tokens(user).await?.len() >= MAX_API_TOKENS {
+            return Err(create_error!(TooManyApiTokens {
+                max: MAX_API_TOKENS
+            }));
+        }
+
+        let secret = nanoid::nanoid!(64);
+        let token = ApiToken {
+            id: Ulid::new().to_string(),
+            user: user.to_string(),
+            name,
+            token_hash: ApiToken::hash_secret(&secret),
+            scope,
+            last_used: None,
+        };
+
+        db.insert_api_token(&token).await?;
+        Ok((token, secret))
+    }
+
+    /// Hash a secret token for storage and lookup
+    pub fn hash_secret(secret: &str) -> String {
+        hex::encode(Sha256::digest(secret.as_bytes()))
+    }
+
+    /// Record that this token was just used
+    pub async fn mark_used(&self, db: &Database) -> Result<()> {
+        let now = Timestamp::now_utc();
+        if self
+            .last_used
+            .is_some_and(|last_used| now.duration_since(last_used) < LAST_USED_GRANULARITY)
+        {
+            return Ok(());
+        }
+
+        db.update_api_token_last_used(&self.id, now).await
+    }
+}
+
+/// Routes which only fetch data, as `(method, path)`
+///
+/// A `*` segment in a path matches any single segment.
+static READ_ROUTES: &[(&str, &str)] = &[
+    ("GET", "/"),
+    ("GET", "/users/dms"),
+    ("GET", "/users/*"),
+    ("GET", "/users/*/profile"),
+    ("GET", "/users/*/flags"),
+    ("GET", "/users/*/mutual"),
+    ("GET", "/users/*/default_avatar"),
+    ("GET", "/channels/*"),
+    ("GET", "/channels/*/members"),
+    ("GET", "/channels/*/messages"),
+    ("GET", "/channels/*/messages/*"),
+    ("GET", "/channels/*/messages/*/thread"),
+    ("GET", "/channels/*/threads"),
+    ("GET", "/servers/*"),
+    ("GET", "/servers/*/members"),
+    ("GET", "/servers/*/members/*"),
+    ("GET", "/servers/*/roles/*"),
+    ("GET", "/servers/*/emojis"),
+    ("GET", "/custom/emoji/*"),
+    ("GET", "/invites/*"),
+    ("GET", "/sync/unreads"),
+];
+
+/// Routes which send, edit or react to messages
+static MESSAGING_ROUTES: &[(&str, &str)] = &[
+    ("POST", "/channels/*/messages"),
+    ("PATCH", "/channels/*/messages/*"),
+    ("DELETE", "/channels/*/messages/*"),
+    ("PUT", "/channels/*/messages/*/reactions/*"),
+    ("DELETE", "/channels/*/messages/*/reactions/*"),
+];
+
+/// Routes which set the user's activity
+static ACTIVITY_ROUTES: &[(&str, &str)] = &[
+    ("PUT", "/users/@me/activity"),
+    ("DELETE", "/users/@me/activity"),
+];
+
+/// Path prefixes which manage the account itself, never reachable with a token
+static ACCOUNT_PREFIXES: &[&str] = &["/auth"];
+
+/// Check whether a request matches any of the given routes
+///
+/// Requests to the versioned API are matched the same as unversioned ones.
+pub fn matches_route(routes: &[(&str, &str)], method: &str, path: &str) -> bool {
+    let path = path.strip_prefix("/0.8").unwrap_or(path);
+    let path = path.strip_suffix('/').unwrap_or(path);
+
+    routes.iter().any(|(route_method, route_path)| {
+        if *route_method != method {
+            return false;
+        }
+
+        let route_path = route_path.strip_suffix('/').unwrap_or(route_path);
+        let mut segments = path.split('/');
+        let mut route_segments = route_path.split('/');
+        loop {
+            match (segments.next(), route_segments.next()) {
+                (None, None) => return true,
+                (Some(segment), Some("*")) if !segment.is_empty() => {}
+                (Some(segment), Some(route_segment)) if segment == route_segment => {}
+                _ => return false,
+            }
+        }
+    })
+}
+
+impl ApiTokenScope {
+    /// Check whether a request may be made with this scope
+    pub fn permits(&self, method: &str, path: &str) -> bool {
+        match self {
+            ApiTokenScope::ReadOnly => matches_route(READ_ROUTES, method, path),
+            ApiTokenScope::Messaging => {
+                matches_route(READ_ROUTES, method, path)
+                    || matches_route(MESSAGING_ROUTES, method, path)
+            }
+            ApiTokenScope::Activity => {
+                matches_route(READ_ROUTES, method, path)
+                    || matches_route(ACTIVITY_ROUTES, method, path)
+            }
+            ApiTokenScope::Admin => {
+                let path = path.strip_prefix("/0.8").unwrap_or(path);
+                !ACCOUNT_PREFIXES.iter().any(|prefix| {
+                    path.strip_prefix(prefix)
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ApiTokenScope;
+
+    #[test]
+    fn read_only_scope() {
+        let scope = ApiTokenScope::ReadOnly;
+        assert!(scope.permits("GET", "/users/@me"));
+        assert!(scope.permits("GET", "/0.8/channels/01H/messages"));
+        assert!(scope.permits("GET", "/servers/01H/members/01J"));
+
+        // Opening a DM creates a channel
+        assert!(!scope.permits("GET", "/users/01H/dm"));
+        assert!(!scope.permits("GET", "/auth/account/tokens"));
+        assert!(!scope.permits("POST", "/channels/01H/messages"));
+        assert!(!scope.permits("GET", "/channels//messages"));
+    }
+
+    #[test]
+    fn messaging_scope() {
+        let scope = ApiTokenScope::Messaging;
+        assert!(scope.permits("GET", "/channels/01H/messages"));
+        assert!(scope.permits("POST", "/channels/01H/messages"));
+        assert!(scope.permits("PATCH", "/channels/01H/messages/01J"));
+        assert!(scope.permits("PUT", "/channels/01H/messages/01J/reactions/01K"));
+
+        assert!(!scope.permits("DELETE", "/channels/01H"));
+        assert!(!scope.permits("POST", "/channels/01H/messages/01J/pin"));
+        assert!(!scope.permits("POST", "/servers/01H/channels/messages"));
+        assert!(!scope.permits("PUT", "/users/@me/activity"));
+    }
+
+    #[test]
+    fn activity_scope() {
+        let scope = ApiTokenScope::Activity;
+        assert!(scope.permits("GET", "/users/@me"));
+        assert!(scope.permits("PUT", "/users/@me/activity"));
+        assert!(scope.permits("DELETE", "/0.8/users/@me/activity"));
+
+        assert!(!scope.permits("PATCH", "/users/@me"));
+        assert!(!scope.permits("POST", "/channels/01H/messages"));
+        assert!(!scope.permits("PUT", "/users/01H/activity"));
+    }
+
+    #[test]
+    fn admin_scope() {
+        let scope = ApiTokenScope::Admin;
+        assert!(scope.permits("GET", "/users/01H/dm"));
+        assert!(scope.permits("DELETE", "/channels/01H"));
+        assert!(scope.permits("GET", "/authors"));
+
+        // Managing the account needs a session
+        assert!(!scope.permits("GET", "/auth"));
+        assert!(!scope.permits("POST", "/auth/session/devices/01H/approve"));
+        assert!(!scope.permits("PUT", "/auth/account/recovery/contacts"));
+        assert!(!scope.permits("POST", "/0.8/auth/account/recovery/requests/01H/approve"));
+    }
+}
@@ -0,0 +1,28 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+
+use crate::ApiToken;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractApiTokens: Sync + Send {
+    /// Insert new API token into database
+    async fn insert_api_token(&self, token: &ApiToken) -> Result<()>;
+
+    /// Fetch an API token by the hash of its secret
+    async fn fetch_api_token_by_hash(&self, token_hash: &str) -> Result<ApiToken>;
+
+    /// Fetch all API tokens belonging to a user
+    async fn fetch_api_tokens(&self, user_id: &str) -> Result<Vec<ApiToken>>;
+
+    /// Update the time at which an API token was last used
+    async fn update_api_token_last_used(&self, id: &str, last_used: Timestamp) -> Result<()>;
+
+    /// Delete an API token belonging to a user
+    async fn delete_api_token(&self, user_id: &str, id: &str) -> Result<()>;
+
+    /// Delete all API tokens belonging to a user
+    async fn delete_api_tokens(&self, user_id: &str) -> Result<()>;
+}
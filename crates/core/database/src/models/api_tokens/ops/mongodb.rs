@@ -0,0 +1,91 @@
+use bson::{to_bson, Document};
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+
+use crate::ApiToken;
+use crate::MongoDb;
+
+use super::AbstractApiTokens;
+
+static COL: &str = "api_tokens";
+
+#[async_trait]
+impl AbstractApiTokens for MongoDb {
+    /// Insert new API token into database
+    async fn insert_api_token(&self, token: &ApiToken) -> Result<()> {
+        query!(self, insert_one, COL, &token).map(|_| ())
+    }
+
+    /// Fetch an API token by the hash of its secret
+    async fn fetch_api_token_by_hash(&self, token_hash: &str) -> Result<ApiToken> {
+        query!(
+            self,
+            find_one,
+            COL,
+            doc! {
+                "token_hash": token_hash
+            }
+        )?
+        .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all API tokens belonging to a user
+    async fn fetch_api_tokens(&self, user_id: &str) -> Result<Vec<ApiToken>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "user": user_id
+            }
+        )
+    }
+
+    /// Update the time at which an API token was last used
+    async fn update_api_token_last_used(&self, id: &str, last_used: Timestamp) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$set": {
+                        "last_used": to_bson(&last_used)
+                            .map_err(|_| create_database_error!("to_bson", "last_used"))?
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Delete an API token belonging to a user
+    async fn delete_api_token(&self, user_id: &str, id: &str) -> Result<()> {
+        let result = self
+            .col::<Document>(COL)
+            .delete_one(doc! {
+                "_id": id,
+                "user": user_id
+            })
+            .await
+            .map_err(|_| create_database_error!("delete_one", COL))?;
+
+        if result.deleted_count == 0 {
+            Err(create_error!(NotFound))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Delete all API tokens belonging to a user
+    async fn delete_api_tokens(&self, user_id: &str) -> Result<()> {
+        self.col::<Document>(COL)
+            .delete_many(doc! {
+                "user": user_id
+            })
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("delete_many", COL))
+    }
+}
@@ -0,0 +1,73 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+
+use crate::ApiToken;
+use crate::ReferenceDb;
+
+use super::AbstractApiTokens;
+
+#[async_trait]
+impl AbstractApiTokens for ReferenceDb {
+    /// Insert new API token into database
+    async fn insert_api_token(&self, token: &ApiToken) -> Result<()> {
+        let mut api_tokens = self.api_tokens.lock().await;
+        if api_tokens.contains_key(&token.id) {
+            Err(create_database_error!("insert", "api_token"))
+        } else {
+            api_tokens.insert(token.id.to_string(), token.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch an API token by the hash of its secret
+    async fn fetch_api_token_by_hash(&self, token_hash: &str) -> Result<ApiToken> {
+        let api_tokens = self.api_tokens.lock().await;
+        api_tokens
+            .values()
+            .find(|api_token| api_token.token_hash == token_hash)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all API tokens belonging to a user
+    async fn fetch_api_tokens(&self, user_id: &str) -> Result<Vec<ApiToken>> {
+        let api_tokens = self.api_tokens.lock().await;
+        Ok(api_tokens
+            .values()
+            .filter(|api_token| api_token.user == user_id)
+            .cloned()
+            .collect())
+    }
+
+    /// Update the time at which an API token was last used
+    async fn update_api_token_last_used(&self, id: &str, last_used: Timestamp) -> Result<()> {
+        let mut api_tokens = self.api_tokens.lock().await;
+        if let Some(api_token) = api_tokens.get_mut(id) {
+            api_token.last_used = Some(last_used);
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Delete an API token belonging to a user
+    async fn delete_api_token(&self, user_id: &str, id: &str) -> Result<()> {
+        let mut api_tokens = self.api_tokens.lock().await;
+        if api_tokens
+            .get(id)
+            .is_some_and(|api_token| api_token.user == user_id)
+        {
+            api_tokens.remove(id);
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Delete all API tokens belonging to a user
+    async fn delete_api_tokens(&self, user_id: &str) -> Result<()> {
+        let mut api_tokens = self.api_tokens.lock().await;
+        api_tokens.retain(|_, api_token| api_token.user != user_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use guilderia_permissions::OverrideField;
+use guilderia_result::Result;
+use ulid::Ulid;
+
+use crate::Database;
+
+auto_derived!(
+    /// Server audit log entry
+    pub struct AuditLogEntry {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the server this entry belongs to
+        pub server: String,
+        /// Id of the user who performed the action
+        pub actor: String,
+        /// Action which was performed
+        pub action: AuditLogAction,
+    }
+
+    /// Action recorded in the audit log
+    #[serde(tag = "type")]
+    pub enum AuditLogAction {
+        /// Permission override changed on a server or channel
+        PermissionOverrideEdit {
+            /// Channel the override belongs to, server-wide if not set
+            #[serde(skip_serializing_if = "Option::is_none")]
+            channel: Option<String>,
+            /// Role the override belongs to, the default permissions if not set
+            #[serde(skip_serializing_if = "Option::is_none")]
+            role: Option<String>,
+            /// Override before the change
+            before: OverrideField,
+            /// Override after the change
+            after: OverrideField,
+        },
+        /// Channel purged on a schedule
+        ScheduledPurge {
+            /// Channel which was purged
+            channel: String,
+            /// Number of messages deleted
+            deleted: i64,
+        },
+        /// Users banned in bulk
+        BulkBan {
+            /// Job which banned the users
+            job: String,
+            /// Reason given for the bans
+            #[serde(skip_serializing_if = "Option::is_none")]
+            reason: Option<String>,
+            /// Number of users banned
+            banned: i64,
+            /// Number of users who could not be banned
+            failed: i64,
+        },
+        /// Inactive members kicked
+        MemberPrune {
+            /// Job which kicked the members
+            job: String,
+            /// Number of days members had been inactive for
+            days: u32,
+            /// Number of members kicked
+            kicked: i64,
+            /// Number of members who could not be kicked
+            failed: i64,
+        },
+    }
+);
+
+impl AuditLogEntry {
+    /// Record a new entry in a server's audit log
+    pub async fn create(
+        db: &Database,
+        server: &str,
+        actor: &str,
+        action: AuditLogAction,
+    ) -> Result<AuditLogEntry> {
+        let entry = AuditLogEntry {
+            id: Ulid::new().to_string(),
+            server: server.to_string(),
+            actor: actor.to_string(),
+            action,
+        };
+
+        db.insert_audit_log_entry(&entry).await?;
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use guilderia_permissions::OverrideField;
+
+    use crate::{AuditLogAction, AuditLogEntry};
+
+    #[async_std::test]
+    async fn fetches_newest_first() {
+        database_test!(|db| async move {
+            let mut ids = vec![];
+            for a in 0..3 {
+                let entry = AuditLogEntry::create(
+                    &db,
+                    "server",
+                    "user",
+                    AuditLogAction::PermissionOverrideEdit {
+                        channel: None,
+                        role: None,
+                        before: OverrideField::default(),
+                        after: OverrideField { a, d: 0 },
+                    },
+                )
+                .await
+                .unwrap();
+
+                ids.push(entry.id);
+
+                // Ids are only ordered between milliseconds
+                async_std::task::sleep(Duration::from_millis(2)).await;
+            }
+
+            let entries = db.fetch_audit_log("server", None, 2).await.unwrap();
+            assert_eq!(entries.len(), 2);
+            assert_eq!(entries[0].id, ids[2]);
+            assert_eq!(entries[1].id, ids[1]);
+
+            let entries = db
+                .fetch_audit_log("server", Some(&ids[1]), 50)
+                .await
+                .unwrap();
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].id, ids[0]);
+
+            assert!(db
+                .fetch_audit_log("other", None, 50)
+                .await
+                .unwrap()
+                .is_empty());
+        });
+    }
+}
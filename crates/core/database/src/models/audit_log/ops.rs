@@ -0,0 +1,20 @@
+use guilderia_result::Result;
+
+use crate::AuditLogEntry;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractAuditLog: Sync + Send {
+    /// Insert a new audit log entry
+    async fn insert_audit_log_entry(&self, entry: &AuditLogEntry) -> Result<()>;
+
+    /// Fetch a server's audit log, newest first
+    async fn fetch_audit_log(
+        &self,
+        server: &str,
+        before: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<AuditLogEntry>>;
+}
@@ -0,0 +1,51 @@
+use guilderia_result::Result;
+use mongodb::options::FindOptions;
+
+use crate::AuditLogEntry;
+use crate::MongoDb;
+
+use super::AbstractAuditLog;
+
+static COL: &str = "audit_log";
+
+#[async_trait]
+impl AbstractAuditLog for MongoDb {
+    /// Insert a new audit log entry
+    async fn insert_audit_log_entry(&self, entry: &AuditLogEntry) -> Result<()> {
+        query!(self, insert_one, COL, &entry).map(|_| ())
+    }
+
+    /// Fetch a server's audit log, newest first
+    async fn fetch_audit_log(
+        &self,
+        server: &str,
+        before: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<AuditLogEntry>> {
+        let mut filter = doc! {
+            "server": server
+        };
+
+        if let Some(before) = before {
+            filter.insert(
+                "_id",
+                doc! {
+                    "$lt": before
+                },
+            );
+        }
+
+        query!(
+            self,
+            find_with_options,
+            COL,
+            filter,
+            FindOptions::builder()
+                .sort(doc! {
+                    "_id": -1_i32
+                })
+                .limit(limit)
+                .build()
+        )
+    }
+}
@@ -0,0 +1,40 @@
+use guilderia_result::Result;
+
+use crate::AuditLogEntry;
+use crate::ReferenceDb;
+
+use super::AbstractAuditLog;
+
+#[async_trait]
+impl AbstractAuditLog for ReferenceDb {
+    /// Insert a new audit log entry
+    async fn insert_audit_log_entry(&self, entry: &AuditLogEntry) -> Result<()> {
+        let mut audit_log = self.audit_log.lock().await;
+        if audit_log.contains_key(&entry.id) {
+            Err(create_database_error!("insert", "audit_log"))
+        } else {
+            audit_log.insert(entry.id.to_string(), entry.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a server's audit log, newest first
+    async fn fetch_audit_log(
+        &self,
+        server: &str,
+        before: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<AuditLogEntry>> {
+        let audit_log = self.audit_log.lock().await;
+        let mut entries: Vec<AuditLogEntry> = audit_log
+            .values()
+            .filter(|entry| entry.server == server)
+            .filter(|entry| before.map_or(true, |before| entry.id.as_str() < before))
+            .cloned()
+            .collect();
+
+        entries.sort_by(|a, b| b.id.cmp(&a.id));
+        entries.truncate(limit as usize);
+        Ok(entries)
+    }
+}
@@ -0,0 +1,436 @@
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::Result;
+use iso8601_timestamp::Timestamp;
+use ulid::Ulid;
+
+use crate::{
+    util::permissions::DatabasePermissionQuery, Database, MemberCompositeKey, RemovalIntention,
+    Server, ServerBan, SystemMessage,
+};
+
+auto_derived!(
+    /// Subscription of a server to the bans of another server
+    ///
+    /// Curated ban lists are published as servers which share their bans.
+    pub struct BanSubscription {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the subscribing server
+        pub server: String,
+        /// Id of the server whose bans are followed
+        pub source: String,
+        /// Id of the user who subscribed
+        pub creator: String,
+        /// What to do with bans from the source
+        pub mode: BanSyncMode,
+        /// Bans created on the source up to this time have been synced
+        pub synced_at: Timestamp,
+    }
+
+    /// What to do with bans from a subscribed source
+    pub enum BanSyncMode {
+        /// Ban the user on the subscriber as well
+        Apply,
+        /// Alert the subscriber's moderators if the user is a member
+        Flag,
+    }
+);
+
+/// Send an alert to the moderators of a server, if it has a channel for them
+async fn alert(db: &Database, server: &Server, content: String) {
+    if let Some(channel) = server
+        .system_messages
+        .as_ref()
+        .and_then(|x| x.moderation_alerts.as_ref())
+    {
+        SystemMessage::Text { content }
+            .into_message(channel.to_string())
+            .send_without_notifications(db, None, None, false, false, false)
+            .await
+            .ok();
+    }
+}
+
+impl BanSubscription {
+    /// Subscribe a server to the bans of another server
+    ///
+    /// Only bans created after subscribing are synced.
+    pub async fn create(
+        db: &Database,
+        server: &Server,
+        source: &Server,
+        creator: &str,
+        mode: BanSyncMode,
+    ) -> Result<BanSubscription> {
+        if server.id == source.id || !source.share_bans {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        if db
+            .fetch_ban_subscriptions(&server.id)
+            .await?
+            .iter()
+            .any(|subscription| subscription.source == source.id)
+        {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        let subscription = BanSubscription {
+            id: Ulid::new().to_string(),
+            server: server.id.to_string(),
+            source: source.id.to_string(),
+            creator: creator.to_string(),
+            mode,
+            synced_at: Timestamp::now_utc(),
+        };
+
+        db.insert_ban_subscription(&subscription).await?;
+        Ok(subscription)
+    }
+
+    /// Check the creator may still ban members of the subscribing server
+    ///
+    /// Returns the creator's rank, bans are only applied to members below it.
+    async fn creator_rank(&self, db: &Database, server: &Server) -> Result<i64> {
+        if self.creator == server.owner {
+            return Ok(i64::MIN);
+        }
+
+        let creator = db.fetch_user(&self.creator).await?;
+        let mut query = DatabasePermissionQuery::new(db, &creator).server(server);
+        calculate_server_permissions(&mut query)
+            .await
+            .throw_if_lacking_channel_permission(ChannelPermission::BanMembers)?;
+
+        Ok(query.get_member_rank().unwrap_or(i64::MIN))
+    }
+
+    /// Apply or flag bans created on the source since the last sync
+    ///
+    /// Only bans issued on the source itself are followed, so bans do not
+    /// bounce between servers which subscribe to each other.
+    /// Fails with `MissingPermission` if the creator may no longer ban members.
+    /// Returns the number of bans processed.
+    pub async fn sync(&mut self, db: &Database) -> Result<usize> {
+        let server = db.fetch_server(&self.server).await?;
+        let rank = self.creator_rank(db, &server).await?;
+
+        let source = db.fetch_server(&self.source).await?;
+        if !source.share_bans {
+            return Ok(0);
+        }
+
+        let mut bans: Vec<ServerBan> = db
+            .fetch_bans(&source.id)
+            .await?
+            .into_iter()
+            .filter(|ban| {
+                ban.subscription.is_none()
+                    && ban
+                        .created_at
+                        .is_some_and(|created_at| *created_at > *self.synced_at)
+            })
+            .collect();
+
+        if bans.is_empty() {
+            return Ok(0);
+        }
+
+        bans.sort_by_key(|ban| ban.created_at.map(|created_at| *created_at));
+
+        let mut applied = 0;
+        for ban in &bans {
+            match self.mode {
+                BanSyncMode::Apply => match self.apply(db, &server, rank, ban).await {
+                    Ok(true) => applied += 1,
+                    Ok(false) => {}
+                    Err(err) => warn!(
+                        "Failed to apply ban of {} from {} to {}: {err:?}",
+                        ban.id.user, source.id, server.id
+                    ),
+                },
+                BanSyncMode::Flag => {
+                    if db.fetch_member(&server.id, &ban.id.user).await.is_ok() {
+                        alert(
+                            db,
+                            &server,
+                            format!(
+                                "<@{}> was banned in {}: {}",
+                                ban.id.user,
+                                source.name,
+                                ban.reason.as_deref().unwrap_or("no reason given")
+                            ),
+                        )
+                        .await;
+                    }
+                }
+            }
+        }
+
+        if applied > 0 {
+            alert(
+                db,
+                &server,
+                format!("Applied {applied} ban(s) from {}.", source.name),
+            )
+            .await;
+        }
+
+        if let Some(synced_at) = bans.last().and_then(|ban| ban.created_at) {
+            db.update_ban_subscription_synced_at(&self.id, synced_at)
+                .await?;
+            self.synced_at = synced_at;
+        }
+
+        Ok(bans.len())
+    }
+
+    /// Ban a user from the source on the subscribing server
+    ///
+    /// Returns false if the user was already banned, owns the server or
+    /// ranks at or above the creator of the subscription.
+    async fn apply(
+        &self,
+        db: &Database,
+        server: &Server,
+        rank: i64,
+        ban: &ServerBan,
+    ) -> Result<bool> {
+        if ban.id.user == server.owner || db.fetch_ban(&server.id, &ban.id.user).await.is_ok() {
+            return Ok(false);
+        }
+
+        if let Ok(member) = db.fetch_member(&server.id, &ban.id.user).await {
+            if member.get_ranking(server) <= rank {
+                return Ok(false);
+            }
+
+            member
+                .remove(db, server, RemovalIntention::Ban, false)
+                .await?;
+        }
+
+        db.insert_ban(&ServerBan {
+            id: MemberCompositeKey {
+                server: server.id.to_string(),
+                user: ban.id.user.to_string(),
+            },
+            reason: ban.reason.clone(),
+            created_at: Some(Timestamp::now_utc()),
+            subscription: Some(self.id.to_string()),
+        })
+        .await?;
+
+        Ok(true)
+    }
+
+    /// Unsubscribe, optionally lifting every ban this subscription applied
+    ///
+    /// Returns the number of bans lifted.
+    pub async fn delete(self, db: &Database, rollback: bool) -> Result<usize> {
+        let mut lifted = 0;
+        if rollback {
+            for ban in db.fetch_bans(&self.server).await? {
+                if ban.subscription.as_ref() == Some(&self.id) {
+                    db.delete_ban(&ban.id).await?;
+                    lifted += 1;
+                }
+            }
+        }
+
+        db.delete_ban_subscription(&self.id).await?;
+        Ok(lifted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use guilderia_models::v0;
+    use guilderia_permissions::{ChannelPermission, OverrideField};
+    use guilderia_result::ErrorType;
+
+    use crate::{
+        BanSubscription, BanSyncMode, Member, PartialMember, PartialServer, Role, Server,
+        ServerBan, User,
+    };
+
+    #[async_std::test]
+    async fn bans_sync_and_roll_back() {
+        database_test!(|db| async move {
+            let owner = User::create(&db, "owner".to_string(), None, None)
+                .await
+                .unwrap();
+            let (source, _) = Server::create(
+                &db,
+                v0::DataCreateServer {
+                    name: "Source".to_string(),
+                    ..Default::default()
+                },
+                &owner,
+                false,
+            )
+            .await
+            .unwrap();
+            let (server, _) = Server::create(
+                &db,
+                v0::DataCreateServer {
+                    name: "Subscriber".to_string(),
+                    ..Default::default()
+                },
+                &owner,
+                false,
+            )
+            .await
+            .unwrap();
+
+            // Sources must opt in to sharing their bans
+            assert!(
+                BanSubscription::create(&db, &server, &source, &owner.id, BanSyncMode::Apply)
+                    .await
+                    .is_err()
+            );
+
+            db.update_server(
+                &source.id,
+                &PartialServer {
+                    share_bans: Some(true),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+            let source = db.fetch_server(&source.id).await.unwrap();
+
+            let mut subscription =
+                BanSubscription::create(&db, &server, &source, &owner.id, BanSyncMode::Apply)
+                    .await
+                    .unwrap();
+
+            ServerBan::create(&db, &source, "spammer", Some("Spam".to_string()))
+                .await
+                .unwrap();
+
+            assert_eq!(subscription.sync(&db).await.unwrap(), 1);
+            assert_eq!(subscription.sync(&db).await.unwrap(), 0);
+
+            let ban = db.fetch_ban(&server.id, "spammer").await.unwrap();
+            assert_eq!(ban.reason.as_deref(), Some("Spam"));
+            assert_eq!(ban.subscription.as_ref(), Some(&subscription.id));
+
+            assert_eq!(subscription.delete(&db, true).await.unwrap(), 1);
+            assert!(db.fetch_ban(&server.id, "spammer").await.is_err());
+        });
+    }
+
+    #[async_std::test]
+    async fn bans_respect_creator_rank() {
+        database_test!(|db| async move {
+            let owner = User::create(&db, "owner".to_string(), None, None)
+                .await
+                .unwrap();
+            let moderator = User::create(&db, "moderator".to_string(), None, None)
+                .await
+                .unwrap();
+            let admin = User::create(&db, "admin".to_string(), None, None)
+                .await
+                .unwrap();
+            let (source, _) = Server::create(
+                &db,
+                v0::DataCreateServer {
+                    name: "Source".to_string(),
+                    ..Default::default()
+                },
+                &owner,
+                false,
+            )
+            .await
+            .unwrap();
+            let (server, _) = Server::create(
+                &db,
+                v0::DataCreateServer {
+                    name: "Subscriber".to_string(),
+                    ..Default::default()
+                },
+                &owner,
+                false,
+            )
+            .await
+            .unwrap();
+
+            db.update_server(
+                &source.id,
+                &PartialServer {
+                    share_bans: Some(true),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+            let source = db.fetch_server(&source.id).await.unwrap();
+
+            let mut ranked = vec![];
+            for (user, rank) in [(&moderator, 5), (&admin, 1)] {
+                let role = Role {
+                    name: user.username.clone(),
+                    permissions: OverrideField {
+                        a: ChannelPermission::BanMembers as i64,
+                        d: 0,
+                    },
+                    colour: None,
+                    hoist: false,
+                    group: None,
+                    rank,
+                }
+                .create(&db, &server.id)
+                .await
+                .unwrap();
+
+                let (member, _) = Member::create(&db, &server, user, None).await.unwrap();
+                db.update_member(
+                    &member.id,
+                    &PartialMember {
+                        roles: Some(vec![role]),
+                        ..Default::default()
+                    },
+                    vec![],
+                )
+                .await
+                .unwrap();
+                ranked.push(member.id);
+            }
+
+            let mut subscription =
+                BanSubscription::create(&db, &server, &source, &moderator.id, BanSyncMode::Apply)
+                    .await
+                    .unwrap();
+
+            // Members ranked above the creator are left alone
+            ServerBan::create(&db, &source, &admin.id, None)
+                .await
+                .unwrap();
+            assert_eq!(subscription.sync(&db).await.unwrap(), 1);
+            assert!(db.fetch_ban(&server.id, &admin.id).await.is_err());
+
+            // Creators who lose permission stop syncing
+            db.update_member(
+                &ranked[0],
+                &PartialMember {
+                    roles: Some(vec![]),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+
+            let err = subscription.sync(&db).await.unwrap_err();
+            assert!(matches!(
+                err.error_type,
+                ErrorType::MissingPermission { .. }
+            ));
+        });
+    }
+}
@@ -0,0 +1,32 @@
+use guilderia_result::Result;
+use iso8601_timestamp::Timestamp;
+
+use crate::BanSubscription;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractBanSubscriptions: Sync + Send {
+    /// Insert a new ban subscription
+    async fn insert_ban_subscription(&self, subscription: &BanSubscription) -> Result<()>;
+
+    /// Fetch a ban subscription by its id
+    async fn fetch_ban_subscription(&self, id: &str) -> Result<BanSubscription>;
+
+    /// Fetch all ban subscriptions of a server
+    async fn fetch_ban_subscriptions(&self, server: &str) -> Result<Vec<BanSubscription>>;
+
+    /// Fetch all ban subscriptions to a source server
+    async fn fetch_ban_subscribers(&self, source: &str) -> Result<Vec<BanSubscription>>;
+
+    /// Fetch every ban subscription
+    async fn fetch_all_ban_subscriptions(&self) -> Result<Vec<BanSubscription>>;
+
+    /// Update the time up to which a ban subscription has been synced
+    async fn update_ban_subscription_synced_at(&self, id: &str, synced_at: Timestamp)
+        -> Result<()>;
+
+    /// Delete a ban subscription by its id
+    async fn delete_ban_subscription(&self, id: &str) -> Result<()>;
+}
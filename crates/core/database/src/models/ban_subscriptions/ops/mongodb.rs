@@ -0,0 +1,80 @@
+use bson::{to_bson, Document};
+use guilderia_result::Result;
+use iso8601_timestamp::Timestamp;
+
+use crate::BanSubscription;
+use crate::MongoDb;
+
+use super::AbstractBanSubscriptions;
+
+static COL: &str = "ban_subscriptions";
+
+#[async_trait]
+impl AbstractBanSubscriptions for MongoDb {
+    /// Insert a new ban subscription
+    async fn insert_ban_subscription(&self, subscription: &BanSubscription) -> Result<()> {
+        query!(self, insert_one, COL, &subscription).map(|_| ())
+    }
+
+    /// Fetch a ban subscription by its id
+    async fn fetch_ban_subscription(&self, id: &str) -> Result<BanSubscription> {
+        query!(self, find_one_by_id, COL, id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all ban subscriptions of a server
+    async fn fetch_ban_subscriptions(&self, server: &str) -> Result<Vec<BanSubscription>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "server": server
+            }
+        )
+    }
+
+    /// Fetch all ban subscriptions to a source server
+    async fn fetch_ban_subscribers(&self, source: &str) -> Result<Vec<BanSubscription>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "source": source
+            }
+        )
+    }
+
+    /// Fetch every ban subscription
+    async fn fetch_all_ban_subscriptions(&self) -> Result<Vec<BanSubscription>> {
+        query!(self, find, COL, doc! {})
+    }
+
+    /// Update the time up to which a ban subscription has been synced
+    async fn update_ban_subscription_synced_at(
+        &self,
+        id: &str,
+        synced_at: Timestamp,
+    ) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$set": {
+                        "synced_at": to_bson(&synced_at)
+                            .map_err(|_| create_database_error!("to_bson", "synced_at"))?
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Delete a ban subscription by its id
+    async fn delete_ban_subscription(&self, id: &str) -> Result<()> {
+        query!(self, delete_one_by_id, COL, id).map(|_| ())
+    }
+}
@@ -0,0 +1,81 @@
+use guilderia_result::Result;
+use iso8601_timestamp::Timestamp;
+
+use crate::BanSubscription;
+use crate::ReferenceDb;
+
+use super::AbstractBanSubscriptions;
+
+#[async_trait]
+impl AbstractBanSubscriptions for ReferenceDb {
+    /// Insert a new ban subscription
+    async fn insert_ban_subscription(&self, subscription: &BanSubscription) -> Result<()> {
+        let mut ban_subscriptions = self.ban_subscriptions.lock().await;
+        if ban_subscriptions.contains_key(&subscription.id) {
+            Err(create_database_error!("insert", "ban_subscriptions"))
+        } else {
+            ban_subscriptions.insert(subscription.id.to_string(), subscription.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a ban subscription by its id
+    async fn fetch_ban_subscription(&self, id: &str) -> Result<BanSubscription> {
+        let ban_subscriptions = self.ban_subscriptions.lock().await;
+        ban_subscriptions
+            .get(id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all ban subscriptions of a server
+    async fn fetch_ban_subscriptions(&self, server: &str) -> Result<Vec<BanSubscription>> {
+        let ban_subscriptions = self.ban_subscriptions.lock().await;
+        Ok(ban_subscriptions
+            .values()
+            .filter(|subscription| subscription.server == server)
+            .cloned()
+            .collect())
+    }
+
+    /// Fetch all ban subscriptions to a source server
+    async fn fetch_ban_subscribers(&self, source: &str) -> Result<Vec<BanSubscription>> {
+        let ban_subscriptions = self.ban_subscriptions.lock().await;
+        Ok(ban_subscriptions
+            .values()
+            .filter(|subscription| subscription.source == source)
+            .cloned()
+            .collect())
+    }
+
+    /// Fetch every ban subscription
+    async fn fetch_all_ban_subscriptions(&self) -> Result<Vec<BanSubscription>> {
+        let ban_subscriptions = self.ban_subscriptions.lock().await;
+        Ok(ban_subscriptions.values().cloned().collect())
+    }
+
+    /// Update the time up to which a ban subscription has been synced
+    async fn update_ban_subscription_synced_at(
+        &self,
+        id: &str,
+        synced_at: Timestamp,
+    ) -> Result<()> {
+        let mut ban_subscriptions = self.ban_subscriptions.lock().await;
+        if let Some(subscription) = ban_subscriptions.get_mut(id) {
+            subscription.synced_at = synced_at;
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Delete a ban subscription by its id
+    async fn delete_ban_subscription(&self, id: &str) -> Result<()> {
+        let mut ban_subscriptions = self.ban_subscriptions.lock().await;
+        if ban_subscriptions.remove(id).is_some() {
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+}
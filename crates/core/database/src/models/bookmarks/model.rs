@@ -0,0 +1,116 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+use ulid::Ulid;
+
+use crate::{events::client::EventV1, Database};
+
+/// Maximum number of bookmarks a user may hold
+pub const MAX_BOOKMARKS: usize = 100;
+
+auto_derived!(
+    /// Named marker a user left on a message to continue reading from
+    pub struct Bookmark {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the user who owns this bookmark
+        pub user: String,
+        /// Id of the channel the message was sent in
+        pub channel: String,
+        /// Id of the message this bookmark points at
+        pub message: String,
+        /// Name given to this bookmark, unique within the channel
+        pub name: String,
+        /// Time at which this bookmark was last moved
+        pub updated_at: Timestamp,
+    }
+);
+
+impl Bookmark {
+    /// Point a user's bookmark at a message, creating it if there is no
+    /// bookmark by this name in the channel yet
+    pub async fn set(
+        db: &Database,
+        user: &str,
+        channel: &str,
+        message: &str,
+        name: String,
+    ) -> Result<Bookmark> {
+        let bookmarks = db.fetch_bookmarks(user).await?;
+        let id = match bookmarks
+            .iter()
+            .find(|bookmark| bookmark.channel == channel && bookmark.name == name)
+        {
+            Some(existing) => existing.id.clone(),
+            None if bookmarks.len() >= MAX_BOOKMARKS => {
+                return Err(create_error!(TooManyBookmarks { max: MAX_BOOKMARKS }))
+            }
+            None => Ulid::new().to_string(),
+        };
+
+        let bookmark = Bookmark {
+            id,
+            user: user.to_string(),
+            channel: channel.to_string(),
+            message: message.to_string(),
+            name,
+            updated_at: Timestamp::now_utc(),
+        };
+
+        db.save_bookmark(&bookmark).await?;
+
+        EventV1::BookmarkUpdate(bookmark.clone().into())
+            .private(user.to_string())
+            .await;
+
+        Ok(bookmark)
+    }
+
+    /// Delete this bookmark
+    pub async fn delete(self, db: &Database) -> Result<()> {
+        db.delete_bookmark(&self.id).await?;
+
+        EventV1::BookmarkDelete {
+            id: self.id,
+            channel: self.channel,
+        }
+        .private(self.user)
+        .await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Bookmark;
+
+    #[async_std::test]
+    async fn set_and_delete() {
+        database_test!(|db| async move {
+            let first = Bookmark::set(&db, "user", "channel", "message", "Later".to_string())
+                .await
+                .unwrap();
+
+            // Setting a bookmark by the same name moves it
+            let moved = Bookmark::set(&db, "user", "channel", "newer", "Later".to_string())
+                .await
+                .unwrap();
+            assert_eq!(moved.id, first.id);
+
+            Bookmark::set(&db, "user", "other", "message", "Later".to_string())
+                .await
+                .unwrap();
+
+            let bookmarks = db.fetch_bookmarks("user").await.unwrap();
+            assert_eq!(bookmarks.len(), 2);
+            assert!(bookmarks
+                .iter()
+                .any(|bookmark| bookmark.id == first.id && bookmark.message == "newer"));
+
+            moved.delete(&db).await.unwrap();
+            assert_eq!(db.fetch_bookmarks("user").await.unwrap().len(), 1);
+            assert!(db.fetch_bookmarks("other").await.unwrap().is_empty());
+        });
+    }
+}
@@ -0,0 +1,21 @@
+use guilderia_result::Result;
+
+use crate::Bookmark;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractBookmarks: Sync + Send {
+    /// Insert or replace a bookmark
+    async fn save_bookmark(&self, bookmark: &Bookmark) -> Result<()>;
+
+    /// Fetch a bookmark by its id
+    async fn fetch_bookmark(&self, id: &str) -> Result<Bookmark>;
+
+    /// Fetch all of a user's bookmarks
+    async fn fetch_bookmarks(&self, user: &str) -> Result<Vec<Bookmark>>;
+
+    /// Delete a bookmark
+    async fn delete_bookmark(&self, id: &str) -> Result<()>;
+}
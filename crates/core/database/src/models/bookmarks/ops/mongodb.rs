@@ -0,0 +1,49 @@
+use guilderia_result::Result;
+use mongodb::options::ReplaceOptions;
+
+use crate::Bookmark;
+use crate::MongoDb;
+
+use super::AbstractBookmarks;
+
+static COL: &str = "bookmarks";
+
+#[async_trait]
+impl AbstractBookmarks for MongoDb {
+    /// Insert or replace a bookmark
+    async fn save_bookmark(&self, bookmark: &Bookmark) -> Result<()> {
+        self.col::<Bookmark>(COL)
+            .replace_one(
+                doc! {
+                    "_id": &bookmark.id
+                },
+                bookmark,
+            )
+            .with_options(ReplaceOptions::builder().upsert(true).build())
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("replace_one", COL))
+    }
+
+    /// Fetch a bookmark by its id
+    async fn fetch_bookmark(&self, id: &str) -> Result<Bookmark> {
+        query!(self, find_one_by_id, COL, id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all of a user's bookmarks
+    async fn fetch_bookmarks(&self, user: &str) -> Result<Vec<Bookmark>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "user": user
+            }
+        )
+    }
+
+    /// Delete a bookmark
+    async fn delete_bookmark(&self, id: &str) -> Result<()> {
+        query!(self, delete_one_by_id, COL, id).map(|_| ())
+    }
+}
@@ -0,0 +1,45 @@
+use guilderia_result::Result;
+
+use crate::Bookmark;
+use crate::ReferenceDb;
+
+use super::AbstractBookmarks;
+
+#[async_trait]
+impl AbstractBookmarks for ReferenceDb {
+    /// Insert or replace a bookmark
+    async fn save_bookmark(&self, bookmark: &Bookmark) -> Result<()> {
+        let mut bookmarks = self.bookmarks.lock().await;
+        bookmarks.insert(bookmark.id.to_string(), bookmark.clone());
+        Ok(())
+    }
+
+    /// Fetch a bookmark by its id
+    async fn fetch_bookmark(&self, id: &str) -> Result<Bookmark> {
+        let bookmarks = self.bookmarks.lock().await;
+        bookmarks
+            .get(id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all of a user's bookmarks
+    async fn fetch_bookmarks(&self, user: &str) -> Result<Vec<Bookmark>> {
+        let bookmarks = self.bookmarks.lock().await;
+        Ok(bookmarks
+            .values()
+            .filter(|bookmark| bookmark.user == user)
+            .cloned()
+            .collect())
+    }
+
+    /// Delete a bookmark
+    async fn delete_bookmark(&self, id: &str) -> Result<()> {
+        let mut bookmarks = self.bookmarks.lock().await;
+        if bookmarks.remove(id).is_some() {
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+}
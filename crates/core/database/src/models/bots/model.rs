@@ -1,7 +1,11 @@
-use guilderia_result::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ed25519_dalek::{Signature, VerifyingKey};
+use guilderia_result::{create_error, Result};
 use ulid::Ulid;
 
-use crate::{events::client::EventV1, BotInformation, Database, PartialUser, User};
+use crate::{
+    events::client::EventV1, BotInformation, Database, MessageProvenance, PartialUser, User,
+};
 
 auto_derived_partial!(
     /// Bot
@@ -25,7 +29,9 @@ auto_derived_partial!(
         /// Whether this bot should be publicly discoverable
         #[serde(skip_serializing_if = "crate::if_false", default)]
         pub discoverable: bool,
-        /// Reserved; URL for handling interactions
+        /// URL interactions are delivered to over HTTP
+        ///
+        /// Bots without one receive interactions from the events server.
         #[serde(skip_serializing_if = "String::is_empty", default)]
         pub interactions_url: String,
         /// URL for terms of service
@@ -38,15 +44,71 @@ auto_derived_partial!(
         /// Enum of bot flags
         #[serde(skip_serializing_if = "Option::is_none")]
         pub flags: Option<i32>,
+
+        /// Bitfield of event intents declared by this bot
+        ///
+        /// Bots which have not declared any intents receive every event.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub intents: Option<i32>,
+        /// Bitfield of intents this bot has been barred from by the instance operator
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub restricted_intents: Option<i32>,
+
+        /// Permissions this bot requests when it is added to a server
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub permissions: Option<i64>,
+
+        /// Whether users may install this bot to use its commands in their DMs and groups
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub user_installable: bool,
+        /// Commands provided by this bot
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pub commands: Vec<BotCommand>,
+        /// Keys used to sign messages bridged from external networks
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pub bridge_keys: Vec<BridgeKey>,
     },
     "PartialBot"
 );
 
 auto_derived!(
+    /// Key a bridge signs messages with
+    pub struct BridgeKey {
+        /// Key Id
+        pub id: String,
+        /// URI prefix of the external network this key may sign messages for
+        pub origin: String,
+        /// Base64 encoded Ed25519 public key
+        pub public_key: String,
+    }
+
+    /// Command provided by a bot
+    pub struct BotCommand {
+        /// Name of the command
+        pub name: String,
+        /// Description of the command
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub description: Option<String>,
+        /// Contexts the command is visible in
+        ///
+        /// Commands without any contexts are visible everywhere.
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pub contexts: Vec<CommandContext>,
+    }
+
+    /// Context a command is invoked in
+    pub enum CommandContext {
+        /// Bot is present in the channel
+        Channel,
+        /// Bot is installed by the invoking user
+        User,
+    }
+
     /// Optional fields on bot object
     pub enum FieldsBot {
         Token,
         InteractionsURL,
+        Intents,
     }
 );
 
@@ -64,6 +126,12 @@ impl Default for Bot {
             terms_of_service_url: Default::default(),
             privacy_policy_url: Default::default(),
             flags: Default::default(),
+            intents: Default::default(),
+            restricted_intents: Default::default(),
+            permissions: Default::default(),
+            user_installable: Default::default(),
+            commands: Default::default(),
+            bridge_keys: Default::default(),
         }
     }
 }
@@ -125,7 +193,52 @@ impl Bot {
             FieldsBot::InteractionsURL => {
                 self.interactions_url = String::new();
             }
+            FieldsBot::Intents => self.intents = None,
+        }
+    }
+
+    /// Intents this bot may receive events for
+    pub fn effective_intents(&self) -> u32 {
+        let declared = self.intents.map(|v| v as u32).unwrap_or(u32::MAX);
+        declared & !(self.restricted_intents.unwrap_or_default() as u32)
+    }
+
+    /// Check whether a command may be invoked in a given context
+    ///
+    /// Bots which have not declared any commands accept any command
+    /// in channels they are present in.
+    pub fn has_command(&self, name: &str, context: &CommandContext) -> bool {
+        if self.commands.is_empty() {
+            return context == &CommandContext::Channel;
         }
+
+        self.commands.iter().any(|command| {
+            command.name == name
+                && (command.contexts.is_empty() || command.contexts.contains(context))
+        })
+    }
+
+    /// Verify the provenance attached to a message sent by this bot
+    pub fn verify_provenance(&self, provenance: &MessageProvenance, content: &str) -> Result<()> {
+        let key = self
+            .bridge_keys
+            .iter()
+            .find(|key| key.id == provenance.key)
+            .filter(|key| provenance.origin.starts_with(&key.origin))
+            .and_then(BridgeKey::verifying_key)
+            .ok_or_else(|| create_error!(InvalidProvenance))?;
+
+        let signature: [u8; 64] = STANDARD
+            .decode(&provenance.signature)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| create_error!(InvalidProvenance))?;
+
+        key.verify_strict(
+            format!("{}\n{content}", provenance.origin).as_bytes(),
+            &Signature::from_bytes(&signature),
+        )
+        .map_err(|_| create_error!(InvalidProvenance))
     }
 
     /// Update this bot
@@ -160,9 +273,53 @@ impl Bot {
     }
 }
 
+impl BridgeKey {
+    /// Decode the public key
+    pub fn verifying_key(&self) -> Option<VerifyingKey> {
+        let bytes: [u8; 32] = STANDARD.decode(&self.public_key).ok()?.try_into().ok()?;
+        VerifyingKey::from_bytes(&bytes).ok()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{Bot, FieldsBot, PartialBot, User};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use crate::{Bot, BridgeKey, FieldsBot, MessageProvenance, PartialBot, User};
+
+    #[test]
+    fn verify_provenance() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let bot = Bot {
+            bridge_keys: vec![BridgeKey {
+                id: "matrix".to_string(),
+                origin: "https://matrix.example.com/".to_string(),
+                public_key: STANDARD.encode(signing_key.verifying_key().as_bytes()),
+            }],
+            ..Default::default()
+        };
+
+        let origin = "https://matrix.example.com/room/event".to_string();
+        let signature = signing_key.sign(format!("{origin}\nhello").as_bytes());
+        let provenance = MessageProvenance {
+            origin,
+            key: "matrix".to_string(),
+            signature: STANDARD.encode(signature.to_bytes()),
+        };
+
+        assert!(bot.verify_provenance(&provenance, "hello").is_ok());
+        assert!(bot.verify_provenance(&provenance, "goodbye").is_err());
+        assert!(bot
+            .verify_provenance(
+                &MessageProvenance {
+                    origin: "https://elsewhere.example.com/".to_string(),
+                    ..provenance.clone()
+                },
+                "hello"
+            )
+            .is_err());
+    }
 
     #[async_std::test]
     async fn crud() {
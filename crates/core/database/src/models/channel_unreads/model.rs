@@ -1,3 +1,9 @@
+use guilderia_result::Result;
+use iso8601_timestamp::{Duration, Timestamp};
+use ulid::Ulid;
+
+use crate::Database;
+
 auto_derived!(
     /// Channel Unread
     pub struct ChannelUnread {
@@ -11,6 +17,14 @@ auto_derived!(
         /// Array of message ids that mention the user
         #[serde(skip_serializing_if = "Option::is_none")]
         pub mentions: Option<Vec<String>>,
+        /// Number of messages sent since the last read message
+        ///
+        /// Maintained as messages are sent, absent if it needs to be recounted.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub unread_count: Option<u64>,
+        /// Time at which the user last acknowledged this channel
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub acknowledged_at: Option<Timestamp>,
     }
 
     /// Composite primary key consisting of channel and user id
@@ -22,3 +36,71 @@ auto_derived!(
         pub user: String,
     }
 );
+
+/// Acknowledgement of a message in a channel by a user
+#[derive(Clone, Debug)]
+pub struct ChannelAck {
+    /// Channel Id
+    pub channel: String,
+    /// User Id
+    pub user: String,
+    /// Id of the message acknowledged
+    pub message: String,
+    /// Number of messages sent after the acknowledged message
+    pub unread_count: u64,
+}
+
+impl ChannelUnread {
+    /// Recount messages sent since the last read message
+    pub async fn reconcile_count(&self, db: &Database) -> Result<()> {
+        let count = db
+            .count_messages_since(&self.id.channel, self.last_id.as_deref())
+            .await?;
+
+        db.set_unread_count(&self.id.channel, &self.id.user, count)
+            .await
+    }
+
+    /// When a user was last seen reading, given their latest acknowledgement
+    ///
+    /// Acknowledgements made before their time was recorded fall back to
+    /// when the last read message was sent.
+    pub fn last_active(
+        acknowledged_at: Option<Timestamp>,
+        last_id: Option<&str>,
+    ) -> Option<Timestamp> {
+        let sent_at = last_id
+            .and_then(|id| Ulid::from_string(id).ok())
+            .and_then(|id| {
+                Timestamp::UNIX_EPOCH.checked_add(Duration::milliseconds(id.timestamp_ms() as i64))
+            });
+
+        acknowledged_at.max(sent_at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iso8601_timestamp::Timestamp;
+    use ulid::Ulid;
+
+    #[async_std::test]
+    async fn finds_last_active_time() {
+        database_test!(|db| async move {
+            // Reading an old message still counts as activity now
+            let old = Ulid::from_parts(0, 0).to_string();
+            let before = Timestamp::now_utc();
+
+            db.acknowledge_message("a", "user", &old, 0).await.unwrap();
+            db.acknowledge_message("c", "other", &old, 0).await.unwrap();
+
+            let last_active = db
+                .fetch_last_active(&["a".to_string(), "b".to_string()])
+                .await
+                .unwrap();
+
+            assert!(last_active.get("user").is_some_and(|at| *at >= before));
+            assert!(!last_active.contains_key("other"));
+        });
+    }
+}
@@ -1,19 +1,28 @@
+use std::collections::HashMap;
+
 use guilderia_result::Result;
+use iso8601_timestamp::Timestamp;
 
-use crate::ChannelUnread;
+use crate::{ChannelAck, ChannelUnread};
 
 mod mongodb;
 mod reference;
 
 #[async_trait]
 pub trait AbstractChannelUnreads: Sync + Send {
-    /// Acknowledge a message, and returns updated channel unread.
+    /// Acknowledge a message.
     async fn acknowledge_message(
         &self,
         channel_id: &str,
         user_id: &str,
         message_id: &str,
-    ) -> Result<Option<ChannelUnread>>;
+        unread_count: u64,
+    ) -> Result<()>;
+
+    /// Acknowledge messages for many users and channels independently of each other.
+    ///
+    /// Returns the outcome of each acknowledgement, in the same order as given.
+    async fn acknowledge_messages_each(&self, acks: &[ChannelAck]) -> Vec<Result<()>>;
 
     /// Acknowledge many channels.
     async fn acknowledge_channels(&self, user_id: &str, channel_ids: &[String]) -> Result<()>;
@@ -34,6 +43,23 @@ pub trait AbstractChannelUnreads: Sync + Send {
         message_ids: &[String],
     ) -> Result<()>;
 
+    /// Count new messages for everyone in a channel other than the author.
+    async fn increment_unread_counts(
+        &self,
+        channel_id: &str,
+        author_id: &str,
+        count: u64,
+    ) -> Result<()>;
+
+    /// Mark unread counts in a channel as needing to be recounted.
+    async fn invalidate_unread_counts(&self, channel_id: &str) -> Result<()>;
+
+    /// Set the unread count for a specific user in a channel.
+    async fn set_unread_count(&self, channel_id: &str, user_id: &str, count: u64) -> Result<()>;
+
+    /// Fetch unreads which need their counts recounted.
+    async fn fetch_unreads_without_counts(&self, limit: i64) -> Result<Vec<ChannelUnread>>;
+
     /// Fetch all unreads with mentions for a user.
     async fn fetch_unread_mentions(&self, user_id: &str) -> Result<Vec<ChannelUnread>>;
 
@@ -42,4 +68,8 @@ pub trait AbstractChannelUnreads: Sync + Send {
 
     /// Fetch unread for a specific user in a channel.
     async fn fetch_unread(&self, user_id: &str, channel_id: &str) -> Result<Option<ChannelUnread>>;
+
+    /// Fetch when each user last acknowledged any of the given channels, keyed by user id.
+    async fn fetch_last_active(&self, channel_ids: &[String])
+        -> Result<HashMap<String, Timestamp>>;
 }
@@ -1,10 +1,15 @@
-use bson::Document;
-use mongodb::options::FindOneAndUpdateOptions;
-use mongodb::options::ReturnDocument;
+use std::collections::HashMap;
+
+use bson::{to_bson, Document};
+use futures::StreamExt;
+use iso8601_timestamp::Timestamp;
+use mongodb::options::FindOptions;
 use mongodb::options::UpdateOptions;
 use guilderia_result::Result;
 use ulid::Ulid;
 
+use crate::tasks::message_batch;
+use crate::ChannelAck;
 use crate::ChannelUnread;
 use crate::MongoDb;
 
@@ -14,43 +19,104 @@ static COL: &str = "channel_unreads";
 
 #[async_trait]
 impl AbstractChannelUnreads for MongoDb {
-    /// Acknowledge a message, and returns updated channel unread.
+    /// Acknowledge a message.
     async fn acknowledge_message(
         &self,
         channel_id: &str,
         user_id: &str,
         message_id: &str,
-    ) -> Result<Option<ChannelUnread>> {
-        self.col::<ChannelUnread>(COL)
-            .find_one_and_update(
-                doc! {
-                    "_id.channel": channel_id,
-                    "_id.user": user_id,
-                },
+        unread_count: u64,
+    ) -> Result<()> {
+        let mut ack = ChannelAck {
+            channel: channel_id.to_string(),
+            user: user_id.to_string(),
+            message: message_id.to_string(),
+            unread_count,
+        };
+
+        if message_batch::is_active() {
+            // Fall through to a direct write if the batch queue is full
+            match message_batch::acknowledge(ack).await {
+                Ok(result) => return result,
+                Err(returned) => ack = returned,
+            }
+        }
+
+        self.acknowledge_messages_each(&[ack]).await.remove(0)
+    }
+
+    /// Acknowledge messages for many users and channels independently of each other.
+    async fn acknowledge_messages_each(&self, acks: &[ChannelAck]) -> Vec<Result<()>> {
+        let Ok(acknowledged_at) = to_bson(&Timestamp::now_utc()) else {
+            return acks
+                .iter()
+                .map(|_| Err(create_database_error!("to_bson", "timestamp")))
+                .collect();
+        };
+
+        let updates: Vec<Document> = acks
+            .iter()
+            .map(|ack| {
                 doc! {
-                    "$pull": {
-                        "mentions": {
-                            "$lte": message_id
+                    "q": {
+                        "_id.channel": &ack.channel,
+                        "_id.user": &ack.user,
+                    },
+                    "u": {
+                        "$pull": {
+                            "mentions": {
+                                "$lte": &ack.message
+                            }
+                        },
+                        "$set": {
+                            "last_id": &ack.message,
+                            "unread_count": ack.unread_count as i64,
+                            "acknowledged_at": acknowledged_at.clone()
                         }
                     },
-                    "$set": {
-                        "last_id": message_id
+                    "upsert": true
+                }
+            })
+            .collect();
+
+        // Send every update in a single round trip
+        let reply = self
+            .db()
+            .run_command(doc! {
+                "update": COL,
+                "updates": updates,
+                "ordered": false
+            })
+            .await;
+
+        let mut results: Vec<Result<()>> = acks.iter().map(|_| Ok(())).collect();
+        match reply {
+            // Only the updates which failed to write were rejected
+            Ok(reply) if !reply.contains_key("writeConcernError") => {
+                for error in reply.get_array("writeErrors").into_iter().flatten() {
+                    let index = error
+                        .as_document()
+                        .and_then(|error| error.get_i32("index").ok());
+
+                    if let Some(result) = index.and_then(|index| results.get_mut(index as usize)) {
+                        *result = Err(create_database_error!("update", COL));
                     }
-                },
-            )
-            .with_options(
-                FindOneAndUpdateOptions::builder()
-                    .upsert(true)
-                    .return_document(ReturnDocument::After)
-                    .build(),
-            )
-            .await
-            .map_err(|_| create_database_error!("update_one", COL))
+                }
+
+                results
+            }
+            _ => acks
+                .iter()
+                .map(|_| Err(create_database_error!("update", COL)))
+                .collect(),
+        }
     }
 
     /// Acknowledge many channels.
     async fn acknowledge_channels(&self, user_id: &str, channel_ids: &[String]) -> Result<()> {
         let current_time = Ulid::new().to_string();
+        let acknowledged_at = to_bson(&Timestamp::now_utc())
+            .map_err(|_| create_database_error!("to_bson", "timestamp"))?;
 
         self.col::<Document>(COL)
             .delete_many(doc! {
@@ -72,7 +138,9 @@ impl AbstractChannelUnreads for MongoDb {
                                 "channel": channel_id,
                                 "user": user_id
                             },
-                            "last_id": &current_time
+                            "last_id": &current_time,
+                            "unread_count": 0_i64,
+                            "acknowledged_at": &acknowledged_at
                         }
                     })
                     .collect::<Vec<Document>>(),
@@ -138,6 +206,87 @@ impl AbstractChannelUnreads for MongoDb {
             .map_err(|_| create_database_error!("update_many", COL))
     }
 
+    /// Count new messages for everyone in a channel other than the author.
+    async fn increment_unread_counts(
+        &self,
+        channel_id: &str,
+        author_id: &str,
+        count: u64,
+    ) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_many(
+                doc! {
+                    "_id.channel": channel_id,
+                    "_id.user": {
+                        "$ne": author_id
+                    },
+                    "unread_count": {
+                        "$exists": true
+                    }
+                },
+                doc! {
+                    "$inc": {
+                        "unread_count": count as i64
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_many", COL))
+    }
+
+    /// Mark unread counts in a channel as needing to be recounted.
+    async fn invalidate_unread_counts(&self, channel_id: &str) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_many(
+                doc! {
+                    "_id.channel": channel_id
+                },
+                doc! {
+                    "$unset": {
+                        "unread_count": 1_i32
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_many", COL))
+    }
+
+    /// Set the unread count for a specific user in a channel.
+    async fn set_unread_count(&self, channel_id: &str, user_id: &str, count: u64) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id.channel": channel_id,
+                    "_id.user": user_id,
+                },
+                doc! {
+                    "$set": {
+                        "unread_count": count as i64
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Fetch unreads which need their counts recounted.
+    async fn fetch_unreads_without_counts(&self, limit: i64) -> Result<Vec<ChannelUnread>> {
+        self.find_with_options(
+            COL,
+            doc! {
+                "unread_count": {
+                    "$exists": false
+                }
+            },
+            FindOptions::builder().limit(limit).build(),
+        )
+        .await
+        .map_err(|_| create_database_error!("find", COL))
+    }
+
     /// Fetch all channel unreads for a user.
     async fn fetch_unreads(&self, user_id: &str) -> Result<Vec<ChannelUnread>> {
         query!(
@@ -174,4 +323,48 @@ impl AbstractChannelUnreads for MongoDb {
             }
         )
     }
+
+    /// Fetch when each user last acknowledged any of the given channels, keyed by user id.
+    async fn fetch_last_active(
+        &self,
+        channel_ids: &[String],
+    ) -> Result<HashMap<String, Timestamp>> {
+        // Message ids are ULIDs, so the greatest id is the latest message
+        Ok(self
+            .col::<Document>(COL)
+            .aggregate(vec![
+                doc! {
+                    "$match": {
+                        "_id.channel": {
+                            "$in": channel_ids
+                        }
+                    }
+                },
+                doc! {
+                    "$group": {
+                        "_id": "$_id.user",
+                        "acknowledged_at": {
+                            "$max": "$acknowledged_at"
+                        },
+                        "last_id": {
+                            "$max": "$last_id"
+                        }
+                    }
+                },
+            ])
+            .await
+            .map_err(|_| create_database_error!("aggregate", COL))?
+            .filter_map(|document| async move {
+                let document = document.ok()?;
+                let user = document.get_str("_id").ok()?.to_string();
+                let acknowledged_at = document
+                    .get_str("acknowledged_at")
+                    .ok()
+                    .and_then(Timestamp::parse);
+                let last_id = document.get_str("last_id").ok();
+                Some((user, ChannelUnread::last_active(acknowledged_at, last_id)?))
+            })
+            .collect()
+            .await)
+    }
 }
@@ -1,7 +1,10 @@
+use std::collections::HashMap;
+
 use guilderia_result::Result;
+use iso8601_timestamp::Timestamp;
 use ulid::Ulid;
 
-use crate::{ChannelCompositeKey, ChannelUnread, ReferenceDb};
+use crate::{ChannelAck, ChannelCompositeKey, ChannelUnread, ReferenceDb};
 
 use super::AbstractChannelUnreads;
 
@@ -13,7 +16,8 @@ impl AbstractChannelUnreads for ReferenceDb {
         channel_id: &str,
         user_id: &str,
         message_id: &str,
-    ) -> Result<Option<ChannelUnread>> {
+        unread_count: u64,
+    ) -> Result<()> {
         let mut unreads = self.channel_unreads.lock().await;
         let key = ChannelCompositeKey {
             channel: channel_id.to_string(),
@@ -23,6 +27,8 @@ impl AbstractChannelUnreads for ReferenceDb {
         if let Some(unread) = unreads.get_mut(&key) {
             unread.mentions = None;
             unread.last_id.replace(message_id.to_string());
+            unread.unread_count = Some(unread_count);
+            unread.acknowledged_at = Some(Timestamp::now_utc());
         } else {
             unreads.insert(
                 key.clone(),
@@ -30,11 +36,27 @@ impl AbstractChannelUnreads for ReferenceDb {
                     id: key.clone(),
                     last_id: Some(message_id.to_string()),
                     mentions: None,
+                    unread_count: Some(unread_count),
+                    acknowledged_at: Some(Timestamp::now_utc()),
                 },
             );
         }
 
-        Ok(unreads.get(&key).cloned())
+        Ok(())
+    }
+
+    /// Acknowledge messages for many users and channels independently of each other.
+    async fn acknowledge_messages_each(&self, acks: &[ChannelAck]) -> Vec<Result<()>> {
+        let mut results = Vec::with_capacity(acks.len());
+        for ack in acks {
+            #[allow(clippy::disallowed_methods)]
+            results.push(
+                self.acknowledge_message(&ack.channel, &ack.user, &ack.message, ack.unread_count)
+                    .await,
+            );
+        }
+
+        results
     }
 
     /// Acknowledge many channels.
@@ -42,7 +64,7 @@ impl AbstractChannelUnreads for ReferenceDb {
         let current_time = Ulid::new().to_string();
         for channel_id in channel_ids {
             #[allow(clippy::disallowed_methods)]
-            self.acknowledge_message(channel_id, user_id, &current_time)
+            self.acknowledge_message(channel_id, user_id, &current_time, 0)
                 .await?;
         }
 
@@ -71,6 +93,8 @@ impl AbstractChannelUnreads for ReferenceDb {
                     id: key,
                     last_id: None,
                     mentions: Some(message_ids.to_vec()),
+                    unread_count: None,
+                    acknowledged_at: None,
                 },
             );
         }
@@ -102,6 +126,8 @@ impl AbstractChannelUnreads for ReferenceDb {
                         id: key,
                         last_id: None,
                         mentions: Some(message_ids.to_vec()),
+                        unread_count: None,
+                        acknowledged_at: None,
                     },
                 );
             }
@@ -110,6 +136,61 @@ impl AbstractChannelUnreads for ReferenceDb {
         Ok(())
     }
 
+    /// Count new messages for everyone in a channel other than the author.
+    async fn increment_unread_counts(
+        &self,
+        channel_id: &str,
+        author_id: &str,
+        count: u64,
+    ) -> Result<()> {
+        let mut unreads = self.channel_unreads.lock().await;
+        for unread in unreads.values_mut() {
+            if unread.id.channel == channel_id && unread.id.user != author_id {
+                if let Some(unread_count) = &mut unread.unread_count {
+                    *unread_count += count;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Mark unread counts in a channel as needing to be recounted.
+    async fn invalidate_unread_counts(&self, channel_id: &str) -> Result<()> {
+        let mut unreads = self.channel_unreads.lock().await;
+        for unread in unreads.values_mut() {
+            if unread.id.channel == channel_id {
+                unread.unread_count = None;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Set the unread count for a specific user in a channel.
+    async fn set_unread_count(&self, channel_id: &str, user_id: &str, count: u64) -> Result<()> {
+        let mut unreads = self.channel_unreads.lock().await;
+        if let Some(unread) = unreads.get_mut(&ChannelCompositeKey {
+            channel: channel_id.to_string(),
+            user: user_id.to_string(),
+        }) {
+            unread.unread_count = Some(count);
+        }
+
+        Ok(())
+    }
+
+    /// Fetch unreads which need their counts recounted.
+    async fn fetch_unreads_without_counts(&self, limit: i64) -> Result<Vec<ChannelUnread>> {
+        let unreads = self.channel_unreads.lock().await;
+        Ok(unreads
+            .values()
+            .filter(|unread| unread.unread_count.is_none())
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
     async fn fetch_unread_mentions(&self, user_id: &str) -> Result<Vec<ChannelUnread>> {
         let unreads = self.channel_unreads.lock().await;
         Ok(unreads
@@ -140,4 +221,34 @@ impl AbstractChannelUnreads for ReferenceDb {
             })
             .cloned())
     }
+
+    /// Fetch when each user last acknowledged any of the given channels, keyed by user id.
+    async fn fetch_last_active(
+        &self,
+        channel_ids: &[String],
+    ) -> Result<HashMap<String, Timestamp>> {
+        let unreads = self.channel_unreads.lock().await;
+        let mut last_active: HashMap<String, Timestamp> = HashMap::new();
+        for unread in unreads.values() {
+            if !channel_ids.contains(&unread.id.channel) {
+                continue;
+            }
+
+            let Some(active) =
+                ChannelUnread::last_active(unread.acknowledged_at, unread.last_id.as_deref())
+            else {
+                continue;
+            };
+
+            let latest = last_active
+                .entry(unread.id.user.to_string())
+                .or_insert(active);
+
+            if active > *latest {
+                *latest = active;
+            }
+        }
+
+        Ok(last_active)
+    }
 }
@@ -1,15 +1,16 @@
 use std::collections::HashMap;
 
 use guilderia_config::config;
-use guilderia_models::v0::{self, MessageAuthor};
+use guilderia_models::v0::{self, AttachmentClass, MessageAuthor};
 use guilderia_permissions::OverrideField;
 use guilderia_result::Result;
 use serde::{Deserialize, Serialize};
 use ulid::Ulid;
 
 use crate::{
-    events::client::EventV1, tasks::ack::AckEvent, Database, File, IntoDocumentPath, PartialServer,
-    Server, SystemMessage, User, AMQP,
+    events::client::EventV1, tasks::ack::AckEvent, AuditLogAction, AuditLogEntry, BotDmPreferences,
+    Category, Database, File, IntoDocumentPath, PartialServer, Server, SystemMessage,
+    SystemMessageChannels, User, AMQP,
 };
 
 auto_derived!(
@@ -103,6 +104,18 @@ auto_derived!(
             /// Whether this channel is marked as not safe for work
             #[serde(skip_serializing_if = "crate::if_false", default)]
             nsfw: bool,
+            /// Whether this channel's permissions are synced with its category
+            #[serde(skip_serializing_if = "crate::if_false", default)]
+            synced: bool,
+            /// Kinds of attachments allowed in this channel, all are allowed if unset
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            allowed_attachments: Option<Vec<AttachmentClass>>,
+            /// Seconds members must wait between messages, unless they can manage messages
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            rate_limit_per_user: Option<u32>,
+            /// Seconds messages are kept for before they are deleted
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            message_retention_seconds: Option<u32>,
         },
         /// Voice channel belonging to a server
         VoiceChannel {
@@ -134,6 +147,12 @@ auto_derived!(
             /// Whether this channel is marked as not safe for work
             #[serde(skip_serializing_if = "crate::if_false", default)]
             nsfw: bool,
+            /// Whether this channel's permissions are synced with its category
+            #[serde(skip_serializing_if = "crate::if_false", default)]
+            synced: bool,
+            /// Text channel in which calls starting and ending are announced
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            activity_channel: Option<String>,
         },
     }
 );
@@ -161,6 +180,16 @@ auto_derived!(
         pub default_permissions: Option<OverrideField>,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub last_message_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub synced: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub allowed_attachments: Option<Vec<AttachmentClass>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub rate_limit_per_user: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub message_retention_seconds: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub activity_channel: Option<String>,
     }
 
     /// Optional fields on channel object
@@ -168,6 +197,10 @@ auto_derived!(
         Description,
         Icon,
         DefaultPermissions,
+        AllowedAttachments,
+        RateLimitPerUser,
+        MessageRetentionSeconds,
+        ActivityChannel,
     }
 );
 
@@ -219,6 +252,10 @@ impl Channel {
                 default_permissions: None,
                 role_permissions: HashMap::new(),
                 nsfw: data.nsfw.unwrap_or(false),
+                synced: false,
+                allowed_attachments: None,
+                rate_limit_per_user: None,
+                message_retention_seconds: None,
             },
             v0::LegacyServerChannelType::Voice => Channel::VoiceChannel {
                 id: id.clone(),
@@ -229,6 +266,8 @@ impl Channel {
                 default_permissions: None,
                 role_permissions: HashMap::new(),
                 nsfw: data.nsfw.unwrap_or(false),
+                synced: false,
+                activity_channel: None,
             },
         };
 
@@ -306,6 +345,11 @@ impl Channel {
 
     /// Create a DM (or return the existing one / saved messages)
     pub async fn create_dm(db: &Database, user_a: &User, user_b: &User) -> Result<Channel> {
+        // Users may refuse direct messages from bots
+        if user_a.bot.is_some() && user_b.bot.is_none() {
+            BotDmPreferences::check(db, &user_b.id, &user_a.id).await?;
+        }
+
         // Try to find existing channel
         if let Ok(channel) = db.find_direct_message_channel(&user_a.id, &user_b.id).await {
             Ok(channel)
@@ -407,6 +451,32 @@ impl Channel {
         matches!(self, Channel::DirectMessage { .. })
     }
 
+    /// Find the channel a system message originating in this channel should be sent to
+    ///
+    /// Returns `None` if the server has disabled this type of system message.
+    pub async fn route_system_message(
+        &self,
+        db: &Database,
+        message: &SystemMessage,
+    ) -> Result<Option<Channel>> {
+        let server = match self {
+            Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } => {
+                db.fetch_server(server).await?
+            }
+            _ => return Ok(Some(self.clone())),
+        };
+
+        match SystemMessageChannels::route(
+            server.system_messages.as_ref(),
+            message,
+            Some(self.id()),
+        ) {
+            Some(id) if id == self.id() => Ok(Some(self.clone())),
+            Some(id) => db.fetch_channel(&id).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
     /// Check whether has a user as a recipient
     pub fn contains_user(&self, user_id: &str) -> bool {
         match self {
@@ -434,39 +504,113 @@ impl Channel {
         }
     }
 
+    /// Kinds of attachments allowed in this channel, if it restricts them
+    pub fn allowed_attachments(&self) -> Option<&[AttachmentClass]> {
+        match self {
+            Channel::TextChannel {
+                allowed_attachments,
+                ..
+            } => allowed_attachments.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Seconds members must wait between messages in this channel, if slowmode is on
+    pub fn rate_limit_per_user(&self) -> Option<u32> {
+        match self {
+            Channel::TextChannel {
+                rate_limit_per_user,
+                ..
+            } => *rate_limit_per_user,
+            _ => None,
+        }
+    }
+
+    /// Seconds messages are kept for in this channel, if they expire
+    pub fn message_retention_seconds(&self) -> Option<u32> {
+        match self {
+            Channel::TextChannel {
+                message_retention_seconds,
+                ..
+            } => *message_retention_seconds,
+            _ => None,
+        }
+    }
+
+    /// Text channel in which calls in this channel are announced, if any
+    pub fn activity_channel(&self) -> Option<&str> {
+        match self {
+            Channel::VoiceChannel {
+                activity_channel, ..
+            } => activity_channel.as_deref(),
+            _ => None,
+        }
+    }
+
     /// Set role permission on a channel
+    ///
+    /// The change is recorded in the server's audit log.
     pub async fn set_role_permission(
         &mut self,
         db: &Database,
         role_id: &str,
         permissions: OverrideField,
+        actor: &str,
     ) -> Result<()> {
         match self {
             Channel::TextChannel {
                 id,
                 server,
                 role_permissions,
+                synced,
                 ..
             }
             | Channel::VoiceChannel {
                 id,
                 server,
                 role_permissions,
+                synced,
                 ..
             } => {
                 db.set_channel_role_permission(id, role_id, permissions)
                     .await?;
 
-                role_permissions.insert(role_id.to_string(), permissions);
+                let before = role_permissions
+                    .insert(role_id.to_string(), permissions)
+                    .unwrap_or_default();
+
+                // Overriding a permission breaks the sync with the category
+                let was_synced = std::mem::take(synced);
+                if was_synced {
+                    db.update_channel(
+                        id,
+                        &PartialChannel {
+                            synced: Some(false),
+                            ..Default::default()
+                        },
+                        vec![],
+                    )
+                    .await?;
+                }
+
+                AuditLogEntry::create(
+                    db,
+                    server,
+                    actor,
+                    AuditLogAction::PermissionOverrideEdit {
+                        channel: Some(id.clone()),
+                        role: Some(role_id.to_string()),
+                        before,
+                        after: permissions,
+                    },
+                )
+                .await?;
 
-                EventV1::ChannelUpdate {
+                EventV1::ChannelRolePermissionUpdate {
                     id: id.clone(),
-                    data: PartialChannel {
-                        role_permissions: Some(role_permissions.clone()),
-                        ..Default::default()
-                    }
-                    .into(),
-                    clear: vec![],
+                    role_id: role_id.to_string(),
+                    diff: v0::PermissionDiff::new(before.into(), permissions.into()),
+                    by: actor.to_string(),
                 }
                 .p(server.clone())
                 .await;
@@ -477,6 +621,52 @@ impl Channel {
         }
     }
 
+    /// Replace this channel's permissions with those of its category
+    pub async fn sync_permissions(&mut self, db: &Database, category: &Category) -> Result<()> {
+        if !matches!(
+            self,
+            Channel::TextChannel { .. } | Channel::VoiceChannel { .. }
+        ) {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        self.update(
+            db,
+            PartialChannel {
+                default_permissions: category.default_permissions,
+                role_permissions: Some(category.role_permissions.clone()),
+                synced: Some(true),
+                ..Default::default()
+            },
+            if category.default_permissions.is_none() {
+                vec![FieldsChannel::DefaultPermissions]
+            } else {
+                vec![]
+            },
+        )
+        .await
+    }
+
+    /// Check whether this channel's permissions match those of a category
+    pub fn matches_category(&self, category: &Category) -> bool {
+        match self {
+            Channel::TextChannel {
+                default_permissions,
+                role_permissions,
+                ..
+            }
+            | Channel::VoiceChannel {
+                default_permissions,
+                role_permissions,
+                ..
+            } => {
+                default_permissions == &category.default_permissions
+                    && role_permissions == &category.role_permissions
+            }
+            _ => false,
+        }
+    }
+
     /// Update channel data
     pub async fn update(
         &mut self,
@@ -539,6 +729,41 @@ impl Channel {
                 }
                 _ => {}
             },
+            FieldsChannel::AllowedAttachments => {
+                if let Self::TextChannel {
+                    allowed_attachments,
+                    ..
+                } = self
+                {
+                    allowed_attachments.take();
+                }
+            }
+            FieldsChannel::RateLimitPerUser => {
+                if let Self::TextChannel {
+                    rate_limit_per_user,
+                    ..
+                } = self
+                {
+                    rate_limit_per_user.take();
+                }
+            }
+            FieldsChannel::MessageRetentionSeconds => {
+                if let Self::TextChannel {
+                    message_retention_seconds,
+                    ..
+                } = self
+                {
+                    message_retention_seconds.take();
+                }
+            }
+            FieldsChannel::ActivityChannel => {
+                if let Self::VoiceChannel {
+                    activity_channel, ..
+                } = self
+                {
+                    activity_channel.take();
+                }
+            }
         }
     }
 
@@ -598,6 +823,7 @@ impl Channel {
                 nsfw,
                 default_permissions,
                 role_permissions,
+                synced,
                 ..
             }
             | Self::VoiceChannel {
@@ -607,6 +833,7 @@ impl Channel {
                 nsfw,
                 default_permissions,
                 role_permissions,
+                synced,
                 ..
             } => {
                 if let Some(v) = partial.name {
@@ -632,6 +859,39 @@ impl Channel {
                 if let Some(v) = partial.default_permissions {
                     default_permissions.replace(v);
                 }
+
+                if let Some(v) = partial.synced {
+                    *synced = v;
+                }
+            }
+        }
+
+        if let Self::TextChannel {
+            allowed_attachments,
+            rate_limit_per_user,
+            message_retention_seconds,
+            ..
+        } = self
+        {
+            if let Some(v) = partial.allowed_attachments {
+                allowed_attachments.replace(v);
+            }
+
+            if let Some(v) = partial.rate_limit_per_user {
+                rate_limit_per_user.replace(v);
+            }
+
+            if let Some(v) = partial.message_retention_seconds {
+                message_retention_seconds.replace(v);
+            }
+        }
+
+        if let Self::VoiceChannel {
+            activity_channel, ..
+        } = self
+        {
+            if let Some(v) = partial.activity_channel {
+                activity_channel.replace(v);
             }
         }
     }
@@ -712,6 +972,7 @@ impl Channel {
                 }
 
                 db.remove_user_from_group(id, &user.id).await?;
+                db.delete_read_receipt(id, &user.id).await?;
 
                 EventV1::ChannelGroupLeave {
                     id: id.to_string(),
@@ -729,6 +990,7 @@ impl Channel {
                     } else {
                         SystemMessage::UserLeft {
                             id: user.id.to_string(),
+                            content: None,
                         }
                     }
                     .into_message(id.to_string())
@@ -758,11 +1020,15 @@ impl Channel {
     /// Delete a channel
     pub async fn delete(&self, db: &Database) -> Result<()> {
         let id = self.id().to_string();
-        EventV1::ChannelDelete { id: id.clone() }.p(id).await;
+        EventV1::ChannelDelete { id: id.clone() }
+            .p(id.clone())
+            .await;
         // TODO: missing functionality:
         // - group invites
         // - channels list / categories list on server
-        db.delete_channel(self).await
+        db.delete_channel(self).await?;
+        crate::tasks::search_index::remove_channel(id).await;
+        Ok(())
     }
 }
 
@@ -772,6 +1038,10 @@ impl IntoDocumentPath for FieldsChannel {
             FieldsChannel::Description => "description",
             FieldsChannel::Icon => "icon",
             FieldsChannel::DefaultPermissions => "default_permissions",
+            FieldsChannel::AllowedAttachments => "allowed_attachments",
+            FieldsChannel::RateLimitPerUser => "rate_limit_per_user",
+            FieldsChannel::MessageRetentionSeconds => "message_retention_seconds",
+            FieldsChannel::ActivityChannel => "activity_channel",
         })
     }
 }
@@ -14,6 +14,9 @@ pub trait AbstractChannels: Sync + Send {
     /// Fetch all channels from the database
     async fn fetch_channels<'a>(&self, ids: &'a [String]) -> Result<Vec<Channel>>;
 
+    /// Search channels in a server whose name starts with the given prefix
+    async fn search_server_channels(&self, server_id: &str, prefix: &str) -> Result<Vec<Channel>>;
+
     /// Fetch all direct messages for a user
     async fn find_direct_messages(&self, user_id: &str) -> Result<Vec<Channel>>;
 
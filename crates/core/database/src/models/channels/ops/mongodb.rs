@@ -1,5 +1,8 @@
 use super::AbstractChannels;
-use crate::{AbstractServers, Channel, FieldsChannel, IntoDocumentPath, MongoDb, PartialChannel};
+use crate::{
+    AbstractServers, Channel, FieldsChannel, IntoDocumentPath, MongoDb, PartialChannel,
+    SystemMessageTarget,
+};
 use bson::{Bson, Document};
 use futures::StreamExt;
 use guilderia_permissions::OverrideField;
@@ -41,6 +44,22 @@ impl AbstractChannels for MongoDb {
             .await)
     }
 
+    /// Search channels in a server whose name starts with the given prefix
+    async fn search_server_channels(&self, server_id: &str, prefix: &str) -> Result<Vec<Channel>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "server": server_id,
+                "name": {
+                    "$regex": format!("^{}", regex::escape(prefix)),
+                    "$options": "i"
+                }
+            }
+        )
+    }
+
     /// Fetch all direct messages for a user
     async fn find_direct_messages(&self, user_id: &str) -> Result<Vec<Channel>> {
         query!(
@@ -236,11 +255,38 @@ impl AbstractChannels for MongoDb {
                     }
                 }
 
+                if let Some(SystemMessageTarget::Channel { id: cid }) = &sys.message_pinned {
+                    if &id == cid {
+                        unset.insert("system_messages.message_pinned", 1_i32);
+                    }
+                }
+
+                if let Some(cid) = &sys.moderation_alerts {
+                    if &id == cid {
+                        unset.insert("system_messages.moderation_alerts", 1_i32);
+                    }
+                }
+
                 if !unset.is_empty() {
                     update.insert("$unset", unset);
                 }
             }
 
+            if server
+                .starboard
+                .as_ref()
+                .is_some_and(|starboard| starboard.channel == id)
+            {
+                match update.get_document_mut("$unset") {
+                    Ok(unset) => {
+                        unset.insert("starboard", 1_i32);
+                    }
+                    Err(_) => {
+                        update.insert("$unset", doc! { "starboard": 1_i32 });
+                    }
+                }
+            }
+
             self.col::<Document>("servers")
                 .update_one(
                     doc! {
@@ -282,6 +328,15 @@ impl MongoDb {
             .map_err(|_| create_database_error!("delete_many", "channel_unreads"))
             .map(|_| ())?;
 
+        // Delete read receipts on channels.
+        self.col::<Document>("read_receipts")
+            .delete_many(doc! {
+                "_id.channel": &id
+            })
+            .await
+            .map_err(|_| create_database_error!("delete_many", "read_receipts"))
+            .map(|_| ())?;
+
         // update many attachments with parent id
 
         // Delete all webhooks on this channel.
@@ -41,6 +41,23 @@ impl AbstractChannels for ReferenceDb {
             .collect()
     }
 
+    /// Search channels in a server whose name starts with the given prefix
+    async fn search_server_channels(&self, server_id: &str, prefix: &str) -> Result<Vec<Channel>> {
+        let prefix = prefix.to_lowercase();
+        let channels = self.channels.lock().await;
+        Ok(channels
+            .values()
+            .filter(|channel| match channel {
+                Channel::TextChannel { server, name, .. }
+                | Channel::VoiceChannel { server, name, .. } => {
+                    server == server_id && name.to_lowercase().starts_with(&prefix)
+                }
+                _ => false,
+            })
+            .cloned()
+            .collect())
+    }
+
     /// Fetch all direct messages for a user
     async fn find_direct_messages(&self, user_id: &str) -> Result<Vec<Channel>> {
         let channels = self.channels.lock().await;
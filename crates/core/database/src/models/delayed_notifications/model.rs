@@ -0,0 +1,45 @@
+use guilderia_models::v0::PushNotification;
+use guilderia_result::Result;
+use iso8601_timestamp::Timestamp;
+use ulid::Ulid;
+
+use crate::Database;
+
+auto_derived!(
+    /// Push notification held back until the recipient's quiet hours end
+    pub struct DelayedNotification {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the user to notify
+        pub user: String,
+        /// Time at which the notification is due
+        pub deliver_at: Timestamp,
+        /// Notification to deliver
+        pub notification: PushNotification,
+        /// Time until which a worker holds the notification while delivering it
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub claimed_until: Option<Timestamp>,
+    }
+);
+
+impl DelayedNotification {
+    /// Hold back a notification for a user until the given time
+    pub async fn create(
+        db: &Database,
+        user: String,
+        deliver_at: Timestamp,
+        notification: PushNotification,
+    ) -> Result<DelayedNotification> {
+        let delayed = DelayedNotification {
+            id: Ulid::new().to_string(),
+            user,
+            deliver_at,
+            notification,
+            claimed_until: None,
+        };
+
+        db.insert_delayed_notification(&delayed).await?;
+        Ok(delayed)
+    }
+}
@@ -0,0 +1,25 @@
+use guilderia_result::Result;
+use iso8601_timestamp::Duration;
+
+use crate::DelayedNotification;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractDelayedNotifications: Sync + Send {
+    /// Insert a delayed notification
+    async fn insert_delayed_notification(&self, notification: &DelayedNotification) -> Result<()>;
+
+    /// Claim a due delayed notification which no other worker holds
+    ///
+    /// The notification is handed out again once `lease` has passed
+    /// unless it was deleted in the meantime.
+    async fn claim_due_delayed_notification(
+        &self,
+        lease: Duration,
+    ) -> Result<Option<DelayedNotification>>;
+
+    /// Delete a delayed notification
+    async fn delete_delayed_notification(&self, id: &str) -> Result<()>;
+}
@@ -0,0 +1,59 @@
+use bson::to_bson;
+use guilderia_result::Result;
+use iso8601_timestamp::{Duration, Timestamp};
+use mongodb::options::{FindOneAndUpdateOptions, ReturnDocument};
+
+use crate::DelayedNotification;
+use crate::MongoDb;
+
+use super::AbstractDelayedNotifications;
+
+static COL: &str = "delayed_notifications";
+
+#[async_trait]
+impl AbstractDelayedNotifications for MongoDb {
+    /// Insert a delayed notification
+    async fn insert_delayed_notification(&self, notification: &DelayedNotification) -> Result<()> {
+        query!(self, insert_one, COL, &notification).map(|_| ())
+    }
+
+    /// Claim a due delayed notification which no other worker holds
+    async fn claim_due_delayed_notification(
+        &self,
+        lease: Duration,
+    ) -> Result<Option<DelayedNotification>> {
+        let now = Timestamp::now_utc();
+        let now_bson = to_bson(&now).map_err(|_| create_database_error!("to_bson", "timestamp"))?;
+
+        self.col::<DelayedNotification>(COL)
+            .find_one_and_update(
+                doc! {
+                    "deliver_at": {
+                        "$lte": now_bson.clone()
+                    },
+                    "$or": [
+                        { "claimed_until": { "$exists": false } },
+                        { "claimed_until": { "$lte": now_bson } }
+                    ]
+                },
+                doc! {
+                    "$set": {
+                        "claimed_until": to_bson(&now.checked_add(lease).unwrap_or(now))
+                            .map_err(|_| create_database_error!("to_bson", "timestamp"))?
+                    }
+                },
+            )
+            .with_options(
+                FindOneAndUpdateOptions::builder()
+                    .return_document(ReturnDocument::After)
+                    .build(),
+            )
+            .await
+            .map_err(|_| create_database_error!("find_one_and_update", COL))
+    }
+
+    /// Delete a delayed notification
+    async fn delete_delayed_notification(&self, id: &str) -> Result<()> {
+        query!(self, delete_one_by_id, COL, id).map(|_| ())
+    }
+}
@@ -0,0 +1,50 @@
+use guilderia_result::Result;
+use iso8601_timestamp::{Duration, Timestamp};
+
+use crate::DelayedNotification;
+use crate::ReferenceDb;
+
+use super::AbstractDelayedNotifications;
+
+#[async_trait]
+impl AbstractDelayedNotifications for ReferenceDb {
+    /// Insert a delayed notification
+    async fn insert_delayed_notification(&self, notification: &DelayedNotification) -> Result<()> {
+        let mut delayed_notifications = self.delayed_notifications.lock().await;
+        if delayed_notifications.contains_key(&notification.id) {
+            Err(create_database_error!("insert", "delayed_notifications"))
+        } else {
+            delayed_notifications.insert(notification.id.to_string(), notification.clone());
+            Ok(())
+        }
+    }
+
+    /// Claim a due delayed notification which no other worker holds
+    async fn claim_due_delayed_notification(
+        &self,
+        lease: Duration,
+    ) -> Result<Option<DelayedNotification>> {
+        let now = Timestamp::now_utc();
+        let mut delayed_notifications = self.delayed_notifications.lock().await;
+        let Some(notification) = delayed_notifications.values_mut().find(|notification| {
+            *notification.deliver_at <= *now
+                && notification
+                    .claimed_until
+                    .map_or(true, |claimed_until| *claimed_until <= *now)
+        }) else {
+            return Ok(None);
+        };
+
+        notification.claimed_until = Some(now.checked_add(lease).unwrap_or(now));
+        Ok(Some(notification.clone()))
+    }
+
+    /// Delete a delayed notification
+    async fn delete_delayed_notification(&self, id: &str) -> Result<()> {
+        if self.delayed_notifications.lock().await.remove(id).is_some() {
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+use authifier::{
+    config::{EmailVerificationConfig, Template},
+    Authifier,
+};
+use guilderia_config::config;
+use guilderia_result::{ErrorType, Result};
+use iso8601_timestamp::Timestamp;
+use serde_json::json;
+
+use crate::{events::client::EventV1, Database, RatelimitEvent, RatelimitEventType, User};
+
+/// Approval emails which may be sent to a user per hour
+const APPROVAL_EMAILS_PER_HOUR: usize = 3;
+
+auto_derived!(
+    /// Device a user has signed in from
+    ///
+    /// Devices are tracked per session and, if the user asks for it,
+    /// sessions on new devices must be approved before they can be used.
+    pub struct Device {
+        /// Id of the session on this device
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the user who owns this device
+        pub user: String,
+        /// Name of the session
+        pub name: String,
+        /// Whether this device has been approved
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub trusted: bool,
+        /// Secret token for approving this device from an email link
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub approval_token: Option<String>,
+        /// Time at which this device was first seen
+        pub created_at: Timestamp,
+        /// Time at which this device was approved
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub approved_at: Option<Timestamp>,
+    }
+);
+
+#[allow(clippy::disallowed_methods)]
+impl Device {
+    /// Record a device which is trusted from the start
+    pub async fn create_trusted(db: &Database, user: &str, id: &str, name: &str) -> Result<Device> {
+        let now = Timestamp::now_utc();
+        let device = Device {
+            id: id.to_string(),
+            user: user.to_string(),
+            name: name.to_string(),
+            trusted: true,
+            approval_token: None,
+            created_at: now,
+            approved_at: Some(now),
+        };
+
+        db.insert_device(&device).await?;
+        Ok(device)
+    }
+
+    /// Record a new device and ask the user's other sessions to approve it
+    ///
+    /// An approval link is also emailed to the account, if email is configured.
+    pub async fn create_pending(db: &Database, user: &str, id: &str, name: &str) -> Result<Device> {
+        let device = Device {
+            id: id.to_string(),
+            user: user.to_string(),
+            name: name.to_string(),
+            trusted: false,
+            approval_token: Some(nanoid::nanoid!(64)),
+            created_at: Timestamp::now_utc(),
+            approved_at: None,
+        };
+
+        db.insert_device(&device).await?;
+        EventV1::DeviceApprovalRequest(device.clone().into())
+            .private(user.to_string())
+            .await;
+
+        let authifier = db.clone().to_authifier().await;
+        device.send_approval_email(db, &authifier).await.ok();
+
+        Ok(device)
+    }
+
+    /// Check whether a session may be used by its user
+    ///
+    /// Sessions on devices seen for the first time are recorded as pending.
+    pub async fn verify(db: &Database, user: &User, id: &str, name: &str) -> Result<()> {
+        if !user.require_device_approval {
+            return Ok(());
+        }
+
+        let trusted = match db.fetch_device(id).await {
+            Ok(device) => device.trusted,
+            Err(err) if matches!(err.error_type, ErrorType::NotFound) => {
+                Device::create_pending(db, &user.id, id, name).await?;
+                false
+            }
+            Err(err) => return Err(err),
+        };
+
+        if trusted {
+            Ok(())
+        } else {
+            Err(create_error!(DeviceApprovalRequired))
+        }
+    }
+
+    /// Email the owner a link which approves this device
+    pub async fn send_approval_email(&self, db: &Database, authifier: &Authifier) -> Result<()> {
+        let (Some(token), EmailVerificationConfig::Enabled { smtp, .. }) =
+            (&self.approval_token, &authifier.config.email_verification)
+        else {
+            return Err(create_error!(InvalidOperation));
+        };
+
+        if db
+            .has_ratelimited(
+                &self.user,
+                RatelimitEventType::DeviceApprovalEmail,
+                Duration::from_secs(60 * 60),
+                APPROVAL_EMAILS_PER_HOUR,
+            )
+            .await?
+        {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        RatelimitEvent::create(
+            db,
+            self.user.to_string(),
+            RatelimitEventType::DeviceApprovalEmail,
+        )
+        .await?;
+
+        let account = authifier
+            .database
+            .find_account(&self.user)
+            .await
+            .map_err(|_| create_error!(InternalError))?;
+
+        smtp.send_email(
+            account.email.clone(),
+            &Template {
+                title: "Approve sign in from a new device.".to_string(),
+                html: None,
+                text: include_str!("../../../templates/device-approval.txt").to_owned(),
+                url: Default::default(),
+            },
+            json!({
+                "email": account.email,
+                "name": self.name,
+                "url": format!("{}/login/device/{token}", config().await.hosts.app),
+            }),
+        )
+        .map_err(|_| create_error!(InternalError))
+    }
+
+    /// Approve this device
+    pub async fn approve(&mut self, db: &Database) -> Result<()> {
+        let now = Timestamp::now_utc();
+        db.trust_device(&self.id, now).await?;
+
+        self.trusted = true;
+        self.approval_token = None;
+        self.approved_at = Some(now);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use guilderia_result::ErrorType;
+
+    use crate::{Device, User};
+
+    #[async_std::test]
+    async fn new_devices_need_approval() {
+        database_test!(|db| async move {
+            let mut user = User {
+                id: "user".to_string(),
+                ..Default::default()
+            };
+
+            Device::verify(&db, &user, "session", "Firefox")
+                .await
+                .unwrap();
+
+            user.require_device_approval = true;
+            Device::create_trusted(&db, "user", "trusted", "Chrome")
+                .await
+                .unwrap();
+            Device::verify(&db, &user, "trusted", "Chrome")
+                .await
+                .unwrap();
+
+            let err = Device::verify(&db, &user, "session", "Firefox")
+                .await
+                .unwrap_err();
+            assert!(matches!(err.error_type, ErrorType::DeviceApprovalRequired));
+
+            let mut device = db.fetch_device("session").await.unwrap();
+            assert!(!device.trusted);
+
+            let token = device.approval_token.clone().unwrap();
+            assert_eq!(
+                db.fetch_device_by_approval_token(&token).await.unwrap().id,
+                "session"
+            );
+
+            device.approve(&db).await.unwrap();
+            Device::verify(&db, &user, "session", "Firefox")
+                .await
+                .unwrap();
+            assert!(db.fetch_device_by_approval_token(&token).await.is_err());
+        });
+    }
+}
@@ -0,0 +1,31 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+
+use crate::Device;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractDevices: Sync + Send {
+    /// Insert new device into database
+    async fn insert_device(&self, device: &Device) -> Result<()>;
+
+    /// Fetch a device by the id of its session
+    async fn fetch_device(&self, id: &str) -> Result<Device>;
+
+    /// Fetch a pending device by its approval token
+    async fn fetch_device_by_approval_token(&self, token: &str) -> Result<Device>;
+
+    /// Fetch all devices belonging to a user
+    async fn fetch_devices(&self, user_id: &str) -> Result<Vec<Device>>;
+
+    /// Mark a device as trusted
+    async fn trust_device(&self, id: &str, approved_at: Timestamp) -> Result<()>;
+
+    /// Delete a device belonging to a user
+    async fn delete_device(&self, user_id: &str, id: &str) -> Result<()>;
+
+    /// Delete all devices belonging to a user
+    async fn delete_devices(&self, user_id: &str) -> Result<()>;
+}
@@ -0,0 +1,100 @@
+use bson::{to_bson, Document};
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+
+use crate::Device;
+use crate::MongoDb;
+
+use super::AbstractDevices;
+
+static COL: &str = "devices";
+
+#[async_trait]
+impl AbstractDevices for MongoDb {
+    /// Insert new device into database
+    async fn insert_device(&self, device: &Device) -> Result<()> {
+        query!(self, insert_one, COL, &device).map(|_| ())
+    }
+
+    /// Fetch a device by the id of its session
+    async fn fetch_device(&self, id: &str) -> Result<Device> {
+        query!(self, find_one_by_id, COL, id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch a pending device by its approval token
+    async fn fetch_device_by_approval_token(&self, token: &str) -> Result<Device> {
+        query!(
+            self,
+            find_one,
+            COL,
+            doc! {
+                "approval_token": token
+            }
+        )?
+        .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all devices belonging to a user
+    async fn fetch_devices(&self, user_id: &str) -> Result<Vec<Device>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "user": user_id
+            }
+        )
+    }
+
+    /// Mark a device as trusted
+    async fn trust_device(&self, id: &str, approved_at: Timestamp) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$set": {
+                        "trusted": true,
+                        "approved_at": to_bson(&approved_at)
+                            .map_err(|_| create_database_error!("to_bson", "approved_at"))?
+                    },
+                    "$unset": {
+                        "approval_token": 1_i32
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Delete a device belonging to a user
+    async fn delete_device(&self, user_id: &str, id: &str) -> Result<()> {
+        let result = self
+            .col::<Document>(COL)
+            .delete_one(doc! {
+                "_id": id,
+                "user": user_id
+            })
+            .await
+            .map_err(|_| create_database_error!("delete_one", COL))?;
+
+        if result.deleted_count == 0 {
+            Err(create_error!(NotFound))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Delete all devices belonging to a user
+    async fn delete_devices(&self, user_id: &str) -> Result<()> {
+        self.col::<Document>(COL)
+            .delete_many(doc! {
+                "user": user_id
+            })
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("delete_many", COL))
+    }
+}
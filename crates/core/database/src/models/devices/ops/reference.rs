@@ -0,0 +1,81 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+
+use crate::Device;
+use crate::ReferenceDb;
+
+use super::AbstractDevices;
+
+#[async_trait]
+impl AbstractDevices for ReferenceDb {
+    /// Insert new device into database
+    async fn insert_device(&self, device: &Device) -> Result<()> {
+        let mut devices = self.devices.lock().await;
+        if devices.contains_key(&device.id) {
+            Err(create_database_error!("insert", "device"))
+        } else {
+            devices.insert(device.id.to_string(), device.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a device by the id of its session
+    async fn fetch_device(&self, id: &str) -> Result<Device> {
+        let devices = self.devices.lock().await;
+        devices
+            .get(id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch a pending device by its approval token
+    async fn fetch_device_by_approval_token(&self, token: &str) -> Result<Device> {
+        let devices = self.devices.lock().await;
+        devices
+            .values()
+            .find(|device| device.approval_token.as_deref() == Some(token))
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all devices belonging to a user
+    async fn fetch_devices(&self, user_id: &str) -> Result<Vec<Device>> {
+        let devices = self.devices.lock().await;
+        Ok(devices
+            .values()
+            .filter(|device| device.user == user_id)
+            .cloned()
+            .collect())
+    }
+
+    /// Mark a device as trusted
+    async fn trust_device(&self, id: &str, approved_at: Timestamp) -> Result<()> {
+        let mut devices = self.devices.lock().await;
+        if let Some(device) = devices.get_mut(id) {
+            device.trusted = true;
+            device.approval_token = None;
+            device.approved_at = Some(approved_at);
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Delete a device belonging to a user
+    async fn delete_device(&self, user_id: &str, id: &str) -> Result<()> {
+        let mut devices = self.devices.lock().await;
+        if devices.get(id).is_some_and(|device| device.user == user_id) {
+            devices.remove(id);
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Delete all devices belonging to a user
+    async fn delete_devices(&self, user_id: &str) -> Result<()> {
+        let mut devices = self.devices.lock().await;
+        devices.retain(|_, device| device.user != user_id);
+        Ok(())
+    }
+}
@@ -33,6 +33,9 @@ auto_derived!(
         /// Whether the emoji is marked as nsfw
         #[serde(skip_serializing_if = "crate::if_false", default)]
         pub nsfw: bool,
+        /// Whether the emoji is waiting for moderator approval
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub pending: bool,
     }
 
     /// Parent Id of the emoji
@@ -75,11 +78,35 @@ impl Emoji {
         db.detach_emoji(&self).await
     }
 
+    /// Approve a pending emoji
+    pub async fn approve(&mut self, db: &Database) -> Result<()> {
+        db.approve_emoji(&self.id).await?;
+        self.pending = false;
+
+        EventV1::EmojiApprove {
+            id: self.id.to_string(),
+        }
+        .p(self.parent().to_string())
+        .await;
+
+        Ok(())
+    }
+
+    /// Reject a pending emoji
+    pub async fn reject(self, db: &Database) -> Result<()> {
+        EventV1::EmojiReject {
+            id: self.id.to_string(),
+        }
+        .p(self.parent().to_string())
+        .await;
+
+        db.detach_emoji(&self).await
+    }
+
     /// Check whether we can use a given emoji
     pub async fn can_use(db: &Database, emoji: &str) -> Result<bool> {
         if Ulid::from_str(emoji).is_ok() {
-            db.fetch_emoji(emoji).await?;
-            Ok(true)
+            Ok(!db.fetch_emoji(emoji).await?.pending)
         } else {
             Ok(PERMISSIBLE_EMOJIS.contains(emoji))
         }
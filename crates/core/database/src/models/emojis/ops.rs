@@ -21,4 +21,7 @@ pub trait AbstractEmojis: Sync + Send {
 
     /// Detach an emoji by its id
     async fn detach_emoji(&self, emoji: &Emoji) -> Result<()>;
+
+    /// Mark a pending emoji as approved
+    async fn approve_emoji(&self, id: &str) -> Result<()>;
 }
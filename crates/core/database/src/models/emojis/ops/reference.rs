@@ -64,4 +64,15 @@ impl AbstractEmojis for ReferenceDb {
             Err(create_error!(NotFound))
         }
     }
+
+    /// Mark a pending emoji as approved
+    async fn approve_emoji(&self, id: &str) -> Result<()> {
+        let mut emojis = self.emojis.lock().await;
+        if let Some(emoji) = emojis.get_mut(id) {
+            emoji.pending = false;
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
 }
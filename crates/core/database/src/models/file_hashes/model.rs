@@ -27,10 +27,29 @@ auto_derived_partial!(
         pub content_type: String,
         /// Size of this file (in bytes)
         pub size: isize,
+
+        /// Result of the most recent virus scan
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        pub scan: Option<FileScan>,
     },
     "PartialFileHash"
 );
 
+auto_derived!(
+    /// Result of a virus scan
+    pub struct FileScan {
+        /// Name of the matched signature, if the file is infected
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        pub signature: Option<String>,
+        /// Engine version used to scan the file
+        pub engine: String,
+        /// Version of the signature database used to scan the file
+        pub signatures: u32,
+        /// When this file was scanned
+        pub scanned_at: Timestamp,
+    }
+);
+
 auto_derived!(
     /// Metadata associated with a file
     #[serde(tag = "type")]
@@ -40,20 +59,83 @@ auto_derived!(
         #[default]
         File,
         /// File contains textual data and should be displayed as such
-        Text,
+        Text {
+            /// Language detected from the file name
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            language: Option<String>,
+            /// Number of lines in the file
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            lines: Option<isize>,
+            /// First few lines of the file
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            preview: Option<String>,
+        },
         /// File is an image with specific dimensions
         Image {
             width: isize,
             height: isize,
             // animated: bool // TODO: https://docs.rs/image/latest/image/trait.AnimationDecoder.html for APNG support
+            /// Compact placeholder to show while the image loads
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            blurhash: Option<String>,
+            /// Metadata removed from the image on upload
+            #[serde(skip_serializing_if = "Vec::is_empty", default)]
+            stripped: Vec<StrippedMetadata>,
         },
         /// File is a video with specific dimensions
-        Video { width: isize, height: isize },
+        Video {
+            width: isize,
+            height: isize,
+            /// Compact placeholder to show while the video loads
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            blurhash: Option<String>,
+        },
         /// File is audio
-        Audio,
+        Audio {
+            /// Length of the audio (in milliseconds)
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            duration: Option<isize>,
+            /// Peak loudness of evenly spaced slices of the audio, from 0 to 255
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            waveform: Option<Vec<u8>>,
+        },
+        /// File is an archive containing other files
+        Archive {
+            /// Listing of files in the archive, may be truncated
+            files: Vec<ArchiveEntry>,
+            /// Total number of files in the archive
+            count: isize,
+            /// Total size of the files once decompressed (in bytes)
+            size: isize,
+        },
+    }
+
+    /// File contained within an archive
+    pub struct ArchiveEntry {
+        /// Path of the file within the archive
+        pub name: String,
+        /// Size of the file once decompressed (in bytes)
+        pub size: isize,
+    }
+
+    /// Kind of metadata removed from an uploaded file
+    pub enum StrippedMetadata {
+        /// EXIF tags such as location or camera details
+        Exif,
+        /// Orientation, discarded without being applied
+        Orientation,
+        /// Embedded colour profile
+        ColorProfile,
     }
 );
 
+impl FileScan {
+    /// Whether the file was found to be clean
+    pub fn is_clean(&self) -> bool {
+        self.signature.is_none()
+    }
+}
+
 impl FileHash {
     /// Create a file from a file hash
     pub fn into_file(
@@ -67,6 +149,7 @@ impl FileHash {
             id,
             tag,
             filename,
+            alt_text: None,
             hash: Some(self.id.clone()),
 
             uploaded_at: Some(Timestamp::now_utc()),
@@ -76,6 +159,9 @@ impl FileHash {
 
             deleted: None,
             reported: None,
+            quarantined: None,
+            voice_note: None,
+            scan: self.scan.clone(),
 
             // TODO: remove this data
             metadata: self.metadata.clone(),
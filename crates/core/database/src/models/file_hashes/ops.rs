@@ -1,6 +1,6 @@
 use guilderia_result::Result;
 
-use crate::FileHash;
+use crate::{FileHash, FileScan};
 
 mod mongodb;
 mod reference;
@@ -16,6 +16,16 @@ pub trait AbstractAttachmentHashes: Sync + Send {
     /// Update an attachment hash nonce value.
     async fn set_attachment_hash_nonce(&self, hash: &str, nonce: &str) -> Result<()>;
 
+    /// Update an attachment hash virus scan result.
+    async fn set_attachment_hash_scan(&self, hash: &str, scan: &FileScan) -> Result<()>;
+
+    /// Fetch attachment hashes last scanned with an older signature database.
+    async fn fetch_attachment_hashes_scanned_before(
+        &self,
+        signatures: u32,
+        limit: i64,
+    ) -> Result<Vec<FileHash>>;
+
     /// Delete attachment hash by id.
     async fn delete_attachment_hash(&self, id: &str) -> Result<()>;
 }
@@ -1,6 +1,11 @@
+use bson::to_document;
+use bson::Document;
+use guilderia_config::report_internal_error;
 use guilderia_result::Result;
+use mongodb::options::FindOptions;
 
 use crate::FileHash;
+use crate::FileScan;
 use crate::MongoDb;
 
 use super::AbstractAttachmentHashes;
@@ -48,6 +53,48 @@ impl AbstractAttachmentHashes for MongoDb {
             .map_err(|_| create_database_error!("update_one", COL))
     }
 
+    /// Update an attachment hash virus scan result.
+    async fn set_attachment_hash_scan(&self, hash: &str, scan: &FileScan) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": hash
+                },
+                doc! {
+                    "$set": {
+                        "scan": report_internal_error!(to_document(scan))?
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Fetch attachment hashes last scanned with an older signature database.
+    async fn fetch_attachment_hashes_scanned_before(
+        &self,
+        signatures: u32,
+        limit: i64,
+    ) -> Result<Vec<FileHash>> {
+        query!(
+            self,
+            find_with_options,
+            COL,
+            doc! {
+                "scan.signatures": {
+                    "$lt": signatures as i64
+                }
+            },
+            FindOptions::builder()
+                .sort(doc! {
+                    "scan.signatures": 1_i32
+                })
+                .limit(limit)
+                .build()
+        )
+    }
+
     /// Delete attachment hash by id.
     async fn delete_attachment_hash(&self, id: &str) -> Result<()> {
         query!(self, delete_one_by_id, COL, id).map(|_| ())
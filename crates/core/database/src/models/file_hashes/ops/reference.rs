@@ -1,6 +1,7 @@
 use guilderia_result::Result;
 
 use crate::FileHash;
+use crate::FileScan;
 use crate::ReferenceDb;
 
 use super::AbstractAttachmentHashes;
@@ -39,6 +40,39 @@ impl AbstractAttachmentHashes for ReferenceDb {
         }
     }
 
+    /// Update an attachment hash virus scan result.
+    async fn set_attachment_hash_scan(&self, hash: &str, scan: &FileScan) -> Result<()> {
+        let mut hashes = self.file_hashes.lock().await;
+        if let Some(file) = hashes.get_mut(hash) {
+            file.scan = Some(scan.clone());
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Fetch attachment hashes last scanned with an older signature database.
+    async fn fetch_attachment_hashes_scanned_before(
+        &self,
+        signatures: u32,
+        limit: i64,
+    ) -> Result<Vec<FileHash>> {
+        let hashes = self.file_hashes.lock().await;
+        let mut hashes: Vec<FileHash> = hashes
+            .values()
+            .filter(|hash| {
+                hash.scan
+                    .as_ref()
+                    .is_some_and(|scan| scan.signatures < signatures)
+            })
+            .cloned()
+            .collect();
+
+        hashes.sort_by_key(|hash| hash.scan.as_ref().map(|scan| scan.signatures));
+        hashes.truncate(limit as usize);
+        Ok(hashes)
+    }
+
     /// Delete attachment hash by id.
     async fn delete_attachment_hash(&self, id: &str) -> Result<()> {
         let mut file_hashes = self.file_hashes.lock().await;
@@ -1,7 +1,17 @@
-use crate::{Database, FileHash, Metadata};
+use crate::{
+    events::client::EventV1, Database, FileHash, FileScan, Metadata, Report, Snapshot,
+    SnapshotContent,
+};
 
-use iso8601_timestamp::Timestamp;
+use guilderia_config::config;
+use guilderia_files::upload_to_s3;
+use guilderia_models::v0::{
+    AttachmentClass, ContentReportReason, ReportStatus, ReportedContent, UserReportReason,
+};
 use guilderia_result::Result;
+use iso8601_timestamp::Timestamp;
+use sha2::{Digest, Sha256};
+use ulid::Ulid;
 
 auto_derived_partial!(
     /// File
@@ -13,6 +23,9 @@ auto_derived_partial!(
         pub tag: String,
         /// Original filename
         pub filename: String,
+        /// Alternative text describing this file
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub alt_text: Option<String>,
         /// Hash of this file
         pub hash: Option<String>, // these are Option<>s to not break file uploads on legacy Autumn
 
@@ -32,6 +45,17 @@ auto_derived_partial!(
         /// Whether this file was reported
         #[serde(skip_serializing_if = "Option::is_none")]
         pub reported: Option<bool>,
+        /// Result of the most recent virus scan
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        pub scan: Option<FileScan>,
+        /// Whether this file is held for review after failing a virus scan
+        ///
+        /// Set to false once a file has been reviewed and released.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub quarantined: Option<bool>,
+        /// Whether this file was recorded as a voice message
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        pub voice_note: Option<bool>,
 
         // !!! DEPRECATED:
         /// Parsed metadata of this file
@@ -70,6 +94,8 @@ auto_derived!(
         LegacyGroupIcon,
         ChannelIcon,
         ServerIcon,
+        ServerBadge,
+        ServerExport,
     }
 
     /// Information about what the file was used for
@@ -82,19 +108,228 @@ auto_derived!(
     }
 );
 
+/// Content types of executables and installers
+static EXECUTABLE_TYPES: &[&str] = &[
+    "application/x-msdownload",
+    "application/x-dosexec",
+    "application/x-executable",
+    "application/x-elf",
+    "application/x-mach-binary",
+    "application/x-sharedlib",
+    "application/x-msi",
+    "application/x-ms-installer",
+    "application/vnd.microsoft.portable-executable",
+    "application/vnd.android.package-archive",
+    "application/x-apple-diskimage",
+    "application/x-sh",
+    "application/x-bat",
+];
+
+/// Content types of archives which may not have been inspected on upload
+static ARCHIVE_TYPES: &[&str] = &[
+    "application/zip",
+    "application/x-tar",
+    "application/gzip",
+    "application/x-bzip2",
+    "application/x-xz",
+    "application/x-7z-compressed",
+    "application/vnd.rar",
+    "application/x-rar-compressed",
+];
+
 impl File {
     /// Get the hash entry for this file
     pub async fn as_hash(&self, db: &Database) -> Result<FileHash> {
         db.fetch_attachment_hash(self.hash.as_ref().unwrap()).await
     }
 
+    /// Upload a file generated by the server and create an unused file for it
+    ///
+    /// Re-uses the stored hash if identical data was uploaded before.
+    pub async fn store(
+        db: &Database,
+        tag: &str,
+        filename: String,
+        content_type: &str,
+        metadata: Metadata,
+        data: &[u8],
+        uploader_id: &str,
+    ) -> Result<File> {
+        let hash = format!("{:02x}", Sha256::digest(data));
+        let file_hash = match db.fetch_attachment_hash(&hash).await {
+            Ok(file_hash) if !file_hash.iv.is_empty() => file_hash,
+            existing => {
+                let mut file_hash = FileHash {
+                    id: hash.clone(),
+                    processed_hash: hash.clone(),
+                    created_at: Timestamp::now_utc(),
+                    bucket_id: config().await.files.s3.default_bucket,
+                    path: hash,
+                    iv: String::new(),
+                    metadata,
+                    content_type: content_type.to_owned(),
+                    size: data.len() as isize,
+                    scan: None,
+                };
+
+                if existing.is_err() {
+                    db.insert_attachment_hash(&file_hash).await?;
+                }
+
+                file_hash.iv = upload_to_s3(&file_hash.bucket_id, &file_hash.id, data).await?;
+                db.set_attachment_hash_nonce(&file_hash.id, &file_hash.iv)
+                    .await?;
+                file_hash
+            }
+        };
+
+        let file = file_hash.into_file(
+            Ulid::new().to_string(),
+            tag.to_owned(),
+            filename,
+            uploader_id.to_owned(),
+        );
+
+        db.insert_attachment(&file).await?;
+        Ok(file)
+    }
+
+    /// Quarantine files with a hash which failed a virus scan and let their uploaders know
+    pub async fn quarantine_hash(db: &Database, hash: &str) -> Result<()> {
+        for mut file in db.quarantine_attachments(hash).await? {
+            file.quarantined = Some(true);
+
+            if let Some(uploader_id) = file.uploader_id.clone() {
+                EventV1::FileQuarantine(file.into())
+                    .private(uploader_id)
+                    .await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Release a quarantined file found to be safe so it is served again
+    pub async fn release(&mut self, db: &Database) -> Result<()> {
+        if !self.quarantined.is_some_and(|v| v) {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        db.set_attachment_quarantined(&self.id, false).await?;
+        self.quarantined = Some(false);
+        Ok(())
+    }
+
+    /// Delete a quarantined file confirmed to be malicious
+    ///
+    /// The message it was attached to, or otherwise its uploader, is reported
+    /// to the moderation team on behalf of the reviewer.
+    pub async fn purge(&self, db: &Database, reviewer: &str) -> Result<Option<Report>> {
+        if !self.quarantined.is_some_and(|v| v) {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        db.mark_attachment_as_deleted(&self.id).await?;
+
+        let message = match &self.used_for {
+            Some(FileUsedFor {
+                object_type: FileUsedForType::Message,
+                id,
+            }) => db.fetch_message(id).await.ok(),
+            _ => None,
+        };
+
+        let (content, snapshot) = if let Some(message) = message {
+            (
+                ReportedContent::Message {
+                    id: message.id.to_string(),
+                    report_reason: ContentReportReason::Malware,
+                },
+                SnapshotContent::generate_from_message(db, message).await?.0,
+            )
+        } else if let Some(uploader_id) = &self.uploader_id {
+            let user = db.fetch_user(uploader_id).await?;
+            (
+                ReportedContent::User {
+                    id: user.id.to_string(),
+                    report_reason: UserReportReason::Malware,
+                    message_id: None,
+                },
+                SnapshotContent::generate_from_user(user)?.0,
+            )
+        } else {
+            return Ok(None);
+        };
+
+        let report = Report {
+            id: Ulid::new().to_string(),
+            author_id: reviewer.to_string(),
+            content,
+            additional_context: format!(
+                "Quarantined file {} ({}) was confirmed to be malicious: {}",
+                self.id,
+                self.filename,
+                self.scan
+                    .as_ref()
+                    .and_then(|scan| scan.signature.as_deref())
+                    .unwrap_or("unknown signature")
+            ),
+            status: ReportStatus::Created {},
+            notes: String::new(),
+        };
+
+        db.insert_snapshot(&Snapshot {
+            id: Ulid::new().to_string(),
+            report_id: report.id.to_string(),
+            content: snapshot,
+        })
+        .await?;
+
+        db.insert_report(&report).await?;
+        EventV1::ReportCreate(report.clone().into()).global().await;
+
+        Ok(Some(report))
+    }
+
+    /// Determine the kind of file this is
+    pub fn class(&self) -> AttachmentClass {
+        match &self.metadata {
+            Metadata::Image { .. } => AttachmentClass::Image,
+            Metadata::Video { .. } => AttachmentClass::Video,
+            Metadata::Audio { .. } => AttachmentClass::Audio,
+            Metadata::Text { .. } => AttachmentClass::Text,
+            Metadata::Archive { .. } => AttachmentClass::Archive,
+            Metadata::File => {
+                if EXECUTABLE_TYPES.contains(&self.content_type.as_str()) {
+                    AttachmentClass::Executable
+                } else if ARCHIVE_TYPES.contains(&self.content_type.as_str()) {
+                    AttachmentClass::Archive
+                } else {
+                    AttachmentClass::Other
+                }
+            }
+        }
+    }
+
     /// Use a file for a message attachment
+    ///
+    /// Fails if the channel only allows certain kinds of attachments and this is not one of them.
     pub async fn use_attachment(
         db: &Database,
         id: &str,
         parent: &str,
         uploader_id: &str,
+        allowed: Option<&[AttachmentClass]>,
     ) -> Result<File> {
+        if let Some(allowed) = allowed {
+            let file = db.fetch_attachment("attachments", id).await?;
+            if !allowed.contains(&file.class()) {
+                return Err(create_error!(AttachmentTypeNotAllowed {
+                    allowed: allowed.iter().map(|class| format!("{class:?}")).collect(),
+                }));
+            }
+        }
+
         db.find_and_use_attachment(
             id,
             "attachments",
@@ -183,6 +418,44 @@ impl File {
         .await
     }
 
+    /// Use a file for a server badge icon
+    pub async fn use_server_badge(
+        db: &Database,
+        id: &str,
+        parent: &str,
+        uploader_id: &str,
+    ) -> Result<File> {
+        db.find_and_use_attachment(
+            id,
+            "icons",
+            FileUsedFor {
+                id: parent.to_owned(),
+                object_type: FileUsedForType::ServerBadge,
+            },
+            uploader_id.to_owned(),
+        )
+        .await
+    }
+
+    /// Use a file for a server data export
+    pub async fn use_server_export(
+        db: &Database,
+        id: &str,
+        parent: &str,
+        uploader_id: &str,
+    ) -> Result<File> {
+        db.find_and_use_attachment(
+            id,
+            "exports",
+            FileUsedFor {
+                id: parent.to_owned(),
+                object_type: FileUsedForType::ServerExport,
+            },
+            uploader_id.to_owned(),
+        )
+        .await
+    }
+
     /// Use a file for a channel icon
     pub async fn use_channel_icon(
         db: &Database,
@@ -240,3 +513,90 @@ impl File {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use guilderia_models::v0::AttachmentClass;
+    use iso8601_timestamp::Timestamp;
+
+    use crate::{File, FileHash, Metadata};
+
+    #[async_std::test]
+    async fn quarantine_skips_reviewed_files() {
+        database_test!(|db| async move {
+            let hash = FileHash {
+                id: "hash".to_string(),
+                processed_hash: "hash".to_string(),
+                created_at: Timestamp::now_utc(),
+                bucket_id: "bucket".to_string(),
+                path: "hash".to_string(),
+                iv: String::new(),
+                metadata: Metadata::File,
+                content_type: "application/octet-stream".to_string(),
+                size: 0,
+                scan: None,
+            };
+
+            for id in ["first", "second"] {
+                db.insert_attachment(&hash.into_file(
+                    id.to_string(),
+                    "attachments".to_string(),
+                    "file.bin".to_string(),
+                    "user".to_string(),
+                ))
+                .await
+                .unwrap();
+            }
+
+            assert_eq!(db.quarantine_attachments("hash").await.unwrap().len(), 2);
+            assert!(db.quarantine_attachments("hash").await.unwrap().is_empty());
+
+            let mut file = db.fetch_attachment_by_id("first").await.unwrap();
+            file.release(&db).await.unwrap();
+            assert!(file.release(&db).await.is_err());
+
+            let quarantined = db.fetch_quarantined_attachments().await.unwrap();
+            assert_eq!(quarantined.len(), 1);
+            assert_eq!(quarantined[0].id, "second");
+        });
+    }
+
+    #[async_std::test]
+    async fn attachments_restricted_by_class() {
+        database_test!(|db| async move {
+            let hash = FileHash {
+                id: "hash".to_string(),
+                processed_hash: "hash".to_string(),
+                created_at: Timestamp::now_utc(),
+                bucket_id: "bucket".to_string(),
+                path: "hash".to_string(),
+                iv: String::new(),
+                metadata: Metadata::File,
+                content_type: "application/x-msdownload".to_string(),
+                size: 0,
+                scan: None,
+            };
+
+            let file = hash.into_file(
+                "file".to_string(),
+                "attachments".to_string(),
+                "setup.exe".to_string(),
+                "user".to_string(),
+            );
+
+            assert_eq!(file.class(), AttachmentClass::Executable);
+            db.insert_attachment(&file).await.unwrap();
+
+            let allowed = [AttachmentClass::Image, AttachmentClass::Text];
+            assert!(
+                File::use_attachment(&db, "file", "message", "user", Some(&allowed))
+                    .await
+                    .is_err()
+            );
+
+            File::use_attachment(&db, "file", "message", "user", None)
+                .await
+                .unwrap();
+        });
+    }
+}
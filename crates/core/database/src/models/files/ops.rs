@@ -1,6 +1,6 @@
 use guilderia_result::Result;
 
-use crate::File;
+use crate::{File, FileScan};
 
 use super::FileUsedFor;
 
@@ -15,6 +15,9 @@ pub trait AbstractAttachments: Sync + Send {
     /// Fetch an attachment by its id.
     async fn fetch_attachment(&self, tag: &str, file_id: &str) -> Result<File>;
 
+    /// Fetch an attachment by its id regardless of tag.
+    async fn fetch_attachment_by_id(&self, file_id: &str) -> Result<File>;
+
     /// Fetch all deleted attachments.
     async fn fetch_deleted_attachments(&self) -> Result<Vec<File>>;
 
@@ -33,6 +36,23 @@ pub trait AbstractAttachments: Sync + Send {
         uploader_id: String,
     ) -> Result<File>;
 
+    /// Set or clear the alternative text of an attachment.
+    async fn set_attachment_alt_text(&self, id: &str, alt_text: Option<&str>) -> Result<()>;
+
+    /// Set the virus scan result on all attachments with a given hash.
+    async fn set_attachments_scan(&self, hash: &str, scan: &FileScan) -> Result<()>;
+
+    /// Quarantine all attachments with a given hash which have not been reviewed before.
+    ///
+    /// Returns the attachments which were newly quarantined.
+    async fn quarantine_attachments(&self, hash: &str) -> Result<Vec<File>>;
+
+    /// Fetch all quarantined attachments awaiting review.
+    async fn fetch_quarantined_attachments(&self) -> Result<Vec<File>>;
+
+    /// Set whether an attachment is quarantined.
+    async fn set_attachment_quarantined(&self, id: &str, quarantined: bool) -> Result<()>;
+
     /// Mark an attachment as having been reported.
     async fn mark_attachment_as_reported(&self, id: &str) -> Result<()>;
 
@@ -4,6 +4,7 @@ use guilderia_config::report_internal_error;
 use guilderia_result::Result;
 
 use crate::File;
+use crate::FileScan;
 use crate::FileUsedFor;
 use crate::MongoDb;
 
@@ -32,6 +33,11 @@ impl AbstractAttachments for MongoDb {
         .ok_or_else(|| create_error!(NotFound))
     }
 
+    /// Fetch an attachment by its id regardless of tag.
+    async fn fetch_attachment_by_id(&self, file_id: &str) -> Result<File> {
+        query!(self, find_one_by_id, COL, file_id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
     /// Fetch all deleted attachments.
     async fn fetch_deleted_attachments(&self) -> Result<Vec<File>> {
         query!(
@@ -117,6 +123,124 @@ impl AbstractAttachments for MongoDb {
         Ok(file)
     }
 
+    /// Set or clear the alternative text of an attachment.
+    async fn set_attachment_alt_text(&self, id: &str, alt_text: Option<&str>) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                match alt_text {
+                    Some(alt_text) => doc! {
+                        "$set": {
+                            "alt_text": alt_text
+                        }
+                    },
+                    None => doc! {
+                        "$unset": {
+                            "alt_text": 1_i32
+                        }
+                    },
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Set the virus scan result on all attachments with a given hash.
+    async fn set_attachments_scan(&self, hash: &str, scan: &FileScan) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_many(
+                doc! {
+                    "hash": hash
+                },
+                doc! {
+                    "$set": {
+                        "scan": report_internal_error!(to_document(scan))?
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_many", COL))
+    }
+
+    /// Quarantine all attachments with a given hash which have not been reviewed before.
+    ///
+    /// Returns the attachments which were newly quarantined.
+    async fn quarantine_attachments(&self, hash: &str) -> Result<Vec<File>> {
+        let files: Vec<File> = query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "hash": hash,
+                "quarantined": {
+                    "$exists": false
+                },
+                "deleted": {
+                    "$ne": true
+                }
+            }
+        )?;
+
+        if files.is_empty() {
+            return Ok(files);
+        }
+
+        self.col::<Document>(COL)
+            .update_many(
+                doc! {
+                    "_id": {
+                        "$in": files.iter().map(|file| &file.id).collect::<Vec<_>>()
+                    }
+                },
+                doc! {
+                    "$set": {
+                        "quarantined": true
+                    }
+                },
+            )
+            .await
+            .map_err(|_| create_database_error!("update_many", COL))?;
+
+        Ok(files)
+    }
+
+    /// Fetch all quarantined attachments awaiting review.
+    async fn fetch_quarantined_attachments(&self) -> Result<Vec<File>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "quarantined": true,
+                "deleted": {
+                    "$ne": true
+                }
+            }
+        )
+    }
+
+    /// Set whether an attachment is quarantined.
+    async fn set_attachment_quarantined(&self, id: &str, quarantined: bool) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$set": {
+                        "quarantined": quarantined
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
     /// Mark an attachment as having been reported.
     async fn mark_attachment_as_reported(&self, id: &str) -> Result<()> {
         self.col::<Document>(COL)
@@ -1,6 +1,7 @@
 use guilderia_result::Result;
 
 use crate::File;
+use crate::FileScan;
 use crate::FileUsedFor;
 use crate::ReferenceDb;
 
@@ -33,6 +34,15 @@ impl AbstractAttachments for ReferenceDb {
         }
     }
 
+    /// Fetch an attachment by its id regardless of tag.
+    async fn fetch_attachment_by_id(&self, file_id: &str) -> Result<File> {
+        let files = self.files.lock().await;
+        files
+            .get(file_id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
     /// Fetch all deleted attachments.
     async fn fetch_deleted_attachments(&self) -> Result<Vec<File>> {
         let files = self.files.lock().await;
@@ -91,6 +101,69 @@ impl AbstractAttachments for ReferenceDb {
         }
     }
 
+    /// Set or clear the alternative text of an attachment.
+    async fn set_attachment_alt_text(&self, id: &str, alt_text: Option<&str>) -> Result<()> {
+        let mut files = self.files.lock().await;
+        if let Some(file) = files.get_mut(id) {
+            file.alt_text = alt_text.map(str::to_string);
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Set the virus scan result on all attachments with a given hash.
+    async fn set_attachments_scan(&self, hash: &str, scan: &FileScan) -> Result<()> {
+        let mut files = self.files.lock().await;
+        for file in files.values_mut() {
+            if file.hash.as_deref() == Some(hash) {
+                file.scan = Some(scan.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Quarantine all attachments with a given hash which have not been reviewed before.
+    ///
+    /// Returns the attachments which were newly quarantined.
+    async fn quarantine_attachments(&self, hash: &str) -> Result<Vec<File>> {
+        let mut files = self.files.lock().await;
+        let mut quarantined = vec![];
+        for file in files.values_mut() {
+            if file.hash.as_deref() == Some(hash)
+                && file.quarantined.is_none()
+                && !file.deleted.is_some_and(|v| v)
+            {
+                file.quarantined = Some(true);
+                quarantined.push(file.clone());
+            }
+        }
+
+        Ok(quarantined)
+    }
+
+    /// Fetch all quarantined attachments awaiting review.
+    async fn fetch_quarantined_attachments(&self) -> Result<Vec<File>> {
+        let files = self.files.lock().await;
+        Ok(files
+            .values()
+            .filter(|file| file.quarantined.is_some_and(|v| v) && !file.deleted.is_some_and(|v| v))
+            .cloned()
+            .collect())
+    }
+
+    /// Set whether an attachment is quarantined.
+    async fn set_attachment_quarantined(&self, id: &str, quarantined: bool) -> Result<()> {
+        let mut files = self.files.lock().await;
+        if let Some(file) = files.get_mut(id) {
+            file.quarantined = Some(quarantined);
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
     /// Mark an attachment as having been reported.
     async fn mark_attachment_as_reported(&self, id: &str) -> Result<()> {
         let mut files = self.files.lock().await;
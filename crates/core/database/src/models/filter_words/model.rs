@@ -0,0 +1,16 @@
+use iso8601_timestamp::Timestamp;
+
+auto_derived!(
+    /// Word or phrase on the instance's base content filter list
+    ///
+    /// Servers can opt in to filtering these on top of their own lists.
+    pub struct FilterWord {
+        /// Word or phrase, as it is matched
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the user who added it
+        pub creator: String,
+        /// Time at which it was added
+        pub created_at: Timestamp,
+    }
+);
@@ -0,0 +1,18 @@
+use guilderia_result::Result;
+
+use crate::FilterWord;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractFilterWords: Sync + Send {
+    /// Insert a word into the base filter list
+    async fn insert_filter_word(&self, word: &FilterWord) -> Result<()>;
+
+    /// Fetch every word on the base filter list
+    async fn fetch_filter_words(&self) -> Result<Vec<FilterWord>>;
+
+    /// Delete a word from the base filter list
+    async fn delete_filter_word(&self, id: &str) -> Result<()>;
+}
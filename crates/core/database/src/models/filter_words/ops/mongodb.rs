@@ -0,0 +1,26 @@
+use guilderia_result::Result;
+
+use crate::FilterWord;
+use crate::MongoDb;
+
+use super::AbstractFilterWords;
+
+static COL: &str = "filter_words";
+
+#[async_trait]
+impl AbstractFilterWords for MongoDb {
+    /// Insert a word into the base filter list
+    async fn insert_filter_word(&self, word: &FilterWord) -> Result<()> {
+        query!(self, insert_one, COL, &word).map(|_| ())
+    }
+
+    /// Fetch every word on the base filter list
+    async fn fetch_filter_words(&self) -> Result<Vec<FilterWord>> {
+        query!(self, find, COL, doc! {})
+    }
+
+    /// Delete a word from the base filter list
+    async fn delete_filter_word(&self, id: &str) -> Result<()> {
+        query!(self, delete_one_by_id, COL, id).map(|_| ())
+    }
+}
@@ -0,0 +1,36 @@
+use guilderia_result::Result;
+
+use crate::FilterWord;
+use crate::ReferenceDb;
+
+use super::AbstractFilterWords;
+
+#[async_trait]
+impl AbstractFilterWords for ReferenceDb {
+    /// Insert a word into the base filter list
+    async fn insert_filter_word(&self, word: &FilterWord) -> Result<()> {
+        let mut filter_words = self.filter_words.lock().await;
+        if filter_words.contains_key(&word.id) {
+            Err(create_database_error!("insert", "filter_words"))
+        } else {
+            filter_words.insert(word.id.to_string(), word.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch every word on the base filter list
+    async fn fetch_filter_words(&self) -> Result<Vec<FilterWord>> {
+        let filter_words = self.filter_words.lock().await;
+        Ok(filter_words.values().cloned().collect())
+    }
+
+    /// Delete a word from the base filter list
+    async fn delete_filter_word(&self, id: &str) -> Result<()> {
+        let mut filter_words = self.filter_words.lock().await;
+        if filter_words.remove(id).is_some() {
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_models::v0::{ReportStatus, ReportedContent, UserReportReason};
+use guilderia_result::Result;
+use ulid::Ulid;
+
+use crate::{events::client::EventV1, Database, Report, Snapshot, SnapshotContent};
+
+/// Id reports filed by the platform itself are authored by
+const SYSTEM_USER_ID: &str = "00000000000000000000000000";
+
+auto_derived!(
+    /// Upload which was blocked for matching an entry on the hash list
+    ///
+    /// The upload itself is kept in the evidence bucket, encrypted like any other file.
+    pub struct HashMatch {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the user who uploaded the file
+        pub user: String,
+        /// Tag the file was uploaded to
+        pub tag: String,
+        /// Name of the uploaded file
+        pub filename: String,
+        /// Detected mime type of the file
+        pub content_type: String,
+        /// Size of the file in bytes
+        pub size: isize,
+        /// SHA-256 hash of the file
+        pub hash: String,
+        /// Perceptual hash of the image
+        pub perceptual_hash: String,
+        /// Entry on the hash list which was matched
+        pub matched: String,
+        /// Label given to the entry on the hash list
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub label: Option<String>,
+        /// Number of bits by which the hashes differ
+        pub distance: u32,
+
+        /// Bucket the evidence is stored in
+        pub bucket_id: String,
+        /// Path to the evidence in the bucket
+        pub path: String,
+        /// Nonce the evidence was encrypted with
+        pub iv: String,
+
+        /// Jurisdiction the upload was made from
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub jurisdiction: Option<String>,
+        /// Address the upload was made from
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub ip: Option<String>,
+        /// Id of the report filed with the moderation team
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub report_id: Option<String>,
+        /// Reporting hooks which acknowledged the match
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub reported_to: Vec<String>,
+
+        /// Time at which the upload was blocked
+        pub created_at: Timestamp,
+    }
+);
+
+impl HashMatch {
+    /// Report the uploader to the moderation team
+    ///
+    /// The report is filed on behalf of the platform and every moderator is alerted.
+    pub async fn report(&mut self, db: &Database) -> Result<Report> {
+        let user = db.fetch_user(&self.user).await?;
+        let report = Report {
+            id: Ulid::new().to_string(),
+            author_id: SYSTEM_USER_ID.to_string(),
+            content: ReportedContent::User {
+                id: user.id.to_string(),
+                report_reason: UserReportReason::ChildSafety,
+                message_id: None,
+            },
+            additional_context: format!(
+                "Upload {} ({}, {}) matched {} at a distance of {} bits, evidence held as hash match {}",
+                self.filename,
+                self.content_type,
+                self.hash,
+                self.label.as_deref().unwrap_or(&self.matched),
+                self.distance,
+                self.id
+            ),
+            status: ReportStatus::Created {},
+            notes: String::new(),
+        };
+
+        db.insert_snapshot(&Snapshot {
+            id: Ulid::new().to_string(),
+            report_id: report.id.to_string(),
+            content: SnapshotContent::generate_from_user(user)?.0,
+        })
+        .await?;
+
+        db.insert_report(&report).await?;
+        db.set_hash_match_report(&self.id, &report.id).await?;
+        self.report_id = Some(report.id.to_string());
+
+        EventV1::ReportCreate(report.clone().into()).global().await;
+        Ok(report)
+    }
+}
@@ -0,0 +1,24 @@
+use guilderia_result::Result;
+
+use crate::HashMatch;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractHashMatches: Sync + Send {
+    /// Insert a new hash match
+    async fn insert_hash_match(&self, hash_match: &HashMatch) -> Result<()>;
+
+    /// Fetch a hash match by its id
+    async fn fetch_hash_match(&self, id: &str) -> Result<HashMatch>;
+
+    /// Fetch all hash matches for uploads by a user
+    async fn fetch_hash_matches_by_user(&self, user: &str) -> Result<Vec<HashMatch>>;
+
+    /// Record the report filed for a hash match
+    async fn set_hash_match_report(&self, id: &str, report_id: &str) -> Result<()>;
+
+    /// Record that a reporting hook acknowledged a hash match
+    async fn add_hash_match_recipient(&self, id: &str, recipient: &str) -> Result<()>;
+}
@@ -0,0 +1,70 @@
+use bson::Document;
+use guilderia_result::Result;
+
+use crate::HashMatch;
+use crate::MongoDb;
+
+use super::AbstractHashMatches;
+
+static COL: &str = "hash_matches";
+
+#[async_trait]
+impl AbstractHashMatches for MongoDb {
+    /// Insert a new hash match
+    async fn insert_hash_match(&self, hash_match: &HashMatch) -> Result<()> {
+        query!(self, insert_one, COL, &hash_match).map(|_| ())
+    }
+
+    /// Fetch a hash match by its id
+    async fn fetch_hash_match(&self, id: &str) -> Result<HashMatch> {
+        query!(self, find_one_by_id, COL, id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all hash matches for uploads by a user
+    async fn fetch_hash_matches_by_user(&self, user: &str) -> Result<Vec<HashMatch>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "user": user
+            }
+        )
+    }
+
+    /// Record the report filed for a hash match
+    async fn set_hash_match_report(&self, id: &str, report_id: &str) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$set": {
+                        "report_id": report_id
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Record that a reporting hook acknowledged a hash match
+    async fn add_hash_match_recipient(&self, id: &str, recipient: &str) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$addToSet": {
+                        "reported_to": recipient
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+}
@@ -0,0 +1,64 @@
+use guilderia_result::Result;
+
+use crate::HashMatch;
+use crate::ReferenceDb;
+
+use super::AbstractHashMatches;
+
+#[async_trait]
+impl AbstractHashMatches for ReferenceDb {
+    /// Insert a new hash match
+    async fn insert_hash_match(&self, hash_match: &HashMatch) -> Result<()> {
+        let mut hash_matches = self.hash_matches.lock().await;
+        if hash_matches.contains_key(&hash_match.id) {
+            Err(create_database_error!("insert", "hash_match"))
+        } else {
+            hash_matches.insert(hash_match.id.to_string(), hash_match.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a hash match by its id
+    async fn fetch_hash_match(&self, id: &str) -> Result<HashMatch> {
+        let hash_matches = self.hash_matches.lock().await;
+        hash_matches
+            .get(id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all hash matches for uploads by a user
+    async fn fetch_hash_matches_by_user(&self, user: &str) -> Result<Vec<HashMatch>> {
+        let hash_matches = self.hash_matches.lock().await;
+        Ok(hash_matches
+            .values()
+            .filter(|hash_match| hash_match.user == user)
+            .cloned()
+            .collect())
+    }
+
+    /// Record the report filed for a hash match
+    async fn set_hash_match_report(&self, id: &str, report_id: &str) -> Result<()> {
+        let mut hash_matches = self.hash_matches.lock().await;
+        if let Some(hash_match) = hash_matches.get_mut(id) {
+            hash_match.report_id = Some(report_id.to_string());
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Record that a reporting hook acknowledged a hash match
+    async fn add_hash_match_recipient(&self, id: &str, recipient: &str) -> Result<()> {
+        let mut hash_matches = self.hash_matches.lock().await;
+        if let Some(hash_match) = hash_matches.get_mut(id) {
+            if !hash_match.reported_to.iter().any(|x| x == recipient) {
+                hash_match.reported_to.push(recipient.to_string());
+            }
+
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+}
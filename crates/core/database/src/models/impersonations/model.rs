@@ -0,0 +1,270 @@
+use authifier::{
+    config::{EmailVerificationConfig, Template},
+    Authifier,
+};
+use guilderia_config::{config, ImpersonationNotice};
+use guilderia_result::Result;
+use iso8601_timestamp::Timestamp;
+use serde_json::json;
+use ulid::Ulid;
+
+use crate::{matches_route, Database, User};
+
+/// Number of requests kept in an impersonation's log
+pub const MAX_LOGGED_REQUESTS: i32 = 500;
+
+/// Routes an admin may use while impersonating, as `(method, path)`
+///
+/// Only routes which fetch data without side effects and without exposing
+/// the user's secrets, such as recovery codes or bot keys, are allowed.
+static IMPERSONATION_ROUTES: &[(&str, &str)] = &[
+    ("GET", "/users/dms"),
+    ("GET", "/users/*"),
+    ("GET", "/users/*/profile"),
+    ("GET", "/users/*/flags"),
+    ("GET", "/users/*/mutual"),
+    ("GET", "/channels/*"),
+    ("GET", "/channels/*/members"),
+    ("GET", "/channels/*/messages"),
+    ("GET", "/channels/*/messages/*"),
+    ("GET", "/channels/*/messages/*/thread"),
+    ("GET", "/channels/*/threads"),
+    ("GET", "/servers/*"),
+    ("GET", "/servers/*/members"),
+    ("GET", "/servers/*/members/*"),
+    ("GET", "/servers/*/roles/*"),
+    ("GET", "/servers/*/emojis"),
+    ("GET", "/custom/emoji/*"),
+    ("GET", "/invites/*"),
+];
+
+auto_derived!(
+    /// Read-only session an admin uses to see the platform as another user
+    ///
+    /// Impersonations are kept after they end so every request made with
+    /// one can be traced back to the admin who made it.
+    pub struct Impersonation {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the admin impersonating the user
+        pub admin: String,
+        /// Id of the user being impersonated
+        pub user: String,
+        /// Why the user is being impersonated
+        pub reason: String,
+        /// Secret token
+        pub token: String,
+        /// Whether the user was told about this impersonation
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub user_notified: bool,
+        /// Time at which this impersonation started
+        pub created_at: Timestamp,
+        /// Time at which this impersonation ends
+        pub expires_at: Timestamp,
+        /// Most recent requests made while impersonating
+        #[serde(default)]
+        pub requests: Vec<ImpersonatedRequest>,
+    }
+
+    /// Request made while impersonating a user
+    pub struct ImpersonatedRequest {
+        /// HTTP method
+        pub method: String,
+        /// Requested path
+        pub path: String,
+        /// Time at which the request was made
+        pub at: Timestamp,
+    }
+);
+
+/// Work out whether the user should be notified under the instance policy
+fn should_notify(policy: ImpersonationNotice, requested: Option<bool>) -> bool {
+    match policy {
+        ImpersonationNotice::Always => true,
+        ImpersonationNotice::Optional => requested.unwrap_or(true),
+        ImpersonationNotice::Never => false,
+    }
+}
+
+impl Impersonation {
+    /// Start impersonating a user
+    ///
+    /// Only privileged users may impersonate and privileged users cannot be impersonated.
+    pub async fn create(
+        db: &Database,
+        admin: &User,
+        user: &User,
+        reason: String,
+        duration_minutes: Option<u64>,
+        notify_user: Option<bool>,
+    ) -> Result<Impersonation> {
+        let settings = config().await.features.impersonation;
+        if !settings.enabled {
+            return Err(create_error!(FeatureDisabled {
+                feature: "impersonation".to_string()
+            }));
+        }
+
+        if !admin.privileged {
+            return Err(create_error!(NotPrivileged));
+        }
+
+        if admin.id == user.id || user.privileged {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        let minutes = duration_minutes
+            .unwrap_or(settings.max_duration_minutes)
+            .clamp(1, settings.max_duration_minutes.max(1));
+
+        let now = Timestamp::now_utc();
+        let impersonation = Impersonation {
+            id: Ulid::new().to_string(),
+            admin: admin.id.to_string(),
+            user: user.id.to_string(),
+            reason,
+            token: nanoid::nanoid!(64),
+            user_notified: should_notify(settings.notify_user, notify_user),
+            created_at: now,
+            expires_at: now
+                .checked_add(iso8601_timestamp::Duration::minutes(minutes as i64))
+                .expect("failed to compute impersonation expiry"),
+            requests: vec![],
+        };
+
+        db.insert_impersonation(&impersonation).await?;
+        info!(
+            "{} started impersonating {} until {:?} ({}): {}",
+            impersonation.admin,
+            impersonation.user,
+            impersonation.expires_at,
+            impersonation.id,
+            impersonation.reason
+        );
+
+        Ok(impersonation)
+    }
+
+    /// Whether this impersonation can still be used
+    pub fn is_active(&self) -> bool {
+        Timestamp::now_utc() < self.expires_at
+    }
+
+    /// Check whether a request may be made while impersonating
+    ///
+    /// Impersonation is read-only, nothing can be changed on behalf of the user.
+    pub fn permits(&self, method: &str, path: &str) -> bool {
+        self.is_active() && matches_route(IMPERSONATION_ROUTES, method, path)
+    }
+
+    /// Record a request made while impersonating
+    pub async fn log_request(&self, db: &Database, method: &str, path: &str) -> Result<()> {
+        info!(
+            "[impersonation {}] {} as {}: {method} {path}",
+            self.id, self.admin, self.user
+        );
+
+        db.log_impersonated_request(
+            &self.id,
+            &ImpersonatedRequest {
+                method: method.to_string(),
+                path: path.to_string(),
+                at: Timestamp::now_utc(),
+            },
+        )
+        .await
+    }
+
+    /// End this impersonation early
+    pub async fn end(&mut self, db: &Database) -> Result<()> {
+        if !self.is_active() {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        let now = Timestamp::now_utc();
+        db.end_impersonation(&self.id, now).await?;
+        self.expires_at = now;
+        Ok(())
+    }
+
+    /// Tell the user they are being impersonated, if the policy asks for it
+    pub async fn send_notice_email(&self, authifier: &Authifier) -> Result<()> {
+        let (true, EmailVerificationConfig::Enabled { smtp, .. }) =
+            (self.user_notified, &authifier.config.email_verification)
+        else {
+            return Ok(());
+        };
+
+        let account = authifier
+            .database
+            .find_account(&self.user)
+            .await
+            .map_err(|_| create_error!(InternalError))?;
+
+        smtp.send_email(
+            account.email.clone(),
+            &Template {
+                title: "An administrator is viewing your account.".to_string(),
+                html: None,
+                text: include_str!("../../../templates/impersonation.txt").to_owned(),
+                url: Default::default(),
+            },
+            json!({
+                "email": account.email,
+                "reason": self.reason,
+                "expires_at": self.expires_at,
+            }),
+        )
+        .map_err(|_| create_error!(InternalError))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use guilderia_config::ImpersonationNotice;
+    use iso8601_timestamp::Timestamp;
+
+    use super::should_notify;
+    use crate::Impersonation;
+
+    #[test]
+    fn notification_policy() {
+        assert!(should_notify(ImpersonationNotice::Always, Some(false)));
+        assert!(should_notify(ImpersonationNotice::Optional, None));
+        assert!(!should_notify(ImpersonationNotice::Optional, Some(false)));
+        assert!(!should_notify(ImpersonationNotice::Never, Some(true)));
+    }
+
+    #[test]
+    fn impersonation_is_read_only() {
+        let mut impersonation = Impersonation {
+            id: "impersonation".to_string(),
+            admin: "admin".to_string(),
+            user: "user".to_string(),
+            reason: "Support ticket".to_string(),
+            token: "token".to_string(),
+            user_notified: true,
+            created_at: Timestamp::now_utc(),
+            expires_at: Timestamp::now_utc()
+                .checked_add(iso8601_timestamp::Duration::minutes(5))
+                .unwrap(),
+            requests: vec![],
+        };
+
+        assert!(impersonation.permits("GET", "/users/@me"));
+        assert!(impersonation.permits("GET", "/0.8/channels/id/messages"));
+        assert!(impersonation.permits("GET", "/servers/id/members/id"));
+        assert!(!impersonation.permits("POST", "/channels/id/messages"));
+        assert!(!impersonation.permits("DELETE", "/users/@me"));
+
+        // Fetching these has side effects or exposes secrets
+        assert!(!impersonation.permits("GET", "/users/id/dm"));
+        assert!(!impersonation.permits("GET", "/bots/id/keys"));
+        assert!(!impersonation.permits("GET", "/auth/account/recovery/codes"));
+        assert!(!impersonation.permits("GET", "/channels/id/webhooks"));
+
+        impersonation.expires_at = Timestamp::now_utc();
+        assert!(!impersonation.permits("GET", "/users/@me"));
+    }
+}
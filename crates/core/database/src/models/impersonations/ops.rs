@@ -0,0 +1,29 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+
+use crate::{ImpersonatedRequest, Impersonation};
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractImpersonations: Sync + Send {
+    /// Insert new impersonation into database
+    async fn insert_impersonation(&self, impersonation: &Impersonation) -> Result<()>;
+
+    /// Fetch an impersonation by its id
+    async fn fetch_impersonation(&self, id: &str) -> Result<Impersonation>;
+
+    /// Fetch an impersonation by its token
+    async fn fetch_impersonation_by_token(&self, token: &str) -> Result<Impersonation>;
+
+    /// Fetch the most recent impersonations, newest first
+    async fn fetch_impersonations(&self, limit: i64) -> Result<Vec<Impersonation>>;
+
+    /// Append a request to an impersonation's log, dropping the oldest past the limit
+    async fn log_impersonated_request(&self, id: &str, request: &ImpersonatedRequest)
+        -> Result<()>;
+
+    /// End an impersonation at the given time
+    async fn end_impersonation(&self, id: &str, expires_at: Timestamp) -> Result<()>;
+}
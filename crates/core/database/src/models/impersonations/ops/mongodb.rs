@@ -0,0 +1,100 @@
+use bson::{to_bson, Document};
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+use mongodb::options::FindOptions;
+
+use crate::MongoDb;
+use crate::{ImpersonatedRequest, Impersonation, MAX_LOGGED_REQUESTS};
+
+use super::AbstractImpersonations;
+
+static COL: &str = "impersonations";
+
+#[async_trait]
+impl AbstractImpersonations for MongoDb {
+    /// Insert new impersonation into database
+    async fn insert_impersonation(&self, impersonation: &Impersonation) -> Result<()> {
+        query!(self, insert_one, COL, &impersonation).map(|_| ())
+    }
+
+    /// Fetch an impersonation by its id
+    async fn fetch_impersonation(&self, id: &str) -> Result<Impersonation> {
+        query!(self, find_one_by_id, COL, id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch an impersonation by its token
+    async fn fetch_impersonation_by_token(&self, token: &str) -> Result<Impersonation> {
+        query!(
+            self,
+            find_one,
+            COL,
+            doc! {
+                "token": token
+            }
+        )?
+        .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch the most recent impersonations, newest first
+    async fn fetch_impersonations(&self, limit: i64) -> Result<Vec<Impersonation>> {
+        query!(
+            self,
+            find_with_options,
+            COL,
+            doc! {},
+            FindOptions::builder()
+                .sort(doc! {
+                    "_id": -1_i32
+                })
+                .limit(limit)
+                .build()
+        )
+    }
+
+    /// Append a request to an impersonation's log, dropping the oldest past the limit
+    async fn log_impersonated_request(
+        &self,
+        id: &str,
+        request: &ImpersonatedRequest,
+    ) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$push": {
+                        "requests": {
+                            "$each": [
+                                to_bson(request)
+                                    .map_err(|_| create_database_error!("to_bson", "request"))?
+                            ],
+                            "$slice": -MAX_LOGGED_REQUESTS
+                        }
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// End an impersonation at the given time
+    async fn end_impersonation(&self, id: &str, expires_at: Timestamp) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$set": {
+                        "expires_at": to_bson(&expires_at)
+                            .map_err(|_| create_database_error!("to_bson", "expires_at"))?
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+}
@@ -0,0 +1,80 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+
+use crate::ReferenceDb;
+use crate::{ImpersonatedRequest, Impersonation, MAX_LOGGED_REQUESTS};
+
+use super::AbstractImpersonations;
+
+#[async_trait]
+impl AbstractImpersonations for ReferenceDb {
+    /// Insert new impersonation into database
+    async fn insert_impersonation(&self, impersonation: &Impersonation) -> Result<()> {
+        let mut impersonations = self.impersonations.lock().await;
+        if impersonations.contains_key(&impersonation.id) {
+            Err(create_database_error!("insert", "impersonation"))
+        } else {
+            impersonations.insert(impersonation.id.to_string(), impersonation.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch an impersonation by its id
+    async fn fetch_impersonation(&self, id: &str) -> Result<Impersonation> {
+        let impersonations = self.impersonations.lock().await;
+        impersonations
+            .get(id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch an impersonation by its token
+    async fn fetch_impersonation_by_token(&self, token: &str) -> Result<Impersonation> {
+        let impersonations = self.impersonations.lock().await;
+        impersonations
+            .values()
+            .find(|impersonation| impersonation.token == token)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch the most recent impersonations, newest first
+    async fn fetch_impersonations(&self, limit: i64) -> Result<Vec<Impersonation>> {
+        let impersonations = self.impersonations.lock().await;
+        let mut impersonations: Vec<Impersonation> = impersonations.values().cloned().collect();
+        impersonations.sort_by(|a, b| b.id.cmp(&a.id));
+        impersonations.truncate(limit as usize);
+        Ok(impersonations)
+    }
+
+    /// Append a request to an impersonation's log, dropping the oldest past the limit
+    async fn log_impersonated_request(
+        &self,
+        id: &str,
+        request: &ImpersonatedRequest,
+    ) -> Result<()> {
+        let mut impersonations = self.impersonations.lock().await;
+        if let Some(impersonation) = impersonations.get_mut(id) {
+            impersonation.requests.push(request.clone());
+            let excess = impersonation
+                .requests
+                .len()
+                .saturating_sub(MAX_LOGGED_REQUESTS as usize);
+            impersonation.requests.drain(..excess);
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// End an impersonation at the given time
+    async fn end_impersonation(&self, id: &str, expires_at: Timestamp) -> Result<()> {
+        let mut impersonations = self.impersonations.lock().await;
+        if let Some(impersonation) = impersonations.get_mut(id) {
+            impersonation.expires_at = expires_at;
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+}
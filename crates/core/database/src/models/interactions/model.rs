@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use guilderia_models::v0;
+use guilderia_result::Result;
+use ulid::Ulid;
+
+use crate::events::client::EventV1;
+use crate::util::idempotency::IdempotencyKey;
+use crate::{tasks, CommandContext, Database, Message, AMQP};
+
+/// How long a bot may keep responding to an interaction
+pub const INTERACTION_TOKEN_LIFETIME: Duration = Duration::from_secs(15 * 60);
+
+auto_derived_partial!(
+    /// Interaction between a user and a bot
+    pub struct Interaction {
+        /// Interaction Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Short-lived token the bot responds with
+        pub token: String,
+
+        /// Id of the bot being interacted with
+        pub bot: String,
+        /// Id of the user who started the interaction
+        pub user: String,
+        /// Id of the channel the interaction took place in
+        pub channel: String,
+        /// Context the command was invoked in
+        pub context: CommandContext,
+
+        /// Name of the command invoked
+        pub command: String,
+        /// Options passed to the command
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub options: Option<String>,
+
+        /// Whether the bot has deferred its response
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub deferred: bool,
+        /// Id of the message sent as the original response
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub response: Option<String>,
+    },
+    "PartialInteraction"
+);
+
+#[allow(clippy::disallowed_methods)]
+impl Interaction {
+    /// Start a new interaction and dispatch it to the bot
+    pub async fn create(
+        db: &Database,
+        bot: String,
+        user: String,
+        channel: String,
+        context: CommandContext,
+        command: String,
+        options: Option<String>,
+    ) -> Result<Interaction> {
+        let interaction = Interaction {
+            id: Ulid::new().to_string(),
+            token: nanoid::nanoid!(64),
+            bot,
+            user,
+            channel,
+            context,
+            command,
+            options,
+            deferred: false,
+            response: None,
+        };
+
+        db.insert_interaction(&interaction).await?;
+
+        let event = EventV1::InteractionCreate {
+            token: interaction.token.clone(),
+            data: interaction.clone().into(),
+        };
+
+        // Bots with an interactions URL receive interactions over HTTP instead,
+        // unless the node can't sign them, in which case they use the events server
+        let delivered = match db.fetch_bot(&interaction.bot).await {
+            Ok(bot) if !bot.interactions_url.is_empty() => {
+                tasks::interactions::deliver(bot.interactions_url, &event).await
+            }
+            _ => false,
+        };
+
+        if !delivered {
+            event.private(interaction.bot.clone()).await;
+        }
+
+        Ok(interaction)
+    }
+
+    /// Check the given token is valid for this interaction and has not yet expired
+    pub fn assert_token(&self, token: &str) -> Result<()> {
+        if self.token != token {
+            return Err(create_error!(NotAuthenticated));
+        }
+
+        let expired = Ulid::from_string(&self.id)
+            .ok()
+            .and_then(|id| id.datetime().elapsed().ok())
+            .is_none_or(|elapsed| elapsed > INTERACTION_TOKEN_LIFETIME);
+
+        if expired {
+            Err(create_error!(InteractionExpired))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Whether the bot has acknowledged this interaction in any way
+    pub fn is_acknowledged(&self) -> bool {
+        self.deferred || self.response.is_some()
+    }
+
+    /// Send a message as the bot in the channel this interaction took place in
+    ///
+    /// The bot does not need permission to send messages in the channel.
+    pub async fn send_message(
+        &self,
+        db: &Database,
+        amqp: Option<&AMQP>,
+        data: v0::DataMessageSend,
+        idempotency: IdempotencyKey,
+    ) -> Result<Message> {
+        let channel = db.fetch_channel(&self.channel).await?;
+        let bot = db.fetch_user(&self.bot).await?;
+        let author: v0::User = bot.clone().into(db, None).await;
+
+        Message::create_from_api(
+            db,
+            amqp,
+            channel,
+            data,
+            v0::MessageAuthor::User(&author),
+            None,
+            None,
+            bot.limits().await,
+            idempotency,
+            true,
+            None,
+            true,
+            None,
+        )
+        .await
+    }
+
+    /// Update interaction data
+    pub async fn update(&mut self, db: &Database, partial: PartialInteraction) -> Result<()> {
+        self.apply_options(partial.clone());
+        db.update_interaction(&self.id, &partial).await
+    }
+}
@@ -0,0 +1,24 @@
+use guilderia_result::Result;
+
+use crate::{Interaction, PartialInteraction};
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractInteractions: Sync + Send {
+    /// Insert new interaction into the database
+    async fn insert_interaction(&self, interaction: &Interaction) -> Result<()>;
+
+    /// Fetch interaction by id
+    async fn fetch_interaction(&self, id: &str) -> Result<Interaction>;
+
+    /// Fetch all interactions which took place in any of the given channels
+    async fn fetch_interactions_in_channels(
+        &self,
+        channel_ids: &[String],
+    ) -> Result<Vec<Interaction>>;
+
+    /// Update interaction with new information
+    async fn update_interaction(&self, id: &str, partial: &PartialInteraction) -> Result<()>;
+}
@@ -0,0 +1,43 @@
+use guilderia_result::Result;
+
+use crate::MongoDb;
+use crate::{Interaction, PartialInteraction};
+
+use super::AbstractInteractions;
+
+static COL: &str = "interactions";
+
+#[async_trait]
+impl AbstractInteractions for MongoDb {
+    /// Insert new interaction into the database
+    async fn insert_interaction(&self, interaction: &Interaction) -> Result<()> {
+        query!(self, insert_one, COL, &interaction).map(|_| ())
+    }
+
+    /// Fetch interaction by id
+    async fn fetch_interaction(&self, id: &str) -> Result<Interaction> {
+        query!(self, find_one_by_id, COL, id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all interactions which took place in any of the given channels
+    async fn fetch_interactions_in_channels(
+        &self,
+        channel_ids: &[String],
+    ) -> Result<Vec<Interaction>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "channel": {
+                    "$in": channel_ids
+                }
+            }
+        )
+    }
+
+    /// Update interaction with new information
+    async fn update_interaction(&self, id: &str, partial: &PartialInteraction) -> Result<()> {
+        query!(self, update_one_by_id, COL, id, partial, vec![], None).map(|_| ())
+    }
+}
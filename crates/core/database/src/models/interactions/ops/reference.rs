@@ -0,0 +1,53 @@
+use guilderia_result::Result;
+
+use crate::ReferenceDb;
+use crate::{Interaction, PartialInteraction};
+
+use super::AbstractInteractions;
+
+#[async_trait]
+impl AbstractInteractions for ReferenceDb {
+    /// Insert new interaction into the database
+    async fn insert_interaction(&self, interaction: &Interaction) -> Result<()> {
+        let mut interactions = self.interactions.lock().await;
+        if interactions.contains_key(&interaction.id) {
+            Err(create_database_error!("insert", "interaction"))
+        } else {
+            interactions.insert(interaction.id.to_string(), interaction.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch interaction by id
+    async fn fetch_interaction(&self, id: &str) -> Result<Interaction> {
+        let interactions = self.interactions.lock().await;
+        interactions
+            .get(id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all interactions which took place in any of the given channels
+    async fn fetch_interactions_in_channels(
+        &self,
+        channel_ids: &[String],
+    ) -> Result<Vec<Interaction>> {
+        let interactions = self.interactions.lock().await;
+        Ok(interactions
+            .values()
+            .filter(|interaction| channel_ids.contains(&interaction.channel))
+            .cloned()
+            .collect())
+    }
+
+    /// Update interaction with new information
+    async fn update_interaction(&self, id: &str, partial: &PartialInteraction) -> Result<()> {
+        let mut interactions = self.interactions.lock().await;
+        if let Some(interaction) = interactions.get_mut(id) {
+            interaction.apply_options(partial.clone());
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+}
@@ -1,25 +1,32 @@
-use std::{collections::HashSet, hash::RandomState};
+use std::{borrow::Cow, collections::HashSet, hash::RandomState, time::Duration};
 
 use indexmap::{IndexMap, IndexSet};
 use iso8601_timestamp::Timestamp;
 use guilderia_config::{config, FeaturesLimits};
 use guilderia_models::v0::{
-    self, BulkMessageResponse, DataMessageSend, Embed, MessageAuthor, MessageFlags, MessageSort,
-    MessageWebhook, PushNotification, ReplyIntent, SendableEmbed, Text,
+    self, BulkMessageResponse, DataMessageSend, Embed, MessageAuthor, MessageEffect,
+    MessageExtension, MessageFlags, MessageSort, MessageWebhook, PushNotification, ReplyIntent,
+    SendableEmbed, Text,
 };
-use guilderia_permissions::{calculate_channel_permissions, ChannelPermission, PermissionValue};
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
 use guilderia_result::{ErrorType, Result};
 use ulid::Ulid;
 use validator::Validate;
 
 use crate::{
     events::client::EventV1,
+    search,
     tasks::{self, ack::AckEvent},
     util::{
-        bulk_permissions::BulkDatabasePermissionQuery, idempotency::IdempotencyKey,
-        permissions::DatabasePermissionQuery,
+        bulk_permissions::BulkDatabasePermissionQuery,
+        content_filter, highlights,
+        idempotency::IdempotencyKey,
+        mention_confirmation, new_member_restrictions,
+        permissions::{DatabasePermissionQuery, ResolvedPermissions},
+        slowmode,
     },
-    Channel, Database, Emoji, File, User, AMQP,
+    BotDmPreferences, Channel, Database, Emoji, File, MentionLimitAction, NotificationMode,
+    RatelimitEvent, RatelimitEventType, Server, StarboardEntry, User, AMQP,
 };
 
 auto_derived_partial!(
@@ -65,15 +72,39 @@ auto_derived_partial!(
         /// Hashmap of emoji IDs to array of user IDs
         #[serde(skip_serializing_if = "IndexMap::is_empty", default)]
         pub reactions: IndexMap<String, IndexSet<String>>,
+        /// Hashmap of emoji IDs to array of user IDs who sent a burst reaction
+        #[serde(skip_serializing_if = "IndexMap::is_empty", default)]
+        pub burst_reactions: IndexMap<String, IndexSet<String>>,
         /// Information about how this message should be interacted with
         #[serde(skip_serializing_if = "Interactions::is_default", default)]
         pub interactions: Interactions,
         /// Name and / or avatar overrides for this message
         #[serde(skip_serializing_if = "Option::is_none")]
         pub masquerade: Option<Masquerade>,
+        /// Verified origin of a bridged message
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub provenance: Option<MessageProvenance>,
+        /// Id of the thread this message was sent in
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub thread: Option<String>,
         /// Whether or not the message in pinned
         #[serde(skip_serializing_if = "crate::if_option_false")]
         pub pinned: Option<bool>,
+        /// Whether clients should read this message aloud
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub tts: bool,
+        /// Effect played when this message is received
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub effect: Option<MessageEffect>,
+        /// Extensions to the message format used by this message
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub extensions: Option<Vec<MessageExtension>>,
+        /// Content rendered as plain text for clients without support for its extensions
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub fallback: Option<String>,
+        /// Time at which this message will be deleted, per the channel's retention period
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub expires_at: Option<Timestamp>,
 
         /// Bitfield of message flags
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -93,9 +124,19 @@ auto_derived!(
         #[serde(rename = "user_remove")]
         UserRemove { id: String, by: String },
         #[serde(rename = "user_joined")]
-        UserJoined { id: String },
+        UserJoined {
+            id: String,
+            /// Text rendered from one of the server's templates
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            content: Option<String>,
+        },
         #[serde(rename = "user_left")]
-        UserLeft { id: String },
+        UserLeft {
+            id: String,
+            /// Text rendered from one of the server's templates
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            content: Option<String>,
+        },
         #[serde(rename = "user_kicked")]
         UserKicked { id: String },
         #[serde(rename = "user_banned")]
@@ -112,6 +153,27 @@ auto_derived!(
         MessagePinned { id: String, by: String },
         #[serde(rename = "message_unpinned")]
         MessageUnpinned { id: String, by: String },
+        #[serde(rename = "message_highlighted")]
+        MessageHighlighted {
+            id: String,
+            channel: String,
+            author: String,
+            count: u32,
+        },
+        #[serde(rename = "call_started")]
+        CallStarted { channel: String, by: String },
+        #[serde(rename = "call_ended")]
+        CallEnded { channel: String, duration: u64 },
+    }
+
+    /// Origin of a message bridged from an external network
+    pub struct MessageProvenance {
+        /// URI of the message on the external network
+        pub origin: String,
+        /// Id of the bridge key used to sign this message
+        pub key: String,
+        /// Detached signature
+        pub signature: String,
     }
 
     /// Name and / or avatar override information
@@ -177,12 +239,19 @@ auto_derived!(
     pub struct MessageFilter {
         /// Parent channel ID
         pub channel: Option<String>,
+        /// Any of these parent channel IDs
+        pub channels: Option<Vec<String>>,
         /// Message author ID
         pub author: Option<String>,
         /// Search query
         pub query: Option<String>,
         /// Search for pinned
         pub pinned: Option<bool>,
+        /// Only messages sent in this thread
+        pub thread: Option<String>,
+        /// Whether to leave out messages sent in threads
+        #[serde(default)]
+        pub exclude_threads: bool,
     }
 
     /// Message Query
@@ -202,6 +271,9 @@ auto_derived!(
     /// Optional fields on message
     pub enum FieldsMessage {
         Pinned,
+        Provenance,
+        Extensions,
+        Fallback,
     }
 );
 
@@ -247,10 +319,18 @@ impl Default for Message {
             role_mentions: None,
             replies: None,
             reactions: Default::default(),
+            burst_reactions: Default::default(),
             interactions: Default::default(),
             masquerade: None,
+            provenance: None,
+            thread: None,
             flags: None,
             pinned: None,
+            tts: false,
+            effect: None,
+            extensions: None,
+            fallback: None,
+            expires_at: None,
         }
     }
 }
@@ -263,14 +343,16 @@ impl Message {
         db: &Database,
         amqp: Option<&AMQP>,
         channel: Channel,
-        data: DataMessageSend,
+        mut data: DataMessageSend,
         author: MessageAuthor<'_>,
         user: Option<v0::User>,
         member: Option<v0::Member>,
         limits: FeaturesLimits,
         mut idempotency: IdempotencyKey,
         generate_embeds: bool,
+        locale: Option<String>,
         allow_mentions: bool,
+        permissions: Option<&ResolvedPermissions>,
     ) -> Result<Message> {
         let config = config().await;
 
@@ -293,14 +375,43 @@ impl Message {
             return Err(create_error!(EmptyMessage));
         }
 
+        // Users may refuse direct messages from bots and webhooks
+        if let Channel::DirectMessage { recipients, .. } = &channel {
+            let sender = match &author {
+                MessageAuthor::User(user) if user.bot.is_some() => Some(&user.id),
+                MessageAuthor::Webhook(webhook) => Some(&webhook.id),
+                _ => None,
+            };
+
+            if let Some(sender) = sender {
+                for recipient in recipients.iter().filter(|id| *id != sender) {
+                    BotDmPreferences::check(db, recipient, sender).await?;
+                }
+            }
+        }
+
+        // Servers may hold new members to stricter limits, moderators are exempt
+        if let (MessageAuthor::User(user), Some(permissions)) = (&author, permissions) {
+            if let (Some(server), Some(member)) = (&permissions.server, &permissions.member) {
+                if let Some(restrictions) = &server.new_member_restrictions {
+                    if user.bot.is_none()
+                        && !permissions.has_channel_permission(ChannelPermission::ManageMessages)
+                    {
+                        new_member_restrictions::check(restrictions, member, &data)?;
+                    }
+                }
+            }
+        }
+
         let allow_mass_mentions = allow_mentions && config.features.mass_mentions_enabled;
 
         let mut mentions_everyone = false;
         let mut mentions_online = false;
         let mut suppress_notifications = false;
+        let mut suppress_embeds = false;
 
         if let Some(raw_flags) = &data.flags {
-            if raw_flags > &7 {
+            if raw_flags > &31 {
                 // quick path to failure: bigger than all the bits combined
                 return Err(create_error!(InvalidProperty));
             }
@@ -308,6 +419,7 @@ impl Message {
             // First step of mass mention resolution
             let flags = MessageFlagsValue(*raw_flags);
             suppress_notifications = flags.has(MessageFlags::SuppressNotifications);
+            suppress_embeds = flags.has(MessageFlags::SuppressEmbeds);
             mentions_everyone = allow_mentions && flags.has(MessageFlags::MentionsEveryone);
             mentions_online = allow_mentions && flags.has(MessageFlags::MentionsOnline);
 
@@ -323,6 +435,49 @@ impl Message {
             }
         }
 
+        // Bridged messages must be signed by one of the bot's keys
+        let provenance = if let Some(provenance) = data.provenance {
+            let MessageAuthor::User(user) = &author else {
+                return Err(create_error!(InvalidProvenance));
+            };
+
+            if user.bot.is_none() {
+                return Err(create_error!(IsNotBot));
+            }
+
+            let provenance: MessageProvenance = provenance.into();
+            db.fetch_bot(&user.id)
+                .await?
+                .verify_provenance(&provenance, data.content.as_deref().unwrap_or_default())?;
+
+            Some(provenance)
+        } else {
+            None
+        };
+
+        // Run content through the server's content filter, moderators are exempt
+        if let (MessageAuthor::User(user), Some(permissions)) = (&author, permissions) {
+            if let Some(server) = &permissions.server {
+                if !permissions.has_channel_permission(ChannelPermission::ManageMessages) {
+                    if let Some(content) = data.content.take() {
+                        data.content = Some(
+                            content_filter::check(db, server, channel.id(), &user.id, content)
+                                .await?,
+                        );
+                    }
+                }
+            }
+        }
+
+        // Hold members to the channel's slowmode, moderators are exempt
+        if let (MessageAuthor::User(user), Some(permissions), Some(interval)) =
+            (&author, permissions, channel.rate_limit_per_user())
+        {
+            if !permissions.has_channel_permission(ChannelPermission::ManageMessages) {
+                slowmode::check(channel.id(), &user.id, interval).await?;
+            }
+        }
+
         let server_id = match channel {
             Channel::TextChannel { ref server, .. } | Channel::VoiceChannel { ref server, .. } => {
                 Some(server.clone())
@@ -345,6 +500,22 @@ impl Message {
             }
         }
 
+        // Messages sent in a thread must stay in the thread's channel
+        let mut thread = if let Some(id) = &data.thread {
+            let thread = db.fetch_thread(id).await?;
+            if thread.channel != channel.id() {
+                return Err(create_error!(NotFound));
+            }
+
+            if thread.archived {
+                return Err(create_error!(ThreadArchived));
+            }
+
+            Some(thread)
+        } else {
+            None
+        };
+
         let (author_id, webhook) = match &author {
             MessageAuthor::User(user) => (user.id.clone(), None),
             MessageAuthor::Webhook(webhook) => (webhook.id.clone(), Some((*webhook).clone())),
@@ -357,6 +528,8 @@ impl Message {
             id: message_id.clone(),
             channel: channel.id().to_string(),
             masquerade: data.masquerade.map(|masquerade| masquerade.into()),
+            provenance,
+            thread: thread.as_ref().map(|thread| thread.id.clone()),
             interactions: data
                 .interactions
                 .map(|interactions| interactions.into())
@@ -364,9 +537,29 @@ impl Message {
             author: author_id,
             webhook: webhook.map(|w| w.into()),
             flags: data.flags,
+            tts: data.tts.unwrap_or_default(),
+            effect: data.effect,
+            expires_at: channel.message_retention_seconds().and_then(|seconds| {
+                Timestamp::now_utc()
+                    .checked_add(iso8601_timestamp::Duration::seconds(seconds as i64))
+            }),
             ..Default::default()
         };
 
+        // Servers may disable text-to-speech entirely
+        if message.tts {
+            if let Some(server_id) = &server_id {
+                let disabled = match permissions.and_then(|p| p.server.as_ref()) {
+                    Some(server) => server.disable_tts,
+                    None => db.fetch_server(server_id).await?.disable_tts,
+                };
+
+                if disabled {
+                    message.tts = false;
+                }
+            }
+        }
+
         // Parse mentions in message.
 
         let mut message_mentions = if let Some(raw_content) = &data.content {
@@ -383,13 +576,47 @@ impl Message {
             mut role_mentions,
             mut mentions_everyone,
             mut mentions_online,
+            ..
         } = message_mentions;
 
+        // Servers may limit how many users a message mentions, moderators are exempt
+        if !user_mentions.is_empty() {
+            if let Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } =
+                &channel
+            {
+                let server_data = match permissions.and_then(|p| p.server.as_ref()) {
+                    Some(server) => Cow::Borrowed(server),
+                    None => Cow::Owned(db.fetch_server(server).await?),
+                };
+
+                if let Some(limit) = &server_data.mention_limit {
+                    if user_mentions.len() > limit.max as usize
+                        && !permissions.is_some_and(|p| {
+                            p.has_channel_permission(ChannelPermission::ManageMessages)
+                        })
+                    {
+                        match limit.action {
+                            MentionLimitAction::Block => {
+                                return Err(create_error!(TooManyMentions {
+                                    max: limit.max as usize
+                                }))
+                            }
+                            MentionLimitAction::Strip => user_mentions.clear(),
+                        }
+                    }
+                }
+            }
+        }
+
         if allow_mass_mentions && server_id.is_some() && !role_mentions.is_empty() {
-            let server_data = db
-                .fetch_server(server_id.unwrap().as_str())
-                .await
-                .expect("Failed to fetch server");
+            let server_data = match permissions.and_then(|p| p.server.as_ref()) {
+                Some(server) => Cow::Borrowed(server),
+                None => Cow::Owned(
+                    db.fetch_server(server_id.unwrap().as_str())
+                        .await
+                        .expect("Failed to fetch server"),
+                ),
+            };
 
             role_mentions.retain(|role_id| server_data.roles.contains_key(role_id));
         }
@@ -414,10 +641,13 @@ impl Message {
                 if user.is_err() {
                     return Err(create_error!(InvalidProperty));
                 }
-                let owned_user: User = user.unwrap().to_owned().into();
-
-                let mut query = DatabasePermissionQuery::new(db, &owned_user).channel(&channel);
-                let perms = calculate_channel_permissions(&mut query).await;
+                let perms = if let Some(permissions) = permissions {
+                    permissions.permissions.clone()
+                } else {
+                    let owned_user: User = user.unwrap().to_owned().into();
+                    let mut query = DatabasePermissionQuery::new(db, &owned_user).channel(&channel);
+                    calculate_channel_permissions(&mut query).await
+                };
 
                 if (mentions_everyone || mentions_online)
                     && !perms.has_channel_permission(ChannelPermission::MentionEveryone)
@@ -434,6 +664,25 @@ impl Message {
                         permission: ChannelPermission::MentionRoles.to_string()
                     }));
                 }
+
+                // Large servers require @everyone to be confirmed before delivery
+                let threshold = config.features.mass_mention_confirmation_threshold;
+                if mentions_everyone && threshold > 0 {
+                    if let Channel::TextChannel { server, .. }
+                    | Channel::VoiceChannel { server, .. } = &channel
+                    {
+                        let recipients = db.fetch_member_count(server).await?;
+                        if recipients >= threshold {
+                            mention_confirmation::confirm(
+                                &user.unwrap().id,
+                                channel.id(),
+                                data.mention_confirmation.as_deref(),
+                                recipients,
+                            )
+                            .await?;
+                        }
+                    }
+                }
             }
         }
 
@@ -542,7 +791,8 @@ impl Message {
         flag_value
             .set(MessageFlags::SuppressNotifications, suppress_notifications)
             .set(MessageFlags::MentionsEveryone, mentions_everyone)
-            .set(MessageFlags::MentionsOnline, mentions_online);
+            .set(MessageFlags::MentionsOnline, mentions_online)
+            .set(MessageFlags::SuppressEmbeds, suppress_embeds);
 
         message.flags = Some(flag_value.0);
 
@@ -568,9 +818,25 @@ impl Message {
             }));
         }
 
+        let mut alt_text = data.alt_text.unwrap_or_default();
         for attachment_id in data.attachments.as_deref().unwrap_or_default() {
-            attachments
-                .push(File::use_attachment(db, attachment_id, &message_id, author.id()).await?);
+            let mut file = File::use_attachment(
+                db,
+                attachment_id,
+                &message_id,
+                author.id(),
+                channel.allowed_attachments(),
+            )
+            .await?;
+            if let Some(text) = alt_text
+                .remove(attachment_id)
+                .filter(|text| !text.is_empty())
+            {
+                db.set_attachment_alt_text(&file.id, Some(&text)).await?;
+                file.alt_text = Some(text);
+            }
+
+            attachments.push(file);
         }
 
         if !attachments.is_empty() {
@@ -584,15 +850,25 @@ impl Message {
 
         // Set content
         message.content = data.content;
+        message.detect_extensions();
 
         // Pass-through nonce value for clients
         message.nonce = Some(idempotency.into_key());
 
         // Send the message
         message
-            .send(db, amqp, author, user, member, &channel, generate_embeds)
+            .send(db, amqp, author, user, member, &channel, false)
             .await?;
 
+        if let Some(thread) = &mut thread {
+            thread.record_message(db, &message).await?;
+        }
+
+        // Generate embeds in the author's language
+        if generate_embeds {
+            message.queue_embeds(locale).await;
+        }
+
         Ok(message)
     }
 
@@ -609,6 +885,7 @@ impl Message {
         mentions_elsewhere: bool,
     ) -> Result<()> {
         db.insert_message(self).await?;
+        tasks::search_index::index(self).await;
 
         // Fan out events
         EventV1::Message(self.clone().into_model(user, member))
@@ -616,7 +893,13 @@ impl Message {
             .await;
 
         // Update last_message_id
-        tasks::last_message_id::queue(self.channel.to_string(), self.id.to_string(), is_dm).await;
+        tasks::last_message_id::queue(
+            self.channel.to_string(),
+            self.id.to_string(),
+            self.author.to_string(),
+            is_dm,
+        )
+        .await;
 
         // Add mentions for affected users
         if !mentions_elsewhere {
@@ -630,6 +913,7 @@ impl Message {
                             mentions.clone(),
                             self.has_suppressed_notifications(),
                         )],
+                        notify_all: false,
                     },
                 )
                 .await;
@@ -638,19 +922,29 @@ impl Message {
 
         // Generate embeds
         if generate_embeds {
-            if let Some(content) = &self.content {
-                tasks::process_embeds::queue(
-                    self.channel.to_string(),
-                    self.id.to_string(),
-                    content.clone(),
-                )
-                .await;
-            }
+            self.queue_embeds(None).await;
         }
 
         Ok(())
     }
 
+    /// Queue embed generation for links in this message
+    pub async fn queue_embeds(&self, locale: Option<String>) {
+        if self.embeds_suppressed() {
+            return;
+        }
+
+        if let Some(content) = &self.content {
+            tasks::process_embeds::queue(
+                self.channel.to_string(),
+                self.id.to_string(),
+                content.clone(),
+                locale,
+            )
+            .await;
+        }
+    }
+
     /// Send a message
     #[allow(clippy::too_many_arguments)]
     pub async fn send(
@@ -673,8 +967,29 @@ impl Message {
         )
         .await?;
 
-        if !self.has_suppressed_notifications()
-            && (self.mentions.is_some() || self.contains_mass_push_mention())
+        if self.has_suppressed_notifications() {
+            return Ok(());
+        }
+
+        // Fetched once for both highlights and the server's default notifications
+        let server = match channel {
+            Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } => {
+                db.fetch_server(server).await.ok()
+            }
+            _ => None,
+        };
+
+        // Keyword highlights notify users as if they were mentioned
+        let highlighted = match &server {
+            Some(server) => highlights::find_highlighted_users(db, server, channel, self).await,
+            None => vec![],
+        };
+
+        let notify_all = Message::notifies_all_members(server.as_ref());
+        if self.mentions.is_some()
+            || !highlighted.is_empty()
+            || self.contains_mass_push_mention()
+            || notify_all
         {
             // send Push notifications
             tasks::ack::queue_message(
@@ -693,13 +1008,18 @@ impl Message {
                         match channel {
                             Channel::DirectMessage { recipients, .. }
                             | Channel::Group { recipients, .. } => recipients.clone(),
-                            Channel::TextChannel { .. } => {
-                                self.mentions.clone().unwrap_or_default()
-                            }
+                            Channel::TextChannel { .. } => self
+                                .mentions
+                                .clone()
+                                .unwrap_or_default()
+                                .into_iter()
+                                .chain(highlighted)
+                                .collect(),
                             _ => vec![],
                         },
                         false, // branch already dictates this
                     )],
+                    notify_all,
                 },
             )
             .await;
@@ -717,7 +1037,17 @@ impl Message {
         })?;
 
         let media = if let Some(id) = embed.media {
-            Some(File::use_attachment(db, &id, &self.id, &self.author).await?)
+            let channel = db.fetch_channel(&self.channel).await?;
+            Some(
+                File::use_attachment(
+                    db,
+                    &id,
+                    &self.id,
+                    &self.author,
+                    channel.allowed_attachments(),
+                )
+                .await?,
+            )
         } else {
             None
         };
@@ -729,9 +1059,18 @@ impl Message {
             description: embed.description,
             media: media.map(|m| m.into()),
             colour: embed.colour,
+            author: embed.author,
+            footer: embed.footer,
+            timestamp: embed.timestamp,
+            fields: embed.fields.unwrap_or_default(),
         }))
     }
 
+    /// Whether the server a message was sent in notifies members of every message by default
+    pub fn notifies_all_members(server: Option<&Server>) -> bool {
+        server.is_some_and(|server| server.default_notifications == NotificationMode::AllMessages)
+    }
+
     /// Whether this message has suppressed notifications
     pub fn has_suppressed_notifications(&self) -> bool {
         if let Some(flags) = self.flags {
@@ -769,6 +1108,10 @@ impl Message {
         db.update_message(&self.id, &partial, remove.clone())
             .await?;
 
+        if partial.content.is_some() {
+            tasks::search_index::index(self).await;
+        }
+
         EventV1::MessageUpdate {
             id: self.id.clone(),
             channel: self.channel.clone(),
@@ -789,8 +1132,7 @@ impl Message {
         include_users: Option<bool>,
         server_id: Option<String>,
     ) -> Result<BulkMessageResponse> {
-        let messages: Vec<v0::Message> = db
-            .fetch_messages(query)
+        let messages: Vec<v0::Message> = search::fetch_messages(db, query)
             .await?
             .into_iter()
             .map(|msg| msg.into_model(None, None))
@@ -830,6 +1172,13 @@ impl Message {
                             v0::SystemMessage::MessageUnpinned { by, .. } => {
                                 users.push(by.clone());
                             }
+                            v0::SystemMessage::MessageHighlighted { author, .. } => {
+                                users.push(author.clone());
+                            }
+                            v0::SystemMessage::CallStarted { by, .. } => {
+                                users.push(by.clone());
+                            }
+                            v0::SystemMessage::CallEnded { .. } => {}
                         }
                     }
                     users
@@ -843,11 +1192,20 @@ impl Message {
                 messages,
                 users,
                 members: if let Some(server_id) = server_id {
+                    let members = db.fetch_members(&server_id, &user_ids).await?;
+                    let server = if members.iter().any(|member| member.show_server_tag) {
+                        Some(db.fetch_server(&server_id).await?)
+                    } else {
+                        None
+                    };
+
                     Some(
-                        db.fetch_members(&server_id, &user_ids)
-                            .await?
+                        members
                             .into_iter()
-                            .map(Into::into)
+                            .map(|member| match &server {
+                                Some(server) => member.into_model_with_server(server),
+                                None => member.into(),
+                            })
                             .collect(),
                     )
                 } else {
@@ -859,6 +1217,11 @@ impl Message {
         }
     }
 
+    /// Whether embeds should not be generated for this message
+    pub fn embeds_suppressed(&self) -> bool {
+        MessageFlagsValue(self.flags.unwrap_or_default()).has(MessageFlags::SuppressEmbeds)
+    }
+
     /// Append content to message
     pub async fn append(
         db: &Database,
@@ -866,6 +1229,11 @@ impl Message {
         channel: String,
         append: AppendMessage,
     ) -> Result<()> {
+        // Embeds may have been suppressed since they were queued
+        if append.embeds.is_some() && db.fetch_message(&id).await?.embeds_suppressed() {
+            return Ok(());
+        }
+
         db.append_message(&id, &append).await?;
 
         EventV1::MessageAppend {
@@ -886,10 +1254,17 @@ impl Message {
         embed: v0::SendableEmbed,
     ) -> Result<()> {
         let media: Option<v0::File> = if let Some(id) = embed.media {
+            let channel = db.fetch_channel(&self.channel).await?;
             Some(
-                File::use_attachment(db, &id, &self.id, &self.author)
-                    .await?
-                    .into(),
+                File::use_attachment(
+                    db,
+                    &id,
+                    &self.id,
+                    &self.author,
+                    channel.allowed_attachments(),
+                )
+                .await?
+                .into(),
             )
         } else {
             None
@@ -902,6 +1277,10 @@ impl Message {
             description: embed.description,
             media,
             colour: embed.colour,
+            author: embed.author,
+            footer: embed.footer,
+            timestamp: embed.timestamp,
+            fields: embed.fields.unwrap_or_default(),
         });
 
         if let Some(embeds) = &mut self.embeds {
@@ -914,7 +1293,15 @@ impl Message {
     }
 
     /// Add a reaction to a message
-    pub async fn add_reaction(&self, db: &Database, user: &User, emoji: &str) -> Result<()> {
+    ///
+    /// Burst reactions are limited to a number per user per day.
+    pub async fn add_reaction(
+        &self,
+        db: &Database,
+        user: &User,
+        emoji: &str,
+        burst: bool,
+    ) -> Result<()> {
         // Check how many reactions are already on the message
         let config = config().await;
         if self.reactions.len() >= config.features.limits.global.message_reactions
@@ -933,18 +1320,133 @@ impl Message {
             return Err(create_error!(InvalidOperation));
         }
 
+        // Count burst reactions against the daily limit, unless already sent
+        let burst = burst
+            && !self
+                .burst_reactions
+                .get(emoji)
+                .is_some_and(|users| users.contains(&user.id));
+
+        if burst {
+            let max = user.limits().await.burst_reactions;
+            if db
+                .has_ratelimited(
+                    &user.id,
+                    RatelimitEventType::BurstReaction,
+                    Duration::from_secs(60 * 60 * 24),
+                    max,
+                )
+                .await?
+            {
+                return Err(create_error!(TooManyBurstReactions { max }));
+            }
+
+            RatelimitEvent::create(db, user.id.to_string(), RatelimitEventType::BurstReaction)
+                .await?;
+        }
+
         // Send reaction event
         EventV1::MessageReact {
             id: self.id.to_string(),
             channel_id: self.channel.to_string(),
             user_id: user.id.to_string(),
             emoji_id: emoji.to_string(),
+            burst,
         }
         .p(self.channel.to_string())
         .await;
 
         // Add emoji
-        db.add_reaction(&self.id, emoji, &user.id).await
+        db.add_reaction(&self.id, emoji, &user.id, burst).await?;
+
+        self.queue_highlight_update(db, emoji);
+        Ok(())
+    }
+
+    /// Update this message's starboard highlight in the background
+    fn queue_highlight_update(&self, db: &Database, emoji: &str) {
+        let db = db.clone();
+        let message = self.clone();
+        let emoji = emoji.to_string();
+        async_std::task::spawn(async move {
+            if let Err(err) = message.update_highlight(&db, &emoji).await {
+                warn!("Failed to update highlight for {}: {err:?}", message.id);
+            }
+        });
+    }
+
+    /// Create, update or remove this message's highlight on the server starboard
+    ///
+    /// Reactions from the author are not counted, and messages in
+    /// the starboard channel are never highlighted to prevent loops.
+    pub async fn update_highlight(&self, db: &Database, emoji: &str) -> Result<()> {
+        let server_id = match db.fetch_channel(&self.channel).await? {
+            Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } => server,
+            _ => return Ok(()),
+        };
+
+        let Some(starboard) = db.fetch_server(&server_id).await?.starboard else {
+            return Ok(());
+        };
+
+        if starboard.emoji != emoji || starboard.channel == self.channel || self.system.is_some() {
+            return Ok(());
+        }
+
+        // Reactions may have changed since this message was fetched
+        let message = db.fetch_message(&self.id).await?;
+        let count = message
+            .reactions
+            .get(&starboard.emoji)
+            .map(|users| users.iter().filter(|id| *id != &message.author).count())
+            .unwrap_or_default() as u32;
+
+        let system = SystemMessage::MessageHighlighted {
+            id: message.id.clone(),
+            channel: message.channel.clone(),
+            author: message.author.clone(),
+            count,
+        };
+
+        match db.fetch_starboard_entry(&message.id).await {
+            Ok(entry) if count < starboard.threshold => {
+                db.delete_starboard_entry(&entry.id).await?;
+                if let Ok(highlight) = db.fetch_message(&entry.highlight).await {
+                    highlight.delete(db).await?;
+                }
+            }
+            Ok(entry) => {
+                if let Ok(mut highlight) = db.fetch_message(&entry.highlight).await {
+                    highlight
+                        .update(
+                            db,
+                            PartialMessage {
+                                system: Some(system),
+                                ..Default::default()
+                            },
+                            vec![],
+                        )
+                        .await?;
+                }
+            }
+            Err(_) if count >= starboard.threshold => {
+                let mut highlight = system.into_message(starboard.channel);
+
+                // Claiming the entry first stops concurrent reactions posting twice
+                db.insert_starboard_entry(&StarboardEntry {
+                    id: message.id.clone(),
+                    highlight: highlight.id.clone(),
+                })
+                .await?;
+
+                highlight
+                    .send_without_notifications(db, None, None, false, false, false)
+                    .await?;
+            }
+            Err(_) => {}
+        }
+
+        Ok(())
     }
 
     /// Validate the sum of content of a message is under threshold
@@ -962,12 +1464,20 @@ impl Message {
             if let Some(desc) = &embed.description {
                 running_total += desc.len();
             }
+
+            for field in embed.fields.as_deref().unwrap_or_default() {
+                running_total += field.name.len() + field.value.len();
+            }
+
+            if let Some(footer) = &embed.footer {
+                running_total += footer.text.len();
+            }
         }
 
         if running_total <= max_length {
             Ok(())
         } else {
-            Err(create_error!(PayloadTooLarge))
+            Err(create_error!(PayloadTooLarge { max: max_length }))
         }
     }
 
@@ -983,6 +1493,7 @@ impl Message {
         }
 
         db.delete_message(&self.id).await?;
+        tasks::search_index::remove(vec![self.id.clone()]).await;
 
         EventV1::MessageDelete {
             id: self.id,
@@ -1004,6 +1515,8 @@ impl Message {
             .collect::<Vec<String>>();
 
         db.delete_messages(channel, &valid_ids).await?;
+        db.invalidate_unread_counts(channel).await?;
+        tasks::search_index::remove(valid_ids.clone()).await;
         EventV1::BulkMessageDelete {
             channel: channel.to_string(),
             ids: valid_ids,
@@ -1013,6 +1526,102 @@ impl Message {
         Ok(())
     }
 
+    /// Delete messages which have passed their expiry, along with their attachments
+    pub async fn delete_expired(
+        db: &Database,
+        channel: &str,
+        messages: Vec<Message>,
+    ) -> Result<()> {
+        let file_ids: Vec<String> = messages
+            .iter()
+            .flat_map(|message| message.attachments.iter().flatten())
+            .map(|file| file.id.to_string())
+            .collect();
+
+        if !file_ids.is_empty() {
+            db.mark_attachments_as_deleted(&file_ids).await?;
+        }
+
+        Message::bulk_delete(
+            db,
+            channel,
+            messages.into_iter().map(|message| message.id).collect(),
+        )
+        .await
+    }
+
+    /// Move messages to another channel, keeping their ids and authorship
+    ///
+    /// A note is left in the destination channel recording where they came from.
+    /// Returns the messages as they now appear in the destination channel.
+    pub async fn bulk_move(
+        db: &Database,
+        source: &str,
+        destination: &str,
+        ids: &[String],
+        by: &str,
+    ) -> Result<Vec<Message>> {
+        let mut originals: Vec<Message> = db
+            .fetch_messages_by_id(ids)
+            .await?
+            .into_iter()
+            .filter(|msg| msg.channel == source && msg.system.is_none())
+            .collect();
+
+        if originals.is_empty() {
+            return Err(create_error!(NotFound));
+        }
+
+        originals.sort_by(|a, b| a.id.cmp(&b.id));
+        let ids: Vec<String> = originals.iter().map(|msg| msg.id.clone()).collect();
+        let moved: Vec<Message> = originals
+            .iter()
+            .cloned()
+            .map(|msg| Message {
+                channel: destination.to_string(),
+                pinned: None,
+                ..msg
+            })
+            .collect();
+
+        db.delete_messages(source, &ids).await?;
+        if let Err(err) = db.insert_messages(&moved).await {
+            // Put the messages back where they were
+            db.insert_messages(&originals).await.ok();
+            return Err(err);
+        }
+
+        db.invalidate_unread_counts(source).await?;
+        db.invalidate_unread_counts(destination).await?;
+        tasks::search_index::index_many(&moved).await;
+
+        EventV1::BulkMessageDelete {
+            channel: source.to_string(),
+            ids,
+        }
+        .p(source.to_string())
+        .await;
+
+        for message in &moved {
+            EventV1::Message(message.clone().into_model(None, None))
+                .p(destination.to_string())
+                .await;
+        }
+
+        SystemMessage::Text {
+            content: format!(
+                "<@{by}> moved {} message(s) here from <#{source}>",
+                moved.len()
+            ),
+        }
+        .into_message(destination.to_string())
+        .send_without_notifications(db, None, None, false, false, false)
+        .await
+        .ok();
+
+        Ok(moved)
+    }
+
     /// Remove a reaction from a message
     pub async fn remove_reaction(&self, db: &Database, user: &str, emoji: &str) -> Result<()> {
         // Check if it actually exists
@@ -1026,6 +1635,12 @@ impl Message {
             return Err(create_error!(NotFound));
         };
 
+        // Whether this is the last burst reaction with this emoji
+        let burst_empty = self
+            .burst_reactions
+            .get(emoji)
+            .is_some_and(|users| users.len() == 1 && users.contains(user));
+
         // Send reaction event
         EventV1::MessageUnreact {
             id: self.id.to_string(),
@@ -1038,11 +1653,18 @@ impl Message {
 
         if empty {
             // If empty, remove the reaction entirely
-            db.clear_reaction(&self.id, emoji).await
+            db.clear_reaction(&self.id, emoji).await?;
         } else {
             // Otherwise only remove that one reaction
-            db.remove_reaction(&self.id, emoji, user).await
+            db.remove_reaction(&self.id, emoji, user).await?;
+
+            if burst_empty {
+                db.clear_burst_reaction(&self.id, emoji).await?;
+            }
         }
+
+        self.queue_highlight_update(db, emoji);
+        Ok(())
     }
 
     /// Remove a reaction from a message
@@ -1057,12 +1679,18 @@ impl Message {
         .await;
 
         // Write to database
-        db.clear_reaction(&self.id, emoji).await
+        db.clear_reaction(&self.id, emoji).await?;
+
+        self.queue_highlight_update(db, emoji);
+        Ok(())
     }
 
     pub fn remove_field(&mut self, field: &FieldsMessage) {
         match field {
             FieldsMessage::Pinned => self.pinned = None,
+            FieldsMessage::Provenance => self.provenance = None,
+            FieldsMessage::Extensions => self.extensions = None,
+            FieldsMessage::Fallback => self.fallback = None,
         }
     }
 }
@@ -1082,12 +1710,14 @@ impl SystemMessage {
 
 impl Interactions {
     /// Validate interactions info is correct
-    pub async fn validate(&self, db: &Database, permissions: &PermissionValue) -> Result<()> {
+    pub async fn validate(&self, db: &Database, permissions: &ResolvedPermissions) -> Result<()> {
         let config = config().await;
 
-        if let Some(reactions) = &self.reactions {
+        if self.reactions.is_some() || self.restrict_reactions {
             permissions.throw_if_lacking_channel_permission(ChannelPermission::React)?;
+        }
 
+        if let Some(reactions) = &self.reactions {
             if reactions.len() > config.features.limits.global.message_reactions {
                 return Err(create_error!(InvalidOperation));
             }
@@ -1120,3 +1750,110 @@ impl Interactions {
         !self.restrict_reactions && self.reactions.is_none()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use iso8601_timestamp::{Duration, Timestamp};
+    use ulid::Ulid;
+
+    use crate::{Message, MessageFilter, MessageQuery, MessageTimePeriod};
+
+    #[async_std::test]
+    async fn expires_unpinned_messages() {
+        database_test!(|db| async move {
+            let past = Timestamp::now_utc()
+                .checked_sub(Duration::seconds(60))
+                .unwrap();
+            let future = Timestamp::now_utc()
+                .checked_add(Duration::seconds(3600))
+                .unwrap();
+
+            for (expires_at, pinned) in [
+                (Some(past), false),
+                (Some(past), true),
+                (Some(future), false),
+                (None, false),
+            ] {
+                db.insert_message(&Message {
+                    id: Ulid::new().to_string(),
+                    channel: "channel".to_string(),
+                    expires_at,
+                    pinned: pinned.then_some(true),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            }
+
+            let expired = db.fetch_expired_messages(100).await.unwrap();
+            assert_eq!(expired.len(), 1);
+
+            Message::delete_expired(&db, "channel", expired)
+                .await
+                .unwrap();
+            assert!(db.fetch_expired_messages(100).await.unwrap().is_empty());
+        });
+    }
+
+    #[async_std::test]
+    async fn reports_each_insert() {
+        database_test!(|db| async move {
+            let existing = Message {
+                id: Ulid::new().to_string(),
+                channel: "channel".to_string(),
+                ..Default::default()
+            };
+
+            db.insert_message(&existing).await.unwrap();
+
+            let fresh = Message {
+                id: Ulid::new().to_string(),
+                channel: "channel".to_string(),
+                ..Default::default()
+            };
+
+            let results = db.insert_messages_each(&[existing, fresh.clone()]).await;
+            assert!(results[0].is_err());
+            assert!(results[1].is_ok());
+            assert!(db.fetch_message(&fresh.id).await.is_ok());
+        });
+    }
+
+    #[async_std::test]
+    async fn counts_messages_beyond_limit() {
+        database_test!(|db| async move {
+            let a = Ulid::new().to_string();
+            let b = Ulid::new().to_string();
+            for channel in [&a, &a, &a, &b] {
+                db.insert_message(&Message {
+                    id: Ulid::new().to_string(),
+                    channel: channel.clone(),
+                    content: Some("hello".to_string()),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            }
+
+            let counts = db
+                .count_messages_by_channel(MessageQuery {
+                    filter: MessageFilter {
+                        channels: Some(vec![a.clone(), b.clone()]),
+                        query: Some("hello".to_string()),
+                        ..Default::default()
+                    },
+                    time_period: MessageTimePeriod::Absolute {
+                        before: None,
+                        after: None,
+                        sort: None,
+                    },
+                    limit: Some(1),
+                })
+                .await
+                .unwrap();
+
+            assert_eq!(counts.get(&a), Some(&3));
+            assert_eq!(counts.get(&b), Some(&1));
+        });
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use guilderia_result::Result;
 
 use crate::{AppendMessage, FieldsMessage, Message, MessageQuery, PartialMessage};
@@ -10,30 +12,53 @@ pub trait AbstractMessages: Sync + Send {
     /// Insert a new message into the database
     async fn insert_message(&self, message: &Message) -> Result<()>;
 
+    /// Insert multiple new messages into the database
+    async fn insert_messages(&self, messages: &[Message]) -> Result<()>;
+
+    /// Insert multiple new messages into the database independently of each other
+    ///
+    /// Returns the outcome of each insert, in the same order as the messages.
+    async fn insert_messages_each(&self, messages: &[Message]) -> Vec<Result<()>>;
+
     /// Fetch a message by its id
     async fn fetch_message(&self, id: &str) -> Result<Message>;
 
     /// Fetch multiple messages by given query
     async fn fetch_messages(&self, query: MessageQuery) -> Result<Vec<Message>>;
 
+    /// Count messages matching a query in each channel, regardless of its limit
+    async fn count_messages_by_channel(&self, query: MessageQuery) -> Result<HashMap<String, usize>>;
+
+    /// Count messages in a channel sent after a given message
+    async fn count_messages_since(&self, channel: &str, after: Option<&str>) -> Result<u64>;
+
     /// Fetch multiple messages by given IDs
     async fn fetch_messages_by_id(&self, ids: &[String]) -> Result<Vec<Message>>;
 
+    /// Fetch messages by an author which have attachments and were sent before a given message id, oldest first
+    async fn fetch_messages_with_attachments_before(&self, author: &str, before: &str, limit: i64) -> Result<Vec<Message>>;
+
+    /// Fetch unpinned messages which have passed their expiry, soonest expired first
+    async fn fetch_expired_messages(&self, limit: i64) -> Result<Vec<Message>>;
+
     /// Update a given message with new information
     async fn update_message(&self, id: &str, message: &PartialMessage, remove: Vec<FieldsMessage>) -> Result<()>;
 
     /// Append information to a given message
     async fn append_message(&self, id: &str, append: &AppendMessage) -> Result<()>;
 
-    /// Add a new reaction to a message
-    async fn add_reaction(&self, id: &str, emoji: &str, user: &str) -> Result<()>;
+    /// Add a new reaction to a message, optionally as a burst reaction
+    async fn add_reaction(&self, id: &str, emoji: &str, user: &str, burst: bool) -> Result<()>;
 
-    /// Remove a reaction from a message
+    /// Remove a reaction and burst reaction from a message
     async fn remove_reaction(&self, id: &str, emoji: &str, user: &str) -> Result<()>;
 
-    /// Remove reaction from a message
+    /// Remove reaction and burst reactions from a message
     async fn clear_reaction(&self, id: &str, emoji: &str) -> Result<()>;
 
+    /// Remove burst reactions from a message, leaving the reaction itself
+    async fn clear_burst_reaction(&self, id: &str, emoji: &str) -> Result<()>;
+
     /// Delete a message from the database by its id
     async fn delete_message(&self, id: &str) -> Result<()>;
 
@@ -1,12 +1,16 @@
+use std::collections::HashMap;
+
 use bson::{to_bson, Document};
-use futures::try_join;
+use futures::{try_join, StreamExt};
+use iso8601_timestamp::Timestamp;
+use mongodb::error::{ErrorKind, InsertManyError};
 use mongodb::options::FindOptions;
 use guilderia_models::v0::MessageSort;
 use guilderia_result::Result;
 
 use crate::{
-    AppendMessage, DocumentId, FieldsMessage, IntoDocumentPath, Message, MessageQuery,
-    MessageTimePeriod, MongoDb, PartialMessage,
+    tasks::message_batch, AppendMessage, DocumentId, FieldsMessage, IntoDocumentPath, Message,
+    MessageFilter, MessageQuery, MessageTimePeriod, MongoDb, PartialMessage,
 };
 
 use super::AbstractMessages;
@@ -17,9 +21,59 @@ static COL: &str = "messages";
 impl AbstractMessages for MongoDb {
     /// Insert a new message into the database
     async fn insert_message(&self, message: &Message) -> Result<()> {
+        if message_batch::is_active() {
+            // Fall through to a direct write if the batch queue is full
+            if let Ok(result) = message_batch::insert(message.clone()).await {
+                return result;
+            }
+        }
+
         query!(self, insert_one, COL, &message).map(|_| ())
     }
 
+    /// Insert multiple new messages into the database
+    async fn insert_messages(&self, messages: &[Message]) -> Result<()> {
+        self.col::<Message>(COL)
+            .insert_many(messages)
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("insert_many", COL))
+    }
+
+    /// Insert multiple new messages into the database independently of each other
+    async fn insert_messages_each(&self, messages: &[Message]) -> Vec<Result<()>> {
+        let mut results: Vec<Result<()>> = messages.iter().map(|_| Ok(())).collect();
+        let Err(err) = self
+            .col::<Message>(COL)
+            .insert_many(messages)
+            .ordered(false)
+            .await
+        else {
+            return results;
+        };
+
+        match *err.kind {
+            // Only the documents which failed to write were rejected
+            ErrorKind::InsertMany(InsertManyError {
+                write_errors: Some(write_errors),
+                write_concern_error: None,
+                ..
+            }) => {
+                for error in write_errors {
+                    if let Some(result) = results.get_mut(error.index) {
+                        *result = Err(create_database_error!("insert_many", COL));
+                    }
+                }
+
+                results
+            }
+            _ => messages
+                .iter()
+                .map(|_| Err(create_database_error!("insert_many", COL)))
+                .collect(),
+        }
+    }
+
     /// Fetch a message by its id
     async fn fetch_message(&self, id: &str) -> Result<Message> {
         query!(self, find_one_by_id, COL, id)?.ok_or_else(|| create_error!(NotFound))
@@ -27,33 +81,9 @@ impl AbstractMessages for MongoDb {
 
     /// Fetch multiple messages by given query
     async fn fetch_messages(&self, query: MessageQuery) -> Result<Vec<Message>> {
-        let mut filter = doc! {};
-
         // 1. Apply message filters
-        if let Some(channel) = query.filter.channel {
-            filter.insert("channel", channel);
-        }
-
-        if let Some(author) = query.filter.author {
-            filter.insert("author", author);
-        }
-
-        let is_search_query = if let Some(query) = query.filter.query {
-            filter.insert(
-                "$text",
-                doc! {
-                    "$search": query
-                },
-            );
-
-            true
-        } else {
-            false
-        };
-
-        if let Some(pinned) = query.filter.pinned {
-            filter.insert("pinned", pinned);
-        };
+        let is_search_query = query.filter.query.is_some();
+        let mut filter = message_filter(query.filter);
 
         // 2. Find query limit
         let limit = query.limit.unwrap_or(50);
@@ -112,19 +142,7 @@ impl AbstractMessages for MongoDb {
                 sort,
             } => {
                 // 3.1. Apply message ID filter
-                if let Some(doc) = match (before, after) {
-                    (Some(before), Some(after)) => Some(doc! {
-                        "$lt": before,
-                        "$gt": after
-                    }),
-                    (Some(before), _) => Some(doc! {
-                        "$lt": before
-                    }),
-                    (_, Some(after)) => Some(doc! {
-                        "$gt": after
-                    }),
-                    _ => None,
-                } {
+                if let Some(doc) = id_range(before, after) {
                     filter.insert("_id", doc);
                 }
 
@@ -166,6 +184,92 @@ impl AbstractMessages for MongoDb {
         }
     }
 
+    /// Count messages matching a query in each channel, regardless of its limit
+    async fn count_messages_by_channel(
+        &self,
+        query: MessageQuery,
+    ) -> Result<HashMap<String, usize>> {
+        let mut filter = message_filter(query.filter);
+        if let MessageTimePeriod::Absolute { before, after, .. } = query.time_period {
+            if let Some(doc) = id_range(before, after) {
+                filter.insert("_id", doc);
+            }
+        }
+
+        Ok(self
+            .col::<Document>(COL)
+            .aggregate(vec![
+                doc! {
+                    "$match": filter
+                },
+                doc! {
+                    "$group": {
+                        "_id": "$channel",
+                        "count": {
+                            "$sum": 1_i32
+                        }
+                    }
+                },
+            ])
+            .await
+            .map_err(|_| create_database_error!("aggregate", COL))?
+            .filter_map(|document| async move {
+                let document = document.ok()?;
+                let channel = document.get_str("_id").ok()?.to_string();
+                let count = document.get_i32("count").ok()? as usize;
+                Some((channel, count))
+            })
+            .collect()
+            .await)
+    }
+
+    /// Count messages in a channel sent after a given message
+    async fn count_messages_since(&self, channel: &str, after: Option<&str>) -> Result<u64> {
+        let mut filter = doc! {
+            "channel": channel
+        };
+
+        if let Some(after) = after {
+            filter.insert(
+                "_id",
+                doc! {
+                    "$gt": after
+                },
+            );
+        }
+
+        query!(self, count_documents, COL, filter)
+    }
+
+    /// Fetch messages by an author which have attachments and were sent before a given message id, oldest first
+    async fn fetch_messages_with_attachments_before(
+        &self,
+        author: &str,
+        before: &str,
+        limit: i64,
+    ) -> Result<Vec<Message>> {
+        self.find_with_options(
+            COL,
+            doc! {
+                "author": author,
+                "_id": {
+                    "$lt": before
+                },
+                "attachments.0": {
+                    "$exists": true
+                }
+            },
+            FindOptions::builder()
+                .sort(doc! {
+                    "_id": 1_i32
+                })
+                .limit(limit)
+                .build(),
+        )
+        .await
+        .map_err(|_| create_database_error!("find", COL))
+    }
+
     /// Fetch multiple messages by given IDs
     async fn fetch_messages_by_id(&self, ids: &[String]) -> Result<Vec<Message>> {
         self.find_with_options(
@@ -181,6 +285,30 @@ impl AbstractMessages for MongoDb {
         .map_err(|_| create_database_error!("find", COL))
     }
 
+    /// Fetch unpinned messages which have passed their expiry, soonest expired first
+    async fn fetch_expired_messages(&self, limit: i64) -> Result<Vec<Message>> {
+        self.find_with_options(
+            COL,
+            doc! {
+                "expires_at": {
+                    "$lte": to_bson(&Timestamp::now_utc())
+                        .map_err(|_| create_database_error!("to_bson", "timestamp"))?
+                },
+                "pinned": {
+                    "$ne": true
+                }
+            },
+            FindOptions::builder()
+                .sort(doc! {
+                    "expires_at": 1_i32
+                })
+                .limit(limit)
+                .build(),
+        )
+        .await
+        .map_err(|_| create_database_error!("find", COL))
+    }
+
     /// Update a given message with new information
     async fn update_message(
         &self,
@@ -234,17 +362,23 @@ impl AbstractMessages for MongoDb {
             .map_err(|_| create_database_error!("update_one", COL))
     }
 
-    /// Add a new reaction to a message
-    async fn add_reaction(&self, id: &str, emoji: &str, user: &str) -> Result<()> {
+    /// Add a new reaction to a message, optionally as a burst reaction
+    async fn add_reaction(&self, id: &str, emoji: &str, user: &str, burst: bool) -> Result<()> {
+        let mut add = doc! {
+            format!("reactions.{emoji}"): user
+        };
+
+        if burst {
+            add.insert(format!("burst_reactions.{emoji}"), user);
+        }
+
         self.col::<Document>(COL)
             .update_one(
                 doc! {
                     "_id": id
                 },
                 doc! {
-                    "$addToSet": {
-                        format!("reactions.{emoji}"): user
-                    }
+                    "$addToSet": add
                 },
             )
             .await
@@ -252,7 +386,7 @@ impl AbstractMessages for MongoDb {
             .map_err(|_| create_database_error!("update_one", COL))
     }
 
-    /// Remove a reaction from a message
+    /// Remove a reaction and burst reaction from a message
     async fn remove_reaction(&self, id: &str, emoji: &str, user: &str) -> Result<()> {
         self.col::<Document>(COL)
             .update_one(
@@ -261,7 +395,8 @@ impl AbstractMessages for MongoDb {
                 },
                 doc! {
                     "$pull": {
-                        format!("reactions.{emoji}"): user
+                        format!("reactions.{emoji}"): user,
+                        format!("burst_reactions.{emoji}"): user
                     }
                 },
             )
@@ -270,7 +405,7 @@ impl AbstractMessages for MongoDb {
             .map_err(|_| create_database_error!("update_one", COL))
     }
 
-    /// Remove reaction from a message
+    /// Remove reaction and burst reactions from a message
     async fn clear_reaction(&self, id: &str, emoji: &str) -> Result<()> {
         self.col::<Document>(COL)
             .update_one(
@@ -279,7 +414,26 @@ impl AbstractMessages for MongoDb {
                 },
                 doc! {
                     "$unset": {
-                        format!("reactions.{emoji}"): 1
+                        format!("reactions.{emoji}"): 1,
+                        format!("burst_reactions.{emoji}"): 1
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Remove burst reactions from a message, leaving the reaction itself
+    async fn clear_burst_reaction(&self, id: &str, emoji: &str) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$unset": {
+                        format!("burst_reactions.{emoji}"): 1
                     }
                 },
             )
@@ -312,6 +466,9 @@ impl IntoDocumentPath for FieldsMessage {
     fn as_path(&self) -> Option<&'static str> {
         Some(match self {
             FieldsMessage::Pinned => "pinned",
+            FieldsMessage::Provenance => "provenance",
+            FieldsMessage::Extensions => "extensions",
+            FieldsMessage::Fallback => "fallback",
         })
     }
 }
@@ -368,3 +525,68 @@ impl MongoDb {
             .map_err(|_| create_database_error!("delete_many", COL))
     }
 }
+
+/// Build a query document from a message filter
+fn message_filter(filter: MessageFilter) -> Document {
+    let mut document = doc! {};
+
+    if let Some(channel) = filter.channel {
+        document.insert("channel", channel);
+    }
+
+    if let Some(channels) = filter.channels {
+        document.insert(
+            "channel",
+            doc! {
+                "$in": channels
+            },
+        );
+    }
+
+    if let Some(author) = filter.author {
+        document.insert("author", author);
+    }
+
+    if let Some(query) = filter.query {
+        document.insert(
+            "$text",
+            doc! {
+                "$search": query
+            },
+        );
+    }
+
+    if let Some(pinned) = filter.pinned {
+        document.insert("pinned", pinned);
+    };
+
+    if let Some(thread) = filter.thread {
+        document.insert("thread", thread);
+    } else if filter.exclude_threads {
+        document.insert(
+            "thread",
+            doc! {
+                "$exists": false
+            },
+        );
+    }
+
+    document
+}
+
+/// Build a message id range from the given bounds
+fn id_range(before: Option<String>, after: Option<String>) -> Option<Document> {
+    match (before, after) {
+        (Some(before), Some(after)) => Some(doc! {
+            "$lt": before,
+            "$gt": after
+        }),
+        (Some(before), _) => Some(doc! {
+            "$lt": before
+        }),
+        (_, Some(after)) => Some(doc! {
+            "$gt": after
+        }),
+        _ => None,
+    }
+}
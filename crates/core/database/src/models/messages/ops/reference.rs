@@ -1,8 +1,14 @@
+use std::collections::HashMap;
+
 use futures::future::try_join_all;
 use indexmap::IndexSet;
+use iso8601_timestamp::Timestamp;
 use guilderia_result::Result;
 
-use crate::{AppendMessage, FieldsMessage, Message, MessageQuery, PartialMessage, ReferenceDb};
+use crate::{
+    AppendMessage, FieldsMessage, Message, MessageQuery, MessageTimePeriod, PartialMessage,
+    ReferenceDb,
+};
 
 use super::AbstractMessages;
 
@@ -19,6 +25,25 @@ impl AbstractMessages for ReferenceDb {
         }
     }
 
+    /// Insert multiple new messages into the database
+    async fn insert_messages(&self, messages: &[Message]) -> Result<()> {
+        for message in messages {
+            self.insert_message(message).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Insert multiple new messages into the database independently of each other
+    async fn insert_messages_each(&self, messages: &[Message]) -> Vec<Result<()>> {
+        let mut results = Vec::with_capacity(messages.len());
+        for message in messages {
+            results.push(self.insert_message(message).await);
+        }
+
+        results
+    }
+
     /// Fetch a message by its id
     async fn fetch_message(&self, id: &str) -> Result<Message> {
         let messages = self.messages.lock().await;
@@ -40,6 +65,12 @@ impl AbstractMessages for ReferenceDb {
                     }
                 }
 
+                if let Some(channels) = &query.filter.channels {
+                    if !channels.contains(&message.channel) {
+                        return false;
+                    }
+                }
+
                 if let Some(author) = &query.filter.author {
                     if &message.author != author {
                         return false;
@@ -62,6 +93,14 @@ impl AbstractMessages for ReferenceDb {
                     }
                 }
 
+                if let Some(thread) = &query.filter.thread {
+                    if message.thread.as_ref() != Some(thread) {
+                        return false;
+                    }
+                } else if query.filter.exclude_threads && message.thread.is_some() {
+                    return false;
+                }
+
                 true
             })
             .cloned()
@@ -183,11 +222,90 @@ impl AbstractMessages for ReferenceDb {
         }*/
     }
 
+    /// Count messages matching a query in each channel, regardless of its limit
+    async fn count_messages_by_channel(
+        &self,
+        query: MessageQuery,
+    ) -> Result<HashMap<String, usize>> {
+        let (before, after) = match &query.time_period {
+            MessageTimePeriod::Absolute { before, after, .. } => (before.clone(), after.clone()),
+            MessageTimePeriod::Relative { .. } => (None, None),
+        };
+
+        let mut counts = HashMap::new();
+        for message in self.fetch_messages(query).await? {
+            if before.as_ref().is_none_or(|before| &message.id < before)
+                && after.as_ref().is_none_or(|after| &message.id > after)
+            {
+                *counts.entry(message.channel).or_default() += 1;
+            }
+        }
+
+        Ok(counts)
+    }
+
+    /// Count messages in a channel sent after a given message
+    async fn count_messages_since(&self, channel: &str, after: Option<&str>) -> Result<u64> {
+        let messages = self.messages.lock().await;
+        Ok(messages
+            .values()
+            .filter(|message| {
+                message.channel == channel && after.is_none_or(|after| message.id.as_str() > after)
+            })
+            .count() as u64)
+    }
+
+    /// Fetch messages by an author which have attachments and were sent before a given message id, oldest first
+    async fn fetch_messages_with_attachments_before(
+        &self,
+        author: &str,
+        before: &str,
+        limit: i64,
+    ) -> Result<Vec<Message>> {
+        let messages = self.messages.lock().await;
+        let mut matched: Vec<Message> = messages
+            .values()
+            .filter(|message| {
+                message.author == author
+                    && message.id.as_str() < before
+                    && message
+                        .attachments
+                        .as_ref()
+                        .is_some_and(|attachments| !attachments.is_empty())
+            })
+            .cloned()
+            .collect();
+
+        matched.sort_by(|a, b| a.id.cmp(&b.id));
+        matched.truncate(limit as usize);
+        Ok(matched)
+    }
+
     /// Fetch multiple messages by given IDs
     async fn fetch_messages_by_id(&self, ids: &[String]) -> Result<Vec<Message>> {
         try_join_all(ids.iter().map(|id| self.fetch_message(id))).await
     }
 
+    /// Fetch unpinned messages which have passed their expiry, soonest expired first
+    async fn fetch_expired_messages(&self, limit: i64) -> Result<Vec<Message>> {
+        let now = Timestamp::now_utc();
+        let messages = self.messages.lock().await;
+        let mut matched: Vec<Message> = messages
+            .values()
+            .filter(|message| {
+                message
+                    .expires_at
+                    .is_some_and(|expires_at| *expires_at <= *now)
+                    && !message.pinned.unwrap_or_default()
+            })
+            .cloned()
+            .collect();
+
+        matched.sort_by_key(|message| message.expires_at.map(|expires_at| *expires_at));
+        matched.truncate(limit as usize);
+        Ok(matched)
+    }
+
     /// Update a given message with new information
     async fn update_message(&self, id: &str, message: &PartialMessage, remove: Vec<FieldsMessage>) -> Result<()> {
         let mut messages = self.messages.lock().await;
@@ -224,8 +342,8 @@ impl AbstractMessages for ReferenceDb {
         }
     }
 
-    /// Add a new reaction to a message
-    async fn add_reaction(&self, id: &str, emoji: &str, user: &str) -> Result<()> {
+    /// Add a new reaction to a message, optionally as a burst reaction
+    async fn add_reaction(&self, id: &str, emoji: &str, user: &str, burst: bool) -> Result<()> {
         let mut messages = self.messages.lock().await;
         if let Some(message) = messages.get_mut(id) {
             if let Some(users) = message.reactions.get_mut(emoji) {
@@ -236,13 +354,21 @@ impl AbstractMessages for ReferenceDb {
                     .insert(emoji.to_string(), IndexSet::from([user.to_string()]));
             }
 
+            if burst {
+                message
+                    .burst_reactions
+                    .entry(emoji.to_string())
+                    .or_default()
+                    .insert(user.to_string());
+            }
+
             Ok(())
         } else {
             Err(create_error!(NotFound))
         }
     }
 
-    /// Remove a reaction from a message
+    /// Remove a reaction and burst reaction from a message
     async fn remove_reaction(&self, id: &str, emoji: &str, user: &str) -> Result<()> {
         let mut messages = self.messages.lock().await;
         if let Some(message) = messages.get_mut(id) {
@@ -250,17 +376,33 @@ impl AbstractMessages for ReferenceDb {
                 users.remove(&user.to_string());
             }
 
+            if let Some(users) = message.burst_reactions.get_mut(emoji) {
+                users.remove(&user.to_string());
+            }
+
             Ok(())
         } else {
             Err(create_error!(NotFound))
         }
     }
 
-    /// Remove reaction from a message
+    /// Remove reaction and burst reactions from a message
     async fn clear_reaction(&self, id: &str, emoji: &str) -> Result<()> {
         let mut messages = self.messages.lock().await;
         if let Some(message) = messages.get_mut(id) {
             message.reactions.remove(emoji);
+            message.burst_reactions.remove(emoji);
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Remove burst reactions from a message, leaving the reaction itself
+    async fn clear_burst_reaction(&self, id: &str, emoji: &str) -> Result<()> {
+        let mut messages = self.messages.lock().await;
+        if let Some(message) = messages.get_mut(id) {
+            message.burst_reactions.remove(emoji);
             Ok(())
         } else {
             Err(create_error!(NotFound))
@@ -1,40 +1,80 @@
 mod admin_migrations;
+mod account_recovery;
+mod api_tokens;
+mod audit_log;
+mod ban_subscriptions;
+mod bookmarks;
 mod bots;
 mod channel_invites;
 mod channel_unreads;
 mod channel_webhooks;
 mod channels;
+mod delayed_notifications;
+mod devices;
 mod emojis;
 mod file_hashes;
 mod files;
+mod filter_words;
+mod hash_matches;
+mod impersonations;
+mod interactions;
 mod messages;
+mod notification_summaries;
+mod pending_actions;
 mod policy_changes;
+mod purge_schedules;
 mod ratelimit_events;
+mod read_receipts;
 mod safety_reports;
 mod safety_snapshots;
+mod screening_responses;
+mod server_applications;
 mod server_bans;
 mod server_members;
 mod servers;
+mod starboard_entries;
+mod threads;
+mod user_apps;
 mod user_settings;
 mod users;
 
 pub use admin_migrations::*;
+pub use account_recovery::*;
+pub use api_tokens::*;
+pub use audit_log::*;
+pub use ban_subscriptions::*;
+pub use bookmarks::*;
 pub use bots::*;
 pub use channel_invites::*;
 pub use channel_unreads::*;
 pub use channel_webhooks::*;
 pub use channels::*;
+pub use delayed_notifications::*;
+pub use devices::*;
 pub use emojis::*;
 pub use file_hashes::*;
 pub use files::*;
+pub use filter_words::*;
+pub use hash_matches::*;
+pub use impersonations::*;
+pub use interactions::*;
 pub use messages::*;
+pub use notification_summaries::*;
+pub use pending_actions::*;
 pub use policy_changes::*;
+pub use purge_schedules::*;
 pub use ratelimit_events::*;
+pub use read_receipts::*;
 pub use safety_reports::*;
 pub use safety_snapshots::*;
+pub use screening_responses::*;
+pub use server_applications::*;
 pub use server_bans::*;
 pub use server_members::*;
 pub use servers::*;
+pub use starboard_entries::*;
+pub use threads::*;
+pub use user_apps::*;
 pub use user_settings::*;
 pub use users::*;
 
@@ -43,23 +83,43 @@ use crate::{Database, MongoDb, ReferenceDb};
 pub trait AbstractDatabase:
     Sync
     + Send
+    + account_recovery::AbstractAccountRecovery
     + admin_migrations::AbstractMigrations
+    + api_tokens::AbstractApiTokens
+    + audit_log::AbstractAuditLog
+    + ban_subscriptions::AbstractBanSubscriptions
+    + bookmarks::AbstractBookmarks
     + bots::AbstractBots
     + channels::AbstractChannels
     + channel_invites::AbstractChannelInvites
     + channel_unreads::AbstractChannelUnreads
     + channel_webhooks::AbstractWebhooks
+    + delayed_notifications::AbstractDelayedNotifications
+    + devices::AbstractDevices
     + emojis::AbstractEmojis
     + file_hashes::AbstractAttachmentHashes
     + files::AbstractAttachments
+    + filter_words::AbstractFilterWords
+    + hash_matches::AbstractHashMatches
+    + impersonations::AbstractImpersonations
+    + interactions::AbstractInteractions
     + messages::AbstractMessages
+    + notification_summaries::AbstractNotificationSummaries
+    + pending_actions::AbstractPendingActions
     + policy_changes::AbstractPolicyChange
+    + purge_schedules::AbstractPurgeSchedules
     + ratelimit_events::AbstractRatelimitEvents
+    + read_receipts::AbstractReadReceipts
     + safety_reports::AbstractReport
     + safety_snapshots::AbstractSnapshot
+    + screening_responses::AbstractScreeningResponses
+    + server_applications::AbstractServerApplications
     + server_bans::AbstractServerBans
     + server_members::AbstractServerMembers
     + servers::AbstractServers
+    + starboard_entries::AbstractStarboardEntries
+    + threads::AbstractThreads
+    + user_apps::AbstractUserApps
     + user_settings::AbstractUserSettings
     + users::AbstractUsers
 {
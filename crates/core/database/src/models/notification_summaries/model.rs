@@ -0,0 +1,116 @@
+use guilderia_models::v0::{self, PushNotification};
+use guilderia_result::Result;
+
+use crate::{util::locale::Locale, Database, User, AMQP};
+
+auto_derived!(
+    /// Notifications held back while a user was in do not disturb
+    pub struct NotificationSummary {
+        /// Id of the user
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Number of notifications held back
+        pub count: u32,
+        /// Channels the notifications were sent in
+        pub channels: Vec<String>,
+    }
+);
+
+impl NotificationSummary {
+    /// Check whether a push notification should be held back because the user is busy
+    ///
+    /// Held back notifications are counted towards the summary sent once
+    /// the user leaves do not disturb. Direct messages from friends still go through.
+    pub async fn suppresses(db: &Database, user: &str, push: &PushNotification) -> bool {
+        let Ok(user) = db.fetch_user(user).await else {
+            return false;
+        };
+
+        if !user.is_busy() {
+            return false;
+        }
+
+        if matches!(push.channel, v0::Channel::DirectMessage { .. })
+            && user.is_friends_with(&push.message.author)
+        {
+            return false;
+        }
+
+        if let Err(err) = db
+            .add_to_notification_summary(&user.id, &push.message.channel)
+            .await
+        {
+            guilderia_config::capture_error(&err);
+        }
+
+        true
+    }
+
+    /// Send the summary of notifications held back while the user was busy
+    pub async fn deliver(db: &Database, amqp: &AMQP, user: &User) -> Result<()> {
+        let Some(summary) = db.take_notification_summary(&user.id).await? else {
+            return Ok(());
+        };
+
+        let localization = guilderia_config::config().await.localization;
+        let locale = Locale::for_user(db, &user.id).await;
+
+        let count = summary.count.to_string();
+        let channels = summary.channels.len().to_string();
+        let (key, default) = match (summary.count, summary.channels.len()) {
+            (1, _) => (
+                "notifications.summary.one",
+                "You missed 1 notification while you were busy.",
+            ),
+            (_, 1) => (
+                "notifications.summary.many",
+                "You missed {count} notifications while you were busy.",
+            ),
+            _ => (
+                "notifications.summary.many_channels",
+                "You missed {count} notifications in {channels} channels while you were busy.",
+            ),
+        };
+
+        let title = locale.translate(
+            &localization,
+            "notifications.summary.title",
+            "Welcome back",
+            &[],
+        );
+        let body = locale.translate(
+            &localization,
+            key,
+            default,
+            &[("count", &count), ("channels", &channels)],
+        );
+
+        if let Err(err) = amqp.generic_message(user, title, body, None).await {
+            guilderia_config::capture_error(&err);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[async_std::test]
+    async fn counts_and_clears() {
+        database_test!(|db| async move {
+            db.add_to_notification_summary("user", "a").await.unwrap();
+            db.add_to_notification_summary("user", "a").await.unwrap();
+            db.add_to_notification_summary("user", "b").await.unwrap();
+
+            let summary = db.take_notification_summary("user").await.unwrap().unwrap();
+            assert_eq!(summary.count, 3);
+            assert_eq!(summary.channels, vec!["a".to_string(), "b".to_string()]);
+
+            assert!(db
+                .take_notification_summary("user")
+                .await
+                .unwrap()
+                .is_none());
+        });
+    }
+}
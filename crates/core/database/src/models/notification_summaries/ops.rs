@@ -0,0 +1,16 @@
+use guilderia_result::Result;
+
+use crate::NotificationSummary;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractNotificationSummaries: Sync + Send {
+    /// Count a notification held back from a user
+    async fn add_to_notification_summary(&self, user_id: &str, channel_id: &str) -> Result<()>;
+
+    /// Fetch and clear a user's notification summary
+    async fn take_notification_summary(&self, user_id: &str)
+        -> Result<Option<NotificationSummary>>;
+}
@@ -0,0 +1,47 @@
+use guilderia_result::Result;
+use mongodb::options::UpdateOptions;
+
+use crate::MongoDb;
+use crate::NotificationSummary;
+
+use super::AbstractNotificationSummaries;
+
+static COL: &str = "notification_summaries";
+
+#[async_trait]
+impl AbstractNotificationSummaries for MongoDb {
+    /// Count a notification held back from a user
+    async fn add_to_notification_summary(&self, user_id: &str, channel_id: &str) -> Result<()> {
+        self.col::<NotificationSummary>(COL)
+            .update_one(
+                doc! {
+                    "_id": user_id
+                },
+                doc! {
+                    "$inc": {
+                        "count": 1_i32
+                    },
+                    "$addToSet": {
+                        "channels": channel_id
+                    }
+                },
+            )
+            .with_options(UpdateOptions::builder().upsert(true).build())
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Fetch and clear a user's notification summary
+    async fn take_notification_summary(
+        &self,
+        user_id: &str,
+    ) -> Result<Option<NotificationSummary>> {
+        self.col::<NotificationSummary>(COL)
+            .find_one_and_delete(doc! {
+                "_id": user_id
+            })
+            .await
+            .map_err(|_| create_database_error!("find_one_and_delete", COL))
+    }
+}
@@ -0,0 +1,37 @@
+use guilderia_result::Result;
+
+use crate::NotificationSummary;
+use crate::ReferenceDb;
+
+use super::AbstractNotificationSummaries;
+
+#[async_trait]
+impl AbstractNotificationSummaries for ReferenceDb {
+    /// Count a notification held back from a user
+    async fn add_to_notification_summary(&self, user_id: &str, channel_id: &str) -> Result<()> {
+        let mut notification_summaries = self.notification_summaries.lock().await;
+        let summary = notification_summaries
+            .entry(user_id.to_string())
+            .or_insert_with(|| NotificationSummary {
+                id: user_id.to_string(),
+                count: 0,
+                channels: vec![],
+            });
+
+        summary.count += 1;
+        if !summary.channels.iter().any(|channel| channel == channel_id) {
+            summary.channels.push(channel_id.to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Fetch and clear a user's notification summary
+    async fn take_notification_summary(
+        &self,
+        user_id: &str,
+    ) -> Result<Option<NotificationSummary>> {
+        let mut notification_summaries = self.notification_summaries.lock().await;
+        Ok(notification_summaries.remove(user_id))
+    }
+}
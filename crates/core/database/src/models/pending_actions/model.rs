@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use guilderia_result::Result;
+use ulid::Ulid;
+
+use crate::{Database, Server, SystemMessage};
+
+/// How long a pending action waits for approval before it expires
+pub const PENDING_ACTION_LIFETIME: Duration = Duration::from_secs(60 * 60);
+
+auto_derived!(
+    /// Destructive action waiting for a second administrator to approve it
+    pub struct PendingAction {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the server the action applies to
+        pub server: String,
+        /// Id of the user who requested the action
+        pub initiator: String,
+        /// Action to carry out once approved
+        pub action: PendingActionKind,
+    }
+
+    /// Destructive action which may require approval
+    #[serde(tag = "type")]
+    pub enum PendingActionKind {
+        /// Delete the server
+        DeleteServer,
+        /// Ban many users at once
+        BanMembers {
+            /// Ids of the users to ban
+            ids: Vec<String>,
+            /// Reason given for the bans
+            #[serde(skip_serializing_if = "Option::is_none")]
+            reason: Option<String>,
+        },
+        /// Delete many messages in a channel at once
+        PurgeMessages {
+            /// Id of the channel
+            channel: String,
+            /// Ids of the messages to delete
+            ids: Vec<String>,
+        },
+        /// Purge a channel on a recurring schedule
+        SchedulePurge {
+            /// Id of the channel
+            channel: String,
+            /// Hour of the day (UTC) at which to purge
+            hour: u8,
+            /// Minute of the hour at which to purge
+            minute: u8,
+            /// Number of days between purges
+            interval_days: u8,
+        },
+    }
+);
+
+impl PendingAction {
+    /// Request approval for a destructive action
+    ///
+    /// Moderators are notified in the server's moderation alerts channel, if one is set.
+    pub async fn create(
+        db: &Database,
+        server: &Server,
+        initiator: &str,
+        action: PendingActionKind,
+    ) -> Result<PendingAction> {
+        let pending = PendingAction {
+            id: Ulid::new().to_string(),
+            server: server.id.to_string(),
+            initiator: initiator.to_string(),
+            action,
+        };
+
+        db.insert_pending_action(&pending).await?;
+
+        if let Some(channel) = server
+            .system_messages
+            .as_ref()
+            .and_then(|x| x.moderation_alerts.as_ref())
+        {
+            SystemMessage::Text {
+                content: format!(
+                    "<@{initiator}> requested to {}, which needs approval from another administrator.",
+                    pending.action.describe()
+                ),
+            }
+            .into_message(channel.to_string())
+            .send_without_notifications(db, None, None, false, false, false)
+            .await
+            .ok();
+        }
+
+        Ok(pending)
+    }
+
+    /// Whether this action has waited too long for approval
+    pub fn is_expired(&self) -> bool {
+        Ulid::from_string(&self.id)
+            .ok()
+            .and_then(|id| id.datetime().elapsed().ok())
+            .is_none_or(|elapsed| elapsed > PENDING_ACTION_LIFETIME)
+    }
+}
+
+impl PendingActionKind {
+    /// Short description of the action for moderators
+    pub fn describe(&self) -> String {
+        match self {
+            PendingActionKind::DeleteServer => "delete the server".to_string(),
+            PendingActionKind::BanMembers { ids, .. } => format!("ban {} users", ids.len()),
+            PendingActionKind::PurgeMessages { channel, ids } => {
+                format!("delete {} messages in <#{channel}>", ids.len())
+            }
+            PendingActionKind::SchedulePurge { channel, .. } => {
+                format!("schedule recurring purges of <#{channel}>")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use ulid::Ulid;
+
+    use crate::{PendingAction, PendingActionKind};
+
+    #[test]
+    fn pending_actions_expire() {
+        let mut pending = PendingAction {
+            id: Ulid::new().to_string(),
+            server: "server".to_string(),
+            initiator: "user".to_string(),
+            action: PendingActionKind::DeleteServer,
+        };
+
+        assert!(!pending.is_expired());
+
+        pending.id =
+            Ulid::from_datetime(SystemTime::now() - Duration::from_secs(2 * 60 * 60)).to_string();
+        assert!(pending.is_expired());
+    }
+}
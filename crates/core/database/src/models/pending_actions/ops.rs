@@ -0,0 +1,21 @@
+use guilderia_result::Result;
+
+use crate::PendingAction;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractPendingActions: Sync + Send {
+    /// Insert a new pending action
+    async fn insert_pending_action(&self, action: &PendingAction) -> Result<()>;
+
+    /// Fetch a pending action by its id
+    async fn fetch_pending_action(&self, id: &str) -> Result<PendingAction>;
+
+    /// Fetch all pending actions of a server
+    async fn fetch_pending_actions(&self, server: &str) -> Result<Vec<PendingAction>>;
+
+    /// Delete a pending action by its id
+    async fn delete_pending_action(&self, id: &str) -> Result<()>;
+}
@@ -0,0 +1,38 @@
+use guilderia_result::Result;
+
+use crate::MongoDb;
+use crate::PendingAction;
+
+use super::AbstractPendingActions;
+
+static COL: &str = "pending_actions";
+
+#[async_trait]
+impl AbstractPendingActions for MongoDb {
+    /// Insert a new pending action
+    async fn insert_pending_action(&self, action: &PendingAction) -> Result<()> {
+        query!(self, insert_one, COL, &action).map(|_| ())
+    }
+
+    /// Fetch a pending action by its id
+    async fn fetch_pending_action(&self, id: &str) -> Result<PendingAction> {
+        query!(self, find_one_by_id, COL, id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all pending actions of a server
+    async fn fetch_pending_actions(&self, server: &str) -> Result<Vec<PendingAction>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "server": server
+            }
+        )
+    }
+
+    /// Delete a pending action by its id
+    async fn delete_pending_action(&self, id: &str) -> Result<()> {
+        query!(self, delete_one_by_id, COL, id).map(|_| ())
+    }
+}
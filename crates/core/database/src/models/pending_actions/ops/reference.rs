@@ -0,0 +1,49 @@
+use guilderia_result::Result;
+
+use crate::PendingAction;
+use crate::ReferenceDb;
+
+use super::AbstractPendingActions;
+
+#[async_trait]
+impl AbstractPendingActions for ReferenceDb {
+    /// Insert a new pending action
+    async fn insert_pending_action(&self, action: &PendingAction) -> Result<()> {
+        let mut pending_actions = self.pending_actions.lock().await;
+        if pending_actions.contains_key(&action.id) {
+            Err(create_database_error!("insert", "pending_actions"))
+        } else {
+            pending_actions.insert(action.id.to_string(), action.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a pending action by its id
+    async fn fetch_pending_action(&self, id: &str) -> Result<PendingAction> {
+        let pending_actions = self.pending_actions.lock().await;
+        pending_actions
+            .get(id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all pending actions of a server
+    async fn fetch_pending_actions(&self, server: &str) -> Result<Vec<PendingAction>> {
+        let pending_actions = self.pending_actions.lock().await;
+        Ok(pending_actions
+            .values()
+            .filter(|action| action.server == server)
+            .cloned()
+            .collect())
+    }
+
+    /// Delete a pending action by its id
+    async fn delete_pending_action(&self, id: &str) -> Result<()> {
+        let mut pending_actions = self.pending_actions.lock().await;
+        if pending_actions.remove(id).is_some() {
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use iso8601_timestamp::{Duration, Timestamp};
+use guilderia_models::v0::{self, MessageSort};
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::{ErrorType, Result};
+
+use crate::{
+    util::permissions::DatabasePermissionQuery, AuditLogAction, AuditLogEntry, Channel, Database,
+    Message, MessageFilter, MessageQuery, MessageTimePeriod,
+};
+
+/// Number of messages deleted at once while purging
+const PURGE_BATCH_SIZE: i64 = 100;
+
+/// Seconds in a day
+const DAY: i64 = 24 * 60 * 60;
+
+auto_derived!(
+    /// Recurring purge of a channel's messages
+    pub struct PurgeSchedule {
+        /// Id of the channel to purge
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the server the channel belongs to
+        pub server: String,
+        /// Id of the user who scheduled the purge
+        pub creator: String,
+        /// Hour of the day (UTC) at which to purge
+        pub hour: u8,
+        /// Minute of the hour at which to purge
+        pub minute: u8,
+        /// Number of days between purges
+        pub interval_days: u8,
+        /// Time at which the next purge is due
+        pub next_run: Timestamp,
+        /// Time at which the channel was last purged
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub last_run: Option<Timestamp>,
+    }
+);
+
+/// Seconds between a timestamp and the unix epoch
+fn unix_seconds(timestamp: Timestamp) -> i64 {
+    timestamp
+        .duration_since(Timestamp::UNIX_EPOCH)
+        .whole_seconds()
+}
+
+impl PurgeSchedule {
+    /// Create a purge schedule for a channel, first running at the next matching time of day
+    pub fn new(
+        channel: &str,
+        server: &str,
+        creator: &str,
+        data: v0::DataEditPurgeSchedule,
+    ) -> PurgeSchedule {
+        let mut schedule = PurgeSchedule {
+            id: channel.to_string(),
+            server: server.to_string(),
+            creator: creator.to_string(),
+            hour: data.hour,
+            minute: data.minute,
+            interval_days: data.interval_days,
+            next_run: Timestamp::UNIX_EPOCH,
+            last_run: None,
+        };
+
+        schedule.next_run = schedule.first_run_after(Timestamp::now_utc());
+        schedule
+    }
+
+    /// First time after the given time at which the scheduled time of day comes around
+    fn first_run_after(&self, after: Timestamp) -> Timestamp {
+        let seconds = unix_seconds(after);
+        let mut next = seconds - seconds.rem_euclid(DAY)
+            + self.hour as i64 * 60 * 60
+            + self.minute as i64 * 60;
+
+        if next <= seconds {
+            next += DAY;
+        }
+
+        Timestamp::UNIX_EPOCH
+            .checked_add(Duration::seconds(next))
+            .unwrap_or(after)
+    }
+
+    /// Time of the next purge after the given time, keeping to the schedule's interval
+    ///
+    /// Purges missed while the schedule was not running are skipped.
+    fn next_run_after(&self, after: Timestamp) -> Timestamp {
+        let interval = self.interval_days.max(1) as i64 * DAY;
+        let missed = (unix_seconds(after) - unix_seconds(self.next_run)).max(0) / interval + 1;
+
+        self.next_run
+            .checked_add(Duration::seconds(missed * interval))
+            .unwrap_or_else(|| self.first_run_after(after))
+    }
+
+    /// Whether the user who scheduled the purge may still purge the channel
+    pub async fn creator_may_purge(&self, db: &Database, channel: &Channel) -> Result<bool> {
+        let creator = match db.fetch_user(&self.creator).await {
+            Ok(creator) => creator,
+            Err(err) if matches!(err.error_type, ErrorType::NotFound) => return Ok(false),
+            Err(err) => return Err(err),
+        };
+
+        let mut query = DatabasePermissionQuery::new(db, &creator).channel(channel);
+        Ok(calculate_channel_permissions(&mut query)
+            .await
+            .has_channel_permission(ChannelPermission::ManageMessages))
+    }
+
+    /// Delete every message sent to the channel before now, except pinned messages
+    ///
+    /// Each run is recorded in the server's audit log against the user who
+    /// scheduled the purge. Returns the number of messages deleted.
+    pub async fn run(&mut self, db: &Database) -> Result<usize> {
+        let now = Timestamp::now_utc();
+        let mut before = ulid::Ulid::new().to_string();
+        let mut deleted = 0;
+
+        loop {
+            let messages = db
+                .fetch_messages(MessageQuery {
+                    limit: Some(PURGE_BATCH_SIZE),
+                    filter: MessageFilter {
+                        channel: Some(self.id.clone()),
+                        ..Default::default()
+                    },
+                    time_period: MessageTimePeriod::Absolute {
+                        before: Some(before.clone()),
+                        after: None,
+                        sort: Some(MessageSort::Latest),
+                    },
+                })
+                .await?;
+
+            let Some(oldest) = messages.last() else {
+                break;
+            };
+
+            before = oldest.id.clone();
+            let exhausted = (messages.len() as i64) < PURGE_BATCH_SIZE;
+
+            let ids: Vec<String> = messages
+                .into_iter()
+                .filter(|message| !message.pinned.unwrap_or_default())
+                .map(|message| message.id)
+                .collect();
+
+            if !ids.is_empty() {
+                deleted += ids.len();
+                Message::bulk_delete(db, &self.id, ids).await?;
+            }
+
+            if exhausted {
+                break;
+            }
+        }
+
+        AuditLogEntry::create(
+            db,
+            &self.server,
+            &self.creator,
+            AuditLogAction::ScheduledPurge {
+                channel: self.id.clone(),
+                deleted: deleted as i64,
+            },
+        )
+        .await?;
+
+        self.next_run = self.next_run_after(now);
+        self.last_run = Some(now);
+        db.save_purge_schedule(self).await?;
+
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iso8601_timestamp::{Duration, Timestamp};
+    use ulid::Ulid;
+
+    use crate::{AuditLogAction, Message, PurgeSchedule};
+
+    fn at(seconds: i64) -> Timestamp {
+        Timestamp::UNIX_EPOCH
+            .checked_add(Duration::seconds(seconds))
+            .unwrap()
+    }
+
+    fn schedule(hour: u8, minute: u8, interval_days: u8) -> PurgeSchedule {
+        PurgeSchedule {
+            id: "channel".to_string(),
+            server: "server".to_string(),
+            creator: "user".to_string(),
+            hour,
+            minute,
+            interval_days,
+            next_run: Timestamp::UNIX_EPOCH,
+            last_run: None,
+        }
+    }
+
+    #[test]
+    fn schedules_runs() {
+        let day = 24 * 60 * 60;
+        let mut schedule = schedule(4, 30, 2);
+
+        // Later the same day, or the following day once the time has passed
+        assert_eq!(
+            schedule.first_run_after(at(10 * day)),
+            at(10 * day + 4 * 3600 + 1800)
+        );
+        assert_eq!(
+            schedule.first_run_after(at(10 * day + 5 * 3600)),
+            at(11 * day + 4 * 3600 + 1800)
+        );
+
+        // Keeps to the interval, skipping runs which were missed
+        schedule.next_run = at(10 * day + 4 * 3600 + 1800);
+        assert_eq!(
+            schedule.next_run_after(at(10 * day + 5 * 3600)),
+            at(12 * day + 4 * 3600 + 1800)
+        );
+        assert_eq!(
+            schedule.next_run_after(at(15 * day)),
+            at(16 * day + 4 * 3600 + 1800)
+        );
+    }
+
+    #[async_std::test]
+    async fn purges_unpinned_messages() {
+        database_test!(|db| async move {
+            for pinned in [false, false, true] {
+                db.insert_message(&Message {
+                    id: Ulid::new().to_string(),
+                    channel: "channel".to_string(),
+                    pinned: pinned.then_some(true),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            }
+
+            let mut schedule = schedule(4, 0, 1);
+            db.save_purge_schedule(&schedule).await.unwrap();
+            assert_eq!(db.fetch_due_purge_schedules().await.unwrap().len(), 1);
+
+            assert_eq!(schedule.run(&db).await.unwrap(), 2);
+            assert!(db.fetch_due_purge_schedules().await.unwrap().is_empty());
+
+            let schedule = db.fetch_purge_schedule("channel").await.unwrap();
+            assert!(schedule.last_run.is_some());
+
+            let entries = db.fetch_audit_log("server", None, 50).await.unwrap();
+            assert_eq!(
+                entries[0].action,
+                AuditLogAction::ScheduledPurge {
+                    channel: "channel".to_string(),
+                    deleted: 2
+                }
+            );
+        });
+    }
+}
@@ -0,0 +1,21 @@
+use guilderia_result::Result;
+
+use crate::PurgeSchedule;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractPurgeSchedules: Sync + Send {
+    /// Insert or replace a channel's purge schedule
+    async fn save_purge_schedule(&self, schedule: &PurgeSchedule) -> Result<()>;
+
+    /// Fetch a channel's purge schedule
+    async fn fetch_purge_schedule(&self, channel: &str) -> Result<PurgeSchedule>;
+
+    /// Fetch purge schedules which are due to run
+    async fn fetch_due_purge_schedules(&self) -> Result<Vec<PurgeSchedule>>;
+
+    /// Delete a channel's purge schedule
+    async fn delete_purge_schedule(&self, channel: &str) -> Result<()>;
+}
@@ -0,0 +1,54 @@
+use bson::to_bson;
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+use mongodb::options::ReplaceOptions;
+
+use crate::MongoDb;
+use crate::PurgeSchedule;
+
+use super::AbstractPurgeSchedules;
+
+static COL: &str = "purge_schedules";
+
+#[async_trait]
+impl AbstractPurgeSchedules for MongoDb {
+    /// Insert or replace a channel's purge schedule
+    async fn save_purge_schedule(&self, schedule: &PurgeSchedule) -> Result<()> {
+        self.col::<PurgeSchedule>(COL)
+            .replace_one(
+                doc! {
+                    "_id": &schedule.id
+                },
+                schedule,
+            )
+            .with_options(ReplaceOptions::builder().upsert(true).build())
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("replace_one", COL))
+    }
+
+    /// Fetch a channel's purge schedule
+    async fn fetch_purge_schedule(&self, channel: &str) -> Result<PurgeSchedule> {
+        query!(self, find_one_by_id, COL, channel)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch purge schedules which are due to run
+    async fn fetch_due_purge_schedules(&self) -> Result<Vec<PurgeSchedule>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "next_run": {
+                    "$lte": to_bson(&Timestamp::now_utc())
+                        .map_err(|_| create_database_error!("to_bson", "timestamp"))?
+                }
+            }
+        )
+    }
+
+    /// Delete a channel's purge schedule
+    async fn delete_purge_schedule(&self, channel: &str) -> Result<()> {
+        query!(self, delete_one_by_id, COL, channel).map(|_| ())
+    }
+}
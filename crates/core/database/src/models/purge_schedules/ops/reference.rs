@@ -0,0 +1,47 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+
+use crate::PurgeSchedule;
+use crate::ReferenceDb;
+
+use super::AbstractPurgeSchedules;
+
+#[async_trait]
+impl AbstractPurgeSchedules for ReferenceDb {
+    /// Insert or replace a channel's purge schedule
+    async fn save_purge_schedule(&self, schedule: &PurgeSchedule) -> Result<()> {
+        let mut purge_schedules = self.purge_schedules.lock().await;
+        purge_schedules.insert(schedule.id.to_string(), schedule.clone());
+        Ok(())
+    }
+
+    /// Fetch a channel's purge schedule
+    async fn fetch_purge_schedule(&self, channel: &str) -> Result<PurgeSchedule> {
+        let purge_schedules = self.purge_schedules.lock().await;
+        purge_schedules
+            .get(channel)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch purge schedules which are due to run
+    async fn fetch_due_purge_schedules(&self) -> Result<Vec<PurgeSchedule>> {
+        let now = Timestamp::now_utc();
+        let purge_schedules = self.purge_schedules.lock().await;
+        Ok(purge_schedules
+            .values()
+            .filter(|schedule| *schedule.next_run <= *now)
+            .cloned()
+            .collect())
+    }
+
+    /// Delete a channel's purge schedule
+    async fn delete_purge_schedule(&self, channel: &str) -> Result<()> {
+        let mut purge_schedules = self.purge_schedules.lock().await;
+        if purge_schedules.remove(channel).is_some() {
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+}
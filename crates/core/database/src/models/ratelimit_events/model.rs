@@ -20,6 +20,10 @@ auto_derived!(
     /// Event type
     pub enum RatelimitEventType {
         DiscriminatorChange,
+        DeviceApprovalEmail,
+        AccountRecovery,
+        BackupCodeRegeneration,
+        BurstReaction,
     }
 );
 
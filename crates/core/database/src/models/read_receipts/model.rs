@@ -0,0 +1,103 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_config::config;
+use guilderia_result::Result;
+
+use crate::{events::client::EventV1, Channel, ChannelCompositeKey, Database};
+
+auto_derived!(
+    /// Most recent message a user has seen in a direct message or group
+    ///
+    /// Unlike unreads, receipts are shared with the other recipients.
+    pub struct ReadReceipt {
+        /// Composite key pointing to a user's view of a channel
+        #[serde(rename = "_id")]
+        pub id: ChannelCompositeKey,
+        /// Id of the last message seen by the user
+        pub message_id: String,
+        /// Time at which the message was seen
+        pub seen_at: Timestamp,
+    }
+);
+
+impl ReadReceipt {
+    /// Record that a user has seen a message and let the other recipients know
+    ///
+    /// Receipts only move forwards and are not kept outside of direct messages and groups.
+    pub async fn mark(db: &Database, channel: &Channel, user: &str, message: &str) -> Result<()> {
+        if !matches!(
+            channel,
+            Channel::DirectMessage { .. } | Channel::Group { .. }
+        ) || !config().await.features.read_receipts_enabled
+        {
+            return Ok(());
+        }
+
+        if let Some(receipt) = db.fetch_read_receipt(channel.id(), user).await? {
+            if receipt.message_id.as_str() >= message {
+                return Ok(());
+            }
+        }
+
+        db.save_read_receipt(&ReadReceipt {
+            id: ChannelCompositeKey {
+                channel: channel.id().to_string(),
+                user: user.to_string(),
+            },
+            message_id: message.to_string(),
+            seen_at: Timestamp::now_utc(),
+        })
+        .await?;
+
+        EventV1::MessageSeen {
+            id: channel.id().to_string(),
+            user: user.to_string(),
+            message_id: message.to_string(),
+        }
+        .p(channel.id().to_string())
+        .await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Channel, ReadReceipt};
+
+    #[async_std::test]
+    async fn receipts_only_move_forwards() {
+        database_test!(|db| async move {
+            let channel = Channel::Group {
+                id: "group".to_string(),
+                name: "Group".to_string(),
+                owner: "owner".to_string(),
+                description: None,
+                recipients: vec!["owner".to_string(), "user".to_string()],
+                icon: None,
+                last_message_id: None,
+                permissions: None,
+                nsfw: false,
+            };
+
+            for message in ["01B", "01C", "01A"] {
+                ReadReceipt::mark(&db, &channel, "user", message)
+                    .await
+                    .unwrap();
+            }
+
+            let receipts = db.fetch_read_receipts("group").await.unwrap();
+            assert_eq!(receipts.len(), 1);
+            assert_eq!(receipts[0].message_id, "01C");
+
+            let saved_messages = Channel::SavedMessages {
+                id: "saved".to_string(),
+                user: "user".to_string(),
+            };
+
+            ReadReceipt::mark(&db, &saved_messages, "user", "01A")
+                .await
+                .unwrap();
+            assert!(db.fetch_read_receipts("saved").await.unwrap().is_empty());
+        });
+    }
+}
@@ -0,0 +1,21 @@
+use guilderia_result::Result;
+
+use crate::ReadReceipt;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractReadReceipts: Sync + Send {
+    /// Insert or replace a user's read receipt
+    async fn save_read_receipt(&self, receipt: &ReadReceipt) -> Result<()>;
+
+    /// Fetch a user's read receipt in a channel
+    async fn fetch_read_receipt(&self, channel: &str, user: &str) -> Result<Option<ReadReceipt>>;
+
+    /// Fetch all read receipts in a channel
+    async fn fetch_read_receipts(&self, channel: &str) -> Result<Vec<ReadReceipt>>;
+
+    /// Delete a user's read receipt in a channel
+    async fn delete_read_receipt(&self, channel: &str, user: &str) -> Result<()>;
+}
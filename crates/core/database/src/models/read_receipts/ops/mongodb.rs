@@ -0,0 +1,67 @@
+use guilderia_result::Result;
+use mongodb::options::ReplaceOptions;
+
+use crate::MongoDb;
+use crate::ReadReceipt;
+
+use super::AbstractReadReceipts;
+
+static COL: &str = "read_receipts";
+
+#[async_trait]
+impl AbstractReadReceipts for MongoDb {
+    /// Insert or replace a user's read receipt
+    async fn save_read_receipt(&self, receipt: &ReadReceipt) -> Result<()> {
+        self.col::<ReadReceipt>(COL)
+            .replace_one(
+                doc! {
+                    "_id.channel": &receipt.id.channel,
+                    "_id.user": &receipt.id.user
+                },
+                receipt,
+            )
+            .with_options(ReplaceOptions::builder().upsert(true).build())
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("replace_one", COL))
+    }
+
+    /// Fetch a user's read receipt in a channel
+    async fn fetch_read_receipt(&self, channel: &str, user: &str) -> Result<Option<ReadReceipt>> {
+        query!(
+            self,
+            find_one,
+            COL,
+            doc! {
+                "_id.channel": channel,
+                "_id.user": user
+            }
+        )
+    }
+
+    /// Fetch all read receipts in a channel
+    async fn fetch_read_receipts(&self, channel: &str) -> Result<Vec<ReadReceipt>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "_id.channel": channel
+            }
+        )
+    }
+
+    /// Delete a user's read receipt in a channel
+    async fn delete_read_receipt(&self, channel: &str, user: &str) -> Result<()> {
+        query!(
+            self,
+            delete_one,
+            COL,
+            doc! {
+                "_id.channel": channel,
+                "_id.user": user
+            }
+        )
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,47 @@
+use guilderia_result::Result;
+
+use crate::{ChannelCompositeKey, ReadReceipt, ReferenceDb};
+
+use super::AbstractReadReceipts;
+
+#[async_trait]
+impl AbstractReadReceipts for ReferenceDb {
+    /// Insert or replace a user's read receipt
+    async fn save_read_receipt(&self, receipt: &ReadReceipt) -> Result<()> {
+        let mut read_receipts = self.read_receipts.lock().await;
+        read_receipts.insert(receipt.id.clone(), receipt.clone());
+        Ok(())
+    }
+
+    /// Fetch a user's read receipt in a channel
+    async fn fetch_read_receipt(&self, channel: &str, user: &str) -> Result<Option<ReadReceipt>> {
+        let read_receipts = self.read_receipts.lock().await;
+        Ok(read_receipts
+            .get(&ChannelCompositeKey {
+                channel: channel.to_string(),
+                user: user.to_string(),
+            })
+            .cloned())
+    }
+
+    /// Fetch all read receipts in a channel
+    async fn fetch_read_receipts(&self, channel: &str) -> Result<Vec<ReadReceipt>> {
+        let read_receipts = self.read_receipts.lock().await;
+        Ok(read_receipts
+            .values()
+            .filter(|receipt| receipt.id.channel == channel)
+            .cloned()
+            .collect())
+    }
+
+    /// Delete a user's read receipt in a channel
+    async fn delete_read_receipt(&self, channel: &str, user: &str) -> Result<()> {
+        let mut read_receipts = self.read_receipts.lock().await;
+        read_receipts.remove(&ChannelCompositeKey {
+            channel: channel.to_string(),
+            user: user.to_string(),
+        });
+
+        Ok(())
+    }
+}
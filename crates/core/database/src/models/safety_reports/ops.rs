@@ -9,4 +9,7 @@ mod reference;
 pub trait AbstractReport: Sync + Send {
     /// Insert a new report into the database
     async fn insert_report(&self, report: &Report) -> Result<()>;
+
+    /// Count reports accusing a user of ban evasion that have not been rejected
+    async fn count_ban_evasion_reports(&self, user_id: &str) -> Result<usize>;
 }
@@ -13,4 +13,22 @@ impl AbstractReport for MongoDb {
     async fn insert_report(&self, report: &Report) -> Result<()> {
         query!(self, insert_one, COL, &report).map(|_| ())
     }
+
+    /// Count reports accusing a user of ban evasion that have not been rejected
+    async fn count_ban_evasion_reports(&self, user_id: &str) -> Result<usize> {
+        query!(
+            self,
+            count_documents,
+            COL,
+            doc! {
+                "content.type": "User",
+                "content.id": user_id,
+                "content.report_reason": "BanEvasion",
+                "status": {
+                    "$ne": "Rejected"
+                }
+            }
+        )
+        .map(|count| count as usize)
+    }
 }
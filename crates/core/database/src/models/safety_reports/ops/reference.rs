@@ -1,3 +1,4 @@
+use guilderia_models::v0::{ReportStatus, ReportedContent, UserReportReason};
 use guilderia_result::Result;
 
 use crate::ReferenceDb;
@@ -17,4 +18,22 @@ impl AbstractReport for ReferenceDb {
             Ok(())
         }
     }
+
+    /// Count reports accusing a user of ban evasion that have not been rejected
+    async fn count_ban_evasion_reports(&self, user_id: &str) -> Result<usize> {
+        let reports = self.safety_reports.lock().await;
+        Ok(reports
+            .values()
+            .filter(|report| {
+                matches!(
+                    &report.content,
+                    ReportedContent::User {
+                        id,
+                        report_reason: UserReportReason::BanEvasion,
+                        ..
+                    } if id == user_id
+                ) && !matches!(report.status, ReportStatus::Rejected { .. })
+            })
+            .count())
+    }
 }
@@ -0,0 +1,69 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_config::config;
+use guilderia_result::Result;
+
+use crate::{Database, Member, MemberCompositeKey};
+
+auto_derived!(
+    /// Answers given by a member to a server's screening questions
+    pub struct ScreeningResponse {
+        /// Unique member id
+        #[serde(rename = "_id")]
+        pub id: MemberCompositeKey,
+        /// Answers to each question
+        pub answers: Vec<ScreeningAnswer>,
+        /// Time at which the answers were submitted
+        pub submitted_at: Timestamp,
+        /// Time at which the member left the server
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub left_at: Option<Timestamp>,
+    }
+
+    /// Answer to a single screening question
+    pub struct ScreeningAnswer {
+        /// Question as it was asked
+        pub question: String,
+        /// Answer given by the member
+        pub answer: String,
+    }
+);
+
+#[allow(clippy::disallowed_methods)]
+impl ScreeningResponse {
+    /// Store a member's answers, replacing any kept from a previous membership
+    pub async fn create(
+        db: &Database,
+        member: &Member,
+        questions: &[String],
+        answers: Vec<String>,
+    ) -> Result<ScreeningResponse> {
+        let response = ScreeningResponse {
+            id: member.id.clone(),
+            answers: questions
+                .iter()
+                .cloned()
+                .zip(answers)
+                .map(|(question, answer)| ScreeningAnswer { question, answer })
+                .collect(),
+            submitted_at: Timestamp::now_utc(),
+            left_at: None,
+        };
+
+        db.delete_screening_response(&response.id).await.ok();
+        db.insert_screening_response(&response).await?;
+        Ok(response)
+    }
+
+    /// Handle a member leaving the server
+    ///
+    /// Answers are purged straight away unless the
+    /// configuration asks for them to be retained.
+    pub async fn on_member_leave(db: &Database, id: &MemberCompositeKey) -> Result<()> {
+        if config().await.features.screening_response_retention_days == 0 {
+            db.delete_screening_response(id).await
+        } else {
+            db.mark_screening_response_left(id, Timestamp::now_utc())
+                .await
+        }
+    }
+}
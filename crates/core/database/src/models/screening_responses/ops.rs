@@ -0,0 +1,36 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+
+use crate::{MemberCompositeKey, ScreeningResponse};
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractScreeningResponses: Sync + Send {
+    /// Insert new screening response into database
+    async fn insert_screening_response(&self, response: &ScreeningResponse) -> Result<()>;
+
+    /// Fetch a screening response by server and user id
+    async fn fetch_screening_response(
+        &self,
+        server_id: &str,
+        user_id: &str,
+    ) -> Result<ScreeningResponse>;
+
+    /// Fetch all screening responses in a server
+    async fn fetch_screening_responses(&self, server_id: &str) -> Result<Vec<ScreeningResponse>>;
+
+    /// Mark a screening response as belonging to a member who has left
+    async fn mark_screening_response_left(
+        &self,
+        id: &MemberCompositeKey,
+        left_at: Timestamp,
+    ) -> Result<()>;
+
+    /// Delete a screening response from the database
+    async fn delete_screening_response(&self, id: &MemberCompositeKey) -> Result<()>;
+
+    /// Delete all screening responses of members who left before a given time
+    async fn delete_screening_responses_left_before(&self, before: Timestamp) -> Result<()>;
+}
@@ -0,0 +1,100 @@
+use bson::to_bson;
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+
+use crate::MongoDb;
+use crate::{MemberCompositeKey, ScreeningResponse};
+
+use super::AbstractScreeningResponses;
+
+static COL: &str = "screening_responses";
+
+#[async_trait]
+impl AbstractScreeningResponses for MongoDb {
+    /// Insert new screening response into database
+    async fn insert_screening_response(&self, response: &ScreeningResponse) -> Result<()> {
+        query!(self, insert_one, COL, &response).map(|_| ())
+    }
+
+    /// Fetch a screening response by server and user id
+    async fn fetch_screening_response(
+        &self,
+        server_id: &str,
+        user_id: &str,
+    ) -> Result<ScreeningResponse> {
+        query!(
+            self,
+            find_one,
+            COL,
+            doc! {
+                "_id.server": server_id,
+                "_id.user": user_id
+            }
+        )?
+        .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all screening responses in a server
+    async fn fetch_screening_responses(&self, server_id: &str) -> Result<Vec<ScreeningResponse>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "_id.server": server_id
+            }
+        )
+    }
+
+    /// Mark a screening response as belonging to a member who has left
+    async fn mark_screening_response_left(
+        &self,
+        id: &MemberCompositeKey,
+        left_at: Timestamp,
+    ) -> Result<()> {
+        self.col::<ScreeningResponse>(COL)
+            .update_one(
+                doc! {
+                    "_id.server": &id.server,
+                    "_id.user": &id.user
+                },
+                doc! {
+                    "$set": {
+                        "left_at": to_bson(&left_at)
+                            .map_err(|_| create_database_error!("to_bson", "timestamp"))?
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Delete a screening response from the database
+    async fn delete_screening_response(&self, id: &MemberCompositeKey) -> Result<()> {
+        query!(
+            self,
+            delete_one,
+            COL,
+            doc! {
+                "_id.server": &id.server,
+                "_id.user": &id.user
+            }
+        )
+        .map(|_| ())
+    }
+
+    /// Delete all screening responses of members who left before a given time
+    async fn delete_screening_responses_left_before(&self, before: Timestamp) -> Result<()> {
+        self.col::<ScreeningResponse>(COL)
+            .delete_many(doc! {
+                "left_at": {
+                    "$lte": to_bson(&before)
+                        .map_err(|_| create_database_error!("to_bson", "timestamp"))?
+                }
+            })
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("delete_many", COL))
+    }
+}
@@ -0,0 +1,81 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+
+use crate::ReferenceDb;
+use crate::{MemberCompositeKey, ScreeningResponse};
+
+use super::AbstractScreeningResponses;
+
+#[async_trait]
+impl AbstractScreeningResponses for ReferenceDb {
+    /// Insert new screening response into database
+    async fn insert_screening_response(&self, response: &ScreeningResponse) -> Result<()> {
+        let mut screening_responses = self.screening_responses.lock().await;
+        if screening_responses.contains_key(&response.id) {
+            Err(create_database_error!("insert", "screening_response"))
+        } else {
+            screening_responses.insert(response.id.clone(), response.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a screening response by server and user id
+    async fn fetch_screening_response(
+        &self,
+        server_id: &str,
+        user_id: &str,
+    ) -> Result<ScreeningResponse> {
+        let screening_responses = self.screening_responses.lock().await;
+        screening_responses
+            .get(&MemberCompositeKey {
+                server: server_id.to_string(),
+                user: user_id.to_string(),
+            })
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all screening responses in a server
+    async fn fetch_screening_responses(&self, server_id: &str) -> Result<Vec<ScreeningResponse>> {
+        let screening_responses = self.screening_responses.lock().await;
+        Ok(screening_responses
+            .values()
+            .filter(|response| response.id.server == server_id)
+            .cloned()
+            .collect())
+    }
+
+    /// Mark a screening response as belonging to a member who has left
+    async fn mark_screening_response_left(
+        &self,
+        id: &MemberCompositeKey,
+        left_at: Timestamp,
+    ) -> Result<()> {
+        let mut screening_responses = self.screening_responses.lock().await;
+        if let Some(response) = screening_responses.get_mut(id) {
+            response.left_at = Some(left_at);
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Delete a screening response from the database
+    async fn delete_screening_response(&self, id: &MemberCompositeKey) -> Result<()> {
+        let mut screening_responses = self.screening_responses.lock().await;
+        if screening_responses.remove(id).is_some() {
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Delete all screening responses of members who left before a given time
+    async fn delete_screening_responses_left_before(&self, before: Timestamp) -> Result<()> {
+        let mut screening_responses = self.screening_responses.lock().await;
+        screening_responses
+            .retain(|_, response| response.left_at.is_none_or(|left_at| *left_at > *before));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_models::v0;
+use guilderia_result::Result;
+
+use crate::{Database, PartialServer, ServerRecognition};
+
+auto_derived!(
+    /// Application for a server to join one of the instance's recognition programs
+    pub struct ServerApplication {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the server applying
+        pub server: String,
+        /// Id of the user who submitted the application
+        pub applicant: String,
+        /// Program the server is applying for
+        pub program: ServerProgram,
+        /// Why the server should be recognised
+        pub reason: String,
+        /// Link to more information, such as the community's website
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub url: Option<String>,
+        /// Time at which the application was submitted
+        pub created_at: Timestamp,
+        /// Current status of the application
+        pub status: ServerApplicationStatus,
+        /// Outcome of the review, once reviewed
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub review: Option<ServerApplicationReview>,
+    }
+
+    /// Program a server may apply for
+    pub enum ServerProgram {
+        /// Server is the verified community of a brand, creator or project
+        Verified,
+        /// Server is part of the partner program
+        Partnered,
+    }
+
+    /// Status of a server application
+    pub enum ServerApplicationStatus {
+        /// Waiting for review by the instance's admins
+        Pending,
+        /// Application was accepted and the server recognised
+        Approved,
+        /// Application was turned down
+        Rejected,
+    }
+
+    /// Outcome of reviewing a server application
+    pub struct ServerApplicationReview {
+        /// Id of the admin who reviewed the application
+        pub reviewer: String,
+        /// Note explaining the decision, shown to the applicant
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub note: Option<String>,
+        /// Time at which the application was reviewed
+        pub reviewed_at: Timestamp,
+    }
+);
+
+impl ServerProgram {
+    /// Flag given to servers accepted into this program
+    pub fn flag(&self) -> v0::ServerFlags {
+        match self {
+            ServerProgram::Verified => v0::ServerFlags::Verified,
+            ServerProgram::Partnered => v0::ServerFlags::Partnered,
+        }
+    }
+}
+
+impl ServerApplication {
+    /// Approve or reject a pending application
+    ///
+    /// Approved servers are given the program's flag, keeping any details
+    /// already shown alongside their other flags.
+    pub async fn review(
+        &mut self,
+        db: &Database,
+        reviewer: &str,
+        approve: bool,
+        note: Option<String>,
+        description: Option<String>,
+    ) -> Result<()> {
+        let review = ServerApplicationReview {
+            reviewer: reviewer.to_string(),
+            note,
+            reviewed_at: Timestamp::now_utc(),
+        };
+
+        let status = if approve {
+            ServerApplicationStatus::Approved
+        } else {
+            ServerApplicationStatus::Rejected
+        };
+
+        db.review_server_application(&self.id, status.clone(), &review)
+            .await?;
+
+        self.status = status;
+        self.review = Some(review);
+
+        if approve {
+            let mut server = db.fetch_server(&self.server).await?;
+            let flags = server.flags.unwrap_or_default() | self.program.flag() as i32;
+            let recognition = match server.recognition.clone() {
+                Some(existing) => ServerRecognition {
+                    description: description.or(existing.description),
+                    url: self.url.clone().or(existing.url),
+                    since: existing.since,
+                },
+                None => ServerRecognition {
+                    description,
+                    url: self.url.clone(),
+                    since: Timestamp::now_utc(),
+                },
+            };
+
+            server
+                .update(
+                    db,
+                    PartialServer {
+                        flags: Some(flags),
+                        recognition: Some(recognition),
+                        ..Default::default()
+                    },
+                    vec![],
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use guilderia_models::v0;
+    use iso8601_timestamp::Timestamp;
+
+    use crate::{Server, ServerApplication, ServerApplicationStatus, ServerProgram, User};
+
+    #[async_std::test]
+    async fn approval_flags_server() {
+        database_test!(|db| async move {
+            let owner = User::create(&db, "owner".to_string(), None, None)
+                .await
+                .unwrap();
+            let (server, _) = Server::create(
+                &db,
+                v0::DataCreateServer {
+                    name: "Server".to_string(),
+                    ..Default::default()
+                },
+                &owner,
+                false,
+            )
+            .await
+            .unwrap();
+
+            let mut application = ServerApplication {
+                id: "application".to_string(),
+                server: server.id.clone(),
+                applicant: owner.id.clone(),
+                program: ServerProgram::Partnered,
+                reason: "We are great".to_string(),
+                url: Some("https://example.com".to_string()),
+                created_at: Timestamp::now_utc(),
+                status: ServerApplicationStatus::Pending,
+                review: None,
+            };
+
+            db.insert_server_application(&application).await.unwrap();
+            application
+                .review(&db, "admin", true, None, Some("Example".to_string()))
+                .await
+                .unwrap();
+
+            assert_eq!(application.status, ServerApplicationStatus::Approved);
+            assert!(db
+                .fetch_server_applications(ServerApplicationStatus::Pending)
+                .await
+                .unwrap()
+                .is_empty());
+
+            let server = db.fetch_server(&server.id).await.unwrap();
+            assert_eq!(server.flags, Some(v0::ServerFlags::Partnered as i32));
+            let recognition = server.recognition.unwrap();
+            assert_eq!(recognition.description.as_deref(), Some("Example"));
+            assert_eq!(recognition.url.as_deref(), Some("https://example.com"));
+
+            // Applications may only be reviewed once
+            assert!(application
+                .review(&db, "admin", false, None, None)
+                .await
+                .is_err());
+        });
+    }
+}
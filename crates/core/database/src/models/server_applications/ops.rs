@@ -0,0 +1,35 @@
+use guilderia_result::Result;
+
+use crate::{ServerApplication, ServerApplicationReview, ServerApplicationStatus};
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractServerApplications: Sync + Send {
+    /// Insert a new server application
+    async fn insert_server_application(&self, application: &ServerApplication) -> Result<()>;
+
+    /// Fetch a server application by its id
+    async fn fetch_server_application(&self, id: &str) -> Result<ServerApplication>;
+
+    /// Fetch server applications with a given status, oldest first
+    async fn fetch_server_applications(
+        &self,
+        status: ServerApplicationStatus,
+    ) -> Result<Vec<ServerApplication>>;
+
+    /// Fetch all applications submitted by a server
+    async fn fetch_server_applications_by_server(
+        &self,
+        server: &str,
+    ) -> Result<Vec<ServerApplication>>;
+
+    /// Record the outcome of reviewing a pending server application
+    async fn review_server_application(
+        &self,
+        id: &str,
+        status: ServerApplicationStatus,
+        review: &ServerApplicationReview,
+    ) -> Result<()>;
+}
@@ -0,0 +1,92 @@
+use bson::{to_bson, to_document};
+use guilderia_result::Result;
+use mongodb::options::FindOptions;
+
+use crate::MongoDb;
+use crate::{ServerApplication, ServerApplicationReview, ServerApplicationStatus};
+
+use super::AbstractServerApplications;
+
+static COL: &str = "server_applications";
+
+#[async_trait]
+impl AbstractServerApplications for MongoDb {
+    /// Insert a new server application
+    async fn insert_server_application(&self, application: &ServerApplication) -> Result<()> {
+        query!(self, insert_one, COL, &application).map(|_| ())
+    }
+
+    /// Fetch a server application by its id
+    async fn fetch_server_application(&self, id: &str) -> Result<ServerApplication> {
+        query!(self, find_one_by_id, COL, id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch server applications with a given status, oldest first
+    async fn fetch_server_applications(
+        &self,
+        status: ServerApplicationStatus,
+    ) -> Result<Vec<ServerApplication>> {
+        query!(
+            self,
+            find_with_options,
+            COL,
+            doc! {
+                "status": to_bson(&status)
+                    .map_err(|_| create_database_error!("to_bson", "status"))?
+            },
+            FindOptions::builder()
+                .sort(doc! {
+                    "_id": 1_i32
+                })
+                .build()
+        )
+    }
+
+    /// Fetch all applications submitted by a server
+    async fn fetch_server_applications_by_server(
+        &self,
+        server: &str,
+    ) -> Result<Vec<ServerApplication>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "server": server
+            }
+        )
+    }
+
+    /// Record the outcome of reviewing a pending server application
+    async fn review_server_application(
+        &self,
+        id: &str,
+        status: ServerApplicationStatus,
+        review: &ServerApplicationReview,
+    ) -> Result<()> {
+        let result = self
+            .col::<ServerApplication>(COL)
+            .update_one(
+                doc! {
+                    "_id": id,
+                    "status": "Pending"
+                },
+                doc! {
+                    "$set": {
+                        "status": to_bson(&status)
+                            .map_err(|_| create_database_error!("to_bson", "status"))?,
+                        "review": to_document(review)
+                            .map_err(|_| create_database_error!("to_document", "review"))?
+                    }
+                },
+            )
+            .await
+            .map_err(|_| create_database_error!("update_one", COL))?;
+
+        if result.matched_count == 0 {
+            Err(create_error!(NotFound))
+        } else {
+            Ok(())
+        }
+    }
+}
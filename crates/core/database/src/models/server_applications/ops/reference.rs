@@ -0,0 +1,76 @@
+use guilderia_result::Result;
+
+use crate::ReferenceDb;
+use crate::{ServerApplication, ServerApplicationReview, ServerApplicationStatus};
+
+use super::AbstractServerApplications;
+
+#[async_trait]
+impl AbstractServerApplications for ReferenceDb {
+    /// Insert a new server application
+    async fn insert_server_application(&self, application: &ServerApplication) -> Result<()> {
+        let mut server_applications = self.server_applications.lock().await;
+        if server_applications.contains_key(&application.id) {
+            Err(create_database_error!("insert", "server_applications"))
+        } else {
+            server_applications.insert(application.id.to_string(), application.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a server application by its id
+    async fn fetch_server_application(&self, id: &str) -> Result<ServerApplication> {
+        let server_applications = self.server_applications.lock().await;
+        server_applications
+            .get(id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch server applications with a given status, oldest first
+    async fn fetch_server_applications(
+        &self,
+        status: ServerApplicationStatus,
+    ) -> Result<Vec<ServerApplication>> {
+        let server_applications = self.server_applications.lock().await;
+        let mut applications: Vec<ServerApplication> = server_applications
+            .values()
+            .filter(|application| application.status == status)
+            .cloned()
+            .collect();
+
+        applications.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(applications)
+    }
+
+    /// Fetch all applications submitted by a server
+    async fn fetch_server_applications_by_server(
+        &self,
+        server: &str,
+    ) -> Result<Vec<ServerApplication>> {
+        let server_applications = self.server_applications.lock().await;
+        Ok(server_applications
+            .values()
+            .filter(|application| application.server == server)
+            .cloned()
+            .collect())
+    }
+
+    /// Record the outcome of reviewing a pending server application
+    async fn review_server_application(
+        &self,
+        id: &str,
+        status: ServerApplicationStatus,
+        review: &ServerApplicationReview,
+    ) -> Result<()> {
+        let mut server_applications = self.server_applications.lock().await;
+        match server_applications.get_mut(id) {
+            Some(application) if application.status == ServerApplicationStatus::Pending => {
+                application.status = status;
+                application.review = Some(review.clone());
+                Ok(())
+            }
+            _ => Err(create_error!(NotFound)),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use guilderia_result::Result;
+use iso8601_timestamp::Timestamp;
 
 use crate::{Database, MemberCompositeKey, Server};
 
@@ -10,6 +11,12 @@ auto_derived!(
         pub id: MemberCompositeKey,
         /// Reason for ban creation
         pub reason: Option<String>,
+        /// When this ban was created
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub created_at: Option<Timestamp>,
+        /// Id of the ban subscription which applied this ban
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub subscription: Option<String>,
     }
 );
 
@@ -28,6 +35,8 @@ impl ServerBan {
                 user: user_id.to_string(),
             },
             reason,
+            created_at: Some(Timestamp::now_utc()),
+            subscription: None,
         };
 
         db.insert_ban(&ban).await?;
@@ -1,10 +1,13 @@
 use iso8601_timestamp::Timestamp;
-use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_config::config;
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission, OverrideField};
 use guilderia_result::{create_error, Result};
+use ulid::Ulid;
 
 use crate::{
-    events::client::EventV1, util::permissions::DatabasePermissionQuery, Channel, Database, File,
-    Server, SystemMessage, User,
+    events::client::EventV1, util::permissions::DatabasePermissionQuery, Bot, Channel, Database,
+    File, PartialRole, Role, ScreeningResponse, Server, SystemMessage, SystemMessageChannels, User,
 };
 
 auto_derived_partial!(
@@ -30,6 +33,25 @@ auto_derived_partial!(
         /// Timestamp this member is timed out until
         #[serde(skip_serializing_if = "Option::is_none")]
         pub timeout: Option<Timestamp>,
+
+        /// Server badges given to this member
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pub badges: Vec<String>,
+
+        /// Roles held by this member which will be removed at a later time
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pub role_expiries: Vec<RoleExpiry>,
+
+        /// Roles this member does not want to be pinged by
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pub role_mention_optouts: Vec<String>,
+        /// Whether to display the server tag next to this member's name
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub show_server_tag: bool,
+
+        /// Permissions granted to this member if it is a bot
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub bot_scope: Option<BotScope>,
     },
     "PartialMember"
 );
@@ -44,12 +66,30 @@ auto_derived!(
         pub user: String,
     }
 
+    /// Role assigned to a member until a given time
+    pub struct RoleExpiry {
+        /// Role Id
+        pub role: String,
+        /// Time at which the role is removed
+        pub expires_at: Timestamp,
+    }
+
+    /// Permissions granted to a bot when it was added to a server
+    pub struct BotScope {
+        /// Id of the role holding the granted permissions
+        pub role: String,
+        /// Permissions granted to the bot
+        pub permissions: i64,
+    }
+
     /// Optional fields on server member object
     pub enum FieldsMember {
         Nickname,
         Avatar,
         Roles,
         Timeout,
+        RoleMentionOptouts,
+        Badges,
     }
 
     /// Member removal intention
@@ -68,7 +108,12 @@ impl Default for Member {
             nickname: None,
             avatar: None,
             roles: vec![],
+            role_expiries: vec![],
             timeout: None,
+            badges: vec![],
+            role_mention_optouts: vec![],
+            show_server_tag: false,
+            bot_scope: None,
         }
     }
 }
@@ -99,12 +144,20 @@ impl Member {
         };
 
         db.insert_member(&member).await?;
+        db.adjust_server_counts(
+            &[server.id.clone()],
+            1,
+            user.is_visibly_online().await as i64,
+        )
+        .await?;
 
         let should_fetch = channels.is_none();
         let mut channels = channels.unwrap_or_default();
 
         if should_fetch {
-            let query = DatabasePermissionQuery::new(db, user).server(server);
+            let query = DatabasePermissionQuery::new(db, user)
+                .server(server)
+                .member(&member);
             let existing_channels = db.fetch_channels(&server.channels).await?;
 
             for channel in existing_channels {
@@ -141,23 +194,114 @@ impl Member {
         .private(user.id.clone())
         .await;
 
+        let message = SystemMessage::UserJoined {
+            id: user.id.clone(),
+            content: None,
+        };
+
+        if let Some(id) =
+            SystemMessageChannels::route(server.system_messages.as_ref(), &message, None)
+        {
+            let mut message = server
+                .render_system_message(db, message, &user.id)
+                .await
+                .into_message(id);
+
+            if server
+                .system_messages
+                .as_ref()
+                .is_some_and(|config| config.welcome_image)
+            {
+                match server.create_welcome_image(db, user, &message.id).await {
+                    Ok(file) => message.attachments = Some(vec![file]),
+                    Err(err) => warn!("Failed to create welcome image in {}: {err:?}", server.id),
+                }
+            }
+
+            message
+                .send_without_notifications(db, None, None, false, false, false)
+                .await
+                .ok();
+        }
+
         if let Some(id) = server
             .system_messages
             .as_ref()
-            .and_then(|x| x.user_joined.as_ref())
+            .and_then(|x| x.moderation_alerts.as_ref())
         {
-            SystemMessage::UserJoined {
-                id: user.id.clone(),
+            let factors = Member::alt_account_risk_factors(db, server, user)
+                .await
+                .unwrap_or_default();
+
+            if !factors.is_empty() {
+                SystemMessage::Text {
+                    content: format!(
+                        "<@{}> joined and may be an alternate account:\n- {}",
+                        user.id,
+                        factors.join("\n- ")
+                    ),
+                }
+                .into_message(id.to_string())
+                .send_without_notifications(db, None, None, false, false, false)
+                .await
+                .ok();
             }
-            .into_message(id.to_string())
-            .send_without_notifications(db, None, None, false, false, false)
-            .await
-            .ok();
         }
 
         Ok((member, channels))
     }
 
+    /// Find reasons a newly joined user may be an alternate account
+    ///
+    /// Considers account age, ban evasion reports and
+    /// whether the username matches someone banned from the server.
+    pub async fn alt_account_risk_factors(
+        db: &Database,
+        server: &Server,
+        user: &User,
+    ) -> Result<Vec<String>> {
+        let mut factors = vec![];
+        if user.bot.is_some() {
+            return Ok(factors);
+        }
+
+        let config = config().await;
+        if let Ok(age) = Ulid::from_string(&user.id)
+            .map_err(|_| ())
+            .and_then(|id| id.datetime().elapsed().map_err(|_| ()))
+        {
+            let hours = age.as_secs() / 3600;
+            if hours < config.features.alt_account_alerts.account_age_hours {
+                factors.push(format!("Account was created {hours} hours ago"));
+            }
+        }
+
+        let reports = db.count_ban_evasion_reports(&user.id).await?;
+        if reports > 0 {
+            factors.push(format!("Reported for ban evasion {reports} time(s)"));
+        }
+
+        let banned_ids: Vec<String> = db
+            .fetch_bans(&server.id)
+            .await?
+            .into_iter()
+            .map(|ban| ban.id.user)
+            .collect();
+
+        if !banned_ids.is_empty() {
+            for banned in db.fetch_users(&banned_ids).await? {
+                if banned.username.eq_ignore_ascii_case(&user.username) {
+                    factors.push(format!(
+                        "Shares a username with banned user <@{}>",
+                        banned.id
+                    ));
+                }
+            }
+        }
+
+        Ok(factors)
+    }
+
     /// Update member data
     pub async fn update(
         &mut self,
@@ -184,15 +328,189 @@ impl Member {
         Ok(())
     }
 
+    /// Grant this bot member a subset of the permissions it requested
+    ///
+    /// Granted permissions are held by a role created for the bot,
+    /// which is reused when the scope is later adjusted.
+    pub async fn set_bot_scope(
+        &mut self,
+        db: &Database,
+        server: &Server,
+        bot: &Bot,
+        name: &str,
+        permissions: u64,
+    ) -> Result<()> {
+        if permissions & !(bot.permissions.unwrap_or_default() as u64) != 0 {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        let allow = OverrideField {
+            a: permissions as i64,
+            d: 0,
+        };
+
+        let existing = self
+            .bot_scope
+            .as_ref()
+            .and_then(|scope| server.roles.get_key_value(&scope.role));
+
+        let role_id = if let Some((id, role)) = existing {
+            role.clone()
+                .update(
+                    db,
+                    &server.id,
+                    id,
+                    PartialRole {
+                        permissions: Some(allow),
+                        ..Default::default()
+                    },
+                    vec![],
+                )
+                .await?;
+
+            id.clone()
+        } else {
+            Role {
+                name: name.to_string(),
+                permissions: allow,
+                colour: None,
+                hoist: false,
+                group: None,
+                rank: server
+                    .roles
+                    .values()
+                    .map(|role| role.rank)
+                    .max()
+                    .unwrap_or_default()
+                    .saturating_add(1),
+            }
+            .create(db, &server.id)
+            .await?
+        };
+
+        let mut roles = self.roles.clone();
+        if !roles.contains(&role_id) {
+            roles.push(role_id.clone());
+        }
+
+        self.update(
+            db,
+            PartialMember {
+                roles: Some(roles),
+                bot_scope: Some(BotScope {
+                    role: role_id,
+                    permissions: permissions as i64,
+                }),
+                ..Default::default()
+            },
+            vec![],
+        )
+        .await
+    }
+
+    /// Assign a role to this member, optionally removing it again at a given time
+    ///
+    /// Assigning a role without an expiry makes it permanent.
+    pub async fn assign_role(
+        &mut self,
+        db: &Database,
+        role_id: &str,
+        expires_at: Option<Timestamp>,
+    ) -> Result<()> {
+        let mut roles = self.roles.clone();
+        if !roles.iter().any(|id| id == role_id) {
+            roles.push(role_id.to_string());
+        }
+
+        let mut role_expiries: Vec<RoleExpiry> = self
+            .role_expiries
+            .iter()
+            .filter(|expiry| expiry.role != role_id)
+            .cloned()
+            .collect();
+
+        if let Some(expires_at) = expires_at {
+            role_expiries.push(RoleExpiry {
+                role: role_id.to_string(),
+                expires_at,
+            });
+        }
+
+        self.update(
+            db,
+            PartialMember {
+                roles: Some(roles),
+                role_expiries: Some(role_expiries),
+                ..Default::default()
+            },
+            vec![],
+        )
+        .await
+    }
+
+    /// Remove any roles from this member which have expired
+    ///
+    /// Returns the ids of the roles which were removed.
+    pub async fn remove_expired_roles(&mut self, db: &Database) -> Result<Vec<String>> {
+        let now = Timestamp::now_utc();
+        let (expired, role_expiries): (Vec<RoleExpiry>, Vec<RoleExpiry>) = self
+            .role_expiries
+            .iter()
+            .cloned()
+            .partition(|expiry| *expiry.expires_at <= *now);
+
+        if expired.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let removed: Vec<String> = expired
+            .into_iter()
+            .map(|expiry| expiry.role)
+            .filter(|role| self.roles.contains(role))
+            .collect();
+
+        let roles = self
+            .roles
+            .iter()
+            .filter(|role| !removed.contains(role))
+            .cloned()
+            .collect();
+
+        self.update(
+            db,
+            PartialMember {
+                roles: Some(roles),
+                role_expiries: Some(role_expiries),
+                ..Default::default()
+            },
+            vec![],
+        )
+        .await?;
+
+        Ok(removed)
+    }
+
     pub fn remove_field(&mut self, field: &FieldsMember) {
         match field {
             FieldsMember::Avatar => self.avatar = None,
             FieldsMember::Nickname => self.nickname = None,
             FieldsMember::Roles => self.roles.clear(),
             FieldsMember::Timeout => self.timeout = None,
+            FieldsMember::RoleMentionOptouts => self.role_mention_optouts.clear(),
+            FieldsMember::Badges => self.badges.clear(),
         }
     }
 
+    /// Check whether this member should be notified by a mention of the given roles
+    ///
+    /// A member is notified if they hold at least one of the mentioned roles
+    /// which they have not opted out of.
+    pub fn is_notified_by_role_mentions(&self, roles: &[String]) -> bool {
+        roles
+            .iter()
+            .any(|role| self.roles.contains(role) && !self.role_mention_optouts.contains(role))
+    }
+
     /// Get this user's current ranking
     pub fn get_ranking(&self, server: &Server) -> i64 {
         let mut value = i64::MAX;
@@ -216,6 +534,20 @@ impl Member {
         }
     }
 
+    /// Convert into API model, including the server's tag if this member displays it
+    pub fn into_model_with_server(self, server: &Server) -> v0::Member {
+        let server_tag = if self.show_server_tag {
+            server.tag.clone()
+        } else {
+            None
+        };
+
+        v0::Member {
+            server_tag,
+            ..self.into()
+        }
+    }
+
     /// Remove member from server
     pub async fn remove(
         self,
@@ -226,6 +558,17 @@ impl Member {
     ) -> Result<()> {
         db.delete_member(&self.id).await?;
 
+        let online = match db.fetch_user(&self.id.user).await {
+            Ok(user) => user.is_visibly_online().await,
+            Err(_) => false,
+        };
+
+        db.adjust_server_counts(&[server.id.clone()], -1, -(online as i64))
+            .await?;
+
+        // Members who joined without answering any questions have nothing to purge
+        ScreeningResponse::on_member_leave(db, &self.id).await.ok();
+
         EventV1::ServerMemberLeave {
             id: self.id.server.to_string(),
             user: self.id.user.to_string(),
@@ -235,25 +578,30 @@ impl Member {
         .await;
 
         if !silent {
-            if let Some(id) = server
-                .system_messages
-                .as_ref()
-                .and_then(|x| match intention {
-                    RemovalIntention::Leave => x.user_left.as_ref(),
-                    RemovalIntention::Kick => x.user_kicked.as_ref(),
-                    RemovalIntention::Ban => x.user_banned.as_ref(),
-                })
+            let message = match intention {
+                RemovalIntention::Leave => SystemMessage::UserLeft {
+                    id: self.id.user.clone(),
+                    content: None,
+                },
+                RemovalIntention::Kick => SystemMessage::UserKicked {
+                    id: self.id.user.clone(),
+                },
+                RemovalIntention::Ban => SystemMessage::UserBanned {
+                    id: self.id.user.clone(),
+                },
+            };
+
+            if let Some(id) =
+                SystemMessageChannels::route(server.system_messages.as_ref(), &message, None)
             {
-                match intention {
-                    RemovalIntention::Leave => SystemMessage::UserLeft { id: self.id.user },
-                    RemovalIntention::Kick => SystemMessage::UserKicked { id: self.id.user },
-                    RemovalIntention::Ban => SystemMessage::UserBanned { id: self.id.user },
-                }
-                .into_message(id.to_string())
-                // TODO: support notifications here in the future?
-                .send_without_notifications(db, None, None, false, false, false)
-                .await
-                .ok();
+                server
+                    .render_system_message(db, message, &self.id.user)
+                    .await
+                    .into_message(id)
+                    // TODO: support notifications here in the future?
+                    .send_without_notifications(db, None, None, false, false, false)
+                    .await
+                    .ok();
             }
         }
 
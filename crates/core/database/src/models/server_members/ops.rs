@@ -101,6 +101,17 @@ pub trait AbstractServerMembers: Sync + Send {
     /// Fetch multiple members by their ids
     async fn fetch_members<'a>(&self, server_id: &str, ids: &'a [String]) -> Result<Vec<Member>>;
 
+    /// Fetch all members holding roles which have expired
+    async fn fetch_members_with_expired_roles(&self) -> Result<Vec<Member>>;
+
+    /// Search members whose nickname or username starts with the given prefix
+    async fn search_members(
+        &self,
+        server_id: &str,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Member>>;
+
     /// Fetch member count of a server
     async fn fetch_member_count(&self, server_id: &str) -> Result<usize>;
 
@@ -1,5 +1,7 @@
+use bson::{to_bson, Document};
 use futures::StreamExt;
-use mongodb::options::ReadConcern;
+use iso8601_timestamp::Timestamp;
+use mongodb::options::{FindOptions, ReadConcern};
 use guilderia_result::Result;
 
 use crate::{FieldsMember, Member, MemberCompositeKey, PartialMember};
@@ -9,6 +11,9 @@ use super::{AbstractServerMembers, ChunkedServerMembersGenerator};
 
 static COL: &str = "server_members";
 
+/// Maximum number of users matching a username to check membership of
+const SEARCH_USER_CANDIDATES: i64 = 100;
+
 #[async_trait]
 impl AbstractServerMembers for MongoDb {
     /// Insert a new server member into the database
@@ -181,6 +186,71 @@ impl AbstractServerMembers for MongoDb {
             .await)
     }
 
+    /// Search members whose nickname or username starts with the given prefix
+    async fn search_members(
+        &self,
+        server_id: &str,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Member>> {
+        let pattern = doc! {
+            "$regex": format!("^{}", regex::escape(prefix)),
+            "$options": "i"
+        };
+
+        let mut members: Vec<Member> = query!(
+            self,
+            find_with_options,
+            COL,
+            doc! {
+                "_id.server": server_id,
+                "nickname": pattern.clone()
+            },
+            FindOptions::builder().limit(limit as i64).build()
+        )?;
+
+        // Usernames live on the user, so find candidates there first
+        let user_ids: Vec<String> = self
+            .col::<Document>("users")
+            .find(doc! {
+                "username": pattern
+            })
+            .projection(doc! { "_id": 1_i32 })
+            .limit(SEARCH_USER_CANDIDATES)
+            .await
+            .map_err(|_| create_database_error!("find", "users"))?
+            .filter_map(|s| async { s.ok()?.get_str("_id").ok().map(ToString::to_string) })
+            .collect()
+            .await;
+
+        for member in self.fetch_members(server_id, &user_ids).await? {
+            if members.len() >= limit {
+                break;
+            }
+
+            if !members.iter().any(|existing| existing.id == member.id) {
+                members.push(member);
+            }
+        }
+
+        Ok(members)
+    }
+
+    /// Fetch all members holding roles which have expired
+    async fn fetch_members_with_expired_roles(&self) -> Result<Vec<Member>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "role_expiries.expires_at": {
+                    "$lte": to_bson(&Timestamp::now_utc())
+                        .map_err(|_| create_database_error!("to_bson", "timestamp"))?
+                }
+            }
+        )
+    }
+
     /// Fetch member count of a server
     async fn fetch_member_count(&self, server_id: &str) -> Result<usize> {
         self.col::<Member>(COL)
@@ -247,6 +317,8 @@ impl IntoDocumentPath for FieldsMember {
             FieldsMember::Nickname => "nickname",
             FieldsMember::Roles => "roles",
             FieldsMember::Timeout => "timeout",
+            FieldsMember::RoleMentionOptouts => "role_mention_optouts",
+            FieldsMember::Badges => "badges",
         })
     }
 }
@@ -1,4 +1,5 @@
 use guilderia_result::Result;
+use iso8601_timestamp::Timestamp;
 
 use crate::ReferenceDb;
 use crate::{FieldsMember, Member, MemberCompositeKey, PartialMember};
@@ -130,6 +131,49 @@ impl AbstractServerMembers for ReferenceDb {
             .collect())
     }
 
+    /// Search members whose nickname or username starts with the given prefix
+    async fn search_members(
+        &self,
+        server_id: &str,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Member>> {
+        let prefix = prefix.to_lowercase();
+        let server_members = self.server_members.lock().await;
+        let users = self.users.lock().await;
+        Ok(server_members
+            .values()
+            .filter(|member| {
+                member.id.server == server_id
+                    && (member
+                        .nickname
+                        .as_ref()
+                        .is_some_and(|nickname| nickname.to_lowercase().starts_with(&prefix))
+                        || users
+                            .get(&member.id.user)
+                            .is_some_and(|user| user.username.to_lowercase().starts_with(&prefix)))
+            })
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    /// Fetch all members holding roles which have expired
+    async fn fetch_members_with_expired_roles(&self) -> Result<Vec<Member>> {
+        let now = Timestamp::now_utc();
+        let server_members = self.server_members.lock().await;
+        Ok(server_members
+            .values()
+            .filter(|member| {
+                member
+                    .role_expiries
+                    .iter()
+                    .any(|expiry| *expiry.expires_at <= *now)
+            })
+            .cloned()
+            .collect())
+    }
+
     /// Fetch member count of a server
     async fn fetch_member_count(&self, server_id: &str) -> Result<usize> {
         let server_members = self.server_members.lock().await;
@@ -1,11 +1,24 @@
 use std::collections::{HashMap, HashSet};
 
+use iso8601_timestamp::Timestamp;
+use guilderia_files::{create_welcome_image, fetch_image, image_size_vec};
 use guilderia_models::v0::{self, DataCreateServerChannel};
 use guilderia_permissions::{OverrideField, DEFAULT_PERMISSION_SERVER};
+use guilderia_presence::filter_online;
 use guilderia_result::Result;
+use rand::seq::SliceRandom;
 use ulid::Ulid;
 
-use crate::{events::client::EventV1, Channel, Database, File, User};
+use crate::{
+    events::client::EventV1, tasks, AuditLogAction, AuditLogEntry, Channel, Database, File,
+    Metadata, SystemMessage, User,
+};
+
+/// Number of times to retry reordering channels which were changed concurrently
+const REORDER_ATTEMPTS: usize = 5;
+
+/// Number of members checked at once while reconciling member counts
+const RECONCILE_BATCH_SIZE: usize = 1000;
 
 auto_derived_partial!(
     /// Server
@@ -21,6 +34,9 @@ auto_derived_partial!(
         /// Description for the server
         #[serde(skip_serializing_if = "Option::is_none")]
         pub description: Option<String>,
+        /// Short tag displayed next to the names of members who opt in
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub tag: Option<String>,
 
         /// Channels within this server
         // TODO: investigate if this is redundant and can be removed
@@ -31,6 +47,18 @@ auto_derived_partial!(
         /// Configuration for sending system event messages
         #[serde(skip_serializing_if = "Option::is_none")]
         pub system_messages: Option<SystemMessageChannels>,
+        /// Configuration for highlighting popular messages
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub starboard: Option<Starboard>,
+        /// Restrictions applied to members who are new to the platform or server
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub new_member_restrictions: Option<NewMemberRestrictions>,
+        /// Limit on the number of users a single message may mention
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub mention_limit: Option<MentionLimit>,
+        /// Filter for words and phrases the server does not allow
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub content_filter: Option<ContentFilter>,
 
         /// Roles for this server
         #[serde(
@@ -38,6 +66,12 @@ auto_derived_partial!(
             skip_serializing_if = "HashMap::<String, Role>::is_empty"
         )]
         pub roles: HashMap<String, Role>,
+        /// Badges which can be given to members of this server
+        #[serde(
+            default = "HashMap::<String, ServerBadge>::new",
+            skip_serializing_if = "HashMap::<String, ServerBadge>::is_empty"
+        )]
+        pub badges: HashMap<String, ServerBadge>,
         /// Default set of server and channel permissions
         pub default_permissions: i64,
 
@@ -51,6 +85,9 @@ auto_derived_partial!(
         /// Bitfield of server flags
         #[serde(skip_serializing_if = "Option::is_none")]
         pub flags: Option<i32>,
+        /// Details shown alongside the server's flags
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub recognition: Option<ServerRecognition>,
 
         /// Whether this server is flagged as not safe for work
         #[serde(skip_serializing_if = "crate::if_false", default)]
@@ -61,6 +98,35 @@ auto_derived_partial!(
         /// Whether this server should be publicly discoverable
         #[serde(skip_serializing_if = "crate::if_false", default)]
         pub discoverable: bool,
+
+        /// Notification mode for members who have not chosen their own
+        #[serde(default)]
+        pub default_notifications: NotificationMode,
+        /// Whether new emoji must be approved before they can be used
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub require_emoji_approval: bool,
+        /// Whether new members must accept the server rules before joining
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub require_rules_acceptance: bool,
+        /// Questions new members must answer before joining
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pub screening_questions: Vec<String>,
+        /// Whether text-to-speech messages are disabled in this server
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub disable_tts: bool,
+        /// Whether destructive actions need a second administrator to approve them
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub require_action_approval: bool,
+        /// Whether other servers may subscribe to this server's bans
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub share_bans: bool,
+
+        /// Number of members in this server
+        #[serde(default)]
+        pub member_count: i64,
+        /// Number of members currently online
+        #[serde(default)]
+        pub online_count: i64,
     },
     "PartialServer"
 );
@@ -80,6 +146,9 @@ auto_derived_partial!(
         /// Whether this role should be shown separately on the member sidebar
         #[serde(skip_serializing_if = "crate::if_false", default)]
         pub hoist: bool,
+        /// Name of the group this role is organised under
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub group: Option<String>,
         /// Ranking of this role
         #[serde(default)]
         pub rank: i64,
@@ -88,6 +157,20 @@ auto_derived_partial!(
 );
 
 auto_derived!(
+    /// Badge which can be given to server members
+    pub struct ServerBadge {
+        /// Badge name
+        pub name: String,
+        /// Icon attachment
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub icon: Option<File>,
+        /// Colour used for this badge
+        ///
+        /// This can be any valid CSS colour
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub colour: Option<String>,
+    }
+
     /// Channel category
     pub struct Category {
         /// Unique ID for this category
@@ -96,9 +179,103 @@ auto_derived!(
         pub title: String,
         /// Channels in this category
         pub channels: Vec<String>,
+
+        /// Default permissions for channels synced with this category
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub default_permissions: Option<OverrideField>,
+        /// Role permissions for channels synced with this category
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        pub role_permissions: HashMap<String, OverrideField>,
+    }
+
+    /// Starboard configuration
+    pub struct Starboard {
+        /// Emoji which counts towards highlighting a message
+        pub emoji: String,
+        /// Number of reactions required to highlight a message
+        pub threshold: u32,
+        /// Id of the channel highlights are posted to
+        pub channel: String,
+    }
+
+    /// Restrictions applied to new members
+    ///
+    /// A member is new if either their account or their membership
+    /// is younger than the respective threshold.
+    pub struct NewMemberRestrictions {
+        /// Accounts younger than this many minutes are restricted
+        pub account_age_minutes: u32,
+        /// Memberships younger than this many minutes are restricted
+        pub membership_age_minutes: u32,
+        /// Minimum number of seconds between messages from a restricted member
+        pub message_interval_seconds: u32,
+        /// Whether restricted members may not post links
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub block_links: bool,
+        /// Whether restricted members may not upload attachments
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub block_attachments: bool,
+    }
+
+    /// Limit on the number of users a single message may mention
+    ///
+    /// Reply mentions do not count towards the limit.
+    pub struct MentionLimit {
+        /// Maximum number of unique users a message may mention
+        pub max: u32,
+        /// What to do with messages over the limit
+        pub action: MentionLimitAction,
+    }
+
+    /// Action taken against messages which mention too many users
+    pub enum MentionLimitAction {
+        /// Reject the message
+        Block,
+        /// Send the message without notifying any of the mentioned users
+        Strip,
+    }
+
+    /// Details shown alongside a server's flags, managed by the instance's admins
+    pub struct ServerRecognition {
+        /// Short description of who the server represents
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub description: Option<String>,
+        /// Link to more information, such as the community's website
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub url: Option<String>,
+        /// Time at which the server was first recognised
+        pub since: Timestamp,
+    }
+
+    /// Filter for words and phrases the server does not allow
+    ///
+    /// Words are matched whole, ignoring case and common character substitutions.
+    pub struct ContentFilter {
+        /// Words and phrases to filter, a trailing `*` also matches longer words
+        #[serde(default)]
+        pub words: Vec<String>,
+        /// Words which are never filtered, even if they are on the instance list
+        #[serde(default)]
+        pub allow: Vec<String>,
+        /// Whether to also filter words on the instance list
+        #[serde(default)]
+        pub use_base_list: bool,
+        /// What to do with messages containing filtered words
+        pub action: ContentFilterAction,
+    }
+
+    /// Action taken against messages containing filtered words
+    pub enum ContentFilterAction {
+        /// Send the message with filtered words blacked out
+        Censor,
+        /// Reject the message
+        Block,
+        /// Send the message and alert the server's moderators
+        Flag,
     }
 
     /// System message channel assignments
+    #[derive(Default)]
     pub struct SystemMessageChannels {
         /// ID of channel to send user join messages in
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -112,6 +289,48 @@ auto_derived!(
         /// ID of channel to send user banned messages in
         #[serde(skip_serializing_if = "Option::is_none")]
         pub user_banned: Option<String>,
+        /// Where to send message pinned and unpinned messages
+        ///
+        /// Defaults to the channel the message was pinned in
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub message_pinned: Option<SystemMessageTarget>,
+
+        /// Templates to pick from when sending user join messages
+        ///
+        /// Supports `{user}`, `{server}` and `{member_count}` placeholders
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pub user_joined_templates: Vec<String>,
+        /// Templates to pick from when sending user left messages
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pub user_left_templates: Vec<String>,
+        /// Whether to attach a generated welcome image to user join messages
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub welcome_image: bool,
+
+        /// ID of channel to send alerts about suspicious new members in
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub moderation_alerts: Option<String>,
+    }
+
+    /// Destination for a system message that originates in a channel
+    #[serde(tag = "type")]
+    pub enum SystemMessageTarget {
+        /// Send to the channel the event happened in
+        SameChannel,
+        /// Send to a specific channel
+        Channel { id: String },
+        /// Do not send the message
+        Disabled,
+    }
+
+    /// Which messages members are notified about
+    #[derive(Default)]
+    pub enum NotificationMode {
+        /// Notify for every message
+        AllMessages,
+        /// Only notify when mentioned
+        #[default]
+        MentionsOnly,
     }
 
     /// Optional fields on server object
@@ -121,11 +340,18 @@ auto_derived!(
         SystemMessages,
         Icon,
         Banner,
+        Tag,
+        Starboard,
+        NewMemberRestrictions,
+        MentionLimit,
+        ContentFilter,
+        Recognition,
     }
 
     /// Optional fields on server object
     pub enum FieldsRole {
         Colour,
+        Group,
     }
 );
 
@@ -150,11 +376,27 @@ impl Server {
             analytics: false,
             banner: None,
             categories: None,
+            tag: None,
             discoverable: false,
             flags: None,
+            recognition: None,
             icon: None,
             roles: HashMap::new(),
+            badges: HashMap::new(),
             system_messages: None,
+            starboard: None,
+            new_member_restrictions: None,
+            mention_limit: None,
+            content_filter: None,
+            default_notifications: Default::default(),
+            require_emoji_approval: false,
+            require_rules_acceptance: false,
+            screening_questions: vec![],
+            disable_tts: false,
+            require_action_approval: false,
+            share_bans: false,
+            member_count: 0,
+            online_count: 0,
         };
 
         let channels: Vec<Channel> = if create_default_channels {
@@ -206,6 +448,166 @@ impl Server {
         Ok(())
     }
 
+    /// Work out the channel list and categories after reordering channels
+    ///
+    /// Listed channels take over the positions they currently occupy in the
+    /// order given and are moved into the given category, if any. Channels in
+    /// categories which changed follow the new server order.
+    pub fn apply_channel_order(
+        &self,
+        positions: &[(String, Option<String>)],
+    ) -> Result<(Vec<String>, Vec<Category>)> {
+        let mut listed = HashSet::new();
+        for (id, category) in positions {
+            if !self.channels.contains(id) || !listed.insert(id) {
+                return Err(create_error!(InvalidOperation));
+            }
+
+            if let Some(category) = category {
+                if !self
+                    .categories
+                    .iter()
+                    .flatten()
+                    .any(|existing| &existing.id == category)
+                {
+                    return Err(create_error!(NotFound));
+                }
+            }
+        }
+
+        let mut order = positions.iter().map(|(id, _)| id);
+        let channels: Vec<String> = self
+            .channels
+            .iter()
+            .map(|id| {
+                if listed.contains(id) {
+                    order.next().unwrap_or(id).to_string()
+                } else {
+                    id.to_string()
+                }
+            })
+            .collect();
+
+        let mut categories = self.categories.clone().unwrap_or_default();
+        let mut changed = HashSet::new();
+        for category in &mut categories {
+            let count = category.channels.len();
+            category.channels.retain(|id| !listed.contains(id));
+            if category.channels.len() != count {
+                changed.insert(category.id.clone());
+            }
+        }
+
+        for (id, category) in positions {
+            if let Some(category) = categories
+                .iter_mut()
+                .find(|existing| Some(&existing.id) == category.as_ref())
+            {
+                category.channels.push(id.to_string());
+                changed.insert(category.id.clone());
+            }
+        }
+
+        for category in &mut categories {
+            if changed.contains(&category.id) {
+                category.channels.sort_by_key(|id| {
+                    channels
+                        .iter()
+                        .position(|channel| channel == id)
+                        .unwrap_or(usize::MAX)
+                });
+            }
+        }
+
+        Ok((channels, categories))
+    }
+
+    /// Reorder channels and move them between categories in a single update
+    ///
+    /// The update only applies if the channels and categories were not changed
+    /// since they were read, otherwise the server is fetched again and the
+    /// order is worked out anew.
+    pub async fn reorder_channels(
+        &mut self,
+        db: &Database,
+        positions: &[(String, Option<String>)],
+    ) -> Result<()> {
+        for _ in 0..REORDER_ATTEMPTS {
+            let (channels, categories) = self.apply_channel_order(positions)?;
+            if db
+                .swap_server_channels(
+                    &self.id,
+                    (&self.channels, self.categories.as_deref()),
+                    &channels,
+                    &categories,
+                )
+                .await?
+            {
+                let partial = PartialServer {
+                    channels: Some(channels),
+                    categories: Some(categories),
+                    ..Default::default()
+                };
+
+                self.apply_options(partial.clone());
+                EventV1::ServerUpdate {
+                    id: self.id.clone(),
+                    data: partial.into(),
+                    clear: vec![],
+                }
+                .p(self.id.clone())
+                .await;
+
+                return Ok(());
+            }
+
+            *self = db.fetch_server(&self.id).await?;
+        }
+
+        Err(create_error!(InternalError))
+    }
+
+    /// Work out new role ranks after reordering roles
+    ///
+    /// Listed roles take over the ranks they currently hold in the order
+    /// given, highest first, so no role can move past an unlisted one.
+    pub fn apply_role_order(&self, order: &[String]) -> Result<HashMap<String, i64>> {
+        let mut listed = HashSet::new();
+        let mut ranks = Vec::with_capacity(order.len());
+        for id in order {
+            let role = self.roles.get(id).ok_or_else(|| create_error!(NotFound))?;
+            if !listed.insert(id) {
+                return Err(create_error!(InvalidOperation));
+            }
+
+            ranks.push(role.rank);
+        }
+
+        ranks.sort();
+        Ok(order.iter().cloned().zip(ranks).collect())
+    }
+
+    /// Reorder roles in a single update
+    pub async fn reorder_roles(&mut self, db: &Database, order: &[String]) -> Result<()> {
+        let ranks = self.apply_role_order(order)?;
+        db.update_role_ranks(&self.id, &ranks).await?;
+
+        for (id, rank) in &ranks {
+            if let Some(role) = self.roles.get_mut(id) {
+                role.rank = *rank;
+            }
+        }
+
+        EventV1::ServerRoleRanksUpdate {
+            id: self.id.clone(),
+            ranks,
+        }
+        .p(self.id.clone())
+        .await;
+
+        Ok(())
+    }
+
     /// Delete a server
     pub async fn delete(self, db: &Database) -> Result<()> {
         EventV1::ServerDelete {
@@ -214,7 +616,12 @@ impl Server {
         .p(self.id.clone())
         .await;
 
-        db.delete_server(&self.id).await
+        db.delete_server(&self.id).await?;
+        for channel in self.channels {
+            tasks::search_index::remove_channel(channel).await;
+        }
+
+        Ok(())
     }
 
     /// Remove a field from Server
@@ -225,17 +632,157 @@ impl Server {
             FieldsServer::SystemMessages => self.system_messages = None,
             FieldsServer::Icon => self.icon = None,
             FieldsServer::Banner => self.banner = None,
+            FieldsServer::Tag => self.tag = None,
+            FieldsServer::Starboard => self.starboard = None,
+            FieldsServer::NewMemberRestrictions => self.new_member_restrictions = None,
+            FieldsServer::MentionLimit => self.mention_limit = None,
+            FieldsServer::ContentFilter => self.content_filter = None,
+            FieldsServer::Recognition => self.recognition = None,
         }
     }
 
+    /// Ids of roles shown separately on the member sidebar, in display order
+    pub fn hoisted_roles(&self) -> Vec<String> {
+        let mut roles: Vec<(&String, &Role)> =
+            self.roles.iter().filter(|(_, role)| role.hoist).collect();
+
+        roles.sort_by(|(a_id, a), (b_id, b)| a.rank.cmp(&b.rank).then_with(|| a_id.cmp(b_id)));
+        roles.into_iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    /// Recount members and online members, correcting any drift in the cached counts
+    ///
+    /// Members are counted in batches so large servers are never loaded at once.
+    /// The cached counts are read before counting and corrected by the difference,
+    /// which keeps most changes made while counting; one made between reading the
+    /// counts and counting its member is counted twice until the next run.
+    pub async fn reconcile_counts(&self, db: &Database) -> Result<()> {
+        let snapshot = db.fetch_server(&self.id).await?;
+        let mut members = db.fetch_all_members_chunked(&self.id).await?;
+
+        let mut member_count = 0;
+        let mut online_count = 0;
+        let mut batch = Vec::with_capacity(RECONCILE_BATCH_SIZE);
+        loop {
+            batch.clear();
+            while batch.len() < RECONCILE_BATCH_SIZE {
+                match members.next().await {
+                    Some(member) => batch.push(member.id.user),
+                    None => break,
+                }
+            }
+
+            if batch.is_empty() {
+                break;
+            }
+
+            member_count += batch.len();
+            let online_ids: Vec<String> = filter_online(&batch).await.into_iter().collect();
+            online_count += db
+                .fetch_users(&online_ids)
+                .await?
+                .into_iter()
+                .filter(|user| !user.is_invisible())
+                .count();
+        }
+
+        db.adjust_server_counts(
+            &[self.id.clone()],
+            member_count as i64 - snapshot.member_count,
+            online_count as i64 - snapshot.online_count,
+        )
+        .await
+    }
+
+    /// Fill in a system message's text from one of the server's templates, if any are configured
+    pub async fn render_system_message(
+        &self,
+        db: &Database,
+        mut message: SystemMessage,
+        user_id: &str,
+    ) -> SystemMessage {
+        let templates = match &self.system_messages {
+            Some(config) => config.templates(&message),
+            None => return message,
+        };
+
+        if templates.is_empty() {
+            return message;
+        }
+
+        let member_count = db.fetch_member_count(&self.id).await.unwrap_or_default();
+        let rendered =
+            SystemMessageChannels::render_template(templates, user_id, &self.name, member_count);
+
+        if let SystemMessage::UserJoined { content, .. } | SystemMessage::UserLeft { content, .. } =
+            &mut message
+        {
+            *content = rendered;
+        }
+
+        message
+    }
+
+    /// Generate a welcome image for a new member and use it as an attachment of a message
+    ///
+    /// Drawn from the server's banner and the member's avatar, either of which may be missing.
+    pub async fn create_welcome_image(
+        &self,
+        db: &Database,
+        user: &User,
+        message_id: &str,
+    ) -> Result<File> {
+        let banner = match &self.banner {
+            Some(file) => {
+                let hash = file.as_hash(db).await?;
+                Some(fetch_image(&hash.bucket_id, &hash.path, &hash.iv, &hash.content_type).await?)
+            }
+            None => None,
+        };
+
+        let avatar = match &user.avatar {
+            Some(file) => {
+                let hash = file.as_hash(db).await?;
+                Some(fetch_image(&hash.bucket_id, &hash.path, &hash.iv, &hash.content_type).await?)
+            }
+            None => None,
+        };
+
+        let data = create_welcome_image(banner, avatar).await;
+        let (width, height) = image_size_vec(&data, "image/webp")
+            .ok_or_else(|| create_error!(ImageProcessingFailed))?;
+
+        let file = File::store(
+            db,
+            "attachments",
+            "welcome.webp".to_string(),
+            "image/webp",
+            Metadata::Image {
+                width: width as isize,
+                height: height as isize,
+                blurhash: None,
+                stripped: vec![],
+            },
+            &data,
+            &user.id,
+        )
+        .await?;
+
+        File::use_attachment(db, &file.id, message_id, &user.id, None).await
+    }
+
     /// Set role permission on a server
+    ///
+    /// The change is recorded in the server's audit log.
     pub async fn set_role_permission(
         &mut self,
         db: &Database,
         role_id: &str,
         permissions: OverrideField,
+        actor: &str,
     ) -> Result<()> {
         if let Some(role) = self.roles.get_mut(role_id) {
+            let before = role.permissions;
             role.update(
                 db,
                 &self.id,
@@ -248,6 +795,19 @@ impl Server {
             )
             .await?;
 
+            AuditLogEntry::create(
+                db,
+                &self.id,
+                actor,
+                AuditLogAction::PermissionOverrideEdit {
+                    channel: None,
+                    role: Some(role_id.to_string()),
+                    before,
+                    after: permissions,
+                },
+            )
+            .await?;
+
             Ok(())
         } else {
             Err(create_error!(NotFound))
@@ -263,6 +823,7 @@ impl Role {
             permissions: Some(self.permissions),
             colour: self.colour,
             hoist: Some(self.hoist),
+            group: self.group,
             rank: Some(self.rank),
         }
     }
@@ -318,6 +879,7 @@ impl Role {
     pub fn remove_field(&mut self, field: &FieldsRole) {
         match field {
             FieldsRole::Colour => self.colour = None,
+            FieldsRole::Group => self.group = None,
         }
     }
 
@@ -334,6 +896,42 @@ impl Role {
     }
 }
 
+impl ServerBadge {
+    /// Create a badge
+    pub async fn create(&self, db: &Database, server_id: &str) -> Result<String> {
+        let badge_id = Ulid::new().to_string();
+        self.save(db, server_id, &badge_id).await?;
+        Ok(badge_id)
+    }
+
+    /// Save this badge, replacing any existing badge with the same id
+    pub async fn save(&self, db: &Database, server_id: &str, badge_id: &str) -> Result<()> {
+        db.insert_server_badge(server_id, badge_id, self).await?;
+
+        EventV1::ServerBadgeUpdate {
+            id: server_id.to_string(),
+            badge_id: badge_id.to_string(),
+            data: self.clone().into(),
+        }
+        .p(server_id.to_string())
+        .await;
+
+        Ok(())
+    }
+
+    /// Delete a badge
+    pub async fn delete(self, db: &Database, server_id: &str, badge_id: &str) -> Result<()> {
+        EventV1::ServerBadgeDelete {
+            id: server_id.to_string(),
+            badge_id: badge_id.to_string(),
+        }
+        .p(server_id.to_string())
+        .await;
+
+        db.delete_server_badge(server_id, badge_id).await
+    }
+}
+
 impl SystemMessageChannels {
     pub fn into_channel_ids(self) -> HashSet<String> {
         let mut ids = HashSet::new();
@@ -354,15 +952,75 @@ impl SystemMessageChannels {
             ids.insert(id);
         }
 
+        if let Some(SystemMessageTarget::Channel { id }) = self.message_pinned {
+            ids.insert(id);
+        }
+
+        if let Some(id) = self.moderation_alerts {
+            ids.insert(id);
+        }
+
         ids
     }
+
+    /// Resolve the channel a system message should be sent to
+    ///
+    /// `source` is the channel the event happened in, if any.
+    /// Returns `None` if this type of message is disabled.
+    pub fn route(
+        config: Option<&Self>,
+        message: &SystemMessage,
+        source: Option<&str>,
+    ) -> Option<String> {
+        match message {
+            SystemMessage::UserJoined { .. } => config.and_then(|x| x.user_joined.clone()),
+            SystemMessage::UserLeft { .. } => config.and_then(|x| x.user_left.clone()),
+            SystemMessage::UserKicked { .. } => config.and_then(|x| x.user_kicked.clone()),
+            SystemMessage::UserBanned { .. } => config.and_then(|x| x.user_banned.clone()),
+            SystemMessage::MessagePinned { .. } | SystemMessage::MessageUnpinned { .. } => {
+                match config.and_then(|x| x.message_pinned.as_ref()) {
+                    None | Some(SystemMessageTarget::SameChannel) => source.map(str::to_string),
+                    Some(SystemMessageTarget::Channel { id }) => Some(id.clone()),
+                    Some(SystemMessageTarget::Disabled) => None,
+                }
+            }
+            _ => source.map(str::to_string),
+        }
+    }
+
+    /// Get the templates configured for a system message, if any
+    pub fn templates(&self, message: &SystemMessage) -> &[String] {
+        match message {
+            SystemMessage::UserJoined { .. } => &self.user_joined_templates,
+            SystemMessage::UserLeft { .. } => &self.user_left_templates,
+            _ => &[],
+        }
+    }
+
+    /// Pick a random template and fill in its placeholders
+    pub fn render_template(
+        templates: &[String],
+        user_id: &str,
+        server_name: &str,
+        member_count: usize,
+    ) -> Option<String> {
+        templates.choose(&mut rand::thread_rng()).map(|template| {
+            template
+                .replace("{user}", &format!("<@{user_id}>"))
+                .replace("{server}", server_name)
+                .replace("{member_count}", &member_count.to_string())
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
 
-    use crate::{fixture, util::permissions::DatabasePermissionQuery};
+    use crate::{
+        fixture, util::permissions::DatabasePermissionQuery, Category, PartialServer, Role, Server,
+        SystemMessage, SystemMessageChannels, SystemMessageTarget,
+    };
 
     #[async_std::test]
     async fn permissions() {
@@ -389,4 +1047,241 @@ mod tests {
                 .has_channel_permission(ChannelPermission::BanMembers));
         });
     }
+
+    #[async_std::test]
+    async fn reorders_latest_channels() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                server server 4);
+
+            let channels =
+                |ids: &[&str]| -> Vec<String> { ids.iter().map(|id| id.to_string()).collect() };
+            let mut server = server;
+            server
+                .update(
+                    &db,
+                    PartialServer {
+                        channels: Some(channels(&["a", "b"])),
+                        ..Default::default()
+                    },
+                    vec![],
+                )
+                .await
+                .unwrap();
+
+            // Another request adds a channel after this copy was read
+            db.update_server(
+                &server.id,
+                &PartialServer {
+                    channels: Some(channels(&["a", "b", "c"])),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+
+            server
+                .reorder_channels(&db, &[("b".to_string(), None), ("a".to_string(), None)])
+                .await
+                .unwrap();
+
+            let expected = channels(&["b", "a", "c"]);
+            assert_eq!(server.channels, expected);
+            assert_eq!(
+                db.fetch_server(&server.id).await.unwrap().channels,
+                expected
+            );
+        });
+    }
+
+    #[test]
+    fn channel_reordering() {
+        let server = Server {
+            id: "server".to_string(),
+            owner: "owner".to_string(),
+            name: "Server".to_string(),
+            description: None,
+            channels: vec!["a", "b", "c", "d"]
+                .into_iter()
+                .map(ToString::to_string)
+                .collect(),
+            nsfw: false,
+            default_permissions: 0,
+            analytics: false,
+            banner: None,
+            categories: Some(vec![Category {
+                id: "cat".to_string(),
+                title: "Category".to_string(),
+                channels: vec!["b".to_string(), "c".to_string()],
+                default_permissions: None,
+                role_permissions: Default::default(),
+            }]),
+            tag: None,
+            discoverable: false,
+            flags: None,
+            recognition: None,
+            icon: None,
+            roles: Default::default(),
+            badges: Default::default(),
+            system_messages: None,
+            starboard: None,
+            new_member_restrictions: None,
+            mention_limit: None,
+            content_filter: None,
+            default_notifications: Default::default(),
+            require_emoji_approval: false,
+            require_rules_acceptance: false,
+            screening_questions: vec![],
+            disable_tts: false,
+            require_action_approval: false,
+            share_bans: false,
+            member_count: 0,
+            online_count: 0,
+        };
+
+        // Partial order: "d" and "a" swap places and "d" joins the category
+        let (channels, categories) = server
+            .apply_channel_order(&[
+                ("d".to_string(), Some("cat".to_string())),
+                ("a".to_string(), None),
+            ])
+            .unwrap();
+
+        assert_eq!(channels, vec!["d", "b", "c", "a"]);
+        assert_eq!(categories[0].channels, vec!["d", "b", "c"]);
+
+        assert!(server
+            .apply_channel_order(&[("a".to_string(), None), ("a".to_string(), None)])
+            .is_err());
+        assert!(server
+            .apply_channel_order(&[("a".to_string(), Some("missing".to_string()))])
+            .is_err());
+    }
+
+    #[test]
+    fn role_reordering() {
+        let role = |rank| Role {
+            name: "Role".to_string(),
+            permissions: Default::default(),
+            colour: None,
+            hoist: false,
+            group: None,
+            rank,
+        };
+
+        let server = Server {
+            id: "server".to_string(),
+            owner: "owner".to_string(),
+            name: "Server".to_string(),
+            description: None,
+            channels: vec![],
+            nsfw: false,
+            default_permissions: 0,
+            analytics: false,
+            banner: None,
+            categories: None,
+            tag: None,
+            discoverable: false,
+            flags: None,
+            recognition: None,
+            icon: None,
+            roles: [("a", 1), ("b", 2), ("c", 5), ("d", 8)]
+                .into_iter()
+                .map(|(id, rank)| (id.to_string(), role(rank)))
+                .collect(),
+            badges: Default::default(),
+            system_messages: None,
+            starboard: None,
+            new_member_restrictions: None,
+            mention_limit: None,
+            content_filter: None,
+            default_notifications: Default::default(),
+            require_emoji_approval: false,
+            require_rules_acceptance: false,
+            screening_questions: vec![],
+            disable_tts: false,
+            require_action_approval: false,
+            share_bans: false,
+            member_count: 0,
+            online_count: 0,
+        };
+
+        // "d" moves to the top of the roles being reordered but stays below "a"
+        let ranks = server
+            .apply_role_order(&["d".to_string(), "b".to_string(), "c".to_string()])
+            .unwrap();
+
+        assert_eq!(ranks.len(), 3);
+        assert_eq!(ranks["d"], 2);
+        assert_eq!(ranks["b"], 5);
+        assert_eq!(ranks["c"], 8);
+
+        assert!(server
+            .apply_role_order(&["a".to_string(), "a".to_string()])
+            .is_err());
+        assert!(server.apply_role_order(&["missing".to_string()]).is_err());
+    }
+
+    #[test]
+    fn system_message_routing() {
+        let pinned = SystemMessage::MessagePinned {
+            id: "message".to_string(),
+            by: "user".to_string(),
+        };
+        let joined = SystemMessage::UserJoined {
+            id: "user".to_string(),
+            content: None,
+        };
+
+        assert_eq!(
+            SystemMessageChannels::route(None, &pinned, Some("source")),
+            Some("source".to_string())
+        );
+        assert_eq!(SystemMessageChannels::route(None, &joined, None), None);
+
+        let config = SystemMessageChannels {
+            user_joined: Some("welcome".to_string()),
+            message_pinned: Some(SystemMessageTarget::Channel {
+                id: "pins".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            SystemMessageChannels::route(Some(&config), &pinned, Some("source")),
+            Some("pins".to_string())
+        );
+        assert_eq!(
+            SystemMessageChannels::route(Some(&config), &joined, None),
+            Some("welcome".to_string())
+        );
+
+        let config = SystemMessageChannels {
+            message_pinned: Some(SystemMessageTarget::Disabled),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            SystemMessageChannels::route(Some(&config), &pinned, Some("source")),
+            None
+        );
+    }
+
+    #[test]
+    fn system_message_templates() {
+        assert_eq!(
+            SystemMessageChannels::render_template(
+                &["Welcome {user} to {server}, member #{member_count}!".to_string()],
+                "user",
+                "Guilderia",
+                42
+            ),
+            Some("Welcome <@user> to Guilderia, member #42!".to_string())
+        );
+        assert_eq!(
+            SystemMessageChannels::render_template(&[], "user", "Guilderia", 42),
+            None
+        );
+    }
 }
@@ -1,6 +1,10 @@
+use std::collections::HashMap;
+
 use guilderia_result::Result;
 
-use crate::{FieldsRole, FieldsServer, PartialRole, PartialServer, Role, Server};
+use crate::{
+    Category, FieldsRole, FieldsServer, PartialRole, PartialServer, Role, Server, ServerBadge,
+};
 
 mod mongodb;
 mod reference;
@@ -16,6 +20,12 @@ pub trait AbstractServers: Sync + Send {
     /// Fetch a servers by their ids
     async fn fetch_servers<'a>(&self, ids: &'a [String]) -> Result<Vec<Server>>;
 
+    /// Fetch a server by its tag
+    async fn fetch_server_by_tag(&self, tag: &str) -> Result<Server>;
+
+    /// Fetch the ids of every server
+    async fn fetch_all_server_ids(&self) -> Result<Vec<String>>;
+
     /// Update a server with new information
     async fn update_server(
         &self,
@@ -24,9 +34,28 @@ pub trait AbstractServers: Sync + Send {
         remove: Vec<FieldsServer>,
     ) -> Result<()>;
 
+    /// Replace a server's channels and categories, only if they still match what was read
+    ///
+    /// Returns false if either was changed in the meantime.
+    async fn swap_server_channels(
+        &self,
+        id: &str,
+        current: (&[String], Option<&[Category]>),
+        channels: &[String],
+        categories: &[Category],
+    ) -> Result<bool>;
+
     /// Delete a server by its id
     async fn delete_server(&self, id: &str) -> Result<()>;
 
+    /// Increment (or decrement) the cached member and online counts of servers
+    async fn adjust_server_counts(
+        &self,
+        ids: &[String],
+        member_count: i64,
+        online_count: i64,
+    ) -> Result<()>;
+
     /// Insert a new role into server object
     async fn insert_role(&self, server_id: &str, role_id: &str, role: &Role) -> Result<()>;
 
@@ -39,8 +68,24 @@ pub trait AbstractServers: Sync + Send {
         remove: Vec<FieldsRole>,
     ) -> Result<()>;
 
+    /// Set the ranks of several roles on a server at once
+    async fn update_role_ranks(&self, server_id: &str, ranks: &HashMap<String, i64>) -> Result<()>;
+
     /// Delete a role from a server
     ///
     /// Also updates channels and members.
     async fn delete_role(&self, server_id: &str, role_id: &str) -> Result<()>;
+
+    /// Insert or replace a badge on a server
+    async fn insert_server_badge(
+        &self,
+        server_id: &str,
+        badge_id: &str,
+        badge: &ServerBadge,
+    ) -> Result<()>;
+
+    /// Delete a badge from a server
+    ///
+    /// Also removes the badge from members.
+    async fn delete_server_badge(&self, server_id: &str, badge_id: &str) -> Result<()>;
 }
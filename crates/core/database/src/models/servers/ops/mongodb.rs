@@ -1,8 +1,12 @@
-use bson::{to_document, Bson, Document};
+use std::collections::HashMap;
+
+use bson::{to_bson, to_document, Bson, Document};
 use futures::StreamExt;
 use guilderia_result::Result;
 
-use crate::{FieldsRole, FieldsServer, PartialRole, PartialServer, Role, Server};
+use crate::{
+    Category, FieldsRole, FieldsServer, PartialRole, PartialServer, Role, Server, ServerBadge,
+};
 use crate::{IntoDocumentPath, MongoDb};
 
 use super::AbstractServers;
@@ -43,6 +47,35 @@ impl AbstractServers for MongoDb {
             .await)
     }
 
+    /// Fetch a server by its tag
+    async fn fetch_server_by_tag(&self, tag: &str) -> Result<Server> {
+        query!(
+            self,
+            find_one,
+            COL,
+            doc! {
+                "tag": tag
+            }
+        )?
+        .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch the ids of every server
+    async fn fetch_all_server_ids(&self) -> Result<Vec<String>> {
+        Ok(self
+            .col::<Document>(COL)
+            .find(doc! {})
+            .projection(doc! { "_id": 1 })
+            .await
+            .map_err(|_| create_database_error!("find", COL))?
+            .filter_map(|s| async {
+                s.ok()
+                    .and_then(|doc| doc.get_str("_id").map(|id| id.to_string()).ok())
+            })
+            .collect()
+            .await)
+    }
+
     /// Update a server with new information
     async fn update_server(
         &self,
@@ -62,12 +95,67 @@ impl AbstractServers for MongoDb {
         .map(|_| ())
     }
 
+    /// Replace a server's channels and categories, only if they still match what was read
+    async fn swap_server_channels(
+        &self,
+        id: &str,
+        (current_channels, current_categories): (&[String], Option<&[Category]>),
+        channels: &[String],
+        categories: &[Category],
+    ) -> Result<bool> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id,
+                    "channels": current_channels,
+                    "categories": to_bson(&current_categories)
+                        .map_err(|_| create_database_error!("to_bson", "categories"))?
+                },
+                doc! {
+                    "$set": {
+                        "channels": channels,
+                        "categories": to_bson(categories)
+                            .map_err(|_| create_database_error!("to_bson", "categories"))?
+                    }
+                },
+            )
+            .await
+            .map(|result| result.matched_count == 1)
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
     /// Delete a server by its id
     async fn delete_server(&self, id: &str) -> Result<()> {
         self.delete_associated_server_objects(id).await?;
         query!(self, delete_one_by_id, COL, id).map(|_| ())
     }
 
+    /// Increment (or decrement) the cached member and online counts of servers
+    async fn adjust_server_counts(
+        &self,
+        ids: &[String],
+        member_count: i64,
+        online_count: i64,
+    ) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_many(
+                doc! {
+                    "_id": {
+                        "$in": ids
+                    }
+                },
+                doc! {
+                    "$inc": {
+                        "member_count": member_count,
+                        "online_count": online_count
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_many", COL))
+    }
+
     /// Insert a new role into server object
     async fn insert_role(&self, server_id: &str, role_id: &str, role: &Role) -> Result<()> {
         self.col::<Document>(COL)
@@ -107,6 +195,27 @@ impl AbstractServers for MongoDb {
         .map(|_| ())
     }
 
+    /// Set the ranks of several roles on a server at once
+    async fn update_role_ranks(&self, server_id: &str, ranks: &HashMap<String, i64>) -> Result<()> {
+        let mut set = Document::new();
+        for (role_id, rank) in ranks {
+            set.insert(format!("roles.{role_id}.rank"), rank);
+        }
+
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": server_id
+                },
+                doc! {
+                    "$set": set
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
     /// Delete a role from a server
     ///
     /// Also updates channels and members.
@@ -154,6 +263,64 @@ impl AbstractServers for MongoDb {
             .map(|_| ())
             .map_err(|_| create_database_error!("update_one", "servers"))
     }
+
+    /// Insert or replace a badge on a server
+    async fn insert_server_badge(
+        &self,
+        server_id: &str,
+        badge_id: &str,
+        badge: &ServerBadge,
+    ) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": server_id
+                },
+                doc! {
+                    "$set": {
+                        "badges.".to_owned() + badge_id: to_document(badge)
+                            .map_err(|_| create_database_error!("to_document", "badge"))?
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Delete a badge from a server
+    ///
+    /// Also removes the badge from members.
+    async fn delete_server_badge(&self, server_id: &str, badge_id: &str) -> Result<()> {
+        self.col::<Document>("server_members")
+            .update_many(
+                doc! {
+                    "_id.server": server_id
+                },
+                doc! {
+                    "$pull": {
+                        "badges": &badge_id
+                    }
+                },
+            )
+            .await
+            .map_err(|_| create_database_error!("update_many", "server_members"))?;
+
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": server_id
+                },
+                doc! {
+                    "$unset": {
+                        "badges.".to_owned() + badge_id: 1_i32
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
 }
 
 impl IntoDocumentPath for FieldsServer {
@@ -164,6 +331,12 @@ impl IntoDocumentPath for FieldsServer {
             FieldsServer::Description => "description",
             FieldsServer::Icon => "icon",
             FieldsServer::SystemMessages => "system_messages",
+            FieldsServer::Tag => "tag",
+            FieldsServer::Starboard => "starboard",
+            FieldsServer::NewMemberRestrictions => "new_member_restrictions",
+            FieldsServer::MentionLimit => "mention_limit",
+            FieldsServer::ContentFilter => "content_filter",
+            FieldsServer::Recognition => "recognition",
         })
     }
 }
@@ -172,6 +345,7 @@ impl IntoDocumentPath for FieldsRole {
     fn as_path(&self) -> Option<&'static str> {
         Some(match self {
             FieldsRole::Colour => "colour",
+            FieldsRole::Group => "group",
         })
     }
 }
@@ -231,8 +405,8 @@ impl MongoDb {
         self.delete_associated_channel_objects(Bson::Document(doc! { "$in": &channels }))
             .await?;
 
-        // Delete members and bans.
-        for with in &["server_members", "server_bans"] {
+        // Delete members, bans and screening responses.
+        for with in &["server_members", "server_bans", "screening_responses"] {
             self.col::<Document>(with)
                 .delete_many(doc! {
                     "_id.server": &server_id
@@ -1,7 +1,11 @@
+use std::collections::HashMap;
+
 use guilderia_result::Result;
 
 use crate::ReferenceDb;
-use crate::{FieldsRole, FieldsServer, PartialRole, PartialServer, Role, Server};
+use crate::{
+    Category, FieldsRole, FieldsServer, PartialRole, PartialServer, Role, Server, ServerBadge,
+};
 
 use super::AbstractServers;
 
@@ -40,6 +44,22 @@ impl AbstractServers for ReferenceDb {
             .collect()
     }
 
+    /// Fetch a server by its tag
+    async fn fetch_server_by_tag(&self, tag: &str) -> Result<Server> {
+        let servers = self.servers.lock().await;
+        servers
+            .values()
+            .find(|server| server.tag.as_deref() == Some(tag))
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch the ids of every server
+    async fn fetch_all_server_ids(&self) -> Result<Vec<String>> {
+        let servers = self.servers.lock().await;
+        Ok(servers.keys().cloned().collect())
+    }
+
     /// Update a server with new information
     async fn update_server(
         &self,
@@ -61,6 +81,26 @@ impl AbstractServers for ReferenceDb {
         }
     }
 
+    /// Replace a server's channels and categories, only if they still match what was read
+    async fn swap_server_channels(
+        &self,
+        id: &str,
+        (current_channels, current_categories): (&[String], Option<&[Category]>),
+        channels: &[String],
+        categories: &[Category],
+    ) -> Result<bool> {
+        let mut servers = self.servers.lock().await;
+        let server = servers.get_mut(id).ok_or_else(|| create_error!(NotFound))?;
+        if server.channels != current_channels || server.categories.as_deref() != current_categories
+        {
+            return Ok(false);
+        }
+
+        server.channels = channels.to_vec();
+        server.categories = Some(categories.to_vec());
+        Ok(true)
+    }
+
     /// Delete a server by its id
     async fn delete_server(&self, id: &str) -> Result<()> {
         let mut servers = self.servers.lock().await;
@@ -71,6 +111,24 @@ impl AbstractServers for ReferenceDb {
         }
     }
 
+    /// Increment (or decrement) the cached member and online counts of servers
+    async fn adjust_server_counts(
+        &self,
+        ids: &[String],
+        member_count: i64,
+        online_count: i64,
+    ) -> Result<()> {
+        let mut servers = self.servers.lock().await;
+        for id in ids {
+            if let Some(server) = servers.get_mut(id) {
+                server.member_count += member_count;
+                server.online_count += online_count;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Insert a new role into server object
     async fn insert_role(&self, server_id: &str, role_id: &str, role: &Role) -> Result<()> {
         let mut servers = self.servers.lock().await;
@@ -108,6 +166,26 @@ impl AbstractServers for ReferenceDb {
         }
     }
 
+    /// Set the ranks of several roles on a server at once
+    async fn update_role_ranks(&self, server_id: &str, ranks: &HashMap<String, i64>) -> Result<()> {
+        let mut servers = self.servers.lock().await;
+        if let Some(server) = servers.get_mut(server_id) {
+            if ranks.keys().any(|id| !server.roles.contains_key(id)) {
+                return Err(create_error!(NotFound));
+            }
+
+            for (role_id, rank) in ranks {
+                if let Some(role) = server.roles.get_mut(role_id) {
+                    role.rank = *rank;
+                }
+            }
+
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
     /// Delete a role from a server
     ///
     /// Also updates channels and members.
@@ -123,4 +201,43 @@ impl AbstractServers for ReferenceDb {
             Err(create_error!(NotFound))
         }
     }
+
+    /// Insert or replace a badge on a server
+    async fn insert_server_badge(
+        &self,
+        server_id: &str,
+        badge_id: &str,
+        badge: &ServerBadge,
+    ) -> Result<()> {
+        let mut servers = self.servers.lock().await;
+        if let Some(server) = servers.get_mut(server_id) {
+            server.badges.insert(badge_id.to_string(), badge.clone());
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Delete a badge from a server
+    ///
+    /// Also removes the badge from members.
+    async fn delete_server_badge(&self, server_id: &str, badge_id: &str) -> Result<()> {
+        let mut servers = self.servers.lock().await;
+        if let Some(server) = servers.get_mut(server_id) {
+            if server.badges.remove(badge_id).is_none() {
+                return Err(create_error!(NotFound));
+            }
+        } else {
+            return Err(create_error!(NotFound));
+        }
+
+        let mut members = self.server_members.lock().await;
+        for member in members.values_mut() {
+            if member.id.server == server_id {
+                member.badges.retain(|id| id != badge_id);
+            }
+        }
+
+        Ok(())
+    }
 }
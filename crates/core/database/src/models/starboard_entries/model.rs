@@ -0,0 +1,10 @@
+auto_derived!(
+    /// Message highlighted on a server's starboard
+    pub struct StarboardEntry {
+        /// Id of the highlighted message
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the highlight posted to the starboard channel
+        pub highlight: String,
+    }
+);
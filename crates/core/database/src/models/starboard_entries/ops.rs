@@ -0,0 +1,18 @@
+use guilderia_result::Result;
+
+use crate::StarboardEntry;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractStarboardEntries: Sync + Send {
+    /// Insert new starboard entry into database
+    async fn insert_starboard_entry(&self, entry: &StarboardEntry) -> Result<()>;
+
+    /// Fetch a starboard entry by the id of the highlighted message
+    async fn fetch_starboard_entry(&self, id: &str) -> Result<StarboardEntry>;
+
+    /// Delete a starboard entry from the database
+    async fn delete_starboard_entry(&self, id: &str) -> Result<()>;
+}
@@ -0,0 +1,26 @@
+use guilderia_result::Result;
+
+use crate::MongoDb;
+use crate::StarboardEntry;
+
+use super::AbstractStarboardEntries;
+
+static COL: &str = "starboard_entries";
+
+#[async_trait]
+impl AbstractStarboardEntries for MongoDb {
+    /// Insert new starboard entry into database
+    async fn insert_starboard_entry(&self, entry: &StarboardEntry) -> Result<()> {
+        query!(self, insert_one, COL, &entry).map(|_| ())
+    }
+
+    /// Fetch a starboard entry by the id of the highlighted message
+    async fn fetch_starboard_entry(&self, id: &str) -> Result<StarboardEntry> {
+        query!(self, find_one_by_id, COL, id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Delete a starboard entry from the database
+    async fn delete_starboard_entry(&self, id: &str) -> Result<()> {
+        query!(self, delete_one_by_id, COL, id).map(|_| ())
+    }
+}
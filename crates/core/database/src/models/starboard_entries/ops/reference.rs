@@ -0,0 +1,39 @@
+use guilderia_result::Result;
+
+use crate::ReferenceDb;
+use crate::StarboardEntry;
+
+use super::AbstractStarboardEntries;
+
+#[async_trait]
+impl AbstractStarboardEntries for ReferenceDb {
+    /// Insert new starboard entry into database
+    async fn insert_starboard_entry(&self, entry: &StarboardEntry) -> Result<()> {
+        let mut starboard_entries = self.starboard_entries.lock().await;
+        if starboard_entries.contains_key(&entry.id) {
+            Err(create_database_error!("insert", "starboard_entry"))
+        } else {
+            starboard_entries.insert(entry.id.to_string(), entry.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a starboard entry by the id of the highlighted message
+    async fn fetch_starboard_entry(&self, id: &str) -> Result<StarboardEntry> {
+        let starboard_entries = self.starboard_entries.lock().await;
+        starboard_entries
+            .get(id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Delete a starboard entry from the database
+    async fn delete_starboard_entry(&self, id: &str) -> Result<()> {
+        let mut starboard_entries = self.starboard_entries.lock().await;
+        if starboard_entries.remove(id).is_some() {
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use guilderia_models::v0::{self, MessageSort};
+use guilderia_result::Result;
+
+use crate::{
+    events::client::EventV1, Database, Message, MessageFilter, MessageQuery, MessageTimePeriod,
+};
+
+/// Number of messages deleted at once while deleting a thread
+const DELETE_BATCH_SIZE: i64 = 100;
+
+auto_derived_partial!(
+    /// Thread of replies branching off a message
+    pub struct Thread {
+        /// Id of the message the thread branches off
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the channel the thread belongs to
+        pub channel: String,
+        /// Id of the user who started the thread
+        pub creator: String,
+        /// Thread name
+        pub name: String,
+        /// Whether the thread is archived
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub archived: bool,
+        /// Ids of the users following the thread
+        #[serde(default)]
+        pub members: Vec<String>,
+        /// Number of messages sent in the thread
+        #[serde(default)]
+        pub message_count: i64,
+        /// Id of the last message sent in the thread
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub last_message_id: Option<String>,
+    },
+    "PartialThread"
+);
+
+impl Thread {
+    /// Start a thread off a message, with its creator as the first member
+    pub async fn create(
+        db: &Database,
+        message: &Message,
+        creator: &str,
+        data: v0::DataCreateThread,
+    ) -> Result<Thread> {
+        if message.thread.is_some() {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        let thread = Thread {
+            id: message.id.clone(),
+            channel: message.channel.clone(),
+            creator: creator.to_string(),
+            name: data.name,
+            archived: false,
+            members: vec![creator.to_string()],
+            message_count: 0,
+            last_message_id: None,
+        };
+
+        db.insert_thread(&thread).await?;
+
+        EventV1::ThreadCreate(thread.clone().into())
+            .p(thread.channel.clone())
+            .await;
+
+        Ok(thread)
+    }
+
+    /// Update thread data
+    pub async fn update(&mut self, db: &Database, partial: PartialThread) -> Result<()> {
+        self.apply_options(partial.clone());
+        db.update_thread(&self.id, &partial).await?;
+
+        self.notify(partial).await;
+        Ok(())
+    }
+
+    /// Let clients know the thread changed
+    async fn notify(&self, partial: PartialThread) {
+        EventV1::ThreadUpdate {
+            id: self.id.clone(),
+            channel: self.channel.clone(),
+            data: partial.into(),
+        }
+        .p(self.channel.clone())
+        .await;
+    }
+
+    /// Add a user to the thread's members
+    pub async fn join(&mut self, db: &Database, user: &str) -> Result<()> {
+        if self.members.iter().any(|member| member == user) {
+            return Ok(());
+        }
+
+        db.add_thread_member(&self.id, user).await?;
+        self.members.push(user.to_string());
+
+        self.notify(PartialThread {
+            members: Some(self.members.clone()),
+            ..Default::default()
+        })
+        .await;
+
+        Ok(())
+    }
+
+    /// Remove a user from the thread's members
+    pub async fn leave(&mut self, db: &Database, user: &str) -> Result<()> {
+        if !self.members.iter().any(|member| member == user) {
+            return Ok(());
+        }
+
+        db.remove_thread_member(&self.id, user).await?;
+        self.members.retain(|member| member != user);
+
+        self.notify(PartialThread {
+            members: Some(self.members.clone()),
+            ..Default::default()
+        })
+        .await;
+
+        Ok(())
+    }
+
+    /// Record a message sent in the thread, adding its author to the members
+    pub async fn record_message(&mut self, db: &Database, message: &Message) -> Result<()> {
+        db.record_thread_message(&self.id, &message.id).await?;
+        self.message_count += 1;
+        self.last_message_id = Some(message.id.clone());
+
+        let mut partial = PartialThread {
+            message_count: Some(self.message_count),
+            last_message_id: self.last_message_id.clone(),
+            ..Default::default()
+        };
+
+        if !self.members.contains(&message.author) {
+            db.add_thread_member(&self.id, &message.author).await?;
+            self.members.push(message.author.clone());
+            partial.members = Some(self.members.clone());
+        }
+
+        self.notify(partial).await;
+        Ok(())
+    }
+
+    /// Delete the thread along with every message sent in it
+    pub async fn delete(&self, db: &Database) -> Result<()> {
+        loop {
+            let ids: Vec<String> = db
+                .fetch_messages(MessageQuery {
+                    limit: Some(DELETE_BATCH_SIZE),
+                    filter: MessageFilter {
+                        channel: Some(self.channel.clone()),
+                        thread: Some(self.id.clone()),
+                        ..Default::default()
+                    },
+                    time_period: MessageTimePeriod::Absolute {
+                        before: None,
+                        after: None,
+                        sort: Some(MessageSort::Latest),
+                    },
+                })
+                .await?
+                .into_iter()
+                .map(|message| message.id)
+                .collect();
+
+            if ids.is_empty() {
+                break;
+            }
+
+            Message::bulk_delete(db, &self.channel, ids).await?;
+        }
+
+        db.delete_thread(&self.id).await?;
+
+        EventV1::ThreadDelete {
+            id: self.id.clone(),
+            channel: self.channel.clone(),
+        }
+        .p(self.channel.clone())
+        .await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use guilderia_models::v0;
+
+    use crate::{Message, MessageFilter, MessageQuery, MessageTimePeriod, PartialThread, Thread};
+
+    #[async_std::test]
+    async fn crud() {
+        database_test!(|db| async move {
+            let parent = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: "channel".to_string(),
+                author: "creator".to_string(),
+                ..Default::default()
+            };
+
+            db.insert_message(&parent).await.unwrap();
+
+            let mut thread = Thread::create(
+                &db,
+                &parent,
+                "creator",
+                v0::DataCreateThread {
+                    name: "Thread".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+
+            assert!(Thread::create(
+                &db,
+                &Message {
+                    thread: Some(thread.id.clone()),
+                    ..parent.clone()
+                },
+                "creator",
+                v0::DataCreateThread {
+                    name: "Nested".to_string(),
+                },
+            )
+            .await
+            .is_err());
+
+            let reply = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: "channel".to_string(),
+                author: "member".to_string(),
+                thread: Some(thread.id.clone()),
+                ..Default::default()
+            };
+
+            db.insert_message(&reply).await.unwrap();
+            thread.record_message(&db, &reply).await.unwrap();
+            thread.join(&db, "member").await.unwrap();
+
+            let fetched = db.fetch_thread(&thread.id).await.unwrap();
+            assert_eq!(fetched.message_count, 1);
+            assert_eq!(fetched.last_message_id, Some(reply.id.clone()));
+            assert_eq!(fetched.members, vec!["creator", "member"]);
+
+            // Messages sent in the thread stay out of the channel
+            let query = |thread: Option<String>| MessageQuery {
+                limit: None,
+                filter: MessageFilter {
+                    channel: Some("channel".to_string()),
+                    exclude_threads: thread.is_none(),
+                    thread,
+                    ..Default::default()
+                },
+                time_period: MessageTimePeriod::Absolute {
+                    before: None,
+                    after: None,
+                    sort: None,
+                },
+            };
+
+            let channel = db.fetch_messages(query(None)).await.unwrap();
+            assert_eq!(channel.len(), 1);
+            assert_eq!(channel[0].id, parent.id);
+
+            let replies = db
+                .fetch_messages(query(Some(thread.id.clone())))
+                .await
+                .unwrap();
+            assert_eq!(replies.len(), 1);
+            assert_eq!(replies[0].id, reply.id);
+
+            thread.leave(&db, "creator").await.unwrap();
+            thread
+                .update(
+                    &db,
+                    PartialThread {
+                        archived: Some(true),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+
+            let fetched = db.fetch_thread(&thread.id).await.unwrap();
+            assert!(fetched.archived);
+            assert_eq!(fetched.members, vec!["member"]);
+            assert_eq!(db.fetch_threads("channel").await.unwrap(), vec![fetched]);
+
+            thread.delete(&db).await.unwrap();
+            assert!(db.fetch_thread(&thread.id).await.is_err());
+            assert!(db.fetch_message(&reply.id).await.is_err());
+            assert!(db.fetch_message(&parent.id).await.is_ok());
+        });
+    }
+}
@@ -0,0 +1,33 @@
+use guilderia_result::Result;
+
+use crate::{PartialThread, Thread};
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractThreads: Sync + Send {
+    /// Insert a new thread
+    async fn insert_thread(&self, thread: &Thread) -> Result<()>;
+
+    /// Fetch a thread by the id of the message it branches off
+    async fn fetch_thread(&self, id: &str) -> Result<Thread>;
+
+    /// Fetch all threads in a channel
+    async fn fetch_threads(&self, channel: &str) -> Result<Vec<Thread>>;
+
+    /// Update a thread with new information
+    async fn update_thread(&self, id: &str, partial: &PartialThread) -> Result<()>;
+
+    /// Add a user to a thread's members
+    async fn add_thread_member(&self, id: &str, user: &str) -> Result<()>;
+
+    /// Remove a user from a thread's members
+    async fn remove_thread_member(&self, id: &str, user: &str) -> Result<()>;
+
+    /// Count a message sent in a thread
+    async fn record_thread_message(&self, id: &str, message: &str) -> Result<()>;
+
+    /// Delete a thread
+    async fn delete_thread(&self, id: &str) -> Result<()>;
+}
@@ -0,0 +1,100 @@
+use guilderia_result::Result;
+
+use crate::MongoDb;
+use crate::{PartialThread, Thread};
+
+use super::AbstractThreads;
+
+static COL: &str = "threads";
+
+#[async_trait]
+impl AbstractThreads for MongoDb {
+    /// Insert a new thread
+    async fn insert_thread(&self, thread: &Thread) -> Result<()> {
+        query!(self, insert_one, COL, &thread).map(|_| ())
+    }
+
+    /// Fetch a thread by the id of the message it branches off
+    async fn fetch_thread(&self, id: &str) -> Result<Thread> {
+        query!(self, find_one_by_id, COL, id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all threads in a channel
+    async fn fetch_threads(&self, channel: &str) -> Result<Vec<Thread>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "channel": channel
+            }
+        )
+    }
+
+    /// Update a thread with new information
+    async fn update_thread(&self, id: &str, partial: &PartialThread) -> Result<()> {
+        query!(self, update_one_by_id, COL, id, partial, vec![], None).map(|_| ())
+    }
+
+    /// Add a user to a thread's members
+    async fn add_thread_member(&self, id: &str, user: &str) -> Result<()> {
+        self.col::<Thread>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$addToSet": {
+                        "members": user
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Remove a user from a thread's members
+    async fn remove_thread_member(&self, id: &str, user: &str) -> Result<()> {
+        self.col::<Thread>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$pull": {
+                        "members": user
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Count a message sent in a thread
+    async fn record_thread_message(&self, id: &str, message: &str) -> Result<()> {
+        self.col::<Thread>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$inc": {
+                        "message_count": 1_i64
+                    },
+                    "$set": {
+                        "last_message_id": message
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Delete a thread
+    async fn delete_thread(&self, id: &str) -> Result<()> {
+        query!(self, delete_one_by_id, COL, id).map(|_| ())
+    }
+}
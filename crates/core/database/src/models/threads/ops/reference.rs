@@ -0,0 +1,97 @@
+use guilderia_result::Result;
+
+use crate::ReferenceDb;
+use crate::{PartialThread, Thread};
+
+use super::AbstractThreads;
+
+#[async_trait]
+impl AbstractThreads for ReferenceDb {
+    /// Insert a new thread
+    async fn insert_thread(&self, thread: &Thread) -> Result<()> {
+        let mut threads = self.threads.lock().await;
+        if threads.contains_key(&thread.id) {
+            Err(create_database_error!("insert", "thread"))
+        } else {
+            threads.insert(thread.id.to_string(), thread.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a thread by the id of the message it branches off
+    async fn fetch_thread(&self, id: &str) -> Result<Thread> {
+        let threads = self.threads.lock().await;
+        threads
+            .get(id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all threads in a channel
+    async fn fetch_threads(&self, channel: &str) -> Result<Vec<Thread>> {
+        let threads = self.threads.lock().await;
+        Ok(threads
+            .values()
+            .filter(|thread| thread.channel == channel)
+            .cloned()
+            .collect())
+    }
+
+    /// Update a thread with new information
+    async fn update_thread(&self, id: &str, partial: &PartialThread) -> Result<()> {
+        let mut threads = self.threads.lock().await;
+        if let Some(thread) = threads.get_mut(id) {
+            thread.apply_options(partial.clone());
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Add a user to a thread's members
+    async fn add_thread_member(&self, id: &str, user: &str) -> Result<()> {
+        let mut threads = self.threads.lock().await;
+        if let Some(thread) = threads.get_mut(id) {
+            if !thread.members.iter().any(|member| member == user) {
+                thread.members.push(user.to_string());
+            }
+
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Remove a user from a thread's members
+    async fn remove_thread_member(&self, id: &str, user: &str) -> Result<()> {
+        let mut threads = self.threads.lock().await;
+        if let Some(thread) = threads.get_mut(id) {
+            thread.members.retain(|member| member != user);
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Count a message sent in a thread
+    async fn record_thread_message(&self, id: &str, message: &str) -> Result<()> {
+        let mut threads = self.threads.lock().await;
+        if let Some(thread) = threads.get_mut(id) {
+            thread.message_count += 1;
+            thread.last_message_id = Some(message.to_string());
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Delete a thread
+    async fn delete_thread(&self, id: &str) -> Result<()> {
+        let mut threads = self.threads.lock().await;
+        if threads.remove(id).is_some() {
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+}
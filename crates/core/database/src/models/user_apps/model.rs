@@ -0,0 +1,56 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_result::Result;
+
+use crate::{Bot, Database, User};
+
+auto_derived!(
+    /// Bot installed by a user
+    ///
+    /// Grants the bot the ability to respond to the user's
+    /// commands in DMs and groups it is not a member of.
+    pub struct UserApp {
+        /// Unique installation id
+        #[serde(rename = "_id")]
+        pub id: UserAppCompositeKey,
+        /// Time at which the bot was installed
+        pub installed_at: Timestamp,
+    }
+
+    /// Composite primary key consisting of bot and user id
+    #[derive(Hash, Default)]
+    pub struct UserAppCompositeKey {
+        /// Bot Id
+        pub bot: String,
+        /// User Id
+        pub user: String,
+    }
+);
+
+#[allow(clippy::disallowed_methods)]
+impl UserApp {
+    /// Install a bot for a user
+    pub async fn create(db: &Database, bot: &Bot, user: &User) -> Result<UserApp> {
+        if user.bot.is_some() {
+            return Err(create_error!(IsBot));
+        }
+
+        if !bot.user_installable {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        if !bot.public && bot.owner != user.id {
+            return Err(create_error!(BotIsPrivate));
+        }
+
+        let app = UserApp {
+            id: UserAppCompositeKey {
+                bot: bot.id.to_string(),
+                user: user.id.to_string(),
+            },
+            installed_at: Timestamp::now_utc(),
+        };
+
+        db.insert_user_app(&app).await?;
+        Ok(app)
+    }
+}
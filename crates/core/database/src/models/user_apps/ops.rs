@@ -0,0 +1,21 @@
+use guilderia_result::Result;
+
+use crate::{UserApp, UserAppCompositeKey};
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractUserApps: Sync + Send {
+    /// Insert new user app into database
+    async fn insert_user_app(&self, app: &UserApp) -> Result<()>;
+
+    /// Fetch a user app by bot and user id
+    async fn fetch_user_app(&self, bot_id: &str, user_id: &str) -> Result<UserApp>;
+
+    /// Fetch all apps a user has installed
+    async fn fetch_user_apps(&self, user_id: &str) -> Result<Vec<UserApp>>;
+
+    /// Delete a user app from the database
+    async fn delete_user_app(&self, id: &UserAppCompositeKey) -> Result<()>;
+}
@@ -0,0 +1,56 @@
+use guilderia_result::Result;
+
+use crate::MongoDb;
+use crate::{UserApp, UserAppCompositeKey};
+
+use super::AbstractUserApps;
+
+static COL: &str = "user_apps";
+
+#[async_trait]
+impl AbstractUserApps for MongoDb {
+    /// Insert new user app into database
+    async fn insert_user_app(&self, app: &UserApp) -> Result<()> {
+        query!(self, insert_one, COL, &app).map(|_| ())
+    }
+
+    /// Fetch a user app by bot and user id
+    async fn fetch_user_app(&self, bot_id: &str, user_id: &str) -> Result<UserApp> {
+        query!(
+            self,
+            find_one,
+            COL,
+            doc! {
+                "_id.bot": bot_id,
+                "_id.user": user_id
+            }
+        )?
+        .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all apps a user has installed
+    async fn fetch_user_apps(&self, user_id: &str) -> Result<Vec<UserApp>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "_id.user": user_id
+            }
+        )
+    }
+
+    /// Delete a user app from the database
+    async fn delete_user_app(&self, id: &UserAppCompositeKey) -> Result<()> {
+        query!(
+            self,
+            delete_one,
+            COL,
+            doc! {
+                "_id.bot": &id.bot,
+                "_id.user": &id.user
+            }
+        )
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,52 @@
+use guilderia_result::Result;
+
+use crate::ReferenceDb;
+use crate::{UserApp, UserAppCompositeKey};
+
+use super::AbstractUserApps;
+
+#[async_trait]
+impl AbstractUserApps for ReferenceDb {
+    /// Insert new user app into database
+    async fn insert_user_app(&self, app: &UserApp) -> Result<()> {
+        let mut user_apps = self.user_apps.lock().await;
+        if user_apps.contains_key(&app.id) {
+            Err(create_database_error!("insert", "user_app"))
+        } else {
+            user_apps.insert(app.id.clone(), app.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a user app by bot and user id
+    async fn fetch_user_app(&self, bot_id: &str, user_id: &str) -> Result<UserApp> {
+        let user_apps = self.user_apps.lock().await;
+        user_apps
+            .get(&UserAppCompositeKey {
+                bot: bot_id.to_string(),
+                user: user_id.to_string(),
+            })
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all apps a user has installed
+    async fn fetch_user_apps(&self, user_id: &str) -> Result<Vec<UserApp>> {
+        let user_apps = self.user_apps.lock().await;
+        Ok(user_apps
+            .values()
+            .filter(|app| app.id.user == user_id)
+            .cloned()
+            .collect())
+    }
+
+    /// Delete a user app from the database
+    async fn delete_user_app(&self, id: &UserAppCompositeKey) -> Result<()> {
+        let mut user_apps = self.user_apps.lock().await;
+        if user_apps.remove(id).is_some() {
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+}
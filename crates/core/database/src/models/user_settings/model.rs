@@ -1,11 +1,149 @@
 use std::collections::HashMap;
 
-use crate::{events::client::EventV1, Database};
+use crate::{events::client::EventV1, Database, DelayedNotification};
 
+use chrono::{DateTime, Days, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use guilderia_models::v0::PushNotification;
 use guilderia_result::Result;
+use iso8601_timestamp::{Duration, Timestamp};
 
 pub type UserSettings = HashMap<String, (i64, String)>;
 
+/// Settings key under which quiet hours are stored
+pub static QUIET_HOURS_KEY: &str = "quiet_hours";
+
+/// Settings key under which the user's timezone is stored
+pub static TIMEZONE_KEY: &str = "timezone";
+
+/// Settings key under which the user's preferred locale is stored
+pub static LOCALE_KEY: &str = "locale";
+
+/// Settings key under which per-server and per-channel notification choices are stored
+pub static NOTIFICATIONS_KEY: &str = "notifications";
+
+/// Settings key under which highlight keywords are stored
+pub static HIGHLIGHTS_KEY: &str = "highlights";
+
+/// Settings key under which data minimisation preferences are stored
+pub static DATA_MINIMISATION_KEY: &str = "data_minimisation";
+
+/// Settings key under which the bots allowed to send the user direct messages are stored
+pub static BOT_DMS_KEY: &str = "bot_dms";
+
+/// Maximum number of bots a user may allow to send them direct messages
+pub const MAX_ALLOWED_BOTS: usize = 100;
+
+/// Settings key under which the server reports on data minimisation
+///
+/// Only the server may write to this key.
+pub static DATA_MINIMISATION_STATUS_KEY: &str = "data_minimisation_status";
+
+auto_derived!(
+    /// Period of the day during which push notifications are suppressed
+    pub struct QuietHours {
+        /// Minutes after local midnight at which quiet hours begin
+        pub start: u16,
+        /// Minutes after local midnight at which quiet hours end
+        pub end: u16,
+        /// Whether direct mentions from friends still notify
+        #[serde(default = "default_allow_friends")]
+        pub allow_friends: bool,
+        /// Whether held back notifications are delivered once quiet hours end
+        /// instead of being dropped
+        #[serde(default)]
+        pub delay: bool,
+    }
+);
+
+auto_derived!(
+    /// Notification modes the user picked for specific servers and channels
+    ///
+    /// Modes are stored as the client writes them, e.g. `all`, `mention`, `none` or `muted`.
+    #[derive(Default)]
+    pub struct NotificationOverrides {
+        /// Modes keyed by server id
+        #[serde(default)]
+        pub server: HashMap<String, String>,
+        /// Modes keyed by channel id
+        #[serde(default)]
+        pub channel: HashMap<String, String>,
+    }
+);
+
+auto_derived!(
+    /// Keywords which notify the user as if they were mentioned
+    #[derive(Default)]
+    pub struct HighlightKeywords {
+        /// Keywords matched in every server
+        #[serde(default)]
+        pub global: Vec<String>,
+        /// Keywords keyed by server id
+        #[serde(default)]
+        pub server: HashMap<String, Vec<String>>,
+    }
+);
+
+auto_derived!(
+    /// Automatic clean-up of data the user created
+    #[derive(Default)]
+    pub struct DataMinimisation {
+        /// Delete messages the user sent in direct messages and groups after this many days
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        pub delete_dm_messages_after_days: Option<u32>,
+        /// Remove attachments from any message the user sent after this many days
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        pub strip_attachments_after_days: Option<u32>,
+    }
+);
+
+auto_derived!(
+    /// Outcome of the most recent data minimisation run for a user
+    #[derive(Default)]
+    pub struct DataMinimisationStatus {
+        /// Time at which the run finished, in milliseconds since the epoch
+        pub last_run: i64,
+        /// Number of messages deleted
+        pub messages_deleted: usize,
+        /// Number of attachments removed
+        pub attachments_removed: usize,
+        /// Whether more data is due and will be handled on the next run
+        pub pending: bool,
+        /// Why the run stopped early, if it failed
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        pub error: Option<String>,
+    }
+);
+
+auto_derived!(
+    /// Which bots may send the user direct messages
+    pub struct BotDmPreferences {
+        /// Whether bots which are not on the allowlist may message the user
+        #[serde(default = "default_allow_all_bots")]
+        pub allow_all: bool,
+        /// Ids of bots and webhooks which may always message the user
+        #[serde(default)]
+        pub allowed: Vec<String>,
+    }
+);
+
+fn default_allow_friends() -> bool {
+    true
+}
+
+fn default_allow_all_bots() -> bool {
+    true
+}
+
+impl Default for BotDmPreferences {
+    fn default() -> Self {
+        BotDmPreferences {
+            allow_all: true,
+            allowed: vec![],
+        }
+    }
+}
+
 #[async_trait]
 pub trait UserSettingsImpl {
     async fn set(self, db: &Database, user: &str) -> Result<()>;
@@ -26,3 +164,351 @@ impl UserSettingsImpl for UserSettings {
         Ok(())
     }
 }
+
+impl NotificationOverrides {
+    /// Read notification overrides from a user's settings
+    pub fn from_settings(settings: &UserSettings) -> NotificationOverrides {
+        settings
+            .get(NOTIFICATIONS_KEY)
+            .map(|(_, value)| NotificationOverrides::parse(value))
+            .unwrap_or_default()
+    }
+
+    /// Parse notification overrides from a stored settings value
+    pub fn parse(value: &str) -> NotificationOverrides {
+        serde_json::from_str(value).unwrap_or_default()
+    }
+
+    /// Whether the user asked for every message in a channel, if they picked anything
+    ///
+    /// Channel choices take priority over server choices.
+    pub fn wants_all_messages(&self, server: &str, channel: &str) -> Option<bool> {
+        self.channel
+            .get(channel)
+            .or_else(|| self.server.get(server))
+            .map(|mode| mode == "all")
+    }
+}
+
+impl HighlightKeywords {
+    /// Parse highlight keywords from a stored settings value
+    pub fn parse(value: &str) -> Option<HighlightKeywords> {
+        serde_json::from_str(value).ok()
+    }
+
+    /// Total number of keywords across all servers
+    pub fn keyword_count(&self) -> usize {
+        self.global.len() + self.server.values().map(Vec::len).sum::<usize>()
+    }
+
+    /// Iterate over every keyword
+    pub fn keywords(&self) -> impl Iterator<Item = &String> {
+        self.global.iter().chain(self.server.values().flatten())
+    }
+
+    /// Check whether any keyword applicable to a server appears in a message
+    ///
+    /// Keywords match whole words, ignoring case, and may span several words.
+    pub fn matches(&self, server: &str, words: &[String]) -> bool {
+        self.global
+            .iter()
+            .chain(self.server.get(server).into_iter().flatten())
+            .any(|keyword| {
+                let keyword: Vec<String> = guilderia_parser::split_words(keyword).collect();
+                !keyword.is_empty()
+                    && words
+                        .windows(keyword.len())
+                        .any(|window| window == keyword.as_slice())
+            })
+    }
+}
+
+impl DataMinimisation {
+    /// Parse data minimisation preferences from a stored settings value
+    ///
+    /// Periods must be at least a day long.
+    pub fn parse(value: &str) -> Option<DataMinimisation> {
+        serde_json::from_str::<DataMinimisation>(value)
+            .ok()
+            .filter(|settings| {
+                settings.delete_dm_messages_after_days != Some(0)
+                    && settings.strip_attachments_after_days != Some(0)
+            })
+    }
+
+    /// Whether any clean-up is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.delete_dm_messages_after_days.is_some() || self.strip_attachments_after_days.is_some()
+    }
+}
+
+impl BotDmPreferences {
+    /// Parse bot direct message preferences from a stored settings value
+    pub fn parse(value: &str) -> Option<BotDmPreferences> {
+        serde_json::from_str::<BotDmPreferences>(value)
+            .ok()
+            .filter(|preferences| preferences.allowed.len() <= MAX_ALLOWED_BOTS)
+    }
+
+    /// Read bot direct message preferences from a user's settings
+    pub fn from_settings(settings: &UserSettings) -> BotDmPreferences {
+        settings
+            .get(BOT_DMS_KEY)
+            .and_then(|(_, value)| BotDmPreferences::parse(value))
+            .unwrap_or_default()
+    }
+
+    /// Whether a bot or webhook may message the user
+    pub fn permits(&self, sender: &str) -> bool {
+        self.allow_all || self.allowed.iter().any(|id| id == sender)
+    }
+
+    /// Ensure a bot or webhook may send a user direct messages
+    ///
+    /// Users may always receive messages from bots they own.
+    pub async fn check(db: &Database, user: &str, sender: &str) -> Result<()> {
+        let settings = db
+            .fetch_user_settings(user, &[BOT_DMS_KEY.to_string()])
+            .await?;
+
+        if BotDmPreferences::from_settings(&settings).permits(sender)
+            || db
+                .fetch_bot(sender)
+                .await
+                .is_ok_and(|bot| bot.owner == user)
+        {
+            Ok(())
+        } else {
+            Err(create_error!(BotMessagesBlocked))
+        }
+    }
+}
+
+impl QuietHours {
+    /// Parse quiet hours from a stored settings value
+    pub fn parse(value: &str) -> Option<QuietHours> {
+        serde_json::from_str::<QuietHours>(value)
+            .ok()
+            .filter(|hours| hours.start < 1440 && hours.end < 1440)
+    }
+
+    /// Parse a timezone from a stored settings value
+    ///
+    /// Accepts either a bare IANA name or a JSON encoded string.
+    pub fn parse_timezone(value: &str) -> Option<Tz> {
+        serde_json::from_str::<String>(value)
+            .unwrap_or_else(|_| value.to_string())
+            .parse()
+            .ok()
+    }
+
+    /// Check whether quiet hours are in effect at the given time
+    pub fn is_active(&self, now: DateTime<Utc>, timezone: Tz) -> bool {
+        let local = now.with_timezone(&timezone);
+        let minute = (local.hour() * 60 + local.minute()) as u16;
+
+        if self.start <= self.end {
+            self.start <= minute && minute < self.end
+        } else {
+            // Wraps around midnight
+            minute >= self.start || minute < self.end
+        }
+    }
+
+    /// Find the next time at which quiet hours end
+    pub fn ends_at(&self, now: DateTime<Utc>, timezone: Tz) -> DateTime<Utc> {
+        let local = now.with_timezone(&timezone).naive_local();
+        let mut end = local
+            .date()
+            .and_hms_opt((self.end / 60) as u32, (self.end % 60) as u32, 0)
+            .unwrap_or(local);
+
+        if end <= local {
+            end = end.checked_add_days(Days::new(1)).unwrap_or(end);
+        }
+
+        // Local times skipped over by daylight saving resolve to the hour after
+        timezone
+            .from_local_datetime(&end)
+            .earliest()
+            .or_else(|| {
+                timezone
+                    .from_local_datetime(&(end + chrono::Duration::hours(1)))
+                    .earliest()
+            })
+            .map(|end| end.with_timezone(&Utc))
+            .unwrap_or(now)
+    }
+
+    /// Filter out users whose quiet hours hold back a push notification
+    ///
+    /// Settings are fetched for all users at once. Direct mentions from friends
+    /// still go through unless the user opted out of that, and users who asked
+    /// for it have the notification delivered once their quiet hours end.
+    pub async fn filter(db: &Database, users: Vec<String>, push: &PushNotification) -> Vec<String> {
+        let quiet_hours = match db.fetch_setting_for_users(QUIET_HOURS_KEY, &users).await {
+            Ok(quiet_hours) if !quiet_hours.is_empty() => quiet_hours,
+            _ => return users,
+        };
+
+        let holders: Vec<String> = quiet_hours.keys().cloned().collect();
+        let timezones = db
+            .fetch_setting_for_users(TIMEZONE_KEY, &holders)
+            .await
+            .unwrap_or_default();
+
+        let now = Utc::now();
+        let mentions = push.message.mentions.as_deref().unwrap_or_default();
+        let mut author = None;
+        let mut targets = Vec::with_capacity(users.len());
+        for user in users {
+            let Some(hours) = quiet_hours
+                .get(&user)
+                .and_then(|value| QuietHours::parse(value))
+            else {
+                targets.push(user);
+                continue;
+            };
+
+            // Falls back to UTC if the user has not stored a timezone
+            let timezone = timezones
+                .get(&user)
+                .and_then(|value| QuietHours::parse_timezone(value))
+                .unwrap_or(Tz::UTC);
+
+            if !hours.is_active(now, timezone) {
+                targets.push(user);
+                continue;
+            }
+
+            if hours.allow_friends && mentions.contains(&user) {
+                if author.is_none() {
+                    author = Some(db.fetch_user(&push.message.author).await.ok());
+                }
+
+                if matches!(&author, Some(Some(author)) if author.is_friends_with(&user)) {
+                    targets.push(user);
+                    continue;
+                }
+            }
+
+            if hours.delay {
+                let deliver_at = Timestamp::UNIX_EPOCH
+                    .checked_add(Duration::seconds(hours.ends_at(now, timezone).timestamp()))
+                    .unwrap_or_else(Timestamp::now_utc);
+
+                if let Err(err) =
+                    DelayedNotification::create(db, user, deliver_at, push.clone()).await
+                {
+                    guilderia_config::capture_error(&err);
+                }
+            }
+        }
+
+        targets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn quiet_hours_wrap_around_midnight() {
+        let hours = QuietHours {
+            start: 22 * 60,
+            end: 7 * 60,
+            allow_friends: true,
+            delay: true,
+        };
+
+        let late = Utc.with_ymd_and_hms(2024, 1, 1, 23, 30, 0).unwrap();
+        let noon = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        assert!(hours.is_active(late, Tz::UTC));
+        assert!(!hours.is_active(noon, Tz::UTC));
+    }
+
+    #[test]
+    fn quiet_hours_respect_timezone() {
+        let hours = QuietHours {
+            start: 9 * 60,
+            end: 17 * 60,
+            allow_friends: false,
+            delay: false,
+        };
+
+        // 20:00 UTC is 09:00 in Tokyo
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap();
+        let tokyo = QuietHours::parse_timezone("\"Asia/Tokyo\"").unwrap();
+
+        assert!(hours.is_active(now, tokyo));
+        assert!(!hours.is_active(now, Tz::UTC));
+    }
+
+    #[test]
+    fn quiet_hours_end_at_next_local_end() {
+        let hours = QuietHours {
+            start: 22 * 60,
+            end: 7 * 60,
+            allow_friends: true,
+            delay: true,
+        };
+
+        let late = Utc.with_ymd_and_hms(2024, 1, 1, 23, 30, 0).unwrap();
+        assert_eq!(
+            hours.ends_at(late, Tz::UTC),
+            Utc.with_ymd_and_hms(2024, 1, 2, 7, 0, 0).unwrap()
+        );
+
+        // 06:00 in Tokyo is 21:00 UTC the day before
+        let early = Utc.with_ymd_and_hms(2024, 1, 1, 21, 0, 0).unwrap();
+        let tokyo = QuietHours::parse_timezone("Asia/Tokyo").unwrap();
+        assert_eq!(
+            hours.ends_at(early, tokyo),
+            Utc.with_ymd_and_hms(2024, 1, 1, 22, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn highlights_match_whole_words() {
+        let keywords =
+            HighlightKeywords::parse(r#"{"global":["Release Notes"],"server":{"a":["deploy"]}}"#)
+                .unwrap();
+
+        assert_eq!(keywords.keyword_count(), 2);
+
+        let words = guilderia_parser::parse_words("the release notes are out, deploying now");
+        assert!(keywords.matches("b", &words));
+
+        let words = guilderia_parser::parse_words("we deploy at noon");
+        assert!(keywords.matches("a", &words));
+        assert!(!keywords.matches("b", &words));
+
+        let words = guilderia_parser::parse_words("`deploy` and release");
+        assert!(!keywords.matches("a", &words));
+    }
+
+    #[test]
+    fn bot_dms_respect_allowlist() {
+        let preferences =
+            BotDmPreferences::parse(r#"{"allow_all":false,"allowed":["bot"]}"#).unwrap();
+        assert!(preferences.permits("bot"));
+        assert!(!preferences.permits("spammer"));
+
+        let settings = UserSettings::new();
+        assert!(BotDmPreferences::from_settings(&settings).permits("spammer"));
+    }
+
+    #[test]
+    fn data_minimisation_requires_whole_days() {
+        let settings = DataMinimisation::parse(r#"{"delete_dm_messages_after_days":30}"#).unwrap();
+        assert!(settings.is_enabled());
+
+        assert!(!DataMinimisation::parse("{}").unwrap().is_enabled());
+        assert!(DataMinimisation::parse(r#"{"strip_attachments_after_days":0}"#).is_none());
+        assert!(DataMinimisation::parse(r#"{"strip_attachments_after_days":-1}"#).is_none());
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use guilderia_result::Result;
 
 use crate::UserSettings;
@@ -10,7 +12,23 @@ pub trait AbstractUserSettings: Sync + Send {
     /// Fetch a subset of user settings
     async fn fetch_user_settings(&'_ self, id: &str, filter: &'_ [String]) -> Result<UserSettings>;
 
+    /// Fetch all of a user's settings
+    async fn fetch_all_user_settings(&self, id: &str) -> Result<UserSettings>;
+
+    /// Fetch a single setting from every user who has set it, keyed by user id
+    async fn fetch_users_with_setting(&self, key: &str) -> Result<HashMap<String, String>>;
+
+    /// Fetch a single setting from each of the given users who has set it, keyed by user id
+    async fn fetch_setting_for_users(
+        &self,
+        key: &str,
+        users: &[String],
+    ) -> Result<HashMap<String, String>>;
+
     /// Update a subset of user settings
+    ///
+    /// Keys are written together or not at all, failing if any of them
+    /// already holds a later revision than the one being written.
     async fn set_user_settings(&self, id: &str, settings: &UserSettings) -> Result<()>;
 
     /// Delete all user settings
@@ -1,6 +1,10 @@
+use std::collections::HashMap;
+
 use ::mongodb::options::FindOneOptions;
 use bson::to_bson;
 use bson::Document;
+use futures::StreamExt;
+use mongodb::error::{ErrorKind, WriteFailure};
 use mongodb::options::UpdateOptions;
 use guilderia_result::Result;
 
@@ -35,20 +39,109 @@ impl AbstractUserSettings for MongoDb {
         .unwrap_or_default())
     }
 
+    /// Fetch all of a user's settings
+    async fn fetch_all_user_settings(&self, id: &str) -> Result<UserSettings> {
+        Ok(query!(
+            self,
+            find_one_with_options,
+            COL,
+            doc! {
+                "_id": id
+            },
+            FindOneOptions::builder()
+                .projection(doc! {
+                    "_id": 0
+                })
+                .build()
+        )?
+        .unwrap_or_default())
+    }
+
+    /// Fetch a single setting from every user who has set it, keyed by user id
+    async fn fetch_users_with_setting(&self, key: &str) -> Result<HashMap<String, String>> {
+        Ok(self
+            .col::<Document>(COL)
+            .find(doc! {
+                key: {
+                    "$exists": true
+                }
+            })
+            .projection(doc! {
+                "_id": 1,
+                key: 1
+            })
+            .await
+            .map_err(|_| create_database_error!("find", COL))?
+            .filter_map(|document| async move {
+                let document = document.ok()?;
+                let id = document.get_str("_id").ok()?.to_string();
+                let value = document.get_array(key).ok()?.get(1)?.as_str()?.to_string();
+                Some((id, value))
+            })
+            .collect()
+            .await)
+    }
+
+    /// Fetch a single setting from each of the given users who has set it, keyed by user id
+    async fn fetch_setting_for_users(
+        &self,
+        key: &str,
+        users: &[String],
+    ) -> Result<HashMap<String, String>> {
+        Ok(self
+            .col::<Document>(COL)
+            .find(doc! {
+                "_id": {
+                    "$in": users
+                },
+                key: {
+                    "$exists": true
+                }
+            })
+            .projection(doc! {
+                "_id": 1,
+                key: 1
+            })
+            .await
+            .map_err(|_| create_database_error!("find", COL))?
+            .filter_map(|document| async move {
+                let document = document.ok()?;
+                let id = document.get_str("_id").ok()?.to_string();
+                let value = document.get_array(key).ok()?.get(1)?.as_str()?.to_string();
+                Some((id, value))
+            })
+            .collect()
+            .await)
+    }
+
     /// Update a subset of user settings
     async fn set_user_settings(&self, id: &str, settings: &UserSettings) -> Result<()> {
+        if settings.is_empty() {
+            return Ok(());
+        }
+
         let mut set = doc! {};
+        let mut newer = vec![];
         for (key, data) in settings {
             set.insert(
                 key,
                 vec![to_bson(&data.0).unwrap(), to_bson(&data.1).unwrap()],
             );
+
+            newer.push(doc! {
+                format!("{key}.0"): {
+                    "$gt": data.0
+                }
+            });
         }
 
+        // If a key holds a later revision the filter misses the existing
+        // document, so the upsert collides with its id and nothing is written
         self.col::<Document>(COL)
             .update_one(
                 doc! {
-                    "_id": id
+                    "_id": id,
+                    "$nor": newer
                 },
                 doc! {
                     "$set": set
@@ -57,7 +150,12 @@ impl AbstractUserSettings for MongoDb {
             .with_options(UpdateOptions::builder().upsert(true).build())
             .await
             .map(|_| ())
-            .map_err(|_| create_database_error!("update_one", "user_settings"))
+            .map_err(|err| match *err.kind {
+                ErrorKind::Write(WriteFailure::WriteError(ref error)) if error.code == 11000 => {
+                    create_error!(OutdatedSettings)
+                }
+                _ => create_database_error!("update_one", COL),
+            })
     }
 
     /// Delete all user settings
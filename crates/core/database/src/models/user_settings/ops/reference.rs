@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use guilderia_result::Result;
 
 use crate::ReferenceDb;
@@ -22,15 +24,57 @@ impl AbstractUserSettings for ReferenceDb {
             .ok_or_else(|| create_error!(NotFound))
     }
 
+    /// Fetch all of a user's settings
+    async fn fetch_all_user_settings(&self, id: &str) -> Result<UserSettings> {
+        let user_settings = self.user_settings.lock().await;
+        Ok(user_settings.get(id).cloned().unwrap_or_default())
+    }
+
+    /// Fetch a single setting from every user who has set it, keyed by user id
+    async fn fetch_users_with_setting(&self, key: &str) -> Result<HashMap<String, String>> {
+        let user_settings = self.user_settings.lock().await;
+        Ok(user_settings
+            .iter()
+            .filter_map(|(id, settings)| {
+                settings
+                    .get(key)
+                    .map(|(_, value)| (id.to_string(), value.to_string()))
+            })
+            .collect())
+    }
+
+    /// Fetch a single setting from each of the given users who has set it, keyed by user id
+    async fn fetch_setting_for_users(
+        &self,
+        key: &str,
+        users: &[String],
+    ) -> Result<HashMap<String, String>> {
+        let user_settings = self.user_settings.lock().await;
+        Ok(users
+            .iter()
+            .filter_map(|id| {
+                user_settings
+                    .get(id)?
+                    .get(key)
+                    .map(|(_, value)| (id.to_string(), value.to_string()))
+            })
+            .collect())
+    }
+
     /// Update a subset of user settings
     async fn set_user_settings(&self, id: &str, settings: &UserSettings) -> Result<()> {
         let mut user_settings = self.user_settings.lock().await;
-        if let Some(settings) = user_settings.get_mut(id) {
-            settings.extend(settings.clone());
-        } else {
-            user_settings.insert(id.to_string(), settings.clone());
+        let stored = user_settings.entry(id.to_string()).or_default();
+
+        if settings.iter().any(|(key, (timestamp, _))| {
+            stored
+                .get(key)
+                .is_some_and(|(revision, _)| revision > timestamp)
+        }) {
+            return Err(create_error!(OutdatedSettings));
         }
 
+        stored.extend(settings.clone());
         Ok(())
     }
 
@@ -2,7 +2,7 @@ use axum::{extract::FromRequestParts, http::request::Parts};
 
 use guilderia_result::{create_error, Error, Result};
 
-use crate::{Database, User};
+use crate::{ApiToken, Database, Device, User};
 
 #[async_trait::async_trait]
 impl FromRequestParts<Database> for User {
@@ -12,11 +12,50 @@ impl FromRequestParts<Database> for User {
         if let Some(Ok(bot_token)) = parts.headers.get("x-bot-token").map(|v| v.to_str()) {
             let bot = db.fetch_bot_by_token(bot_token).await?;
             db.fetch_user(&bot.id).await
+        } else if let Some(Ok(api_token)) = parts.headers.get("x-api-token").map(|v| v.to_str()) {
+            let token = db
+                .fetch_api_token_by_hash(&ApiToken::hash_secret(api_token))
+                .await
+                .map_err(|_| create_error!(InvalidSession))?;
+
+            if !token.scope.permits(parts.method.as_str(), parts.uri.path()) {
+                return Err(create_error!(MissingPermission {
+                    permission: "ApiTokenScope".to_string()
+                }));
+            }
+
+            token.mark_used(db).await.ok();
+            db.fetch_user(&token.user).await
+        } else if let Some(Ok(impersonation_token)) = parts
+            .headers
+            .get("x-impersonation-token")
+            .map(|v| v.to_str())
+        {
+            let impersonation = db
+                .fetch_impersonation_by_token(impersonation_token)
+                .await
+                .ok()
+                .filter(|impersonation| impersonation.is_active())
+                .ok_or_else(|| create_error!(InvalidSession))?;
+
+            if !impersonation.permits(parts.method.as_str(), parts.uri.path()) {
+                return Err(create_error!(MissingPermission {
+                    permission: "Impersonation".to_string()
+                }));
+            }
+
+            impersonation
+                .log_request(db, parts.method.as_str(), parts.uri.path())
+                .await
+                .ok();
+            db.fetch_user(&impersonation.user).await
         } else if let Some(Ok(session_token)) =
             parts.headers.get("x-session-token").map(|v| v.to_str())
         {
             let session = db.fetch_session_by_token(session_token).await?;
-            db.fetch_user(&session.user_id).await
+            let user = db.fetch_user(&session.user_id).await?;
+            Device::verify(db, &user, &session.id, &session.name).await?;
+            Ok(user)
         } else {
             Err(create_error!(NotAuthenticated))
         }
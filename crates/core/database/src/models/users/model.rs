@@ -1,6 +1,6 @@
 use std::{collections::HashSet, str::FromStr, time::Duration};
 
-use crate::{events::client::EventV1, Database, File, RatelimitEvent, AMQP};
+use crate::{events::client::EventV1, Database, Device, File, RatelimitEvent, AMQP};
 
 use authifier::config::{EmailVerificationConfig, Template};
 use futures::future::join_all;
@@ -10,7 +10,7 @@ use rand::seq::SliceRandom;
 use guilderia_config::{config, FeaturesLimits};
 use guilderia_models::v0::{self, UserBadges, UserFlags};
 use guilderia_presence::filter_online;
-use guilderia_result::{create_error, Result};
+use guilderia_result::{create_error, ErrorType, Result};
 use serde_json::json;
 use ulid::Ulid;
 
@@ -59,6 +59,12 @@ auto_derived_partial!(
         pub suspended_until: Option<Timestamp>,
         /// Last acknowledged policy change
         pub last_acknowledged_policy_change: Timestamp,
+        /// Whether sessions on new devices must be approved before they can be used
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub require_device_approval: bool,
+        /// Whether this user's account was created by an identity provider
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub provisioned: bool,
     },
     "PartialUser"
 );
@@ -72,6 +78,7 @@ auto_derived!(
         ProfileContent,
         ProfileBackground,
         DisplayName,
+        StatusActivity,
 
         // internal fields
         Suspension,
@@ -119,6 +126,70 @@ auto_derived!(
         /// Current presence option
         #[serde(skip_serializing_if = "Option::is_none")]
         pub presence: Option<Presence>,
+        /// What the user is currently doing
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub activity: Option<Activity>,
+    }
+
+    /// Activity shown on a user's presence, such as a game being played
+    pub struct Activity {
+        /// Kind of activity
+        #[serde(rename = "type")]
+        pub kind: ActivityType,
+        /// Name of the game, song or stream
+        pub name: String,
+        /// What the user is doing within the activity
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub details: Option<String>,
+        /// Current state of the activity, such as the album or party size
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub state: Option<String>,
+        /// Link to the stream
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub url: Option<String>,
+        /// Images shown alongside the activity
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub assets: Option<ActivityAssets>,
+        /// When the activity started and is due to end
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub timestamps: Option<ActivityTimestamps>,
+        /// Id of the bot which set this activity
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub application: Option<String>,
+    }
+
+    /// Kind of activity
+    pub enum ActivityType {
+        Playing,
+        Listening,
+        Watching,
+        Streaming,
+    }
+
+    /// Images shown alongside an activity
+    pub struct ActivityAssets {
+        /// Reference to the large image
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub large_image: Option<String>,
+        /// Text shown when hovering over the large image
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub large_text: Option<String>,
+        /// Reference to the small image
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub small_image: Option<String>,
+        /// Text shown when hovering over the small image
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub small_text: Option<String>,
+    }
+
+    /// When an activity started and is due to end
+    pub struct ActivityTimestamps {
+        /// Time at which the activity started
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub start: Option<Timestamp>,
+        /// Time at which the activity is due to end
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub end: Option<Timestamp>,
     }
 
     /// User's profile
@@ -181,6 +252,8 @@ impl Default for User {
             bot: Default::default(),
             suspended_until: Default::default(),
             last_acknowledged_policy_change: Timestamp::UNIX_EPOCH,
+            require_device_approval: Default::default(),
+            provisioned: Default::default(),
         }
     }
 }
@@ -231,6 +304,51 @@ impl User {
         }
     }
 
+    /// Whether this user has chosen to appear offline
+    pub fn is_invisible(&self) -> bool {
+        matches!(
+            self.status,
+            Some(UserStatus {
+                presence: Some(Presence::Invisible),
+                ..
+            })
+        )
+    }
+
+    /// Whether this user has set their presence to do not disturb
+    pub fn is_busy(&self) -> bool {
+        matches!(
+            self.status,
+            Some(UserStatus {
+                presence: Some(Presence::Busy),
+                ..
+            })
+        )
+    }
+
+    /// Whether this user is online and visible to others
+    pub async fn is_visibly_online(&self) -> bool {
+        !self.is_invisible() && guilderia_presence::is_online(&self.id).await
+    }
+
+    /// Move this user in or out of their servers' online counts after
+    /// switching to or from appearing offline
+    pub async fn update_online_counts(&self, db: &Database, was_invisible: bool) -> Result<()> {
+        if was_invisible == self.is_invisible() || !guilderia_presence::is_online(&self.id).await {
+            return Ok(());
+        }
+
+        let servers: Vec<String> = db
+            .fetch_all_memberships(&self.id)
+            .await?
+            .into_iter()
+            .map(|member| member.id.server)
+            .collect();
+
+        db.adjust_server_counts(&servers, 0, if was_invisible { 1 } else { -1 })
+            .await
+    }
+
     /// Get the relationship with another user
     pub fn relationship_with(&self, user_b: &str) -> RelationshipStatus {
         if self.id == user_b {
@@ -332,15 +450,17 @@ impl User {
             )),
             UserHint::User => {
                 let session = db.fetch_session_by_token(token).await?;
-                Ok((db.fetch_user(&session.user_id).await?, session.id))
+                let user = db.fetch_user(&session.user_id).await?;
+                Device::verify(db, &user, &session.id, &session.name).await?;
+                Ok((user, session.id))
             }
-            UserHint::Any => {
-                if let Ok(result) = User::from_token(db, token, UserHint::User).await {
-                    Ok(result)
-                } else {
+            UserHint::Any => match User::from_token(db, token, UserHint::User).await {
+                // Surface pending approval rather than trying the token as a bot token
+                Err(err) if !matches!(err.error_type, ErrorType::DeviceApprovalRequired) => {
                     User::from_token(db, token, UserHint::Bot).await
                 }
-            }
+                result => result,
+            },
         }
     }
 
@@ -414,6 +534,26 @@ impl User {
             .to_string())
     }
 
+    /// Set or clear what the user is currently doing
+    pub async fn set_activity(&mut self, db: &Database, activity: Option<Activity>) -> Result<()> {
+        if let Some(activity) = activity {
+            let mut status = self.status.clone().unwrap_or_default();
+            status.activity = Some(activity);
+            self.update(
+                db,
+                PartialUser {
+                    status: Some(status),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+        } else {
+            self.update(db, Default::default(), vec![FieldsUser::StatusActivity])
+                .await
+        }
+    }
+
     /// Update a user's username
     pub async fn update_username(&mut self, db: &Database, username: String) -> Result<()> {
         let username = User::validate_username(username)?;
@@ -679,6 +819,11 @@ impl User {
                     x.presence = None;
                 }
             }
+            FieldsUser::StatusActivity => {
+                if let Some(x) = self.status.as_mut() {
+                    x.activity = None;
+                }
+            }
             FieldsUser::ProfileContent => {
                 if let Some(x) = self.profile.as_mut() {
                     x.content = None;
@@ -792,6 +937,9 @@ impl User {
 
     /// Mark as deleted
     pub async fn mark_deleted(&mut self, db: &Database) -> Result<()> {
+        db.delete_api_tokens(&self.id).await?;
+        db.delete_devices(&self.id).await?;
+        db.delete_account_recovery(&self.id).await?;
         self.update(
             db,
             PartialUser {
@@ -803,6 +951,7 @@ impl User {
                 FieldsUser::Avatar,
                 FieldsUser::StatusText,
                 FieldsUser::StatusPresence,
+                FieldsUser::StatusActivity,
                 FieldsUser::ProfileContent,
                 FieldsUser::ProfileBackground,
                 FieldsUser::Suspension,
@@ -825,3 +974,113 @@ impl User {
         badges
     }
 }
+
+/// Whether an activity image reference is an attachment id or HTTPS URL
+fn is_asset_reference(reference: &str) -> bool {
+    reference.starts_with("https://") || !reference.contains(':')
+}
+
+impl Activity {
+    /// Check the activity makes sense before showing it to others
+    ///
+    /// Only streams may link out, images must be attachments or served over
+    /// HTTPS and the activity may not end before it starts.
+    pub fn check(&self) -> Result<()> {
+        if let Some(url) = &self.url {
+            if self.kind != ActivityType::Streaming
+                || !(url.starts_with("https://") || url.starts_with("http://"))
+            {
+                return Err(create_error!(InvalidProperty));
+            }
+        }
+
+        if let Some(assets) = &self.assets {
+            if [&assets.large_image, &assets.small_image]
+                .into_iter()
+                .flatten()
+                .any(|reference| !is_asset_reference(reference))
+            {
+                return Err(create_error!(InvalidProperty));
+            }
+        }
+
+        if let Some(ActivityTimestamps {
+            start: Some(start),
+            end: Some(end),
+        }) = &self.timestamps
+        {
+            if end < start {
+                return Err(create_error!(InvalidProperty));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iso8601_timestamp::{Duration, Timestamp};
+
+    use crate::{Activity, ActivityAssets, ActivityTimestamps, ActivityType};
+
+    #[test]
+    fn checks_activity() {
+        let activity = Activity {
+            kind: ActivityType::Listening,
+            name: "Music".to_string(),
+            details: None,
+            state: None,
+            url: None,
+            assets: Some(ActivityAssets {
+                large_image: Some("https://example.com/cover.png".to_string()),
+                large_text: None,
+                small_image: Some("01HZ0000000000000000000000".to_string()),
+                small_text: None,
+            }),
+            timestamps: None,
+            application: None,
+        };
+
+        assert!(activity.check().is_ok());
+
+        // Only streams may link out
+        let url = Some("https://example.com/live".to_string());
+        assert!(Activity {
+            url: url.clone(),
+            ..activity.clone()
+        }
+        .check()
+        .is_err());
+        assert!(Activity {
+            kind: ActivityType::Streaming,
+            url,
+            ..activity.clone()
+        }
+        .check()
+        .is_ok());
+
+        assert!(Activity {
+            assets: Some(ActivityAssets {
+                large_image: Some("javascript:alert(1)".to_string()),
+                large_text: None,
+                small_image: None,
+                small_text: None,
+            }),
+            ..activity.clone()
+        }
+        .check()
+        .is_err());
+
+        let now = Timestamp::now_utc();
+        assert!(Activity {
+            timestamps: Some(ActivityTimestamps {
+                start: Some(now),
+                end: now.checked_sub(Duration::seconds(60)),
+            }),
+            ..activity
+        }
+        .check()
+        .is_err());
+    }
+}
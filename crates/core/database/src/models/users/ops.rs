@@ -61,4 +61,11 @@ pub trait AbstractUsers: Sync + Send {
 
     /// Remove push subscription for a session by session id (TODO: remove)
     async fn remove_push_subscription_by_session_id(&self, session_id: &str) -> Result<()>;
+
+    /// Remove a push endpoint from every session except the given one
+    async fn remove_push_subscription_by_endpoint(
+        &self,
+        endpoint: &str,
+        except_session_id: &str,
+    ) -> Result<()>;
 }
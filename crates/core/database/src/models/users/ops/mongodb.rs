@@ -319,6 +319,31 @@ impl AbstractUsers for MongoDb {
             .map(|_| ())
             .map_err(|_| create_database_error!("update_one", COL))
     }
+
+    /// Remove a push endpoint from every session except the given one
+    async fn remove_push_subscription_by_endpoint(
+        &self,
+        endpoint: &str,
+        except_session_id: &str,
+    ) -> Result<()> {
+        self.col::<User>("sessions")
+            .update_many(
+                doc! {
+                    "_id": {
+                        "$ne": except_session_id
+                    },
+                    "subscription.endpoint": endpoint
+                },
+                doc! {
+                    "$unset": {
+                        "subscription": 1
+                    }
+                },
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_many", "sessions"))
+    }
 }
 
 impl IntoDocumentPath for FieldsUser {
@@ -329,6 +354,7 @@ impl IntoDocumentPath for FieldsUser {
             FieldsUser::ProfileContent => "profile.content",
             FieldsUser::StatusPresence => "status.presence",
             FieldsUser::StatusText => "status.text",
+            FieldsUser::StatusActivity => "status.activity",
             FieldsUser::DisplayName => "display_name",
             FieldsUser::Suspension => "suspended_until",
             FieldsUser::None => "none",
@@ -168,4 +168,14 @@ impl AbstractUsers for ReferenceDb {
     async fn remove_push_subscription_by_session_id(&self, _session_id: &str) -> Result<()> {
         todo!()
     }
+
+    /// Remove a push endpoint from every session except the given one
+    async fn remove_push_subscription_by_endpoint(
+        &self,
+        _endpoint: &str,
+        _except_session_id: &str,
+    ) -> Result<()> {
+        // Sessions are not stored in the reference database
+        Ok(())
+    }
 }
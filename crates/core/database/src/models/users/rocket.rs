@@ -2,7 +2,7 @@ use authifier::models::Session;
 use rocket::http::Status;
 use rocket::request::{self, FromRequest, Outcome, Request};
 
-use crate::{Database, User};
+use crate::{ApiToken, Database, Device, User};
 
 #[rocket::async_trait]
 impl<'r> FromRequest<'r> for User {
@@ -19,15 +19,60 @@ impl<'r> FromRequest<'r> for User {
                     .next()
                     .map(|x| x.to_string());
 
+                let header_api_token = request
+                    .headers()
+                    .get("x-api-token")
+                    .next()
+                    .map(|x| x.to_string());
+
+                let header_impersonation_token = request
+                    .headers()
+                    .get("x-impersonation-token")
+                    .next()
+                    .map(|x| x.to_string());
+
                 if let Some(bot_token) = header_bot_token {
                     if let Ok(bot) = db.fetch_bot_by_token(&bot_token).await {
                         if let Ok(user) = db.fetch_user(&bot.id).await {
                             return Some(user);
                         }
                     }
+                } else if let Some(api_token) = header_api_token {
+                    if let Ok(token) = db
+                        .fetch_api_token_by_hash(&ApiToken::hash_secret(&api_token))
+                        .await
+                    {
+                        if token
+                            .scope
+                            .permits(request.method().as_str(), request.uri().path().as_str())
+                        {
+                            token.mark_used(db).await.ok();
+                            if let Ok(user) = db.fetch_user(&token.user).await {
+                                return Some(user);
+                            }
+                        }
+                    }
+                } else if let Some(impersonation_token) = header_impersonation_token {
+                    if let Ok(impersonation) =
+                        db.fetch_impersonation_by_token(&impersonation_token).await
+                    {
+                        let method = request.method().as_str();
+                        let path = request.uri().path().as_str();
+                        if impersonation.permits(method, path) {
+                            impersonation.log_request(db, method, path).await.ok();
+                            if let Ok(user) = db.fetch_user(&impersonation.user).await {
+                                return Some(user);
+                            }
+                        }
+                    }
                 } else if let Outcome::Success(session) = request.guard::<Session>().await {
                     if let Ok(user) = db.fetch_user(&session.user_id).await {
-                        return Some(user);
+                        if Device::verify(db, &user, &session.id, &session.name)
+                            .await
+                            .is_ok()
+                        {
+                            return Some(user);
+                        }
                     }
                 }
 
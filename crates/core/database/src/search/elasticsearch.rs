@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+
+use guilderia_models::v0::MessageSort;
+use guilderia_result::Result;
+use serde_json::{json, Value};
+
+use super::{request, SearchDocument, SearchEngine, SearchQuery, MAX_FACETS};
+
+/// Elasticsearch (or OpenSearch) cluster
+pub struct Elasticsearch {
+    url: String,
+    api_key: String,
+    index: String,
+}
+
+impl Elasticsearch {
+    pub fn new(url: String, api_key: String, index: String) -> Elasticsearch {
+        Elasticsearch {
+            url: url.trim_end_matches('/').to_string(),
+            api_key,
+            index,
+        }
+    }
+
+    /// Make a request to the cluster
+    async fn request(
+        &self,
+        method: &str,
+        path: &str,
+        content_type: &str,
+        body: String,
+    ) -> Result<Value> {
+        request(
+            method,
+            format!("{}{path}", self.url),
+            Some(format!("ApiKey {}", self.api_key)).filter(|_| !self.api_key.is_empty()),
+            content_type,
+            body,
+        )
+        .await
+    }
+
+    /// Send a batch of bulk actions
+    async fn bulk(&self, actions: String) -> Result<()> {
+        let response = self
+            .request("POST", "/_bulk", "application/x-ndjson", actions)
+            .await?;
+
+        if response["errors"].as_bool().unwrap_or_default() {
+            error!("Search engine failed some bulk actions: {response}");
+        }
+
+        Ok(())
+    }
+}
+
+/// Build a search request body
+fn search_body(query: &SearchQuery) -> Value {
+    let mut filter = vec![];
+    let mut must_not = vec![];
+
+    if let Some(channels) = &query.channels {
+        filter.push(json!({ "terms": { "channel": channels } }));
+    }
+
+    if let Some(author) = &query.author {
+        filter.push(json!({ "term": { "author": author } }));
+    }
+
+    if let Some(thread) = &query.thread {
+        filter.push(json!({ "term": { "thread": thread } }));
+    } else if query.exclude_threads {
+        must_not.push(json!({ "exists": { "field": "thread" } }));
+    }
+
+    if query.before.is_some() || query.after.is_some() {
+        let mut range = json!({});
+        if let Some(before) = query.before {
+            range["lte"] = json!(before);
+        }
+
+        if let Some(after) = query.after {
+            range["gte"] = json!(after);
+        }
+
+        filter.push(json!({ "range": { "created_at": range } }));
+    }
+
+    let mut body = json!({
+        "size": query.limit,
+        "_source": false,
+        "query": {
+            "bool": {
+                "must": [{
+                    "match": {
+                        "content": {
+                            "query": query.query,
+                            "operator": "and"
+                        }
+                    }
+                }],
+                "filter": filter,
+                "must_not": must_not
+            }
+        }
+    });
+
+    // Results are ranked by relevance unless asked otherwise
+    match query.sort {
+        MessageSort::Relevance => {}
+        MessageSort::Latest => body["sort"] = json!([{ "created_at": "desc" }]),
+        MessageSort::Oldest => body["sort"] = json!([{ "created_at": "asc" }]),
+    }
+
+    body
+}
+
+/// Build a request body counting matches in each channel
+fn count_body(query: &SearchQuery) -> Value {
+    let mut body = search_body(query);
+    body["size"] = json!(0);
+    body["aggs"] = json!({
+        "channels": {
+            "terms": {
+                "field": "channel",
+                "size": query.channels.as_ref().map_or(MAX_FACETS, Vec::len).max(1)
+            }
+        }
+    });
+
+    if let Some(body) = body.as_object_mut() {
+        body.remove("sort");
+    }
+
+    body
+}
+
+#[async_trait]
+impl SearchEngine for Elasticsearch {
+    async fn prepare(&self) -> Result<()> {
+        let mappings = json!({
+            "mappings": {
+                "properties": {
+                    "id": { "type": "keyword" },
+                    "channel": { "type": "keyword" },
+                    "author": { "type": "keyword" },
+                    "thread": { "type": "keyword" },
+                    "content": { "type": "text" },
+                    "created_at": { "type": "long" }
+                }
+            }
+        });
+
+        // Existing indexes keep their mappings
+        if self
+            .request(
+                "HEAD",
+                &format!("/{}", self.index),
+                "application/json",
+                String::new(),
+            )
+            .await
+            .is_ok()
+        {
+            return Ok(());
+        }
+
+        self.request(
+            "PUT",
+            &format!("/{}", self.index),
+            "application/json",
+            mappings.to_string(),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn index_messages(&self, documents: &[SearchDocument]) -> Result<()> {
+        let mut actions = String::new();
+        for document in documents {
+            actions.push_str(
+                &json!({ "index": { "_index": self.index, "_id": document.id } }).to_string(),
+            );
+            actions.push('\n');
+            actions.push_str(&json!(document).to_string());
+            actions.push('\n');
+        }
+
+        self.bulk(actions).await
+    }
+
+    async fn remove_messages(&self, ids: &[String]) -> Result<()> {
+        let mut actions = String::new();
+        for id in ids {
+            actions.push_str(&json!({ "delete": { "_index": self.index, "_id": id } }).to_string());
+            actions.push('\n');
+        }
+
+        self.bulk(actions).await
+    }
+
+    async fn remove_channel(&self, channel: &str) -> Result<()> {
+        self.request(
+            "POST",
+            &format!("/{}/_delete_by_query?conflicts=proceed", self.index),
+            "application/json",
+            json!({ "query": { "term": { "channel": channel } } }).to_string(),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<String>> {
+        let response = self
+            .request(
+                "POST",
+                &format!("/{}/_search", self.index),
+                "application/json",
+                search_body(query).to_string(),
+            )
+            .await?;
+
+        Ok(response["hits"]["hits"]
+            .as_array()
+            .map(|hits| {
+                hits.iter()
+                    .filter_map(|hit| hit["_id"].as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn count_by_channel(&self, query: &SearchQuery) -> Result<HashMap<String, usize>> {
+        let response = self
+            .request(
+                "POST",
+                &format!("/{}/_search", self.index),
+                "application/json",
+                count_body(query).to_string(),
+            )
+            .await?;
+
+        Ok(response["aggregations"]["channels"]["buckets"]
+            .as_array()
+            .map(|buckets| {
+                buckets
+                    .iter()
+                    .filter_map(|bucket| {
+                        Some((
+                            bucket["key"].as_str()?.to_string(),
+                            bucket["doc_count"].as_u64()? as usize,
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use guilderia_models::v0::MessageSort;
+    use serde_json::json;
+
+    use super::{count_body, search_body, SearchQuery};
+
+    #[test]
+    fn builds_search_requests() {
+        let body = search_body(&SearchQuery {
+            query: "hello".to_string(),
+            channels: Some(vec!["a".to_string()]),
+            author: None,
+            thread: None,
+            exclude_threads: true,
+            before: Some(20),
+            after: None,
+            sort: MessageSort::Latest,
+            limit: 50,
+        });
+
+        assert_eq!(body["size"], 50);
+        assert_eq!(body["sort"], json!([{ "created_at": "desc" }]));
+        assert_eq!(
+            body["query"]["bool"]["filter"],
+            json!([
+                { "terms": { "channel": ["a"] } },
+                { "range": { "created_at": { "lte": 20 } } }
+            ])
+        );
+        assert_eq!(
+            body["query"]["bool"]["must_not"],
+            json!([{ "exists": { "field": "thread" } }])
+        );
+    }
+
+    #[test]
+    fn builds_count_requests() {
+        let body = count_body(&SearchQuery {
+            query: "hello".to_string(),
+            channels: Some(vec!["a".to_string(), "b".to_string()]),
+            author: None,
+            thread: None,
+            exclude_threads: false,
+            before: None,
+            after: None,
+            sort: MessageSort::Latest,
+            limit: 50,
+        });
+
+        assert_eq!(body["size"], 0);
+        assert_eq!(body["sort"], json!(null));
+        assert_eq!(
+            body["aggs"],
+            json!({ "channels": { "terms": { "field": "channel", "size": 2 } } })
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use guilderia_models::v0::MessageSort;
+use guilderia_result::Result;
+use serde_json::{json, Value};
+
+use super::{request, SearchDocument, SearchEngine, SearchQuery, MAX_FACETS};
+
+/// MeiliSearch instance
+pub struct MeiliSearch {
+    url: String,
+    api_key: String,
+    index: String,
+}
+
+impl MeiliSearch {
+    pub fn new(url: String, api_key: String, index: String) -> MeiliSearch {
+        MeiliSearch {
+            url: url.trim_end_matches('/').to_string(),
+            api_key,
+            index,
+        }
+    }
+
+    /// Make a request to the instance
+    async fn request(&self, method: &str, path: &str, body: Value) -> Result<Value> {
+        request(
+            method,
+            format!("{}{path}", self.url),
+            Some(format!("Bearer {}", self.api_key)).filter(|_| !self.api_key.is_empty()),
+            "application/json",
+            body.to_string(),
+        )
+        .await
+    }
+
+    /// Make a request to the index
+    async fn request_index(&self, method: &str, path: &str, body: Value) -> Result<Value> {
+        self.request(method, &format!("/indexes/{}{path}", self.index), body)
+            .await
+    }
+}
+
+/// Quote a value for use in a filter expression
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Build the filter expressions for a query, all of which must match
+fn filter(query: &SearchQuery) -> Vec<String> {
+    let mut filter = vec![];
+    if let Some(channels) = &query.channels {
+        filter.push(format!(
+            "channel IN [{}]",
+            channels
+                .iter()
+                .map(|channel| quote(channel))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+    }
+
+    if let Some(author) = &query.author {
+        filter.push(format!("author = {}", quote(author)));
+    }
+
+    if let Some(thread) = &query.thread {
+        filter.push(format!("thread = {}", quote(thread)));
+    } else if query.exclude_threads {
+        filter.push("thread NOT EXISTS".to_string());
+    }
+
+    if let Some(before) = query.before {
+        filter.push(format!("created_at <= {before}"));
+    }
+
+    if let Some(after) = query.after {
+        filter.push(format!("created_at >= {after}"));
+    }
+
+    filter
+}
+
+#[async_trait]
+impl SearchEngine for MeiliSearch {
+    async fn prepare(&self) -> Result<()> {
+        // Creating an index which already exists fails in the background
+        self.request(
+            "POST",
+            "/indexes",
+            json!({ "uid": self.index, "primaryKey": "id" }),
+        )
+        .await?;
+
+        self.request_index(
+            "PATCH",
+            "/settings",
+            json!({
+                "searchableAttributes": ["content"],
+                "filterableAttributes": ["channel", "author", "thread", "created_at"],
+                "sortableAttributes": ["created_at"],
+                "faceting": { "maxValuesPerFacet": MAX_FACETS }
+            }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn index_messages(&self, documents: &[SearchDocument]) -> Result<()> {
+        self.request_index("POST", "/documents?primaryKey=id", json!(documents))
+            .await
+            .map(|_| ())
+    }
+
+    async fn remove_messages(&self, ids: &[String]) -> Result<()> {
+        self.request_index("POST", "/documents/delete-batch", json!(ids))
+            .await
+            .map(|_| ())
+    }
+
+    async fn remove_channel(&self, channel: &str) -> Result<()> {
+        self.request_index(
+            "POST",
+            "/documents/delete",
+            json!({ "filter": format!("channel = {}", quote(channel)) }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<String>> {
+        let mut body = json!({
+            "q": query.query,
+            "filter": filter(query),
+            "limit": query.limit,
+            "attributesToRetrieve": ["id"]
+        });
+
+        // Results are ranked by relevance unless asked otherwise
+        match query.sort {
+            MessageSort::Relevance => {}
+            MessageSort::Latest => body["sort"] = json!(["created_at:desc"]),
+            MessageSort::Oldest => body["sort"] = json!(["created_at:asc"]),
+        }
+
+        let response = self.request_index("POST", "/search", body).await?;
+        Ok(response["hits"]
+            .as_array()
+            .map(|hits| {
+                hits.iter()
+                    .filter_map(|hit| hit["id"].as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn count_by_channel(&self, query: &SearchQuery) -> Result<HashMap<String, usize>> {
+        let body = json!({
+            "q": query.query,
+            "filter": filter(query),
+            "limit": 0,
+            "facets": ["channel"]
+        });
+
+        let response = self.request_index("POST", "/search", body).await?;
+        Ok(response["facetDistribution"]["channel"]
+            .as_object()
+            .map(|counts| {
+                counts
+                    .iter()
+                    .filter_map(|(channel, count)| {
+                        count
+                            .as_u64()
+                            .map(|count| (channel.clone(), count as usize))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use guilderia_models::v0::MessageSort;
+
+    use super::{filter, SearchQuery};
+
+    #[test]
+    fn builds_filters() {
+        let query = SearchQuery {
+            query: "hello".to_string(),
+            channels: Some(vec!["a".to_string(), "b\"".to_string()]),
+            author: Some("author".to_string()),
+            thread: None,
+            exclude_threads: true,
+            before: Some(20),
+            after: Some(10),
+            sort: MessageSort::Relevance,
+            limit: 50,
+        };
+
+        assert_eq!(
+            filter(&query),
+            vec![
+                r#"channel IN ["a", "b\""]"#,
+                r#"author = "author""#,
+                "thread NOT EXISTS",
+                "created_at <= 20",
+                "created_at >= 10",
+            ]
+        );
+    }
+}
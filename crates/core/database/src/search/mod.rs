@@ -0,0 +1,326 @@
+//! Full-text message search
+//!
+//! Message content is searched using the database's own text indexes unless
+//! an external search engine is configured. External engines are kept up to
+//! date in the background as messages are sent, edited and deleted, and rank
+//! results by relevance. Only the ids and per-channel counts of matching
+//! messages are taken from the engine, the messages themselves are always read
+//! from the database.
+use std::{collections::HashMap, time::Duration};
+
+use guilderia_config::{config, SearchEngine as SearchEngineKind};
+use guilderia_models::v0::MessageSort;
+use guilderia_result::{create_error, Result};
+use isahc::{prelude::*, Request};
+use once_cell::sync::OnceCell;
+use serde_json::Value;
+use ulid::Ulid;
+
+use crate::{Database, Message, MessageQuery, MessageTimePeriod};
+
+mod elasticsearch;
+mod meilisearch;
+
+pub use elasticsearch::Elasticsearch;
+pub use meilisearch::MeiliSearch;
+
+/// How long to wait on the search engine before giving up
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Most channels to count matching messages in when a query is not limited to some
+const MAX_FACETS: usize = 1000;
+
+/// Message as stored in the search index
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SearchDocument {
+    pub id: String,
+    pub channel: String,
+    pub author: String,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<String>,
+    /// Unix timestamp in milliseconds, taken from the message id
+    pub created_at: u64,
+}
+
+impl SearchDocument {
+    /// Prepare a message for indexing
+    ///
+    /// Returns None for messages without any text to search.
+    pub fn from_message(message: &Message) -> Option<SearchDocument> {
+        if message.system.is_some() {
+            return None;
+        }
+
+        let content = message.content.as_ref().filter(|x| !x.trim().is_empty())?;
+        Some(SearchDocument {
+            id: message.id.clone(),
+            channel: message.channel.clone(),
+            author: message.author.clone(),
+            content: content.clone(),
+            thread: message.thread.clone(),
+            created_at: timestamp(&message.id).unwrap_or_default(),
+        })
+    }
+}
+
+/// Search for messages in a set of channels
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchQuery {
+    pub query: String,
+    /// Only messages in these channels, if given
+    pub channels: Option<Vec<String>>,
+    pub author: Option<String>,
+    pub thread: Option<String>,
+    pub exclude_threads: bool,
+    /// Only messages created at or before this time
+    pub before: Option<u64>,
+    /// Only messages created at or after this time
+    pub after: Option<u64>,
+    pub sort: MessageSort,
+    pub limit: i64,
+}
+
+impl SearchQuery {
+    /// Translate a message query for the search engine
+    ///
+    /// Returns None if the query does not search message content or filters
+    /// on something the search index does not hold.
+    pub fn from_query(query: &MessageQuery) -> Option<SearchQuery> {
+        let MessageTimePeriod::Absolute {
+            before,
+            after,
+            sort,
+        } = &query.time_period
+        else {
+            return None;
+        };
+
+        if query.filter.pinned.is_some() {
+            return None;
+        }
+
+        let channels = match &query.filter.channel {
+            Some(channel) => Some(vec![channel.clone()]),
+            None => query.filter.channels.clone(),
+        };
+
+        Some(SearchQuery {
+            query: query.filter.query.clone()?,
+            channels,
+            author: query.filter.author.clone(),
+            thread: query.filter.thread.clone(),
+            exclude_threads: query.filter.exclude_threads,
+            before: before.as_deref().and_then(timestamp),
+            after: after.as_deref().and_then(timestamp),
+            sort: sort.clone().unwrap_or(MessageSort::Latest),
+            limit: query.limit.unwrap_or(50),
+        })
+    }
+}
+
+/// Search engine holding an index of message content
+#[async_trait]
+pub trait SearchEngine: Sync + Send {
+    /// Create the index and configure which fields may be filtered and sorted on
+    async fn prepare(&self) -> Result<()>;
+
+    /// Add or replace messages in the index
+    async fn index_messages(&self, documents: &[SearchDocument]) -> Result<()>;
+
+    /// Remove messages from the index
+    async fn remove_messages(&self, ids: &[String]) -> Result<()>;
+
+    /// Remove all messages in a channel from the index
+    async fn remove_channel(&self, channel: &str) -> Result<()>;
+
+    /// Find the ids of matching messages, in the order they should be returned
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<String>>;
+
+    /// Count every matching message in each channel, ignoring the query's limit
+    async fn count_by_channel(&self, query: &SearchQuery) -> Result<HashMap<String, usize>>;
+}
+
+/// Configured search engine
+static ENGINE: OnceCell<Option<Box<dyn SearchEngine>>> = OnceCell::new();
+
+/// Get the configured search engine, if messages are not searched in the database
+pub async fn engine() -> Option<&'static dyn SearchEngine> {
+    if let Some(engine) = ENGINE.get() {
+        return engine.as_deref();
+    }
+
+    let config = config().await.database.search;
+    ENGINE
+        .get_or_init(|| match config.engine {
+            SearchEngineKind::Database => None,
+            SearchEngineKind::Meilisearch => Some(Box::new(MeiliSearch::new(
+                config.url,
+                config.api_key,
+                config.index,
+            ))),
+            SearchEngineKind::Elasticsearch => Some(Box::new(Elasticsearch::new(
+                config.url,
+                config.api_key,
+                config.index,
+            ))),
+        })
+        .as_deref()
+}
+
+/// Fetch messages, searching message content with the configured search engine
+pub async fn fetch_messages(db: &Database, query: MessageQuery) -> Result<Vec<Message>> {
+    let (Some(engine), Some(search)) = (engine().await, SearchQuery::from_query(&query)) else {
+        return db.fetch_messages(query).await;
+    };
+
+    let ids = engine.search(&search).await?;
+    let mut messages: HashMap<String, Message> = db
+        .fetch_messages_by_id(&ids)
+        .await?
+        .into_iter()
+        .map(|message| (message.id.clone(), message))
+        .collect();
+
+    // Timestamps only have millisecond precision, so trim messages
+    // sent in the same millisecond as the given bounds
+    let (before, after) = match &query.time_period {
+        MessageTimePeriod::Absolute { before, after, .. } => (before.as_ref(), after.as_ref()),
+        MessageTimePeriod::Relative { .. } => (None, None),
+    };
+
+    Ok(ids
+        .into_iter()
+        .filter_map(|id| messages.remove(&id))
+        .filter(|message| before.is_none_or(|before| &message.id < before))
+        .filter(|message| after.is_none_or(|after| &message.id > after))
+        .collect())
+}
+
+/// Count matching messages in each channel, searching with the configured search engine
+pub async fn count_messages_by_channel(
+    db: &Database,
+    query: MessageQuery,
+) -> Result<HashMap<String, usize>> {
+    let (Some(engine), Some(search)) = (engine().await, SearchQuery::from_query(&query)) else {
+        return db.count_messages_by_channel(query).await;
+    };
+
+    engine.count_by_channel(&search).await
+}
+
+/// Unix timestamp in milliseconds at which an id was generated
+fn timestamp(id: &str) -> Option<u64> {
+    Ulid::from_string(id).ok().map(|id| id.timestamp_ms())
+}
+
+/// Make a request to the search engine and read its response
+async fn request(
+    method: &str,
+    url: String,
+    authorization: Option<String>,
+    content_type: &str,
+    body: String,
+) -> Result<Value> {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(url)
+        .timeout(TIMEOUT)
+        .header("Content-Type", content_type);
+
+    if let Some(authorization) = authorization {
+        request = request.header("Authorization", authorization);
+    }
+
+    let request = request
+        .body(body)
+        .map_err(|_| create_error!(InternalError))?;
+
+    let mut response = isahc::send_async(request).await.map_err(|err| {
+        error!("Failed to reach search engine: {err}");
+        create_error!(InternalError)
+    })?;
+
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or_default();
+    if !status.is_success() {
+        error!("Search engine responded with {status}: {body}");
+        return Err(create_error!(InternalError));
+    }
+
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use guilderia_models::v0::MessageSort;
+
+    use crate::{Message, MessageFilter, MessageQuery, MessageTimePeriod};
+
+    use super::{SearchDocument, SearchQuery};
+
+    #[test]
+    fn translates_queries() {
+        let query = MessageQuery {
+            filter: MessageFilter {
+                channel: Some("channel".to_string()),
+                query: Some("hello".to_string()),
+                ..Default::default()
+            },
+            time_period: MessageTimePeriod::Absolute {
+                before: Some("01HGJ4Y2G0AAAAAAAAAAAAAAAA".to_string()),
+                after: None,
+                sort: Some(MessageSort::Relevance),
+            },
+            limit: Some(20),
+        };
+
+        assert_eq!(
+            SearchQuery::from_query(&query),
+            Some(SearchQuery {
+                query: "hello".to_string(),
+                channels: Some(vec!["channel".to_string()]),
+                author: None,
+                thread: None,
+                exclude_threads: false,
+                before: Some(1_701_416_208_896),
+                after: None,
+                sort: MessageSort::Relevance,
+                limit: 20,
+            })
+        );
+
+        // Pinned messages are only known to the database
+        let pinned = MessageQuery {
+            filter: MessageFilter {
+                pinned: Some(true),
+                ..query.filter
+            },
+            ..query
+        };
+
+        assert_eq!(SearchQuery::from_query(&pinned), None);
+    }
+
+    #[test]
+    fn skips_messages_without_text() {
+        let message = Message {
+            id: "01HGJ4Y2G0AAAAAAAAAAAAAAAA".to_string(),
+            channel: "channel".to_string(),
+            author: "author".to_string(),
+            content: Some("hello world".to_string()),
+            ..Default::default()
+        };
+
+        let document = SearchDocument::from_message(&message).unwrap();
+        assert_eq!(document.content, "hello world");
+        assert_eq!(document.created_at, 1_701_416_208_896);
+
+        let empty = Message {
+            content: Some("  ".to_string()),
+            ..message
+        };
+
+        assert_eq!(SearchDocument::from_message(&empty), None);
+    }
+}
@@ -2,6 +2,7 @@
 use crate::{Database, Message, AMQP};
 
 use deadqueue::limited::Queue;
+use futures::future::join_all;
 use once_cell::sync::Lazy;
 use guilderia_config::capture_message;
 use guilderia_models::v0::PushNotification;
@@ -13,16 +14,23 @@ use validator::HasLen;
 
 use guilderia_result::Result;
 
-use super::DelayedTask;
+use super::{
+    hold,
+    persistent::{PersistentQueue, Receipt},
+    settle, DelayedTask,
+};
 use crate::Channel::{TextChannel, VoiceChannel};
 
 /// Enumeration of possible events
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub enum AckEvent {
     /// Add mentions for a channel
     ProcessMessage {
         /// push notification, message, recipients, push silenced
         messages: Vec<(Option<PushNotification>, Message, Vec<String>, bool)>,
+        /// Whether the server notifies members of every message by default
+        #[serde(default)]
+        notify_all: bool,
     },
 
     /// Acknowledge message in a channel for a user
@@ -33,6 +41,7 @@ pub enum AckEvent {
 }
 
 /// Task information
+#[derive(Serialize, Deserialize)]
 struct Data {
     /// Channel to ack
     channel: String,
@@ -45,18 +54,47 @@ struct Data {
 #[derive(Debug)]
 struct Task {
     event: AckEvent,
+    /// Persisted items which were merged into this task
+    receipts: Vec<Receipt>,
 }
 
+/// In-process fallback for when the persistent queue is unavailable
 static Q: Lazy<Queue<Data>> = Lazy::new(|| Queue::new(10_000));
 
+pub(super) static QUEUE: PersistentQueue = PersistentQueue::new("ack");
+
+/// Check whether the queue is empty
+pub(super) fn is_empty() -> bool {
+    Q.is_empty()
+}
+
+/// Push a task onto the persistent queue, falling back to the in-process queue
+async fn push(data: Data) {
+    if !QUEUE.push(&data).await {
+        Q.try_push(data).ok();
+    }
+}
+
+/// Take the next task from either queue
+async fn next() -> Option<(Option<Receipt>, Data)> {
+    if let Some(data) = Q.try_pop() {
+        return Some((None, data));
+    }
+
+    QUEUE
+        .pop()
+        .await
+        .map(|(receipt, data)| (Some(receipt), data))
+}
+
 /// Queue a new task for a worker
 pub async fn queue_ack(channel: String, user: String, event: AckEvent) {
-    Q.try_push(Data {
+    push(Data {
         channel,
         user: Some(user),
         event,
     })
-    .ok();
+    .await;
 
     info!(
         "Queue is using {} slots from {}. Queued type: ACK",
@@ -67,12 +105,12 @@ pub async fn queue_ack(channel: String, user: String, event: AckEvent) {
 
 /// Do not add more than one message per event.
 pub async fn queue_message(channel: String, event: AckEvent) {
-    Q.try_push(Data {
+    push(Data {
         channel,
         user: None,
         event,
     })
-    .ok();
+    .await;
 
     info!(
         "Queue is using {} slots from {}. Queued type: MENTION",
@@ -95,13 +133,18 @@ pub async fn handle_ack_event(
             let user: &str = user.as_str();
 
             let unread = db.fetch_unread(user, channel).await?;
-            let updated = db.acknowledge_message(channel, user, id).await?;
-
-            if let (Some(before), Some(after)) = (unread, updated) {
-                let before_mentions = before.mentions.unwrap_or_default().len();
-                let after_mentions = after.mentions.unwrap_or_default().len();
-
-                let mentions_acked = before_mentions - after_mentions;
+            let unread_count = db.count_messages_since(channel, Some(id)).await?;
+            db.acknowledge_message(channel, user, id, unread_count)
+                .await?;
+
+            if let Some(before) = unread {
+                // Acknowledging a message clears every mention up to it
+                let mentions_acked = before
+                    .mentions
+                    .unwrap_or_default()
+                    .iter()
+                    .filter(|mention| mention.as_str() <= id.as_str())
+                    .count();
 
                 if mentions_acked > 0 {
                     if let Err(err) = amqp
@@ -113,7 +156,10 @@ pub async fn handle_ack_event(
                 };
             }
         }
-        AckEvent::ProcessMessage { messages } => {
+        AckEvent::ProcessMessage {
+            messages,
+            notify_all,
+        } => {
             let mut users: HashSet<&String> = HashSet::new();
             info!(
                 "Processing {} messages from channel {}",
@@ -149,10 +195,15 @@ pub async fn handle_ack_event(
 
             let mut mass_mentions = vec![];
 
+            // Servers may opt members into notifications for every message
+            let notify_all = *notify_all;
+
             for (push, message, recipients, silenced) in messages {
                 if *silenced
                     || push.is_none()
-                    || (recipients.is_empty() && !message.contains_mass_push_mention())
+                    || (recipients.is_empty()
+                        && !message.contains_mass_push_mention()
+                        && !notify_all)
                 {
                     debug!(
                         "Rejecting push: silenced: {}, recipient count: {}, push exists: {:?}",
@@ -175,7 +226,7 @@ pub async fn handle_ack_event(
                     guilderia_config::capture_error(&err);
                 }
 
-                if message.contains_mass_push_mention() {
+                if message.contains_mass_push_mention() || notify_all {
                     mass_mentions.push(push.clone().unwrap());
                 }
             }
@@ -214,6 +265,7 @@ pub async fn handle_ack_event(
 pub async fn worker(db: Database, amqp: AMQP) {
     let mut tasks = HashMap::<(Option<String>, String, u8), DelayedTask<Task>>::new();
     let mut keys: Vec<(Option<String>, String, u8)> = vec![];
+    let mut held = 0;
 
     loop {
         // Find due tasks.
@@ -223,17 +275,33 @@ pub async fn worker(db: Database, amqp: AMQP) {
             }
         }
 
-        // Commit any due tasks to the database.
-        for key in &keys {
-            if let Some(task) = tasks.remove(key) {
-                let Task { event } = task.data;
-                let (user, channel, _) = key;
-
-                if let Err(err) = handle_ack_event(&event, &db, &amqp, user, channel).await {
-                    revolt_config::capture_error(&err);
-                    error!("{err:?} for {event:?}. ({user:?}, {channel})");
-                } else {
-                    info!("User {user:?} ack in {channel} with {event:?}");
+        // Commit any due tasks to the database together, so their writes can share a batch.
+        let due: Vec<_> = keys
+            .iter()
+            .filter_map(|key| tasks.remove(key).map(|task| (key, task.data)))
+            .collect();
+
+        let results = join_all(due.iter().map(|((user, channel, _), task)| {
+            handle_ack_event(&task.event, &db, &amqp, user, channel)
+        }))
+        .await;
+
+        for (((user, channel, _), Task { event, receipts }), result) in due.into_iter().zip(results)
+        {
+            QUEUE.record_batch(receipts.len());
+
+            if let Err(err) = result {
+                revolt_config::capture_error(&err);
+                error!("{err:?} for {event:?}. ({user:?}, {channel})");
+
+                for receipt in receipts {
+                    QUEUE.fail(receipt).await;
+                }
+            } else {
+                info!("User {user:?} ack in {channel} with {event:?}");
+
+                for receipt in receipts {
+                    QUEUE.complete(receipt).await;
                 }
             }
         }
@@ -242,12 +310,17 @@ pub async fn worker(db: Database, amqp: AMQP) {
         keys.clear();
 
         // Queue incoming tasks.
-        while let Some(Data {
-            channel,
-            user,
-            mut event,
-        }) = Q.try_pop()
+        while let Some((
+            receipt,
+            Data {
+                channel,
+                user,
+                mut event,
+            },
+        )) = next().await
         {
+            hold(1);
+            held += 1;
             info!("Took next ack from queue, now {} remaining", Q.len());
 
             let key: (Option<String>, String, u8) = (
@@ -259,11 +332,20 @@ pub async fn worker(db: Database, amqp: AMQP) {
                 },
             );
             if let Some(task) = tasks.get_mut(&key) {
+                task.data.receipts.extend(receipt);
+
                 match &mut event {
-                    AckEvent::ProcessMessage { messages: new_data } => {
-                        if let AckEvent::ProcessMessage { messages: existing } =
-                            &mut task.data.event
+                    AckEvent::ProcessMessage {
+                        messages: new_data,
+                        notify_all: new_notify_all,
+                    } => {
+                        if let AckEvent::ProcessMessage {
+                            messages: existing,
+                            notify_all,
+                        } = &mut task.data.event
                         {
+                            *notify_all |= *new_notify_all;
+
                             if let Some(new_event) = new_data.pop() {
                                 // if the message contains a mass mention, do not delay it any further.
                                 if new_event.1.contains_mass_push_mention() {
@@ -301,10 +383,18 @@ pub async fn worker(db: Database, amqp: AMQP) {
                     }
                 }
             } else {
-                tasks.insert(key, DelayedTask::new(Task { event }));
+                tasks.insert(
+                    key,
+                    DelayedTask::new(Task {
+                        event,
+                        receipts: receipt.into_iter().collect(),
+                    }),
+                );
             }
         }
 
+        settle(&mut held, tasks.len());
+
         // Sleep for an arbitrary amount of time.
         async_std::task::sleep(Duration::from_secs(1)).await;
     }
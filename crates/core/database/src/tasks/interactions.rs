@@ -0,0 +1,235 @@
+//! Delivery of interactions to bots over HTTP
+//!
+//! Bots which registered an interactions URL receive their interactions as
+//! signed POST requests, so they do not need to stay connected to the events
+//! server. Bots verify requests using the public key advertised by the node.
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_std::{net::ToSocketAddrs, task};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ed25519_dalek::{Signer, SigningKey, SECRET_KEY_LENGTH};
+use guilderia_config::config;
+use isahc::{config::Dialer, prelude::*, Request};
+use serde::Serialize;
+use url::{Host, Url};
+
+/// Header carrying the base64 encoded signature
+pub const SIGNATURE_HEADER: &str = "X-Signature-Ed25519";
+
+/// Header carrying the unix timestamp the signature was made at
+pub const TIMESTAMP_HEADER: &str = "X-Signature-Timestamp";
+
+/// Longest delay between delivery attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Load the key interactions are signed with, if one is configured
+async fn signing_key() -> Option<SigningKey> {
+    let key = STANDARD
+        .decode(config().await.api.security.interactions_signing_key)
+        .ok()?;
+
+    let key: [u8; SECRET_KEY_LENGTH] = key.try_into().ok()?;
+    Some(SigningKey::from_bytes(&key))
+}
+
+/// Base64 encoded public key bots verify interactions with
+pub async fn public_key() -> Option<String> {
+    signing_key()
+        .await
+        .map(|key| STANDARD.encode(key.verifying_key().as_bytes()))
+}
+
+/// Sign a request body, the signature covers the timestamp followed by the body
+fn sign(key: &SigningKey, timestamp: &str, body: &str) -> String {
+    STANDARD.encode(key.sign(format!("{timestamp}{body}").as_bytes()).to_bytes())
+}
+
+/// Whether an address may be reached from outside the node's own network
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                // Shared address space (100.64.0.0/10)
+                || (a == 100 && (b & 0b1100_0000) == 64))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(ip));
+            }
+
+            let segment = ip.segments()[0];
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                // Unique local (fc00::/7)
+                || (segment & 0xfe00) == 0xfc00
+                // Link-local (fe80::/10)
+                || (segment & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// Resolve an interactions URL to the address requests should be sent to
+///
+/// URLs which resolve to loopback, private, link-local or metadata addresses
+/// are refused, so bots can't use the node to reach its internal network.
+pub async fn resolve_endpoint(url: &str) -> Option<SocketAddr> {
+    let url = Url::parse(url).ok()?;
+    let port = url.port_or_known_default()?;
+    let addresses: Vec<SocketAddr> = match url.host()? {
+        Host::Ipv4(ip) => vec![SocketAddr::new(ip.into(), port)],
+        Host::Ipv6(ip) => vec![SocketAddr::new(ip.into(), port)],
+        Host::Domain(domain) => (domain, port).to_socket_addrs().await.ok()?.collect(),
+    };
+
+    if addresses.iter().all(|address| is_public(address.ip())) {
+        addresses.into_iter().next()
+    } else {
+        None
+    }
+}
+
+/// Make a single signed request, returning whether the bot accepted it
+///
+/// The request is sent to the already resolved address so the host can't
+/// be rebound to an internal address between checking and connecting.
+async fn attempt(
+    key: &SigningKey,
+    url: &str,
+    address: SocketAddr,
+    body: &str,
+    timeout: Duration,
+) -> bool {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+        .to_string();
+
+    let Ok(request) = Request::post(url)
+        .timeout(timeout)
+        .dial(Dialer::ip_socket(address))
+        .header("Content-Type", "application/json")
+        .header(SIGNATURE_HEADER, sign(key, &timestamp, body))
+        .header(TIMESTAMP_HEADER, &timestamp)
+        .body(body.to_string())
+    else {
+        return false;
+    };
+
+    isahc::send_async(request)
+        .await
+        .is_ok_and(|response| response.status().is_success())
+}
+
+/// Check that a bot's interactions endpoint accepts signed requests
+///
+/// The endpoint must acknowledge a ping with a successful response.
+pub async fn verify_endpoint(url: &str) -> bool {
+    let Some(key) = signing_key().await else {
+        return false;
+    };
+
+    let Some(address) = resolve_endpoint(url).await else {
+        return false;
+    };
+
+    let timeout = Duration::from_secs(config().await.api.interactions.timeout_seconds);
+    let body = serde_json::json!({ "type": "Ping" }).to_string();
+    attempt(&key, url, address, &body, timeout).await
+}
+
+/// Deliver an event to a bot's interactions endpoint in the background
+///
+/// Failed deliveries are retried with exponential backoff. Returns false
+/// without sending anything if no signing key is configured, in which case
+/// the caller should deliver the event over the events server instead.
+pub async fn deliver<T: Serialize>(url: String, event: &T) -> bool {
+    let Some(key) = signing_key().await else {
+        return false;
+    };
+
+    let Ok(body) = serde_json::to_string(event) else {
+        return false;
+    };
+
+    task::spawn(async move {
+        let Some(address) = resolve_endpoint(&url).await else {
+            warn!("Refused to deliver interaction to {url}.");
+            return;
+        };
+
+        let settings = config().await.api.interactions;
+        let timeout = Duration::from_secs(settings.timeout_seconds);
+        let mut backoff = Duration::from_secs(1);
+
+        for attempt_number in 0..settings.max_attempts {
+            if attempt_number > 0 {
+                task::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+
+            if attempt(&key, &url, address, &body, timeout).await {
+                return;
+            }
+        }
+
+        warn!("Failed to deliver interaction to {url}.");
+    });
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use ed25519_dalek::{Signature, SigningKey, Verifier};
+
+    use super::{is_public, sign};
+
+    #[test]
+    fn signature_covers_timestamp_and_body() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let signature = sign(&key, "1700000000", "{}");
+
+        let signature: [u8; 64] = STANDARD.decode(signature).unwrap().try_into().unwrap();
+        let signature = Signature::from_bytes(&signature);
+
+        let verifying_key = key.verifying_key();
+        assert!(verifying_key.verify(b"1700000000{}", &signature).is_ok());
+        assert!(verifying_key.verify(b"1700000001{}", &signature).is_err());
+    }
+
+    #[test]
+    fn refuses_internal_addresses() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00:ec2::254",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip}");
+        }
+
+        for ip in ["1.1.1.1", "2606:4700:4700::1111"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip}");
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! Authenticated client for January's embed API
+//!
+//! Requests are retried within a shared retry budget and are paused
+//! entirely while January appears to be down, so an outage only costs
+//! us the embeds rather than stalling the queue.
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use guilderia_config::config;
+use guilderia_models::v0::Embed;
+use isahc::{prelude::*, Request};
+use once_cell::sync::Lazy;
+
+/// Window over which the retry budget is calculated
+const BUDGET_WINDOW: Duration = Duration::from_secs(10);
+
+/// Retries always allowed within a window regardless of the budget
+const MIN_RETRIES: usize = 5;
+
+/// Health of January as observed by this process
+struct Health {
+    /// Failures since the last successful request
+    consecutive_failures: usize,
+    /// Requests are not attempted until this time
+    open_until: Option<Instant>,
+    /// Start of the current budget window
+    window_start: Instant,
+    /// Requests made in the current window
+    requests: usize,
+    /// Retries made in the current window
+    retries: usize,
+}
+
+static HEALTH: Lazy<Mutex<Health>> = Lazy::new(|| {
+    Mutex::new(Health {
+        consecutive_failures: 0,
+        open_until: None,
+        window_start: Instant::now(),
+        requests: 0,
+        retries: 0,
+    })
+});
+
+impl Health {
+    /// Start a new budget window if the current one has passed
+    fn roll_window(&mut self) {
+        if self.window_start.elapsed() >= BUDGET_WINDOW {
+            self.window_start = Instant::now();
+            self.requests = 0;
+            self.retries = 0;
+        }
+    }
+
+    /// Check whether the circuit is closed and count the request
+    fn try_request(&mut self) -> bool {
+        if self
+            .open_until
+            .is_some_and(|open_until| Instant::now() < open_until)
+        {
+            return false;
+        }
+
+        self.roll_window();
+        self.requests += 1;
+        true
+    }
+
+    /// Check whether the retry budget allows another retry
+    fn try_retry(&mut self, budget: f64) -> bool {
+        self.roll_window();
+
+        let allowed = MIN_RETRIES.max((self.requests as f64 * budget) as usize);
+        if self.retries < allowed {
+            self.retries += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.open_until = None;
+    }
+
+    fn record_failure(&mut self, threshold: usize, cooldown: Duration) {
+        self.consecutive_failures += 1;
+        if self.consecutive_failures >= threshold {
+            if self.open_until.is_none() {
+                warn!(
+                    "January failed {} times in a row, pausing embeds for {:?}.",
+                    self.consecutive_failures, cooldown
+                );
+            }
+
+            self.open_until = Some(Instant::now() + cooldown);
+        }
+    }
+}
+
+/// Outcome of a single attempt
+enum Attempt {
+    /// January responded, with or without an embed
+    Done(Option<Embed>),
+    /// January could not be reached or errored
+    Failed,
+}
+
+/// Make a single request to January
+async fn attempt(url: &str, key: &str, timeout: Duration) -> Attempt {
+    let mut request = Request::get(url).timeout(timeout);
+    if !key.is_empty() {
+        request = request.header("Authorization", format!("Bearer {key}"));
+    }
+
+    let Ok(request) = request.body(()) else {
+        return Attempt::Done(None);
+    };
+
+    match isahc::send_async(request).await {
+        Ok(mut response) if response.status().is_success() => {
+            Attempt::Done(response.json::<Embed>().await.ok())
+        }
+        // January had nothing to embed or rejected the link
+        Ok(response) if response.status().is_client_error() => Attempt::Done(None),
+        _ => Attempt::Failed,
+    }
+}
+
+/// Fetch an embed for a link from January
+///
+/// Returns None if there is no embed or January is unavailable.
+pub async fn fetch_embed(host: &str, link: &str, locale: Option<&str>) -> Option<Embed> {
+    let config = config().await;
+    let settings = &config.api.january;
+    let key = &config.api.security.january_key;
+
+    let mut url = format!("{host}/embed?url={}", url_escape::encode_component(link));
+    if let Some(locale) = locale {
+        url.push_str(&format!("&lang={}", url_escape::encode_component(locale)));
+    }
+
+    if !HEALTH.lock().unwrap().try_request() {
+        return None;
+    }
+
+    let timeout = Duration::from_secs(settings.timeout_seconds);
+    let cooldown = Duration::from_secs(settings.cooldown_seconds);
+    let mut retries = 0;
+
+    loop {
+        match attempt(&url, key, timeout).await {
+            Attempt::Done(embed) => {
+                HEALTH.lock().unwrap().record_success();
+                return embed;
+            }
+            Attempt::Failed => {
+                let mut health = HEALTH.lock().unwrap();
+                health.record_failure(settings.failure_threshold, cooldown);
+
+                if retries >= settings.max_retries
+                    || health.open_until.is_some()
+                    || !health.try_retry(settings.retry_budget)
+                {
+                    return None;
+                }
+            }
+        }
+
+        retries += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Health, MIN_RETRIES};
+
+    fn new_health() -> Health {
+        Health {
+            consecutive_failures: 0,
+            open_until: None,
+            window_start: Instant::now(),
+            requests: 0,
+            retries: 0,
+        }
+    }
+
+    #[test]
+    fn circuit_opens_after_threshold() {
+        let mut health = new_health();
+        assert!(health.try_request());
+
+        for _ in 0..3 {
+            health.record_failure(3, Duration::from_secs(60));
+        }
+
+        assert!(!health.try_request());
+
+        health.record_success();
+        assert!(health.try_request());
+    }
+
+    #[test]
+    fn retries_are_budgeted() {
+        let mut health = new_health();
+        for _ in 0..100 {
+            health.try_request();
+        }
+
+        let mut retries = 0;
+        while health.try_retry(0.1) {
+            retries += 1;
+        }
+
+        assert_eq!(retries, 10);
+
+        let mut health = new_health();
+        let mut retries = 0;
+        while health.try_retry(0.1) {
+            retries += 1;
+        }
+
+        assert_eq!(retries, MIN_RETRIES);
+    }
+}
@@ -5,14 +5,22 @@ use std::{collections::HashMap, time::Duration};
 
 use crate::{Database, PartialChannel};
 
-use super::DelayedTask;
+use super::{
+    hold,
+    persistent::{PersistentQueue, Receipt},
+    settle, DelayedTask,
+};
 
 /// Task information
+#[derive(Serialize, Deserialize)]
 struct Data {
     /// Channel to update
     channel: String,
     /// Latest message ID
     id: String,
+    /// Author of the message
+    #[serde(default)]
+    author: Option<String>,
     /// Whether the channel is a DM
     is_dm: bool,
 }
@@ -24,13 +32,46 @@ struct Task {
     id: String,
     /// Whether the channel is a DM
     is_dm: bool,
+    /// Number of new messages by each author
+    authors: HashMap<String, u64>,
+    /// Persisted items which were merged into this task
+    receipts: Vec<Receipt>,
 }
 
+/// In-process fallback for when the persistent queue is unavailable
 static Q: Lazy<Queue<Data>> = Lazy::new(|| Queue::new(10_000));
 
+pub(super) static QUEUE: PersistentQueue = PersistentQueue::new("last_message_id");
+
+/// Check whether the queue is empty
+pub(super) fn is_empty() -> bool {
+    Q.is_empty()
+}
+
+/// Take the next task from either queue
+async fn next() -> Option<(Option<Receipt>, Data)> {
+    if let Some(data) = Q.try_pop() {
+        return Some((None, data));
+    }
+
+    QUEUE
+        .pop()
+        .await
+        .map(|(receipt, data)| (Some(receipt), data))
+}
+
 /// Queue a new task for a worker
-pub async fn queue(channel: String, id: String, is_dm: bool) {
-    Q.try_push(Data { channel, id, is_dm }).ok();
+pub async fn queue(channel: String, id: String, author: String, is_dm: bool) {
+    let data = Data {
+        channel,
+        id,
+        author: Some(author),
+        is_dm,
+    };
+    if !QUEUE.push(&data).await {
+        Q.try_push(data).ok();
+    }
+
     info!("Queue is using {} slots from {}.", Q.len(), Q.capacity());
 }
 
@@ -38,6 +79,7 @@ pub async fn queue(channel: String, id: String, is_dm: bool) {
 pub async fn worker(db: Database) {
     let mut tasks = HashMap::<String, DelayedTask<Task>>::new();
     let mut keys = vec![];
+    let mut held = 0;
 
     loop {
         // Find due tasks.
@@ -50,7 +92,13 @@ pub async fn worker(db: Database) {
         // Commit any due tasks to the database.
         for key in &keys {
             if let Some(task) = tasks.remove(key) {
-                let Task { id, is_dm, .. } = task.data;
+                let Task {
+                    id,
+                    is_dm,
+                    authors,
+                    receipts,
+                } = task.data;
+                QUEUE.record_batch(receipts.len());
 
                 let mut channel = PartialChannel {
                     last_message_id: Some(id.to_string()),
@@ -62,8 +110,27 @@ pub async fn worker(db: Database) {
                 }
 
                 match db.update_channel(key, &channel, vec![]).await {
-                    Ok(_) => info!("Updated last_message_id for {key} to {id}."),
-                    Err(err) => error!("Failed to update last_message_id with {err:?}!"),
+                    Ok(_) => {
+                        info!("Updated last_message_id for {key} to {id}.");
+
+                        for (author, count) in authors {
+                            if let Err(err) = db.increment_unread_counts(key, &author, count).await
+                            {
+                                error!("Failed to update unread counts with {err:?}!");
+                            }
+                        }
+
+                        for receipt in receipts {
+                            QUEUE.complete(receipt).await;
+                        }
+                    }
+                    Err(err) => {
+                        error!("Failed to update last_message_id with {err:?}!");
+
+                        for receipt in receipts {
+                            QUEUE.fail(receipt).await;
+                        }
+                    }
                 }
             }
         }
@@ -72,15 +139,41 @@ pub async fn worker(db: Database) {
         keys.clear();
 
         // Queue incoming tasks.
-        while let Some(Data { channel, id, is_dm }) = Q.try_pop() {
+        while let Some((
+            receipt,
+            Data {
+                channel,
+                id,
+                author,
+                is_dm,
+            },
+        )) = next().await
+        {
+            hold(1);
+            held += 1;
+
             if let Some(task) = tasks.get_mut(&channel) {
                 task.data.id = id;
+                if let Some(author) = author {
+                    *task.data.authors.entry(author).or_default() += 1;
+                }
+                task.data.receipts.extend(receipt);
                 task.delay();
             } else {
-                tasks.insert(channel, DelayedTask::new(Task { id, is_dm }));
+                tasks.insert(
+                    channel,
+                    DelayedTask::new(Task {
+                        id,
+                        is_dm,
+                        authors: author.into_iter().map(|author| (author, 1)).collect(),
+                        receipts: receipt.into_iter().collect(),
+                    }),
+                );
             }
         }
 
+        settle(&mut held, tasks.len());
+
         // Sleep for an arbitrary amount of time.
         async_std::task::sleep(Duration::from_secs(1)).await;
     }
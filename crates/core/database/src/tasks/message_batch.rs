@@ -0,0 +1,166 @@
+// Queue Type: Batched
+//
+// Callers wait until the batch holding their write has been committed, so
+// nothing is acknowledged to them before it is durable. Acknowledgements
+// reach this queue through the persistent ack queue, which keeps them until
+// the write succeeds.
+use deadqueue::limited::Queue;
+use futures::channel::oneshot;
+use guilderia_result::Result;
+use once_cell::sync::Lazy;
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::{ChannelAck, Database, Message};
+
+use super::{hold, release};
+
+/// Write to commit as part of a batch
+enum Write {
+    /// Insert a message
+    Insert(Message),
+    /// Acknowledge a message for a user
+    Acknowledge(ChannelAck),
+}
+
+/// Task information
+struct Data {
+    /// Write to commit
+    write: Write,
+    /// Notified once the batch containing this write is committed
+    done: oneshot::Sender<Result<()>>,
+}
+
+static Q: Lazy<Queue<Data>> = Lazy::new(|| Queue::new(10_000));
+
+/// Whether a worker is currently draining the queue
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Check whether writes should be routed through the batching worker
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Check whether the queue is empty
+pub(super) fn is_empty() -> bool {
+    Q.is_empty()
+}
+
+/// Queue a write and wait until it has been committed
+///
+/// Returns the write back if the queue is full so the caller can commit it directly.
+async fn commit(write: Write) -> std::result::Result<Result<()>, Write> {
+    let (done, receiver) = oneshot::channel();
+    if let Err(Data { write, .. }) = Q.try_push(Data { write, done }) {
+        return Err(write);
+    }
+
+    Ok(receiver
+        .await
+        .unwrap_or_else(|_| Err(create_database_error!("batch", "messages"))))
+}
+
+/// Queue a message for insertion and wait until it has been written
+///
+/// Returns the message back if the queue is full so the caller can write it directly.
+pub async fn insert(message: Message) -> std::result::Result<Result<()>, Message> {
+    commit(Write::Insert(message))
+        .await
+        .map_err(|write| match write {
+            Write::Insert(message) => message,
+            Write::Acknowledge(_) => unreachable!(),
+        })
+}
+
+/// Queue an acknowledgement and wait until it has been written
+///
+/// Returns the acknowledgement back if the queue is full so the caller can write it directly.
+pub async fn acknowledge(ack: ChannelAck) -> std::result::Result<Result<()>, ChannelAck> {
+    commit(Write::Acknowledge(ack))
+        .await
+        .map_err(|write| match write {
+            Write::Acknowledge(ack) => ack,
+            Write::Insert(_) => unreachable!(),
+        })
+}
+
+/// Start a new worker
+pub async fn worker(db: Database) {
+    let config = guilderia_config::config().await;
+    if !config.database.batching.enabled {
+        return;
+    }
+
+    let flush_interval = Duration::from_millis(config.database.batching.flush_interval_ms);
+    let max_batch_size = config.database.batching.max_batch_size.max(1);
+
+    ACTIVE.store(true, Ordering::Relaxed);
+
+    let mut batch: Vec<Data> = Vec::with_capacity(max_batch_size);
+    loop {
+        // Wait for the first write of the next batch.
+        batch.push(Q.pop().await);
+        hold(1);
+
+        // Gather more writes until the batch is full or the interval elapses.
+        let started = Instant::now();
+        while batch.len() < max_batch_size {
+            let Some(remaining) = flush_interval.checked_sub(started.elapsed()) else {
+                break;
+            };
+
+            match async_std::future::timeout(remaining, Q.pop()).await {
+                Ok(data) => {
+                    hold(1);
+                    batch.push(data);
+                }
+                Err(_) => break,
+            }
+        }
+
+        // Commit the batch and notify each waiter of its own write's outcome.
+        let (inserts, acks): (Vec<Data>, Vec<Data>) = batch
+            .drain(..)
+            .partition(|data| matches!(data.write, Write::Insert(_)));
+
+        let messages: Vec<Message> = inserts
+            .iter()
+            .filter_map(|data| match &data.write {
+                Write::Insert(message) => Some(message.clone()),
+                Write::Acknowledge(_) => None,
+            })
+            .collect();
+
+        let channel_acks: Vec<ChannelAck> = acks
+            .iter()
+            .filter_map(|data| match &data.write {
+                Write::Acknowledge(ack) => Some(ack.clone()),
+                Write::Insert(_) => None,
+            })
+            .collect();
+
+        let mut results = vec![];
+        if !messages.is_empty() {
+            results.extend(db.insert_messages_each(&messages).await);
+        }
+
+        if !channel_acks.is_empty() {
+            results.extend(db.acknowledge_messages_each(&channel_acks).await);
+        }
+
+        let failed = results.iter().filter(|result| result.is_err()).count();
+        if failed > 0 {
+            error!(
+                "Failed to write {failed} of {} batched writes.",
+                results.len()
+            );
+        }
+
+        release(results.len());
+        for (Data { done, .. }, result) in inserts.into_iter().chain(acks).zip(results) {
+            done.send(result).ok();
+        }
+    }
+}
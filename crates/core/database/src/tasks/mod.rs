@@ -3,26 +3,130 @@
 use crate::{Database, AMQP};
 
 use async_std::task;
-use std::time::Instant;
+use once_cell::sync::OnceCell;
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
 
 const WORKER_COUNT: usize = 5;
 
 pub mod ack;
 pub mod authifier_relay;
+pub mod interactions;
+mod january;
 pub mod last_message_id;
+pub mod message_batch;
+pub mod persistent;
 pub mod process_embeds;
+pub mod search_index;
+pub mod server_jobs;
+
+/// Queues which are persisted to Redis
+static PERSISTENT_QUEUES: [&persistent::PersistentQueue; 4] = [
+    &ack::QUEUE,
+    &last_message_id::QUEUE,
+    &process_embeds::QUEUE,
+    &server_jobs::QUEUE,
+];
 
 /// Spawn background workers
 pub fn start_workers(db: Database, amqp: AMQP) {
     task::spawn(authifier_relay::worker());
+    task::spawn(message_batch::worker(db.clone()));
+    task::spawn(persistent::worker(&PERSISTENT_QUEUES));
+    task::spawn(search_index::worker());
 
     for _ in 0..WORKER_COUNT {
         task::spawn(ack::worker(db.clone(), amqp.clone()));
         task::spawn(last_message_id::worker(db.clone()));
         task::spawn(process_embeds::worker(db.clone()));
+        task::spawn(server_jobs::worker(db.clone()));
     }
 }
 
+/// Time at which the service started shutting down
+static SHUTDOWN: OnceCell<Instant> = OnceCell::new();
+
+/// Number of tasks taken off a queue which have not been committed yet
+static HELD: AtomicUsize = AtomicUsize::new(0);
+
+/// Start shutting down, all held tasks are treated as due from now on
+pub fn begin_shutdown() {
+    SHUTDOWN.get_or_init(Instant::now);
+}
+
+/// Check whether the service is shutting down
+pub fn is_shutting_down() -> bool {
+    SHUTDOWN.get().is_some()
+}
+
+/// Track tasks taken off a queue by a worker
+fn hold(count: usize) {
+    HELD.fetch_add(count, Ordering::SeqCst);
+}
+
+/// Track tasks which a worker has finished with
+fn release(count: usize) {
+    HELD.fetch_sub(count, Ordering::SeqCst);
+}
+
+/// Release tasks a worker no longer holds, given how many it still has
+fn settle(held: &mut usize, remaining: usize) {
+    release(*held - remaining);
+    *held = remaining;
+}
+
+/// Check whether all queued work has been committed
+fn is_idle() -> bool {
+    HELD.load(Ordering::SeqCst) == 0
+        && ack::is_empty()
+        && last_message_id::is_empty()
+        && message_batch::is_empty()
+        && process_embeds::is_empty()
+        && search_index::is_empty()
+        && server_jobs::is_empty()
+}
+
+/// Flush all background task queues
+///
+/// Returns false if work was still outstanding once `deadline`
+/// had passed since shutdown began.
+pub async fn drain(deadline: Duration) -> bool {
+    let started = *SHUTDOWN.get_or_init(Instant::now);
+
+    while !is_idle() {
+        if started.elapsed() >= deadline {
+            warn!(
+                "Shutdown deadline elapsed with {} tasks outstanding.",
+                HELD.load(Ordering::SeqCst)
+            );
+
+            return false;
+        }
+
+        task::sleep(Duration::from_millis(100)).await;
+    }
+
+    true
+}
+
+/// Counters for each of the persistent queues
+pub fn queue_stats() -> Vec<persistent::QueueStats> {
+    PERSISTENT_QUEUES
+        .iter()
+        .map(|queue| queue.stats())
+        .collect()
+}
+
+/// Find a persistent queue by its name
+pub fn find_queue(name: &str) -> Option<&'static persistent::PersistentQueue> {
+    PERSISTENT_QUEUES
+        .iter()
+        .find(|queue| queue.name() == name)
+        .copied()
+}
+
 /// Task with additional information on when it should run
 pub struct DelayedTask<T> {
     pub data: T,
@@ -61,6 +165,7 @@ impl<T> DelayedTask<T> {
     /// Check if a task should run yet
     pub fn should_run(&self) -> bool {
         self.run_now
+            || is_shutting_down()
             || self.first_seen.elapsed().as_secs() > EXPIRE_CONSTANT
             || self.last_updated.elapsed().as_secs() > SAVE_CONSTANT
     }
@@ -0,0 +1,429 @@
+//! Persistent task queues backed by Redis lists
+//!
+//! Workers move items onto their own processing list while they hold them,
+//! so work left behind by a node that crashed is reclaimed by the others.
+//! Items which keep failing are moved onto a dead-letter list for inspection.
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use once_cell::sync::Lazy;
+use redis_kiss::{get_connection, AsyncCommands};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use ulid::Ulid;
+
+/// Number of attempts before an item is dead-lettered
+const MAX_ATTEMPTS: u32 = 5;
+
+/// How long a consumer may go without a heartbeat before its work is reclaimed
+const HEARTBEAT_TTL: usize = 60;
+
+/// How often consumers send heartbeats and look for abandoned work
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(20);
+
+/// Identifier for the workers in this process
+static CONSUMER: Lazy<String> = Lazy::new(|| Ulid::new().to_string());
+
+/// Item as stored in Redis
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    /// Number of failed attempts so far
+    attempts: u32,
+    /// Time at which the item was first queued, in milliseconds since the epoch
+    #[serde(default)]
+    queued_at: u64,
+    /// Task data
+    data: T,
+}
+
+/// Handle to an item a worker has taken off the queue
+#[derive(Debug)]
+pub struct Receipt {
+    /// Item exactly as it is stored in Redis
+    raw: String,
+    /// Number of failed attempts so far
+    attempts: u32,
+    /// Time at which the item was first queued
+    queued_at: u64,
+}
+
+/// Snapshot of a queue's counters
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueStats {
+    pub name: &'static str,
+    /// Items waiting to be processed, as of the last maintenance run
+    pub pending: u64,
+    /// Items on the dead-letter list, as of the last maintenance run
+    pub dead: u64,
+    pub pushed: u64,
+    pub completed: u64,
+    pub retried: u64,
+    pub dead_lettered: u64,
+    /// Total time between queueing and completion of completed items
+    pub latency_ms: u64,
+    /// Number of batches committed
+    pub batches: u64,
+    /// Number of items across all committed batches
+    pub batched: u64,
+    /// Largest batch committed
+    pub largest_batch: u64,
+}
+
+/// Named queue stored in Redis
+pub struct PersistentQueue {
+    name: &'static str,
+    pending: AtomicU64,
+    dead: AtomicU64,
+    pushed: AtomicU64,
+    completed: AtomicU64,
+    retried: AtomicU64,
+    dead_lettered: AtomicU64,
+    latency_ms: AtomicU64,
+    batches: AtomicU64,
+    batched: AtomicU64,
+    largest_batch: AtomicU64,
+}
+
+/// Current time in milliseconds since the epoch
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+impl PersistentQueue {
+    /// Create a new queue handle
+    pub const fn new(name: &'static str) -> Self {
+        PersistentQueue {
+            name,
+            pending: AtomicU64::new(0),
+            dead: AtomicU64::new(0),
+            pushed: AtomicU64::new(0),
+            completed: AtomicU64::new(0),
+            retried: AtomicU64::new(0),
+            dead_lettered: AtomicU64::new(0),
+            latency_ms: AtomicU64::new(0),
+            batches: AtomicU64::new(0),
+            batched: AtomicU64::new(0),
+            largest_batch: AtomicU64::new(0),
+        }
+    }
+
+    /// Name of this queue
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Key of the list holding pending items
+    fn pending_key(&self) -> String {
+        format!("tasks:{}", self.name)
+    }
+
+    /// Key of the list holding items a consumer is working on
+    fn processing_key(&self, consumer: &str) -> String {
+        format!("tasks:{}:processing:{consumer}", self.name)
+    }
+
+    /// Key of the list holding items which failed too many times
+    fn dead_key(&self) -> String {
+        format!("tasks:{}:dead", self.name)
+    }
+
+    /// Key of the set of consumers which have used this queue
+    fn consumers_key(&self) -> String {
+        format!("tasks:{}:consumers", self.name)
+    }
+
+    /// Add an item to the queue
+    ///
+    /// Returns false if the item could not be stored,
+    /// in which case the caller should handle it in-process.
+    pub async fn push<T: Serialize>(&self, data: &T) -> bool {
+        let Ok(raw) = serde_json::to_string(&Envelope {
+            attempts: 0,
+            queued_at: now_ms(),
+            data,
+        }) else {
+            return false;
+        };
+
+        let Ok(mut conn) = get_connection().await else {
+            return false;
+        };
+
+        if conn
+            .lpush::<_, _, ()>(self.pending_key(), raw)
+            .await
+            .is_err()
+        {
+            return false;
+        }
+
+        self.pushed.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    /// Take the next item off the queue, if there is one
+    pub async fn pop<T: DeserializeOwned>(&self) -> Option<(Receipt, T)> {
+        let mut conn = get_connection().await.ok()?;
+        let processing = self.processing_key(&CONSUMER);
+
+        loop {
+            let raw = conn
+                .rpoplpush::<_, _, Option<String>>(self.pending_key(), &processing)
+                .await
+                .ok()
+                .flatten()?;
+
+            match serde_json::from_str::<Envelope<T>>(&raw) {
+                Ok(Envelope {
+                    attempts,
+                    queued_at,
+                    data,
+                }) => {
+                    return Some((
+                        Receipt {
+                            raw,
+                            attempts,
+                            queued_at,
+                        },
+                        data,
+                    ))
+                }
+                Err(err) => {
+                    error!(
+                        "Dead-lettering malformed item in {} queue: {err:?}",
+                        self.name
+                    );
+                    self.dead_letter(Receipt {
+                        raw,
+                        attempts: 0,
+                        queued_at: 0,
+                    })
+                    .await;
+                }
+            }
+        }
+    }
+
+    /// Mark an item as done
+    pub async fn complete(&self, receipt: Receipt) {
+        if let Ok(mut conn) = get_connection().await {
+            let _: Option<()> = conn
+                .lrem(self.processing_key(&CONSUMER), 1, &receipt.raw)
+                .await
+                .ok();
+
+            self.completed.fetch_add(1, Ordering::Relaxed);
+            if receipt.queued_at > 0 {
+                self.latency_ms.fetch_add(
+                    now_ms().saturating_sub(receipt.queued_at),
+                    Ordering::Relaxed,
+                );
+            }
+        }
+    }
+
+    /// Record the number of items committed together in a batch
+    pub fn record_batch(&self, size: usize) {
+        self.batches.fetch_add(1, Ordering::Relaxed);
+        self.batched.fetch_add(size as u64, Ordering::Relaxed);
+        self.largest_batch.fetch_max(size as u64, Ordering::Relaxed);
+    }
+
+    /// Mark an item as failed, it is retried until it runs out of attempts
+    pub async fn fail(&self, receipt: Receipt) {
+        let attempts = receipt.attempts + 1;
+        if attempts >= MAX_ATTEMPTS {
+            warn!(
+                "Dead-lettering item in {} queue after {attempts} attempts.",
+                self.name
+            );
+
+            return self.dead_letter(receipt).await;
+        }
+
+        let Ok(mut envelope) = serde_json::from_str::<Envelope<serde_json::Value>>(&receipt.raw)
+        else {
+            return self.dead_letter(receipt).await;
+        };
+
+        envelope.attempts = attempts;
+        if let (Ok(raw), Ok(mut conn)) = (serde_json::to_string(&envelope), get_connection().await)
+        {
+            let _: Option<()> = conn.lpush(self.pending_key(), raw).await.ok();
+            let _: Option<()> = conn
+                .lrem(self.processing_key(&CONSUMER), 1, &receipt.raw)
+                .await
+                .ok();
+
+            self.retried.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Move an item onto the dead-letter list
+    async fn dead_letter(&self, receipt: Receipt) {
+        if let Ok(mut conn) = get_connection().await {
+            let _: Option<()> = conn.lpush(self.dead_key(), &receipt.raw).await.ok();
+            let _: Option<()> = conn
+                .lrem(self.processing_key(&CONSUMER), 1, &receipt.raw)
+                .await
+                .ok();
+
+            self.dead_lettered.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Fetch items from the dead-letter list, oldest last
+    pub async fn dead_letters(&self, limit: isize) -> Vec<String> {
+        let Ok(mut conn) = get_connection().await else {
+            return vec![];
+        };
+
+        conn.lrange(self.dead_key(), 0, limit - 1)
+            .await
+            .unwrap_or_default()
+    }
+
+    /// Move all items on the dead-letter list back onto the queue with fresh attempts
+    ///
+    /// Returns the number of items requeued.
+    pub async fn requeue_dead_letters(&self) -> usize {
+        let Ok(mut conn) = get_connection().await else {
+            return 0;
+        };
+
+        let mut requeued = 0;
+        while let Ok(Some(raw)) = conn.rpop::<_, Option<String>>(self.dead_key(), None).await {
+            // Malformed items are requeued as-is and will be dead-lettered again
+            let raw = match serde_json::from_str::<Envelope<serde_json::Value>>(&raw) {
+                Ok(mut envelope) => {
+                    envelope.attempts = 0;
+                    serde_json::to_string(&envelope).unwrap_or(raw)
+                }
+                Err(_) => raw,
+            };
+
+            if conn
+                .lpush::<_, _, ()>(self.pending_key(), &raw)
+                .await
+                .is_err()
+            {
+                // Put it back where it came from and try again later
+                let _: Option<()> = conn.rpush(self.dead_key(), &raw).await.ok();
+                break;
+            }
+
+            requeued += 1;
+        }
+
+        if requeued > 0 {
+            info!(
+                "Requeued {requeued} dead-lettered items in {} queue.",
+                self.name
+            );
+        }
+
+        requeued
+    }
+
+    /// Update the cached length of the pending and dead-letter lists
+    async fn refresh_depth(&self) {
+        if let Ok(mut conn) = get_connection().await {
+            if let Ok(pending) = conn.llen::<_, u64>(self.pending_key()).await {
+                self.pending.store(pending, Ordering::Relaxed);
+            }
+
+            if let Ok(dead) = conn.llen::<_, u64>(self.dead_key()).await {
+                self.dead.store(dead, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Let other consumers know we are still alive
+    async fn heartbeat(&self) {
+        if let Ok(mut conn) = get_connection().await {
+            let _: Option<()> = conn.sadd(self.consumers_key(), &*CONSUMER).await.ok();
+            let _: Option<()> = conn
+                .set_ex(format!("tasks:consumer:{}", &*CONSUMER), 1, HEARTBEAT_TTL)
+                .await
+                .ok();
+        }
+    }
+
+    /// Move work held by consumers which have stopped sending heartbeats back onto the queue
+    async fn reclaim(&self) {
+        let Ok(mut conn) = get_connection().await else {
+            return;
+        };
+
+        let consumers: Vec<String> = conn
+            .smembers(self.consumers_key())
+            .await
+            .unwrap_or_default();
+
+        for consumer in consumers {
+            if consumer == *CONSUMER
+                || conn
+                    .exists(format!("tasks:consumer:{consumer}"))
+                    .await
+                    .unwrap_or(true)
+            {
+                continue;
+            }
+
+            let mut reclaimed = 0;
+            while let Ok(Some(_)) = conn
+                .rpoplpush::<_, _, Option<String>>(
+                    self.processing_key(&consumer),
+                    self.pending_key(),
+                )
+                .await
+            {
+                reclaimed += 1;
+            }
+
+            if reclaimed > 0 {
+                info!(
+                    "Reclaimed {reclaimed} items from {} queue held by {consumer}.",
+                    self.name
+                );
+            }
+
+            let _: Option<()> = conn.srem(self.consumers_key(), &consumer).await.ok();
+        }
+    }
+
+    /// Take a snapshot of this queue's counters
+    pub fn stats(&self) -> QueueStats {
+        QueueStats {
+            name: self.name,
+            pending: self.pending.load(Ordering::Relaxed),
+            dead: self.dead.load(Ordering::Relaxed),
+            pushed: self.pushed.load(Ordering::Relaxed),
+            completed: self.completed.load(Ordering::Relaxed),
+            retried: self.retried.load(Ordering::Relaxed),
+            dead_lettered: self.dead_lettered.load(Ordering::Relaxed),
+            latency_ms: self.latency_ms.load(Ordering::Relaxed),
+            batches: self.batches.load(Ordering::Relaxed),
+            batched: self.batched.load(Ordering::Relaxed),
+            largest_batch: self.largest_batch.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Keep consumers registered and reclaim abandoned work
+pub async fn worker(queues: &'static [&'static PersistentQueue]) {
+    loop {
+        for queue in queues {
+            queue.heartbeat().await;
+            queue.reclaim().await;
+            queue.refresh_depth().await;
+            debug!("{:?}", queue.stats());
+        }
+
+        async_std::task::sleep(MAINTENANCE_INTERVAL).await;
+    }
+}
@@ -11,12 +11,19 @@ use async_std::task::spawn;
 use deadqueue::limited::Queue;
 use once_cell::sync::Lazy;
 use guilderia_models::v0::Embed;
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, sync::Arc, time::Duration};
 
-use isahc::prelude::*;
+use super::{
+    hold,
+    persistent::{PersistentQueue, Receipt},
+    release,
+};
+
+/// How long to wait on the in-process queue before polling the persistent queue again
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
 
 /// Task information
-#[derive(Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 struct EmbedTask {
     /// Channel we're processing the event in
     channel: String,
@@ -24,22 +31,50 @@ struct EmbedTask {
     id: String,
     /// Content of the message
     content: String,
+    /// Preferred locale of the message author
+    #[serde(default)]
+    locale: Option<String>,
 }
 
+/// In-process fallback for when the persistent queue is unavailable
 static Q: Lazy<Queue<EmbedTask>> = Lazy::new(|| Queue::new(10_000));
 
+pub(super) static QUEUE: PersistentQueue = PersistentQueue::new("process_embeds");
+
+/// Check whether the queue is empty
+pub(super) fn is_empty() -> bool {
+    Q.is_empty()
+}
+
 /// Queue a new task for a worker
-pub async fn queue(channel: String, id: String, content: String) {
-    Q.try_push(EmbedTask {
+pub async fn queue(channel: String, id: String, content: String, locale: Option<String>) {
+    let task = EmbedTask {
         channel,
         id,
         content,
-    })
-    .ok();
+        locale,
+    };
+
+    if !QUEUE.push(&task).await {
+        Q.try_push(task).ok();
+    }
 
     info!("Queue is using {} slots from {}.", Q.len(), Q.capacity());
 }
 
+/// Wait for the next task from either queue
+async fn next() -> (Option<Receipt>, EmbedTask) {
+    loop {
+        if let Some((receipt, task)) = QUEUE.pop().await {
+            return (Some(receipt), task);
+        }
+
+        if let Ok(task) = async_std::future::timeout(POLL_INTERVAL, Q.pop()).await {
+            return (None, task);
+        }
+    }
+}
+
 /// Start a new worker
 pub async fn worker(db: Database) {
     let semaphore = Arc::new(Semaphore::new(
@@ -47,7 +82,9 @@ pub async fn worker(db: Database) {
     ));
 
     loop {
-        let task = Q.pop().await;
+        let (receipt, task) = next().await;
+        hold(1);
+
         let db = db.clone();
         let semaphore = semaphore.clone();
 
@@ -58,9 +95,11 @@ pub async fn worker(db: Database) {
                 &config.hosts.january,
                 config.features.limits.global.message_embeds,
                 semaphore,
+                task.locale,
             )
             .await;
 
+            let mut failed = false;
             if let Ok(embeds) = embeds {
                 if let Err(err) = Message::append(
                     &db,
@@ -73,26 +112,39 @@ pub async fn worker(db: Database) {
                 .await
                 {
                     error!("Encountered an error appending to message: {:?}", err);
+                    failed = true;
                 }
             }
+
+            if let Some(receipt) = receipt {
+                if failed {
+                    QUEUE.fail(receipt).await;
+                } else {
+                    QUEUE.complete(receipt).await;
+                }
+            }
+
+            release(1);
         });
     }
 }
 
 static RE_CODE: Lazy<Regex> = Lazy::new(|| Regex::new("```(?:.|\n)+?```|`(?:.|\n)+?`").unwrap());
-static RE_IGNORED: Lazy<Regex> = Lazy::new(|| Regex::new("(<http.+>)").unwrap());
 
 pub async fn generate(
     content: String,
     host: &str,
     max_embeds: usize,
     semaphore: Arc<Semaphore>,
+    locale: Option<String>,
 ) -> Result<Vec<Embed>> {
     // Ignore code blocks.
-    let content = RE_CODE.replace_all(&content, "");
+    let mut content = RE_CODE.replace_all(&content, "").into_owned();
 
-    // Ignore all content between angle brackets starting with http.
-    let content = RE_IGNORED.replace_all(&content, "");
+    // Ignore links wrapped in angle brackets.
+    for link in guilderia_parser::parse_message(&content).suppressed_links {
+        content = content.replace(&format!("<{link}>"), "");
+    }
 
     let content = content
         // Ignore quoted lines.
@@ -138,20 +190,10 @@ pub async fn generate(
     for link in links {
         let semaphore = semaphore.clone();
         let host = host.to_string();
+        let locale = locale.clone();
         tasks.push(spawn(async move {
-            let guard = semaphore.acquire().await;
-
-            if let Ok(mut response) = isahc::get_async(format!(
-                "{host}/embed?url={}",
-                url_escape::encode_component(&link)
-            ))
-            .await
-            {
-                drop(guard);
-                response.json::<Embed>().await.ok()
-            } else {
-                None
-            }
+            let _guard = semaphore.acquire().await;
+            super::january::fetch_embed(&host, &link, locale.as_deref()).await
         }));
     }
 
@@ -0,0 +1,176 @@
+// Queue Type: Batched
+use deadqueue::limited::Queue;
+use once_cell::sync::Lazy;
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use crate::{
+    search::{engine, SearchDocument, SearchEngine},
+    Message,
+};
+
+use super::{hold, release};
+
+/// Most changes sent to the search engine at once
+const MAX_BATCH_SIZE: usize = 100;
+
+/// Change to the search index
+enum Task {
+    /// Add or replace a message
+    Index(SearchDocument),
+    /// Remove messages
+    Remove(Vec<String>),
+    /// Remove all messages in a channel
+    RemoveChannel(String),
+}
+
+static Q: Lazy<Queue<Task>> = Lazy::new(|| Queue::new(10_000));
+
+/// Whether a worker is currently draining the queue
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Check whether the queue is empty
+pub(super) fn is_empty() -> bool {
+    Q.is_empty()
+}
+
+/// Queue a change to the search index
+///
+/// Changes are applied right away by services which do not run a worker,
+/// or if the queue is full.
+async fn queue(task: Task) {
+    let Some(engine) = engine().await else {
+        return;
+    };
+
+    if !ACTIVE.load(Ordering::Relaxed) {
+        commit(engine, vec![task]).await;
+    } else if let Err(task) = Q.try_push(task) {
+        commit(engine, vec![task]).await;
+    }
+}
+
+/// Index a new or edited message
+pub async fn index(message: &Message) {
+    match SearchDocument::from_message(message) {
+        Some(document) => queue(Task::Index(document)).await,
+        // Content may have been removed from the message
+        None => queue(Task::Remove(vec![message.id.clone()])).await,
+    }
+}
+
+/// Index a set of new or edited messages
+pub async fn index_many(messages: &[Message]) {
+    for message in messages {
+        index(message).await;
+    }
+}
+
+/// Remove deleted messages from the index
+pub async fn remove(ids: Vec<String>) {
+    if !ids.is_empty() {
+        queue(Task::Remove(ids)).await;
+    }
+}
+
+/// Remove all messages in a deleted channel from the index
+pub async fn remove_channel(channel: String) {
+    queue(Task::RemoveChannel(channel)).await;
+}
+
+/// Apply changes in order, grouping consecutive changes of the same kind
+async fn commit(engine: &dyn SearchEngine, tasks: Vec<Task>) {
+    let mut documents: Vec<SearchDocument> = vec![];
+    let mut ids: Vec<String> = vec![];
+
+    for task in tasks {
+        match task {
+            Task::Index(document) => {
+                flush_removals(engine, &mut ids).await;
+                documents.push(document);
+            }
+            Task::Remove(mut removed) => {
+                flush_documents(engine, &mut documents).await;
+                ids.append(&mut removed);
+            }
+            Task::RemoveChannel(channel) => {
+                flush_documents(engine, &mut documents).await;
+                flush_removals(engine, &mut ids).await;
+                if let Err(err) = engine.remove_channel(&channel).await {
+                    error!("Failed to remove channel {channel} from search index: {err:?}");
+                }
+            }
+        }
+    }
+
+    flush_documents(engine, &mut documents).await;
+    flush_removals(engine, &mut ids).await;
+}
+
+/// Send pending messages to the search engine
+async fn flush_documents(engine: &dyn SearchEngine, documents: &mut Vec<SearchDocument>) {
+    if documents.is_empty() {
+        return;
+    }
+
+    if let Err(err) = engine.index_messages(documents).await {
+        error!(
+            "Failed to index {} messages for search: {err:?}",
+            documents.len()
+        );
+    }
+
+    documents.clear();
+}
+
+/// Send pending removals to the search engine
+async fn flush_removals(engine: &dyn SearchEngine, ids: &mut Vec<String>) {
+    if ids.is_empty() {
+        return;
+    }
+
+    if let Err(err) = engine.remove_messages(ids).await {
+        error!(
+            "Failed to remove {} messages from search index: {err:?}",
+            ids.len()
+        );
+    }
+
+    ids.clear();
+}
+
+/// Start a new worker
+pub async fn worker() {
+    let Some(engine) = engine().await else {
+        return;
+    };
+
+    // Keep trying until the search engine is reachable
+    while let Err(err) = engine.prepare().await {
+        error!("Failed to prepare search index: {err:?}");
+        async_std::task::sleep(Duration::from_secs(10)).await;
+    }
+
+    ACTIVE.store(true, Ordering::Relaxed);
+
+    loop {
+        let mut batch = vec![Q.pop().await];
+        hold(1);
+
+        while batch.len() < MAX_BATCH_SIZE {
+            match Q.try_pop() {
+                Some(task) => {
+                    hold(1);
+                    batch.push(task);
+                }
+                None => break,
+            }
+        }
+
+        let count = batch.len();
+        commit(engine, batch).await;
+        release(count);
+    }
+}
@@ -0,0 +1,370 @@
+//! Long-running moderation jobs against server members
+//!
+//! Each member a job acts on is queued as its own task, so a job which
+//! was interrupted by a restart is picked up again where it left off.
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use deadqueue::limited::Queue;
+use guilderia_result::Result;
+use iso8601_timestamp::Timestamp;
+use once_cell::sync::Lazy;
+use redis_kiss::{get_connection, AsyncCommands};
+use ulid::Ulid;
+
+use crate::{
+    events::client::EventV1, AuditLogAction, AuditLogEntry, Database, RemovalIntention, Server,
+    ServerBan,
+};
+
+use super::{
+    hold, is_shutting_down,
+    persistent::{PersistentQueue, Receipt},
+    release,
+};
+
+/// Number of members to process between progress reports
+const PROGRESS_INTERVAL: usize = 25;
+
+/// How long job progress is kept for
+const PROGRESS_TTL: usize = 60 * 60 * 24;
+
+/// How long to wait on the in-process queue before polling the persistent queue again
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Job to run against a server
+pub enum Job {
+    /// Ban a list of users with a shared reason
+    Ban {
+        ids: Vec<String>,
+        reason: Option<String>,
+    },
+    /// Kick members who have been inactive for a number of days
+    Prune {
+        days: u32,
+        exempt_roles: Vec<String>,
+    },
+}
+
+/// What to do to a single member
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum Action {
+    /// Ban the user, removing them if they are a member
+    Ban { reason: Option<String> },
+    /// Kick the member for having been inactive for a number of days
+    Kick { days: u32 },
+}
+
+/// Task information
+#[derive(Serialize, Deserialize, Debug)]
+struct JobTask {
+    /// Id of the job
+    job: String,
+    /// Server the job runs against
+    server: String,
+    /// User who started the job
+    initiator: String,
+    /// Members ranked at or above this are skipped
+    rank: i64,
+    /// Number of members in the job
+    total: usize,
+    /// User to act on
+    user: String,
+    /// What to do to them
+    action: Action,
+}
+
+/// In-process fallback for when the persistent queue is unavailable
+static Q: Lazy<Queue<JobTask>> = Lazy::new(|| Queue::new(10_000));
+
+pub(super) static QUEUE: PersistentQueue = PersistentQueue::new("server_jobs");
+
+/// Progress of jobs queued in-process, as `(processed, failed)`
+static LOCAL_PROGRESS: Lazy<Mutex<HashMap<String, (usize, usize)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Check whether the queue is empty
+pub(super) fn is_empty() -> bool {
+    Q.is_empty()
+}
+
+/// Queue a job and return its id
+///
+/// Progress is reported to the initiating user, members ranked
+/// at or above `rank` are skipped.
+pub async fn start(
+    db: &Database,
+    server: &Server,
+    initiator: String,
+    rank: i64,
+    job: Job,
+) -> Result<String> {
+    let id = Ulid::new().to_string();
+    let (targets, action) = match job {
+        Job::Ban { ids, reason } => (ids, Action::Ban { reason }),
+        Job::Prune { days, exempt_roles } => (
+            find_inactive(db, server, days, &exempt_roles).await?,
+            Action::Kick { days },
+        ),
+    };
+
+    let total = targets.len();
+    if total == 0 {
+        report(&id, &server.id, &initiator, 0, 0, 0).await;
+        finish(db, &id, &server.id, &initiator, &action, 0, 0).await;
+        return Ok(id);
+    }
+
+    for user in targets {
+        let task = JobTask {
+            job: id.clone(),
+            server: server.id.clone(),
+            initiator: initiator.clone(),
+            rank,
+            total,
+            user,
+            action: action.clone(),
+        };
+
+        if !QUEUE.push(&task).await {
+            Q.try_push(task).map_err(|_| create_error!(InternalError))?;
+        }
+    }
+
+    Ok(id)
+}
+
+/// Wait for the next task from either queue
+///
+/// Persisted tasks are left for the next start once shutdown begins.
+async fn next() -> (Option<Receipt>, JobTask) {
+    loop {
+        if !is_shutting_down() {
+            if let Some((receipt, task)) = QUEUE.pop().await {
+                return (Some(receipt), task);
+            }
+        }
+
+        if let Ok(task) = async_std::future::timeout(POLL_INTERVAL, Q.pop()).await {
+            return (None, task);
+        }
+    }
+}
+
+/// Start a new worker
+pub async fn worker(db: Database) {
+    loop {
+        let (receipt, task) = next().await;
+        hold(1);
+
+        let result = match db.fetch_server(&task.server).await {
+            Ok(server) => match &task.action {
+                Action::Ban { reason } => {
+                    ban(&db, &server, task.rank, &task.user, reason.clone()).await
+                }
+                Action::Kick { .. } => kick(&db, &server, task.rank, &task.user).await,
+            },
+            Err(err) => Err(err),
+        };
+
+        // Failures are final, the initiator can start another job for them
+        if let Err(err) = &result {
+            warn!(
+                "Job {} failed for {} in {}: {err:?}",
+                task.job, task.user, task.server
+            );
+        }
+
+        let (processed, failed) = progress(&task.job, receipt.is_some(), result.is_err()).await;
+        if processed % PROGRESS_INTERVAL == 0 || processed >= task.total {
+            report(
+                &task.job,
+                &task.server,
+                &task.initiator,
+                processed.min(task.total),
+                failed,
+                task.total,
+            )
+            .await;
+        }
+
+        // Only one worker sees the last member of a job
+        if processed == task.total {
+            finish(
+                &db,
+                &task.job,
+                &task.server,
+                &task.initiator,
+                &task.action,
+                processed.saturating_sub(failed),
+                failed,
+            )
+            .await;
+        }
+
+        if let Some(receipt) = receipt {
+            QUEUE.complete(receipt).await;
+        }
+
+        release(1);
+    }
+}
+
+/// Count a processed member towards a job, returning `(processed, failed)` so far
+async fn progress(job: &str, persisted: bool, failed: bool) -> (usize, usize) {
+    if persisted {
+        if let Ok(mut conn) = get_connection().await {
+            let key = format!("jobs:{job}");
+            if failed {
+                let _: Option<()> = conn.hincr(&key, "failed", 1).await.ok();
+            }
+
+            if let Ok(processed) = conn.hincr::<_, _, _, usize>(&key, "processed", 1).await {
+                let _: Option<()> = conn.expire(&key, PROGRESS_TTL).await.ok();
+                let failed: usize = conn
+                    .hget::<_, _, Option<usize>>(&key, "failed")
+                    .await
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default();
+
+                return (processed, failed);
+            }
+        }
+    }
+
+    let mut jobs = LOCAL_PROGRESS.lock().unwrap();
+    let entry = jobs.entry(job.to_string()).or_default();
+    entry.0 += 1;
+    if failed {
+        entry.1 += 1;
+    }
+
+    *entry
+}
+
+/// Record a finished job in the server's audit log and forget its progress
+async fn finish(
+    db: &Database,
+    id: &str,
+    server: &str,
+    initiator: &str,
+    action: &Action,
+    succeeded: usize,
+    failed: usize,
+) {
+    let action = match action {
+        Action::Ban { reason } => AuditLogAction::BulkBan {
+            job: id.to_string(),
+            reason: reason.clone(),
+            banned: succeeded as i64,
+            failed: failed as i64,
+        },
+        Action::Kick { days } => AuditLogAction::MemberPrune {
+            job: id.to_string(),
+            days: *days,
+            kicked: succeeded as i64,
+            failed: failed as i64,
+        },
+    };
+
+    if let Err(err) = AuditLogEntry::create(db, server, initiator, action).await {
+        error!("Failed to record job {id} in the audit log: {err:?}");
+    }
+
+    LOCAL_PROGRESS.lock().unwrap().remove(id);
+    if let Ok(mut conn) = get_connection().await {
+        let _: Option<()> = conn.del(format!("jobs:{id}")).await.ok();
+    }
+}
+
+/// Send a progress update to the user who started the job
+async fn report(
+    id: &str,
+    server: &str,
+    initiator: &str,
+    processed: usize,
+    failed: usize,
+    total: usize,
+) {
+    EventV1::ServerJobProgress {
+        id: id.to_string(),
+        server: server.to_string(),
+        processed,
+        failed,
+        total,
+    }
+    .private(initiator.to_string())
+    .await;
+}
+
+/// Ban a single user, removing them from the server if present
+async fn ban(
+    db: &Database,
+    server: &Server,
+    rank: i64,
+    user_id: &str,
+    reason: Option<String>,
+) -> Result<()> {
+    if user_id == server.owner {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    if let Ok(member) = db.fetch_member(&server.id, user_id).await {
+        if member.get_ranking(server) <= rank {
+            return Err(create_error!(NotElevated));
+        }
+
+        member
+            .remove(db, server, RemovalIntention::Ban, false)
+            .await?;
+    }
+
+    ServerBan::create(db, server, user_id, reason)
+        .await
+        .map(|_| ())
+}
+
+/// Kick a single member
+async fn kick(db: &Database, server: &Server, rank: i64, user_id: &str) -> Result<()> {
+    let member = db.fetch_member(&server.id, user_id).await?;
+    if member.get_ranking(server) <= rank {
+        return Err(create_error!(NotElevated));
+    }
+
+    member
+        .remove(db, server, RemovalIntention::Kick, false)
+        .await
+}
+
+/// Find members who have not read any messages in the server for a number of days
+///
+/// Activity is judged by when the member last acknowledged any channel.
+/// Members who never acknowledged a message are judged by when they joined.
+async fn find_inactive(
+    db: &Database,
+    server: &Server,
+    days: u32,
+    exempt_roles: &[String],
+) -> Result<Vec<String>> {
+    let cutoff = Duration::from_secs(days as u64 * 24 * 60 * 60);
+    let last_active = db.fetch_last_active(&server.channels).await?;
+    let now = Timestamp::now_utc();
+
+    Ok(db
+        .fetch_all_members(&server.id)
+        .await?
+        .into_iter()
+        .filter(|member| {
+            member.id.user != server.owner
+                && !member.roles.iter().any(|role| exempt_roles.contains(role))
+        })
+        .filter(|member| {
+            let active_recently = last_active
+                .get(&member.id.user)
+                .is_some_and(|active| now.duration_since(*active) < cutoff);
+
+            !active_recently && now.duration_since(member.joined_at) >= cutoff
+        })
+        .map(|member| member.id.user)
+        .collect())
+}
@@ -35,6 +35,60 @@ impl From<crate::Bot> for Bot {
             terms_of_service_url: value.terms_of_service_url,
             privacy_policy_url: value.privacy_policy_url,
             flags: value.flags.unwrap_or_default() as u32,
+            intents: value.intents.map(|v| v as u32),
+            restricted_intents: value.restricted_intents.unwrap_or_default() as u32,
+            permissions: value.permissions.map(|v| v as u64),
+            user_installable: value.user_installable,
+            commands: value.commands.into_iter().map(|v| v.into()).collect(),
+            bridge_keys: value.bridge_keys.into_iter().map(|v| v.into()).collect(),
+        }
+    }
+}
+
+impl From<crate::BotCommand> for BotCommand {
+    fn from(value: crate::BotCommand) -> Self {
+        BotCommand {
+            name: value.name,
+            description: value.description,
+            contexts: value.contexts.into_iter().map(|v| v.into()).collect(),
+        }
+    }
+}
+
+impl From<BotCommand> for crate::BotCommand {
+    fn from(value: BotCommand) -> crate::BotCommand {
+        crate::BotCommand {
+            name: value.name,
+            description: value.description,
+            contexts: value.contexts.into_iter().map(|v| v.into()).collect(),
+        }
+    }
+}
+
+impl From<crate::CommandContext> for CommandContext {
+    fn from(value: crate::CommandContext) -> Self {
+        match value {
+            crate::CommandContext::Channel => CommandContext::Channel,
+            crate::CommandContext::User => CommandContext::User,
+        }
+    }
+}
+
+impl From<CommandContext> for crate::CommandContext {
+    fn from(value: CommandContext) -> crate::CommandContext {
+        match value {
+            CommandContext::Channel => crate::CommandContext::Channel,
+            CommandContext::User => crate::CommandContext::User,
+        }
+    }
+}
+
+impl From<crate::UserApp> for UserApp {
+    fn from(value: crate::UserApp) -> Self {
+        UserApp {
+            bot: value.id.bot,
+            user: value.id.user,
+            installed_at: value.installed_at,
         }
     }
 }
@@ -44,6 +98,7 @@ impl From<FieldsBot> for crate::FieldsBot {
         match value {
             FieldsBot::InteractionsURL => crate::FieldsBot::InteractionsURL,
             FieldsBot::Token => crate::FieldsBot::Token,
+            FieldsBot::Intents => crate::FieldsBot::Intents,
         }
     }
 }
@@ -53,6 +108,7 @@ impl From<crate::FieldsBot> for FieldsBot {
         match value {
             crate::FieldsBot::InteractionsURL => FieldsBot::InteractionsURL,
             crate::FieldsBot::Token => FieldsBot::Token,
+            crate::FieldsBot::Intents => FieldsBot::Intents,
         }
     }
 }
@@ -90,6 +146,17 @@ impl From<crate::ChannelUnread> for ChannelUnread {
             id: value.id.into(),
             last_id: value.last_id,
             mentions: value.mentions.unwrap_or_default(),
+            unread_count: value.unread_count,
+        }
+    }
+}
+
+impl From<crate::ReadReceipt> for ReadReceipt {
+    fn from(value: crate::ReadReceipt) -> Self {
+        ReadReceipt {
+            user: value.id.user,
+            message_id: value.message_id,
+            seen_at: value.seen_at,
         }
     }
 }
@@ -103,6 +170,22 @@ impl From<crate::ChannelCompositeKey> for ChannelCompositeKey {
     }
 }
 
+impl From<crate::Interaction> for Interaction {
+    fn from(value: crate::Interaction) -> Self {
+        Interaction {
+            id: value.id,
+            bot: value.bot,
+            user: value.user,
+            channel: value.channel,
+            context: value.context.into(),
+            command: value.command,
+            options: value.options,
+            deferred: value.deferred,
+            response: value.response,
+        }
+    }
+}
+
 impl From<crate::Webhook> for Webhook {
     fn from(value: crate::Webhook) -> Self {
         Webhook {
@@ -189,6 +272,10 @@ impl From<crate::Channel> for Channel {
                 default_permissions,
                 role_permissions,
                 nsfw,
+                synced,
+                allowed_attachments,
+                rate_limit_per_user,
+                message_retention_seconds,
             } => Channel::TextChannel {
                 id,
                 server,
@@ -199,6 +286,10 @@ impl From<crate::Channel> for Channel {
                 default_permissions,
                 role_permissions,
                 nsfw,
+                synced,
+                allowed_attachments,
+                rate_limit_per_user,
+                message_retention_seconds,
             },
             crate::Channel::VoiceChannel {
                 id,
@@ -209,6 +300,8 @@ impl From<crate::Channel> for Channel {
                 default_permissions,
                 role_permissions,
                 nsfw,
+                synced,
+                activity_channel,
             } => Channel::VoiceChannel {
                 id,
                 server,
@@ -218,6 +311,8 @@ impl From<crate::Channel> for Channel {
                 default_permissions,
                 role_permissions,
                 nsfw,
+                synced,
+                activity_channel,
             },
         }
     }
@@ -269,6 +364,10 @@ impl From<Channel> for crate::Channel {
                 default_permissions,
                 role_permissions,
                 nsfw,
+                synced,
+                allowed_attachments,
+                rate_limit_per_user,
+                message_retention_seconds,
             } => crate::Channel::TextChannel {
                 id,
                 server,
@@ -279,6 +378,10 @@ impl From<Channel> for crate::Channel {
                 default_permissions,
                 role_permissions,
                 nsfw,
+                synced,
+                allowed_attachments,
+                rate_limit_per_user,
+                message_retention_seconds,
             },
             Channel::VoiceChannel {
                 id,
@@ -289,6 +392,8 @@ impl From<Channel> for crate::Channel {
                 default_permissions,
                 role_permissions,
                 nsfw,
+                synced,
+                activity_channel,
             } => crate::Channel::VoiceChannel {
                 id,
                 server,
@@ -298,6 +403,8 @@ impl From<Channel> for crate::Channel {
                 default_permissions,
                 role_permissions,
                 nsfw,
+                synced,
+                activity_channel,
             },
         }
     }
@@ -316,6 +423,11 @@ impl From<crate::PartialChannel> for PartialChannel {
             role_permissions: value.role_permissions,
             default_permissions: value.default_permissions,
             last_message_id: value.last_message_id,
+            synced: value.synced,
+            allowed_attachments: value.allowed_attachments,
+            rate_limit_per_user: value.rate_limit_per_user,
+            message_retention_seconds: value.message_retention_seconds,
+            activity_channel: value.activity_channel,
         }
     }
 }
@@ -333,6 +445,11 @@ impl From<PartialChannel> for crate::PartialChannel {
             role_permissions: value.role_permissions,
             default_permissions: value.default_permissions,
             last_message_id: value.last_message_id,
+            synced: value.synced,
+            allowed_attachments: value.allowed_attachments,
+            rate_limit_per_user: value.rate_limit_per_user,
+            message_retention_seconds: value.message_retention_seconds,
+            activity_channel: value.activity_channel,
         }
     }
 }
@@ -343,6 +460,10 @@ impl From<FieldsChannel> for crate::FieldsChannel {
             FieldsChannel::Description => crate::FieldsChannel::Description,
             FieldsChannel::Icon => crate::FieldsChannel::Icon,
             FieldsChannel::DefaultPermissions => crate::FieldsChannel::DefaultPermissions,
+            FieldsChannel::AllowedAttachments => crate::FieldsChannel::AllowedAttachments,
+            FieldsChannel::RateLimitPerUser => crate::FieldsChannel::RateLimitPerUser,
+            FieldsChannel::MessageRetentionSeconds => crate::FieldsChannel::MessageRetentionSeconds,
+            FieldsChannel::ActivityChannel => crate::FieldsChannel::ActivityChannel,
         }
     }
 }
@@ -353,6 +474,10 @@ impl From<crate::FieldsChannel> for FieldsChannel {
             crate::FieldsChannel::Description => FieldsChannel::Description,
             crate::FieldsChannel::Icon => FieldsChannel::Icon,
             crate::FieldsChannel::DefaultPermissions => FieldsChannel::DefaultPermissions,
+            crate::FieldsChannel::AllowedAttachments => FieldsChannel::AllowedAttachments,
+            crate::FieldsChannel::RateLimitPerUser => FieldsChannel::RateLimitPerUser,
+            crate::FieldsChannel::MessageRetentionSeconds => FieldsChannel::MessageRetentionSeconds,
+            crate::FieldsChannel::ActivityChannel => FieldsChannel::ActivityChannel,
         }
     }
 }
@@ -366,6 +491,7 @@ impl From<crate::Emoji> for Emoji {
             name: value.name,
             animated: value.animated,
             nsfw: value.nsfw,
+            pending: value.pending,
         }
     }
 }
@@ -394,11 +520,15 @@ impl From<crate::File> for File {
             id: value.id,
             tag: value.tag,
             filename: value.filename,
+            alt_text: value.alt_text,
             metadata: value.metadata.into(),
             content_type: value.content_type,
             size: value.size,
             deleted: value.deleted,
             reported: value.reported,
+            scan: value.scan.map(|scan| scan.into()),
+            quarantined: value.quarantined,
+            voice_note: value.voice_note,
             message_id: value.message_id,
             user_id: value.user_id,
             server_id: value.server_id,
@@ -413,11 +543,15 @@ impl From<File> for crate::File {
             id: value.id,
             tag: value.tag,
             filename: value.filename,
+            alt_text: value.alt_text,
             metadata: value.metadata.into(),
             content_type: value.content_type,
             size: value.size,
             deleted: value.deleted,
             reported: value.reported,
+            scan: value.scan.map(|scan| scan.into()),
+            quarantined: value.quarantined,
+            voice_note: value.voice_note,
             message_id: value.message_id,
             user_id: value.user_id,
             server_id: value.server_id,
@@ -430,20 +564,70 @@ impl From<File> for crate::File {
     }
 }
 
+impl From<crate::FileScan> for FileScan {
+    fn from(value: crate::FileScan) -> Self {
+        FileScan {
+            signature: value.signature,
+            engine: value.engine,
+            signatures: value.signatures,
+            scanned_at: value.scanned_at,
+        }
+    }
+}
+
+impl From<FileScan> for crate::FileScan {
+    fn from(value: FileScan) -> crate::FileScan {
+        crate::FileScan {
+            signature: value.signature,
+            engine: value.engine,
+            signatures: value.signatures,
+            scanned_at: value.scanned_at,
+        }
+    }
+}
+
 impl From<crate::Metadata> for Metadata {
     fn from(value: crate::Metadata) -> Self {
         match value {
             crate::Metadata::File => Metadata::File,
-            crate::Metadata::Text => Metadata::Text,
-            crate::Metadata::Image { width, height } => Metadata::Image {
+            crate::Metadata::Text {
+                language,
+                lines,
+                preview,
+            } => Metadata::Text {
+                language,
+                lines: lines.map(|lines| lines as usize),
+                preview,
+            },
+            crate::Metadata::Image {
+                width,
+                height,
+                blurhash,
+                stripped,
+            } => Metadata::Image {
                 width: width as usize,
                 height: height as usize,
+                blurhash,
+                stripped: stripped.into_iter().map(|s| s.into()).collect(),
             },
-            crate::Metadata::Video { width, height } => Metadata::Video {
+            crate::Metadata::Video {
+                width,
+                height,
+                blurhash,
+            } => Metadata::Video {
                 width: width as usize,
                 height: height as usize,
+                blurhash,
+            },
+            crate::Metadata::Audio { duration, waveform } => Metadata::Audio {
+                duration: duration.map(|duration| duration as usize),
+                waveform,
+            },
+            crate::Metadata::Archive { files, count, size } => Metadata::Archive {
+                files: files.into_iter().map(|file| file.into()).collect(),
+                count: count as usize,
+                size: size as usize,
             },
-            crate::Metadata::Audio => Metadata::Audio,
         }
     }
 }
@@ -452,16 +636,82 @@ impl From<Metadata> for crate::Metadata {
     fn from(value: Metadata) -> crate::Metadata {
         match value {
             Metadata::File => crate::Metadata::File,
-            Metadata::Text => crate::Metadata::Text,
-            Metadata::Image { width, height } => crate::Metadata::Image {
+            Metadata::Text {
+                language,
+                lines,
+                preview,
+            } => crate::Metadata::Text {
+                language,
+                lines: lines.map(|lines| lines as isize),
+                preview,
+            },
+            Metadata::Image {
+                width,
+                height,
+                blurhash,
+                stripped,
+            } => crate::Metadata::Image {
                 width: width as isize,
                 height: height as isize,
+                blurhash,
+                stripped: stripped.into_iter().map(|s| s.into()).collect(),
             },
-            Metadata::Video { width, height } => crate::Metadata::Video {
+            Metadata::Video {
+                width,
+                height,
+                blurhash,
+            } => crate::Metadata::Video {
                 width: width as isize,
                 height: height as isize,
+                blurhash,
+            },
+            Metadata::Audio { duration, waveform } => crate::Metadata::Audio {
+                duration: duration.map(|duration| duration as isize),
+                waveform,
+            },
+            Metadata::Archive { files, count, size } => crate::Metadata::Archive {
+                files: files.into_iter().map(|file| file.into()).collect(),
+                count: count as isize,
+                size: size as isize,
             },
-            Metadata::Audio => crate::Metadata::Audio,
+        }
+    }
+}
+
+impl From<crate::StrippedMetadata> for StrippedMetadata {
+    fn from(value: crate::StrippedMetadata) -> Self {
+        match value {
+            crate::StrippedMetadata::Exif => StrippedMetadata::Exif,
+            crate::StrippedMetadata::Orientation => StrippedMetadata::Orientation,
+            crate::StrippedMetadata::ColorProfile => StrippedMetadata::ColorProfile,
+        }
+    }
+}
+
+impl From<StrippedMetadata> for crate::StrippedMetadata {
+    fn from(value: StrippedMetadata) -> crate::StrippedMetadata {
+        match value {
+            StrippedMetadata::Exif => crate::StrippedMetadata::Exif,
+            StrippedMetadata::Orientation => crate::StrippedMetadata::Orientation,
+            StrippedMetadata::ColorProfile => crate::StrippedMetadata::ColorProfile,
+        }
+    }
+}
+
+impl From<crate::ArchiveEntry> for ArchiveEntry {
+    fn from(value: crate::ArchiveEntry) -> Self {
+        ArchiveEntry {
+            name: value.name,
+            size: value.size as usize,
+        }
+    }
+}
+
+impl From<ArchiveEntry> for crate::ArchiveEntry {
+    fn from(value: ArchiveEntry) -> crate::ArchiveEntry {
+        crate::ArchiveEntry {
+            name: value.name,
+            size: value.size as isize,
         }
     }
 }
@@ -487,10 +737,18 @@ impl crate::Message {
             role_mentions: self.role_mentions,
             replies: self.replies,
             reactions: self.reactions,
+            burst_reactions: self.burst_reactions,
             interactions: self.interactions.into(),
             masquerade: self.masquerade.map(Into::into),
+            provenance: self.provenance.map(Into::into),
+            thread: self.thread,
             flags: self.flags.unwrap_or_default(),
             pinned: self.pinned,
+            tts: self.tts,
+            effect: self.effect,
+            extensions: self.extensions,
+            fallback: self.fallback,
+            expires_at: self.expires_at,
         }
     }
 }
@@ -516,10 +774,18 @@ impl From<crate::PartialMessage> for PartialMessage {
             role_mentions: value.role_mentions,
             replies: value.replies,
             reactions: value.reactions,
+            burst_reactions: value.burst_reactions,
             interactions: value.interactions.map(Into::into),
             masquerade: value.masquerade.map(Into::into),
+            provenance: value.provenance.map(Into::into),
+            thread: value.thread,
             flags: value.flags,
             pinned: value.pinned,
+            tts: value.tts,
+            effect: value.effect,
+            extensions: value.extensions,
+            fallback: value.fallback,
+            expires_at: value.expires_at,
         }
     }
 }
@@ -538,12 +804,27 @@ impl From<crate::SystemMessage> for SystemMessage {
             crate::SystemMessage::Text { content } => Self::Text { content },
             crate::SystemMessage::UserAdded { id, by } => Self::UserAdded { id, by },
             crate::SystemMessage::UserBanned { id } => Self::UserBanned { id },
-            crate::SystemMessage::UserJoined { id } => Self::UserJoined { id },
+            crate::SystemMessage::UserJoined { id, content } => Self::UserJoined { id, content },
             crate::SystemMessage::UserKicked { id } => Self::UserKicked { id },
-            crate::SystemMessage::UserLeft { id } => Self::UserLeft { id },
+            crate::SystemMessage::UserLeft { id, content } => Self::UserLeft { id, content },
             crate::SystemMessage::UserRemove { id, by } => Self::UserRemove { id, by },
             crate::SystemMessage::MessagePinned { id, by } => Self::MessagePinned { id, by },
             crate::SystemMessage::MessageUnpinned { id, by } => Self::MessageUnpinned { id, by },
+            crate::SystemMessage::MessageHighlighted {
+                id,
+                channel,
+                author,
+                count,
+            } => Self::MessageHighlighted {
+                id,
+                channel,
+                author,
+                count,
+            },
+            crate::SystemMessage::CallStarted { channel, by } => Self::CallStarted { channel, by },
+            crate::SystemMessage::CallEnded { channel, duration } => {
+                Self::CallEnded { channel, duration }
+            }
         }
     }
 }
@@ -598,6 +879,95 @@ impl From<Masquerade> for crate::Masquerade {
     }
 }
 
+impl From<crate::AuditLogEntry> for AuditLogEntry {
+    fn from(value: crate::AuditLogEntry) -> Self {
+        AuditLogEntry {
+            id: value.id,
+            server: value.server,
+            actor: value.actor,
+            action: value.action.into(),
+        }
+    }
+}
+
+impl From<crate::AuditLogAction> for AuditLogAction {
+    fn from(value: crate::AuditLogAction) -> Self {
+        match value {
+            crate::AuditLogAction::PermissionOverrideEdit {
+                channel,
+                role,
+                before,
+                after,
+            } => AuditLogAction::PermissionOverrideEdit {
+                channel,
+                role,
+                diff: PermissionDiff::new(before.into(), after.into()),
+            },
+            crate::AuditLogAction::ScheduledPurge { channel, deleted } => {
+                AuditLogAction::ScheduledPurge { channel, deleted }
+            }
+            crate::AuditLogAction::BulkBan {
+                job,
+                reason,
+                banned,
+                failed,
+            } => AuditLogAction::BulkBan {
+                job,
+                reason,
+                banned,
+                failed,
+            },
+            crate::AuditLogAction::MemberPrune {
+                job,
+                days,
+                kicked,
+                failed,
+            } => AuditLogAction::MemberPrune {
+                job,
+                days,
+                kicked,
+                failed,
+            },
+        }
+    }
+}
+
+impl From<crate::PendingAction> for PendingAction {
+    fn from(value: crate::PendingAction) -> Self {
+        PendingAction {
+            id: value.id,
+            server: value.server,
+            initiator: value.initiator,
+            action: value.action.into(),
+        }
+    }
+}
+
+impl From<crate::PendingActionKind> for PendingActionKind {
+    fn from(value: crate::PendingActionKind) -> Self {
+        match value {
+            crate::PendingActionKind::DeleteServer => PendingActionKind::DeleteServer,
+            crate::PendingActionKind::BanMembers { ids, reason } => {
+                PendingActionKind::BanMembers { ids, reason }
+            }
+            crate::PendingActionKind::PurgeMessages { channel, ids } => {
+                PendingActionKind::PurgeMessages { channel, ids }
+            }
+            crate::PendingActionKind::SchedulePurge {
+                channel,
+                hour,
+                minute,
+                interval_days,
+            } => PendingActionKind::SchedulePurge {
+                channel,
+                hour,
+                minute,
+                interval_days,
+            },
+        }
+    }
+}
+
 impl From<crate::PolicyChange> for PolicyChange {
     fn from(value: crate::PolicyChange) -> Self {
         PolicyChange {
@@ -627,6 +997,39 @@ impl From<crate::ServerBan> for ServerBan {
         ServerBan {
             id: value.id.into(),
             reason: value.reason,
+            created_at: value.created_at,
+            subscription: value.subscription,
+        }
+    }
+}
+
+impl From<crate::BanSubscription> for BanSubscription {
+    fn from(value: crate::BanSubscription) -> Self {
+        BanSubscription {
+            id: value.id,
+            server: value.server,
+            source: value.source,
+            creator: value.creator,
+            mode: value.mode.into(),
+            synced_at: value.synced_at,
+        }
+    }
+}
+
+impl From<crate::BanSyncMode> for BanSyncMode {
+    fn from(value: crate::BanSyncMode) -> Self {
+        match value {
+            crate::BanSyncMode::Apply => BanSyncMode::Apply,
+            crate::BanSyncMode::Flag => BanSyncMode::Flag,
+        }
+    }
+}
+
+impl From<BanSyncMode> for crate::BanSyncMode {
+    fn from(value: BanSyncMode) -> crate::BanSyncMode {
+        match value {
+            BanSyncMode::Apply => crate::BanSyncMode::Apply,
+            BanSyncMode::Flag => crate::BanSyncMode::Flag,
         }
     }
 }
@@ -639,7 +1042,13 @@ impl From<crate::Member> for Member {
             nickname: value.nickname,
             avatar: value.avatar.map(|f| f.into()),
             roles: value.roles,
+            role_expiries: value.role_expiries.into_iter().map(Into::into).collect(),
             timeout: value.timeout,
+            badges: value.badges,
+            role_mention_optouts: value.role_mention_optouts,
+            show_server_tag: value.show_server_tag,
+            bot_scope: value.bot_scope.map(|scope| scope.into()),
+            server_tag: None,
         }
     }
 }
@@ -652,7 +1061,12 @@ impl From<Member> for crate::Member {
             nickname: value.nickname,
             avatar: value.avatar.map(|f| f.into()),
             roles: value.roles,
+            role_expiries: value.role_expiries.into_iter().map(Into::into).collect(),
             timeout: value.timeout,
+            badges: value.badges,
+            role_mention_optouts: value.role_mention_optouts,
+            show_server_tag: value.show_server_tag,
+            bot_scope: value.bot_scope.map(|scope| scope.into()),
         }
     }
 }
@@ -665,7 +1079,15 @@ impl From<crate::PartialMember> for PartialMember {
             nickname: value.nickname,
             avatar: value.avatar.map(|f| f.into()),
             roles: value.roles,
+            role_expiries: value
+                .role_expiries
+                .map(|expiries| expiries.into_iter().map(Into::into).collect()),
             timeout: value.timeout,
+            badges: value.badges,
+            role_mention_optouts: value.role_mention_optouts,
+            show_server_tag: value.show_server_tag,
+            bot_scope: value.bot_scope.map(|scope| scope.into()),
+            server_tag: None,
         }
     }
 }
@@ -678,7 +1100,70 @@ impl From<PartialMember> for crate::PartialMember {
             nickname: value.nickname,
             avatar: value.avatar.map(|f| f.into()),
             roles: value.roles,
+            role_expiries: value
+                .role_expiries
+                .map(|expiries| expiries.into_iter().map(Into::into).collect()),
             timeout: value.timeout,
+            badges: value.badges,
+            role_mention_optouts: value.role_mention_optouts,
+            show_server_tag: value.show_server_tag,
+            bot_scope: value.bot_scope.map(|scope| scope.into()),
+        }
+    }
+}
+
+impl From<crate::ScreeningResponse> for ScreeningResponse {
+    fn from(value: crate::ScreeningResponse) -> Self {
+        ScreeningResponse {
+            id: value.id.into(),
+            answers: value.answers.into_iter().map(Into::into).collect(),
+            submitted_at: value.submitted_at,
+            left_at: value.left_at,
+        }
+    }
+}
+
+impl From<crate::ScreeningAnswer> for ScreeningAnswer {
+    fn from(value: crate::ScreeningAnswer) -> Self {
+        ScreeningAnswer {
+            question: value.question,
+            answer: value.answer,
+        }
+    }
+}
+
+impl From<crate::RoleExpiry> for RoleExpiry {
+    fn from(value: crate::RoleExpiry) -> Self {
+        RoleExpiry {
+            role: value.role,
+            expires_at: value.expires_at,
+        }
+    }
+}
+
+impl From<RoleExpiry> for crate::RoleExpiry {
+    fn from(value: RoleExpiry) -> crate::RoleExpiry {
+        crate::RoleExpiry {
+            role: value.role,
+            expires_at: value.expires_at,
+        }
+    }
+}
+
+impl From<crate::BotScope> for BotScope {
+    fn from(value: crate::BotScope) -> Self {
+        BotScope {
+            role: value.role,
+            permissions: value.permissions as u64,
+        }
+    }
+}
+
+impl From<BotScope> for crate::BotScope {
+    fn from(value: BotScope) -> crate::BotScope {
+        crate::BotScope {
+            role: value.role,
+            permissions: value.permissions as i64,
         }
     }
 }
@@ -708,6 +1193,8 @@ impl From<crate::FieldsMember> for FieldsMember {
             crate::FieldsMember::Nickname => FieldsMember::Nickname,
             crate::FieldsMember::Roles => FieldsMember::Roles,
             crate::FieldsMember::Timeout => FieldsMember::Timeout,
+            crate::FieldsMember::RoleMentionOptouts => FieldsMember::RoleMentionOptouts,
+            crate::FieldsMember::Badges => FieldsMember::Badges,
         }
     }
 }
@@ -719,6 +1206,8 @@ impl From<FieldsMember> for crate::FieldsMember {
             FieldsMember::Nickname => crate::FieldsMember::Nickname,
             FieldsMember::Roles => crate::FieldsMember::Roles,
             FieldsMember::Timeout => crate::FieldsMember::Timeout,
+            FieldsMember::RoleMentionOptouts => crate::FieldsMember::RoleMentionOptouts,
+            FieldsMember::Badges => crate::FieldsMember::Badges,
         }
     }
 }
@@ -740,23 +1229,43 @@ impl From<crate::Server> for Server {
             owner: value.owner,
             name: value.name,
             description: value.description,
+            tag: value.tag,
             channels: value.channels,
             categories: value
                 .categories
                 .map(|categories| categories.into_iter().map(|v| v.into()).collect()),
             system_messages: value.system_messages.map(|v| v.into()),
+            starboard: value.starboard.map(|v| v.into()),
+            new_member_restrictions: value.new_member_restrictions.map(|v| v.into()),
+            mention_limit: value.mention_limit.map(|v| v.into()),
+            content_filter: value.content_filter.map(|v| v.into()),
             roles: value
                 .roles
                 .into_iter()
                 .map(|(k, v)| (k, v.into()))
                 .collect(),
+            badges: value
+                .badges
+                .into_iter()
+                .map(|(k, v)| (k, v.into()))
+                .collect(),
             default_permissions: value.default_permissions,
             icon: value.icon.map(|f| f.into()),
             banner: value.banner.map(|f| f.into()),
             flags: value.flags.unwrap_or_default() as u32,
+            recognition: value.recognition.map(|v| v.into()),
             nsfw: value.nsfw,
             analytics: value.analytics,
             discoverable: value.discoverable,
+            default_notifications: value.default_notifications.into(),
+            require_emoji_approval: value.require_emoji_approval,
+            require_rules_acceptance: value.require_rules_acceptance,
+            screening_questions: value.screening_questions,
+            disable_tts: value.disable_tts,
+            require_action_approval: value.require_action_approval,
+            share_bans: value.share_bans,
+            member_count: value.member_count,
+            online_count: value.online_count,
         }
     }
 }
@@ -768,23 +1277,43 @@ impl From<Server> for crate::Server {
             owner: value.owner,
             name: value.name,
             description: value.description,
+            tag: value.tag,
             channels: value.channels,
             categories: value
                 .categories
                 .map(|categories| categories.into_iter().map(|v| v.into()).collect()),
             system_messages: value.system_messages.map(|v| v.into()),
+            starboard: value.starboard.map(|v| v.into()),
+            new_member_restrictions: value.new_member_restrictions.map(|v| v.into()),
+            mention_limit: value.mention_limit.map(|v| v.into()),
+            content_filter: value.content_filter.map(|v| v.into()),
             roles: value
                 .roles
                 .into_iter()
                 .map(|(k, v)| (k, v.into()))
                 .collect(),
+            badges: value
+                .badges
+                .into_iter()
+                .map(|(k, v)| (k, v.into()))
+                .collect(),
             default_permissions: value.default_permissions,
             icon: value.icon.map(|f| f.into()),
             banner: value.banner.map(|f| f.into()),
             flags: Some(value.flags as i32),
+            recognition: value.recognition.map(|v| v.into()),
             nsfw: value.nsfw,
             analytics: value.analytics,
             discoverable: value.discoverable,
+            default_notifications: value.default_notifications.into(),
+            require_emoji_approval: value.require_emoji_approval,
+            require_rules_acceptance: value.require_rules_acceptance,
+            screening_questions: value.screening_questions,
+            disable_tts: value.disable_tts,
+            require_action_approval: value.require_action_approval,
+            share_bans: value.share_bans,
+            member_count: value.member_count,
+            online_count: value.online_count,
         }
     }
 }
@@ -796,21 +1325,39 @@ impl From<crate::PartialServer> for PartialServer {
             owner: value.owner,
             name: value.name,
             description: value.description,
+            tag: value.tag,
             channels: value.channels,
             categories: value
                 .categories
                 .map(|categories| categories.into_iter().map(|v| v.into()).collect()),
             system_messages: value.system_messages.map(|v| v.into()),
+            starboard: value.starboard.map(|v| v.into()),
+            new_member_restrictions: value.new_member_restrictions.map(|v| v.into()),
+            mention_limit: value.mention_limit.map(|v| v.into()),
+            content_filter: value.content_filter.map(|v| v.into()),
             roles: value
                 .roles
                 .map(|roles| roles.into_iter().map(|(k, v)| (k, v.into())).collect()),
+            badges: value
+                .badges
+                .map(|badges| badges.into_iter().map(|(k, v)| (k, v.into())).collect()),
             default_permissions: value.default_permissions,
             icon: value.icon.map(|f| f.into()),
             banner: value.banner.map(|f| f.into()),
             flags: value.flags.map(|v| v as u32),
+            recognition: value.recognition.map(|v| v.into()),
             nsfw: value.nsfw,
             analytics: value.analytics,
             discoverable: value.discoverable,
+            default_notifications: value.default_notifications.map(Into::into),
+            require_emoji_approval: value.require_emoji_approval,
+            require_rules_acceptance: value.require_rules_acceptance,
+            screening_questions: value.screening_questions,
+            disable_tts: value.disable_tts,
+            require_action_approval: value.require_action_approval,
+            share_bans: value.share_bans,
+            member_count: value.member_count,
+            online_count: value.online_count,
         }
     }
 }
@@ -822,21 +1369,57 @@ impl From<PartialServer> for crate::PartialServer {
             owner: value.owner,
             name: value.name,
             description: value.description,
+            tag: value.tag,
             channels: value.channels,
             categories: value
                 .categories
                 .map(|categories| categories.into_iter().map(|v| v.into()).collect()),
             system_messages: value.system_messages.map(|v| v.into()),
+            starboard: value.starboard.map(|v| v.into()),
+            new_member_restrictions: value.new_member_restrictions.map(|v| v.into()),
+            mention_limit: value.mention_limit.map(|v| v.into()),
+            content_filter: value.content_filter.map(|v| v.into()),
             roles: value
                 .roles
                 .map(|roles| roles.into_iter().map(|(k, v)| (k, v.into())).collect()),
+            badges: value
+                .badges
+                .map(|badges| badges.into_iter().map(|(k, v)| (k, v.into())).collect()),
             default_permissions: value.default_permissions,
             icon: value.icon.map(|f| f.into()),
             banner: value.banner.map(|f| f.into()),
             flags: value.flags.map(|v| v as i32),
+            recognition: value.recognition.map(|v| v.into()),
             nsfw: value.nsfw,
             analytics: value.analytics,
             discoverable: value.discoverable,
+            default_notifications: value.default_notifications.map(Into::into),
+            require_emoji_approval: value.require_emoji_approval,
+            require_rules_acceptance: value.require_rules_acceptance,
+            screening_questions: value.screening_questions,
+            disable_tts: value.disable_tts,
+            require_action_approval: value.require_action_approval,
+            share_bans: value.share_bans,
+            member_count: value.member_count,
+            online_count: value.online_count,
+        }
+    }
+}
+
+impl From<crate::NotificationMode> for NotificationMode {
+    fn from(value: crate::NotificationMode) -> Self {
+        match value {
+            crate::NotificationMode::AllMessages => NotificationMode::AllMessages,
+            crate::NotificationMode::MentionsOnly => NotificationMode::MentionsOnly,
+        }
+    }
+}
+
+impl From<NotificationMode> for crate::NotificationMode {
+    fn from(value: NotificationMode) -> crate::NotificationMode {
+        match value {
+            NotificationMode::AllMessages => crate::NotificationMode::AllMessages,
+            NotificationMode::MentionsOnly => crate::NotificationMode::MentionsOnly,
         }
     }
 }
@@ -849,6 +1432,12 @@ impl From<crate::FieldsServer> for FieldsServer {
             crate::FieldsServer::Description => FieldsServer::Description,
             crate::FieldsServer::Icon => FieldsServer::Icon,
             crate::FieldsServer::SystemMessages => FieldsServer::SystemMessages,
+            crate::FieldsServer::Tag => FieldsServer::Tag,
+            crate::FieldsServer::Starboard => FieldsServer::Starboard,
+            crate::FieldsServer::NewMemberRestrictions => FieldsServer::NewMemberRestrictions,
+            crate::FieldsServer::MentionLimit => FieldsServer::MentionLimit,
+            crate::FieldsServer::ContentFilter => FieldsServer::ContentFilter,
+            crate::FieldsServer::Recognition => FieldsServer::Recognition,
         }
     }
 }
@@ -861,6 +1450,283 @@ impl From<FieldsServer> for crate::FieldsServer {
             FieldsServer::Description => crate::FieldsServer::Description,
             FieldsServer::Icon => crate::FieldsServer::Icon,
             FieldsServer::SystemMessages => crate::FieldsServer::SystemMessages,
+            FieldsServer::Tag => crate::FieldsServer::Tag,
+            FieldsServer::Starboard => crate::FieldsServer::Starboard,
+            FieldsServer::NewMemberRestrictions => crate::FieldsServer::NewMemberRestrictions,
+            FieldsServer::MentionLimit => crate::FieldsServer::MentionLimit,
+            FieldsServer::ContentFilter => crate::FieldsServer::ContentFilter,
+            FieldsServer::Recognition => crate::FieldsServer::Recognition,
+        }
+    }
+}
+
+impl From<crate::Starboard> for Starboard {
+    fn from(value: crate::Starboard) -> Self {
+        Starboard {
+            emoji: value.emoji,
+            threshold: value.threshold,
+            channel: value.channel,
+        }
+    }
+}
+
+impl From<Starboard> for crate::Starboard {
+    fn from(value: Starboard) -> crate::Starboard {
+        crate::Starboard {
+            emoji: value.emoji,
+            threshold: value.threshold,
+            channel: value.channel,
+        }
+    }
+}
+
+impl From<crate::NewMemberRestrictions> for NewMemberRestrictions {
+    fn from(value: crate::NewMemberRestrictions) -> Self {
+        NewMemberRestrictions {
+            account_age_minutes: value.account_age_minutes,
+            membership_age_minutes: value.membership_age_minutes,
+            message_interval_seconds: value.message_interval_seconds,
+            block_links: value.block_links,
+            block_attachments: value.block_attachments,
+        }
+    }
+}
+
+impl From<NewMemberRestrictions> for crate::NewMemberRestrictions {
+    fn from(value: NewMemberRestrictions) -> crate::NewMemberRestrictions {
+        crate::NewMemberRestrictions {
+            account_age_minutes: value.account_age_minutes,
+            membership_age_minutes: value.membership_age_minutes,
+            message_interval_seconds: value.message_interval_seconds,
+            block_links: value.block_links,
+            block_attachments: value.block_attachments,
+        }
+    }
+}
+
+impl From<crate::MentionLimit> for MentionLimit {
+    fn from(value: crate::MentionLimit) -> Self {
+        MentionLimit {
+            max: value.max,
+            action: value.action.into(),
+        }
+    }
+}
+
+impl From<MentionLimit> for crate::MentionLimit {
+    fn from(value: MentionLimit) -> crate::MentionLimit {
+        crate::MentionLimit {
+            max: value.max,
+            action: value.action.into(),
+        }
+    }
+}
+
+impl From<crate::MentionLimitAction> for MentionLimitAction {
+    fn from(value: crate::MentionLimitAction) -> Self {
+        match value {
+            crate::MentionLimitAction::Block => MentionLimitAction::Block,
+            crate::MentionLimitAction::Strip => MentionLimitAction::Strip,
+        }
+    }
+}
+
+impl From<MentionLimitAction> for crate::MentionLimitAction {
+    fn from(value: MentionLimitAction) -> crate::MentionLimitAction {
+        match value {
+            MentionLimitAction::Block => crate::MentionLimitAction::Block,
+            MentionLimitAction::Strip => crate::MentionLimitAction::Strip,
+        }
+    }
+}
+
+impl From<crate::ServerRecognition> for ServerRecognition {
+    fn from(value: crate::ServerRecognition) -> Self {
+        ServerRecognition {
+            description: value.description,
+            url: value.url,
+            since: value.since,
+        }
+    }
+}
+
+impl From<ServerRecognition> for crate::ServerRecognition {
+    fn from(value: ServerRecognition) -> crate::ServerRecognition {
+        crate::ServerRecognition {
+            description: value.description,
+            url: value.url,
+            since: value.since,
+        }
+    }
+}
+
+impl From<crate::PurgeSchedule> for PurgeSchedule {
+    fn from(value: crate::PurgeSchedule) -> Self {
+        PurgeSchedule {
+            id: value.id,
+            server: value.server,
+            creator: value.creator,
+            hour: value.hour,
+            minute: value.minute,
+            interval_days: value.interval_days,
+            next_run: value.next_run,
+            last_run: value.last_run,
+        }
+    }
+}
+
+impl From<crate::BotDmPreferences> for BotDmPreferences {
+    fn from(value: crate::BotDmPreferences) -> Self {
+        BotDmPreferences {
+            allow_all: value.allow_all,
+            allowed: value.allowed,
+        }
+    }
+}
+
+impl From<BotDmPreferences> for crate::BotDmPreferences {
+    fn from(value: BotDmPreferences) -> Self {
+        crate::BotDmPreferences {
+            allow_all: value.allow_all,
+            allowed: value.allowed,
+        }
+    }
+}
+
+impl From<crate::Bookmark> for Bookmark {
+    fn from(value: crate::Bookmark) -> Self {
+        Bookmark {
+            id: value.id,
+            channel: value.channel,
+            message: value.message,
+            name: value.name,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+impl From<crate::Thread> for Thread {
+    fn from(value: crate::Thread) -> Self {
+        Thread {
+            id: value.id,
+            channel: value.channel,
+            creator: value.creator,
+            name: value.name,
+            archived: value.archived,
+            members: value.members,
+            message_count: value.message_count,
+            last_message_id: value.last_message_id,
+        }
+    }
+}
+
+impl From<crate::PartialThread> for PartialThread {
+    fn from(value: crate::PartialThread) -> Self {
+        PartialThread {
+            id: value.id,
+            channel: value.channel,
+            creator: value.creator,
+            name: value.name,
+            archived: value.archived,
+            members: value.members,
+            message_count: value.message_count,
+            last_message_id: value.last_message_id,
+        }
+    }
+}
+
+impl From<crate::ServerApplication> for ServerApplication {
+    fn from(value: crate::ServerApplication) -> Self {
+        ServerApplication {
+            id: value.id,
+            server: value.server,
+            applicant: value.applicant,
+            program: value.program.into(),
+            reason: value.reason,
+            url: value.url,
+            created_at: value.created_at,
+            status: value.status.into(),
+            review: value.review.map(|v| v.into()),
+        }
+    }
+}
+
+impl From<crate::ServerProgram> for ServerProgram {
+    fn from(value: crate::ServerProgram) -> Self {
+        match value {
+            crate::ServerProgram::Verified => ServerProgram::Verified,
+            crate::ServerProgram::Partnered => ServerProgram::Partnered,
+        }
+    }
+}
+
+impl From<ServerProgram> for crate::ServerProgram {
+    fn from(value: ServerProgram) -> crate::ServerProgram {
+        match value {
+            ServerProgram::Verified => crate::ServerProgram::Verified,
+            ServerProgram::Partnered => crate::ServerProgram::Partnered,
+        }
+    }
+}
+
+impl From<crate::ServerApplicationStatus> for ServerApplicationStatus {
+    fn from(value: crate::ServerApplicationStatus) -> Self {
+        match value {
+            crate::ServerApplicationStatus::Pending => ServerApplicationStatus::Pending,
+            crate::ServerApplicationStatus::Approved => ServerApplicationStatus::Approved,
+            crate::ServerApplicationStatus::Rejected => ServerApplicationStatus::Rejected,
+        }
+    }
+}
+
+impl From<crate::ServerApplicationReview> for ServerApplicationReview {
+    fn from(value: crate::ServerApplicationReview) -> Self {
+        ServerApplicationReview {
+            reviewer: value.reviewer,
+            note: value.note,
+            reviewed_at: value.reviewed_at,
+        }
+    }
+}
+
+impl From<crate::ContentFilter> for ContentFilter {
+    fn from(value: crate::ContentFilter) -> Self {
+        ContentFilter {
+            words: value.words,
+            allow: value.allow,
+            use_base_list: value.use_base_list,
+            action: value.action.into(),
+        }
+    }
+}
+
+impl From<ContentFilter> for crate::ContentFilter {
+    fn from(value: ContentFilter) -> crate::ContentFilter {
+        crate::ContentFilter {
+            words: value.words,
+            allow: value.allow,
+            use_base_list: value.use_base_list,
+            action: value.action.into(),
+        }
+    }
+}
+
+impl From<crate::ContentFilterAction> for ContentFilterAction {
+    fn from(value: crate::ContentFilterAction) -> Self {
+        match value {
+            crate::ContentFilterAction::Censor => ContentFilterAction::Censor,
+            crate::ContentFilterAction::Block => ContentFilterAction::Block,
+            crate::ContentFilterAction::Flag => ContentFilterAction::Flag,
+        }
+    }
+}
+
+impl From<ContentFilterAction> for crate::ContentFilterAction {
+    fn from(value: ContentFilterAction) -> crate::ContentFilterAction {
+        match value {
+            ContentFilterAction::Censor => crate::ContentFilterAction::Censor,
+            ContentFilterAction::Block => crate::ContentFilterAction::Block,
+            ContentFilterAction::Flag => crate::ContentFilterAction::Flag,
         }
     }
 }
@@ -871,6 +1737,8 @@ impl From<crate::Category> for Category {
             id: value.id,
             title: value.title,
             channels: value.channels,
+            default_permissions: value.default_permissions,
+            role_permissions: value.role_permissions,
         }
     }
 }
@@ -881,6 +1749,8 @@ impl From<Category> for crate::Category {
             id: value.id,
             title: value.title,
             channels: value.channels,
+            default_permissions: value.default_permissions,
+            role_permissions: value.role_permissions,
         }
     }
 }
@@ -892,6 +1762,11 @@ impl From<crate::SystemMessageChannels> for SystemMessageChannels {
             user_left: value.user_left,
             user_kicked: value.user_kicked,
             user_banned: value.user_banned,
+            message_pinned: value.message_pinned.map(Into::into),
+            user_joined_templates: value.user_joined_templates,
+            user_left_templates: value.user_left_templates,
+            welcome_image: value.welcome_image,
+            moderation_alerts: value.moderation_alerts,
         }
     }
 }
@@ -903,6 +1778,51 @@ impl From<SystemMessageChannels> for crate::SystemMessageChannels {
             user_left: value.user_left,
             user_kicked: value.user_kicked,
             user_banned: value.user_banned,
+            message_pinned: value.message_pinned.map(Into::into),
+            user_joined_templates: value.user_joined_templates,
+            user_left_templates: value.user_left_templates,
+            welcome_image: value.welcome_image,
+            moderation_alerts: value.moderation_alerts,
+        }
+    }
+}
+
+impl From<crate::SystemMessageTarget> for SystemMessageTarget {
+    fn from(value: crate::SystemMessageTarget) -> Self {
+        match value {
+            crate::SystemMessageTarget::SameChannel => SystemMessageTarget::SameChannel,
+            crate::SystemMessageTarget::Channel { id } => SystemMessageTarget::Channel { id },
+            crate::SystemMessageTarget::Disabled => SystemMessageTarget::Disabled,
+        }
+    }
+}
+
+impl From<SystemMessageTarget> for crate::SystemMessageTarget {
+    fn from(value: SystemMessageTarget) -> Self {
+        match value {
+            SystemMessageTarget::SameChannel => crate::SystemMessageTarget::SameChannel,
+            SystemMessageTarget::Channel { id } => crate::SystemMessageTarget::Channel { id },
+            SystemMessageTarget::Disabled => crate::SystemMessageTarget::Disabled,
+        }
+    }
+}
+
+impl From<crate::ServerBadge> for ServerBadge {
+    fn from(value: crate::ServerBadge) -> Self {
+        ServerBadge {
+            name: value.name,
+            icon: value.icon.map(|f| f.into()),
+            colour: value.colour,
+        }
+    }
+}
+
+impl From<ServerBadge> for crate::ServerBadge {
+    fn from(value: ServerBadge) -> crate::ServerBadge {
+        crate::ServerBadge {
+            name: value.name,
+            icon: value.icon.map(|f| f.into()),
+            colour: value.colour,
         }
     }
 }
@@ -914,6 +1834,7 @@ impl From<crate::Role> for Role {
             permissions: value.permissions,
             colour: value.colour,
             hoist: value.hoist,
+            group: value.group,
             rank: value.rank,
         }
     }
@@ -926,6 +1847,7 @@ impl From<Role> for crate::Role {
             permissions: value.permissions,
             colour: value.colour,
             hoist: value.hoist,
+            group: value.group,
             rank: value.rank,
         }
     }
@@ -938,6 +1860,7 @@ impl From<crate::PartialRole> for PartialRole {
             permissions: value.permissions,
             colour: value.colour,
             hoist: value.hoist,
+            group: value.group,
             rank: value.rank,
         }
     }
@@ -950,6 +1873,7 @@ impl From<PartialRole> for crate::PartialRole {
             permissions: value.permissions,
             colour: value.colour,
             hoist: value.hoist,
+            group: value.group,
             rank: value.rank,
         }
     }
@@ -959,6 +1883,7 @@ impl From<crate::FieldsRole> for FieldsRole {
     fn from(value: crate::FieldsRole) -> Self {
         match value {
             crate::FieldsRole::Colour => FieldsRole::Colour,
+            crate::FieldsRole::Group => FieldsRole::Group,
         }
     }
 }
@@ -967,6 +1892,7 @@ impl From<FieldsRole> for crate::FieldsRole {
     fn from(value: FieldsRole) -> Self {
         match value {
             FieldsRole::Colour => crate::FieldsRole::Colour,
+            FieldsRole::Group => crate::FieldsRole::Group,
         }
     }
 }
@@ -1201,6 +2127,8 @@ impl From<User> for crate::User {
             bot: value.bot.map(Into::into),
             suspended_until: None,
             last_acknowledged_policy_change: Timestamp::UNIX_EPOCH,
+            require_device_approval: false,
+            provisioned: false,
         }
     }
 }
@@ -1239,6 +2167,7 @@ impl From<FieldsUser> for crate::FieldsUser {
             FieldsUser::StatusPresence => crate::FieldsUser::StatusPresence,
             FieldsUser::StatusText => crate::FieldsUser::StatusText,
             FieldsUser::DisplayName => crate::FieldsUser::DisplayName,
+            FieldsUser::StatusActivity => crate::FieldsUser::StatusActivity,
 
             FieldsUser::Internal => crate::FieldsUser::None,
         }
@@ -1254,6 +2183,7 @@ impl From<crate::FieldsUser> for FieldsUser {
             crate::FieldsUser::StatusPresence => FieldsUser::StatusPresence,
             crate::FieldsUser::StatusText => FieldsUser::StatusText,
             crate::FieldsUser::DisplayName => FieldsUser::DisplayName,
+            crate::FieldsUser::StatusActivity => FieldsUser::StatusActivity,
 
             crate::FieldsUser::Suspension => FieldsUser::Internal,
             crate::FieldsUser::None => FieldsUser::Internal,
@@ -1310,6 +2240,7 @@ impl From<Presence> for crate::Presence {
 
 impl crate::UserStatus {
     fn into(self, discard_invisible: bool) -> Option<UserStatus> {
+        let invisible = self.presence == Some(crate::Presence::Invisible);
         let status = UserStatus {
             text: self.text,
             presence: self.presence.and_then(|presence| {
@@ -1319,9 +2250,15 @@ impl crate::UserStatus {
                     Some(presence.into())
                 }
             }),
+            // Activity would give away that an invisible user is online
+            activity: if discard_invisible && invisible {
+                None
+            } else {
+                self.activity.map(Into::into)
+            },
         };
 
-        if status.text.is_none() && status.presence.is_none() {
+        if status.text.is_none() && status.presence.is_none() && status.activity.is_none() {
             None
         } else {
             Some(status)
@@ -1334,6 +2271,99 @@ impl From<UserStatus> for crate::UserStatus {
         crate::UserStatus {
             text: value.text,
             presence: value.presence.map(|presence| presence.into()),
+            activity: value.activity.map(Into::into),
+        }
+    }
+}
+
+impl From<crate::Activity> for Activity {
+    fn from(value: crate::Activity) -> Self {
+        Activity {
+            kind: value.kind.into(),
+            name: value.name,
+            details: value.details,
+            state: value.state,
+            url: value.url,
+            assets: value.assets.map(Into::into),
+            timestamps: value.timestamps.map(Into::into),
+            application: value.application,
+        }
+    }
+}
+
+impl From<Activity> for crate::Activity {
+    fn from(value: Activity) -> Self {
+        crate::Activity {
+            kind: value.kind.into(),
+            name: value.name,
+            details: value.details,
+            state: value.state,
+            url: value.url,
+            assets: value.assets.map(Into::into),
+            timestamps: value.timestamps.map(Into::into),
+            application: value.application,
+        }
+    }
+}
+
+impl From<crate::ActivityType> for ActivityType {
+    fn from(value: crate::ActivityType) -> Self {
+        match value {
+            crate::ActivityType::Playing => ActivityType::Playing,
+            crate::ActivityType::Listening => ActivityType::Listening,
+            crate::ActivityType::Watching => ActivityType::Watching,
+            crate::ActivityType::Streaming => ActivityType::Streaming,
+        }
+    }
+}
+
+impl From<ActivityType> for crate::ActivityType {
+    fn from(value: ActivityType) -> Self {
+        match value {
+            ActivityType::Playing => crate::ActivityType::Playing,
+            ActivityType::Listening => crate::ActivityType::Listening,
+            ActivityType::Watching => crate::ActivityType::Watching,
+            ActivityType::Streaming => crate::ActivityType::Streaming,
+        }
+    }
+}
+
+impl From<crate::ActivityAssets> for ActivityAssets {
+    fn from(value: crate::ActivityAssets) -> Self {
+        ActivityAssets {
+            large_image: value.large_image,
+            large_text: value.large_text,
+            small_image: value.small_image,
+            small_text: value.small_text,
+        }
+    }
+}
+
+impl From<ActivityAssets> for crate::ActivityAssets {
+    fn from(value: ActivityAssets) -> Self {
+        crate::ActivityAssets {
+            large_image: value.large_image,
+            large_text: value.large_text,
+            small_image: value.small_image,
+            small_text: value.small_text,
+        }
+    }
+}
+
+impl From<crate::ActivityTimestamps> for ActivityTimestamps {
+    fn from(value: crate::ActivityTimestamps) -> Self {
+        ActivityTimestamps {
+            start: value.start,
+            end: value.end,
+        }
+    }
+}
+
+impl From<ActivityTimestamps> for crate::ActivityTimestamps {
+    fn from(value: ActivityTimestamps) -> Self {
+        crate::ActivityTimestamps {
+            start: value.start,
+            end: value.end,
         }
     }
 }
@@ -1376,6 +2406,9 @@ impl From<crate::FieldsMessage> for FieldsMessage {
     fn from(value: crate::FieldsMessage) -> Self {
         match value {
             crate::FieldsMessage::Pinned => FieldsMessage::Pinned,
+            crate::FieldsMessage::Provenance => FieldsMessage::Provenance,
+            crate::FieldsMessage::Extensions => FieldsMessage::Extensions,
+            crate::FieldsMessage::Fallback => FieldsMessage::Fallback,
         }
     }
 }
@@ -1383,6 +2416,175 @@ impl From<FieldsMessage> for crate::FieldsMessage {
     fn from(value: FieldsMessage) -> Self {
         match value {
             FieldsMessage::Pinned => crate::FieldsMessage::Pinned,
+            FieldsMessage::Provenance => crate::FieldsMessage::Provenance,
+            FieldsMessage::Extensions => crate::FieldsMessage::Extensions,
+            FieldsMessage::Fallback => crate::FieldsMessage::Fallback,
+        }
+    }
+}
+
+impl From<crate::tasks::persistent::QueueStats> for TaskQueue {
+    fn from(value: crate::tasks::persistent::QueueStats) -> Self {
+        TaskQueue {
+            name: value.name.to_string(),
+            pending: value.pending,
+            dead: value.dead,
+            pushed: value.pushed,
+            completed: value.completed,
+            retried: value.retried,
+            dead_lettered: value.dead_lettered,
+            average_latency_ms: value
+                .latency_ms
+                .checked_div(value.completed)
+                .unwrap_or_default(),
+            average_batch_size: value.batched.checked_div(value.batches).unwrap_or_default(),
+            largest_batch_size: value.largest_batch,
+        }
+    }
+}
+
+impl From<crate::ApiToken> for ApiToken {
+    fn from(value: crate::ApiToken) -> Self {
+        ApiToken {
+            id: value.id,
+            name: value.name,
+            scope: value.scope.into(),
+            last_used: value.last_used,
+        }
+    }
+}
+
+impl From<crate::ApiTokenScope> for ApiTokenScope {
+    fn from(value: crate::ApiTokenScope) -> Self {
+        match value {
+            crate::ApiTokenScope::ReadOnly => ApiTokenScope::ReadOnly,
+            crate::ApiTokenScope::Messaging => ApiTokenScope::Messaging,
+            crate::ApiTokenScope::Activity => ApiTokenScope::Activity,
+            crate::ApiTokenScope::Admin => ApiTokenScope::Admin,
+        }
+    }
+}
+
+impl From<ApiTokenScope> for crate::ApiTokenScope {
+    fn from(value: ApiTokenScope) -> Self {
+        match value {
+            ApiTokenScope::ReadOnly => crate::ApiTokenScope::ReadOnly,
+            ApiTokenScope::Messaging => crate::ApiTokenScope::Messaging,
+            ApiTokenScope::Activity => crate::ApiTokenScope::Activity,
+            ApiTokenScope::Admin => crate::ApiTokenScope::Admin,
+        }
+    }
+}
+
+impl From<crate::Device> for Device {
+    fn from(value: crate::Device) -> Self {
+        Device {
+            id: value.id,
+            name: value.name,
+            trusted: value.trusted,
+            created_at: value.created_at,
+            approved_at: value.approved_at,
+        }
+    }
+}
+
+impl From<crate::RecoveryContact> for RecoveryContact {
+    fn from(value: crate::RecoveryContact) -> Self {
+        RecoveryContact {
+            id: value.id,
+            user: value.user,
+            contact: value.contact,
+            accepted: value.accepted,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<crate::RecoveryRequest> for RecoveryRequest {
+    fn from(value: crate::RecoveryRequest) -> Self {
+        RecoveryRequest {
+            id: value.id,
+            user: value.user,
+            contact: value.contact,
+            approved_at: value.approved_at,
+            available_at: value.available_at,
+            expires_at: value.expires_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<crate::Impersonation> for Impersonation {
+    fn from(value: crate::Impersonation) -> Self {
+        Impersonation {
+            id: value.id,
+            admin: value.admin,
+            user: value.user,
+            reason: value.reason,
+            user_notified: value.user_notified,
+            created_at: value.created_at,
+            expires_at: value.expires_at,
+            requests: value.requests.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<crate::FilterWord> for FilterWord {
+    fn from(value: crate::FilterWord) -> Self {
+        FilterWord {
+            id: value.id,
+            creator: value.creator,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<crate::ImpersonatedRequest> for ImpersonatedRequest {
+    fn from(value: crate::ImpersonatedRequest) -> Self {
+        ImpersonatedRequest {
+            method: value.method,
+            path: value.path,
+            at: value.at,
+        }
+    }
+}
+
+impl From<crate::MessageProvenance> for MessageProvenance {
+    fn from(value: crate::MessageProvenance) -> Self {
+        MessageProvenance {
+            origin: value.origin,
+            key: value.key,
+            signature: value.signature,
+        }
+    }
+}
+
+impl From<MessageProvenance> for crate::MessageProvenance {
+    fn from(value: MessageProvenance) -> crate::MessageProvenance {
+        crate::MessageProvenance {
+            origin: value.origin,
+            key: value.key,
+            signature: value.signature,
+        }
+    }
+}
+
+impl From<crate::BridgeKey> for BridgeKey {
+    fn from(value: crate::BridgeKey) -> Self {
+        BridgeKey {
+            id: value.id,
+            origin: value.origin,
+            public_key: value.public_key,
+        }
+    }
+}
+
+impl From<BridgeKey> for crate::BridgeKey {
+    fn from(value: BridgeKey) -> crate::BridgeKey {
+        crate::BridgeKey {
+            id: value.id,
+            origin: value.origin,
+            public_key: value.public_key,
         }
     }
 }
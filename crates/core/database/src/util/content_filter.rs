@@ -0,0 +1,309 @@
+//! Content filter
+//!
+//! Servers may filter words and phrases out of messages, optionally on top
+//! of a base list managed by the instance's admins. Text is normalised
+//! before matching so common character substitutions and stretched out
+//! letters do not slip through.
+use std::ops::Range;
+
+use guilderia_result::{create_error, Result};
+
+use crate::{ContentFilter, ContentFilterAction, Database, Server, SystemMessage};
+
+/// Character used to black out filtered words
+const TOMBSTONE: char = '█';
+
+/// Symbols commonly used in place of letters
+const SUBSTITUTIONS: &[(char, char)] = &[
+    ('0', 'o'),
+    ('1', 'i'),
+    ('!', 'i'),
+    ('|', 'i'),
+    ('3', 'e'),
+    ('4', 'a'),
+    ('@', 'a'),
+    ('5', 's'),
+    ('$', 's'),
+    ('7', 't'),
+    ('8', 'b'),
+];
+
+/// Whether a character may form part of a word
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || SUBSTITUTIONS.iter().any(|(from, _)| *from == c)
+}
+
+/// Word prepared for matching
+#[derive(Debug, PartialEq)]
+struct Word {
+    /// Lowercased word with substitutions undone
+    normalised: String,
+    /// Normalised word with repeated letters collapsed
+    squeezed: String,
+}
+
+impl Word {
+    /// Prepare a word for matching, so `H3LLLO` matches `hello`
+    fn new(word: &str) -> Word {
+        let normalised: String = word
+            .chars()
+            .flat_map(char::to_lowercase)
+            .map(|c| {
+                SUBSTITUTIONS
+                    .iter()
+                    .find(|(from, _)| *from == c)
+                    .map(|(_, to)| *to)
+                    .unwrap_or(c)
+            })
+            .collect();
+
+        let mut squeezed = String::with_capacity(normalised.len());
+        for c in normalised.chars() {
+            if !squeezed.ends_with(c) {
+                squeezed.push(c);
+            }
+        }
+
+        Word {
+            normalised,
+            squeezed,
+        }
+    }
+
+    /// Whether this word from a message matches a word on a filter list
+    ///
+    /// Stretched out letters still match, but a word may not be shorter
+    /// than the filtered word, so filtering `ass` does not filter `as`.
+    fn matches(&self, filtered: &Word, prefix: bool) -> bool {
+        if prefix {
+            self.squeezed.starts_with(&filtered.squeezed)
+        } else {
+            self.squeezed == filtered.squeezed
+                && self.normalised.chars().count() >= filtered.normalised.chars().count()
+        }
+    }
+}
+
+/// Split text into words along with where they appear
+fn tokenise(text: &str) -> Vec<(Range<usize>, Word)> {
+    let mut tokens = vec![];
+    let mut start = None;
+    for (index, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (start, is_word_char(c)) {
+            (None, true) => start = Some(index),
+            (Some(from), false) => {
+                // Punctuation at the end of a word is not a substitution
+                let word = text[from..index].trim_end_matches(['!', '|']);
+                if !word.is_empty() {
+                    tokens.push((from..from + word.len(), Word::new(word)));
+                }
+
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+/// Entry on a filter list
+struct Entry {
+    /// Words of the entry
+    words: Vec<Word>,
+    /// Whether the last word also matches longer words
+    prefix: bool,
+}
+
+impl Entry {
+    /// Whether the entry matches the words of a message starting at an index
+    fn matches_at(&self, tokens: &[(Range<usize>, Word)], index: usize) -> bool {
+        let Some(window) = tokens.get(index..index + self.words.len()) else {
+            return false;
+        };
+
+        let last = self.words.len() - 1;
+        window
+            .iter()
+            .zip(&self.words)
+            .enumerate()
+            .all(|(position, ((_, token), word))| {
+                token.matches(word, self.prefix && position == last)
+            })
+    }
+}
+
+/// Compiled list of words and phrases to filter
+pub struct WordFilter {
+    entries: Vec<Entry>,
+}
+
+impl WordFilter {
+    /// Compile a filter list, leaving out any allowed words
+    pub fn new<'a>(
+        words: impl IntoIterator<Item = &'a str>,
+        allow: impl IntoIterator<Item = &'a str>,
+    ) -> WordFilter {
+        let allow: Vec<String> = allow
+            .into_iter()
+            .map(|word| Word::new(word.trim()).normalised)
+            .collect();
+
+        WordFilter {
+            entries: words
+                .into_iter()
+                .filter_map(|word| {
+                    let (word, prefix) = match word.trim().strip_suffix('*') {
+                        Some(word) => (word, true),
+                        None => (word, false),
+                    };
+
+                    let words: Vec<Word> =
+                        tokenise(word).into_iter().map(|(_, word)| word).collect();
+
+                    (!words.is_empty()
+                        && !(words.len() == 1 && allow.contains(&words[0].normalised)))
+                    .then_some(Entry { words, prefix })
+                })
+                .collect(),
+        }
+    }
+
+    /// Find where filtered words and phrases appear in text
+    pub fn find(&self, text: &str) -> Vec<Range<usize>> {
+        let tokens = tokenise(text);
+        let mut matches: Vec<Range<usize>> = vec![];
+
+        let mut index = 0;
+        while index < tokens.len() {
+            if let Some(entry) = self
+                .entries
+                .iter()
+                .find(|entry| entry.matches_at(&tokens, index))
+            {
+                let end = index + entry.words.len() - 1;
+                matches.push(tokens[index].0.start..tokens[end].0.end);
+                index = end + 1;
+            } else {
+                index += 1;
+            }
+        }
+
+        matches
+    }
+
+    /// Black out filtered words and phrases in text
+    pub fn censor(text: &str, matches: &[Range<usize>]) -> String {
+        let mut censored = String::with_capacity(text.len());
+        let mut last = 0;
+        for range in matches {
+            censored.push_str(&text[last..range.start]);
+            censored.extend(std::iter::repeat(TOMBSTONE).take(text[range.clone()].chars().count()));
+            last = range.end;
+        }
+
+        censored.push_str(&text[last..]);
+        censored
+    }
+}
+
+impl ContentFilter {
+    /// Compile the server's filter list, including the instance list if enabled
+    pub async fn compile(&self, db: &Database) -> Result<WordFilter> {
+        let base = if self.use_base_list {
+            db.fetch_filter_words().await?
+        } else {
+            vec![]
+        };
+
+        Ok(WordFilter::new(
+            self.words
+                .iter()
+                .map(String::as_str)
+                .chain(base.iter().map(|word| word.id.as_str())),
+            self.allow.iter().map(String::as_str),
+        ))
+    }
+}
+
+/// Run message content through a server's content filter
+///
+/// Returns the content to send, which is censored if the server asks for
+/// it. Flagged messages are reported to the server's moderators.
+pub async fn check(
+    db: &Database,
+    server: &Server,
+    channel: &str,
+    author: &str,
+    content: String,
+) -> Result<String> {
+    let Some(filter) = &server.content_filter else {
+        return Ok(content);
+    };
+
+    let matches = filter.compile(db).await?.find(&content);
+    if matches.is_empty() {
+        return Ok(content);
+    }
+
+    match filter.action {
+        ContentFilterAction::Censor => Ok(WordFilter::censor(&content, &matches)),
+        ContentFilterAction::Block => Err(create_error!(MessageFiltered)),
+        ContentFilterAction::Flag => {
+            if let Some(alerts) = server
+                .system_messages
+                .as_ref()
+                .and_then(|x| x.moderation_alerts.as_ref())
+            {
+                let words: Vec<&str> = matches
+                    .iter()
+                    .map(|range| &content[range.clone()])
+                    .collect();
+                SystemMessage::Text {
+                    content: format!(
+                        "<@{author}> used filtered words in <#{channel}>: {}",
+                        words.join(", ")
+                    ),
+                }
+                .into_message(alerts.to_string())
+                .send_without_notifications(db, None, None, false, false, false)
+                .await
+                .ok();
+            }
+
+            Ok(content)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Word, WordFilter};
+
+    #[test]
+    fn normalises_substitutions() {
+        assert_eq!(Word::new("$p@m").normalised, "spam");
+        assert_eq!(Word::new("H3LLLO").squeezed, "helo");
+        assert!(Word::new("h3llllo").matches(&Word::new("hello"), false));
+        assert!(!Word::new("as").matches(&Word::new("ass"), false));
+    }
+
+    #[test]
+    fn finds_whole_words_and_phrases() {
+        let filter = WordFilter::new(["spam", "bad phrase", "scam*"], []);
+
+        let text = "no Sp4mmm here, just a b@d   phrase and scammers. Spamalot!";
+        let matches = filter.find(text);
+        let found: Vec<&str> = matches.iter().map(|range| &text[range.clone()]).collect();
+        assert_eq!(found, vec!["Sp4mmm", "b@d   phrase", "scammers"]);
+
+        assert_eq!(WordFilter::censor("spam!", &filter.find("spam!")), "████!");
+    }
+
+    #[test]
+    fn respects_allowed_words() {
+        let filter = WordFilter::new(["spam", "ham"], ["SPAM"]);
+        assert!(filter.find("spam and eggs").is_empty());
+        assert_eq!(filter.find("ham and eggs").len(), 1);
+    }
+}
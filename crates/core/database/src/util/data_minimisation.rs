@@ -0,0 +1,206 @@
+//! Data minimisation
+//!
+//! Users may ask for messages they sent in direct messages to be deleted
+//! and for attachments to be removed from their messages once they reach
+//! a certain age. Each run handles a bounded amount of work per user so
+//! nobody holds up everyone else, and the outcome is reported back to the
+//! user through their settings.
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
+
+use chrono::Utc;
+use guilderia_models::v0::MessageSort;
+use guilderia_result::Result;
+use ulid::Ulid;
+
+use crate::{
+    Channel, DataMinimisation, DataMinimisationStatus, Database, Message, MessageFilter,
+    MessageQuery, MessageTimePeriod, PartialMessage, UserSettings, UserSettingsImpl,
+    DATA_MINIMISATION_STATUS_KEY,
+};
+
+/// Messages handled per batch
+const BATCH_SIZE: i64 = 100;
+
+/// Batches of each kind handled per user in a single run
+const MAX_BATCHES: usize = 10;
+
+/// Id before which messages are older than the given number of days
+fn cutoff(days: u32) -> String {
+    Ulid::from_datetime(SystemTime::now() - Duration::from_secs(days as u64 * 24 * 60 * 60))
+        .to_string()
+}
+
+/// Delete messages the user sent in direct messages and groups before the cutoff
+async fn delete_dm_messages(
+    db: &Database,
+    user: &str,
+    days: u32,
+    status: &mut DataMinimisationStatus,
+) -> Result<()> {
+    let channels: Vec<String> = db
+        .find_direct_messages(user)
+        .await?
+        .into_iter()
+        .filter(|channel| {
+            matches!(
+                channel,
+                Channel::DirectMessage { .. } | Channel::Group { .. }
+            )
+        })
+        .map(|channel| channel.id().to_string())
+        .collect();
+
+    if channels.is_empty() {
+        return Ok(());
+    }
+
+    let before = cutoff(days);
+    for _ in 0..MAX_BATCHES {
+        let messages = db
+            .fetch_messages(MessageQuery {
+                limit: Some(BATCH_SIZE),
+                filter: MessageFilter {
+                    channels: Some(channels.clone()),
+                    author: Some(user.to_string()),
+                    ..Default::default()
+                },
+                time_period: MessageTimePeriod::Absolute {
+                    before: Some(before.clone()),
+                    after: None,
+                    sort: Some(MessageSort::Oldest),
+                },
+            })
+            .await?;
+
+        let done = (messages.len() as i64) < BATCH_SIZE;
+        let files: Vec<String> = messages
+            .iter()
+            .flat_map(|message| message.attachments.iter().flatten())
+            .map(|file| file.id.to_string())
+            .collect();
+
+        if !files.is_empty() {
+            db.mark_attachments_as_deleted(&files).await?;
+        }
+
+        let mut by_channel: HashMap<String, Vec<String>> = HashMap::new();
+        for message in messages {
+            by_channel
+                .entry(message.channel)
+                .or_default()
+                .push(message.id);
+        }
+
+        for (channel, ids) in by_channel {
+            status.messages_deleted += ids.len();
+            Message::bulk_delete(db, &channel, ids).await?;
+        }
+
+        if done {
+            return Ok(());
+        }
+    }
+
+    status.pending = true;
+    Ok(())
+}
+
+/// Remove attachments from messages the user sent before the cutoff
+///
+/// Messages left with nothing to show are deleted outright.
+async fn strip_attachments(
+    db: &Database,
+    user: &str,
+    days: u32,
+    status: &mut DataMinimisationStatus,
+) -> Result<()> {
+    let before = cutoff(days);
+    for _ in 0..MAX_BATCHES {
+        let messages = db
+            .fetch_messages_with_attachments_before(user, &before, BATCH_SIZE)
+            .await?;
+
+        let done = (messages.len() as i64) < BATCH_SIZE;
+        for mut message in messages {
+            let files: Vec<String> = message
+                .attachments
+                .iter()
+                .flatten()
+                .map(|file| file.id.to_string())
+                .collect();
+
+            status.attachments_removed += files.len();
+
+            if message
+                .content
+                .as_ref()
+                .is_none_or(|content| content.is_empty())
+                && message
+                    .embeds
+                    .as_ref()
+                    .is_none_or(|embeds| embeds.is_empty())
+            {
+                status.messages_deleted += 1;
+                message.delete(db).await?;
+            } else {
+                db.mark_attachments_as_deleted(&files).await?;
+                message
+                    .update(
+                        db,
+                        PartialMessage {
+                            attachments: Some(vec![]),
+                            ..Default::default()
+                        },
+                        vec![],
+                    )
+                    .await?;
+            }
+        }
+
+        if done {
+            return Ok(());
+        }
+    }
+
+    status.pending = true;
+    Ok(())
+}
+
+/// Apply a user's data minimisation preferences and report the outcome in their settings
+pub async fn run(
+    db: &Database,
+    user: &str,
+    settings: &DataMinimisation,
+) -> Result<DataMinimisationStatus> {
+    let mut status = DataMinimisationStatus::default();
+
+    let mut result = Ok(());
+    if let Some(days) = settings.delete_dm_messages_after_days {
+        result = delete_dm_messages(db, user, days, &mut status).await;
+    }
+
+    if let (Ok(()), Some(days)) = (&result, settings.strip_attachments_after_days) {
+        result = strip_attachments(db, user, days, &mut status).await;
+    }
+
+    if let Err(err) = result {
+        status.error = Some(err.error_type.key().to_string());
+    }
+
+    status.last_run = Utc::now().timestamp_millis();
+
+    let mut update = UserSettings::new();
+    update.insert(
+        DATA_MINIMISATION_STATUS_KEY.to_string(),
+        (
+            status.last_run,
+            serde_json::to_string(&status).expect("serialisable status"),
+        ),
+    );
+
+    update.set(db, user).await?;
+    Ok(status)
+}
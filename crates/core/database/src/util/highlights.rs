@@ -0,0 +1,153 @@
+//! Keyword highlights
+//!
+//! Users register keywords in their settings and are notified as if they
+//! were mentioned whenever one of them comes up in a channel they can see.
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use guilderia_config::config;
+use once_cell::sync::Lazy;
+
+use crate::{
+    util::bulk_permissions::BulkDatabasePermissionQuery, Channel, Database, HighlightKeywords,
+    Message, Server, HIGHLIGHTS_KEY,
+};
+
+/// How long registered keywords are cached before being fetched again
+const KEYWORD_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Keywords of every user who registered any, and when they were fetched
+type KeywordCache = Option<(Instant, Arc<Vec<(String, HighlightKeywords)>>)>;
+
+static KEYWORDS: Lazy<Mutex<KeywordCache>> = Lazy::new(|| Mutex::new(None));
+
+/// Last highlight notification for each user and channel
+static COOLDOWNS: Lazy<Mutex<lru::LruCache<(String, String), Instant>>> =
+    Lazy::new(|| Mutex::new(lru::LruCache::new(NonZeroUsize::new(10_000).unwrap())));
+
+/// Fetch the keywords of every user who registered any
+async fn registered_keywords(db: &Database) -> Arc<Vec<(String, HighlightKeywords)>> {
+    {
+        let cache = KEYWORDS.lock().unwrap();
+        if let Some((fetched_at, keywords)) = &*cache {
+            if fetched_at.elapsed() < KEYWORD_CACHE_TTL {
+                return keywords.clone();
+            }
+        }
+    }
+
+    let keywords: Arc<Vec<_>> = Arc::new(match db.fetch_users_with_setting(HIGHLIGHTS_KEY).await {
+        Ok(settings) => settings
+            .into_iter()
+            .filter_map(|(user, value)| {
+                HighlightKeywords::parse(&value)
+                    .filter(|keywords| keywords.keyword_count() > 0)
+                    .map(|keywords| (user, keywords))
+            })
+            .collect(),
+        Err(err) => {
+            guilderia_config::capture_error(&err);
+            vec![]
+        }
+    });
+
+    KEYWORDS
+        .lock()
+        .unwrap()
+        .replace((Instant::now(), keywords.clone()));
+
+    keywords
+}
+
+/// Record a highlight for a user in a channel unless they were highlighted too recently
+fn take_cooldown(user: &str, channel: &str, cooldown: Duration) -> bool {
+    let mut cooldowns = COOLDOWNS.lock().unwrap();
+    let key = (user.to_string(), channel.to_string());
+    if cooldowns
+        .get(&key)
+        .is_some_and(|last| last.elapsed() < cooldown)
+    {
+        return false;
+    }
+
+    cooldowns.put(key, Instant::now());
+    true
+}
+
+/// Find users who should be notified of a message because of their highlight keywords
+///
+/// The author, anyone already mentioned and anyone who cannot see the channel are left out.
+pub async fn find_highlighted_users(
+    db: &Database,
+    server: &Server,
+    channel: &Channel,
+    message: &Message,
+) -> Vec<String> {
+    let (Channel::TextChannel { .. }, Some(content)) = (channel, &message.content) else {
+        return vec![];
+    };
+
+    let keywords = registered_keywords(db).await;
+    if keywords.is_empty() {
+        return vec![];
+    }
+
+    let words = guilderia_parser::parse_words(content);
+    let mentions = message.mentions.as_deref().unwrap_or_default();
+    let candidates: Vec<String> = keywords
+        .iter()
+        .filter(|(user, keywords)| {
+            *user != message.author
+                && !mentions.contains(user)
+                && keywords.matches(&server.id, &words)
+        })
+        .map(|(user, _)| user.to_string())
+        .collect();
+
+    if candidates.is_empty() {
+        return vec![];
+    }
+
+    let members = match db.fetch_members(&server.id, &candidates).await {
+        Ok(members) if !members.is_empty() => members,
+        Ok(_) => return vec![],
+        Err(err) => {
+            guilderia_config::capture_error(&err);
+            return vec![];
+        }
+    };
+
+    let can_see_channel = BulkDatabasePermissionQuery::new(db, server.clone())
+        .channel(channel)
+        .members(&members)
+        .members_can_see_channel()
+        .await;
+
+    let cooldown = Duration::from_secs(config().await.features.highlights.cooldown_seconds);
+    members
+        .into_iter()
+        .map(|member| member.id.user)
+        .filter(|user| *can_see_channel.get(user).unwrap_or(&false))
+        .filter(|user| take_cooldown(user, &message.channel, cooldown))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::take_cooldown;
+
+    #[test]
+    fn highlights_are_rate_limited() {
+        let cooldown = Duration::from_secs(60);
+
+        assert!(take_cooldown("user", "channel", cooldown));
+        assert!(!take_cooldown("user", "channel", cooldown));
+        assert!(take_cooldown("user", "other", cooldown));
+        assert!(take_cooldown("user", "channel", Duration::ZERO));
+    }
+}
@@ -0,0 +1,271 @@
+use guilderia_config::Localization;
+use guilderia_models::v0;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{Database, UserSettings, LOCALE_KEY};
+
+#[cfg(feature = "rocket-impl")]
+use guilderia_result::Error;
+
+/// Regex for matching a single BCP 47 language tag
+static RE_LANGUAGE_TAG: Lazy<Regex> =
+    Lazy::new(|| Regex::new("^[a-zA-Z]{2,8}(?:-[a-zA-Z0-9]{1,8}){0,3}$").unwrap());
+
+/// Preferred locale of a user
+///
+/// Taken from the first language in the `Accept-Language` header and
+/// forwarded to January so embeds are generated in the right language,
+/// or from the user's settings to translate server-generated text.
+#[derive(Default, Debug, Clone)]
+pub struct Locale(Option<String>);
+
+impl Locale {
+    /// Parse the preferred language from an `Accept-Language` header
+    pub fn from_header(header: &str) -> Self {
+        Locale(
+            header
+                .split(',')
+                .next()
+                .and_then(|language| language.split(';').next())
+                .map(str::trim)
+                .filter(|language| RE_LANGUAGE_TAG.is_match(language))
+                .map(ToString::to_string),
+        )
+    }
+
+    /// Parse a locale from a stored settings value
+    ///
+    /// Accepts either a bare language tag or a JSON encoded string.
+    pub fn parse(value: &str) -> Option<Locale> {
+        Some(serde_json::from_str::<String>(value).unwrap_or_else(|_| value.to_string()))
+            .filter(|language| RE_LANGUAGE_TAG.is_match(language))
+            .map(|language| Locale(Some(language)))
+    }
+
+    /// Read the preferred locale from a user's settings
+    pub fn from_settings(settings: &UserSettings) -> Locale {
+        settings
+            .get(LOCALE_KEY)
+            .and_then(|(_, value)| Locale::parse(value))
+            .unwrap_or_default()
+    }
+
+    /// Fetch the preferred locale of a user
+    pub async fn for_user(db: &Database, user: &str) -> Locale {
+        db.fetch_user_settings(user, &[LOCALE_KEY.to_string()])
+            .await
+            .map(|settings| Locale::from_settings(&settings))
+            .unwrap_or_default()
+    }
+
+    pub fn into_inner(self) -> Option<String> {
+        self.0
+    }
+
+    /// Locales to look up translations in, most specific first
+    ///
+    /// Each locale is followed by its configured fallbacks and then its
+    /// parent, e.g. `de-AT` is followed by `de`, before the default locale.
+    fn chain(&self, localization: &Localization) -> Vec<String> {
+        fn expand(locale: &str, localization: &Localization, chain: &mut Vec<String>) {
+            let locale = locale.to_lowercase();
+            if chain.contains(&locale) {
+                return;
+            }
+
+            chain.push(locale.clone());
+            for fallback in localization
+                .fallbacks
+                .iter()
+                .filter(|(key, _)| key.eq_ignore_ascii_case(&locale))
+                .flat_map(|(_, fallbacks)| fallbacks)
+            {
+                expand(fallback, localization, chain);
+            }
+
+            if let Some((parent, _)) = locale.rsplit_once('-') {
+                expand(parent, localization, chain);
+            }
+        }
+
+        let mut chain = vec![];
+        for locale in self.0.iter().chain([&localization.default_locale]) {
+            expand(locale, localization, &mut chain);
+        }
+
+        chain
+    }
+
+    /// Translate a message, substituting `{name}` placeholders with the given arguments
+    ///
+    /// Falls back to the built-in English text if no catalog has a translation.
+    pub fn translate(
+        &self,
+        localization: &Localization,
+        key: &str,
+        default: &str,
+        args: &[(&str, &str)],
+    ) -> String {
+        let template = self
+            .chain(localization)
+            .iter()
+            .find_map(|locale| {
+                localization
+                    .catalogs
+                    .iter()
+                    .find(|(catalog, _)| catalog.eq_ignore_ascii_case(locale))
+                    .and_then(|(_, catalog)| catalog.get(key))
+            })
+            .map(String::as_str)
+            .unwrap_or(default);
+
+        args.iter()
+            .fold(template.to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{name}}}"), value)
+            })
+    }
+
+    /// Translate the summary of a system message shown in notifications
+    ///
+    /// Custom text is sent as is, other messages are looked up as `system.<type>`.
+    pub fn translate_system_message(
+        &self,
+        localization: &Localization,
+        message: &v0::SystemMessage,
+    ) -> String {
+        let default: String = message.clone().into();
+        if matches!(message, v0::SystemMessage::Text { .. }) {
+            return default;
+        }
+
+        match serde_json::to_value(message)
+            .ok()
+            .and_then(|value| value["type"].as_str().map(ToString::to_string))
+        {
+            Some(kind) => self.translate(localization, &format!("system.{kind}"), &default, &[]),
+            None => default,
+        }
+    }
+}
+
+#[cfg(feature = "rocket-impl")]
+use revolt_rocket_okapi::{
+    gen::OpenApiGenerator,
+    request::{OpenApiFromRequest, RequestHeaderInput},
+};
+
+#[cfg(feature = "rocket-impl")]
+impl<'r> OpenApiFromRequest<'r> for Locale {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> guilderia_rocket_okapi::Result<RequestHeaderInput> {
+        Ok(RequestHeaderInput::None)
+    }
+}
+
+#[cfg(feature = "rocket-impl")]
+use rocket::request::{FromRequest, Outcome};
+
+#[cfg(feature = "rocket-impl")]
+#[async_trait]
+impl<'r> FromRequest<'r> for Locale {
+    type Error = Error;
+
+    async fn from_request(request: &'r rocket::Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(
+            request
+                .headers()
+                .get_one("Accept-Language")
+                .map(Locale::from_header)
+                .unwrap_or_default(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use guilderia_config::Localization;
+
+    use super::Locale;
+
+    #[test]
+    fn parses_accept_language() {
+        assert_eq!(
+            Locale::from_header("de-DE,de;q=0.9,en;q=0.8").into_inner(),
+            Some("de-DE".to_string())
+        );
+
+        assert_eq!(
+            Locale::from_header("fr;q=0.9").into_inner(),
+            Some("fr".to_string())
+        );
+
+        assert_eq!(Locale::from_header("*").into_inner(), None);
+        assert_eq!(
+            Locale::from_header("en\r\nX-Injected: 1").into_inner(),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_stored_locales() {
+        assert_eq!(
+            Locale::parse("\"pt-BR\"").and_then(Locale::into_inner),
+            Some("pt-BR".to_string())
+        );
+        assert_eq!(
+            Locale::parse("de").and_then(Locale::into_inner),
+            Some("de".to_string())
+        );
+        assert!(Locale::parse("not a locale").is_none());
+    }
+
+    #[test]
+    fn translates_with_fallbacks() {
+        let localization = Localization {
+            default_locale: "en".to_string(),
+            fallbacks: HashMap::from([("pt-br".to_string(), vec!["pt-pt".to_string()])]),
+            catalogs: HashMap::from([
+                (
+                    "de".to_string(),
+                    HashMap::from([(
+                        "push.fr.received".to_string(),
+                        "{name} hat dir eine Freundschaftsanfrage gesendet".to_string(),
+                    )]),
+                ),
+                (
+                    "pt-pt".to_string(),
+                    HashMap::from([(
+                        "push.fr.received".to_string(),
+                        "{name} enviou-te um pedido de amizade".to_string(),
+                    )]),
+                ),
+            ]),
+        };
+
+        let translate = |locale: Option<&str>| {
+            Locale(locale.map(ToString::to_string)).translate(
+                &localization,
+                "push.fr.received",
+                "{name} sent you a friend request",
+                &[("name", "Ana")],
+            )
+        };
+
+        assert_eq!(
+            translate(Some("de-AT")),
+            "Ana hat dir eine Freundschaftsanfrage gesendet"
+        );
+        assert_eq!(
+            translate(Some("pt-BR")),
+            "Ana enviou-te um pedido de amizade"
+        );
+        assert_eq!(translate(Some("fr")), "Ana sent you a friend request");
+        assert_eq!(translate(None), "Ana sent you a friend request");
+    }
+}
@@ -0,0 +1,46 @@
+//! Confirmation of mass mentions in large servers
+use guilderia_result::{create_error, Result};
+use redis_kiss::{get_connection, AsyncCommands};
+
+/// How long a confirmation token remains valid for, in seconds
+const MENTION_CONFIRMATION_TTL: usize = 300;
+
+/// Key under which a confirmation token is stored
+fn key(token: &str) -> String {
+    format!("mention_confirmation:{token}")
+}
+
+/// Check whether a mass mention has been confirmed
+///
+/// Tokens are single-use and only valid for the same author and channel.
+/// If no valid token was given, a new one is issued and returned as part
+/// of the error alongside the number of users who would be notified.
+pub async fn confirm(
+    author: &str,
+    channel: &str,
+    token: Option<&str>,
+    recipients: usize,
+) -> Result<()> {
+    let mut conn = get_connection()
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    let expected = format!("{author}:{channel}");
+    if let Some(token) = token {
+        let value: Option<String> = conn.get(key(token)).await.unwrap_or_default();
+        if value.as_deref() == Some(expected.as_str()) {
+            let _: Option<()> = conn.del(key(token)).await.ok();
+            return Ok(());
+        }
+    }
+
+    let token = nanoid::nanoid!(32);
+    conn.set_ex::<_, _, ()>(key(&token), expected, MENTION_CONFIRMATION_TTL)
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    Err(create_error!(MentionConfirmationRequired {
+        token,
+        recipients
+    }))
+}
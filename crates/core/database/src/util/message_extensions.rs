@@ -0,0 +1,146 @@
+//! Message extensions
+//!
+//! The message format grows over time, so clients list the extensions they
+//! support when connecting. Messages using extensions keep a plain text
+//! rendering of their content alongside, which is delivered to clients that
+//! would otherwise show the raw markup.
+use chrono::{TimeZone, Utc};
+use guilderia_models::v0::{MessageEffect, MessageExtension};
+use guilderia_parser::MessageToken;
+
+use crate::{FieldsMessage, Message, PartialMessage};
+
+/// Text shown in place of spoilered content
+const SPOILER: &str = "[spoiler]";
+
+/// Render content as plain text
+///
+/// Returns the extensions used by the content and its rendering, if any
+/// extensions were used.
+pub fn render_fallback(content: &str) -> (Vec<MessageExtension>, Option<String>) {
+    let tokens = guilderia_parser::parse_extensions(content);
+    if tokens.is_empty() {
+        return (vec![], None);
+    }
+
+    let mut extensions = vec![];
+    let mut fallback = String::with_capacity(content.len());
+    let mut last = 0;
+    let mut in_spoiler = false;
+    for (token, span) in tokens {
+        // Anything between spoiler markers is hidden
+        if in_spoiler {
+            if token == MessageToken::SpoilerMarker {
+                in_spoiler = false;
+                last = span.end;
+            }
+
+            continue;
+        }
+
+        fallback.push_str(&content[last..span.start]);
+        last = span.end;
+
+        let extension = match token {
+            MessageToken::SpoilerMarker => {
+                in_spoiler = true;
+                fallback.push_str(SPOILER);
+                MessageExtension::Spoiler
+            }
+            MessageToken::Timestamp(time) => {
+                match Utc.timestamp_opt(time, 0).single() {
+                    Some(time) => fallback.push_str(&time.format("%Y-%m-%d %H:%M UTC").to_string()),
+                    None => fallback.push_str(&content[span]),
+                }
+
+                MessageExtension::Timestamp
+            }
+            MessageToken::SubtextMarker => MessageExtension::Subtext,
+            _ => continue,
+        };
+
+        if !extensions.contains(&extension) {
+            extensions.push(extension);
+        }
+    }
+
+    fallback.push_str(&content[last..]);
+    (extensions, Some(fallback))
+}
+
+/// Work out the extensions used by a message and its plain text fallback
+fn describe(
+    content: Option<&str>,
+    effect: Option<&MessageEffect>,
+) -> (Option<Vec<MessageExtension>>, Option<String>) {
+    let (mut extensions, fallback) = content.map(render_fallback).unwrap_or_default();
+    if effect.is_some() {
+        extensions.push(MessageExtension::Effect);
+    }
+
+    (Some(extensions).filter(|x| !x.is_empty()), fallback)
+}
+
+impl Message {
+    /// Record the extensions used by this message and render its fallback
+    pub fn detect_extensions(&mut self) {
+        (self.extensions, self.fallback) = describe(self.content.as_deref(), self.effect.as_ref());
+    }
+
+    /// Record the extensions used by new content for this message
+    pub fn detect_extensions_in_edit(
+        &self,
+        content: &str,
+        partial: &mut PartialMessage,
+        remove: &mut Vec<FieldsMessage>,
+    ) {
+        let (extensions, fallback) = describe(Some(content), self.effect.as_ref());
+        match extensions {
+            Some(extensions) => partial.extensions = Some(extensions),
+            None if self.extensions.is_some() => remove.push(FieldsMessage::Extensions),
+            None => {}
+        }
+
+        match fallback {
+            Some(fallback) => partial.fallback = Some(fallback),
+            None if self.fallback.is_some() => remove.push(FieldsMessage::Fallback),
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use guilderia_models::v0::{MessageEffect, MessageExtension};
+
+    use super::{describe, render_fallback};
+
+    #[test]
+    fn renders_fallbacks() {
+        let (extensions, fallback) =
+            render_fallback("-# the answer is ||42 <t:0>||, as of <t:1700000000:R>");
+        assert_eq!(
+            extensions,
+            vec![
+                MessageExtension::Subtext,
+                MessageExtension::Spoiler,
+                MessageExtension::Timestamp
+            ]
+        );
+        assert_eq!(
+            fallback.as_deref(),
+            Some("the answer is [spoiler], as of 2023-11-14 22:13 UTC")
+        );
+
+        assert_eq!(render_fallback("plain `||text||`"), (vec![], None));
+    }
+
+    #[test]
+    fn describes_effects() {
+        assert_eq!(
+            describe(Some("hello"), Some(&MessageEffect::Confetti)),
+            (Some(vec![MessageExtension::Effect]), None)
+        );
+        assert_eq!(describe(Some("hello"), None), (None, None));
+    }
+}
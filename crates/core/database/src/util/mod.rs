@@ -1,6 +1,15 @@
 pub mod bridge;
 pub mod bulk_permissions;
+pub mod content_filter;
+pub mod data_minimisation;
+pub mod highlights;
 pub mod idempotency;
+pub mod locale;
+pub mod mention_confirmation;
+pub mod message_extensions;
+pub mod new_member_restrictions;
 pub mod permissions;
 pub mod reference;
+pub mod slowmode;
 pub mod test_fixtures;
+pub mod voice_activity;
@@ -0,0 +1,116 @@
+//! New member restrictions
+//!
+//! Servers may hold members whose accounts or memberships are young to
+//! stricter rules, slowing them down and keeping them from posting links
+//! or files until they have been around for a while.
+use std::{
+    num::NonZeroUsize,
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime},
+};
+
+use guilderia_models::v0::DataMessageSend;
+use guilderia_result::{create_error, Result};
+use iso8601_timestamp::Timestamp;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use ulid::Ulid;
+
+use crate::{Member, NewMemberRestrictions};
+
+/// Regex for finding links in message content
+static RE_LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b(?:https?://|www\.)\S").unwrap());
+
+/// Last message sent by each restricted member in each server
+static LAST_MESSAGE: Lazy<Mutex<lru::LruCache<(String, String), Instant>>> =
+    Lazy::new(|| Mutex::new(lru::LruCache::new(NonZeroUsize::new(10_000).unwrap())));
+
+impl NewMemberRestrictions {
+    /// Whether a member is still considered new
+    pub fn applies_to(&self, member: &Member) -> bool {
+        let account_age = Ulid::from_string(&member.id.user)
+            .ok()
+            .and_then(|id| SystemTime::now().duration_since(id.datetime()).ok())
+            .unwrap_or_default();
+
+        account_age < Duration::from_secs(self.account_age_minutes as u64 * 60)
+            || Timestamp::now_utc().duration_since(member.joined_at)
+                < Duration::from_secs(self.membership_age_minutes as u64 * 60)
+    }
+}
+
+/// Record a message from a member unless they sent one too recently
+///
+/// Returns the number of seconds left to wait if they did.
+fn take_slot(server: &str, user: &str, interval: Duration) -> Option<u64> {
+    let mut last_message = LAST_MESSAGE.lock().unwrap();
+    let key = (server.to_string(), user.to_string());
+    if let Some(elapsed) = last_message
+        .get(&key)
+        .map(Instant::elapsed)
+        .filter(|elapsed| *elapsed < interval)
+    {
+        return Some((interval - elapsed).as_secs().max(1));
+    }
+
+    last_message.put(key, Instant::now());
+    None
+}
+
+/// Check a message from a member against the restrictions of their server
+pub fn check(
+    restrictions: &NewMemberRestrictions,
+    member: &Member,
+    data: &DataMessageSend,
+) -> Result<()> {
+    if !restrictions.applies_to(member) {
+        return Ok(());
+    }
+
+    if restrictions.block_attachments && data.attachments.as_ref().is_some_and(|v| !v.is_empty()) {
+        return Err(create_error!(NewMemberRestricted));
+    }
+
+    if restrictions.block_links
+        && data
+            .content
+            .as_ref()
+            .is_some_and(|content| RE_LINK.is_match(content))
+    {
+        return Err(create_error!(NewMemberRestricted));
+    }
+
+    if restrictions.message_interval_seconds > 0 {
+        let interval = Duration::from_secs(restrictions.message_interval_seconds as u64);
+        if let Some(retry_after) = take_slot(&member.id.server, &member.id.user, interval) {
+            return Err(create_error!(NewMemberSlowmode { retry_after }));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{take_slot, RE_LINK};
+
+    #[test]
+    fn new_members_are_slowed_down() {
+        let interval = Duration::from_secs(30);
+
+        assert_eq!(take_slot("server", "user", interval), None);
+        assert_eq!(take_slot("server", "user", interval), Some(30));
+        assert_eq!(take_slot("other", "user", interval), None);
+        assert_eq!(take_slot("server", "user", Duration::ZERO), None);
+    }
+
+    #[test]
+    fn links_are_detected() {
+        assert!(RE_LINK.is_match("check out https://example.com"));
+        assert!(RE_LINK.is_match("WWW.example.com"));
+        assert!(!RE_LINK.is_match("https:// is a scheme"));
+        assert!(!RE_LINK.is_match("no links here"));
+    }
+}
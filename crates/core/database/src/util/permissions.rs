@@ -1,8 +1,8 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, ops::Deref};
 
 use guilderia_permissions::{
-    calculate_user_permissions, ChannelType, Override, PermissionQuery, PermissionValue,
-    RelationshipStatus, DEFAULT_PERMISSION_DIRECT_MESSAGE,
+    calculate_channel_permissions, calculate_user_permissions, ChannelType, Override,
+    PermissionQuery, PermissionValue, RelationshipStatus, DEFAULT_PERMISSION_DIRECT_MESSAGE,
 };
 
 use crate::{Channel, Database, Member, Server, User};
@@ -23,6 +23,29 @@ pub struct DatabasePermissionQuery<'a> {
     cached_user_permission: Option<PermissionValue>,
     cached_mutual_connection: Option<bool>,
     cached_permission: Option<u64>,
+    cached_member_missing: bool,
+}
+
+/// Channel permissions resolved once for a request
+///
+/// Carries the server and member fetched during calculation so that
+/// helpers further down don't need to fetch them again.
+#[derive(Clone, Debug)]
+pub struct ResolvedPermissions {
+    /// Calculated permission value
+    pub permissions: PermissionValue,
+    /// Server the channel belongs to, if any
+    pub server: Option<Server>,
+    /// Our member in that server, if any
+    pub member: Option<Member>,
+}
+
+impl Deref for ResolvedPermissions {
+    type Target = PermissionValue;
+
+    fn deref(&self) -> &Self::Target {
+        &self.permissions
+    }
 }
 
 #[async_trait]
@@ -126,6 +149,8 @@ impl PermissionQuery for DatabasePermissionQuery<'_> {
         if let Some(server) = &self.server {
             if self.member.is_some() {
                 true
+            } else if self.cached_member_missing {
+                false
             } else if let Ok(member) = self
                 .database
                 .fetch_member(&server.id, &self.perspective.id)
@@ -134,6 +159,7 @@ impl PermissionQuery for DatabasePermissionQuery<'_> {
                 self.member = Some(Cow::Owned(member));
                 true
             } else {
+                self.cached_member_missing = true;
                 false
             }
         } else {
@@ -367,6 +393,7 @@ impl PermissionQuery for DatabasePermissionQuery<'_> {
 
                     if let Ok(server) = self.database.fetch_server(server).await {
                         self.server.replace(Cow::Owned(server));
+                        self.cached_member_missing = false;
                     }
                 }
                 _ => unimplemented!(),
@@ -389,6 +416,33 @@ impl<'a> DatabasePermissionQuery<'a> {
             cached_mutual_connection: None,
             cached_user_permission: None,
             cached_permission: None,
+            cached_member_missing: false,
+        }
+    }
+
+    /// Calculate channel permissions, reusing the result if already known
+    pub async fn resolve(&mut self) -> ResolvedPermissions {
+        let permissions = if let Some(value) = self.cached_permission {
+            value.into()
+        } else {
+            let value = calculate_channel_permissions(self).await;
+            self.cached_permission = Some(value.clone().into());
+            value
+        };
+
+        // Owners skip the membership check, make sure we have it anyway
+        self.are_we_a_member().await;
+
+        ResolvedPermissions {
+            permissions,
+            server: self
+                .server
+                .as_ref()
+                .map(|server| server.clone().into_owned()),
+            member: self
+                .member
+                .as_ref()
+                .map(|member| member.clone().into_owned()),
         }
     }
 
@@ -429,6 +483,7 @@ impl<'a> DatabasePermissionQuery<'a> {
     pub fn channel(self, channel: &'a Channel) -> DatabasePermissionQuery<'a> {
         DatabasePermissionQuery {
             channel: Some(Cow::Borrowed(channel)),
+            cached_permission: None,
             ..self
         }
     }
@@ -437,6 +492,8 @@ impl<'a> DatabasePermissionQuery<'a> {
     pub fn server(self, server: &'a Server) -> DatabasePermissionQuery<'a> {
         DatabasePermissionQuery {
             server: Some(Cow::Borrowed(server)),
+            cached_permission: None,
+            cached_member_missing: false,
             ..self
         }
     }
@@ -445,6 +502,8 @@ impl<'a> DatabasePermissionQuery<'a> {
     pub fn member(self, member: &'a Member) -> DatabasePermissionQuery<'a> {
         DatabasePermissionQuery {
             member: Some(Cow::Borrowed(member)),
+            cached_permission: None,
+            cached_member_missing: false,
             ..self
         }
     }
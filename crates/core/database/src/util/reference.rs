@@ -10,7 +10,8 @@ use schemars::{
 };
 
 use crate::{
-    Bot, Channel, Database, Emoji, Invite, Member, Message, Server, ServerBan, User, Webhook,
+    Bot, Channel, Database, Emoji, Invite, Member, Message, Server, ServerBan, Thread, User,
+    Webhook,
 };
 
 /// Reference to some object in the database
@@ -94,6 +95,16 @@ impl Reference {
         db.fetch_server(&self.id).await
     }
 
+    /// Fetch thread started off the referenced message and validate channel
+    pub async fn as_thread_in_channel(&self, db: &Database, channel: &str) -> Result<Thread> {
+        let thread = db.fetch_thread(&self.id).await?;
+        if thread.channel != channel {
+            return Err(create_error!(NotFound));
+        }
+
+        Ok(thread)
+    }
+
     /// Fetch user from Ref
     pub async fn as_user(&self, db: &Database) -> Result<User> {
         db.fetch_user(&self.id).await
@@ -0,0 +1,48 @@
+//! Channel slowmode
+//!
+//! Text channels may limit how often each member can send messages. The
+//! time of each member's last message is kept in Redis so that the limit
+//! holds no matter which node a message is sent through.
+use guilderia_result::{create_error, Result};
+use redis_kiss::{get_connection, AsyncCommands};
+
+/// Key under which a member's last message in a channel is tracked
+fn key(channel: &str, user: &str) -> String {
+    format!("slowmode:{channel}:{user}")
+}
+
+/// Record a message from a member unless they sent one too recently
+///
+/// Fails with the number of seconds left to wait if they did.
+pub async fn check(channel: &str, user: &str, interval: u32) -> Result<()> {
+    if interval == 0 {
+        return Ok(());
+    }
+
+    let mut conn = get_connection()
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    let key = key(channel, user);
+    let fresh: bool = conn
+        .set_nx(&key, 1)
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    // Expiry is set separately, so also recover keys which never got one
+    let ttl: i64 = if fresh {
+        -1
+    } else {
+        conn.ttl(&key).await.unwrap_or(-1)
+    };
+
+    if ttl >= 0 {
+        return Err(create_error!(ChannelSlowmode {
+            retry_after: (ttl as u64).max(1)
+        }));
+    }
+
+    conn.expire::<_, ()>(&key, interval as usize)
+        .await
+        .map_err(|_| create_error!(InternalError))
+}
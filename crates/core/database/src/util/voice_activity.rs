@@ -0,0 +1,116 @@
+//! Call activity in voice channels
+//!
+//! The voice server reports participants joining and leaving calls. Who is
+//! in each call is kept in Redis, so that the first participant joining and
+//! the last one leaving can be announced in the voice channel's activity
+//! channel, whichever node the reports arrive at.
+use chrono::Utc;
+use guilderia_result::{create_error, Result};
+use redis_kiss::{get_connection, AsyncCommands};
+
+use crate::{Channel, Database, SystemMessage};
+
+/// Key under which the participants of a call are tracked
+fn participants_key(channel: &str) -> String {
+    format!("voice:{channel}:participants")
+}
+
+/// Key under which the time a call started is tracked
+fn started_key(channel: &str) -> String {
+    format!("voice:{channel}:started")
+}
+
+/// Record a participant joining a call, announcing the call if they started it
+pub async fn joined(db: &Database, channel: &Channel, user: &str) -> Result<()> {
+    let mut conn = get_connection()
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    conn.sadd::<_, _, ()>(participants_key(channel.id()), user)
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    let started: bool = conn
+        .set_nx(started_key(channel.id()), Utc::now().timestamp())
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    if started {
+        announce(
+            db,
+            channel,
+            SystemMessage::CallStarted {
+                channel: channel.id().to_string(),
+                by: user.to_string(),
+            },
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Record a participant leaving a call, announcing the call if they were the last
+pub async fn left(db: &Database, channel: &Channel, user: &str) -> Result<()> {
+    let mut conn = get_connection()
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    let key = participants_key(channel.id());
+    let removed: bool = conn
+        .srem(&key, user)
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    let remaining: usize = conn
+        .scard(&key)
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    if !removed || remaining > 0 {
+        return Ok(());
+    }
+
+    // Only whoever deletes the start time announces the end of the call
+    let key = started_key(channel.id());
+    let started: Option<i64> = conn.get(&key).await.unwrap_or_default();
+    let deleted: usize = conn
+        .del(&key)
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    if let (Some(started), 1) = (started, deleted) {
+        announce(
+            db,
+            channel,
+            SystemMessage::CallEnded {
+                channel: channel.id().to_string(),
+                duration: (Utc::now().timestamp() - started).max(0) as u64,
+            },
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Send a message to the activity channel of a voice channel, if it has one
+///
+/// The activity channel must still be a text channel in the same server.
+async fn announce(db: &Database, channel: &Channel, message: SystemMessage) -> Result<()> {
+    let (Channel::VoiceChannel { server, .. }, Some(target)) =
+        (channel, channel.activity_channel())
+    else {
+        return Ok(());
+    };
+
+    match db.fetch_channel(target).await {
+        Ok(Channel::TextChannel { server: other, .. }) if &other == server => {
+            message
+                .into_message(target.to_string())
+                .send_without_notifications(db, None, None, false, false, false)
+                .await
+        }
+        _ => Ok(()),
+    }
+}
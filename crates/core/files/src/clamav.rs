@@ -0,0 +1,102 @@
+use guilderia_config::{config, report_internal_error};
+use guilderia_result::Result;
+
+/// Version information reported by clamd
+#[derive(Debug, Clone)]
+pub struct ClamVersion {
+    /// Engine version (e.g. `ClamAV 1.4.1`)
+    pub engine: String,
+    /// Version of the loaded signature database
+    pub signatures: u32,
+}
+
+/// Result of scanning a buffer with clamd
+#[derive(Debug, Clone)]
+pub struct ClamScan {
+    /// Name of the matched signature, if the buffer is infected
+    pub signature: Option<String>,
+    /// Version of clamd used to scan the buffer
+    pub version: ClamVersion,
+}
+
+/// Parse the response to a `VERSION` command
+///
+/// e.g. `ClamAV 1.4.1/27431/Tue Oct 13 08:36:29 2026`
+fn parse_version(response: &str) -> Option<ClamVersion> {
+    let mut parts = response.trim_end_matches(['\0', '\n']).split('/');
+    let engine = parts.next()?.to_owned();
+    let signatures = parts.next()?.parse().ok()?;
+
+    Some(ClamVersion { engine, signatures })
+}
+
+/// Parse the response to a scan command
+///
+/// e.g. `stream: OK` or `stream: Eicar-Signature FOUND`
+fn parse_signature(response: &str) -> Option<String> {
+    response
+        .trim_end_matches(['\0', '\n'])
+        .strip_suffix(" FOUND")
+        .map(|found| found.rsplit(": ").next().unwrap_or(found).to_owned())
+}
+
+/// Fetch the engine and signature database version from clamd
+///
+/// Returns None if ClamAV is not configured
+pub async fn version() -> Result<Option<ClamVersion>> {
+    let config = config().await;
+    if config.files.clamd_host.is_empty() {
+        return Ok(None);
+    }
+
+    let response = report_internal_error!(revolt_clamav_client::get_version_tcp(
+        config.files.clamd_host
+    ))?;
+
+    Ok(parse_version(&String::from_utf8_lossy(&response)))
+}
+
+/// Scan a buffer for malware
+///
+/// Returns None if ClamAV is not configured
+pub async fn scan(buf: &[u8]) -> Result<Option<ClamScan>> {
+    let Some(version) = version().await? else {
+        return Ok(None);
+    };
+
+    let config = config().await;
+    let response = report_internal_error!(revolt_clamav_client::scan_buffer_tcp(
+        buf,
+        config.files.clamd_host,
+        None
+    ))?;
+
+    let signature = if report_internal_error!(revolt_clamav_client::clean(&response))? {
+        None
+    } else {
+        Some(
+            parse_signature(&String::from_utf8_lossy(&response))
+                .unwrap_or_else(|| "Unknown".to_owned()),
+        )
+    };
+
+    Ok(Some(ClamScan { signature, version }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_signature, parse_version};
+
+    #[test]
+    fn parses_clamd_responses() {
+        let version = parse_version("ClamAV 1.4.1/27431/Tue Oct 13 08:36:29 2026\0").unwrap();
+        assert_eq!(version.engine, "ClamAV 1.4.1");
+        assert_eq!(version.signatures, 27431);
+
+        assert_eq!(parse_signature("stream: OK\0"), None);
+        assert_eq!(
+            parse_signature("stream: Eicar-Signature FOUND\0").as_deref(),
+            Some("Eicar-Signature")
+        );
+    }
+}
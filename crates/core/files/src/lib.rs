@@ -1,10 +1,10 @@
-use std::io::{BufRead, Read, Seek, Write};
+use std::io::{BufRead, Cursor, Read, Seek, Write};
 
 use aes_gcm::{
     aead::{AeadCore, AeadMutInPlace, OsRng},
     Aes256Gcm, Key, KeyInit, Nonce,
 };
-use image::{DynamicImage, ImageBuffer};
+use image::{imageops::FilterType, DynamicImage, ImageBuffer, Pixel, Rgba, RgbaImage};
 use guilderia_config::{config, report_internal_error, FilesS3};
 use guilderia_result::{create_error, Result};
 
@@ -17,9 +17,20 @@ use base64::prelude::*;
 use tempfile::NamedTempFile;
 use tiny_skia::Pixmap;
 
+pub mod clamav;
+
 /// Size of the authentication tag in the buffer
 pub const AUTHENTICATION_TAG_SIZE_BYTES: usize = 16;
 
+/// Size of generated welcome images
+const WELCOME_IMAGE_SIZE: (u32, u32) = (960, 320);
+
+/// Size of the avatar drawn onto welcome images
+const WELCOME_AVATAR_SIZE: u32 = 192;
+
+/// Background used for welcome images when the server has no banner
+const WELCOME_BACKGROUND: Rgba<u8> = Rgba([36, 36, 36, 255]);
+
 /// Create an S3 client
 pub fn create_client(s3_config: FilesS3) -> Client {
     let provider_name = "my-creds";
@@ -83,6 +94,17 @@ pub async fn fetch_from_s3(bucket_id: &str, path: &str, nonce: &str) -> Result<V
     Ok(buf)
 }
 
+/// Fetch an image from S3 and decode it
+pub async fn fetch_image(
+    bucket_id: &str,
+    path: &str,
+    nonce: &str,
+    mime: &str,
+) -> Result<DynamicImage> {
+    let buf = fetch_from_s3(bucket_id, path, nonce).await?;
+    decode_image(&mut Cursor::new(buf), mime)
+}
+
 /// Encrypt and upload a file to S3 (returning its nonce/IV)
 pub async fn upload_to_s3(bucket_id: &str, path: &str, buf: &[u8]) -> Result<String> {
     let config = config().await;
@@ -280,10 +302,57 @@ pub async fn create_thumbnail(image: DynamicImage, tag: &str) -> Vec<u8> {
     // so we use thumbnail
     let image = image.thumbnail(image.width().min(*w as u32), image.height().min(*h as u32));
 
-    // Encode it into WEBP
-    let encoder = webp::Encoder::from_image(&image).expect("Could not create encoder.");
-    if config.files.webp_quality != 100.0 {
-        encoder.encode(config.files.webp_quality).to_vec()
+    encode_webp(&image, config.files.webp_quality)
+}
+
+/// Create welcome image from a server banner and a user avatar
+///
+/// The banner is cropped to fill the image and the avatar is drawn as a circle in its centre.
+pub async fn create_welcome_image(
+    banner: Option<DynamicImage>,
+    avatar: Option<DynamicImage>,
+) -> Vec<u8> {
+    // Load configuration
+    let config = config().await;
+    let (width, height) = WELCOME_IMAGE_SIZE;
+
+    let mut canvas = match banner {
+        Some(banner) => banner
+            .resize_to_fill(width, height, FilterType::Triangle)
+            .to_rgba8(),
+        None => RgbaImage::from_pixel(width, height, WELCOME_BACKGROUND),
+    };
+
+    if let Some(avatar) = avatar {
+        let avatar = avatar
+            .resize_to_fill(
+                WELCOME_AVATAR_SIZE,
+                WELCOME_AVATAR_SIZE,
+                FilterType::Triangle,
+            )
+            .to_rgba8();
+
+        let radius = WELCOME_AVATAR_SIZE as f32 / 2.0;
+        let left = (width - WELCOME_AVATAR_SIZE) / 2;
+        let top = (height - WELCOME_AVATAR_SIZE) / 2;
+
+        for (x, y, pixel) in avatar.enumerate_pixels() {
+            let dx = x as f32 + 0.5 - radius;
+            let dy = y as f32 + 0.5 - radius;
+            if dx * dx + dy * dy <= radius * radius {
+                canvas.get_pixel_mut(left + x, top + y).blend(pixel);
+            }
+        }
+    }
+
+    encode_webp(&DynamicImage::ImageRgba8(canvas), config.files.webp_quality)
+}
+
+/// Encode image into WEBP
+fn encode_webp(image: &DynamicImage, quality: f32) -> Vec<u8> {
+    let encoder = webp::Encoder::from_image(image).expect("Could not create encoder.");
+    if quality != 100.0 {
+        encoder.encode(quality).to_vec()
     } else {
         encoder.encode_lossless().to_vec()
     }
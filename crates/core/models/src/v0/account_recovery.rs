@@ -0,0 +1,84 @@
+use iso8601_timestamp::Timestamp;
+
+auto_derived!(
+    /// Account nominated to help recover another account
+    pub struct RecoveryContact {
+        /// Unique Id
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Id of the user who may be recovered
+        pub user: String,
+        /// Id of the user who can approve a recovery
+        pub contact: String,
+        /// Whether the contact has agreed to help
+        pub accepted: bool,
+        /// Time at which the contact was nominated
+        pub created_at: Timestamp,
+    }
+
+    /// Request to recover an account
+    pub struct RecoveryRequest {
+        /// Unique Id
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Id of the user being recovered
+        pub user: String,
+        /// Id of the recovery contact asked to approve this request
+        pub contact: String,
+        /// Time at which the contact approved this request
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub approved_at: Option<Timestamp>,
+        /// Time after which this request may be completed
+        pub available_at: Timestamp,
+        /// Time after which this request is discarded
+        pub expires_at: Timestamp,
+        /// Time at which this request was started
+        pub created_at: Timestamp,
+    }
+
+    /// Newly started recovery request
+    pub struct RecoveryTicket {
+        /// Recovery request
+        pub request: RecoveryRequest,
+        /// Secret token required to complete the recovery
+        pub token: String,
+    }
+
+    /// Backup code status of an account
+    pub struct BackupCodeStatus {
+        /// Number of unused backup codes
+        pub remaining: usize,
+        /// Number of codes issued when they were last generated
+        pub issued: usize,
+        /// Number of times backup codes have been regenerated
+        pub regenerations: u32,
+        /// Time at which backup codes were last generated
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub generated_at: Option<Timestamp>,
+    }
+
+    /// Nominate a recovery contact
+    pub struct DataCreateRecoveryContact {
+        /// Id of the user to nominate
+        pub user: String,
+    }
+
+    /// Start recovering an account
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct DataStartRecovery {
+        /// Email of the account
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 256)))]
+        pub email: String,
+        /// Current account password
+        #[cfg_attr(feature = "validator", validate(length(min = 8, max = 1024)))]
+        pub password: String,
+        /// Id of the recovery contact to ask for approval
+        pub contact: String,
+    }
+
+    /// Complete an account recovery
+    pub struct DataCompleteRecovery {
+        /// Secret token returned when the recovery was started
+        pub token: String,
+    }
+);
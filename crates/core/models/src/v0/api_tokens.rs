@@ -0,0 +1,53 @@
+use iso8601_timestamp::Timestamp;
+
+#[cfg(feature = "validator")]
+use validator::Validate;
+
+auto_derived!(
+    /// Personal access token which lets scripts act as a user
+    pub struct ApiToken {
+        /// Unique Id
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Name given to this token
+        pub name: String,
+        /// What this token may be used for
+        pub scope: ApiTokenScope,
+        /// Time at which this token was last used
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub last_used: Option<Timestamp>,
+    }
+
+    /// What an API token may be used for
+    pub enum ApiTokenScope {
+        /// Only fetch data
+        ReadOnly,
+        /// Fetch data and send messages
+        Messaging,
+        /// Fetch data and set the user's activity
+        Activity,
+        /// Anything a session may do, apart from managing the account (`/auth` routes)
+        Admin,
+    }
+
+    /// Newly created API token
+    pub struct CreatedApiToken {
+        /// Token information
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub info: ApiToken,
+        /// Secret token, sent as `x-api-token`
+        ///
+        /// This is only shown once.
+        pub token: String,
+    }
+
+    /// New API token information
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataCreateApiToken {
+        /// Name to give the token
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 32)))]
+        pub name: String,
+        /// What the token may be used for
+        pub scope: ApiTokenScope,
+    }
+);
@@ -0,0 +1,104 @@
+use guilderia_permissions::Override;
+
+#[cfg(feature = "rocket")]
+use rocket::FromForm;
+
+auto_derived!(
+    /// Server audit log entry
+    pub struct AuditLogEntry {
+        /// Unique Id
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Id of the server this entry belongs to
+        pub server: String,
+        /// Id of the user who performed the action
+        pub actor: String,
+        /// Action which was performed
+        pub action: AuditLogAction,
+    }
+
+    /// Action recorded in the audit log
+    #[cfg_attr(feature = "serde", serde(tag = "type"))]
+    pub enum AuditLogAction {
+        /// Permission override changed on a server or channel
+        PermissionOverrideEdit {
+            /// Channel the override belongs to, server-wide if not set
+            #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+            channel: Option<String>,
+            /// Role the override belongs to, the default permissions if not set
+            #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+            role: Option<String>,
+            /// Permissions which changed
+            diff: PermissionDiff,
+        },
+        /// Channel purged on a schedule
+        ScheduledPurge {
+            /// Channel which was purged
+            channel: String,
+            /// Number of messages deleted
+            deleted: i64,
+        },
+        /// Users banned in bulk
+        BulkBan {
+            /// Job which banned the users
+            job: String,
+            /// Reason given for the bans
+            #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+            reason: Option<String>,
+            /// Number of users banned
+            banned: i64,
+            /// Number of users who could not be banned
+            failed: i64,
+        },
+        /// Inactive members kicked
+        MemberPrune {
+            /// Job which kicked the members
+            job: String,
+            /// Number of days members had been inactive for
+            days: u32,
+            /// Number of members kicked
+            kicked: i64,
+            /// Number of members who could not be kicked
+            failed: i64,
+        },
+    }
+
+    /// Change made to a permission override
+    pub struct PermissionDiff {
+        /// Override before the change
+        pub before: Override,
+        /// Override after the change
+        pub after: Override,
+        /// Permissions which are newly allowed
+        pub granted: u64,
+        /// Permissions which are newly denied
+        pub revoked: u64,
+        /// Permissions which are no longer allowed or denied and are now inherited
+        pub reset: u64,
+    }
+
+    /// Options for fetching a server's audit log
+    #[cfg_attr(feature = "rocket", derive(FromForm))]
+    pub struct OptionsFetchAuditLog {
+        /// Fetch entries before this id
+        pub before: Option<String>,
+        /// Maximum number of entries to fetch (1-100, default 50)
+        pub limit: Option<i64>,
+    }
+);
+
+impl PermissionDiff {
+    /// Work out which permissions changed between two overrides
+    pub fn new(before: Override, after: Override) -> PermissionDiff {
+        let before_set = before.allow | before.deny;
+        let after_set = after.allow | after.deny;
+
+        PermissionDiff {
+            granted: after.allow & !before.allow,
+            revoked: after.deny & !before.deny,
+            reset: before_set & !after_set,
+            before,
+            after,
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use iso8601_timestamp::Timestamp;
+
+auto_derived!(
+    /// Named marker left on a message to continue reading from
+    pub struct Bookmark {
+        /// Unique Id
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Id of the channel the message was sent in
+        pub channel: String,
+        /// Id of the message this bookmark points at
+        pub message: String,
+        /// Name given to this bookmark
+        pub name: String,
+        /// Time at which this bookmark was last moved
+        pub updated_at: Timestamp,
+    }
+
+    /// Point a bookmark at a message
+    ///
+    /// A bookmark by the same name in the channel is moved rather than duplicated.
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct DataSetBookmark {
+        /// Id of the message to bookmark
+        #[cfg_attr(feature = "validator", validate(length(min = 26, max = 26)))]
+        pub message: String,
+        /// Bookmark name
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 32)))]
+        pub name: String,
+    }
+);
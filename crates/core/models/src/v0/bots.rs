@@ -1,3 +1,5 @@
+use iso8601_timestamp::Timestamp;
+
 use super::User;
 
 auto_derived!(
@@ -29,7 +31,7 @@ auto_derived!(
             serde(skip_serializing_if = "crate::if_false", default)
         )]
         pub discoverable: bool,
-        /// Reserved; URL for handling interactions
+        /// URL interactions are delivered to over HTTP
         #[cfg_attr(
             feature = "serde",
             serde(skip_serializing_if = "String::is_empty", default)
@@ -54,12 +56,90 @@ auto_derived!(
             serde(skip_serializing_if = "crate::if_zero_u32", default)
         )]
         pub flags: u32,
+
+        /// Bitfield of event intents declared by this bot
+        ///
+        /// Bots which have not declared any intents receive every event.
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub intents: Option<u32>,
+        /// Bitfield of intents this bot has been barred from by the instance operator
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_zero_u32", default)
+        )]
+        pub restricted_intents: u32,
+
+        /// Permissions this bot requests when it is added to a server
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub permissions: Option<u64>,
+
+        /// Whether users may install this bot to use its commands in their DMs and groups
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub user_installable: bool,
+        /// Commands provided by this bot
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "Vec::is_empty", default)
+        )]
+        pub commands: Vec<BotCommand>,
+        /// Keys used to sign messages bridged from external networks
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "Vec::is_empty", default)
+        )]
+        pub bridge_keys: Vec<BridgeKey>,
+    }
+
+    /// Key a bridge signs messages with
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct BridgeKey {
+        /// Key Id, referenced by message provenance
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 64)))]
+        pub id: String,
+        /// URI prefix of the external network this key may sign messages for
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 256)))]
+        pub origin: String,
+        /// Base64 encoded Ed25519 public key
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 64)))]
+        pub public_key: String,
+    }
+
+    /// Command provided by a bot
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct BotCommand {
+        /// Name of the command
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 32)))]
+        pub name: String,
+        /// Description of the command
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 100)))]
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub description: Option<String>,
+        /// Contexts the command is visible in
+        ///
+        /// Commands without any contexts are visible everywhere.
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "Vec::is_empty", default)
+        )]
+        pub contexts: Vec<CommandContext>,
+    }
+
+    /// Context a command is invoked in
+    pub enum CommandContext {
+        /// Bot is present in the channel
+        Channel,
+        /// Bot is installed by the invoking user
+        User,
     }
 
     /// Optional fields on bot object
     pub enum FieldsBot {
         Token,
         InteractionsURL,
+        Intents,
     }
 
     /// Flags that may be attributed to a bot
@@ -69,6 +149,17 @@ auto_derived!(
         Official = 2,
     }
 
+    /// Groups of events a bot may subscribe to
+    #[repr(u32)]
+    pub enum BotIntents {
+        /// Content, embeds and attachments of messages which do not mention the bot
+        MessageContent = 1,
+        /// Presence changes of other users
+        Presence = 2,
+        /// Member list changes in servers
+        Members = 4,
+    }
+
     /// Public Bot
     pub struct PublicBot {
         /// Bot Id
@@ -109,6 +200,10 @@ auto_derived!(
             validate(length(min = 2, max = 32), regex = "super::RE_USERNAME")
         )]
         pub name: String,
+        /// Bitfield of event intents to declare
+        pub intents: Option<u32>,
+        /// Permissions to request when added to a server
+        pub permissions: Option<u64>,
     }
 
     /// New Bot Details
@@ -131,6 +226,22 @@ auto_derived!(
         /// Interactions URL
         #[cfg_attr(feature = "validator", validate(length(min = 1, max = 2048)))]
         pub interactions_url: Option<String>,
+        /// Bitfield of event intents to declare
+        pub intents: Option<u32>,
+        /// Bitfield of intents to bar this bot from
+        ///
+        /// Only instance operators may change this.
+        pub restricted_intents: Option<u32>,
+        /// Permissions to request when added to a server
+        pub permissions: Option<u64>,
+        /// Whether users may install this bot
+        pub user_installable: Option<bool>,
+        /// Commands provided by this bot
+        #[cfg_attr(feature = "validator", validate)]
+        pub commands: Option<Vec<BotCommand>>,
+        /// Keys used to sign messages bridged from external networks
+        #[cfg_attr(feature = "validator", validate(length(max = 5)))]
+        pub bridge_keys: Option<Vec<BridgeKey>>,
         /// Fields to remove from bot object
         #[cfg_attr(feature = "validator", validate(length(min = 1)))]
         pub remove: Option<Vec<FieldsBot>>,
@@ -143,6 +254,10 @@ auto_derived!(
         Server {
             /// Server Id
             server: String,
+            /// Subset of the bot's requested permissions to grant
+            ///
+            /// Grants every requested permission if not given.
+            permissions: Option<u64>,
         },
         /// Invite to a group
         Group {
@@ -151,6 +266,22 @@ auto_derived!(
         },
     }
 
+    /// Permissions to grant a bot in a server
+    pub struct DataEditBotScope {
+        /// Subset of the bot's requested permissions to grant
+        pub permissions: u64,
+    }
+
+    /// Bot installed by a user
+    pub struct UserApp {
+        /// Id of the bot
+        pub bot: String,
+        /// Id of the user who installed the bot
+        pub user: String,
+        /// Time at which the bot was installed
+        pub installed_at: Timestamp,
+    }
+
     /// Owned Bots Response
     ///
     /// Both lists are sorted by their IDs.
@@ -1,4 +1,4 @@
-use super::{Channel, File, Server, User};
+use super::{Channel, File, Server, ServerRecognition, User};
 
 auto_derived!(
     /// Invite
@@ -49,6 +49,9 @@ auto_derived!(
             /// Enum of server flags
             #[serde(skip_serializing_if = "Option::is_none")]
             server_flags: Option<i32>,
+            /// Details shown alongside the server's flags
+            #[serde(skip_serializing_if = "Option::is_none")]
+            server_recognition: Option<ServerRecognition>,
             /// Id of server channel
             channel_id: String,
             /// Name of server channel
@@ -63,6 +66,15 @@ auto_derived!(
             user_avatar: Option<File>,
             /// Number of members in this server
             member_count: i64,
+            /// Number of members currently online
+            #[serde(default)]
+            online_count: i64,
+            /// Whether joining requires accepting the server rules
+            #[serde(skip_serializing_if = "crate::if_false", default)]
+            requires_rules_acceptance: bool,
+            /// Questions which must be answered when joining
+            #[serde(skip_serializing_if = "Vec::is_empty", default)]
+            screening_questions: Vec<String>,
         },
         /// Group channel invite
         Group {
@@ -83,6 +95,17 @@ auto_derived!(
         },
     }
 
+    /// Information submitted when joining an invite
+    #[derive(Default)]
+    pub struct DataJoinInvite {
+        /// Answers to the server's screening questions, in order
+        #[serde(default)]
+        pub screening_answers: Vec<String>,
+        /// Whether the user accepts the server's rules
+        #[serde(default)]
+        pub accept_rules: bool,
+    }
+
     /// Invite join response
     #[serde(tag = "type")]
     #[allow(clippy::large_enum_variant)]
@@ -1,3 +1,5 @@
+use iso8601_timestamp::Timestamp;
+
 auto_derived!(
     /// Channel Unread
     pub struct ChannelUnread {
@@ -14,6 +16,19 @@ auto_derived!(
             serde(skip_serializing_if = "Vec::is_empty", default)
         )]
         pub mentions: Vec<String>,
+        /// Number of messages sent since the last read message, if known
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub unread_count: Option<u64>,
+    }
+
+    /// Most recent message a user has seen in a direct message or group
+    pub struct ReadReceipt {
+        /// Id of the user
+        pub user: String,
+        /// Id of the last message seen by the user
+        pub message_id: String,
+        /// Time at which the message was seen
+        pub seen_at: Timestamp,
     }
 
     /// Composite primary key consisting of channel and user id
@@ -1,6 +1,9 @@
 #[cfg(feature = "validator")]
 use validator::Validate;
 
+#[cfg(feature = "rocket")]
+use rocket::FromForm;
+
 use super::File;
 
 auto_derived_partial!(
@@ -92,6 +95,15 @@ auto_derived!(
         #[validate(length(min = 1, max = 128))]
         pub avatar: Option<String>,
     }
+
+    /// Options for executing a webhook
+    #[cfg_attr(feature = "rocket", derive(FromForm))]
+    pub struct OptionsWebhookExecute {
+        /// Whether to wait for the message to be sent and return it
+        ///
+        /// Defaults to true. If false, the message is sent in the background and nothing is returned.
+        pub wait: Option<bool>,
+    }
 );
 
 impl From<Webhook> for MessageWebhook {
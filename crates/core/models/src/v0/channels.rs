@@ -1,4 +1,4 @@
-use super::File;
+use super::{AttachmentClass, File};
 
 use guilderia_permissions::{Override, OverrideField};
 use std::collections::{HashMap, HashSet};
@@ -107,6 +107,30 @@ auto_derived!(
                 serde(skip_serializing_if = "crate::if_false", default)
             )]
             nsfw: bool,
+            /// Whether this channel's permissions are synced with its category
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "crate::if_false", default)
+            )]
+            synced: bool,
+            /// Kinds of attachments allowed in this channel, all are allowed if unset
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "Option::is_none", default)
+            )]
+            allowed_attachments: Option<Vec<AttachmentClass>>,
+            /// Seconds members must wait between messages, unless they can manage messages
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "Option::is_none", default)
+            )]
+            rate_limit_per_user: Option<u32>,
+            /// Seconds messages are kept for before they are deleted
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "Option::is_none", default)
+            )]
+            message_retention_seconds: Option<u32>,
         },
         /// Voice channel belonging to a server
         VoiceChannel {
@@ -144,6 +168,18 @@ auto_derived!(
                 serde(skip_serializing_if = "crate::if_false", default)
             )]
             nsfw: bool,
+            /// Whether this channel's permissions are synced with its category
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "crate::if_false", default)
+            )]
+            synced: bool,
+            /// Text channel in which calls starting and ending are announced
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "Option::is_none", default)
+            )]
+            activity_channel: Option<String>,
         },
     }
 
@@ -170,6 +206,16 @@ auto_derived!(
         pub default_permissions: Option<OverrideField>,
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         pub last_message_id: Option<String>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub synced: Option<bool>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub allowed_attachments: Option<Vec<AttachmentClass>>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub rate_limit_per_user: Option<u32>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub message_retention_seconds: Option<u32>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub activity_channel: Option<String>,
     }
 
     /// Optional fields on channel object
@@ -177,6 +223,10 @@ auto_derived!(
         Description,
         Icon,
         DefaultPermissions,
+        AllowedAttachments,
+        RateLimitPerUser,
+        MessageRetentionSeconds,
+        ActivityChannel,
     }
 
     /// New webhook information
@@ -205,6 +255,32 @@ auto_derived!(
         /// Whether this channel is archived
         pub archived: Option<bool>,
 
+        /// Kinds of attachments allowed in this channel
+        ///
+        /// Only applies to text channels, remove the field to allow all attachments again.
+        #[cfg_attr(feature = "validator", validate(length(min = 1)))]
+        pub allowed_attachments: Option<Vec<AttachmentClass>>,
+
+        /// Seconds members must wait between messages
+        ///
+        /// Only applies to text channels, remove the field to turn slowmode off.
+        #[cfg_attr(feature = "validator", validate(range(min = 1, max = 21600)))]
+        pub rate_limit_per_user: Option<u32>,
+
+        /// Seconds messages are kept for before they are deleted, between a minute and a year
+        ///
+        /// Only applies to text channels and to messages sent from then on,
+        /// remove the field to keep messages indefinitely again.
+        #[cfg_attr(feature = "validator", validate(range(min = 60, max = 31536000)))]
+        pub message_retention_seconds: Option<u32>,
+
+        /// Text channel in which calls starting and ending are announced
+        ///
+        /// Only applies to voice channels and must be a text channel in the same
+        /// server, remove the field to stop announcing calls.
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 128)))]
+        pub activity_channel: Option<String>,
+
         /// Fields to remove from channel
         #[cfg_attr(feature = "serde", serde(default))]
         pub remove: Option<Vec<FieldsChannel>>,
@@ -288,11 +364,35 @@ auto_derived!(
         pub leave_silently: Option<bool>,
     }
 
+    /// Options for searching channels in a server
+    #[cfg_attr(feature = "rocket", derive(FromForm))]
+    pub struct OptionsSearchChannels {
+        /// Start of the channel name to search for
+        pub query: String,
+        /// Maximum number of channels to return (1-25, default 10)
+        pub limit: Option<i64>,
+    }
+
     /// Voice server token response
     pub struct LegacyCreateVoiceUserResponse {
         /// Token for authenticating with the voice server
         token: String,
     }
+
+    /// Participant joining or leaving a call, as reported by the voice server
+    #[serde(tag = "type")]
+    pub enum DataCallActivity {
+        /// User joined the call
+        Join {
+            /// Id of the user
+            user: String,
+        },
+        /// User left the call
+        Leave {
+            /// Id of the user
+            user: String,
+        },
+    }
 );
 
 impl Channel {
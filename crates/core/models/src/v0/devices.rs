@@ -0,0 +1,25 @@
+use iso8601_timestamp::Timestamp;
+
+auto_derived!(
+    /// Device a user has signed in from
+    pub struct Device {
+        /// Id of the session on this device
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Name of the session
+        pub name: String,
+        /// Whether this device has been approved
+        pub trusted: bool,
+        /// Time at which this device was first seen
+        pub created_at: Timestamp,
+        /// Time at which this device was approved
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub approved_at: Option<Timestamp>,
+    }
+
+    /// Login approval preference
+    pub struct DeviceApprovalSettings {
+        /// Whether sessions on new devices must be approved before they can be used
+        pub enabled: bool,
+    }
+);
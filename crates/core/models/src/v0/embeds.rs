@@ -1,3 +1,5 @@
+use iso8601_timestamp::Timestamp;
+
 use super::File;
 
 auto_derived!(
@@ -49,6 +51,24 @@ auto_derived!(
         Track,
     }
 
+    /// Type of YouTube video
+    #[derive(Default)]
+    pub enum YouTubeType {
+        #[default]
+        Video,
+        Short,
+        Live,
+    }
+
+    /// Playlist context of a YouTube video
+    pub struct YouTubePlaylist {
+        /// Playlist id
+        pub id: String,
+        /// Position of the video in the playlist
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub index: Option<usize>,
+    }
+
     /// Information about special remote content
     #[serde(tag = "type")]
     pub enum Special {
@@ -62,8 +82,18 @@ auto_derived!(
         YouTube {
             id: String,
 
+            /// Start time as given in the link
             #[serde(skip_serializing_if = "Option::is_none")]
             timestamp: Option<String>,
+            /// Start time in seconds
+            #[serde(skip_serializing_if = "Option::is_none")]
+            start: Option<usize>,
+            /// Playlist the video was linked from
+            #[serde(skip_serializing_if = "Option::is_none")]
+            playlist: Option<YouTubePlaylist>,
+            /// Kind of video
+            #[serde(default)]
+            content_type: YouTubeType,
         },
         /// Lightspeed.tv stream
         Lightspeed {
@@ -92,6 +122,54 @@ auto_derived!(
         },
         /// Streamable Video
         Streamable { id: String },
+        /// Post on X (formerly Twitter)
+        Twitter { id: String, post: Post },
+        /// Post on Bluesky
+        Bluesky { id: String, post: Post },
+    }
+
+    /// Author of a social media post
+    pub struct PostAuthor {
+        /// Display name of the author
+        pub name: String,
+        /// Handle of the author
+        pub handle: String,
+        /// URL to the author's avatar
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub avatar: Option<String>,
+    }
+
+    /// Engagement statistics of a social media post
+    pub struct PostStats {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub replies: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub reposts: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub quotes: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub likes: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub views: Option<usize>,
+    }
+
+    /// Social media post
+    pub struct Post {
+        /// Author of the post
+        pub author: PostAuthor,
+        /// Text content of the post
+        pub text: String,
+        /// When the post was created
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub created_at: Option<Timestamp>,
+        /// Images attached to the post
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pub images: Vec<Image>,
+        /// Video attached to the post
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub video: Option<Video>,
+        /// Engagement statistics
+        pub stats: PostStats,
     }
 
     /// Website metadata
@@ -150,6 +228,60 @@ auto_derived!(
         /// CSS Colour
         #[serde(skip_serializing_if = "Option::is_none")]
         pub colour: Option<String>,
+        /// Author of text embed
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub author: Option<EmbedAuthor>,
+        /// Footer of text embed
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub footer: Option<EmbedFooter>,
+        /// Time associated with the content of text embed
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub timestamp: Option<Timestamp>,
+        /// Fields of text embed
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pub fields: Vec<EmbedField>,
+    }
+
+    /// Author of a text embed
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct EmbedAuthor {
+        /// Name of the author
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 256)))]
+        pub name: String,
+        /// URL to the author's icon
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 256)))]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub icon_url: Option<String>,
+        /// URL for the author's name
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 256)))]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub url: Option<String>,
+    }
+
+    /// Footer of a text embed
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct EmbedFooter {
+        /// Footer text
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 2048)))]
+        pub text: String,
+        /// URL to the footer icon
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 256)))]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub icon_url: Option<String>,
+    }
+
+    /// Field of a text embed
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct EmbedField {
+        /// Name of the field
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 256)))]
+        pub name: String,
+        /// Value of the field
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 1024)))]
+        pub value: String,
+        /// Whether this field may be displayed next to other fields
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub inline: bool,
     }
 
     /// Embed
@@ -165,35 +297,47 @@ auto_derived!(
     }
 );
 
+/// Truncate a string to at most `max` bytes without splitting a character
+fn truncate_string(s: &mut String, max: usize) {
+    if s.len() > max {
+        let mut end = max;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        s.truncate(end);
+    }
+}
+
 impl WebsiteMetadata {
     /// Truncate strings in metadata
     pub fn truncate(&mut self) {
         if let Some(s) = self.url.as_mut() {
-            s.truncate(256);
+            truncate_string(s, 256);
         }
 
         if let Some(s) = self.original_url.as_mut() {
-            s.truncate(256);
+            truncate_string(s, 256);
         }
 
         if let Some(s) = self.title.as_mut() {
-            s.truncate(100);
+            truncate_string(s, 100);
         }
 
         if let Some(s) = self.description.as_mut() {
-            s.truncate(1000);
+            truncate_string(s, 1000);
         }
 
         if let Some(s) = self.site_name.as_mut() {
-            s.truncate(32);
+            truncate_string(s, 32);
         }
 
         if let Some(s) = self.icon_url.as_mut() {
-            s.truncate(256);
+            truncate_string(s, 256);
         }
 
         if let Some(s) = self.colour.as_mut() {
-            s.truncate(32);
+            truncate_string(s, 32);
         }
     }
 
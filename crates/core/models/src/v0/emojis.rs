@@ -33,6 +33,12 @@ auto_derived!(
             serde(skip_serializing_if = "crate::if_false", default)
         )]
         pub nsfw: bool,
+        /// Whether the emoji is waiting for moderator approval
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub pending: bool,
     }
 
     /// Parent Id of the emoji
@@ -54,4 +60,56 @@ auto_derived!(
         #[serde(default)]
         pub nsfw: bool,
     }
+
+    /// Import emoji from a pack archive
+    pub struct DataImportEmojiPack {
+        /// Autumn upload id of a zip archive containing images and a `manifest.json`
+        pub file: String,
+    }
+
+    /// Manifest describing the contents of an emoji pack archive
+    pub struct EmojiPackManifest {
+        /// Emoji to create from the pack
+        pub emojis: Vec<EmojiPackEntry>,
+    }
+
+    /// Emoji within an emoji pack archive
+    pub struct EmojiPackEntry {
+        /// Emoji name
+        pub name: String,
+        /// Path of the image within the archive
+        pub file: String,
+        /// Whether the emoji is mature
+        #[serde(default)]
+        pub nsfw: bool,
+    }
+
+    /// Emoji which could not be imported from a pack
+    pub struct EmojiImportFailure {
+        /// Emoji name from the manifest
+        pub name: String,
+        /// Reason the emoji could not be imported
+        pub error: String,
+    }
+
+    /// Result of importing an emoji pack
+    pub struct EmojiImportResult {
+        /// Emoji created from the pack
+        pub emojis: Vec<Emoji>,
+        /// Emoji which could not be imported
+        pub failed: Vec<EmojiImportFailure>,
+        /// Id of the job importing the pack in the background
+        ///
+        /// Large packs are imported in the background, progress is
+        /// reported through `ServerJobProgress` events and failures
+        /// through an `EmojiImportComplete` event once it finishes.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub job: Option<String>,
+    }
+
+    /// Emoji review decision
+    pub struct DataReviewEmoji {
+        /// Whether to approve the emoji, otherwise it is removed
+        pub approve: bool,
+    }
 );
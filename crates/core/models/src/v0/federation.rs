@@ -0,0 +1,40 @@
+use super::{File, InviteResponse};
+
+auto_derived!(
+    /// Public profile card shared with other instances
+    pub struct FederatedUser {
+        /// Unique Id
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Instance this user belongs to
+        pub instance: String,
+        /// Username
+        pub username: String,
+        /// Discriminator
+        pub discriminator: String,
+        /// Display name
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub display_name: Option<String>,
+        /// Avatar attachment
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub avatar: Option<File>,
+        /// Bitfield of user badges
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_zero_u32", default)
+        )]
+        pub badges: u32,
+        /// Whether this user is a bot
+        #[cfg_attr(feature = "serde", serde(default))]
+        pub bot: bool,
+    }
+
+    /// Invite preview resolved from another instance
+    pub struct FederatedInvite {
+        /// Instance this invite belongs to
+        pub instance: String,
+        /// Invite preview
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        pub invite: InviteResponse,
+    }
+);
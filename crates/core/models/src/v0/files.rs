@@ -1,3 +1,5 @@
+use iso8601_timestamp::Timestamp;
+
 auto_derived!(
     /// File
     pub struct File {
@@ -8,6 +10,9 @@ auto_derived!(
         pub tag: String,
         /// Original filename
         pub filename: String,
+        /// Alternative text describing this file
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub alt_text: Option<String>,
         /// Parsed metadata of this file
         pub metadata: Metadata,
         /// Raw content type of this file
@@ -21,6 +26,21 @@ auto_derived!(
         /// Whether this file was reported
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         pub reported: Option<bool>,
+        /// Result of the most recent virus scan
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "Option::is_none", default)
+        )]
+        pub scan: Option<FileScan>,
+        /// Whether this file is held for review after failing a virus scan
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub quarantined: Option<bool>,
+        /// Whether this file was recorded as a voice message
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "Option::is_none", default)
+        )]
+        pub voice_note: Option<bool>,
 
         // TODO: migrate this mess to having:
         // - author_id
@@ -37,6 +57,40 @@ auto_derived!(
         pub object_id: Option<String>,
     }
 
+    /// Kind of file, used to restrict which attachments a channel accepts
+    pub enum AttachmentClass {
+        /// Images
+        Image,
+        /// Videos
+        Video,
+        /// Audio
+        Audio,
+        /// Plain text and source code
+        Text,
+        /// Archives such as zip or tar files
+        Archive,
+        /// Executables and installers
+        Executable,
+        /// Any other file
+        Other,
+    }
+
+    /// Result of a virus scan
+    pub struct FileScan {
+        /// Name of the matched signature, if the file is infected
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "Option::is_none", default)
+        )]
+        pub signature: Option<String>,
+        /// Engine version used to scan the file
+        pub engine: String,
+        /// Version of the signature database used to scan the file
+        pub signatures: u32,
+        /// When this file was scanned
+        pub scanned_at: Timestamp,
+    }
+
     /// Metadata associated with a file
     #[cfg_attr(feature = "serde", serde(tag = "type"))]
     #[derive(Default)]
@@ -45,12 +99,95 @@ auto_derived!(
         #[default]
         File,
         /// File contains textual data and should be displayed as such
-        Text,
+        Text {
+            /// Language detected from the file name
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "Option::is_none", default)
+            )]
+            language: Option<String>,
+            /// Number of lines in the file
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "Option::is_none", default)
+            )]
+            lines: Option<usize>,
+            /// First few lines of the file
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "Option::is_none", default)
+            )]
+            preview: Option<String>,
+        },
         /// File is an image with specific dimensions
-        Image { width: usize, height: usize },
+        Image {
+            width: usize,
+            height: usize,
+            /// Compact placeholder to show while the image loads
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "Option::is_none", default)
+            )]
+            blurhash: Option<String>,
+            /// Metadata removed from the image on upload
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "Vec::is_empty", default)
+            )]
+            stripped: Vec<StrippedMetadata>,
+        },
         /// File is a video with specific dimensions
-        Video { width: usize, height: usize },
+        Video {
+            width: usize,
+            height: usize,
+            /// Compact placeholder to show while the video loads
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "Option::is_none", default)
+            )]
+            blurhash: Option<String>,
+        },
         /// File is audio
-        Audio,
+        Audio {
+            /// Length of the audio (in milliseconds)
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "Option::is_none", default)
+            )]
+            duration: Option<usize>,
+            /// Peak loudness of evenly spaced slices of the audio, from 0 to 255
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "Option::is_none", default)
+            )]
+            waveform: Option<Vec<u8>>,
+        },
+        /// File is an archive containing other files
+        Archive {
+            /// Listing of files in the archive, may be truncated
+            files: Vec<ArchiveEntry>,
+            /// Total number of files in the archive
+            count: usize,
+            /// Total size of the files once decompressed (in bytes)
+            size: usize,
+        },
+    }
+
+    /// File contained within an archive
+    pub struct ArchiveEntry {
+        /// Path of the file within the archive
+        pub name: String,
+        /// Size of the file once decompressed (in bytes)
+        pub size: usize,
+    }
+
+    /// Kind of metadata removed from an uploaded file
+    pub enum StrippedMetadata {
+        /// EXIF tags such as location or camera details
+        Exif,
+        /// Orientation, discarded without being applied
+        Orientation,
+        /// Embedded colour profile
+        ColorProfile,
     }
 );
@@ -0,0 +1,22 @@
+use iso8601_timestamp::Timestamp;
+
+auto_derived!(
+    /// Word or phrase on the instance's base content filter list
+    pub struct FilterWord {
+        /// Word or phrase, as it is matched
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Id of the user who added it
+        pub creator: String,
+        /// Time at which it was added
+        pub created_at: Timestamp,
+    }
+
+    /// Add words to the base content filter list
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct DataAddFilterWords {
+        /// Words or phrases to add, a trailing `*` also matches longer words
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 1000)))]
+        pub words: Vec<String>,
+    }
+);
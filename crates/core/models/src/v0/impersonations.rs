@@ -0,0 +1,57 @@
+use iso8601_timestamp::Timestamp;
+
+auto_derived!(
+    /// Read-only session an admin uses to see the platform as another user
+    pub struct Impersonation {
+        /// Unique Id
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Id of the admin impersonating the user
+        pub admin: String,
+        /// Id of the user being impersonated
+        pub user: String,
+        /// Why the user is being impersonated
+        pub reason: String,
+        /// Whether the user was told about this impersonation
+        pub user_notified: bool,
+        /// Time at which this impersonation started
+        pub created_at: Timestamp,
+        /// Time at which this impersonation ends
+        pub expires_at: Timestamp,
+        /// Most recent requests made while impersonating
+        #[cfg_attr(feature = "serde", serde(default))]
+        pub requests: Vec<ImpersonatedRequest>,
+    }
+
+    /// Request made while impersonating a user
+    pub struct ImpersonatedRequest {
+        /// HTTP method
+        pub method: String,
+        /// Requested path
+        pub path: String,
+        /// Time at which the request was made
+        pub at: Timestamp,
+    }
+
+    /// Newly started impersonation
+    pub struct ImpersonationTicket {
+        /// Impersonation
+        pub impersonation: Impersonation,
+        /// Secret token to send as `x-impersonation-token`
+        pub token: String,
+    }
+
+    /// Start impersonating a user
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct DataCreateImpersonation {
+        /// Id of the user to impersonate
+        pub user: String,
+        /// Why the user is being impersonated, such as a support ticket
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 512)))]
+        pub reason: String,
+        /// How long the impersonation should last, capped by the instance
+        pub duration_minutes: Option<u64>,
+        /// Whether to notify the user, if the instance leaves this to admins
+        pub notify_user: Option<bool>,
+    }
+);
@@ -0,0 +1,51 @@
+#[cfg(feature = "validator")]
+use validator::Validate;
+
+use super::CommandContext;
+
+auto_derived!(
+    /// Interaction between a user and a bot
+    pub struct Interaction {
+        /// Interaction Id
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+
+        /// Id of the bot being interacted with
+        pub bot: String,
+        /// Id of the user who started the interaction
+        pub user: String,
+        /// Id of the channel the interaction took place in
+        pub channel: String,
+        /// Context the command was invoked in
+        pub context: CommandContext,
+
+        /// Name of the command invoked
+        pub command: String,
+        /// Options passed to the command
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub options: Option<String>,
+
+        /// Whether the bot has deferred its response
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub deferred: bool,
+        /// Id of the message sent as the original response
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub response: Option<String>,
+    }
+
+    /// Information about a bot command to invoke
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataCreateInteraction {
+        /// Id of the bot to interact with
+        pub bot: String,
+        /// Name of the command to invoke
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 32)))]
+        pub command: String,
+        /// Options to pass to the command
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 2000)))]
+        pub options: Option<String>,
+    }
+);
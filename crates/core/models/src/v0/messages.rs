@@ -1,4 +1,4 @@
-use std::time::SystemTime;
+use std::{collections::HashMap, time::SystemTime};
 
 use indexmap::{IndexMap, IndexSet};
 use guilderia_config::config;
@@ -11,7 +11,10 @@ use rocket::{FromForm, FromFormField};
 
 use iso8601_timestamp::Timestamp;
 
-use super::{Channel, Embed, File, Member, MessageWebhook, User, Webhook, RE_COLOUR};
+use super::{
+    Channel, Embed, EmbedAuthor, EmbedField, EmbedFooter, File, Member, MessageWebhook, User,
+    Webhook, RE_COLOUR,
+};
 
 auto_derived_partial!(
     /// Message
@@ -62,15 +65,42 @@ auto_derived_partial!(
         /// Hashmap of emoji IDs to array of user IDs
         #[serde(skip_serializing_if = "IndexMap::is_empty", default)]
         pub reactions: IndexMap<String, IndexSet<String>>,
+        /// Hashmap of emoji IDs to array of user IDs who sent a burst reaction
+        #[serde(skip_serializing_if = "IndexMap::is_empty", default)]
+        pub burst_reactions: IndexMap<String, IndexSet<String>>,
         /// Information about how this message should be interacted with
         #[serde(skip_serializing_if = "Interactions::is_default", default)]
         pub interactions: Interactions,
         /// Name and / or avatar overrides for this message
         #[serde(skip_serializing_if = "Option::is_none")]
         pub masquerade: Option<Masquerade>,
+        /// Verified origin of a bridged message
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub provenance: Option<MessageProvenance>,
+        /// Id of the thread this message was sent in
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub thread: Option<String>,
         /// Whether or not the message in pinned
         #[serde(skip_serializing_if = "crate::if_option_false")]
         pub pinned: Option<bool>,
+        /// Whether clients should read this message aloud
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub tts: bool,
+        /// Effect played when this message is received
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub effect: Option<MessageEffect>,
+        /// Extensions to the message format used by this message
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub extensions: Option<Vec<MessageExtension>>,
+        /// Content rendered as plain text for clients without support for its extensions
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub fallback: Option<String>,
+        /// Time at which this message will be deleted, per the channel's retention period
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub expires_at: Option<Timestamp>,
 
         /// Bitfield of message flags
         ///
@@ -103,6 +133,36 @@ auto_derived!(
         },
     }
 
+    /// Message search results across many channels
+    pub struct GlobalSearchResponse {
+        /// List of messages
+        pub messages: Vec<Message>,
+        /// List of users
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub users: Vec<User>,
+        /// List of members
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub members: Vec<Member>,
+        /// Breakdown of the results by where they were found
+        pub facets: SearchFacets,
+    }
+
+    /// Breakdown of search results
+    pub struct SearchFacets {
+        /// Number of results per server
+        pub servers: Vec<SearchFacet>,
+        /// Number of results per channel
+        pub channels: Vec<SearchFacet>,
+    }
+
+    /// Number of search results in a server or channel
+    pub struct SearchFacet {
+        /// Server or channel id
+        pub id: String,
+        /// Number of results
+        pub count: usize,
+    }
+
     /// System Event
     #[serde(tag = "type")]
     pub enum SystemMessage {
@@ -113,9 +173,19 @@ auto_derived!(
         #[serde(rename = "user_remove")]
         UserRemove { id: String, by: String },
         #[serde(rename = "user_joined")]
-        UserJoined { id: String },
+        UserJoined {
+            id: String,
+            /// Text rendered from one of the server's templates
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            content: Option<String>,
+        },
         #[serde(rename = "user_left")]
-        UserLeft { id: String },
+        UserLeft {
+            id: String,
+            /// Text rendered from one of the server's templates
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            content: Option<String>,
+        },
         #[serde(rename = "user_kicked")]
         UserKicked { id: String },
         #[serde(rename = "user_banned")]
@@ -132,6 +202,35 @@ auto_derived!(
         MessagePinned { id: String, by: String },
         #[serde(rename = "message_unpinned")]
         MessageUnpinned { id: String, by: String },
+        #[serde(rename = "message_highlighted")]
+        MessageHighlighted {
+            id: String,
+            channel: String,
+            author: String,
+            count: u32,
+        },
+        #[serde(rename = "call_started")]
+        CallStarted { channel: String, by: String },
+        #[serde(rename = "call_ended")]
+        CallEnded { channel: String, duration: u64 },
+    }
+
+    /// Origin of a message bridged from an external network
+    ///
+    /// The signature is a base64 encoded Ed25519 signature over the origin and
+    /// the message content joined by a newline, made using one of the bridge
+    /// keys registered on the sending bot.
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct MessageProvenance {
+        /// URI of the message on the external network
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 256)))]
+        pub origin: String,
+        /// Id of the bridge key used to sign this message
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 64)))]
+        pub key: String,
+        /// Detached signature
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 128)))]
+        pub signature: String,
     }
 
     /// Name and / or avatar override information
@@ -166,6 +265,30 @@ auto_derived!(
         pub restrict_reactions: bool,
     }
 
+    /// Effect played when a message is received
+    pub enum MessageEffect {
+        Confetti,
+        Fireworks,
+        Hearts,
+        Shake,
+    }
+
+    /// Extension to the message format
+    ///
+    /// Clients list the extensions they support when connecting, any others
+    /// are replaced with a plain text fallback.
+    #[derive(Hash, Copy)]
+    pub enum MessageExtension {
+        /// Content hidden between `||` markers
+        Spoiler,
+        /// Time rendered in the reader's timezone, written as `<t:unix time>`
+        Timestamp,
+        /// Line of small text starting with `-# `
+        Subtext,
+        /// Effect played when the message is received
+        Effect,
+    }
+
     /// Appended Information
     pub struct AppendMessage {
         /// Additional embeds to include in this message
@@ -209,6 +332,9 @@ auto_derived!(
         pub message: Message,
         /// The channel object itself, for clients to process
         pub channel: Channel,
+        /// Whether clients should read this notification aloud
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub tts: bool,
     }
 
     /// Representation of a text embed before it is sent.
@@ -229,6 +355,14 @@ auto_derived!(
             validate(length(min = 1, max = 128), regex = "RE_COLOUR")
         )]
         pub colour: Option<String>,
+        #[cfg_attr(feature = "validator", validate)]
+        pub author: Option<EmbedAuthor>,
+        #[cfg_attr(feature = "validator", validate)]
+        pub footer: Option<EmbedFooter>,
+        pub timestamp: Option<Timestamp>,
+        #[cfg_attr(feature = "validator", validate(length(max = 25)))]
+        #[cfg_attr(feature = "validator", validate)]
+        pub fields: Option<Vec<EmbedField>>,
     }
 
     /// What this message should reply to and how
@@ -264,6 +398,9 @@ auto_derived!(
         /// Text embed content contributes to the content length cap
         #[cfg_attr(feature = "validator", validate)]
         pub embeds: Option<Vec<SendableEmbed>>,
+        /// Alternative text for attachments, keyed by attachment id
+        #[cfg_attr(feature = "validator", validate(custom = "validate_alt_text"))]
+        pub alt_text: Option<HashMap<String, String>>,
         /// Masquerade to apply to this message
         #[cfg_attr(feature = "validator", validate)]
         pub masquerade: Option<Masquerade>,
@@ -274,6 +411,24 @@ auto_derived!(
         ///
         /// https://docs.rs/revolt-models/latest/revolt_models/v0/enum.MessageFlags.html
         pub flags: Option<u32>,
+        /// Whether clients should read this message aloud
+        ///
+        /// Requires the `SendTTSMessages` permission.
+        pub tts: Option<bool>,
+        /// Confirmation token returned when mentioning everyone in a large server
+        ///
+        /// Resend the message with this token to deliver it.
+        pub mention_confirmation: Option<String>,
+        /// Signed origin of a bridged message
+        ///
+        /// Only bots may attach provenance, signed using one of their bridge keys.
+        #[cfg_attr(feature = "validator", validate)]
+        pub provenance: Option<MessageProvenance>,
+        /// Id of the thread to send this message in
+        #[cfg_attr(feature = "validator", validate(length(min = 26, max = 26)))]
+        pub thread: Option<String>,
+        /// Effect to play when this message is received
+        pub effect: Option<MessageEffect>,
     }
 
     /// Options for querying messages
@@ -302,6 +457,11 @@ auto_derived!(
         pub nearby: Option<String>,
         /// Whether to include user (and member, if server channel) objects
         pub include_users: Option<bool>,
+        /// Id of the thread to fetch messages from
+        ///
+        /// Messages sent in threads are left out unless this is specified.
+        #[cfg_attr(feature = "validator", validate(length(min = 26, max = 26)))]
+        pub thread: Option<String>,
     }
 
     /// Options for searching for messages
@@ -333,6 +493,37 @@ auto_derived!(
         pub include_users: Option<bool>,
     }
 
+    /// Options for searching for messages across all channels
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataGlobalMessageSearch {
+        /// Full-text search query
+        ///
+        /// See [MongoDB documentation](https://docs.mongodb.com/manual/text-search/#-text-operator) for more information.
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 64)))]
+        pub query: String,
+        /// Only search within this server
+        pub server: Option<String>,
+        /// Only search within this channel
+        pub channel: Option<String>,
+
+        /// Maximum number of messages to fetch
+        #[cfg_attr(feature = "validator", validate(range(min = 1, max = 100)))]
+        pub limit: Option<i64>,
+        /// Message id before which messages should be fetched
+        #[cfg_attr(feature = "validator", validate(length(min = 26, max = 26)))]
+        pub before: Option<String>,
+        /// Message id after which messages should be fetched
+        #[cfg_attr(feature = "validator", validate(length(min = 26, max = 26)))]
+        pub after: Option<String>,
+        /// Message sort direction
+        ///
+        /// By default, it will be sorted by relevance.
+        #[cfg_attr(feature = "serde", serde(default = "MessageSort::default"))]
+        pub sort: MessageSort,
+        /// Whether to include user and member objects
+        pub include_users: Option<bool>,
+    }
+
     /// Changes to make to message
     #[cfg_attr(feature = "validator", derive(Validate))]
     pub struct DataEditMessage {
@@ -342,6 +533,15 @@ auto_derived!(
         /// Embeds to include in the message
         #[cfg_attr(feature = "validator", validate(length(min = 0, max = 10)))]
         pub embeds: Option<Vec<SendableEmbed>>,
+        /// Whether to stop generating embeds for links in this message
+        ///
+        /// May be changed by the author or anyone with `ManageMessages`.
+        pub suppress_embeds: Option<bool>,
+        /// New alternative text for attachments, keyed by attachment id
+        ///
+        /// Empty text removes the existing alternative text.
+        #[cfg_attr(feature = "validator", validate(custom = "validate_alt_text"))]
+        pub alt_text: Option<HashMap<String, String>>,
     }
 
     /// Options for bulk deleting messages
@@ -355,6 +555,23 @@ auto_derived!(
         pub ids: Vec<String>,
     }
 
+    /// Messages to move to another channel
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataMoveMessages {
+        /// Message IDs
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 100)))]
+        pub ids: Vec<String>,
+        /// Id of the channel to move the messages to
+        pub channel: String,
+    }
+
+    /// Options for adding a reaction
+    #[cfg_attr(feature = "rocket", derive(FromForm))]
+    pub struct OptionsReact {
+        /// Send a burst reaction, limited to a number per day
+        pub burst: Option<bool>,
+    }
+
     /// Options for removing reaction
     #[cfg_attr(feature = "rocket", derive(FromForm))]
     pub struct OptionsUnreact {
@@ -374,14 +591,35 @@ auto_derived!(
         /// Message will mention all users who are online and can see the channel.
         /// This cannot be true if MentionsEveryone is true
         MentionsOnline = 3,
+        /// Message will not have embeds generated for links in its content
+        SuppressEmbeds = 4,
     }
 
     /// Optional fields on message
     pub enum FieldsMessage {
         Pinned,
+        Provenance,
+        Extensions,
+        Fallback,
     }
 );
 
+/// Maximum length of alternative text for an attachment
+pub const MAX_ALT_TEXT_LENGTH: usize = 1024;
+
+/// Ensure alternative text for attachments is not too long
+#[cfg(feature = "validator")]
+fn validate_alt_text(alt_text: &HashMap<String, String>) -> Result<(), validator::ValidationError> {
+    if alt_text
+        .values()
+        .any(|text| text.chars().count() > MAX_ALT_TEXT_LENGTH)
+    {
+        Err(validator::ValidationError::new("alt_text"))
+    } else {
+        Ok(())
+    }
+}
+
 /// Message Author Abstraction
 pub enum MessageAuthor<'a> {
     User(&'a User),
@@ -431,8 +669,12 @@ impl From<SystemMessage> for String {
             SystemMessage::Text { content } => content,
             SystemMessage::UserAdded { .. } => "User added to the channel.".to_string(),
             SystemMessage::UserRemove { .. } => "User removed from the channel.".to_string(),
-            SystemMessage::UserJoined { .. } => "User joined the channel.".to_string(),
-            SystemMessage::UserLeft { .. } => "User left the channel.".to_string(),
+            SystemMessage::UserJoined { content, .. } => {
+                content.unwrap_or_else(|| "User joined the channel.".to_string())
+            }
+            SystemMessage::UserLeft { content, .. } => {
+                content.unwrap_or_else(|| "User left the channel.".to_string())
+            }
             SystemMessage::UserKicked { .. } => "User kicked from the channel.".to_string(),
             SystemMessage::UserBanned { .. } => "User banned from the channel.".to_string(),
             SystemMessage::ChannelRenamed { .. } => "Channel renamed.".to_string(),
@@ -445,6 +687,9 @@ impl From<SystemMessage> for String {
             }
             SystemMessage::MessagePinned { .. } => "Message pinned.".to_string(),
             SystemMessage::MessageUnpinned { .. } => "Message unpinned.".to_string(),
+            SystemMessage::MessageHighlighted { .. } => "Message highlighted.".to_string(),
+            SystemMessage::CallStarted { .. } => "Call started.".to_string(),
+            SystemMessage::CallEnded { .. } => "Call ended.".to_string(),
         }
     }
 }
@@ -472,7 +717,7 @@ impl PushNotification {
 
         let body = if let Some(ref sys) = msg.system {
             sys.clone().into()
-        } else if let Some(ref text) = msg.content {
+        } else if let Some(text) = msg.fallback.as_ref().or(msg.content.as_ref()) {
             text.clone()
         } else if let Some(text) = msg.embeds.as_ref().and_then(|embeds| match embeds.first() {
             Some(Embed::Image(_)) => Some("Sent an image".to_string()),
@@ -508,6 +753,7 @@ impl PushNotification {
             tag: channel.id().to_string(),
             timestamp,
             url: format!("{}/channel/{}/{}", config.hosts.app, channel.id(), msg.id),
+            tts: msg.tts,
             message: msg,
             channel,
         }
@@ -1,33 +1,65 @@
+mod account_recovery;
+mod api_tokens;
+mod audit_log;
+mod bookmarks;
 mod bots;
 mod channel_invites;
 mod channel_unreads;
 mod channel_webhooks;
 mod channels;
+mod devices;
 mod embeds;
 mod emojis;
+mod federation;
 mod files;
+mod filter_words;
+mod impersonations;
+mod interactions;
 mod messages;
+mod pending_actions;
 mod policy_changes;
+mod purge_schedules;
+mod push_subscriptions;
 mod safety_reports;
+mod server_applications;
 mod server_bans;
 mod server_members;
 mod servers;
+mod sync;
+mod task_queues;
+mod threads;
 mod user_settings;
 mod users;
 
+pub use account_recovery::*;
+pub use api_tokens::*;
+pub use audit_log::*;
+pub use bookmarks::*;
 pub use bots::*;
 pub use channel_invites::*;
 pub use channel_unreads::*;
 pub use channel_webhooks::*;
 pub use channels::*;
+pub use devices::*;
 pub use embeds::*;
 pub use emojis::*;
+pub use federation::*;
 pub use files::*;
+pub use filter_words::*;
+pub use impersonations::*;
+pub use interactions::*;
 pub use messages::*;
+pub use pending_actions::*;
 pub use policy_changes::*;
+pub use purge_schedules::*;
+pub use push_subscriptions::*;
 pub use safety_reports::*;
+pub use server_applications::*;
 pub use server_bans::*;
 pub use server_members::*;
 pub use servers::*;
+pub use sync::*;
+pub use task_queues::*;
+pub use threads::*;
 pub use user_settings::*;
 pub use users::*;
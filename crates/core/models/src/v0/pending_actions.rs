@@ -0,0 +1,47 @@
+auto_derived!(
+    /// Destructive action waiting for a second administrator to approve it
+    pub struct PendingAction {
+        /// Unique Id
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Id of the server the action applies to
+        pub server: String,
+        /// Id of the user who requested the action
+        pub initiator: String,
+        /// Action to carry out once approved
+        pub action: PendingActionKind,
+    }
+
+    /// Destructive action which may require approval
+    #[cfg_attr(feature = "serde", serde(tag = "type"))]
+    pub enum PendingActionKind {
+        /// Delete the server
+        DeleteServer,
+        /// Ban many users at once
+        BanMembers {
+            /// Ids of the users to ban
+            ids: Vec<String>,
+            /// Reason given for the bans
+            #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+            reason: Option<String>,
+        },
+        /// Delete many messages in a channel at once
+        PurgeMessages {
+            /// Id of the channel
+            channel: String,
+            /// Ids of the messages to delete
+            ids: Vec<String>,
+        },
+        /// Purge a channel on a recurring schedule
+        SchedulePurge {
+            /// Id of the channel
+            channel: String,
+            /// Hour of the day (UTC) at which to purge
+            hour: u8,
+            /// Minute of the hour at which to purge
+            minute: u8,
+            /// Number of days between purges
+            interval_days: u8,
+        },
+    }
+);
@@ -0,0 +1,42 @@
+use iso8601_timestamp::Timestamp;
+
+auto_derived!(
+    /// Recurring purge of a channel's messages
+    pub struct PurgeSchedule {
+        /// Id of the channel to purge
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Id of the server the channel belongs to
+        pub server: String,
+        /// Id of the user who scheduled the purge
+        pub creator: String,
+        /// Hour of the day (UTC) at which to purge
+        pub hour: u8,
+        /// Minute of the hour at which to purge
+        pub minute: u8,
+        /// Number of days between purges
+        pub interval_days: u8,
+        /// Time at which the next purge is due
+        pub next_run: Timestamp,
+        /// Time at which the channel was last purged
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub last_run: Option<Timestamp>,
+    }
+
+    /// Schedule a recurring purge of a channel
+    ///
+    /// Every message sent before each purge is deleted, except pinned messages.
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct DataEditPurgeSchedule {
+        /// Hour of the day (UTC) at which to purge
+        #[cfg_attr(feature = "validator", validate(range(max = 23)))]
+        pub hour: u8,
+        /// Minute of the hour at which to purge
+        #[cfg_attr(feature = "validator", validate(range(max = 59)))]
+        #[cfg_attr(feature = "serde", serde(default))]
+        pub minute: u8,
+        /// Number of days between purges
+        #[cfg_attr(feature = "validator", validate(range(min = 1, max = 28)))]
+        pub interval_days: u8,
+    }
+);
@@ -0,0 +1,11 @@
+auto_derived!(
+    /// Web Push subscription attached to a session
+    pub struct PushSubscription {
+        /// Id of the session this subscription belongs to
+        pub session_id: String,
+        /// Name of the session
+        pub session_name: String,
+        /// Push service endpoint
+        pub endpoint: String,
+    }
+);
@@ -88,6 +88,12 @@ auto_derived!(
 
         /// User is not of minimum age to use the platform
         Underage,
+
+        /// User is distributing malware
+        Malware,
+
+        /// User is sharing content which sexualises children
+        ChildSafety,
     }
 
     /// The content being reported
@@ -0,0 +1,96 @@
+use iso8601_timestamp::Timestamp;
+
+auto_derived!(
+    /// Application for a server to join one of the instance's recognition programs
+    pub struct ServerApplication {
+        /// Unique Id
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Id of the server applying
+        pub server: String,
+        /// Id of the user who submitted the application
+        pub applicant: String,
+        /// Program the server is applying for
+        pub program: ServerProgram,
+        /// Why the server should be recognised
+        pub reason: String,
+        /// Link to more information, such as the community's website
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub url: Option<String>,
+        /// Time at which the application was submitted
+        pub created_at: Timestamp,
+        /// Current status of the application
+        pub status: ServerApplicationStatus,
+        /// Outcome of the review, once reviewed
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub review: Option<ServerApplicationReview>,
+    }
+
+    /// Program a server may apply for
+    pub enum ServerProgram {
+        /// Server is the verified community of a brand, creator or project
+        Verified,
+        /// Server is part of the partner program
+        Partnered,
+    }
+
+    /// Status of a server application
+    pub enum ServerApplicationStatus {
+        /// Waiting for review by the instance's admins
+        Pending,
+        /// Application was accepted and the server recognised
+        Approved,
+        /// Application was turned down
+        Rejected,
+    }
+
+    /// Outcome of reviewing a server application
+    pub struct ServerApplicationReview {
+        /// Id of the admin who reviewed the application
+        pub reviewer: String,
+        /// Note explaining the decision, shown to the applicant
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub note: Option<String>,
+        /// Time at which the application was reviewed
+        pub reviewed_at: Timestamp,
+    }
+
+    /// Apply for a server to join a recognition program
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct DataCreateServerApplication {
+        /// Program to apply for
+        pub program: ServerProgram,
+        /// Why the server should be recognised
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 2000)))]
+        pub reason: String,
+        /// Link to more information, such as the community's website
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 2048)))]
+        pub url: Option<String>,
+    }
+
+    /// Review a pending server application
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct DataReviewServerApplication {
+        /// Whether to approve the application
+        pub approve: bool,
+        /// Note explaining the decision, shown to the applicant
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 1000)))]
+        pub note: Option<String>,
+        /// Description shown alongside the server's flags, if approved
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 128)))]
+        pub description: Option<String>,
+    }
+
+    /// Set the flags of a server and the details shown alongside them
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct DataEditServerRecognition {
+        /// Bitfield of server flags, removing all flags removes the details
+        pub flags: u32,
+        /// Short description of who the server represents
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 128)))]
+        pub description: Option<String>,
+        /// Link to more information, such as the community's website
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 2048)))]
+        pub url: Option<String>,
+    }
+);
@@ -1,5 +1,9 @@
+use iso8601_timestamp::Timestamp;
+
 use super::{File, MemberCompositeKey, User};
 
+#[cfg(feature = "rocket")]
+use rocket::FromForm;
 #[cfg(feature = "validator")]
 use validator::Validate;
 
@@ -11,6 +15,12 @@ auto_derived!(
         pub id: MemberCompositeKey,
         /// Reason for ban creation
         pub reason: Option<String>,
+        /// When this ban was created
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub created_at: Option<Timestamp>,
+        /// Id of the ban subscription which applied this ban
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub subscription: Option<String>,
     }
 
     /// Information for new server ban
@@ -21,6 +31,17 @@ auto_derived!(
         pub reason: Option<String>,
     }
 
+    /// Information for banning many users at once
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataBanBulk {
+        /// Ids of users to ban
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 1000)))]
+        pub ids: Vec<String>,
+        /// Ban reason, shared by all bans
+        #[cfg_attr(feature = "validator", validate(length(min = 0, max = 1024)))]
+        pub reason: Option<String>,
+    }
+
     /// Just enough information to list a ban
     pub struct BannedUser {
         /// Id of the banned user
@@ -34,6 +55,48 @@ auto_derived!(
         pub avatar: Option<File>,
     }
 
+    /// Subscription of a server to the bans of another server
+    ///
+    /// Curated ban lists are published as servers which share their bans.
+    pub struct BanSubscription {
+        /// Unique Id
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Id of the subscribing server
+        pub server: String,
+        /// Id of the server whose bans are followed
+        pub source: String,
+        /// Id of the user who subscribed
+        pub creator: String,
+        /// What to do with bans from the source
+        pub mode: BanSyncMode,
+        /// Bans created on the source up to this time have been synced
+        pub synced_at: Timestamp,
+    }
+
+    /// What to do with bans from a subscribed source
+    pub enum BanSyncMode {
+        /// Ban the user on the subscriber as well
+        Apply,
+        /// Alert the subscriber's moderators if the user is a member
+        Flag,
+    }
+
+    /// Information for subscribing to the bans of another server
+    pub struct DataCreateBanSubscription {
+        /// Id of the server to follow, it must share its bans
+        pub source: String,
+        /// What to do with bans from the source
+        pub mode: BanSyncMode,
+    }
+
+    /// Options when unsubscribing from a ban feed
+    #[cfg_attr(feature = "rocket", derive(FromForm))]
+    pub struct OptionsDeleteBanSubscription {
+        /// Whether to lift every ban the subscription applied
+        pub rollback: Option<bool>,
+    }
+
     /// Ban list result
     pub struct BanListResult {
         /// Users objects
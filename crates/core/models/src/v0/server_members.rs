@@ -54,9 +54,43 @@ auto_derived_partial!(
             serde(skip_serializing_if = "Vec::is_empty", default)
         )]
         pub roles: Vec<String>,
+        /// Roles held by this member which will be removed at a later time
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "Vec::is_empty", default)
+        )]
+        pub role_expiries: Vec<RoleExpiry>,
         /// Timestamp this member is timed out until
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         pub timeout: Option<Timestamp>,
+
+        /// Server badges given to this member
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "Vec::is_empty", default)
+        )]
+        pub badges: Vec<String>,
+
+        /// Roles this member does not want to be pinged by
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "Vec::is_empty", default)
+        )]
+        pub role_mention_optouts: Vec<String>,
+        /// Whether to display the server tag next to this member's name
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub show_server_tag: bool,
+        /// Permissions granted to this member if it is a bot
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub bot_scope: Option<BotScope>,
+        /// Tag of the server this member is displaying
+        ///
+        /// Only present on members hydrated alongside messages.
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub server_tag: Option<String>,
     },
     "PartialMember"
 );
@@ -71,12 +105,30 @@ auto_derived!(
         pub user: String,
     }
 
+    /// Role assigned to a member until a given time
+    pub struct RoleExpiry {
+        /// Role Id
+        pub role: String,
+        /// Time at which the role is removed
+        pub expires_at: Timestamp,
+    }
+
+    /// Permissions granted to a bot when it was added to a server
+    pub struct BotScope {
+        /// Id of the role holding the granted permissions
+        pub role: String,
+        /// Permissions granted to the bot
+        pub permissions: u64,
+    }
+
     /// Optional fields on server member object
     pub enum FieldsMember {
         Nickname,
         Avatar,
         Roles,
         Timeout,
+        RoleMentionOptouts,
+        Badges,
     }
 
     /// Member removal intention
@@ -96,6 +148,28 @@ auto_derived!(
         },
     }
 
+    /// Answers given by a member to a server's screening questions
+    pub struct ScreeningResponse {
+        /// Unique member id
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: MemberCompositeKey,
+        /// Answers to each question
+        pub answers: Vec<ScreeningAnswer>,
+        /// Time at which the answers were submitted
+        pub submitted_at: Timestamp,
+        /// Time at which the member left the server
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub left_at: Option<Timestamp>,
+    }
+
+    /// Answer to a single screening question
+    pub struct ScreeningAnswer {
+        /// Question as it was asked
+        pub question: String,
+        /// Answer given by the member
+        pub answer: String,
+    }
+
     /// Options for fetching all members
     #[cfg_attr(feature = "rocket", derive(FromForm))]
     pub struct OptionsFetchAllMembers {
@@ -103,12 +177,31 @@ auto_derived!(
         pub exclude_offline: Option<bool>,
     }
 
+    /// Options for searching members
+    #[cfg_attr(feature = "rocket", derive(FromForm))]
+    pub struct OptionsSearchMembers {
+        /// Start of the nickname or username to search for
+        pub query: String,
+        /// Maximum number of members to return (1-25, default 10)
+        pub limit: Option<i64>,
+    }
+
+    /// Response with members matching a search
+    pub struct MemberSearchResponse {
+        /// List of members
+        pub members: Vec<Member>,
+        /// List of users
+        pub users: Vec<User>,
+    }
+
     /// Response with all members
     pub struct AllMemberResponse {
         /// List of members
         pub members: Vec<Member>,
         /// List of users
         pub users: Vec<User>,
+        /// Ids of hoisted roles in the order they should be displayed
+        pub hoisted_roles: Vec<String>,
     }
 
     /// New member information
@@ -123,6 +216,12 @@ auto_derived!(
         pub roles: Option<Vec<String>>,
         /// Timestamp this member is timed out until
         pub timeout: Option<Timestamp>,
+        /// Array of server badge ids
+        pub badges: Option<Vec<String>>,
+        /// Array of role ids to not receive mention notifications from
+        pub role_mention_optouts: Option<Vec<String>>,
+        /// Whether to display the server tag next to your name
+        pub show_server_tag: Option<bool>,
         /// Fields to remove from channel object
         #[cfg_attr(feature = "validator", validate(length(min = 1)))]
         pub remove: Option<Vec<FieldsMember>>,
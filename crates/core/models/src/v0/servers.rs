@@ -1,6 +1,9 @@
 use super::{Channel, File, RE_COLOUR};
 
 use guilderia_permissions::{Override, OverrideField};
+use iso8601_timestamp::Timestamp;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use std::collections::HashMap;
 
 #[cfg(feature = "validator")]
@@ -9,6 +12,9 @@ use validator::Validate;
 #[cfg(feature = "rocket")]
 use rocket::FromForm;
 
+/// Regex for valid server tags
+pub static RE_SERVER_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-zA-Z0-9]+$").unwrap());
+
 auto_derived_partial!(
     /// Server
     pub struct Server {
@@ -23,6 +29,9 @@ auto_derived_partial!(
         /// Description for the server
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         pub description: Option<String>,
+        /// Short tag displayed next to the names of members who opt in
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub tag: Option<String>,
 
         /// Channels within this server
         // TODO: investigate if this is redundant and can be removed
@@ -33,6 +42,18 @@ auto_derived_partial!(
         /// Configuration for sending system event messages
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         pub system_messages: Option<SystemMessageChannels>,
+        /// Configuration for highlighting popular messages
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub starboard: Option<Starboard>,
+        /// Restrictions applied to members who are new to the platform or server
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub new_member_restrictions: Option<NewMemberRestrictions>,
+        /// Limit on the number of users a single message may mention
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub mention_limit: Option<MentionLimit>,
+        /// Filter for words and phrases the server does not allow
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub content_filter: Option<ContentFilter>,
 
         /// Roles for this server
         #[cfg_attr(
@@ -43,6 +64,15 @@ auto_derived_partial!(
             )
         )]
         pub roles: HashMap<String, Role>,
+        /// Badges which can be given to members of this server
+        #[cfg_attr(
+            feature = "serde",
+            serde(
+                default = "HashMap::<String, ServerBadge>::new",
+                skip_serializing_if = "HashMap::<String, ServerBadge>::is_empty"
+            )
+        )]
+        pub badges: HashMap<String, ServerBadge>,
         /// Default set of server and channel permissions
         pub default_permissions: i64,
 
@@ -59,6 +89,9 @@ auto_derived_partial!(
             serde(skip_serializing_if = "crate::if_zero_u32", default)
         )]
         pub flags: u32,
+        /// Details shown alongside the server's flags
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub recognition: Option<ServerRecognition>,
 
         /// Whether this server is flagged as not safe for work
         #[cfg_attr(
@@ -78,6 +111,53 @@ auto_derived_partial!(
             serde(skip_serializing_if = "crate::if_false", default)
         )]
         pub discoverable: bool,
+
+        /// Notification mode for members who have not chosen their own
+        #[cfg_attr(feature = "serde", serde(default))]
+        pub default_notifications: NotificationMode,
+        /// Whether new emoji must be approved before they can be used
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub require_emoji_approval: bool,
+        /// Whether new members must accept the server rules before joining
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub require_rules_acceptance: bool,
+        /// Questions new members must answer before joining
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "Vec::is_empty", default)
+        )]
+        pub screening_questions: Vec<String>,
+        /// Whether text-to-speech messages are disabled in this server
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub disable_tts: bool,
+        /// Whether destructive actions need a second administrator to approve them
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub require_action_approval: bool,
+        /// Whether other servers may subscribe to this server's bans
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub share_bans: bool,
+
+        /// Number of members in this server
+        #[cfg_attr(feature = "serde", serde(default))]
+        pub member_count: i64,
+        /// Number of members currently online
+        #[cfg_attr(feature = "serde", serde(default))]
+        pub online_count: i64,
     },
     "PartialServer"
 );
@@ -100,6 +180,9 @@ auto_derived_partial!(
             serde(skip_serializing_if = "crate::if_false", default)
         )]
         pub hoist: bool,
+        /// Name of the group this role is organised under
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub group: Option<String>,
         /// Ranking of this role
         #[cfg_attr(feature = "serde", serde(default))]
         pub rank: i64,
@@ -108,6 +191,38 @@ auto_derived_partial!(
 );
 
 auto_derived!(
+    /// Badge which can be given to server members
+    pub struct ServerBadge {
+        /// Badge name
+        pub name: String,
+        /// Icon attachment
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub icon: Option<File>,
+        /// Colour used for this badge
+        ///
+        /// This can be any valid CSS colour
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub colour: Option<String>,
+    }
+
+    /// Optional fields on server badge object
+    pub enum FieldsServerBadge {
+        Icon,
+        Colour,
+    }
+);
+
+auto_derived!(
+    /// Which messages members are notified about
+    #[derive(Default)]
+    pub enum NotificationMode {
+        /// Notify for every message
+        AllMessages,
+        /// Only notify when mentioned
+        #[default]
+        MentionsOnly,
+    }
+
     /// Optional fields on server object
     pub enum FieldsServer {
         Description,
@@ -115,11 +230,41 @@ auto_derived!(
         SystemMessages,
         Icon,
         Banner,
+        Tag,
+        Starboard,
+        NewMemberRestrictions,
+        MentionLimit,
+        ContentFilter,
+        Recognition,
+    }
+
+    /// Flags that may be attributed to a server by the instance's admins
+    #[repr(u32)]
+    pub enum ServerFlags {
+        /// Server is the verified community of a brand, creator or project
+        Verified = 1,
+        /// Server is run by the instance itself
+        Official = 2,
+        /// Server is part of the partner program
+        Partnered = 4,
+    }
+
+    /// Details shown alongside a server's flags, managed by the instance's admins
+    pub struct ServerRecognition {
+        /// Short description of who the server represents
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub description: Option<String>,
+        /// Link to more information, such as the community's website
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub url: Option<String>,
+        /// Time at which the server was first recognised
+        pub since: Timestamp,
     }
 
     /// Optional fields on server object
     pub enum FieldsRole {
         Colour,
+        Group,
     }
 
     /// Channel category
@@ -133,6 +278,108 @@ auto_derived!(
         pub title: String,
         /// Channels in this category
         pub channels: Vec<String>,
+
+        /// Default permissions for channels synced with this category
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub default_permissions: Option<OverrideField>,
+        /// Role permissions for channels synced with this category
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "HashMap::is_empty")
+        )]
+        pub role_permissions: HashMap<String, OverrideField>,
+    }
+
+    /// Starboard configuration
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct Starboard {
+        /// Emoji which counts towards highlighting a message
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 128)))]
+        pub emoji: String,
+        /// Number of reactions required to highlight a message
+        #[cfg_attr(feature = "validator", validate(range(min = 1, max = 1000)))]
+        pub threshold: u32,
+        /// Id of the channel highlights are posted to
+        pub channel: String,
+    }
+
+    /// Restrictions applied to new members
+    ///
+    /// A member is new if either their account or their membership
+    /// is younger than the respective threshold.
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct NewMemberRestrictions {
+        /// Accounts younger than this many minutes are restricted
+        #[cfg_attr(feature = "validator", validate(range(max = 525600)))]
+        pub account_age_minutes: u32,
+        /// Memberships younger than this many minutes are restricted
+        #[cfg_attr(feature = "validator", validate(range(max = 525600)))]
+        pub membership_age_minutes: u32,
+        /// Minimum number of seconds between messages from a restricted member
+        #[cfg_attr(feature = "validator", validate(range(max = 21600)))]
+        pub message_interval_seconds: u32,
+        /// Whether restricted members may not post links
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub block_links: bool,
+        /// Whether restricted members may not upload attachments
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub block_attachments: bool,
+    }
+
+    /// Limit on the number of users a single message may mention
+    ///
+    /// Reply mentions do not count towards the limit.
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct MentionLimit {
+        /// Maximum number of unique users a message may mention
+        #[cfg_attr(feature = "validator", validate(range(min = 1, max = 100)))]
+        pub max: u32,
+        /// What to do with messages over the limit
+        pub action: MentionLimitAction,
+    }
+
+    /// Action taken against messages which mention too many users
+    pub enum MentionLimitAction {
+        /// Reject the message
+        Block,
+        /// Send the message without notifying any of the mentioned users
+        Strip,
+    }
+
+    /// Filter for words and phrases the server does not allow
+    ///
+    /// Words are matched whole, ignoring case and common character substitutions.
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct ContentFilter {
+        /// Words and phrases to filter, a trailing `*` also matches longer words
+        #[cfg_attr(feature = "serde", serde(default))]
+        #[cfg_attr(feature = "validator", validate(length(max = 1000)))]
+        pub words: Vec<String>,
+        /// Words which are never filtered, even if they are on the instance list
+        #[cfg_attr(feature = "serde", serde(default))]
+        #[cfg_attr(feature = "validator", validate(length(max = 1000)))]
+        pub allow: Vec<String>,
+        /// Whether to also filter words on the instance list
+        #[cfg_attr(feature = "serde", serde(default))]
+        pub use_base_list: bool,
+        /// What to do with messages containing filtered words
+        pub action: ContentFilterAction,
+    }
+
+    /// Action taken against messages containing filtered words
+    pub enum ContentFilterAction {
+        /// Send the message with filtered words blacked out
+        Censor,
+        /// Reject the message
+        Block,
+        /// Send the message and alert the server's moderators
+        Flag,
     }
 
     /// System message channel assignments
@@ -149,6 +396,47 @@ auto_derived!(
         /// ID of channel to send user banned messages in
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         pub user_banned: Option<String>,
+        /// Where to send message pinned and unpinned messages
+        ///
+        /// Defaults to the channel the message was pinned in
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub message_pinned: Option<SystemMessageTarget>,
+
+        /// Templates to pick from when sending user join messages
+        ///
+        /// Supports `{user}`, `{server}` and `{member_count}` placeholders
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "Vec::is_empty", default)
+        )]
+        pub user_joined_templates: Vec<String>,
+        /// Templates to pick from when sending user left messages
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "Vec::is_empty", default)
+        )]
+        pub user_left_templates: Vec<String>,
+        /// Whether to attach a generated welcome image to user join messages
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub welcome_image: bool,
+
+        /// ID of channel to send alerts about suspicious new members in
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub moderation_alerts: Option<String>,
+    }
+
+    /// Destination for a system message that originates in a channel
+    #[cfg_attr(feature = "serde", serde(tag = "type"))]
+    pub enum SystemMessageTarget {
+        /// Send to the channel the event happened in
+        SameChannel,
+        /// Send to a specific channel
+        Channel { id: String },
+        /// Do not send the message
+        Disabled,
     }
 
     /// Information about new server to create
@@ -186,6 +474,49 @@ auto_derived!(
         pub role: Role,
     }
 
+    /// Information about new server badge to create
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataCreateServerBadge {
+        /// Badge name
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 32)))]
+        pub name: String,
+        /// Attachment Id for icon
+        pub icon: Option<String>,
+        /// Badge colour
+        #[cfg_attr(
+            feature = "validator",
+            validate(length(min = 1, max = 128), regex = "RE_COLOUR")
+        )]
+        pub colour: Option<String>,
+    }
+
+    /// New server badge information
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataEditServerBadge {
+        /// Badge name
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 32)))]
+        pub name: Option<String>,
+        /// Attachment Id for icon
+        pub icon: Option<String>,
+        /// Badge colour
+        #[cfg_attr(
+            feature = "validator",
+            validate(length(min = 1, max = 128), regex = "RE_COLOUR")
+        )]
+        pub colour: Option<String>,
+        /// Fields to remove from badge object
+        #[cfg_attr(feature = "validator", validate(length(min = 1)))]
+        pub remove: Option<Vec<FieldsServerBadge>>,
+    }
+
+    /// Response after creating new server badge
+    pub struct NewServerBadgeResponse {
+        /// Id of the badge
+        pub id: String,
+        /// New badge
+        pub badge: ServerBadge,
+    }
+
     /// Information returned when creating server
     pub struct CreateServerLegacyResponse {
         /// Server object
@@ -221,6 +552,12 @@ auto_derived!(
         /// Server description
         #[cfg_attr(feature = "validator", validate(length(min = 0, max = 1024)))]
         pub description: Option<String>,
+        /// Short tag displayed next to the names of members who opt in
+        #[cfg_attr(
+            feature = "validator",
+            validate(length(min = 2, max = 4), regex = "RE_SERVER_TAG")
+        )]
+        pub tag: Option<String>,
 
         /// Attachment Id for icon
         pub icon: Option<String>,
@@ -232,6 +569,18 @@ auto_derived!(
         pub categories: Option<Vec<Category>>,
         /// System message configuration
         pub system_messages: Option<SystemMessageChannels>,
+        /// Starboard configuration
+        #[cfg_attr(feature = "validator", validate)]
+        pub starboard: Option<Starboard>,
+        /// Restrictions applied to new members
+        #[cfg_attr(feature = "validator", validate)]
+        pub new_member_restrictions: Option<NewMemberRestrictions>,
+        /// Limit on the number of users a single message may mention
+        #[cfg_attr(feature = "validator", validate)]
+        pub mention_limit: Option<MentionLimit>,
+        /// Filter for words and phrases the server does not allow
+        #[cfg_attr(feature = "validator", validate)]
+        pub content_filter: Option<ContentFilter>,
 
         /// Bitfield of server flags
         #[cfg_attr(feature = "validator", serde(skip_serializing_if = "Option::is_none"))]
@@ -245,12 +594,51 @@ auto_derived!(
         ///
         /// Must be enabled in order to show up on [Guilderia Discover](https://guilderia.gg).
         pub analytics: Option<bool>,
+        /// Notification mode for members who have not chosen their own
+        pub default_notifications: Option<NotificationMode>,
+        /// Whether new emoji must be approved before they can be used
+        pub require_emoji_approval: Option<bool>,
+        /// Whether new members must accept the server rules before joining
+        pub require_rules_acceptance: Option<bool>,
+        /// Questions new members must answer before joining
+        #[cfg_attr(
+            feature = "validator",
+            validate(length(max = 5), custom = "validate_screening_questions")
+        )]
+        pub screening_questions: Option<Vec<String>>,
+        /// Whether text-to-speech messages are disabled in this server
+        pub disable_tts: Option<bool>,
+        /// Whether destructive actions need a second administrator to approve them
+        ///
+        /// Only the server owner may change this.
+        pub require_action_approval: Option<bool>,
+        /// Whether other servers may subscribe to this server's bans
+        pub share_bans: Option<bool>,
 
         /// Fields to remove from server object
         #[cfg_attr(feature = "validator", validate(length(min = 1)))]
         pub remove: Option<Vec<FieldsServer>>,
     }
 
+    /// New position of a channel
+    pub struct ChannelPosition {
+        /// Channel Id
+        pub id: String,
+        /// Category to place the channel in, channels without one are uncategorised
+        pub category: Option<String>,
+    }
+
+    /// New channel order
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataReorderChannels {
+        /// Channels in their new order
+        ///
+        /// Listed channels are reordered among the positions they currently occupy,
+        /// so a partial list leaves every other channel where it is.
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 200)))]
+        pub channels: Vec<ChannelPosition>,
+    }
+
     /// New role information
     #[cfg_attr(feature = "validator", derive(Validate))]
     pub struct DataEditRole {
@@ -265,6 +653,9 @@ auto_derived!(
         pub colour: Option<String>,
         /// Whether this role should be displayed separately
         pub hoist: Option<bool>,
+        /// Name of the group to organise this role under
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 32)))]
+        pub group: Option<String>,
         /// Ranking position
         ///
         /// Smaller values take priority.
@@ -274,12 +665,64 @@ auto_derived!(
         pub remove: Option<Vec<FieldsRole>>,
     }
 
+    /// New role order
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataReorderRoles {
+        /// Role ids in their new order, highest first
+        ///
+        /// Listed roles are reordered among the ranks they currently hold,
+        /// so a partial list leaves every other role where it is.
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 200)))]
+        pub roles: Vec<String>,
+    }
+
     /// New role permissions
     pub struct DataSetServerRolePermission {
         /// Allow / deny values for the role in this server.
         pub permissions: Override,
     }
 
+    /// Information for pruning inactive members
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataServerPrune {
+        /// Number of days a member must have been inactive for
+        #[cfg_attr(feature = "validator", validate(range(min = 1, max = 365)))]
+        pub days: u32,
+        /// Members with any of these roles will not be pruned
+        #[cfg_attr(feature = "serde", serde(default))]
+        pub exempt_roles: Vec<String>,
+    }
+
+    /// Background job running against a server
+    pub struct ServerJob {
+        /// Job Id
+        pub id: String,
+    }
+
+    /// Data which can be exported from a server
+    pub enum ServerExportKind {
+        /// Answers given to the server's screening questions
+        Screening,
+        /// Roles and how many members hold each of them
+        Roles,
+        /// Usage of bot commands in the server's channels
+        Interactions,
+    }
+
+    /// File format of a server export
+    pub enum ServerExportFormat {
+        Csv,
+        Json,
+    }
+
+    /// Information for exporting server data
+    pub struct DataCreateServerExport {
+        /// Data to export
+        pub kind: ServerExportKind,
+        /// Format to export the data in
+        pub format: ServerExportFormat,
+    }
+
     /// Options when leaving a server
     #[cfg_attr(feature = "rocket", derive(FromForm))]
     pub struct OptionsServerDelete {
@@ -287,3 +730,21 @@ auto_derived!(
         pub leave_silently: Option<bool>,
     }
 );
+
+/// Maximum length of a screening question
+pub const MAX_SCREENING_QUESTION_LENGTH: usize = 300;
+
+/// Maximum length of an answer to a screening question
+pub const MAX_SCREENING_ANSWER_LENGTH: usize = 1000;
+
+/// Ensure screening questions are neither empty nor too long
+#[cfg(feature = "validator")]
+fn validate_screening_questions(questions: &[String]) -> Result<(), validator::ValidationError> {
+    if questions.iter().any(|question| {
+        question.trim().is_empty() || question.chars().count() > MAX_SCREENING_QUESTION_LENGTH
+    }) {
+        Err(validator::ValidationError::new("screening_questions"))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+#[cfg(feature = "rocket")]
+use rocket::FromForm;
+
+use super::{Channel, ChannelUnread, Relationship, UserSettings};
+
+auto_derived!(
+    /// Options for fetching changes since the last sync
+    #[cfg_attr(feature = "rocket", derive(FromForm))]
+    pub struct OptionsSyncDelta {
+        /// Timestamp (in milliseconds) of the last sync
+        pub since: i64,
+    }
+
+    /// Changes since the last sync
+    pub struct SyncDelta {
+        /// Timestamp (in milliseconds) to pass as `since` on the next sync
+        pub timestamp: i64,
+        /// Direct message and group channels created or messaged in since the last sync
+        pub channels: Vec<Channel>,
+        /// Unread state of channels with unread messages or read since the last sync
+        pub unreads: Vec<ChannelUnread>,
+        /// All of the user's relationships
+        pub relationships: Vec<Relationship>,
+        /// Settings changed since the last sync
+        pub settings: UserSettings,
+    }
+);
@@ -0,0 +1,38 @@
+auto_derived!(
+    /// Background task queue status
+    pub struct TaskQueue {
+        /// Queue name
+        pub name: String,
+        /// Items waiting to be processed
+        pub pending: u64,
+        /// Items which failed too many times and were set aside
+        pub dead: u64,
+
+        /// Items queued by this node since it started
+        pub pushed: u64,
+        /// Items completed by this node since it started
+        pub completed: u64,
+        /// Items retried by this node since it started
+        pub retried: u64,
+        /// Items set aside by this node since it started
+        pub dead_lettered: u64,
+        /// Average time between queueing and completion in milliseconds
+        pub average_latency_ms: u64,
+        /// Average number of items committed together in a batch
+        pub average_batch_size: u64,
+        /// Largest number of items committed together in a batch
+        pub largest_batch_size: u64,
+    }
+
+    /// Items set aside from a task queue
+    pub struct DeadLetteredTasks {
+        /// Raw items, most recently set aside first
+        pub items: Vec<String>,
+    }
+
+    /// Result of requeueing items set aside from a task queue
+    pub struct RequeuedTasks {
+        /// Number of items put back on the queue
+        pub count: usize,
+    }
+);
@@ -0,0 +1,52 @@
+auto_derived_partial!(
+    /// Thread of replies branching off a message
+    pub struct Thread {
+        /// Id of the message the thread branches off
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Id of the channel the thread belongs to
+        pub channel: String,
+        /// Id of the user who started the thread
+        pub creator: String,
+        /// Thread name
+        pub name: String,
+        /// Whether the thread is archived
+        ///
+        /// Archived threads no longer accept new messages.
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub archived: bool,
+        /// Ids of the users following the thread
+        #[cfg_attr(feature = "serde", serde(default))]
+        pub members: Vec<String>,
+        /// Number of messages sent in the thread
+        #[cfg_attr(feature = "serde", serde(default))]
+        pub message_count: i64,
+        /// Id of the last message sent in the thread
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub last_message_id: Option<String>,
+    },
+    "PartialThread"
+);
+
+auto_derived!(
+    /// Start a thread
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct DataCreateThread {
+        /// Thread name
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 100)))]
+        pub name: String,
+    }
+
+    /// Changes to make to a thread
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct DataEditThread {
+        /// Thread name
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 100)))]
+        pub name: Option<String>,
+        /// Whether the thread is archived
+        pub archived: Option<bool>,
+    }
+);
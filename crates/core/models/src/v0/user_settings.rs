@@ -24,3 +24,15 @@ auto_derived!(
         pub timestamp: Option<i64>,
     }
 );
+
+auto_derived!(
+    /// Which bots may send the user direct messages
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct BotDmPreferences {
+        /// Whether bots which are not on the allowlist may message the user
+        pub allow_all: bool,
+        /// Ids of bots and webhooks which may always message the user
+        #[cfg_attr(feature = "validator", validate(length(max = 100)))]
+        pub allowed: Vec<String>,
+    }
+);
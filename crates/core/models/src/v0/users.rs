@@ -1,3 +1,4 @@
+use iso8601_timestamp::Timestamp;
 use once_cell::sync::Lazy;
 use regex::Regex;
 
@@ -88,6 +89,7 @@ auto_derived!(
         ProfileContent,
         ProfileBackground,
         DisplayName,
+        StatusActivity,
 
         /// Internal field, ignore this.
         Internal,
@@ -147,6 +149,87 @@ auto_derived!(
         /// Current presence option
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         pub presence: Option<Presence>,
+        /// What the user is currently doing
+        ///
+        /// This is set through the activity API rather than by editing the user.
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub activity: Option<Activity>,
+    }
+
+    /// Activity shown on a user's presence, such as a game being played
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct Activity {
+        /// Kind of activity
+        #[cfg_attr(feature = "serde", serde(rename = "type"))]
+        pub kind: ActivityType,
+        /// Name of the game, song or stream
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 128)))]
+        pub name: String,
+        /// What the user is doing within the activity
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 128)))]
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub details: Option<String>,
+        /// Current state of the activity, such as the album or party size
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 128)))]
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub state: Option<String>,
+        /// Link to the stream, only shown when streaming
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 256)))]
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub url: Option<String>,
+        /// Images shown alongside the activity
+        #[cfg_attr(feature = "validator", validate)]
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub assets: Option<ActivityAssets>,
+        /// When the activity started and is due to end
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub timestamps: Option<ActivityTimestamps>,
+        /// Id of the bot which set this activity
+        ///
+        /// This is ignored when setting an activity.
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub application: Option<String>,
+    }
+
+    /// Kind of activity
+    pub enum ActivityType {
+        Playing,
+        Listening,
+        Watching,
+        Streaming,
+    }
+
+    /// Images shown alongside an activity
+    ///
+    /// Images are referenced by attachment id or by HTTPS URL.
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct ActivityAssets {
+        /// Reference to the large image
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 256)))]
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub large_image: Option<String>,
+        /// Text shown when hovering over the large image
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 128)))]
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub large_text: Option<String>,
+        /// Reference to the small image
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 256)))]
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub small_image: Option<String>,
+        /// Text shown when hovering over the small image
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 128)))]
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub small_text: Option<String>,
+    }
+
+    /// When an activity started and is due to end
+    pub struct ActivityTimestamps {
+        /// Time at which the activity started
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub start: Option<Timestamp>,
+        /// Time at which the activity is due to end
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub end: Option<Timestamp>,
     }
 
     /// User's profile
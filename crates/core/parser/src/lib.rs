@@ -1,4 +1,4 @@
-use std::collections::{HashSet, VecDeque};
+use std::{collections::{HashSet, VecDeque}, ops::Range};
 
 use logos::Logos;
 
@@ -16,7 +16,15 @@ pub enum MessageToken {
     #[token("@everyone")]
     MentionEveryone,
     #[token("@online")]
-    MentionOnline
+    MentionOnline,
+    #[regex("<https?://[^\\s<>]+>", |lex| lex.slice()[1..lex.slice().len() - 1].to_owned())]
+    SuppressedLink(String),
+    #[token("||")]
+    SpoilerMarker,
+    #[regex("<t:-?[0-9]{1,12}(:[tTdDfFR])?>", |lex| lex.slice()[3..].trim_end_matches(|c: char| !c.is_ascii_digit()).parse::<i64>().ok())]
+    Timestamp(i64),
+    #[token("-# ")]
+    SubtextMarker
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -24,7 +32,8 @@ pub struct MessageResults {
     pub user_mentions: HashSet<String>,
     pub role_mentions: HashSet<String>,
     pub mentions_everyone: bool,
-    pub mentions_online: bool
+    pub mentions_online: bool,
+    pub suppressed_links: HashSet<String>
 }
 
 struct MessageParserIterator<I> {
@@ -84,12 +93,112 @@ pub fn parse_message(text: &str) -> MessageResults {
             MessageToken::RoleMention(id) => { results.role_mentions.insert(id); },
             MessageToken::MentionEveryone => results.mentions_everyone = true,
             MessageToken::MentionOnline => results.mentions_online = true,
+            MessageToken::SuppressedLink(link) => { results.suppressed_links.insert(link); },
+            MessageToken::SpoilerMarker
+            | MessageToken::Timestamp(_)
+            | MessageToken::SubtextMarker => {}
         };
     };
 
     results
 }
 
+/// Split text into lowercase words
+pub fn split_words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+}
+
+/// Collect the words of a message that are shown as plain text
+///
+/// Code, mentions and suppressed links are skipped the same way the parser skips them.
+pub fn parse_words(text: &str) -> Vec<String> {
+    let mut excluded = vec![];
+    let mut pending = vec![];
+    let mut open: Option<(usize, usize)> = None;
+    let mut lexer = MessageToken::lexer(text).spanned();
+
+    while let Some((token, span)) = lexer.next() {
+        match (token, open) {
+            (Ok(MessageToken::CodeblockMarker(ty)), Some((open_ty, start))) if ty == open_ty => {
+                excluded.push(start..span.end);
+                pending.clear();
+                open = None;
+            }
+            (Ok(_), Some(_)) => pending.push(span),
+            (Ok(MessageToken::Escape), None) => {
+                lexer.next();
+            }
+            (Ok(MessageToken::CodeblockMarker(ty)), None) => open = Some((ty, span.start)),
+            (Ok(_), None) => excluded.push(span),
+            (Err(_), _) => {}
+        }
+    }
+
+    // Unclosed code blocks are not code, but their tokens still aren't words
+    excluded.extend(pending);
+
+    let mut visible = String::with_capacity(text.len());
+    let mut last = 0;
+    for range in excluded {
+        visible.push_str(&text[last..range.start]);
+        visible.push(' ');
+        last = range.end;
+    }
+
+    visible.push_str(&text[last..]);
+    split_words(&visible).collect()
+}
+
+/// Find the markdown extensions used outside of code, along with where they appear
+///
+/// Spoiler markers are only kept in pairs and subtext markers only at the start of a line.
+pub fn parse_extensions(text: &str) -> Vec<(MessageToken, Range<usize>)> {
+    let mut found = vec![];
+    let mut pending = vec![];
+    let mut open: Option<usize> = None;
+    let mut lexer = MessageToken::lexer(text).spanned();
+
+    while let Some((token, span)) = lexer.next() {
+        match (token, open) {
+            (Ok(MessageToken::CodeblockMarker(ty)), Some(open_ty)) if ty == open_ty => {
+                pending.clear();
+                open = None;
+            }
+            (Ok(token), Some(_)) => pending.push((token, span)),
+            (Ok(MessageToken::Escape), None) => {
+                lexer.next();
+            }
+            (Ok(MessageToken::CodeblockMarker(ty)), None) => open = Some(ty),
+            (Ok(token), None) => found.push((token, span)),
+            (Err(_), _) => {}
+        }
+    }
+
+    // Unclosed code blocks are not code
+    found.extend(pending);
+    found.retain(|(token, span)| match token {
+        MessageToken::SpoilerMarker | MessageToken::Timestamp(_) => true,
+        MessageToken::SubtextMarker => span.start == 0 || text[..span.start].ends_with('\n'),
+        _ => false,
+    });
+
+    // A lone spoiler marker is shown as-is
+    let spoilers: Vec<usize> = found
+        .iter()
+        .enumerate()
+        .filter(|(_, (token, _))| *token == MessageToken::SpoilerMarker)
+        .map(|(index, _)| index)
+        .collect();
+
+    if spoilers.len() % 2 == 1 {
+        found.remove(spoilers[spoilers.len() - 1]);
+    }
+
+    found
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -235,6 +344,23 @@ mod tests {
         assert_eq!(output[2], MessageToken::CodeblockMarker(1));
     }
 
+    #[test]
+    fn test_suppressed_link() {
+        let output = parse_message_iter("see <https://example.com/page> and https://example.org").collect::<Vec<_>>();
+
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0], MessageToken::SuppressedLink("https://example.com/page".to_string()));
+    }
+
+    #[test]
+    fn test_codeblock_no_suppressed_link() {
+        let output = parse_message_iter("`<https://example.com>`").collect::<Vec<_>>();
+
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0], MessageToken::CodeblockMarker(1));
+        assert_eq!(output[1], MessageToken::CodeblockMarker(1));
+    }
+
     #[test]
     fn test_escape_mention() {
         let output = parse_message_iter("i wont ping \\@everyone").collect::<Vec<_>>();
@@ -242,4 +368,46 @@ mod tests {
         assert_eq!(output.len(), 1);
         assert_eq!(output[0], MessageToken::Escape);
     }
+
+    #[test]
+    fn test_words() {
+        let output = parse_words("Deploy <@01FD58YK5W7QRV5H3D64KTQYX3> the `Release-Notes` now, Release-Notes!");
+
+        assert_eq!(output, vec!["deploy", "the", "now", "release", "notes"]);
+    }
+
+    #[test]
+    fn test_extensions() {
+        let text = "-# small ||secret|| at <t:1700000000:R> ||lone\nnot -# subtext `||code||`";
+        let output: Vec<MessageToken> = parse_extensions(text)
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect();
+
+        assert_eq!(
+            output,
+            vec![
+                MessageToken::SubtextMarker,
+                MessageToken::SpoilerMarker,
+                MessageToken::SpoilerMarker,
+                MessageToken::Timestamp(1700000000)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extensions_spans() {
+        let text = "see ||this||";
+        let output = parse_extensions(text);
+
+        assert_eq!(output[0].1, 4..6);
+        assert_eq!(output[1].1, 10..12);
+    }
+
+    #[test]
+    fn test_words_uncontained_codeblock() {
+        let output = parse_words("```rust\n<@01FD58YK5W7QRV5H3D64KTQYX3> hello");
+
+        assert_eq!(output, vec!["rust", "hello"]);
+    }
 }
\ No newline at end of file
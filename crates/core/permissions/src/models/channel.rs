@@ -95,9 +95,11 @@ pub enum ChannelPermission {
     MentionEveryone = 1 << 37,
     /// Mention roles
     MentionRoles = 1 << 38,
+    /// Send text-to-speech messages
+    SendTTSMessages = 1 << 39,
 
     // * Misc. permissions
-    // % Bits 38 to 52: free area
+    // % Bits 40 to 52: free area
     // % Bits 53 to 64: do not use
 
     // * Grant all permissions
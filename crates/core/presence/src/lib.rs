@@ -84,6 +84,15 @@ async fn delete_session_internal(user_id: &str, session_id: u32, skip_region: bo
     }
 }
 
+/// Count the presence sessions a user has open across all regions
+pub async fn count_sessions(user_id: &str) -> u32 {
+    if let Ok(mut conn) = get_connection().await {
+        __get_set_size(&mut conn, user_id).await
+    } else {
+        0
+    }
+}
+
 /// Check whether a given user ID is online
 pub async fn is_online(user_id: &str) -> bool {
     if let Ok(mut conn) = get_connection().await {
@@ -192,7 +201,9 @@ pub async fn clear_region(region_id: Option<&str>) {
 
 #[cfg(test)]
 mod tests {
-    use crate::{clear_region, create_session, delete_session, filter_online, is_online};
+    use crate::{
+        clear_region, count_sessions, create_session, delete_session, filter_online, is_online,
+    };
     use rand::Rng;
 
     #[async_std::test]
@@ -222,6 +233,7 @@ mod tests {
         let (first_session, second_session_id) = create_session(&user_id, 0).await;
         assert!(!first_session);
         assert_eq!(second_session_id as u8 & 1, 0);
+        assert_eq!(count_sessions(&user_id).await, 2);
 
         let (first_session, other_session_id) = create_session(&other_id, 0).await;
         assert!(first_session);
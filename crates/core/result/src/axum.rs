@@ -20,6 +20,15 @@ impl IntoResponse for Error {
             ErrorType::BlockedByOther => StatusCode::FORBIDDEN,
             ErrorType::NotFriends => StatusCode::FORBIDDEN,
             ErrorType::TooManyPendingFriendRequests { .. } => StatusCode::BAD_REQUEST,
+            ErrorType::TooManyApiTokens { .. } => StatusCode::BAD_REQUEST,
+            ErrorType::EmailInUse => StatusCode::CONFLICT,
+            ErrorType::DeviceApprovalRequired => StatusCode::FORBIDDEN,
+            ErrorType::TooManyRecoveryContacts { .. } => StatusCode::BAD_REQUEST,
+            ErrorType::RecoveryInProgress => StatusCode::CONFLICT,
+            ErrorType::TooManyBookmarks { .. } => StatusCode::BAD_REQUEST,
+            ErrorType::BotMessagesBlocked => StatusCode::FORBIDDEN,
+            ErrorType::TooManySessions { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ErrorType::OutdatedSettings => StatusCode::CONFLICT,
 
             ErrorType::UnknownChannel => StatusCode::NOT_FOUND,
             ErrorType::UnknownMessage => StatusCode::NOT_FOUND,
@@ -29,30 +38,45 @@ impl IntoResponse for Error {
             ErrorType::TooManyAttachments { .. } => StatusCode::BAD_REQUEST,
             ErrorType::TooManyReplies { .. } => StatusCode::BAD_REQUEST,
             ErrorType::EmptyMessage => StatusCode::UNPROCESSABLE_ENTITY,
-            ErrorType::PayloadTooLarge => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorType::PayloadTooLarge { .. } => StatusCode::UNPROCESSABLE_ENTITY,
             ErrorType::CannotRemoveYourself => StatusCode::BAD_REQUEST,
             ErrorType::GroupTooLarge { .. } => StatusCode::FORBIDDEN,
             ErrorType::AlreadyInGroup => StatusCode::CONFLICT,
             ErrorType::NotInGroup => StatusCode::NOT_FOUND,
             ErrorType::AlreadyPinned => StatusCode::BAD_REQUEST,
             ErrorType::NotPinned => StatusCode::BAD_REQUEST,
+            ErrorType::MentionConfirmationRequired { .. } => StatusCode::PRECONDITION_REQUIRED,
+            ErrorType::InvalidProvenance => StatusCode::BAD_REQUEST,
+            ErrorType::NewMemberRestricted => StatusCode::FORBIDDEN,
+            ErrorType::NewMemberSlowmode { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ErrorType::AttachmentTypeNotAllowed { .. } => StatusCode::BAD_REQUEST,
+            ErrorType::TooManyMentions { .. } => StatusCode::BAD_REQUEST,
+            ErrorType::TooManyBurstReactions { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ErrorType::MessageFiltered => StatusCode::BAD_REQUEST,
+            ErrorType::ThreadArchived => StatusCode::FORBIDDEN,
+            ErrorType::ChannelSlowmode { .. } => StatusCode::TOO_MANY_REQUESTS,
 
             ErrorType::UnknownServer => StatusCode::NOT_FOUND,
             ErrorType::InvalidRole => StatusCode::NOT_FOUND,
             ErrorType::Banned => StatusCode::FORBIDDEN,
             ErrorType::AlreadyInServer => StatusCode::CONFLICT,
             ErrorType::CannotTimeoutYourself => StatusCode::BAD_REQUEST,
+            ErrorType::ServerTagTaken => StatusCode::CONFLICT,
+            ErrorType::ApprovalRequired { .. } => StatusCode::ACCEPTED,
+            ErrorType::ApplicationPending => StatusCode::CONFLICT,
 
             ErrorType::TooManyServers { .. } => StatusCode::BAD_REQUEST,
             ErrorType::TooManyEmbeds { .. } => StatusCode::BAD_REQUEST,
             ErrorType::TooManyEmoji { .. } => StatusCode::BAD_REQUEST,
             ErrorType::TooManyChannels { .. } => StatusCode::BAD_REQUEST,
             ErrorType::TooManyRoles { .. } => StatusCode::BAD_REQUEST,
+            ErrorType::TooManyBadges { .. } => StatusCode::BAD_REQUEST,
 
             ErrorType::ReachedMaximumBots => StatusCode::BAD_REQUEST,
             ErrorType::IsBot => StatusCode::BAD_REQUEST,
             ErrorType::IsNotBot => StatusCode::BAD_REQUEST,
             ErrorType::BotIsPrivate => StatusCode::FORBIDDEN,
+            ErrorType::InteractionExpired => StatusCode::GONE,
 
             ErrorType::CannotReportYourself => StatusCode::BAD_REQUEST,
 
@@ -84,6 +108,7 @@ impl IntoResponse for Error {
             ErrorType::FileTypeNotAllowed => StatusCode::BAD_REQUEST,
             ErrorType::ImageProcessingFailed => StatusCode::INTERNAL_SERVER_ERROR,
             ErrorType::NoEmbedData => StatusCode::BAD_REQUEST,
+            ErrorType::ArchiveTooLarge => StatusCode::UNPROCESSABLE_ENTITY,
         };
 
         (status, Json(&self)).into_response()
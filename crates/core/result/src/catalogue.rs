@@ -0,0 +1,174 @@
+//! Catalogue of stable error codes and localization keys
+use crate::ErrorType;
+
+/// Catalogue entry describing a type of error
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "schemas", derive(JsonSchema))]
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorInfo {
+    /// Stable numeric code
+    pub code: u32,
+    /// Name of the error, as found in the `type` field
+    pub name: &'static str,
+    /// Localization key for a human-readable description
+    pub key: &'static str,
+    /// Names of additional fields included with this error
+    pub context: &'static [&'static str],
+}
+
+macro_rules! catalogue {
+    ( $( $variant:ident $( { $( $field:ident ),* } )? => $code:literal, $key:literal; )* ) => {
+        impl ErrorType {
+            /// Stable numeric code identifying this type of error
+            pub fn code(&self) -> u32 {
+                match self {
+                    $( ErrorType::$variant $( { $( $field: _ ),* } )? => $code, )*
+                }
+            }
+
+            /// Localization key for a human-readable description of this error
+            pub fn key(&self) -> &'static str {
+                match self {
+                    $( ErrorType::$variant { .. } => $key, )*
+                }
+            }
+        }
+
+        /// Every type of error which may be returned
+        pub static ERRORS: &[ErrorInfo] = &[
+            $(
+                ErrorInfo {
+                    code: $code,
+                    name: stringify!($variant),
+                    key: $key,
+                    context: &[ $( $( stringify!($field) ),* )? ],
+                },
+            )*
+        ];
+    };
+}
+
+// Codes must never be reused or changed once published,
+// new errors should take the next free code in their group.
+catalogue! {
+    LabelMe => 0, "error.label_me";
+    // ? Onboarding errors
+    AlreadyOnboarded => 1000, "error.already_onboarded";
+    // ? User errors
+    UsernameTaken => 2000, "error.username_taken";
+    InvalidUsername => 2001, "error.invalid_username";
+    DiscriminatorChangeRatelimited => 2002, "error.discriminator_change_ratelimited";
+    UnknownUser => 2003, "error.unknown_user";
+    AlreadyFriends => 2004, "error.already_friends";
+    AlreadySentRequest => 2005, "error.already_sent_request";
+    Blocked => 2006, "error.blocked";
+    BlockedByOther => 2007, "error.blocked_by_other";
+    NotFriends => 2008, "error.not_friends";
+    TooManyPendingFriendRequests { max } => 2009, "error.too_many_pending_friend_requests";
+    TooManyApiTokens { max } => 2010, "error.too_many_api_tokens";
+    EmailInUse => 2011, "error.email_in_use";
+    DeviceApprovalRequired => 2012, "error.device_approval_required";
+    TooManyRecoveryContacts { max } => 2013, "error.too_many_recovery_contacts";
+    RecoveryInProgress => 2014, "error.recovery_in_progress";
+    TooManyBookmarks { max } => 2015, "error.too_many_bookmarks";
+    BotMessagesBlocked => 2016, "error.bot_messages_blocked";
+    TooManySessions { max } => 2017, "error.too_many_sessions";
+    OutdatedSettings => 2018, "error.outdated_settings";
+    // ? Channel errors
+    UnknownChannel => 3000, "error.unknown_channel";
+    UnknownAttachment => 3001, "error.unknown_attachment";
+    UnknownMessage => 3002, "error.unknown_message";
+    CannotEditMessage => 3003, "error.cannot_edit_message";
+    CannotJoinCall => 3004, "error.cannot_join_call";
+    TooManyAttachments { max } => 3005, "error.too_many_attachments";
+    TooManyEmbeds { max } => 3006, "error.too_many_embeds";
+    TooManyReplies { max } => 3007, "error.too_many_replies";
+    TooManyChannels { max } => 3008, "error.too_many_channels";
+    EmptyMessage => 3009, "error.empty_message";
+    PayloadTooLarge { max } => 3010, "error.payload_too_large";
+    CannotRemoveYourself => 3011, "error.cannot_remove_yourself";
+    GroupTooLarge { max } => 3012, "error.group_too_large";
+    AlreadyInGroup => 3013, "error.already_in_group";
+    NotInGroup => 3014, "error.not_in_group";
+    AlreadyPinned => 3015, "error.already_pinned";
+    NotPinned => 3016, "error.not_pinned";
+    MentionConfirmationRequired { token, recipients } => 3017, "error.mention_confirmation_required";
+    InvalidProvenance => 3018, "error.invalid_provenance";
+    NewMemberRestricted => 3019, "error.new_member_restricted";
+    NewMemberSlowmode { retry_after } => 3020, "error.new_member_slowmode";
+    AttachmentTypeNotAllowed { allowed } => 3021, "error.attachment_type_not_allowed";
+    TooManyMentions { max } => 3022, "error.too_many_mentions";
+    TooManyBurstReactions { max } => 3023, "error.too_many_burst_reactions";
+    MessageFiltered => 3024, "error.message_filtered";
+    ThreadArchived => 3025, "error.thread_archived";
+    ChannelSlowmode { retry_after } => 3026, "error.channel_slowmode";
+    // ? Server errors
+    UnknownServer => 4000, "error.unknown_server";
+    InvalidRole => 4001, "error.invalid_role";
+    Banned => 4002, "error.banned";
+    TooManyServers { max } => 4003, "error.too_many_servers";
+    TooManyEmoji { max } => 4004, "error.too_many_emoji";
+    TooManyRoles { max } => 4005, "error.too_many_roles";
+    AlreadyInServer => 4006, "error.already_in_server";
+    CannotTimeoutYourself => 4007, "error.cannot_timeout_yourself";
+    ServerTagTaken => 4008, "error.server_tag_taken";
+    TooManyBadges { max } => 4009, "error.too_many_badges";
+    ApprovalRequired { id } => 4010, "error.approval_required";
+    ApplicationPending => 4011, "error.application_pending";
+    // ? Bot errors
+    ReachedMaximumBots => 5000, "error.reached_maximum_bots";
+    IsBot => 5001, "error.is_bot";
+    IsNotBot => 5002, "error.is_not_bot";
+    BotIsPrivate => 5003, "error.bot_is_private";
+    InteractionExpired => 5004, "error.interaction_expired";
+    // ? Safety errors
+    CannotReportYourself => 6000, "error.cannot_report_yourself";
+    // ? Permission errors
+    MissingPermission { permission } => 7000, "error.missing_permission";
+    MissingUserPermission { permission } => 7001, "error.missing_user_permission";
+    NotElevated => 7002, "error.not_elevated";
+    NotPrivileged => 7003, "error.not_privileged";
+    CannotGiveMissingPermissions => 7004, "error.cannot_give_missing_permissions";
+    NotOwner => 7005, "error.not_owner";
+    // ? General errors
+    DatabaseError { operation, collection } => 8000, "error.database_error";
+    InternalError => 8001, "error.internal_error";
+    InvalidOperation => 8002, "error.invalid_operation";
+    InvalidCredentials => 8003, "error.invalid_credentials";
+    InvalidProperty => 8004, "error.invalid_property";
+    InvalidSession => 8005, "error.invalid_session";
+    InvalidFlagValue => 8006, "error.invalid_flag_value";
+    NotAuthenticated => 8007, "error.not_authenticated";
+    DuplicateNonce => 8008, "error.duplicate_nonce";
+    NotFound => 8009, "error.not_found";
+    NoEffect => 8010, "error.no_effect";
+    FailedValidation { error } => 8011, "error.failed_validation";
+    // ? Micro-service errors
+    ProxyError => 9000, "error.proxy_error";
+    FileTooSmall => 9001, "error.file_too_small";
+    FileTooLarge { max } => 9002, "error.file_too_large";
+    FileTypeNotAllowed => 9003, "error.file_type_not_allowed";
+    ImageProcessingFailed => 9004, "error.image_processing_failed";
+    NoEmbedData => 9005, "error.no_embed_data";
+    ArchiveTooLarge => 9006, "error.archive_too_large";
+    // ? Legacy errors
+    VosoUnavailable => 9500, "error.voso_unavailable";
+    // ? Feature errors
+    FeatureDisabled { feature } => 9900, "error.feature_disabled";
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::ERRORS;
+
+    #[test]
+    fn codes_and_keys_are_unique() {
+        let codes: HashSet<u32> = ERRORS.iter().map(|info| info.code).collect();
+        let keys: HashSet<&str> = ERRORS.iter().map(|info| info.key).collect();
+
+        assert_eq!(codes.len(), ERRORS.len());
+        assert_eq!(keys.len(), ERRORS.len());
+    }
+}
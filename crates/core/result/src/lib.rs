@@ -21,6 +21,9 @@ pub mod axum;
 #[cfg(feature = "okapi")]
 pub mod okapi;
 
+mod catalogue;
+pub use catalogue::{ErrorInfo, ERRORS};
+
 /// Result type with custom Error
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -34,6 +37,13 @@ pub struct Error {
     #[cfg_attr(feature = "serde", serde(flatten))]
     pub error_type: ErrorType,
 
+    /// Stable numeric code for this type of error
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub code: u32,
+    /// Localization key for a human-readable description of this error
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub key: String,
+
     /// Where this error occurred
     pub location: String,
 }
@@ -72,6 +82,23 @@ pub enum ErrorType {
     TooManyPendingFriendRequests {
         max: usize,
     },
+    TooManyApiTokens {
+        max: usize,
+    },
+    EmailInUse,
+    DeviceApprovalRequired,
+    TooManyRecoveryContacts {
+        max: usize,
+    },
+    RecoveryInProgress,
+    TooManyBookmarks {
+        max: usize,
+    },
+    BotMessagesBlocked,
+    TooManySessions {
+        max: usize,
+    },
+    OutdatedSettings,
 
     // ? Channel related errors
     UnknownChannel,
@@ -92,7 +119,9 @@ pub enum ErrorType {
         max: usize,
     },
     EmptyMessage,
-    PayloadTooLarge,
+    PayloadTooLarge {
+        max: usize,
+    },
     CannotRemoveYourself,
     GroupTooLarge {
         max: usize,
@@ -101,6 +130,29 @@ pub enum ErrorType {
     NotInGroup,
     AlreadyPinned,
     NotPinned,
+    MentionConfirmationRequired {
+        token: String,
+        recipients: usize,
+    },
+    InvalidProvenance,
+    NewMemberRestricted,
+    NewMemberSlowmode {
+        retry_after: u64,
+    },
+    AttachmentTypeNotAllowed {
+        allowed: Vec<String>,
+    },
+    TooManyMentions {
+        max: usize,
+    },
+    TooManyBurstReactions {
+        max: usize,
+    },
+    MessageFiltered,
+    ThreadArchived,
+    ChannelSlowmode {
+        retry_after: u64,
+    },
 
     // ? Server related errors
     UnknownServer,
@@ -115,14 +167,23 @@ pub enum ErrorType {
     TooManyRoles {
         max: usize,
     },
+    TooManyBadges {
+        max: usize,
+    },
     AlreadyInServer,
     CannotTimeoutYourself,
+    ServerTagTaken,
+    ApprovalRequired {
+        id: String,
+    },
+    ApplicationPending,
 
     // ? Bot related errors
     ReachedMaximumBots,
     IsBot,
     IsNotBot,
     BotIsPrivate,
+    InteractionExpired,
 
     // ? User safety related errors
     CannotReportYourself,
@@ -167,6 +228,7 @@ pub enum ErrorType {
     FileTypeNotAllowed,
     ImageProcessingFailed,
     NoEmbedData,
+    ArchiveTooLarge,
 
     // ? Legacy errors
     VosoUnavailable,
@@ -179,12 +241,15 @@ pub enum ErrorType {
 
 #[macro_export]
 macro_rules! create_error {
-    ( $error: ident $( $tt:tt )? ) => {
+    ( $error: ident $( $tt:tt )? ) => {{
+        let error_type = $crate::ErrorType::$error $( $tt )?;
         $crate::Error {
-            error_type: $crate::ErrorType::$error $( $tt )?,
+            code: error_type.code(),
+            key: error_type.key().to_string(),
+            error_type,
             location: format!("{}:{}:{}", file!(), line!(), column!()),
         }
-    };
+    }};
 }
 
 #[macro_export]
@@ -207,6 +272,13 @@ mod tests {
         assert!(matches!(error.error_type, ErrorType::LabelMe));
     }
 
+    #[test]
+    fn use_macro_to_attach_code_and_key() {
+        let error = create_error!(TooManyEmbeds { max: 5 });
+        assert_eq!(error.code, 3006);
+        assert_eq!(error.key, "error.too_many_embeds");
+    }
+
     #[test]
     fn use_macro_to_construct_complex_error() {
         let error = create_error!(LabelMe);
@@ -26,6 +26,15 @@ impl<'r> Responder<'r, 'static> for Error {
             ErrorType::BlockedByOther => Status::Forbidden,
             ErrorType::NotFriends => Status::Forbidden,
             ErrorType::TooManyPendingFriendRequests { .. } => Status::BadRequest,
+            ErrorType::TooManyApiTokens { .. } => Status::BadRequest,
+            ErrorType::EmailInUse => Status::Conflict,
+            ErrorType::DeviceApprovalRequired => Status::Forbidden,
+            ErrorType::TooManyRecoveryContacts { .. } => Status::BadRequest,
+            ErrorType::RecoveryInProgress => Status::Conflict,
+            ErrorType::TooManyBookmarks { .. } => Status::BadRequest,
+            ErrorType::BotMessagesBlocked => Status::Forbidden,
+            ErrorType::TooManySessions { .. } => Status::TooManyRequests,
+            ErrorType::OutdatedSettings => Status::Conflict,
 
             ErrorType::UnknownChannel => Status::NotFound,
             ErrorType::UnknownMessage => Status::NotFound,
@@ -35,13 +44,23 @@ impl<'r> Responder<'r, 'static> for Error {
             ErrorType::TooManyAttachments { .. } => Status::BadRequest,
             ErrorType::TooManyReplies { .. } => Status::BadRequest,
             ErrorType::EmptyMessage => Status::UnprocessableEntity,
-            ErrorType::PayloadTooLarge => Status::UnprocessableEntity,
+            ErrorType::PayloadTooLarge { .. } => Status::UnprocessableEntity,
             ErrorType::CannotRemoveYourself => Status::BadRequest,
             ErrorType::GroupTooLarge { .. } => Status::Forbidden,
             ErrorType::AlreadyInGroup => Status::Conflict,
             ErrorType::NotInGroup => Status::NotFound,
             ErrorType::AlreadyPinned => Status::BadRequest,
             ErrorType::NotPinned => Status::BadRequest,
+            ErrorType::MentionConfirmationRequired { .. } => Status::PreconditionRequired,
+            ErrorType::InvalidProvenance => Status::BadRequest,
+            ErrorType::NewMemberRestricted => Status::Forbidden,
+            ErrorType::NewMemberSlowmode { .. } => Status::TooManyRequests,
+            ErrorType::AttachmentTypeNotAllowed { .. } => Status::BadRequest,
+            ErrorType::TooManyMentions { .. } => Status::BadRequest,
+            ErrorType::TooManyBurstReactions { .. } => Status::TooManyRequests,
+            ErrorType::MessageFiltered => Status::BadRequest,
+            ErrorType::ThreadArchived => Status::Forbidden,
+            ErrorType::ChannelSlowmode { .. } => Status::TooManyRequests,
             ErrorType::InvalidFlagValue => Status::BadRequest,
 
             ErrorType::UnknownServer => Status::NotFound,
@@ -49,17 +68,22 @@ impl<'r> Responder<'r, 'static> for Error {
             ErrorType::Banned => Status::Forbidden,
             ErrorType::AlreadyInServer => Status::Conflict,
             ErrorType::CannotTimeoutYourself => Status::BadRequest,
+            ErrorType::ServerTagTaken => Status::Conflict,
+            ErrorType::ApprovalRequired { .. } => Status::Accepted,
+            ErrorType::ApplicationPending => Status::Conflict,
 
             ErrorType::TooManyServers { .. } => Status::BadRequest,
             ErrorType::TooManyEmbeds { .. } => Status::BadRequest,
             ErrorType::TooManyEmoji { .. } => Status::BadRequest,
             ErrorType::TooManyChannels { .. } => Status::BadRequest,
             ErrorType::TooManyRoles { .. } => Status::BadRequest,
+            ErrorType::TooManyBadges { .. } => Status::BadRequest,
 
             ErrorType::ReachedMaximumBots => Status::BadRequest,
             ErrorType::IsBot => Status::BadRequest,
             ErrorType::IsNotBot => Status::BadRequest,
             ErrorType::BotIsPrivate => Status::Forbidden,
+            ErrorType::InteractionExpired => Status::Gone,
 
             ErrorType::CannotReportYourself => Status::BadRequest,
 
@@ -90,6 +114,7 @@ impl<'r> Responder<'r, 'static> for Error {
             ErrorType::FileTypeNotAllowed => Status::BadRequest,
             ErrorType::ImageProcessingFailed => Status::InternalServerError,
             ErrorType::NoEmbedData => Status::BadRequest,
+            ErrorType::ArchiveTooLarge => Status::UnprocessableEntity,
         };
 
         // Serialize the error data structure into JSON.
@@ -1,19 +1,70 @@
-use guilderia_config::configure;
+use guilderia_config::{config, configure, doctor};
 use guilderia_database::DatabaseInfo;
 use guilderia_result::Result;
-use tasks::{file_deletion, prune_dangling_files};
-use tokio::try_join;
+use log::{info, warn};
+use tasks::{
+    expire_messages, expire_roles, file_deletion, minimise_user_data, prune_dangling_files,
+    purge_screening_responses, reconcile_server_counts, reconcile_unread_counts, rescan_files,
+    run_purge_schedules, sync_ban_subscriptions,
+};
+use tokio::{
+    select,
+    signal::unix::{signal, SignalKind},
+    time::timeout,
+    try_join,
+};
 
 pub mod tasks;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Check the configuration and exit if asked to
+    if doctor::requested() {
+        std::process::exit(doctor::run(doctor::Service::Crond).await);
+    }
+
     configure!(crond);
 
     let db = DatabaseInfo::Auto.connect().await.expect("database");
-    try_join!(
-        file_deletion::task(db.clone()),
-        prune_dangling_files::task(db)
-    )
-    .map(|_| ())
+    select! {
+        result = async {
+            try_join!(
+                file_deletion::task(db.clone()),
+                prune_dangling_files::task(db.clone()),
+                reconcile_server_counts::task(db.clone()),
+                expire_roles::task(db.clone()),
+                expire_messages::task(db.clone()),
+                purge_screening_responses::task(db.clone()),
+                reconcile_unread_counts::task(db.clone()),
+                rescan_files::task(db.clone()),
+                minimise_user_data::task(db.clone()),
+                sync_ban_subscriptions::task(db.clone()),
+                run_purge_schedules::task(db.clone())
+            )
+        } => {
+            result?;
+        }
+        _ = shutdown_signal() => {
+            info!("Shutting down");
+        }
+    }
+
+    if timeout(config().await.shutdown.deadline(), db.shutdown())
+        .await
+        .is_err()
+    {
+        warn!("Shutdown deadline elapsed before the database connection was closed");
+    }
+
+    Ok(())
+}
+
+/// Wait until the process is asked to stop
+async fn shutdown_signal() {
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+
+    select! {
+        _ = tokio::signal::ctrl_c() => {},
+        _ = terminate.recv() => {},
+    }
 }
@@ -0,0 +1,46 @@
+use std::{collections::HashMap, time::Duration};
+
+use guilderia_database::{Database, Message};
+use guilderia_result::Result;
+use tokio::time::sleep;
+
+use log::{info, warn};
+
+/// Number of expired messages deleted at once
+const BATCH_SIZE: i64 = 100;
+
+pub async fn task(db: Database) -> Result<()> {
+    loop {
+        loop {
+            let messages = db.fetch_expired_messages(BATCH_SIZE).await?;
+            let exhausted = (messages.len() as i64) < BATCH_SIZE;
+
+            let mut channels: HashMap<String, Vec<Message>> = HashMap::new();
+            for message in messages {
+                channels
+                    .entry(message.channel.clone())
+                    .or_default()
+                    .push(message);
+            }
+
+            // Try again later rather than fetching the same batch over and over
+            let mut failed = false;
+            for (channel, messages) in channels {
+                let count = messages.len();
+                match Message::delete_expired(&db, &channel, messages).await {
+                    Ok(()) => info!("Deleted {count} expired message(s) from {channel}"),
+                    Err(err) => {
+                        warn!("Failed to delete expired messages from {channel}: {err:?}");
+                        failed = true;
+                    }
+                }
+            }
+
+            if exhausted || failed {
+                break;
+            }
+        }
+
+        sleep(Duration::from_secs(60)).await;
+    }
+}
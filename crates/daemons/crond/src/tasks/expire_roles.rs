@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use guilderia_database::{Database, SystemMessage};
+use guilderia_result::Result;
+use tokio::time::sleep;
+
+use log::{info, warn};
+
+pub async fn task(db: Database) -> Result<()> {
+    loop {
+        for mut member in db.fetch_members_with_expired_roles().await? {
+            let server = match db.fetch_server(&member.id.server).await {
+                Ok(server) => server,
+                Err(err) => {
+                    warn!("Failed to fetch server {}: {err:?}", member.id.server);
+                    continue;
+                }
+            };
+
+            let removed = match member.remove_expired_roles(&db).await {
+                Ok(removed) => removed,
+                Err(err) => {
+                    warn!(
+                        "Failed to remove expired roles from {} in {}: {err:?}",
+                        member.id.user, server.id
+                    );
+                    continue;
+                }
+            };
+
+            if removed.is_empty() {
+                continue;
+            }
+
+            info!(
+                "Removed {} expired role(s) from {} in {}",
+                removed.len(),
+                member.id.user,
+                server.id
+            );
+
+            // Leave a record of the removal for the server's moderators
+            if let Some(id) = server
+                .system_messages
+                .as_ref()
+                .and_then(|x| x.moderation_alerts.as_ref())
+            {
+                let names: Vec<&str> = removed
+                    .iter()
+                    .filter_map(|role| server.roles.get(role))
+                    .map(|role| role.name.as_str())
+                    .collect();
+
+                SystemMessage::Text {
+                    content: format!(
+                        "Removed expired role(s) from <@{}>: {}",
+                        member.id.user,
+                        names.join(", ")
+                    ),
+                }
+                .into_message(id.to_string())
+                .send_without_notifications(&db, None, None, false, false, false)
+                .await
+                .ok();
+            }
+        }
+
+        sleep(Duration::from_secs(60)).await;
+    }
+}
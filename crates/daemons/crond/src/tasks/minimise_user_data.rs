@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+use guilderia_database::{
+    util::data_minimisation, DataMinimisation, Database, DATA_MINIMISATION_KEY,
+};
+use guilderia_result::Result;
+use tokio::time::sleep;
+
+use log::{info, warn};
+
+pub async fn task(db: Database) -> Result<()> {
+    loop {
+        // Users who opted into data minimisation have their old direct
+        // messages and attachments removed, a bounded amount per run.
+        let settings = db.fetch_users_with_setting(DATA_MINIMISATION_KEY).await?;
+        for (user, value) in settings {
+            let Some(settings) =
+                DataMinimisation::parse(&value).filter(DataMinimisation::is_enabled)
+            else {
+                continue;
+            };
+
+            match data_minimisation::run(&db, &user, &settings).await {
+                Ok(status) => {
+                    if let Some(error) = status.error {
+                        warn!("Data minimisation for {user} stopped early: {error}");
+                    } else if status.messages_deleted > 0 || status.attachments_removed > 0 {
+                        info!(
+                            "Data minimisation for {user} deleted {} messages and removed {} attachments",
+                            status.messages_deleted, status.attachments_removed
+                        );
+                    }
+                }
+                Err(err) => warn!("Failed to record data minimisation for {user}: {err:?}"),
+            }
+        }
+
+        sleep(Duration::from_secs(60 * 60)).await;
+    }
+}
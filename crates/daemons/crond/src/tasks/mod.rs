@@ -1,2 +1,11 @@
+pub mod expire_messages;
+pub mod expire_roles;
 pub mod file_deletion;
+pub mod minimise_user_data;
 pub mod prune_dangling_files;
+pub mod purge_screening_responses;
+pub mod reconcile_server_counts;
+pub mod reconcile_unread_counts;
+pub mod rescan_files;
+pub mod run_purge_schedules;
+pub mod sync_ban_subscriptions;
@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use guilderia_config::config;
+use guilderia_database::{
+    iso8601_timestamp::{self, Timestamp},
+    Database,
+};
+use guilderia_result::Result;
+use tokio::time::sleep;
+
+use log::info;
+
+pub async fn task(db: Database) -> Result<()> {
+    loop {
+        // Answers of members who left are kept around for a while
+        // so moderators can review them, then purged for good.
+        let days = config().await.features.screening_response_retention_days;
+        if days > 0 {
+            let cutoff = Timestamp::now_utc()
+                .checked_sub(iso8601_timestamp::Duration::days(days as i64))
+                .unwrap_or(Timestamp::UNIX_EPOCH);
+
+            db.delete_screening_responses_left_before(cutoff).await?;
+            info!("Purged screening responses retained for over {days} days");
+        }
+
+        sleep(Duration::from_secs(60 * 60)).await;
+    }
+}
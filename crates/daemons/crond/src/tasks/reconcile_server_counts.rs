@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use guilderia_database::Database;
+use guilderia_result::Result;
+use tokio::time::sleep;
+
+use log::{info, warn};
+
+pub async fn task(db: Database) -> Result<()> {
+    loop {
+        // Member and online counts are maintained incrementally,
+        // but may drift if a node dies mid-update, so periodically
+        // recount them from scratch.
+        let server_ids = db.fetch_all_server_ids().await?;
+        for id in &server_ids {
+            match db.fetch_server(id).await {
+                Ok(server) => {
+                    if let Err(err) = server.reconcile_counts(&db).await {
+                        warn!("Failed to reconcile counts for server {id}: {err:?}");
+                    }
+                }
+                Err(err) => warn!("Failed to fetch server {id}: {err:?}"),
+            }
+        }
+
+        info!("Reconciled member counts for {} servers", server_ids.len());
+        sleep(Duration::from_secs(60 * 60)).await;
+    }
+}
@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use guilderia_database::Database;
+use guilderia_result::Result;
+use tokio::time::sleep;
+
+use log::{info, warn};
+
+/// Number of unreads to recount at a time
+const BATCH_SIZE: i64 = 500;
+
+pub async fn task(db: Database) -> Result<()> {
+    loop {
+        // Unread counts are maintained as messages are sent and acknowledged,
+        // but are dropped when messages are removed in bulk and are missing on
+        // unreads from before they were tracked, so recount those here.
+        let unreads = db.fetch_unreads_without_counts(BATCH_SIZE).await?;
+        let mut reconciled = 0;
+        for unread in &unreads {
+            if let Err(err) = unread.reconcile_count(&db).await {
+                warn!(
+                    "Failed to reconcile unread count for {} in {}: {err:?}",
+                    unread.id.user, unread.id.channel
+                );
+            } else {
+                reconciled += 1;
+            }
+        }
+
+        if !unreads.is_empty() {
+            info!("Reconciled {reconciled} unread counts");
+        }
+
+        // Keep going while there is a backlog and progress is being made
+        if unreads.len() < BATCH_SIZE as usize || reconciled == 0 {
+            sleep(Duration::from_secs(60 * 10)).await;
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use guilderia_config::config;
+use guilderia_database::{iso8601_timestamp::Timestamp, Database, File, FileHash, FileScan};
+use guilderia_files::{clamav, fetch_from_s3};
+use guilderia_result::Result;
+use tokio::time::sleep;
+
+use log::{info, warn};
+
+/// Number of files to rescan at a time
+const BATCH_SIZE: i64 = 50;
+
+/// Scan a stored file again and record the verdict
+async fn rescan(db: &Database, hash: &FileHash) -> Result<Option<FileScan>> {
+    let data = fetch_from_s3(&hash.bucket_id, &hash.path, &hash.iv).await?;
+    let Some(scan) = clamav::scan(&data).await? else {
+        return Ok(None);
+    };
+
+    let scan = FileScan {
+        signature: scan.signature,
+        engine: scan.version.engine,
+        signatures: scan.version.signatures,
+        scanned_at: Timestamp::now_utc(),
+    };
+
+    db.set_attachment_hash_scan(&hash.id, &scan).await?;
+    db.set_attachments_scan(&hash.id, &scan).await?;
+    if !scan.is_clean() {
+        File::quarantine_hash(db, &hash.id).await?;
+    }
+
+    Ok(Some(scan))
+}
+
+pub async fn task(db: Database) -> Result<()> {
+    loop {
+        // Files are scanned on upload, once the signature database has moved
+        // far enough ahead since then, scan them again to catch new threats.
+        let threshold = config().await.files.rescan_signature_threshold;
+        let version = if threshold > 0 {
+            clamav::version().await.unwrap_or_else(|err| {
+                warn!("Failed to fetch clamd version: {err:?}");
+                None
+            })
+        } else {
+            None
+        };
+
+        let hashes = if let Some(version) = &version {
+            db.fetch_attachment_hashes_scanned_before(
+                version.signatures.saturating_sub(threshold),
+                BATCH_SIZE,
+            )
+            .await?
+        } else {
+            vec![]
+        };
+
+        let mut rescanned = 0;
+        for hash in &hashes {
+            match rescan(&db, hash).await {
+                Ok(Some(scan)) => {
+                    rescanned += 1;
+                    if let Some(signature) = scan.signature {
+                        warn!("File hash {} is infected with {signature}", hash.id);
+                    }
+                }
+                Ok(None) => break,
+                Err(err) => warn!("Failed to rescan file hash {}: {err:?}", hash.id),
+            }
+        }
+
+        if !hashes.is_empty() {
+            info!("Rescanned {rescanned} files");
+        }
+
+        // Keep going while there is a backlog and progress is being made
+        if hashes.len() < BATCH_SIZE as usize || rescanned == 0 {
+            sleep(Duration::from_secs(60 * 10)).await;
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use guilderia_database::Database;
+use guilderia_result::{ErrorType, Result};
+use tokio::time::sleep;
+
+use log::{info, warn};
+
+pub async fn task(db: Database) -> Result<()> {
+    loop {
+        for mut schedule in db.fetch_due_purge_schedules().await? {
+            let channel = match db.fetch_channel(&schedule.id).await {
+                Ok(channel) => channel,
+                // Channel no longer exists
+                Err(err) => {
+                    if matches!(err.error_type, ErrorType::NotFound) {
+                        if let Err(err) = db.delete_purge_schedule(&schedule.id).await {
+                            warn!("Failed to delete purge schedule {}: {err:?}", schedule.id);
+                        }
+                    }
+
+                    continue;
+                }
+            };
+
+            // Creator is no longer allowed to purge the channel
+            match schedule.creator_may_purge(&db, &channel).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    info!(
+                        "Disabling purge schedule {}, {} lost permission",
+                        schedule.id, schedule.creator
+                    );
+
+                    if let Err(err) = db.delete_purge_schedule(&schedule.id).await {
+                        warn!("Failed to delete purge schedule {}: {err:?}", schedule.id);
+                    }
+
+                    continue;
+                }
+                Err(err) => {
+                    warn!("Failed to check permissions for {}: {err:?}", schedule.id);
+                    continue;
+                }
+            }
+
+            match schedule.run(&db).await {
+                Ok(count) => info!("Purged {count} message(s) from {}", schedule.id),
+                Err(err) => warn!("Failed to purge {}: {err:?}", schedule.id),
+            }
+        }
+
+        sleep(Duration::from_secs(60)).await;
+    }
+}
@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use guilderia_database::Database;
+use guilderia_result::{ErrorType, Result};
+use tokio::time::sleep;
+
+use log::{info, warn};
+
+pub async fn task(db: Database) -> Result<()> {
+    loop {
+        for mut subscription in db.fetch_all_ban_subscriptions().await? {
+            match subscription.sync(&db).await {
+                Ok(0) => {}
+                Ok(count) => info!(
+                    "Synced {count} ban(s) from {} to {}",
+                    subscription.source, subscription.server
+                ),
+                // Creator may no longer ban members
+                Err(err) if matches!(err.error_type, ErrorType::MissingPermission { .. }) => {
+                    info!(
+                        "Dropping ban subscription {}, {} lost permission",
+                        subscription.id, subscription.creator
+                    );
+
+                    if let Err(err) = db.delete_ban_subscription(&subscription.id).await {
+                        warn!(
+                            "Failed to delete ban subscription {}: {err:?}",
+                            subscription.id
+                        );
+                    }
+                }
+                // Either server or the creator no longer exists
+                Err(err) if matches!(err.error_type, ErrorType::NotFound) => {
+                    if let Err(err) = db.delete_ban_subscription(&subscription.id).await {
+                        warn!(
+                            "Failed to delete ban subscription {}: {err:?}",
+                            subscription.id
+                        );
+                    }
+                }
+                Err(err) => warn!(
+                    "Failed to sync ban subscription {}: {err:?}",
+                    subscription.id
+                ),
+            }
+        }
+
+        sleep(Duration::from_secs(60)).await;
+    }
+}
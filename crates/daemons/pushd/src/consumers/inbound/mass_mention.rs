@@ -14,7 +14,8 @@ use anyhow::Result;
 use async_trait::async_trait;
 use guilderia_database::{
     events::rabbit::*, util::bulk_permissions::BulkDatabasePermissionQuery, Database, Member,
-    MessageFlagsValue,
+    Message, MessageFlagsValue, NotificationOverrides, NotificationSummary, QuietHours,
+    NOTIFICATIONS_KEY,
 };
 use revolt_models::v0::{MessageFlags, PushNotification};
 
@@ -67,9 +68,18 @@ impl MassMessageConsumer {
         push: &PushNotification,
         users: &[String],
     ) -> Result<()> {
+        let mut targets = Vec::with_capacity(users.len());
+        for user in users {
+            if !NotificationSummary::suppresses(&self.db, user, push).await {
+                targets.push(user.clone());
+            }
+        }
+
+        let targets = QuietHours::filter(&self.db, targets, push).await;
+
         if let Ok(sessions) = self
             .authifier_db
-            .find_sessions_with_subscription(users)
+            .find_sessions_with_subscription(&targets)
             .await
         {
             let config = guilderia_config::config().await;
@@ -133,6 +143,9 @@ impl MassMessageConsumer {
 
         debug!("Received mass message event");
 
+        let server = self.db.fetch_server(&payload.server_id).await.ok();
+        let notify_all = Message::notifies_all_members(server.as_ref());
+
         // We should only ever receive clumped messages from a single channel, so it's safe to reuse this many times.
         let mut query: Option<BulkDatabasePermissionQuery<'_>> = None;
         let query_db = self.db.clone();
@@ -211,56 +224,137 @@ impl MassMessageConsumer {
                             break;
                         }
                     }
-                } else if let Some(roles) = &push.message.role_mentions {
-                    // role mentions
-                    let mut role_members = self
-                        .db
-                        .fetch_all_members_with_roles_chunked(&payload.server_id, roles)
-                        .await?;
+                } else {
+                    // members who were already considered for a role mention
+                    let mut notified: HashSet<String> = HashSet::new();
 
-                    let mut chunk = vec![];
-                    let mut exhausted = false;
+                    if let Some(roles) = &push.message.role_mentions {
+                        // role mentions
+                        let mut role_members = self
+                            .db
+                            .fetch_all_members_with_roles_chunked(&payload.server_id, roles)
+                            .await?;
 
-                    while !exhausted {
-                        chunk.clear();
+                        let mut chunk = vec![];
+                        let mut exhausted = false;
 
-                        for _ in 0..config.pushd.mass_mention_chunk_size {
-                            if let Some(member) = role_members.next().await {
-                                chunk.push(member);
-                            } else {
-                                exhausted = true;
-                                break;
-                            }
-                        }
+                        while !exhausted {
+                            chunk.clear();
 
-                        let mut q = query.clone().members(&chunk);
-                        let viewing_members: Vec<String> = q
-                            .members_can_see_channel()
-                            .await
-                            .iter()
-                            .filter_map(|(uid, viewable)| {
-                                if *viewable && !existing_mentions.contains(uid) {
-                                    Some(uid.clone())
+                            for _ in 0..config.pushd.mass_mention_chunk_size {
+                                if let Some(member) = role_members.next().await {
+                                    chunk.push(member);
                                 } else {
-                                    None
+                                    exhausted = true;
+                                    break;
                                 }
-                            })
-                            .collect();
+                            }
 
-                        debug!("viewing members: {:?}", viewing_members);
+                            // drop anyone who has opted out of every mentioned role they hold
+                            chunk.retain(|member| member.is_notified_by_role_mentions(roles));
+
+                            let mut q = query.clone().members(&chunk);
+                            let viewing_members: Vec<String> = q
+                                .members_can_see_channel()
+                                .await
+                                .iter()
+                                .filter_map(|(uid, viewable)| {
+                                    if *viewable && !existing_mentions.contains(uid) {
+                                        Some(uid.clone())
+                                    } else {
+                                        None
+                                    }
+                                })
+                                .collect();
+
+                            debug!("viewing members: {:?}", viewing_members);
+                            notified.extend(viewing_members.iter().cloned());
+
+                            let online = revolt_presence::filter_online(&viewing_members).await;
+                            debug!("online: {:?}", online);
+
+                            let targets: Vec<String> = viewing_members
+                                .iter()
+                                .filter(|m| !online.contains(*m))
+                                .cloned()
+                                .collect();
+
+                            debug!("targets: {:?}", targets);
+
+                            self.fire_notification_for_users(&push, &targets).await?;
+                        }
+                    }
 
-                        let online = revolt_presence::filter_online(&viewing_members).await;
-                        debug!("online: {:?}", online);
+                    if notify_all {
+                        // server notifies members of every message by default,
+                        // anyone reached through a role mention has been handled above
+                        let mut members = self
+                            .db
+                            .fetch_all_members_chunked(&payload.server_id)
+                            .await?;
 
-                        let targets: Vec<String> = viewing_members
-                            .iter()
-                            .filter(|m| !online.contains(*m))
-                            .cloned()
-                            .collect();
+                        let mut chunk = vec![];
+                        let mut exhausted = false;
+
+                        while !exhausted {
+                            chunk.clear();
 
-                        debug!("targets: {:?}", targets);
+                            for _ in 0..config.pushd.mass_mention_chunk_size {
+                                if let Some(member) = members.next().await {
+                                    chunk.push(member);
+                                } else {
+                                    exhausted = true;
+                                    break;
+                                }
+                            }
 
-                        self.fire_notification_for_users(&push, &targets).await?;
+                            let mut q = query.clone().members(&chunk);
+                            let viewing_members: Vec<String> = q
+                                .members_can_see_channel()
+                                .await
+                                .iter()
+                                .filter_map(|(uid, viewable)| {
+                                    if *viewable
+                                        && !existing_mentions.contains(uid)
+                                        && !notified.contains(uid)
+                                        && uid != &push.message.author
+                                    {
+                                        Some(uid.clone())
+                                    } else {
+                                        None
+                                    }
+                                })
+                                .collect();
+
+                            let online = revolt_presence::filter_online(&viewing_members).await;
+                            let offline: Vec<String> = viewing_members
+                                .into_iter()
+                                .filter(|user| !online.contains(user))
+                                .collect();
+
+                            // respect any mode the member picked for this server or channel
+                            let overrides = self
+                                .db
+                                .fetch_setting_for_users(NOTIFICATIONS_KEY, &offline)
+                                .await
+                                .unwrap_or_default();
+
+                            let targets: Vec<String> = offline
+                                .into_iter()
+                                .filter(|user| {
+                                    overrides
+                                        .get(user)
+                                        .map(|value| NotificationOverrides::parse(value))
+                                        .unwrap_or_default()
+                                        .wants_all_messages(&payload.server_id, push.channel.id())
+                                        .unwrap_or(true)
+                                })
+                                .collect();
+
+                            debug!("targets: {:?}", targets);
+
+                            self.fire_notification_for_users(&push, &targets).await?;
+                        }
                     }
                 }
             }
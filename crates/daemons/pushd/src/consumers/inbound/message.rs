@@ -10,10 +10,11 @@ use amqprs::{
 use anyhow::Result;
 use async_trait::async_trait;
 use log::debug;
-use guilderia_database::{events::rabbit::*, Database};
+use guilderia_database::{
+    events::rabbit::*, util::locale::Locale, Database, NotificationSummary, QuietHours,
+};
 
 pub struct MessageConsumer {
-    #[allow(dead_code)]
     db: Database,
     authifier_db: authifier::Database,
     conn: Option<Connection>,
@@ -68,18 +69,44 @@ impl MessageConsumer {
 
         debug!("Received message event on origin");
 
+        let mut users = Vec::with_capacity(payload.users.len());
+        for user in payload.users {
+            if !NotificationSummary::suppresses(&self.db, &user, &payload.notification).await {
+                users.push(user);
+            }
+        }
+
+        let users = QuietHours::filter(&self.db, users, &payload.notification).await;
+
+        let config = guilderia_config::config().await;
+
+        // System messages are summarised in each recipient's own language
+        let mut bodies = HashMap::new();
+        if let Some(system) = &payload.notification.message.system {
+            for user in &users {
+                bodies.insert(
+                    user.clone(),
+                    Locale::for_user(&self.db, user)
+                        .await
+                        .translate_system_message(&config.localization, system),
+                );
+            }
+        }
+
         if let Ok(sessions) = self
             .authifier_db
-            .find_sessions_with_subscription(&payload.users)
+            .find_sessions_with_subscription(&users)
             .await
         {
-            let config = guilderia_config::config().await;
             for session in sessions {
                 if let Some(sub) = session.subscription {
+                    let mut notification = payload.notification.clone();
+                    if let Some(body) = bodies.get(&session.user_id) {
+                        notification.body = body.clone();
+                    }
+
                     let mut sendable = PayloadToService {
-                        notification: PayloadKind::MessageNotification(
-                            payload.notification.clone(),
-                        ),
+                        notification: PayloadKind::MessageNotification(notification),
                         token: sub.auth,
                         user_id: session.user_id,
                         session_id: session.id,
@@ -8,7 +8,7 @@ use base64::{
     engine::{self},
     Engine as _,
 };
-use guilderia_database::{events::rabbit::*, Database};
+use guilderia_database::{events::rabbit::*, util::locale::Locale, Database};
 use web_push::{
     ContentEncoding, IsahcWebPushClient, SubscriptionInfo, SubscriptionKeys, VapidSignatureBuilder,
     WebPushClient, WebPushError, WebPushMessageBuilder,
@@ -80,8 +80,19 @@ impl VapidOutboundConsumer {
                     .clone()
                     .ok_or_else(|| anyhow!("missing name"))?;
 
+                let localization = revolt_config::config().await.localization;
                 let mut body = HashMap::new();
-                body.insert("body", format!("{} sent you a friend request", name));
+                body.insert(
+                    "body",
+                    Locale::for_user(&self.db, &payload.user_id)
+                        .await
+                        .translate(
+                            &localization,
+                            "push.fr.received",
+                            "{name} sent you a friend request",
+                            &[("name", &name)],
+                        ),
+                );
 
                 payload_body = serde_json::to_string(&body)?;
             }
@@ -96,8 +107,19 @@ impl VapidOutboundConsumer {
                     .clone()
                     .ok_or_else(|| anyhow!("missing name"))?;
 
+                let localization = revolt_config::config().await.localization;
                 let mut body = HashMap::new();
-                body.insert("body", format!("{} accepted your friend request", name));
+                body.insert(
+                    "body",
+                    Locale::for_user(&self.db, &payload.user_id)
+                        .await
+                        .translate(
+                            &localization,
+                            "push.fr.accepted",
+                            "{name} accepted your friend request",
+                            &[("name", &name)],
+                        ),
+                );
 
                 payload_body = serde_json::to_string(&body)?;
             }
@@ -123,7 +145,18 @@ impl VapidOutboundConsumer {
                     match builder.build() {
                         Ok(msg) => {
                             if let Err(err) = self.client.send(msg).await {
-                                if err == WebPushError::Unauthorized {
+                                // Prune subscriptions the push service no longer accepts
+                                if matches!(
+                                    err,
+                                    WebPushError::Unauthorized
+                                        | WebPushError::EndpointNotFound
+                                        | WebPushError::EndpointNotValid
+                                ) {
+                                    info!(
+                                        "Removing Web Push subscription for session {} ({err:?})",
+                                        payload.session_id
+                                    );
+
                                     self.db
                                         .remove_push_subscription_by_session_id(&payload.session_id)
                                         .await?;
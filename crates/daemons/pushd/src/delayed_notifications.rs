@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use amqprs::{
+    channel::{BasicPublishArguments, Channel},
+    connection::{Connection, OpenConnectionArguments},
+    BasicProperties,
+};
+use guilderia_database::{events::rabbit::MessageSentPayload, Database};
+
+/// How often to look for due notifications once none are left
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long to wait before reconnecting to RabbitMQ
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long a claimed notification is held before another worker may retry it
+const CLAIM_LEASE: i64 = 60;
+
+/// Open a channel to RabbitMQ, retrying until one is available
+async fn connect() -> (Connection, Channel) {
+    let config = guilderia_config::config().await;
+
+    loop {
+        match Connection::open(&OpenConnectionArguments::new(
+            &config.rabbit.host,
+            config.rabbit.port,
+            &config.rabbit.username,
+            &config.rabbit.password,
+        ))
+        .await
+        {
+            Ok(connection) => match connection.open_channel(None).await {
+                Ok(channel) => return (connection, channel),
+                Err(err) => error!("Failed to open channel for delayed notifications: {err:?}"),
+            },
+            Err(err) => error!("Failed to connect for delayed notifications: {err:?}"),
+        }
+
+        tokio::time::sleep(RETRY_INTERVAL).await;
+    }
+}
+
+/// Send notifications which were held back until the end of quiet hours
+///
+/// Due notifications are queued as regular message notifications, so they
+/// pass through every other check again before being delivered. Each one is
+/// only deleted once it was published, otherwise it is retried after its lease.
+pub async fn task(db: Database) {
+    let config = guilderia_config::config().await;
+    let routing_key = config.pushd.get_message_routing_key();
+    let lease = iso8601_timestamp::Duration::seconds(CLAIM_LEASE);
+
+    loop {
+        let (_connection, channel) = connect().await;
+
+        loop {
+            let delayed = match db.claim_due_delayed_notification(lease).await {
+                Ok(Some(delayed)) => delayed,
+                Ok(None) => {
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                    continue;
+                }
+                Err(err) => {
+                    guilderia_config::capture_error(&err);
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                    continue;
+                }
+            };
+
+            let id = delayed.id;
+            let payload = MessageSentPayload {
+                notification: delayed.notification,
+                users: vec![delayed.user],
+            };
+
+            let Ok(payload) = serde_json::to_string(&payload) else {
+                warn!("Dropping delayed notification {id} which could not be serialised");
+                db.delete_delayed_notification(&id).await.ok();
+                continue;
+            };
+
+            if let Err(err) = channel
+                .basic_publish(
+                    BasicProperties::default(),
+                    payload.into(),
+                    BasicPublishArguments::new(&config.pushd.exchange, &routing_key),
+                )
+                .await
+            {
+                warn!("Failed to queue delayed notification, reconnecting: {err:?}");
+                break;
+            }
+
+            if let Err(err) = db.delete_delayed_notification(&id).await {
+                guilderia_config::capture_error(&err);
+            }
+        }
+    }
+}
@@ -11,9 +11,10 @@ use amqprs::{
     FieldTable,
 };
 use guilderia_config::{config, Settings};
-use tokio::sync::Notify;
+use tokio::signal::unix::{signal, SignalKind};
 
 mod consumers;
+mod delayed_notifications;
 use consumers::{
     inbound::{
         ack::AckConsumer, fr_accepted::FRAcceptedConsumer, fr_received::FRReceivedConsumer,
@@ -166,12 +167,37 @@ async fn main() {
         )
     }
 
-    let guard = Notify::new();
-    guard.notified().await;
+    // Deliver notifications held back until the end of quiet hours
+    tokio::spawn(delayed_notifications::task(db.clone()));
 
-    for (channel, conn) in connections {
-        channel.close().await.expect("Unable to close channel");
-        conn.close().await.expect("Unable to close connection");
+    shutdown_signal().await;
+    info!("Shutting down");
+
+    // Stop consuming and close connections, bounded by the shutdown deadline
+    let cleanup = async {
+        for (channel, conn) in connections {
+            channel.close().await.expect("Unable to close channel");
+            conn.close().await.expect("Unable to close connection");
+        }
+
+        db.shutdown().await;
+    };
+
+    if tokio::time::timeout(config.shutdown.deadline(), cleanup)
+        .await
+        .is_err()
+    {
+        warn!("Shutdown deadline elapsed before connections were closed");
+    }
+}
+
+/// Wait until the process is asked to stop
+async fn shutdown_signal() {
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {},
+        _ = terminate.recv() => {},
     }
 }
 
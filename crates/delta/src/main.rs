@@ -8,9 +8,10 @@ extern crate serde_json;
 pub mod routes;
 pub mod util;
 
-use guilderia_config::config;
+use guilderia_config::{config, doctor};
 use guilderia_database::events::client::EventV1;
-use guilderia_database::AMQP;
+use guilderia_database::{tasks, Database, AMQP};
+use rocket::fairing::AdHoc;
 use rocket::{Build, Rocket};
 use rocket_cors::{AllowedOrigins, CorsOptions};
 use rocket_prometheus::PrometheusMetrics;
@@ -121,33 +122,73 @@ pub async fn web() -> Rocket<Build> {
     // Configure Rocket
     let rocket = rocket::build();
     let prometheus = PrometheusMetrics::new();
+    prometheus
+        .registry()
+        .register(Box::new(util::task_metrics::TaskQueueCollector::default()))
+        .expect("Failed to register task queue metrics.");
 
     routes::mount(config, rocket)
         .attach(prometheus.clone())
         .mount("/metrics", prometheus)
         .mount("/", rocket_cors::catch_all_options_routes())
         .mount("/", util::ratelimiter::routes())
+        .mount("/", util::experiments::routes())
         .mount("/swagger/", swagger)
         .mount("/0.8/swagger/", swagger_0_8)
         .manage(authifier)
         .manage(db)
         .manage(amqp)
         .manage(cors.clone())
+        .attach(util::client_ip::ClientIpFairing)
         .attach(util::ratelimiter::RatelimitFairing)
+        .attach(util::experiments::ExperimentFairing)
         .attach(cors)
+        .attach(AdHoc::on_shutdown("Task Queues", |_| {
+            Box::pin(async { tasks::begin_shutdown() })
+        }))
         .configure(rocket::Config {
-            limits: rocket::data::Limits::default().limit("string", 5.megabytes()),
+            limits: rocket::data::Limits::default()
+                .limit("string", 5.megabytes())
+                .limit("json", config.api.body_limits.default.bytes()),
             address: Ipv4Addr::new(0, 0, 0, 0).into(),
             port: 14702,
+            // Client addresses are resolved from trusted proxies only
+            ip_header: None,
+            shutdown: rocket::config::Shutdown {
+                grace: config.shutdown.deadline_seconds as u32,
+                mercy: 0,
+                ..Default::default()
+            },
             ..Default::default()
         })
 }
 
-#[launch]
-async fn rocket() -> _ {
+#[rocket::main]
+async fn main() -> Result<(), rocket::Error> {
+    // Check the configuration and exit if asked to
+    if doctor::requested() {
+        std::process::exit(doctor::run(doctor::Service::Api).await);
+    }
+
     // Configure logging and environment
     guilderia_config::configure!(api);
 
-    // Start web server
-    web().await
+    // Start web server, this returns once Rocket has shut down
+    let rocket = web().await.launch().await?;
+
+    // Flush any work queued by in-flight requests
+    tasks::drain(config().await.shutdown.deadline()).await;
+
+    // Close connections
+    if let Some(amqp) = rocket.state::<AMQP>() {
+        if let Err(err) = amqp.clone().close().await {
+            log::error!("Failed to close RabbitMQ connection: {err:?}");
+        }
+    }
+
+    if let Some(db) = rocket.state::<Database>() {
+        db.clone().shutdown().await;
+    }
+
+    Ok(())
 }
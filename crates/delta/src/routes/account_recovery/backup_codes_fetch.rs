@@ -0,0 +1,32 @@
+use authifier::models::Account;
+use guilderia_database::Database;
+use guilderia_models::v0;
+use guilderia_result::{ErrorType, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Backup Code Status
+///
+/// Fetch how many backup codes are left and when they were last generated.
+#[openapi(tag = "Account")]
+#[get("/codes")]
+pub async fn fetch_backup_codes(
+    db: &State<Database>,
+    account: Account,
+) -> Result<Json<v0::BackupCodeStatus>> {
+    let remaining = account.mfa.recovery_codes.len();
+    Ok(Json(match db.fetch_backup_codes(&account.id).await {
+        Ok(codes) => v0::BackupCodeStatus {
+            remaining,
+            issued: codes.issued,
+            regenerations: codes.regenerations,
+            generated_at: Some(codes.generated_at),
+        },
+        Err(err) if matches!(err.error_type, ErrorType::NotFound) => v0::BackupCodeStatus {
+            remaining,
+            issued: remaining,
+            regenerations: 0,
+            generated_at: None,
+        },
+        Err(err) => return Err(err),
+    }))
+}
@@ -0,0 +1,27 @@
+use guilderia_database::{Database, User};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Accept Recovery Contact
+///
+/// Agree to help recover the account which nominated you.
+#[openapi(tag = "Account")]
+#[put("/contacts/<id>")]
+pub async fn accept_contact(
+    db: &State<Database>,
+    user: User,
+    id: String,
+) -> Result<Json<v0::RecoveryContact>> {
+    let mut contact = db.fetch_recovery_contact(&id).await?;
+    if contact.contact != user.id {
+        return Err(create_error!(NotFound));
+    }
+
+    if contact.accepted {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    contact.accept(db).await?;
+    Ok(Json(contact.into()))
+}
@@ -0,0 +1,23 @@
+use guilderia_database::{Database, RecoveryContact, User};
+use guilderia_models::v0;
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Nominate Recovery Contact
+///
+/// Ask a friend to help recover your account if you lose access to it.
+///
+/// They must accept before they can approve a recovery.
+#[openapi(tag = "Account")]
+#[post("/contacts", data = "<data>")]
+pub async fn create_contact(
+    db: &State<Database>,
+    user: User,
+    data: Json<v0::DataCreateRecoveryContact>,
+) -> Result<Json<v0::RecoveryContact>> {
+    let contact = db.fetch_user(&data.into_inner().user).await?;
+    RecoveryContact::create(db, &user, &contact)
+        .await
+        .map(Into::into)
+        .map(Json)
+}
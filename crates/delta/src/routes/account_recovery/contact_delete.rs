@@ -0,0 +1,28 @@
+use guilderia_database::{Database, User};
+use guilderia_result::{create_error, Result};
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Remove Recovery Contact
+///
+/// Remove a contact you nominated, or stop being a contact for someone else.
+///
+/// Any recovery the contact was asked to approve is cancelled.
+#[openapi(tag = "Account")]
+#[delete("/contacts/<id>")]
+pub async fn delete_contact(db: &State<Database>, user: User, id: String) -> Result<EmptyResponse> {
+    let contact = db.fetch_recovery_contact(&id).await?;
+    if contact.user != user.id && contact.contact != user.id {
+        return Err(create_error!(NotFound));
+    }
+
+    for request in db.fetch_recovery_requests(&contact.user).await? {
+        if request.user == contact.user && request.contact == contact.contact {
+            db.delete_recovery_request(&request.id).await?;
+        }
+    }
+
+    db.delete_recovery_contact(&contact.id)
+        .await
+        .map(|_| EmptyResponse)
+}
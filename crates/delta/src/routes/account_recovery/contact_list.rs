@@ -0,0 +1,19 @@
+use guilderia_database::{Database, User};
+use guilderia_models::v0;
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Recovery Contacts
+///
+/// Fetch contacts you nominated and accounts which nominated you.
+#[openapi(tag = "Account")]
+#[get("/contacts")]
+pub async fn list_contacts(
+    db: &State<Database>,
+    user: User,
+) -> Result<Json<Vec<v0::RecoveryContact>>> {
+    db.fetch_recovery_contacts(&user.id)
+        .await
+        .map(|contacts| contacts.into_iter().map(Into::into).collect())
+        .map(Json)
+}
@@ -0,0 +1,28 @@
+use guilderia_rocket_okapi::revolt_okapi::openapi3::OpenApi;
+use rocket::Route;
+
+mod backup_codes_fetch;
+mod contact_accept;
+mod contact_create;
+mod contact_delete;
+mod contact_list;
+mod request_approve;
+mod request_cancel;
+mod request_complete;
+mod request_list;
+mod request_start;
+
+pub fn routes() -> (Vec<Route>, OpenApi) {
+    openapi_get_routes_spec![
+        backup_codes_fetch::fetch_backup_codes,
+        contact_list::list_contacts,
+        contact_create::create_contact,
+        contact_accept::accept_contact,
+        contact_delete::delete_contact,
+        request_list::list_requests,
+        request_start::start_recovery,
+        request_approve::approve_recovery,
+        request_cancel::cancel_recovery,
+        request_complete::complete_recovery,
+    ]
+}
@@ -0,0 +1,24 @@
+use guilderia_database::{Database, User};
+use guilderia_result::{create_error, Result};
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Approve Account Recovery
+///
+/// Approve a recovery of an account which nominated you as a recovery contact.
+///
+/// Only approve recoveries which you have confirmed with the owner of the account.
+#[openapi(tag = "Account")]
+#[post("/requests/<id>/approve")]
+pub async fn approve_recovery(
+    db: &State<Database>,
+    user: User,
+    id: String,
+) -> Result<EmptyResponse> {
+    let mut request = db.fetch_recovery_request(&id).await?;
+    if request.contact != user.id {
+        return Err(create_error!(NotFound));
+    }
+
+    request.approve(db).await.map(|_| EmptyResponse)
+}
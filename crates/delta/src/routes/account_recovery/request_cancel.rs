@@ -0,0 +1,24 @@
+use guilderia_database::{Database, User};
+use guilderia_result::{create_error, Result};
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Cancel Account Recovery
+///
+/// Cancel a recovery of your account, or decline one you were asked to approve.
+#[openapi(tag = "Account")]
+#[delete("/requests/<id>")]
+pub async fn cancel_recovery(
+    db: &State<Database>,
+    user: User,
+    id: String,
+) -> Result<EmptyResponse> {
+    let request = db.fetch_recovery_request(&id).await?;
+    if request.user != user.id && request.contact != user.id {
+        return Err(create_error!(NotFound));
+    }
+
+    db.delete_recovery_request(&request.id)
+        .await
+        .map(|_| EmptyResponse)
+}
@@ -0,0 +1,52 @@
+use authifier::Authifier;
+use guilderia_database::Database;
+use guilderia_models::v0;
+use guilderia_result::{create_database_error, create_error, Result};
+use rocket::{serde::json::Json, State};
+use rocket_empty::EmptyResponse;
+
+/// # Complete Account Recovery
+///
+/// Turn off multi-factor authentication on the recovered account and sign
+/// out all of its sessions, after which it can be signed in to with just
+/// the account password.
+///
+/// The recovery must have been approved and its delay must have passed.
+#[openapi(tag = "Account")]
+#[post("/requests/<id>/complete", data = "<data>")]
+pub async fn complete_recovery(
+    db: &State<Database>,
+    authifier: &State<Authifier>,
+    id: String,
+    data: Json<v0::DataCompleteRecovery>,
+) -> Result<EmptyResponse> {
+    let request = db.fetch_recovery_request(&id).await?;
+    if request.token != data.into_inner().token {
+        return Err(create_error!(NotFound));
+    }
+
+    if !request.can_complete() {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    let mut account = authifier
+        .database
+        .find_account(&request.user)
+        .await
+        .map_err(|_| create_error!(NotFound))?;
+
+    account.mfa = Default::default();
+    account
+        .save(authifier)
+        .await
+        .map_err(|_| create_database_error!("save", "accounts"))?;
+
+    account
+        .delete_all_sessions(authifier, None)
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    db.delete_recovery_request(&request.id)
+        .await
+        .map(|_| EmptyResponse)
+}
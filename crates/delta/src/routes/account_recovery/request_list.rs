@@ -0,0 +1,25 @@
+use guilderia_database::{Database, User};
+use guilderia_models::v0;
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Recovery Requests
+///
+/// Fetch recoveries of your account and recoveries you were asked to approve.
+#[openapi(tag = "Account")]
+#[get("/requests")]
+pub async fn list_requests(
+    db: &State<Database>,
+    user: User,
+) -> Result<Json<Vec<v0::RecoveryRequest>>> {
+    db.fetch_recovery_requests(&user.id)
+        .await
+        .map(|requests| {
+            requests
+                .into_iter()
+                .filter(|request| !request.is_expired())
+                .map(Into::into)
+                .collect()
+        })
+        .map(Json)
+}
@@ -0,0 +1,56 @@
+use authifier::Authifier;
+use guilderia_database::{Database, RecoveryRequest};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+use crate::util::provisioning::find_account_by_email;
+
+/// # Start Account Recovery
+///
+/// Start recovering an account which you can no longer sign in to because
+/// you lost your multi-factor authentication method and backup codes.
+///
+/// The chosen recovery contact must approve the recovery and it can only
+/// be completed after a delay, during which the owner is notified by email
+/// and can cancel it from any signed in session.
+#[openapi(tag = "Account")]
+#[post("/requests", data = "<data>")]
+pub async fn start_recovery(
+    db: &State<Database>,
+    authifier: &State<Authifier>,
+    data: Json<v0::DataStartRecovery>,
+) -> Result<Json<v0::RecoveryTicket>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let account = find_account_by_email(authifier, &data.email)
+        .await?
+        .filter(|account| !account.disabled)
+        .ok_or_else(|| create_error!(InvalidCredentials))?;
+
+    RecoveryRequest::take_attempt(db, &account.id).await?;
+    account
+        .verify_password(&data.password)
+        .map_err(|_| create_error!(InvalidCredentials))?;
+
+    let contact = db
+        .fetch_recovery_contacts(&account.id)
+        .await?
+        .into_iter()
+        .find(|contact| contact.user == account.id && contact.contact == data.contact)
+        .ok_or_else(|| create_error!(NotFound))?;
+
+    let request = RecoveryRequest::create(db, &contact).await?;
+    request.send_notice_email(authifier).await?;
+
+    Ok(Json(v0::RecoveryTicket {
+        token: request.token.clone(),
+        request: request.into(),
+    }))
+}
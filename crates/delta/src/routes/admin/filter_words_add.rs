@@ -0,0 +1,60 @@
+use std::collections::HashSet;
+
+use iso8601_timestamp::Timestamp;
+use guilderia_database::{Database, FilterWord, User};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Add Filter Words
+///
+/// Add words or phrases to the instance's base content filter list.
+///
+/// Words already on the list are skipped, only newly added words are returned.
+///
+/// Requires a privileged account.
+#[openapi(tag = "Admin")]
+#[post("/filter_words", data = "<data>")]
+pub async fn add_filter_words(
+    db: &State<Database>,
+    user: User,
+    data: Json<v0::DataAddFilterWords>,
+) -> Result<Json<Vec<v0::FilterWord>>> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let mut existing: HashSet<String> = db
+        .fetch_filter_words()
+        .await?
+        .into_iter()
+        .map(|word| word.id)
+        .collect();
+
+    let mut added = vec![];
+    for word in data.words {
+        let word = word.trim().to_lowercase();
+        if word.is_empty() || !existing.insert(word.clone()) {
+            continue;
+        }
+
+        let word = FilterWord {
+            id: word,
+            creator: user.id.clone(),
+            created_at: Timestamp::now_utc(),
+        };
+
+        db.insert_filter_word(&word).await?;
+        added.push(word.into());
+    }
+
+    Ok(Json(added))
+}
@@ -0,0 +1,25 @@
+use guilderia_database::{Database, User};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Filter Words
+///
+/// Fetch the instance's base content filter list, which servers may opt in to.
+///
+/// Requires a privileged account.
+#[openapi(tag = "Admin")]
+#[get("/filter_words")]
+pub async fn fetch_filter_words(
+    db: &State<Database>,
+    user: User,
+) -> Result<Json<Vec<v0::FilterWord>>> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    db.fetch_filter_words()
+        .await
+        .map(|words| words.into_iter().map(Into::into).collect())
+        .map(Json)
+}
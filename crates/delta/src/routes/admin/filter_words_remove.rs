@@ -0,0 +1,23 @@
+use guilderia_database::{Database, User};
+use guilderia_result::{create_error, Result};
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Remove Filter Word
+///
+/// Remove a word or phrase from the instance's base content filter list.
+///
+/// Requires a privileged account.
+#[openapi(tag = "Admin")]
+#[delete("/filter_words/<word>")]
+pub async fn remove_filter_word(
+    db: &State<Database>,
+    user: User,
+    word: String,
+) -> Result<EmptyResponse> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    db.delete_filter_word(&word).await.map(|_| EmptyResponse)
+}
@@ -0,0 +1,53 @@
+use authifier::Authifier;
+use guilderia_database::{Database, Impersonation, User};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Impersonate User
+///
+/// Start a read-only session as another user to debug an issue they reported.
+///
+/// Every request made with the session is logged against the admin who started
+/// it, the session expires automatically and the user is notified according
+/// to the instance's notification policy.
+///
+/// Requires a privileged account.
+#[openapi(tag = "Admin")]
+#[post("/impersonations", data = "<data>")]
+pub async fn create_impersonation(
+    db: &State<Database>,
+    authifier: &State<Authifier>,
+    user: User,
+    data: Json<v0::DataCreateImpersonation>,
+) -> Result<Json<v0::ImpersonationTicket>> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let target = db.fetch_user(&data.user).await?;
+    let impersonation = Impersonation::create(
+        db,
+        &user,
+        &target,
+        data.reason,
+        data.duration_minutes,
+        data.notify_user,
+    )
+    .await?;
+
+    impersonation.send_notice_email(authifier).await?;
+
+    Ok(Json(v0::ImpersonationTicket {
+        token: impersonation.token.clone(),
+        impersonation: impersonation.into(),
+    }))
+}
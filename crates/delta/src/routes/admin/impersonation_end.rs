@@ -0,0 +1,24 @@
+use guilderia_database::{Database, User};
+use guilderia_result::{create_error, Result};
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # End Impersonation
+///
+/// End an impersonation before it expires.
+///
+/// Requires a privileged account.
+#[openapi(tag = "Admin")]
+#[delete("/impersonations/<id>")]
+pub async fn end_impersonation(
+    db: &State<Database>,
+    user: User,
+    id: String,
+) -> Result<EmptyResponse> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    let mut impersonation = db.fetch_impersonation(&id).await?;
+    impersonation.end(db).await.map(|_| EmptyResponse)
+}
@@ -0,0 +1,25 @@
+use guilderia_database::{Database, User};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Impersonations
+///
+/// Fetch the most recent impersonations along with the requests made during them.
+///
+/// Requires a privileged account.
+#[openapi(tag = "Admin")]
+#[get("/impersonations")]
+pub async fn fetch_impersonations(
+    db: &State<Database>,
+    user: User,
+) -> Result<Json<Vec<v0::Impersonation>>> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    db.fetch_impersonations(100)
+        .await
+        .map(|impersonations| impersonations.into_iter().map(Into::into).collect())
+        .map(Json)
+}
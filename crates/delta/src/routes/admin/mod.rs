@@ -0,0 +1,43 @@
+use guilderia_rocket_okapi::revolt_okapi::openapi3::OpenApi;
+use rocket::Route;
+
+mod filter_words_add;
+mod filter_words_fetch;
+mod filter_words_remove;
+mod impersonation_create;
+mod impersonation_end;
+mod impersonations_fetch;
+mod quarantine_fetch;
+mod quarantine_purge;
+mod quarantine_release;
+mod server_application_review;
+mod server_applications_fetch;
+mod server_recognition_edit;
+mod task_queue_dead_letters;
+mod task_queue_requeue;
+mod task_queues_fetch;
+
+pub fn routes() -> (Vec<Route>, OpenApi) {
+    openapi_get_routes_spec![
+        // Task Queues
+        task_queues_fetch::fetch_task_queues,
+        task_queue_dead_letters::fetch_dead_letters,
+        task_queue_requeue::requeue_dead_letters,
+        // Impersonation
+        impersonations_fetch::fetch_impersonations,
+        impersonation_create::create_impersonation,
+        impersonation_end::end_impersonation,
+        // Quarantine
+        quarantine_fetch::fetch_quarantine,
+        quarantine_release::release_quarantined_file,
+        quarantine_purge::purge_quarantined_file,
+        // Content Filter
+        filter_words_fetch::fetch_filter_words,
+        filter_words_add::add_filter_words,
+        filter_words_remove::remove_filter_word,
+        // Server Recognition
+        server_applications_fetch::fetch_server_applications,
+        server_application_review::review_server_application,
+        server_recognition_edit::edit_server_recognition,
+    ]
+}
@@ -0,0 +1,22 @@
+use guilderia_database::{Database, User};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Quarantined Files
+///
+/// Fetch files held in quarantine after failing a virus scan which are awaiting review.
+///
+/// Requires a privileged account.
+#[openapi(tag = "Admin")]
+#[get("/quarantine")]
+pub async fn fetch_quarantine(db: &State<Database>, user: User) -> Result<Json<Vec<v0::File>>> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    db.fetch_quarantined_attachments()
+        .await
+        .map(|files| files.into_iter().map(Into::into).collect())
+        .map(Json)
+}
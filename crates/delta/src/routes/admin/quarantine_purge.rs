@@ -0,0 +1,29 @@
+use guilderia_database::{Database, User};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Purge Quarantined File
+///
+/// Delete a quarantined file confirmed to be malicious.
+///
+/// The message it was attached to, or otherwise its uploader, is reported
+/// to the moderation team and the created report is returned.
+///
+/// Requires a privileged account.
+#[openapi(tag = "Admin")]
+#[delete("/quarantine/<id>")]
+pub async fn purge_quarantined_file(
+    db: &State<Database>,
+    user: User,
+    id: String,
+) -> Result<Json<Option<v0::Report>>> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    let file = db.fetch_attachment_by_id(&id).await?;
+    file.purge(db, &user.id)
+        .await
+        .map(|report| Json(report.map(Into::into)))
+}
@@ -0,0 +1,27 @@
+use guilderia_database::{Database, User};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Release Quarantined File
+///
+/// Release a quarantined file found to be safe so it is served again.
+///
+/// Released files are not quarantined again by later scans.
+///
+/// Requires a privileged account.
+#[openapi(tag = "Admin")]
+#[post("/quarantine/<id>/release")]
+pub async fn release_quarantined_file(
+    db: &State<Database>,
+    user: User,
+    id: String,
+) -> Result<Json<v0::File>> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    let mut file = db.fetch_attachment_by_id(&id).await?;
+    file.release(db).await?;
+    Ok(Json(file.into()))
+}
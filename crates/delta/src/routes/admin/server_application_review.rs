@@ -0,0 +1,40 @@
+use guilderia_database::{Database, User};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Review Server Application
+///
+/// Approve or reject a pending server application.
+///
+/// Approved servers are given the program's flag, along with the
+/// description and link shown alongside it.
+///
+/// Requires a privileged account.
+#[openapi(tag = "Admin")]
+#[post("/server_applications/<id>/review", data = "<data>")]
+pub async fn review_server_application(
+    db: &State<Database>,
+    user: User,
+    id: String,
+    data: Json<v0::DataReviewServerApplication>,
+) -> Result<Json<v0::ServerApplication>> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let mut application = db.fetch_server_application(&id).await?;
+    application
+        .review(db, &user.id, data.approve, data.note, data.description)
+        .await?;
+
+    Ok(Json(application.into()))
+}
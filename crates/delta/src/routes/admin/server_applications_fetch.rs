@@ -0,0 +1,25 @@
+use guilderia_database::{Database, ServerApplicationStatus, User};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Server Applications
+///
+/// Fetch server applications awaiting review, oldest first.
+///
+/// Requires a privileged account.
+#[openapi(tag = "Admin")]
+#[get("/server_applications")]
+pub async fn fetch_server_applications(
+    db: &State<Database>,
+    user: User,
+) -> Result<Json<Vec<v0::ServerApplication>>> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    db.fetch_server_applications(ServerApplicationStatus::Pending)
+        .await
+        .map(|applications| applications.into_iter().map(Into::into).collect())
+        .map(Json)
+}
@@ -0,0 +1,74 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_database::{
+    util::reference::Reference, Database, FieldsServer, PartialServer, ServerRecognition, User,
+};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Edit Server Recognition
+///
+/// Set a server's flags and the details shown alongside them, such as
+/// marking a server as official.
+///
+/// Removing every flag also removes the details.
+///
+/// Requires a privileged account.
+#[openapi(tag = "Admin")]
+#[put("/servers/<target>/recognition", data = "<data>")]
+pub async fn edit_server_recognition(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataEditServerRecognition>,
+) -> Result<Json<v0::Server>> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let mut server = target.as_server(db).await?;
+    if data.flags == 0 {
+        server
+            .update(
+                db,
+                PartialServer {
+                    flags: Some(0),
+                    ..Default::default()
+                },
+                vec![FieldsServer::Recognition],
+            )
+            .await?;
+    } else {
+        let since = server
+            .recognition
+            .as_ref()
+            .map(|recognition| recognition.since)
+            .unwrap_or_else(Timestamp::now_utc);
+
+        server
+            .update(
+                db,
+                PartialServer {
+                    flags: Some(data.flags as i32),
+                    recognition: Some(ServerRecognition {
+                        description: data.description,
+                        url: data.url,
+                        since,
+                    }),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await?;
+    }
+
+    Ok(Json(server.into()))
+}
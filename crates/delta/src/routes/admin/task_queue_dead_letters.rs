@@ -0,0 +1,29 @@
+use guilderia_database::{tasks, User};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::serde::json::Json;
+
+/// # Fetch Dead-lettered Tasks
+///
+/// Fetch items which failed too many times and were set aside from a task queue.
+///
+/// Requires a privileged account.
+#[openapi(tag = "Admin")]
+#[get("/tasks/<queue>/dead?<limit>")]
+pub async fn fetch_dead_letters(
+    user: User,
+    queue: String,
+    limit: Option<isize>,
+) -> Result<Json<v0::DeadLetteredTasks>> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    let queue = tasks::find_queue(&queue).ok_or_else(|| create_error!(NotFound))?;
+
+    Ok(Json(v0::DeadLetteredTasks {
+        items: queue
+            .dead_letters(limit.unwrap_or(100).clamp(1, 1000))
+            .await,
+    }))
+}
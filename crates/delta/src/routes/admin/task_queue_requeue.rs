@@ -0,0 +1,23 @@
+use guilderia_database::{tasks, User};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::serde::json::Json;
+
+/// # Requeue Dead-lettered Tasks
+///
+/// Put all items which were set aside from a task queue back on the queue.
+///
+/// Requires a privileged account.
+#[openapi(tag = "Admin")]
+#[post("/tasks/<queue>/requeue")]
+pub async fn requeue_dead_letters(user: User, queue: String) -> Result<Json<v0::RequeuedTasks>> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    let queue = tasks::find_queue(&queue).ok_or_else(|| create_error!(NotFound))?;
+
+    Ok(Json(v0::RequeuedTasks {
+        count: queue.requeue_dead_letters().await,
+    }))
+}
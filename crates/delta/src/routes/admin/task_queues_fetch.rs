@@ -0,0 +1,21 @@
+use guilderia_database::{tasks, User};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::serde::json::Json;
+
+/// # Fetch Task Queues
+///
+/// Fetch the status of background task queues on this node.
+///
+/// Requires a privileged account.
+#[openapi(tag = "Admin")]
+#[get("/tasks")]
+pub async fn fetch_task_queues(user: User) -> Result<Json<Vec<v0::TaskQueue>>> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    Ok(Json(
+        tasks::queue_stats().into_iter().map(Into::into).collect(),
+    ))
+}
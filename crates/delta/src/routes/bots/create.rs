@@ -1,4 +1,4 @@
-use guilderia_database::{Bot, Database, User};
+use guilderia_database::{Bot, Database, PartialBot, User};
 use guilderia_models::v0;
 use guilderia_result::{create_error, Result};
 use rocket::serde::json::Json;
@@ -22,7 +22,17 @@ pub async fn create_bot(
         })
     })?;
 
-    let (bot, user) = Bot::create(db, info.name, &user, None).await?;
+    let (bot, user) = Bot::create(
+        db,
+        info.name,
+        &user,
+        PartialBot {
+            intents: info.intents.map(|v| v as i32),
+            permissions: info.permissions.map(|v| v as i64),
+            ..Default::default()
+        },
+    )
+    .await?;
     Ok(Json(v0::BotWithUserResponse {
         bot: bot.into(),
         user: user.into_self(false).await,
@@ -48,6 +58,7 @@ mod test {
             .body(
                 json!(v0::DataCreateBot {
                     name: TestHarness::rand_string(),
+                    ..Default::default()
                 })
                 .to_string(),
             )
@@ -1,4 +1,6 @@
-use guilderia_database::{util::reference::Reference, Database, PartialBot, User};
+use guilderia_database::{
+    tasks::interactions, util::reference::Reference, BridgeKey, Database, PartialBot, User,
+};
 use guilderia_models::v0::{self, DataEditBot};
 use guilderia_result::{create_error, Result};
 use rocket::State;
@@ -26,7 +28,68 @@ pub async fn edit_bot(
 
     let mut bot = target.as_bot(db).await?;
     if bot.owner != user.id {
-        return Err(create_error!(NotFound));
+        // Operators may only restrict the intents of bots they do not own
+        if !user.privileged
+            || data.name.is_some()
+            || data.public.is_some()
+            || data.analytics.is_some()
+            || data.interactions_url.is_some()
+            || data.intents.is_some()
+            || data.permissions.is_some()
+            || data.user_installable.is_some()
+            || data.commands.is_some()
+            || data.bridge_keys.is_some()
+            || data.remove.is_some()
+        {
+            return Err(create_error!(NotFound));
+        }
+    }
+
+    if data.restricted_intents.is_some() && !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    if let Some(bridge_keys) = &data.bridge_keys {
+        for key in bridge_keys {
+            key.validate().map_err(|error| {
+                create_error!(FailedValidation {
+                    error: error.to_string()
+                })
+            })?;
+
+            if BridgeKey::from(key.clone()).verifying_key().is_none() {
+                return Err(create_error!(FailedValidation {
+                    error: format!("bridge key {} is not a valid Ed25519 public key", key.id)
+                }));
+            }
+        }
+    }
+
+    // Interactions endpoints must be reachable and acknowledge a signed ping
+    if let Some(url) = &data.interactions_url {
+        if !url.starts_with("https://") {
+            return Err(create_error!(FailedValidation {
+                error: "interactions URL must use HTTPS".to_string()
+            }));
+        }
+
+        if interactions::public_key().await.is_none() {
+            return Err(create_error!(FailedValidation {
+                error: "interactions URLs are not enabled on this node".to_string()
+            }));
+        }
+
+        if interactions::resolve_endpoint(url).await.is_none() {
+            return Err(create_error!(FailedValidation {
+                error: "interactions URL must resolve to a public address".to_string()
+            }));
+        }
+
+        if !interactions::verify_endpoint(url).await {
+            return Err(create_error!(FailedValidation {
+                error: "interactions URL did not acknowledge ping".to_string()
+            }));
+        }
     }
 
     let mut user = db.fetch_user(&bot.id).await?;
@@ -37,6 +100,12 @@ pub async fn edit_bot(
     if data.public.is_none()
         && data.analytics.is_none()
         && data.interactions_url.is_none()
+        && data.intents.is_none()
+        && data.restricted_intents.is_none()
+        && data.permissions.is_none()
+        && data.user_installable.is_none()
+        && data.commands.is_none()
+        && data.bridge_keys.is_none()
         && data.remove.is_none()
     {
         return Ok(Json(v0::BotWithUserResponse {
@@ -49,6 +118,12 @@ pub async fn edit_bot(
         public,
         analytics,
         interactions_url,
+        intents,
+        restricted_intents,
+        permissions,
+        user_installable,
+        commands,
+        bridge_keys,
         remove,
         ..
     } = data;
@@ -57,6 +132,12 @@ pub async fn edit_bot(
         public,
         analytics,
         interactions_url,
+        intents: intents.map(|v| v as i32),
+        restricted_intents: restricted_intents.map(|v| v as i32),
+        permissions: permissions.map(|v| v as i64),
+        user_installable,
+        commands: commands.map(|v| v.into_iter().map(|v| v.into()).collect()),
+        bridge_keys: bridge_keys.map(|v| v.into_iter().map(|v| v.into()).collect()),
         ..Default::default()
     };
 
@@ -115,4 +196,40 @@ mod test {
         assert!(!bot.public);
         assert!(updated_bot.public);
     }
+
+    #[rocket::async_test]
+    async fn edit_bot_restricted_intents_requires_privilege() {
+        let harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let (bot, _) = Bot::create(&harness.db, TestHarness::rand_string(), &user, None)
+            .await
+            .expect("`Bot`");
+
+        let response = harness
+            .client
+            .patch(format!("/bots/{}", bot.id))
+            .header(ContentType::JSON)
+            .body(
+                json!(v0::DataEditBot {
+                    restricted_intents: Some(v0::BotIntents::MessageContent as u32),
+                    ..Default::default()
+                })
+                .to_string(),
+            )
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Forbidden);
+        assert_eq!(
+            harness
+                .db
+                .fetch_bot(&bot.id)
+                .await
+                .expect("`Bot`")
+                .effective_intents(),
+            u32::MAX
+        );
+    }
 }
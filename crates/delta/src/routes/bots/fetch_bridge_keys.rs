@@ -0,0 +1,23 @@
+use guilderia_database::{util::reference::Reference, Database, User};
+use guilderia_models::v0::BridgeKey;
+use guilderia_result::Result;
+
+use rocket::serde::json::Json;
+use rocket::State;
+
+/// # Fetch Bridge Keys
+///
+/// Fetch the keys a bot signs bridged messages with,
+/// used to verify the provenance of its messages.
+#[openapi(tag = "Bots")]
+#[get("/<target>/keys")]
+pub async fn fetch_bridge_keys(
+    db: &State<Database>,
+    _user: User,
+    target: Reference,
+) -> Result<Json<Vec<BridgeKey>>> {
+    let bot = db.fetch_bot(&target.id).await?;
+    Ok(Json(
+        bot.bridge_keys.into_iter().map(|key| key.into()).collect(),
+    ))
+}
@@ -0,0 +1,19 @@
+use guilderia_database::{Database, User};
+use guilderia_models::v0;
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Installed Bots
+///
+/// Fetch all of the bots you have installed to your account.
+#[openapi(tag = "Bots")]
+#[get("/@me/installed")]
+pub async fn fetch_installed_bots(
+    db: &State<Database>,
+    user: User,
+) -> Result<Json<Vec<v0::UserApp>>> {
+    db.fetch_user_apps(&user.id)
+        .await
+        .map(|v| v.into_iter().map(Into::into).collect())
+        .map(Json)
+}
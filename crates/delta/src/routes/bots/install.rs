@@ -0,0 +1,79 @@
+use guilderia_database::{util::reference::Reference, Database, User, UserApp};
+use guilderia_models::v0;
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Install Bot
+///
+/// Install a bot to your account so that its commands
+/// can be used in your DMs and groups.
+#[openapi(tag = "Bots")]
+#[post("/<target>/install")]
+pub async fn install_bot(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<Json<v0::UserApp>> {
+    let bot = target.as_bot(db).await?;
+    UserApp::create(db, &bot, &user)
+        .await
+        .map(|app| Json(app.into()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use guilderia_database::{Bot, PartialBot};
+    use guilderia_models::v0;
+    use rocket::http::{Header, Status};
+
+    #[rocket::async_test]
+    async fn install_bot() {
+        let harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let (bot, _) = Bot::create(
+            &harness.db,
+            TestHarness::rand_string(),
+            &user,
+            PartialBot {
+                user_installable: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("`Bot`");
+
+        let response = harness
+            .client
+            .post(format!("/bots/{}/install", bot.id))
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let app: v0::UserApp = response.into_json().await.expect("`UserApp`");
+        assert_eq!(app.bot, bot.id);
+        assert!(harness.db.fetch_user_app(&bot.id, &user.id).await.is_ok());
+    }
+
+    #[rocket::async_test]
+    async fn install_bot_not_installable() {
+        let harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let (bot, _) = Bot::create(&harness.db, TestHarness::rand_string(), &user, None)
+            .await
+            .expect("`Bot`");
+
+        let response = harness
+            .client
+            .post(format!("/bots/{}/install", bot.id))
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+}
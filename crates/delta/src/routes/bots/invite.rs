@@ -35,17 +35,36 @@ pub async fn invite_bot(
     let bot_user = db.fetch_user(&bot.id).await?;
 
     match dest.into_inner() {
-        v0::InviteBotDestination::Server { server } => {
+        v0::InviteBotDestination::Server {
+            server,
+            permissions: granted,
+        } => {
             let server = db.fetch_server(&server).await?;
 
             let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
-            calculate_server_permissions(&mut query)
-                .await
-                .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+            let permissions = calculate_server_permissions(&mut query).await;
+            permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
 
-            Member::create(db, &server, &bot_user, None)
-                .await
-                .map(|_| EmptyResponse)
+            // The bot may only be granted permissions it asked for and which
+            // the installer holds, checked before the bot joins the server
+            let requested = bot.permissions.unwrap_or_default() as u64;
+            let granted = granted.unwrap_or(requested);
+            if granted & !requested != 0 {
+                return Err(create_error!(InvalidOperation));
+            }
+
+            if !permissions.has(granted) {
+                return Err(create_error!(CannotGiveMissingPermissions));
+            }
+
+            let (mut member, _) = Member::create(db, &server, &bot_user, None).await?;
+            if granted != 0 {
+                member
+                    .set_bot_scope(db, &server, &bot, &bot_user.username, granted)
+                    .await?;
+            }
+
+            Ok(EmptyResponse)
         }
         v0::InviteBotDestination::Group { group } => {
             let mut channel = db.fetch_channel(&group).await?;
@@ -66,8 +85,9 @@ pub async fn invite_bot(
 #[cfg(test)]
 mod test {
     use crate::{rocket, util::test::TestHarness};
-    use guilderia_database::{events::client::EventV1, Bot, Channel, Server};
+    use guilderia_database::{events::client::EventV1, Bot, Channel, PartialBot, Server};
     use guilderia_models::v0::{self, DataCreateServer};
+    use guilderia_permissions::ChannelPermission;
     use rocket::http::{ContentType, Header, Status};
 
     #[rocket::async_test]
@@ -149,7 +169,8 @@ mod test {
             .header(ContentType::JSON)
             .body(
                 json!(v0::InviteBotDestination::Server {
-                    server: server.id.to_string()
+                    server: server.id.to_string(),
+                    permissions: None,
                 })
                 .to_string(),
             )
@@ -174,4 +195,116 @@ mod test {
             _ => unreachable!(),
         }
     }
+
+    #[rocket::async_test]
+    async fn invite_bot_to_server_with_scope() {
+        let harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let requested = ChannelPermission::SendMessage as u64 | ChannelPermission::React as u64;
+        let (bot, _) = Bot::create(
+            &harness.db,
+            TestHarness::rand_string(),
+            &user,
+            PartialBot {
+                permissions: Some(requested as i64),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("`Bot`");
+
+        let (server, _) = Server::create(
+            &harness.db,
+            DataCreateServer {
+                name: TestHarness::rand_string(),
+                ..Default::default()
+            },
+            &user,
+            false,
+        )
+        .await
+        .unwrap();
+
+        let response = harness
+            .client
+            .post(format!("/bots/{}/invite", bot.id))
+            .header(ContentType::JSON)
+            .body(
+                json!(v0::InviteBotDestination::Server {
+                    server: server.id.to_string(),
+                    permissions: Some(ChannelPermission::SendMessage as u64),
+                })
+                .to_string(),
+            )
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NoContent);
+        drop(response);
+
+        let member = harness.db.fetch_member(&server.id, &bot.id).await.unwrap();
+        let scope = member.bot_scope.expect("`BotScope`");
+        assert_eq!(scope.permissions, ChannelPermission::SendMessage as i64);
+        assert!(member.roles.contains(&scope.role));
+
+        let server = harness.db.fetch_server(&server.id).await.unwrap();
+        assert_eq!(
+            server.roles.get(&scope.role).unwrap().permissions.a,
+            ChannelPermission::SendMessage as i64
+        );
+    }
+
+    #[rocket::async_test]
+    async fn invite_bot_refuses_unrequested_permissions() {
+        let harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let (bot, _) = Bot::create(
+            &harness.db,
+            TestHarness::rand_string(),
+            &user,
+            PartialBot {
+                permissions: Some(ChannelPermission::SendMessage as i64),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("`Bot`");
+
+        let (server, _) = Server::create(
+            &harness.db,
+            DataCreateServer {
+                name: TestHarness::rand_string(),
+                ..Default::default()
+            },
+            &user,
+            false,
+        )
+        .await
+        .unwrap();
+
+        let response = harness
+            .client
+            .post(format!("/bots/{}/invite", bot.id))
+            .header(ContentType::JSON)
+            .body(
+                json!(v0::InviteBotDestination::Server {
+                    server: server.id.to_string(),
+                    permissions: Some(
+                        ChannelPermission::SendMessage as u64 | ChannelPermission::React as u64
+                    ),
+                })
+                .to_string(),
+            )
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::BadRequest);
+        drop(response);
+
+        assert!(harness.db.fetch_member(&server.id, &bot.id).await.is_err());
+    }
 }
@@ -5,9 +5,13 @@ mod create;
 mod delete;
 mod edit;
 mod fetch;
+mod fetch_bridge_keys;
+mod fetch_installed;
 mod fetch_owned;
 mod fetch_public;
+mod install;
 mod invite;
+mod uninstall;
 
 pub fn routes() -> (Vec<Route>, OpenApi) {
     openapi_get_routes_spec![
@@ -15,8 +19,12 @@ pub fn routes() -> (Vec<Route>, OpenApi) {
         invite::invite_bot,
         fetch_public::fetch_public_bot,
         fetch::fetch_bot,
+        fetch_bridge_keys::fetch_bridge_keys,
         fetch_owned::fetch_owned_bots,
         edit::edit_bot,
         delete::delete_bot,
+        install::install_bot,
+        uninstall::uninstall_bot,
+        fetch_installed::fetch_installed_bots,
     ]
 }
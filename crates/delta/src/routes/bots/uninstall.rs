@@ -0,0 +1,22 @@
+use guilderia_database::{util::reference::Reference, Database, User, UserAppCompositeKey};
+use guilderia_result::Result;
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Uninstall Bot
+///
+/// Remove a bot you have installed from your account.
+#[openapi(tag = "Bots")]
+#[delete("/<target>/install")]
+pub async fn uninstall_bot(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<EmptyResponse> {
+    db.delete_user_app(&UserAppCompositeKey {
+        bot: target.id,
+        user: user.id,
+    })
+    .await
+    .map(|_| EmptyResponse)
+}
@@ -0,0 +1,23 @@
+use guilderia_database::{util::reference::Reference, Database, User};
+use guilderia_result::{create_error, Result};
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Delete Bookmark
+///
+/// Delete one of your bookmarks in this channel.
+#[openapi(tag = "Messaging")]
+#[delete("/<target>/bookmarks/<bookmark>")]
+pub async fn delete_bookmark(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    bookmark: Reference,
+) -> Result<EmptyResponse> {
+    let bookmark = db.fetch_bookmark(&bookmark.id).await?;
+    if bookmark.user != user.id || bookmark.channel != target.id {
+        return Err(create_error!(NotFound));
+    }
+
+    bookmark.delete(db).await.map(|_| EmptyResponse)
+}
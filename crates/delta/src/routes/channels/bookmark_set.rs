@@ -0,0 +1,45 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Bookmark, Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Set Bookmark
+///
+/// Save a named bookmark on a message to continue reading from later.
+///
+/// Bookmarks are private and independent of the unread marker. Setting a
+/// bookmark by a name already used in this channel moves it.
+#[openapi(tag = "Messaging")]
+#[put("/<target>/bookmarks", data = "<data>")]
+pub async fn set_bookmark(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataSetBookmark>,
+) -> Result<Json<v0::Bookmark>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ReadMessageHistory)?;
+
+    let message = Reference::from_unchecked(data.message)
+        .as_message_in_channel(db, channel.id())
+        .await?;
+
+    Bookmark::set(db, &user.id, channel.id(), &message.id, data.name)
+        .await
+        .map(|bookmark| Json(bookmark.into()))
+}
@@ -1,6 +1,6 @@
 use guilderia_database::{
     util::{permissions::DatabasePermissionQuery, reference::Reference},
-    Database, User,
+    Database, ReadReceipt, User,
 };
 use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
 use guilderia_result::{create_error, Result};
@@ -10,6 +10,8 @@ use rocket_empty::EmptyResponse;
 /// # Acknowledge Message
 ///
 /// Lets the server and all other clients know that we've seen this message id in this channel.
+///
+/// In direct messages and groups, the other recipients are also sent a read receipt.
 #[openapi(tag = "Messaging")]
 #[put("/<target>/ack/<message>")]
 pub async fn ack(
@@ -28,8 +30,8 @@ pub async fn ack(
         .await
         .throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
 
-    channel
-        .ack(&user.id, &message.id)
+    channel.ack(&user.id, &message.id).await?;
+    ReadReceipt::mark(db, &channel, &user.id, &message.id)
         .await
         .map(|_| EmptyResponse)
 }
@@ -38,6 +38,10 @@ pub async fn edit(
         && data.icon.is_none()
         && data.nsfw.is_none()
         && data.owner.is_none()
+        && data.allowed_attachments.is_none()
+        && data.rate_limit_per_user.is_none()
+        && data.message_retention_seconds.is_none()
+        && data.activity_channel.is_none()
         && data.remove.is_none()
     {
         return Ok(Json(channel.into()));
@@ -87,6 +91,68 @@ pub async fn edit(
         .ok();
     }
 
+    // Restrict which kinds of attachments may be sent
+    if let Some(allowed) = data.allowed_attachments {
+        if let Channel::TextChannel {
+            allowed_attachments,
+            ..
+        } = &mut channel
+        {
+            partial.allowed_attachments = Some(allowed.clone());
+            *allowed_attachments = Some(allowed);
+        } else {
+            return Err(create_error!(InvalidOperation));
+        }
+    }
+
+    // Slow down how often members may send messages
+    if let Some(seconds) = data.rate_limit_per_user {
+        if let Channel::TextChannel {
+            rate_limit_per_user,
+            ..
+        } = &mut channel
+        {
+            partial.rate_limit_per_user = Some(seconds);
+            *rate_limit_per_user = Some(seconds);
+        } else {
+            return Err(create_error!(InvalidOperation));
+        }
+    }
+
+    // Delete messages once they are older than the retention period
+    if let Some(seconds) = data.message_retention_seconds {
+        if let Channel::TextChannel {
+            message_retention_seconds,
+            ..
+        } = &mut channel
+        {
+            partial.message_retention_seconds = Some(seconds);
+            *message_retention_seconds = Some(seconds);
+        } else {
+            return Err(create_error!(InvalidOperation));
+        }
+    }
+
+    // Announce calls starting and ending in a text channel
+    if let Some(target) = data.activity_channel {
+        if let Channel::VoiceChannel {
+            server,
+            activity_channel,
+            ..
+        } = &mut channel
+        {
+            match db.fetch_channel(&target).await? {
+                Channel::TextChannel { server: other, .. } if &other == server => {}
+                _ => return Err(create_error!(InvalidOperation)),
+            }
+
+            partial.activity_channel = Some(target.clone());
+            *activity_channel = Some(target);
+        } else {
+            return Err(create_error!(InvalidOperation));
+        }
+    }
+
     match &mut channel {
         Channel::Group {
             id,
@@ -59,6 +59,7 @@ mod test {
             .body(
                 json!(v0::DataCreateBot {
                     name: TestHarness::rand_string(),
+                    ..Default::default()
                 })
                 .to_string(),
             )
@@ -0,0 +1,81 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Channel, CommandContext, Database, Interaction, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Invoke Bot Command
+///
+/// Start an interaction with a bot present in this channel,
+/// or with a bot you have installed if this is a DM or group.
+#[openapi(tag = "Interactions")]
+#[post("/<target>/interactions", data = "<data>")]
+pub async fn create_interaction(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataCreateInteraction>,
+) -> Result<Json<v0::Interaction>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    if user.bot.is_some() {
+        return Err(create_error!(IsBot));
+    }
+
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::SendMessage)?;
+
+    // Ensure the bot is either present in this channel or
+    // installed by the user, if this is a DM or group
+    let bot = db.fetch_bot(&data.bot).await?;
+    let context = match &channel {
+        Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } => db
+            .fetch_member(server, &bot.id)
+            .await
+            .ok()
+            .map(|_| CommandContext::Channel),
+        Channel::DirectMessage { recipients, .. } | Channel::Group { recipients, .. } => {
+            if recipients.contains(&bot.id) {
+                Some(CommandContext::Channel)
+            } else {
+                db.fetch_user_app(&bot.id, &user.id)
+                    .await
+                    .ok()
+                    .map(|_| CommandContext::User)
+            }
+        }
+        Channel::SavedMessages { .. } => None,
+    };
+
+    let Some(context) = context else {
+        return Err(create_error!(NotFound));
+    };
+
+    if !bot.has_command(&data.command, &context) {
+        return Err(create_error!(NotFound));
+    }
+
+    Interaction::create(
+        db,
+        bot.id,
+        user.id,
+        channel.id().to_string(),
+        context,
+        data.command,
+        data.options,
+    )
+    .await
+    .map(|interaction| Json(interaction.into()))
+}
@@ -1,7 +1,7 @@
 use chrono::Utc;
 use guilderia_database::{
     util::{permissions::DatabasePermissionQuery, reference::Reference},
-    Database, Message, User,
+    Database, Message, PendingAction, PendingActionKind, User,
 };
 use guilderia_models::v0;
 use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
@@ -17,6 +17,9 @@ use validator::Validate;
 /// This will always require `ManageMessages` permission regardless of whether you own the message or not.
 ///
 /// Messages must have been sent within the past 1 week.
+///
+/// If the server requires approval for destructive actions, this creates
+/// a pending action which another administrator has to approve.
 #[openapi(tag = "Messaging")]
 #[delete("/<target>/messages/bulk", data = "<options>", rank = 1)]
 pub async fn bulk_delete_messages(
@@ -51,6 +54,23 @@ pub async fn bulk_delete_messages(
         .await
         .throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
 
+    if let Some(server) = query.server_ref() {
+        if server.require_action_approval {
+            let pending = PendingAction::create(
+                db,
+                server,
+                &user.id,
+                PendingActionKind::PurgeMessages {
+                    channel: channel.id().to_string(),
+                    ids: options.ids,
+                },
+            )
+            .await?;
+
+            return Err(create_error!(ApprovalRequired { id: pending.id }));
+        }
+    }
+
     Message::bulk_delete(db, &target.id, options.ids)
         .await
         .map(|_| EmptyResponse)
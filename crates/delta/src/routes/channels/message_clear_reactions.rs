@@ -35,6 +35,7 @@ pub async fn clear_reactions(
             db,
             PartialMessage {
                 reactions: Some(Default::default()),
+                burst_reactions: Some(Default::default()),
                 ..Default::default()
             },
             vec![]
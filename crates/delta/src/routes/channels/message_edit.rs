@@ -1,18 +1,25 @@
 use iso8601_timestamp::Timestamp;
 use guilderia_database::{
     tasks,
-    util::{permissions::DatabasePermissionQuery, reference::Reference},
-    Database, Message, PartialMessage, User,
+    util::{
+        content_filter, locale::Locale, permissions::DatabasePermissionQuery, reference::Reference,
+    },
+    Database, FieldsMessage, Message, MessageFlagsValue, PartialMessage, User,
 };
-use guilderia_models::v0::{self, Embed};
+use guilderia_models::v0::{self, Embed, MessageFlags};
 use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
 use guilderia_result::{create_error, Result};
 use rocket::{serde::json::Json, State};
 use validator::Validate;
 
+use crate::util::body_limits::LimitedJson;
+
 /// # Edit Message
 ///
 /// Edits a message that you've previously sent.
+///
+/// Embed suppression may also be toggled on other users'
+/// messages if you have permission to manage messages.
 #[openapi(tag = "Messaging")]
 #[patch("/<target>/messages/<msg>", data = "<edit>")]
 pub async fn edit(
@@ -20,7 +27,8 @@ pub async fn edit(
     user: User,
     target: Reference,
     msg: Reference,
-    edit: Json<v0::DataEditMessage>,
+    edit: LimitedJson<v0::DataEditMessage>,
+    locale: Locale,
 ) -> Result<Json<v0::Message>> {
     let edit = edit.into_inner();
     edit.validate().map_err(|error| {
@@ -43,19 +51,41 @@ pub async fn edit(
     permissions.throw_if_lacking_channel_permission(ChannelPermission::SendMessage)?;
 
     let mut message = msg.as_message_in_channel(db, channel.id()).await?;
-    if message.author != user.id {
+    let edits_content = edit.content.is_some() || edit.embeds.is_some();
+    if message.author != user.id
+        && (edits_content
+            || edit.alt_text.is_some()
+            || !permissions.has_channel_permission(ChannelPermission::ManageMessages))
+    {
         return Err(create_error!(CannotEditMessage));
     }
 
-    message.edited = Some(Timestamp::now_utc());
-    let mut partial = PartialMessage {
-        edited: message.edited,
-        ..Default::default()
-    };
+    let mut partial = PartialMessage::default();
+    if edits_content {
+        message.edited = Some(Timestamp::now_utc());
+        partial.edited = message.edited;
+    }
 
     // 1. Handle content update
+    let mut remove = vec![];
     if let Some(content) = &edit.content {
-        partial.content = Some(content.clone());
+        let mut content = content.clone();
+
+        // Run content through the server's content filter, moderators are exempt
+        if let Some(server) = query.server_ref() {
+            if !permissions.has_channel_permission(ChannelPermission::ManageMessages) {
+                content =
+                    content_filter::check(db, server, channel.id(), &user.id, content).await?;
+            }
+        }
+
+        message.detect_extensions_in_edit(&content, &mut partial, &mut remove);
+        partial.content = Some(content);
+
+        // The signature no longer matches the content
+        if message.provenance.is_some() {
+            remove.push(FieldsMessage::Provenance);
+        }
     }
 
     // 2. Clear any auto generated embeds
@@ -82,15 +112,40 @@ pub async fn edit(
 
     partial.embeds = Some(new_embeds);
 
-    message.update(db, partial, vec![]).await?;
+    // 4. Toggle embed suppression
+    if let Some(suppress) = edit.suppress_embeds {
+        let mut flags = MessageFlagsValue(message.flags.unwrap_or_default());
+        flags.set(MessageFlags::SuppressEmbeds, suppress);
+        partial.flags = Some(flags.0);
+    }
+
+    // 5. Update alternative text of attachments
+    if let (Some(alt_text), Some(attachments)) = (&edit.alt_text, &message.attachments) {
+        let mut attachments = attachments.clone();
+        for file in &mut attachments {
+            if let Some(text) = alt_text.get(&file.id) {
+                file.alt_text = Some(text.clone()).filter(|text| !text.is_empty());
+                db.set_attachment_alt_text(&file.id, file.alt_text.as_deref())
+                    .await?;
+            }
+        }
+
+        partial.attachments = Some(attachments);
+    }
+
+    message.update(db, partial, remove).await?;
 
     // Queue up a task for processing embeds if the we have sufficient permissions
-    if permissions.has_channel_permission(ChannelPermission::SendEmbeds) {
-        if let Some(content) = edit.content {
+    if permissions.has_channel_permission(ChannelPermission::SendEmbeds)
+        && !message.embeds_suppressed()
+        && (edit.content.is_some() || edit.suppress_embeds == Some(false))
+    {
+        if let Some(content) = &message.content {
             tasks::process_embeds::queue(
                 message.channel.to_string(),
                 message.id.to_string(),
-                content,
+                content.clone(),
+                locale.into_inner(),
             )
             .await;
         }
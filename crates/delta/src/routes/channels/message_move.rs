@@ -0,0 +1,65 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Channel, Database, Message, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+use crate::util::body_limits::LimitedJson;
+
+/// # Move Messages
+///
+/// Move messages to another channel in the same server.
+///
+/// Requires `ManageMessages` permission in both channels.
+#[openapi(tag = "Messaging")]
+#[post("/<target>/messages/move", data = "<data>", rank = 1)]
+pub async fn move_messages(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: LimitedJson<v0::DataMoveMessages>,
+) -> Result<Json<Vec<v0::Message>>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let source = target.as_channel(db).await?;
+    let destination = Reference::from_unchecked(data.channel)
+        .as_channel(db)
+        .await?;
+
+    if source.id() == destination.id() {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    // Both channels must belong to the same server
+    match (&source, &destination) {
+        (
+            Channel::TextChannel { server: a, .. } | Channel::VoiceChannel { server: a, .. },
+            Channel::TextChannel { server: b, .. } | Channel::VoiceChannel { server: b, .. },
+        ) if a == b => {}
+        _ => return Err(create_error!(InvalidOperation)),
+    }
+
+    for channel in [&source, &destination] {
+        let mut query = DatabasePermissionQuery::new(db, &user).channel(channel);
+        calculate_channel_permissions(&mut query)
+            .await
+            .throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+    }
+
+    Ok(Json(
+        Message::bulk_move(db, source.id(), destination.id(), &data.ids, &user.id)
+            .await?
+            .into_iter()
+            .map(|message| message.into_model(None, None))
+            .collect(),
+    ))
+}
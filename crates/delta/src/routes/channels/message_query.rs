@@ -43,6 +43,7 @@ pub async fn query(
         sort,
         nearby,
         include_users,
+        thread,
     } = options;
 
     Message::fetch_with_users(
@@ -50,6 +51,8 @@ pub async fn query(
         MessageQuery {
             filter: MessageFilter {
                 channel: Some(channel.id().to_string()),
+                exclude_threads: thread.is_none(),
+                thread,
                 ..Default::default()
             },
             time_period: if let Some(nearby) = nearby {
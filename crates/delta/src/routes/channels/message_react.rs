@@ -2,6 +2,7 @@ use guilderia_database::{
     util::{permissions::DatabasePermissionQuery, reference::Reference},
     Database, User,
 };
+use guilderia_models::v0;
 use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
 use guilderia_result::Result;
 use rocket::State;
@@ -10,14 +11,17 @@ use rocket_empty::EmptyResponse;
 /// # Add Reaction to Message
 ///
 /// React to a given message.
+///
+/// Burst reactions are limited to a number per day.
 #[openapi(tag = "Interactions")]
-#[put("/<target>/messages/<msg>/reactions/<emoji>")]
+#[put("/<target>/messages/<msg>/reactions/<emoji>?<options..>")]
 pub async fn react_message(
     db: &State<Database>,
     user: User,
     target: Reference,
     msg: Reference,
     emoji: Reference,
+    options: v0::OptionsReact,
 ) -> Result<EmptyResponse> {
     let channel = target.as_channel(db).await?;
     let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
@@ -30,7 +34,49 @@ pub async fn react_message(
 
     // Add the reaction
     message
-        .add_reaction(db, &user, &emoji.id)
+        .add_reaction(db, &user, &emoji.id, options.burst.unwrap_or_default())
         .await
         .map(|_| EmptyResponse)
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use rocket::http::{Header, Status};
+
+    #[rocket::async_test]
+    async fn burst_reactions_are_limited() {
+        let harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+        let (server, channels) = harness.new_server(&user).await;
+        let (channel, _, message) = harness.new_message(&user, &server, channels).await;
+
+        let react = |emoji: &str| {
+            harness
+                .client
+                .put(format!(
+                    "/channels/{}/messages/{}/reactions/{emoji}?burst=true",
+                    channel.id(),
+                    message.id
+                ))
+                .header(Header::new("x-session-token", session.token.to_string()))
+        };
+
+        // New users may send one burst reaction per day
+        let response = react("%F0%9F%91%8D").dispatch().await;
+        assert_eq!(response.status(), Status::NoContent);
+        drop(response);
+
+        let response = react("%F0%9F%8E%89").dispatch().await;
+        assert_eq!(response.status(), Status::TooManyRequests);
+        drop(response);
+
+        let message = harness
+            .db
+            .fetch_message(&message.id)
+            .await
+            .expect("Failed to fetch message");
+        assert!(message.burst_reactions["👍"].contains(&user.id));
+        assert_eq!(message.reactions.len(), 1);
+    }
+}
@@ -1,17 +1,19 @@
 use chrono::{Duration, Utc};
 use guilderia_database::util::permissions::DatabasePermissionQuery;
 use guilderia_database::{
-    util::idempotency::IdempotencyKey, util::reference::Reference, Database, User,
+    util::idempotency::IdempotencyKey, util::locale::Locale, util::reference::Reference, Database,
+    User,
 };
 use guilderia_database::{Interactions, Message, AMQP};
 use guilderia_models::v0;
-use guilderia_permissions::PermissionQuery;
-use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_permissions::ChannelPermission;
 use guilderia_result::{create_error, Result};
 use rocket::serde::json::Json;
 use rocket::State;
 use validator::Validate;
 
+use crate::util::body_limits::LimitedJson;
+
 /// # Send Message
 ///
 /// Sends a message to the given channel.
@@ -22,8 +24,9 @@ pub async fn message_send(
     amqp: &State<AMQP>,
     user: User,
     target: Reference,
-    data: Json<v0::DataMessageSend>,
+    data: LimitedJson<v0::DataMessageSend>,
     idempotency: IdempotencyKey,
+    locale: Locale,
 ) -> Result<Json<v0::Message>> {
     let data = data.into_inner();
     data.validate().map_err(|error| {
@@ -35,7 +38,7 @@ pub async fn message_send(
     // Ensure we have permissions to send a message
     let channel = target.as_channel(db).await?;
     let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
-    let permissions = calculate_channel_permissions(&mut query).await;
+    let permissions = query.resolve().await;
     permissions.throw_if_lacking_channel_permission(ChannelPermission::SendMessage)?;
 
     // Verify permissions for masquerade
@@ -57,6 +60,11 @@ pub async fn message_send(
         permissions.throw_if_lacking_channel_permission(ChannelPermission::UploadFiles)?;
     }
 
+    // Check permissions for text-to-speech
+    if data.tts == Some(true) {
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::SendTTSMessages)?;
+    }
+
     // Ensure interactions information is correct
     if let Some(interactions) = &data.interactions {
         let interactions: Interactions = interactions.clone().into();
@@ -64,7 +72,7 @@ pub async fn message_send(
     }
 
     // Disallow mentions for new users (TRUST-0: <12 hours age) in public servers
-    let allow_mentions = if let Some(server) = query.server_ref() {
+    let allow_mentions = if let Some(server) = &permissions.server {
         if server.discoverable {
             (Utc::now() - ulid::Ulid::from_string(&user.id).unwrap().datetime())
                 >= Duration::hours(12)
@@ -78,18 +86,19 @@ pub async fn message_send(
     // Create the message
     let author: v0::User = user.clone().into(db, Some(&user)).await;
 
-    // Make sure we have server member (edge case if server owner)
-    query.are_we_a_member().await;
-
     // Create model user / members
     let model_user = user
         .clone()
         .into_known_static(revolt_presence::is_online(&user.id).await).await;
 
-    let model_member: Option<v0::Member> = query
-        .member_ref()
-        .as_ref()
-        .map(|member| member.clone().into_owned().into());
+    let model_member: Option<v0::Member> =
+        permissions
+            .member
+            .clone()
+            .map(|member| match &permissions.server {
+                Some(server) => member.into_model_with_server(server),
+                None => member.into(),
+            });
 
     Ok(Json(
         Message::create_from_api(
@@ -103,7 +112,9 @@ pub async fn message_send(
             user.limits().await,
             idempotency,
             permissions.has_channel_permission(ChannelPermission::SendEmbeds),
+            locale.into_inner(),
             allow_mentions,
+            Some(&permissions),
         )
         .await?
         .into_model(Some(model_user), model_member),
@@ -115,13 +126,16 @@ mod test {
     use std::collections::HashMap;
 
     use crate::{rocket, util::test::TestHarness};
+    use authifier::models::Session;
     use guilderia_database::{
         util::{idempotency::IdempotencyKey, reference::Reference},
-        Channel, Member, Message, MessageFlagsValue, PartialChannel, PartialMember, Role, Server,
+        Channel, Member, MentionLimit, MentionLimitAction, Message, MessageFlagsValue,
+        NewMemberRestrictions, PartialChannel, PartialMember, PartialServer, Role, Server,
     };
     use guilderia_models::v0::{self, DataCreateServerChannel, MessageFlags};
     use guilderia_permissions::{ChannelPermission, OverrideField};
     use guilderia_result::ErrorType;
+    use rocket::http::{ContentType, Header, Status};
 
     #[rocket::async_test]
     async fn message_mention_constraints() {
@@ -161,6 +175,7 @@ mod test {
             permissions: OverrideField { a: 0, d: 0 },
             colour: None,
             hoist: false,
+            group: None,
             rank: 5,
         };
 
@@ -217,9 +232,15 @@ mod test {
                 attachments: None,
                 replies: None,
                 embeds: None,
+                alt_text: None,
+                mention_confirmation: None,
+                provenance: None,
+                thread: None,
+                effect: None,
                 masquerade: None,
                 interactions: None,
                 flags: None,
+                tts: None,
             },
             v0::MessageAuthor::User(&user.clone().into(&harness.db, Some(&user)).await),
             Some(user.clone().into(&harness.db, Some(&user)).await),
@@ -227,7 +248,9 @@ mod test {
             user.limits().await,
             IdempotencyKey::unchecked_from_string("0".to_string()),
             false,
+            None,
             true,
+            None,
         )
         .await
         .expect("Failed to create message");
@@ -257,9 +280,15 @@ mod test {
                 attachments: None,
                 replies: None,
                 embeds: None,
+                alt_text: None,
+                mention_confirmation: None,
+                provenance: None,
+                thread: None,
+                effect: None,
                 masquerade: None,
                 interactions: None,
                 flags: None,
+                tts: None,
             },
             v0::MessageAuthor::User(&user.clone().into(&harness.db, Some(&user)).await),
             Some(user.clone().into(&harness.db, Some(&user)).await),
@@ -267,7 +296,9 @@ mod test {
             user.limits().await,
             IdempotencyKey::unchecked_from_string("1".to_string()),
             false,
+            None,
             true,
+            None,
         )
         .await
         .expect("Failed to create message");
@@ -286,6 +317,11 @@ mod test {
             avatar: None,
             timeout: None,
             roles: Some(second_member_roles),
+            role_expiries: None,
+            badges: None,
+            role_mention_optouts: None,
+            show_server_tag: None,
+            bot_scope: None,
         };
         second_member
             .update(&harness.db, partial, vec![])
@@ -303,9 +339,15 @@ mod test {
                 attachments: None,
                 replies: None,
                 embeds: None,
+                alt_text: None,
+                mention_confirmation: None,
+                provenance: None,
+                thread: None,
+                effect: None,
                 masquerade: None,
                 interactions: None,
                 flags: None,
+                tts: None,
             },
             v0::MessageAuthor::User(&user.clone().into(&harness.db, Some(&user)).await),
             Some(user.clone().into(&harness.db, Some(&user)).await),
@@ -313,7 +355,9 @@ mod test {
             user.limits().await,
             IdempotencyKey::unchecked_from_string("2".to_string()),
             false,
+            None,
             true,
+            None,
         )
         .await
         .expect("Failed to create message");
@@ -349,9 +393,15 @@ mod test {
                     fail_if_not_exists: Some(true),
                 }]),
                 embeds: None,
+                alt_text: None,
+                mention_confirmation: None,
+                provenance: None,
+                thread: None,
+                effect: None,
                 masquerade: None,
                 interactions: None,
                 flags: None,
+                tts: None,
             },
             v0::MessageAuthor::User(&user.clone().into(&harness.db, Some(&user)).await),
             Some(user.clone().into(&harness.db, Some(&user)).await),
@@ -359,7 +409,9 @@ mod test {
             user.limits().await,
             IdempotencyKey::unchecked_from_string("1".to_string()),
             false,
+            None,
             false,
+            None,
         )
         .await
         .expect("Failed to create message with reply");
@@ -400,9 +452,15 @@ mod test {
                     fail_if_not_exists: Some(false),
                 }]),
                 embeds: None,
+                alt_text: None,
+                mention_confirmation: None,
+                provenance: None,
+                thread: None,
+                effect: None,
                 masquerade: None,
                 interactions: None,
                 flags: None,
+                tts: None,
             },
             v0::MessageAuthor::User(&user.clone().into(&harness.db, Some(&user)).await),
             Some(user.clone().into(&harness.db, Some(&user)).await),
@@ -410,7 +468,9 @@ mod test {
             user.limits().await,
             IdempotencyKey::unchecked_from_string("3".to_string()),
             false,
+            None,
             false,
+            None,
         )
         .await
         .expect("Failed to create message with missing reply");
@@ -437,9 +497,15 @@ mod test {
                     fail_if_not_exists: Some(true),
                 }]),
                 embeds: None,
+                alt_text: None,
+                mention_confirmation: None,
+                provenance: None,
+                thread: None,
+                effect: None,
                 masquerade: None,
                 interactions: None,
                 flags: None,
+                tts: None,
             },
             v0::MessageAuthor::User(&user.clone().into(&harness.db, Some(&user)).await),
             Some(user.clone().into(&harness.db, Some(&user)).await),
@@ -447,7 +513,9 @@ mod test {
             user.limits().await,
             IdempotencyKey::unchecked_from_string("4".to_string()),
             false,
+            None,
             false,
+            None,
         )
         .await
         .expect_err("Created message with missing reply and true fail");
@@ -468,9 +536,15 @@ mod test {
                     fail_if_not_exists: None,
                 }]),
                 embeds: None,
+                alt_text: None,
+                mention_confirmation: None,
+                provenance: None,
+                thread: None,
+                effect: None,
                 masquerade: None,
                 interactions: None,
                 flags: None,
+                tts: None,
             },
             v0::MessageAuthor::User(&user.clone().into(&harness.db, Some(&user)).await),
             Some(user.clone().into(&harness.db, Some(&user)).await),
@@ -478,7 +552,9 @@ mod test {
             user.limits().await,
             IdempotencyKey::unchecked_from_string("4".to_string()),
             false,
+            None,
             false,
+            None,
         )
         .await
         .expect_err("Created message with missing reply and none fail");
@@ -518,9 +594,15 @@ mod test {
                 attachments: None,
                 replies: None,
                 embeds: None,
+                alt_text: None,
+                mention_confirmation: None,
+                provenance: None,
+                thread: None,
+                effect: None,
                 masquerade: None,
                 interactions: None,
                 flags: None,
+                tts: None,
             },
             v0::MessageAuthor::User(
                 &other_user
@@ -538,7 +620,9 @@ mod test {
             other_user.limits().await,
             IdempotencyKey::unchecked_from_string("1".to_string()),
             false,
+            None,
             true,
+            None,
         )
         .await
         .expect_err("Should not have created message with everyone and role pings");
@@ -563,9 +647,15 @@ mod test {
                 attachments: None,
                 replies: None,
                 embeds: None,
+                alt_text: None,
+                mention_confirmation: None,
+                provenance: None,
+                thread: None,
+                effect: None,
                 masquerade: None,
                 interactions: None,
                 flags: None,
+                tts: None,
             },
             v0::MessageAuthor::User(
                 &other_user
@@ -583,7 +673,9 @@ mod test {
             other_user.limits().await,
             IdempotencyKey::unchecked_from_string("1".to_string()),
             false,
+            None,
             true,
+            None,
         )
         .await
         .expect("Failed to create message with everyone and role pings in codeblocks");
@@ -611,7 +703,12 @@ mod test {
                     joined_at: None,
                     nickname: None,
                     roles: Some(vec![role_id.clone()]),
+                    role_expiries: None,
                     timeout: None,
+                    badges: None,
+                    role_mention_optouts: None,
+                    show_server_tag: None,
+                    bot_scope: None,
                 },
                 vec![],
             )
@@ -630,9 +727,15 @@ mod test {
                 attachments: None,
                 replies: None,
                 embeds: None,
+                alt_text: None,
+                mention_confirmation: None,
+                provenance: None,
+                thread: None,
+                effect: None,
                 masquerade: None,
                 interactions: None,
                 flags: None,
+                tts: None,
             },
             v0::MessageAuthor::User(
                 &other_user
@@ -650,7 +753,9 @@ mod test {
             other_user.limits().await,
             IdempotencyKey::unchecked_from_string("1".to_string()),
             false,
+            None,
             true,
+            None,
         )
         .await
         .expect("Failed to create message with everyone and role pings");
@@ -672,4 +777,288 @@ mod test {
             "Message has no role mentions"
         );
     }
+
+    #[rocket::async_test]
+    async fn message_tts_disabled_by_server() {
+        let harness = TestHarness::new().await;
+        let (_, _, user) = harness.new_user().await;
+
+        let (server, channels) = Server::create(
+            &harness.db,
+            v0::DataCreateServer {
+                name: "Test Server".to_string(),
+                ..Default::default()
+            },
+            &user,
+            true,
+        )
+        .await
+        .expect("Failed to create test server");
+
+        Member::create(&harness.db, &server, &user, Some(channels.clone()))
+            .await
+            .expect("Failed to create member");
+
+        harness
+            .db
+            .update_server(
+                &server.id,
+                &PartialServer {
+                    disable_tts: Some(true),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .expect("Failed to disable text-to-speech");
+
+        let message = Message::create_from_api(
+            &harness.db,
+            Some(&harness.amqp),
+            channels[0].clone(),
+            v0::DataMessageSend {
+                content: Some("Hello".to_string()),
+                nonce: None,
+                attachments: None,
+                replies: None,
+                embeds: None,
+                alt_text: None,
+                mention_confirmation: None,
+                provenance: None,
+                thread: None,
+                effect: None,
+                masquerade: None,
+                interactions: None,
+                flags: None,
+                tts: Some(true),
+            },
+            v0::MessageAuthor::User(&user.clone().into(&harness.db, Some(&user)).await),
+            Some(user.clone().into(&harness.db, Some(&user)).await),
+            None,
+            user.limits().await,
+            IdempotencyKey::unchecked_from_string("0".to_string()),
+            false,
+            None,
+            true,
+            None,
+        )
+        .await
+        .expect("Failed to create message");
+
+        assert!(!message.tts, "Text-to-speech was not stripped");
+    }
+
+    #[rocket::async_test]
+    async fn message_new_member_restrictions() {
+        let harness = TestHarness::new().await;
+        let (_, owner_session, owner) = harness.new_user().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let (server, channels) = harness.new_server(&owner).await;
+        Member::create(&harness.db, &server, &user, Some(channels.clone()))
+            .await
+            .expect("Failed to create member");
+
+        harness
+            .db
+            .update_server(
+                &server.id,
+                &PartialServer {
+                    new_member_restrictions: Some(NewMemberRestrictions {
+                        account_age_minutes: 60,
+                        membership_age_minutes: 0,
+                        message_interval_seconds: 30,
+                        block_links: true,
+                        block_attachments: false,
+                    }),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .expect("Failed to restrict new members");
+
+        let send = |session: &Session, content: &str| {
+            harness
+                .client
+                .post(format!("/channels/{}/messages", channels[0].id()))
+                .header(ContentType::JSON)
+                .body(json!({ "content": content }).to_string())
+                .header(Header::new("x-session-token", session.token.to_string()))
+        };
+
+        let response = send(&session, "https://example.com").dispatch().await;
+        assert_eq!(response.status(), Status::Forbidden);
+        drop(response);
+
+        let response = send(&session, "Hello").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        drop(response);
+
+        let response = send(&session, "Hello again").dispatch().await;
+        assert_eq!(response.status(), Status::TooManyRequests);
+        drop(response);
+
+        // Moderators are exempt
+        let response = send(&owner_session, "https://example.com").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[rocket::async_test]
+    async fn message_channel_slowmode() {
+        let harness = TestHarness::new().await;
+        let (_, owner_session, owner) = harness.new_user().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let (server, channels) = harness.new_server(&owner).await;
+        Member::create(&harness.db, &server, &user, Some(channels.clone()))
+            .await
+            .expect("Failed to create member");
+
+        let response = harness
+            .client
+            .patch(format!("/channels/{}", channels[0].id()))
+            .header(ContentType::JSON)
+            .body(json!({ "rate_limit_per_user": 30 }).to_string())
+            .header(Header::new(
+                "x-session-token",
+                owner_session.token.to_string(),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+        let channel: v0::Channel = response.into_json().await.expect("`Channel`");
+        assert!(matches!(
+            channel,
+            v0::Channel::TextChannel {
+                rate_limit_per_user: Some(30),
+                ..
+            }
+        ));
+
+        let send = |session: &Session, content: &str| {
+            harness
+                .client
+                .post(format!("/channels/{}/messages", channels[0].id()))
+                .header(ContentType::JSON)
+                .body(json!({ "content": content }).to_string())
+                .header(Header::new("x-session-token", session.token.to_string()))
+        };
+
+        let response = send(&session, "Hello").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        drop(response);
+
+        let response = send(&session, "Hello again").dispatch().await;
+        assert_eq!(response.status(), Status::TooManyRequests);
+        drop(response);
+
+        // Moderators are exempt
+        for content in ["Hello", "Hello again"] {
+            let response = send(&owner_session, content).dispatch().await;
+            assert_eq!(response.status(), Status::Ok);
+        }
+    }
+
+    #[rocket::async_test]
+    async fn message_mention_limit() {
+        let harness = TestHarness::new().await;
+        let (_, _, owner) = harness.new_user().await;
+        let (_, session, user) = harness.new_user().await;
+        let (_, _, first) = harness.new_user().await;
+        let (_, _, second) = harness.new_user().await;
+
+        let (server, channels) = harness.new_server(&owner).await;
+        for member in [&user, &first, &second] {
+            Member::create(&harness.db, &server, member, Some(channels.clone()))
+                .await
+                .expect("Failed to create member");
+        }
+
+        let limit = |action| PartialServer {
+            mention_limit: Some(MentionLimit { max: 1, action }),
+            ..Default::default()
+        };
+
+        let send = || {
+            harness
+                .client
+                .post(format!("/channels/{}/messages", channels[0].id()))
+                .header(ContentType::JSON)
+                .body(json!({ "content": format!("<@{}> <@{}>", first.id, second.id) }).to_string())
+                .header(Header::new("x-session-token", session.token.to_string()))
+        };
+
+        harness
+            .db
+            .update_server(&server.id, &limit(MentionLimitAction::Block), vec![])
+            .await
+            .expect("Failed to limit mentions");
+
+        let response = send().dispatch().await;
+        assert_eq!(response.status(), Status::BadRequest);
+        drop(response);
+
+        harness
+            .db
+            .update_server(&server.id, &limit(MentionLimitAction::Strip), vec![])
+            .await
+            .expect("Failed to limit mentions");
+
+        let response = send().dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        let message: v0::Message = response.into_json().await.expect("`Message`");
+        assert!(message.mentions.is_none());
+    }
+
+    #[rocket::async_test]
+    async fn message_interactions_require_react() {
+        let harness = TestHarness::new().await;
+        let (_, owner_session, owner) = harness.new_user().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let (server, channels) = harness.new_server(&owner).await;
+        Member::create(&harness.db, &server, &user, Some(channels.clone()))
+            .await
+            .expect("Failed to create member");
+
+        harness
+            .db
+            .update_server(
+                &server.id,
+                &PartialServer {
+                    default_permissions: Some(
+                        (ChannelPermission::ViewChannel as u64
+                            | ChannelPermission::SendMessage as u64) as i64,
+                    ),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .expect("Failed to update default permissions");
+
+        let send = |session: &Session| {
+            harness
+                .client
+                .post(format!("/channels/{}/messages", channels[0].id()))
+                .header(ContentType::JSON)
+                .body(
+                    json!({
+                        "content": "Hello",
+                        "interactions": { "restrict_reactions": true }
+                    })
+                    .to_string(),
+                )
+                .header(Header::new("x-session-token", session.token.to_string()))
+        };
+
+        let response = send(&session).dispatch().await;
+        assert_eq!(response.status(), Status::Forbidden);
+        drop(response);
+
+        let response = send(&owner_session).dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+    }
 }
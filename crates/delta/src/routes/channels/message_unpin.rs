@@ -36,24 +36,28 @@ pub async fn message_unpin(
         .update(db, PartialMessage::default(), vec![FieldsMessage::Pinned])
         .await?;
 
-    SystemMessage::MessageUnpinned {
+    let system_message = SystemMessage::MessageUnpinned {
         id: message.id.clone(),
         by: user.id.clone(),
+    };
+
+    if let Some(target) = channel.route_system_message(db, &system_message).await? {
+        system_message
+            .into_message(target.id().to_string())
+            .send(
+                db,
+                Some(amqp),
+                MessageAuthor::System {
+                    username: &user.username,
+                    avatar: user.avatar.as_ref().map(|file| file.id.as_ref()),
+                },
+                None,
+                None,
+                &target,
+                false,
+            )
+            .await?;
     }
-    .into_message(channel.id().to_string())
-    .send(
-        db,
-        Some(amqp),
-        MessageAuthor::System {
-            username: &user.username,
-            avatar: user.avatar.as_ref().map(|file| file.id.as_ref()),
-        },
-        None,
-        None,
-        &channel,
-        false,
-    )
-    .await?;
 
     Ok(EmptyResponse)
 }
@@ -106,9 +110,15 @@ mod test {
                 attachments: None,
                 replies: None,
                 embeds: None,
+                alt_text: None,
+                mention_confirmation: None,
+                provenance: None,
+                thread: None,
+                effect: None,
                 masquerade: None,
                 interactions: None,
                 flags: None,
+                tts: None,
             },
             v0::MessageAuthor::User(&user.clone().into(&harness.db, Some(&user)).await),
             Some(user.clone().into(&harness.db, Some(&user)).await),
@@ -116,7 +126,9 @@ mod test {
             user.limits().await,
             IdempotencyKey::unchecked_from_string("0".to_string()),
             false,
+            None,
             false,
+            None,
         )
         .await
         .expect("Failed to create message");
@@ -1,6 +1,8 @@
 use guilderia_rocket_okapi::revolt_okapi::openapi3::OpenApi;
 use rocket::Route;
 
+mod bookmark_delete;
+mod bookmark_set;
 mod channel_ack;
 mod channel_delete;
 mod channel_edit;
@@ -8,6 +10,7 @@ mod channel_fetch;
 mod group_add_member;
 mod group_create;
 mod group_remove_member;
+mod interaction_create;
 mod invite_create;
 mod members_fetch;
 mod message_bulk_delete;
@@ -15,6 +18,7 @@ mod message_clear_reactions;
 mod message_delete;
 mod message_edit;
 mod message_fetch;
+mod message_move;
 mod message_pin;
 mod message_query;
 mod message_react;
@@ -24,6 +28,19 @@ mod message_unpin;
 mod message_unreact;
 mod permissions_set;
 mod permissions_set_default;
+mod permissions_sync;
+mod purge_schedule_delete;
+mod purge_schedule_fetch;
+mod purge_schedule_set;
+mod read_receipts_fetch;
+mod thread_create;
+mod thread_delete;
+mod thread_edit;
+mod thread_fetch;
+mod thread_join;
+mod thread_leave;
+mod threads_fetch;
+mod voice_activity;
 mod voice_join;
 mod webhook_create;
 mod webhook_fetch_all;
@@ -43,18 +60,35 @@ pub fn routes() -> (Vec<Route>, OpenApi) {
         message_fetch::fetch,
         message_edit::edit,
         message_bulk_delete::bulk_delete_messages,
+        message_move::move_messages,
+        bookmark_set::set_bookmark,
+        bookmark_delete::delete_bookmark,
+        purge_schedule_fetch::fetch_purge_schedule,
+        purge_schedule_set::set_purge_schedule,
+        purge_schedule_delete::delete_purge_schedule,
+        read_receipts_fetch::fetch_read_receipts,
+        thread_create::create_thread,
+        thread_fetch::fetch_thread,
+        threads_fetch::fetch_threads,
+        thread_edit::edit_thread,
+        thread_delete::delete_thread,
+        thread_join::join_thread,
+        thread_leave::leave_thread,
         message_delete::delete,
         message_unpin::message_unpin,
         group_create::create_group,
         group_add_member::add_member,
         group_remove_member::remove_member,
         voice_join::call,
+        voice_activity::call_activity,
         permissions_set::set_role_permissions,
         permissions_set_default::set_default_permissions,
+        permissions_sync::sync_permissions,
         message_react::react_message,
         message_unreact::unreact_message,
         message_clear_reactions::clear_reactions,
         webhook_create::create_webhook,
         webhook_fetch_all::fetch_webhooks,
+        interaction_create::create_interaction,
     ]
 }
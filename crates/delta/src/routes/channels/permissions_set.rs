@@ -39,7 +39,7 @@ pub async fn set_role_permissions(
                 .await?;
 
             channel
-                .set_role_permission(db, &role_id, data.permissions.clone().into())
+                .set_role_permission(db, &role_id, data.permissions.clone().into(), &user.id)
                 .await?;
 
             Ok(Json(channel.into()))
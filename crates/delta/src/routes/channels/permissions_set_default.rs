@@ -1,6 +1,6 @@
 use guilderia_database::{
     util::{permissions::DatabasePermissionQuery, reference::Reference},
-    Channel, Database, PartialChannel, User,
+    AuditLogAction, AuditLogEntry, Channel, Database, PartialChannel, User,
 };
 use guilderia_models::v0::{self, DataDefaultChannelPermissions};
 use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
@@ -46,10 +46,14 @@ pub async fn set_default_permissions(
             }
         }
         Channel::TextChannel {
+            id,
+            server,
             default_permissions,
             ..
         }
         | Channel::VoiceChannel {
+            id,
+            server,
             default_permissions,
             ..
         } => {
@@ -58,16 +62,27 @@ pub async fn set_default_permissions(
                     .throw_permission_override(default_permissions.map(|x| x.into()), &field)
                     .await?;
 
+                let action = AuditLogAction::PermissionOverrideEdit {
+                    channel: Some(id.clone()),
+                    role: None,
+                    before: default_permissions.unwrap_or_default(),
+                    after: field.clone().into(),
+                };
+                let server = server.clone();
+
                 channel
                     .update(
                         db,
                         PartialChannel {
                             default_permissions: Some(field.into()),
+                            synced: Some(false),
                             ..Default::default()
                         },
                         vec![],
                     )
                     .await?;
+
+                AuditLogEntry::create(db, &server, &user.id, action).await?;
             } else {
                 return Err(create_error!(InvalidOperation));
             }
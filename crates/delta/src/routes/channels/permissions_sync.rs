@@ -0,0 +1,42 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Sync Permissions
+///
+/// Replaces this channel's permission overrides with those of its category.
+///
+/// Channel must be a `TextChannel` or `VoiceChannel` within a category.
+#[openapi(tag = "Channel Permissions")]
+#[post("/<target>/permissions/sync")]
+pub async fn sync_permissions(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<Json<v0::Channel>> {
+    let mut channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ManagePermissions)?;
+
+    let category = query
+        .server_ref()
+        .as_ref()
+        .and_then(|server| server.categories.as_ref())
+        .and_then(|categories| {
+            categories
+                .iter()
+                .find(|category| category.channels.iter().any(|id| id == channel.id()))
+        })
+        .cloned()
+        .ok_or_else(|| create_error!(InvalidOperation))?;
+
+    channel.sync_permissions(db, &category).await?;
+    Ok(Json(channel.into()))
+}
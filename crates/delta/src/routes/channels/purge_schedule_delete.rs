@@ -0,0 +1,29 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::Result;
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Delete Purge Schedule
+///
+/// Stop purging this channel on a schedule.
+#[openapi(tag = "Messaging")]
+#[delete("/<target>/purge_schedule")]
+pub async fn delete_purge_schedule(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<EmptyResponse> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+
+    db.delete_purge_schedule(channel.id())
+        .await
+        .map(|_| EmptyResponse)
+}
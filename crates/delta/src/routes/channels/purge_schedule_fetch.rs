@@ -0,0 +1,29 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Purge Schedule
+///
+/// Fetch the recurring purge scheduled for this channel.
+#[openapi(tag = "Messaging")]
+#[get("/<target>/purge_schedule")]
+pub async fn fetch_purge_schedule(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<Json<v0::PurgeSchedule>> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+
+    db.fetch_purge_schedule(channel.id())
+        .await
+        .map(|schedule| Json(schedule.into()))
+}
@@ -0,0 +1,114 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Channel, Database, PendingAction, PendingActionKind, PurgeSchedule, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Set Purge Schedule
+///
+/// Schedule a recurring purge of this channel, replacing any existing schedule.
+///
+/// Each purge deletes every message sent before it, except pinned messages,
+/// and is recorded in the server's audit log. The schedule is removed if the
+/// user who set it loses `ManageMessages` in the channel.
+///
+/// If the server requires approval for destructive actions, the schedule is
+/// only set once another administrator approves it.
+#[openapi(tag = "Messaging")]
+#[put("/<target>/purge_schedule", data = "<data>")]
+pub async fn set_purge_schedule(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataEditPurgeSchedule>,
+) -> Result<Json<v0::PurgeSchedule>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let channel = target.as_channel(db).await?;
+    let server = match &channel {
+        Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } => server,
+        _ => return Err(create_error!(InvalidOperation)),
+    };
+
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+
+    if let Some(server) = query.server_ref() {
+        if server.require_action_approval {
+            let pending = PendingAction::create(
+                db,
+                server,
+                &user.id,
+                PendingActionKind::SchedulePurge {
+                    channel: channel.id().to_string(),
+                    hour: data.hour,
+                    minute: data.minute,
+                    interval_days: data.interval_days,
+                },
+            )
+            .await?;
+
+            return Err(create_error!(ApprovalRequired { id: pending.id }));
+        }
+    }
+
+    let schedule = PurgeSchedule::new(channel.id(), server, &user.id, data);
+    db.save_purge_schedule(&schedule).await?;
+
+    Ok(Json(schedule.into()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use guilderia_database::PartialServer;
+    use rocket::http::{ContentType, Header, Status};
+
+    #[rocket::async_test]
+    async fn requires_approval_when_enabled() {
+        let harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+        let (mut server, channels) = harness.new_server(&user).await;
+
+        server
+            .update(
+                &harness.db,
+                PartialServer {
+                    require_action_approval: Some(true),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        let response = harness
+            .client
+            .put(format!("/channels/{}/purge_schedule", channels[0].id()))
+            .header(ContentType::JSON)
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .body(json!({ "hour": 4, "interval_days": 1 }).to_string())
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Accepted);
+        drop(response);
+
+        assert!(harness
+            .db
+            .fetch_purge_schedule(channels[0].id())
+            .await
+            .is_err());
+    }
+}
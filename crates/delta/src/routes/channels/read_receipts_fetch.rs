@@ -0,0 +1,100 @@
+use guilderia_config::config;
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Channel, Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Read Receipts
+///
+/// Fetch the most recent message each recipient of a direct message or group has seen.
+#[openapi(tag = "Messaging")]
+#[get("/<target>/receipts")]
+pub async fn fetch_read_receipts(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<Json<Vec<v0::ReadReceipt>>> {
+    if !config().await.features.read_receipts_enabled {
+        return Err(create_error!(FeatureDisabled {
+            feature: "read_receipts".to_string()
+        }));
+    }
+
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    let recipients = match &channel {
+        Channel::DirectMessage { recipients, .. } | Channel::Group { recipients, .. } => recipients,
+        _ => return Err(create_error!(InvalidOperation)),
+    };
+
+    Ok(Json(
+        db.fetch_read_receipts(channel.id())
+            .await?
+            .into_iter()
+            .filter(|receipt| recipients.contains(&receipt.id.user))
+            .map(Into::into)
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use guilderia_database::{events::client::EventV1, Channel};
+    use guilderia_models::v0::{self, DataCreateGroup};
+    use rocket::http::{Header, Status};
+
+    #[rocket::async_test]
+    async fn success_read_receipts() {
+        let mut harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let group = Channel::create_group(
+            &harness.db,
+            DataCreateGroup {
+                ..Default::default()
+            },
+            user.id.clone(),
+        )
+        .await
+        .expect("`Channel`");
+
+        let message_id = ulid::Ulid::new().to_string();
+        let response = harness
+            .client
+            .put(format!("/channels/{}/ack/{}", group.id(), message_id))
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NoContent);
+        drop(response);
+
+        harness
+            .wait_for_event(group.id(), |event| match event {
+                EventV1::MessageSeen { user: id, .. } => id == &user.id,
+                _ => false,
+            })
+            .await;
+
+        let response = harness
+            .client
+            .get(format!("/channels/{}/receipts", group.id()))
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+        let receipts: Vec<v0::ReadReceipt> = response.into_json().await.expect("`ReadReceipt`s");
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].message_id, message_id);
+    }
+}
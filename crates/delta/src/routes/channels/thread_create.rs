@@ -0,0 +1,42 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, Thread, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Create Thread
+///
+/// Start a thread of replies off a message, joining it.
+///
+/// Messages sent in the thread are left out when fetching the channel's messages.
+#[openapi(tag = "Threads")]
+#[post("/<target>/messages/<msg>/thread", data = "<data>")]
+pub async fn create_thread(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+    data: Json<v0::DataCreateThread>,
+) -> Result<Json<v0::Thread>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::SendMessage)?;
+
+    let message = msg.as_message_in_channel(db, channel.id()).await?;
+    Thread::create(db, &message, &user.id, data)
+        .await
+        .map(|thread| Json(thread.into()))
+}
@@ -0,0 +1,54 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, PartialThread, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Edit Thread
+///
+/// Rename a thread, or archive it so it no longer accepts new messages.
+///
+/// Requires `ManageMessages` unless you started the thread.
+#[openapi(tag = "Threads")]
+#[patch("/<target>/messages/<msg>/thread", data = "<data>")]
+pub async fn edit_thread(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+    data: Json<v0::DataEditThread>,
+) -> Result<Json<v0::Thread>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    let mut thread = msg.as_thread_in_channel(db, channel.id()).await?;
+    if thread.creator != user.id {
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+    }
+
+    thread
+        .update(
+            db,
+            PartialThread {
+                name: data.name,
+                archived: data.archived,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    Ok(Json(thread.into()))
+}
@@ -0,0 +1,30 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Thread
+///
+/// Fetch the thread started off a message.
+#[openapi(tag = "Threads")]
+#[get("/<target>/messages/<msg>/thread")]
+pub async fn fetch_thread(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+) -> Result<Json<v0::Thread>> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    msg.as_thread_in_channel(db, channel.id())
+        .await
+        .map(|thread| Json(thread.into()))
+}
@@ -0,0 +1,32 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::Result;
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Join Thread
+///
+/// Follow a thread. Sending a message in a thread joins it automatically.
+#[openapi(tag = "Threads")]
+#[put("/<target>/messages/<msg>/thread/members")]
+pub async fn join_thread(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+) -> Result<EmptyResponse> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    msg.as_thread_in_channel(db, channel.id())
+        .await?
+        .join(db, &user.id)
+        .await
+        .map(|_| EmptyResponse)
+}
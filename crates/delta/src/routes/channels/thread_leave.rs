@@ -0,0 +1,32 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::Result;
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Leave Thread
+///
+/// Stop following a thread.
+#[openapi(tag = "Threads")]
+#[delete("/<target>/messages/<msg>/thread/members")]
+pub async fn leave_thread(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+) -> Result<EmptyResponse> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    msg.as_thread_in_channel(db, channel.id())
+        .await?
+        .leave(db, &user.id)
+        .await
+        .map(|_| EmptyResponse)
+}
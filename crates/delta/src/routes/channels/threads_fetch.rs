@@ -0,0 +1,29 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Threads
+///
+/// Fetch all threads started in this channel.
+#[openapi(tag = "Threads")]
+#[get("/<target>/threads")]
+pub async fn fetch_threads(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<Json<Vec<v0::Thread>>> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    db.fetch_threads(channel.id())
+        .await
+        .map(|threads| Json(threads.into_iter().map(Into::into).collect()))
+}
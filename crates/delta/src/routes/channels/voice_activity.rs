@@ -0,0 +1,148 @@
+use guilderia_config::config;
+use guilderia_database::{
+    util::{reference::Reference, voice_activity},
+    Channel, Database,
+};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Error, Result};
+use rocket::{
+    http::Status,
+    request::{FromRequest, Outcome},
+    serde::json::Json,
+    Request, State,
+};
+use rocket_empty::EmptyResponse;
+
+/// Voice server authenticated using the configured voice server token
+pub struct VoiceServer;
+
+#[async_trait]
+impl<'r> FromRequest<'r> for VoiceServer {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let config = config().await;
+        let expected = &config.api.security.voso_legacy_token;
+        if expected.is_empty() {
+            return Outcome::Error((Status::NotFound, create_error!(VosoUnavailable)));
+        }
+
+        match request.headers().get_one("Authorization") {
+            Some(token) if token == expected => Outcome::Success(VoiceServer),
+            _ => Outcome::Error((Status::Unauthorized, create_error!(NotAuthenticated))),
+        }
+    }
+}
+
+/// # Report Call Activity
+///
+/// Used by the voice server to report participants joining and leaving calls.
+#[openapi(skip)]
+#[post("/<target>/call_activity", data = "<data>")]
+pub async fn call_activity(
+    db: &State<Database>,
+    _voice_server: VoiceServer,
+    target: Reference,
+    data: Json<v0::DataCallActivity>,
+) -> Result<EmptyResponse> {
+    let channel = target.as_channel(db).await?;
+    if !matches!(channel, Channel::VoiceChannel { .. }) {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    match data.into_inner() {
+        v0::DataCallActivity::Join { user } => voice_activity::joined(db, &channel, &user).await,
+        v0::DataCallActivity::Leave { user } => voice_activity::left(db, &channel, &user).await,
+    }
+    .map(|_| EmptyResponse)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use guilderia_database::{events::client::EventV1, util::voice_activity, Channel};
+    use guilderia_models::v0;
+    use rocket::http::{ContentType, Header, Status};
+
+    #[rocket::async_test]
+    async fn announces_calls_in_activity_channel() {
+        let mut harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+        let (_, _, other_user) = harness.new_user().await;
+        let (server, channels) = harness.new_server(&user).await;
+        let text_channel = &channels[0];
+
+        let voice_channel = Channel::create_server_channel(
+            &harness.db,
+            &mut server.clone(),
+            v0::DataCreateServerChannel {
+                channel_type: v0::LegacyServerChannelType::Voice,
+                name: "Voice".to_string(),
+                description: None,
+                nsfw: Some(false),
+            },
+            true,
+        )
+        .await
+        .expect("`Channel`");
+
+        let response = harness
+            .client
+            .patch(format!("/channels/{}", voice_channel.id()))
+            .header(ContentType::JSON)
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .body(json!({ "activity_channel": text_channel.id() }).to_string())
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+        drop(response);
+
+        let voice_channel = harness
+            .db
+            .fetch_channel(voice_channel.id())
+            .await
+            .expect("`Channel`");
+
+        // Only the first to join and the last to leave are announced
+        voice_activity::joined(&harness.db, &voice_channel, &user.id)
+            .await
+            .unwrap();
+        voice_activity::joined(&harness.db, &voice_channel, &other_user.id)
+            .await
+            .unwrap();
+        voice_activity::left(&harness.db, &voice_channel, &user.id)
+            .await
+            .unwrap();
+        voice_activity::left(&harness.db, &voice_channel, &other_user.id)
+            .await
+            .unwrap();
+
+        let message = harness.wait_for_message(text_channel.id()).await;
+        assert_eq!(
+            message.system,
+            Some(v0::SystemMessage::CallStarted {
+                channel: voice_channel.id().to_string(),
+                by: user.id.clone(),
+            })
+        );
+
+        let event = harness
+            .wait_for_event(text_channel.id(), |event| match event {
+                EventV1::Message(v0::Message { system, .. }) => {
+                    matches!(system, Some(v0::SystemMessage::CallEnded { .. }))
+                }
+                _ => false,
+            })
+            .await;
+
+        let EventV1::Message(message) = event else {
+            unreachable!()
+        };
+
+        assert!(matches!(
+            message.system,
+            Some(v0::SystemMessage::CallEnded { channel, .. }) if channel == voice_channel.id()
+        ));
+    }
+}
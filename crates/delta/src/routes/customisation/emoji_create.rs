@@ -28,7 +28,7 @@ pub async fn create_emoji(
     })?;
 
     // Validate we have permission to write into parent
-    match &data.parent {
+    let pending = match &data.parent {
         v0::EmojiParent::Server { id } => {
             let server = db.fetch_server(id).await?;
 
@@ -45,6 +45,8 @@ pub async fn create_emoji(
                     max: config.features.limits.global.server_emoji,
                 }));
             }
+
+            config.features.require_emoji_approval || server.require_emoji_approval
         }
         v0::EmojiParent::Detached => return Err(create_error!(InvalidOperation)),
     };
@@ -60,6 +62,7 @@ pub async fn create_emoji(
         name: data.name,
         animated: "image/gif" == &attachment.content_type,
         nsfw: data.nsfw,
+        pending,
     };
 
     // Save emoji
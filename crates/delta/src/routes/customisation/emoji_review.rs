@@ -0,0 +1,46 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, EmojiParent, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+
+use rocket::{serde::json::Json, State};
+use rocket_empty::EmptyResponse;
+
+/// # Review Emoji
+///
+/// Approve or reject an emoji waiting for approval.
+#[openapi(tag = "Emojis")]
+#[post("/emoji/<emoji_id>/review", data = "<data>")]
+pub async fn review_emoji(
+    db: &State<Database>,
+    user: User,
+    emoji_id: Reference,
+    data: Json<v0::DataReviewEmoji>,
+) -> Result<EmptyResponse> {
+    let mut emoji = emoji_id.as_emoji(db).await?;
+    if !emoji.pending {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    // Reviewing emoji requires managing the server
+    match &emoji.parent {
+        EmojiParent::Server { id } => {
+            let server = db.fetch_server(id).await?;
+
+            let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+            calculate_server_permissions(&mut query)
+                .await
+                .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+        }
+        EmojiParent::Detached => return Err(create_error!(NotFound)),
+    };
+
+    if data.into_inner().approve {
+        emoji.approve(db).await.map(|_| EmptyResponse)
+    } else {
+        emoji.reject(db).await.map(|_| EmptyResponse)
+    }
+}
@@ -0,0 +1,14 @@
+use guilderia_database::User;
+use guilderia_models::v0;
+use rocket::serde::json::Json;
+
+/// # Fetch Login Approval Settings
+///
+/// Fetch whether sessions on new devices must be approved.
+#[openapi(tag = "Session")]
+#[get("/approval")]
+pub async fn fetch_approval(user: User) -> Json<v0::DeviceApprovalSettings> {
+    Json(v0::DeviceApprovalSettings {
+        enabled: user.require_device_approval,
+    })
+}
@@ -0,0 +1,52 @@
+use authifier::{models::Session, Authifier};
+use guilderia_database::{Database, Device, PartialUser, User};
+use guilderia_models::v0;
+use guilderia_result::{create_database_error, ErrorType, Result};
+use rocket::{serde::json::Json, State};
+use rocket_empty::EmptyResponse;
+
+/// # Set Login Approval Settings
+///
+/// Choose whether sessions on new devices must be approved by an existing
+/// session or from an email link before they can be used.
+///
+/// Sessions which already exist when this is enabled are trusted.
+#[openapi(tag = "Session")]
+#[put("/approval", data = "<data>")]
+pub async fn set_approval(
+    db: &State<Database>,
+    authifier: &State<Authifier>,
+    user: User,
+    session: Session,
+    data: Json<v0::DeviceApprovalSettings>,
+) -> Result<EmptyResponse> {
+    let data = data.into_inner();
+    if data.enabled && !user.require_device_approval {
+        let sessions = authifier
+            .database
+            .find_sessions(&session.user_id)
+            .await
+            .map_err(|_| create_database_error!("find", "sessions"))?;
+
+        for session in sessions {
+            match db.fetch_device(&session.id).await {
+                Ok(_) => {}
+                Err(err) if matches!(err.error_type, ErrorType::NotFound) => {
+                    Device::create_trusted(db, &user.id, &session.id, &session.name).await?;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    db.update_user(
+        &user.id,
+        &PartialUser {
+            require_device_approval: Some(data.enabled),
+            ..Default::default()
+        },
+        vec![],
+    )
+    .await
+    .map(|_| EmptyResponse)
+}
@@ -0,0 +1,92 @@
+use guilderia_database::Database;
+use guilderia_result::{create_error, Result};
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+use crate::util::trusted_device::TrustedSession;
+
+/// # Approve Device
+///
+/// Approve a session on a new device from an existing session.
+///
+/// Must be made with a session on a trusted device, API tokens are refused.
+#[openapi(tag = "Session")]
+#[post("/<id>/approve", rank = 2)]
+pub async fn approve_device(
+    db: &State<Database>,
+    trusted: TrustedSession,
+    id: String,
+) -> Result<EmptyResponse> {
+    let user = trusted.user;
+    let mut device = db.fetch_device(&id).await?;
+    if device.user != user.id {
+        return Err(create_error!(NotFound));
+    }
+
+    if device.trusted {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    device.approve(db).await.map(|_| EmptyResponse)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use guilderia_database::{ApiToken, ApiTokenScope, Device, PartialUser};
+    use rocket::http::{Header, Status};
+
+    #[rocket::async_test]
+    async fn refuses_api_tokens() {
+        let harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+
+        harness
+            .db
+            .update_user(
+                &user.id,
+                &PartialUser {
+                    require_device_approval: Some(true),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        // The session itself is on a trusted device
+        Device::create_pending(&harness.db, &user.id, &session.id, &session.name)
+            .await
+            .expect("`Device`")
+            .approve(&harness.db)
+            .await
+            .unwrap();
+
+        let pending = Device::create_pending(&harness.db, &user.id, "pending", "Firefox")
+            .await
+            .expect("`Device`");
+
+        let (_, secret) = ApiToken::create(
+            &harness.db,
+            &user.id,
+            TestHarness::rand_string(),
+            ApiTokenScope::Admin,
+        )
+        .await
+        .expect("`ApiToken`");
+
+        let response = harness
+            .client
+            .post(format!("/auth/session/devices/{}/approve", pending.id))
+            .header(Header::new("x-api-token", secret))
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Forbidden);
+        drop(response);
+
+        let device = harness.db.fetch_device(&pending.id).await.unwrap();
+        assert!(!device.trusted);
+    }
+}
@@ -0,0 +1,14 @@
+use guilderia_database::Database;
+use guilderia_result::Result;
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Approve Device by Email
+///
+/// Approve a session on a new device using the link sent by email.
+#[openapi(tag = "Session")]
+#[post("/approve/<token>", rank = 1)]
+pub async fn approve_device_by_email(db: &State<Database>, token: String) -> Result<EmptyResponse> {
+    let mut device = db.fetch_device_by_approval_token(&token).await?;
+    device.approve(db).await.map(|_| EmptyResponse)
+}
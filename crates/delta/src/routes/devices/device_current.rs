@@ -0,0 +1,17 @@
+use authifier::models::Session;
+use guilderia_database::Database;
+use guilderia_models::v0;
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Current Device
+///
+/// Fetch the device of the current session, to check whether it has been approved yet.
+#[openapi(tag = "Session")]
+#[get("/current")]
+pub async fn fetch_current_device(
+    db: &State<Database>,
+    session: Session,
+) -> Result<Json<v0::Device>> {
+    db.fetch_device(&session.id).await.map(Into::into).map(Json)
+}
@@ -0,0 +1,16 @@
+use guilderia_database::{Database, User};
+use guilderia_models::v0;
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Devices
+///
+/// Fetch all devices which have signed in to your account.
+#[openapi(tag = "Session")]
+#[get("/")]
+pub async fn list_devices(db: &State<Database>, user: User) -> Result<Json<Vec<v0::Device>>> {
+    db.fetch_devices(&user.id)
+        .await
+        .map(|devices| devices.into_iter().map(Into::into).collect())
+        .map(Json)
+}
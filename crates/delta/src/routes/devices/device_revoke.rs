@@ -0,0 +1,46 @@
+use authifier::Authifier;
+use guilderia_database::Database;
+use guilderia_result::{create_database_error, create_error, ErrorType, Result};
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+use crate::util::trusted_device::TrustedSession;
+
+/// # Revoke Device
+///
+/// Sign a device out and forget whether it was trusted.
+///
+/// Revoking a device which is waiting for approval denies it.
+///
+/// Must be made with a session on a trusted device, API tokens are refused.
+#[openapi(tag = "Session")]
+#[delete("/<id>")]
+pub async fn revoke_device(
+    db: &State<Database>,
+    authifier: &State<Authifier>,
+    trusted: TrustedSession,
+    id: String,
+) -> Result<EmptyResponse> {
+    let user = trusted.user;
+    match db.delete_device(&user.id, &id).await {
+        Ok(()) => {}
+        Err(err) if matches!(err.error_type, ErrorType::NotFound) => {}
+        Err(err) => return Err(err),
+    }
+
+    let session = authifier
+        .database
+        .find_session(&id)
+        .await
+        .map_err(|_| create_error!(NotFound))?;
+
+    if session.user_id != user.id {
+        return Err(create_error!(NotFound));
+    }
+
+    session
+        .delete(authifier)
+        .await
+        .map(|_| EmptyResponse)
+        .map_err(|_| create_database_error!("delete", "session"))
+}
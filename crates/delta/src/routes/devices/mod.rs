@@ -0,0 +1,22 @@
+use guilderia_rocket_okapi::revolt_okapi::openapi3::OpenApi;
+use rocket::Route;
+
+mod approval_fetch;
+mod approval_set;
+mod device_approve;
+mod device_approve_email;
+mod device_current;
+mod device_list;
+mod device_revoke;
+
+pub fn routes() -> (Vec<Route>, OpenApi) {
+    openapi_get_routes_spec![
+        approval_fetch::fetch_approval,
+        approval_set::set_approval,
+        device_list::list_devices,
+        device_current::fetch_current_device,
+        device_approve::approve_device,
+        device_approve_email::approve_device_by_email,
+        device_revoke::revoke_device,
+    ]
+}
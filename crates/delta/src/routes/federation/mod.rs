@@ -0,0 +1,32 @@
+//! Read-only federation with allowed instances
+//!
+//! Peers resolve public invites and profile cards using signed requests,
+//! clients resolve them from peers through this instance.
+use guilderia_result::{create_error, Result};
+use guilderia_rocket_okapi::revolt_okapi::openapi3::OpenApi;
+use rocket::Route;
+
+mod peer_invite_fetch;
+mod peer_user_fetch;
+mod remote_invite_fetch;
+mod remote_user_fetch;
+
+pub fn routes() -> (Vec<Route>, OpenApi) {
+    openapi_get_routes_spec![
+        // Peers
+        peer_invite_fetch::fetch_invite,
+        peer_user_fetch::fetch_user,
+        // Clients
+        remote_invite_fetch::fetch_remote_invite,
+        remote_user_fetch::fetch_remote_user,
+    ]
+}
+
+/// Ensure an id can be safely forwarded to a peer
+pub fn validate_id(id: &str) -> Result<()> {
+    if !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        Ok(())
+    } else {
+        Err(create_error!(NotFound))
+    }
+}
@@ -0,0 +1,23 @@
+use guilderia_config::config;
+use guilderia_database::{util::reference::Reference, Database};
+use guilderia_models::v0;
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+use crate::{routes::invites::preview, util::federation::FederatedPeer};
+
+/// # Fetch Invite (Peer)
+///
+/// Fetch the public preview of an invite for another instance.
+#[openapi(skip)]
+#[get("/invites/<target>")]
+pub async fn fetch_invite(
+    db: &State<Database>,
+    _peer: FederatedPeer,
+    target: Reference,
+) -> Result<Json<v0::FederatedInvite>> {
+    Ok(Json(v0::FederatedInvite {
+        instance: config().await.federation.host,
+        invite: preview(db, target).await?,
+    }))
+}
@@ -0,0 +1,30 @@
+use guilderia_config::config;
+use guilderia_database::{util::reference::Reference, Database};
+use guilderia_models::v0;
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+use crate::util::federation::FederatedPeer;
+
+/// # Fetch User (Peer)
+///
+/// Fetch the public profile card of a user for another instance.
+#[openapi(skip)]
+#[get("/users/<target>")]
+pub async fn fetch_user(
+    db: &State<Database>,
+    _peer: FederatedPeer,
+    target: Reference,
+) -> Result<Json<v0::FederatedUser>> {
+    let user = target.as_user(db).await?;
+    Ok(Json(v0::FederatedUser {
+        id: user.id,
+        instance: config().await.federation.host,
+        username: user.username,
+        discriminator: user.discriminator,
+        display_name: user.display_name,
+        avatar: user.avatar.map(|file| file.into()),
+        badges: user.badges.unwrap_or_default() as u32,
+        bot: user.bot.is_some(),
+    }))
+}
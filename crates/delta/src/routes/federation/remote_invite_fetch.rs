@@ -0,0 +1,24 @@
+use guilderia_database::User;
+use guilderia_models::v0;
+use guilderia_result::Result;
+use rocket::serde::json::Json;
+
+use crate::util::federation::fetch;
+
+use super::validate_id;
+
+/// # Fetch Remote Invite
+///
+/// Resolve the preview of an invite hosted on another instance.
+#[openapi(tag = "Federation")]
+#[get("/remote/<host>/invites/<code>")]
+pub async fn fetch_remote_invite(
+    _user: User,
+    host: String,
+    code: String,
+) -> Result<Json<v0::FederatedInvite>> {
+    validate_id(&code)?;
+    fetch(&host, &format!("/federation/invites/{code}"))
+        .await
+        .map(Json)
+}
@@ -0,0 +1,24 @@
+use guilderia_database::User;
+use guilderia_models::v0;
+use guilderia_result::Result;
+use rocket::serde::json::Json;
+
+use crate::util::federation::fetch;
+
+use super::validate_id;
+
+/// # Fetch Remote User
+///
+/// Resolve the profile card of a user on another instance.
+#[openapi(tag = "Federation")]
+#[get("/remote/<host>/users/<id>")]
+pub async fn fetch_remote_user(
+    _user: User,
+    host: String,
+    id: String,
+) -> Result<Json<v0::FederatedUser>> {
+    validate_id(&id)?;
+    fetch(&host, &format!("/federation/users/{id}"))
+        .await
+        .map(Json)
+}
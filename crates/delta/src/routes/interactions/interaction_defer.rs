@@ -0,0 +1,30 @@
+use guilderia_database::{Database, PartialInteraction};
+use guilderia_result::{create_error, Result};
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Defer Interaction Response
+///
+/// Acknowledge an interaction, leaving the bot up to 15 minutes
+/// from when it started to provide the original response.
+#[openapi(tag = "Interactions")]
+#[post("/<id>/<token>/defer")]
+pub async fn defer(db: &State<Database>, id: String, token: String) -> Result<EmptyResponse> {
+    let mut interaction = db.fetch_interaction(&id).await?;
+    interaction.assert_token(&token)?;
+
+    if interaction.is_acknowledged() {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    interaction
+        .update(
+            db,
+            PartialInteraction {
+                deferred: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .map(|_| EmptyResponse)
+}
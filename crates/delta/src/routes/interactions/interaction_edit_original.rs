@@ -0,0 +1,130 @@
+use guilderia_database::{
+    tasks, util::idempotency::IdempotencyKey, Database, Message, PartialInteraction,
+    PartialMessage, AMQP,
+};
+use guilderia_models::v0::{self, Embed};
+use guilderia_result::{create_error, Result};
+use iso8601_timestamp::Timestamp;
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Edit Original Response
+///
+/// Edit the original response to an interaction.
+///
+/// If the interaction was deferred and has no response yet,
+/// this sends the original response instead.
+#[openapi(tag = "Interactions")]
+#[patch("/<id>/<token>/messages/original", data = "<edit>")]
+pub async fn edit_original(
+    db: &State<Database>,
+    amqp: &State<AMQP>,
+    id: String,
+    token: String,
+    edit: Json<v0::DataEditMessage>,
+    idempotency: IdempotencyKey,
+) -> Result<Json<v0::Message>> {
+    let edit = edit.into_inner();
+    edit.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let mut interaction = db.fetch_interaction(&id).await?;
+    interaction.assert_token(&token)?;
+
+    let Some(response) = &interaction.response else {
+        if !interaction.deferred {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        let message = interaction
+            .send_message(
+                db,
+                Some(amqp),
+                v0::DataMessageSend {
+                    nonce: None,
+                    content: edit.content,
+                    attachments: None,
+                    replies: None,
+                    embeds: edit.embeds,
+                    alt_text: None,
+                    mention_confirmation: None,
+                    provenance: None,
+                    thread: None,
+                    effect: None,
+                    masquerade: None,
+                    interactions: None,
+                    flags: None,
+                    tts: None,
+                },
+                idempotency,
+            )
+            .await?;
+
+        interaction
+            .update(
+                db,
+                PartialInteraction {
+                    response: Some(message.id.clone()),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        return Ok(Json(message.into_model(None, None)));
+    };
+
+    let bot = db.fetch_user(&interaction.bot).await?;
+    Message::validate_sum(
+        &edit.content,
+        edit.embeds.as_deref().unwrap_or_default(),
+        bot.limits().await.message_length,
+    )?;
+
+    let mut message = db.fetch_message(response).await?;
+    message.edited = Some(Timestamp::now_utc());
+    let mut partial = PartialMessage {
+        edited: message.edited,
+        content: edit.content.clone(),
+        ..Default::default()
+    };
+
+    let mut remove = vec![];
+    if let Some(content) = &edit.content {
+        message.detect_extensions_in_edit(content, &mut partial, &mut remove);
+    }
+
+    // Keep text embeds unless we are given new ones
+    let mut new_embeds: Vec<Embed> = message
+        .embeds
+        .iter()
+        .flatten()
+        .filter(|embed| matches!(embed, Embed::Text(_)))
+        .cloned()
+        .collect();
+
+    if let Some(embeds) = edit.embeds {
+        new_embeds.clear();
+
+        for embed in embeds {
+            new_embeds.push(message.create_embed(db, embed).await?);
+        }
+    }
+
+    partial.embeds = Some(new_embeds);
+    message.update(db, partial, remove).await?;
+
+    if let Some(content) = edit.content.filter(|_| !message.embeds_suppressed()) {
+        tasks::process_embeds::queue(
+            message.channel.to_string(),
+            message.id.to_string(),
+            content,
+            None,
+        )
+        .await;
+    }
+
+    Ok(Json(message.into_model(None, None)))
+}
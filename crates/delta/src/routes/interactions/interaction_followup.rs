@@ -0,0 +1,41 @@
+use guilderia_database::{util::idempotency::IdempotencyKey, Database, AMQP};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Send Follow-up Message
+///
+/// Send an additional message in response to an interaction
+/// the bot has already acknowledged.
+#[openapi(tag = "Interactions")]
+#[post("/<id>/<token>/followup", data = "<data>")]
+pub async fn followup(
+    db: &State<Database>,
+    amqp: &State<AMQP>,
+    id: String,
+    token: String,
+    data: Json<v0::DataMessageSend>,
+    idempotency: IdempotencyKey,
+) -> Result<Json<v0::Message>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let interaction = db.fetch_interaction(&id).await?;
+    interaction.assert_token(&token)?;
+
+    if !interaction.is_acknowledged() {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    Ok(Json(
+        interaction
+            .send_message(db, Some(amqp), data, idempotency)
+            .await?
+            .into_model(None, None),
+    ))
+}
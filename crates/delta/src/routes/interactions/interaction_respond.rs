@@ -0,0 +1,51 @@
+use guilderia_database::{
+    util::idempotency::IdempotencyKey, Database, PartialInteraction, AMQP,
+};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Respond to Interaction
+///
+/// Send the original response to an interaction.
+#[openapi(tag = "Interactions")]
+#[post("/<id>/<token>", data = "<data>")]
+pub async fn respond(
+    db: &State<Database>,
+    amqp: &State<AMQP>,
+    id: String,
+    token: String,
+    data: Json<v0::DataMessageSend>,
+    idempotency: IdempotencyKey,
+) -> Result<Json<v0::Message>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let mut interaction = db.fetch_interaction(&id).await?;
+    interaction.assert_token(&token)?;
+
+    if interaction.response.is_some() {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    let message = interaction
+        .send_message(db, Some(amqp), data, idempotency)
+        .await?;
+
+    interaction
+        .update(
+            db,
+            PartialInteraction {
+                response: Some(message.id.clone()),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    Ok(Json(message.into_model(None, None)))
+}
@@ -0,0 +1,16 @@
+use guilderia_rocket_okapi::revolt_okapi::openapi3::OpenApi;
+use rocket::Route;
+
+mod interaction_defer;
+mod interaction_edit_original;
+mod interaction_followup;
+mod interaction_respond;
+
+pub fn routes() -> (Vec<Route>, OpenApi) {
+    openapi_get_routes_spec![
+        interaction_defer::defer,
+        interaction_respond::respond,
+        interaction_edit_original::edit_original,
+        interaction_followup::followup,
+    ]
+}
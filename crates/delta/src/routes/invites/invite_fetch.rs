@@ -9,7 +9,12 @@ use rocket::{serde::json::Json, State};
 #[openapi(tag = "Invites")]
 #[get("/<target>")]
 pub async fn fetch(db: &State<Database>, target: Reference) -> Result<Json<v0::InviteResponse>> {
-    Ok(Json(match target.as_invite(db).await? {
+    preview(db, target).await.map(Json)
+}
+
+/// Build the public preview of an invite
+pub async fn preview(db: &Database, target: Reference) -> Result<v0::InviteResponse> {
+    Ok(match target.as_invite(db).await? {
         Invite::Server {
             channel, creator, ..
         } => {
@@ -35,12 +40,16 @@ pub async fn fetch(db: &State<Database>, target: Reference) -> Result<Json<v0::I
 
                     v0::InviteResponse::Server {
                         code: target.id,
-                        member_count: db.fetch_member_count(&server.id).await? as i64,
+                        member_count: server.member_count,
+                        online_count: server.online_count,
+                        requires_rules_acceptance: server.require_rules_acceptance,
+                        screening_questions: server.screening_questions,
                         server_id: server.id,
                         server_name: server.name,
                         server_icon: server.icon.map(|f| f.into()),
                         server_banner: server.banner.map(|f| f.into()),
                         server_flags: server.flags,
+                        server_recognition: server.recognition.map(|r| r.into()),
                         channel_id: id,
                         channel_name: name,
                         channel_description: description,
@@ -74,7 +83,7 @@ pub async fn fetch(db: &State<Database>, target: Reference) -> Result<Json<v0::I
                 _ => unreachable!(),
             }
         }
-    }))
+    })
 }
 
 #[cfg(test)]
@@ -1,4 +1,6 @@
-use guilderia_database::{util::reference::Reference, Channel, Database, Invite, Member, User, AMQP};
+use guilderia_database::{
+    util::reference::Reference, Channel, Database, Invite, Member, ScreeningResponse, User, AMQP,
+};
 use guilderia_models::v0::{self, InviteJoinResponse};
 use guilderia_result::{create_error, Result};
 use rocket::{serde::json::Json, State};
@@ -6,13 +8,17 @@ use rocket::{serde::json::Json, State};
 /// # Join Invite
 ///
 /// Join an invite by its ID
+///
+/// Servers with screening questions require an answer to each of them, and
+/// servers which require rules acceptance require the rules to be accepted.
 #[openapi(tag = "Invites")]
-#[post("/<target>")]
+#[post("/<target>", data = "<data>")]
 pub async fn join(
     db: &State<Database>,
     amqp: &State<AMQP>,
     user: User,
     target: Reference,
+    data: Option<Json<v0::DataJoinInvite>>,
 ) -> Result<Json<v0::InviteJoinResponse>> {
     if user.bot.is_some() {
         return Err(create_error!(IsBot));
@@ -24,7 +30,35 @@ pub async fn join(
     match &invite {
         Invite::Server { server, .. } => {
             let server = db.fetch_server(server).await?;
-            let (_, channels) = Member::create(db, &server, &user, None).await?;
+
+            let answers = data.map(|data| data.into_inner()).unwrap_or_default();
+            if answers.screening_answers.len() != server.screening_questions.len()
+                || answers.screening_answers.iter().any(|answer| {
+                    answer.trim().is_empty()
+                        || answer.chars().count() > v0::MAX_SCREENING_ANSWER_LENGTH
+                })
+            {
+                return Err(create_error!(FailedValidation {
+                    error: "screening_answers".to_string()
+                }));
+            }
+
+            if server.require_rules_acceptance && !answers.accept_rules {
+                return Err(create_error!(FailedValidation {
+                    error: "accept_rules".to_string()
+                }));
+            }
+
+            let (member, channels) = Member::create(db, &server, &user, None).await?;
+            if !server.screening_questions.is_empty() {
+                ScreeningResponse::create(
+                    db,
+                    &member,
+                    &server.screening_questions,
+                    answers.screening_answers,
+                )
+                .await?;
+            }
 
             Ok(Json(InviteJoinResponse::Server {
                 channels: channels.into_iter().map(|c| c.into()).collect(),
@@ -47,3 +81,61 @@ pub async fn join(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use guilderia_database::{Invite, PartialServer};
+    use rocket::http::{ContentType, Header, Status};
+
+    #[rocket::async_test]
+    async fn requires_rules_acceptance() {
+        let harness = TestHarness::new().await;
+        let (_, _, owner) = harness.new_user().await;
+        let (_, session, user) = harness.new_user().await;
+        let (server, channels) = harness.new_server(&owner).await;
+
+        harness
+            .db
+            .update_server(
+                &server.id,
+                &PartialServer {
+                    require_rules_acceptance: Some(true),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        let invite = Invite::create_channel_invite(&harness.db, &owner, &channels[0])
+            .await
+            .expect("`Invite`");
+
+        let response = harness
+            .client
+            .post(format!("/invites/{}", invite.code()))
+            .header(ContentType::JSON)
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .body(json!({}).to_string())
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::BadRequest);
+        drop(response);
+        assert!(harness.db.fetch_member(&server.id, &user.id).await.is_err());
+
+        let response = harness
+            .client
+            .post(format!("/invites/{}", invite.code()))
+            .header(ContentType::JSON)
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .body(json!({ "accept_rules": true }).to_string())
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+        drop(response);
+        assert!(harness.db.fetch_member(&server.id, &user.id).await.is_ok());
+    }
+}
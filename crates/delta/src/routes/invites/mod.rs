@@ -5,6 +5,8 @@ mod invite_delete;
 mod invite_fetch;
 mod invite_join;
 
+pub use invite_fetch::preview;
+
 pub fn routes() -> (Vec<Route>, OpenApi) {
     openapi_get_routes_spec![
         invite_fetch::fetch,
@@ -0,0 +1,88 @@
+use authifier::{models::Session, Authifier};
+use guilderia_config::config;
+use guilderia_database::Database;
+use guilderia_result::{create_error, Result};
+use ldap3::{dn_escape, LdapConnAsync};
+use rocket::{serde::json::Json, State};
+use serde::Deserialize;
+
+use crate::util::provisioning::find_or_create_account;
+
+/// # LDAP Login Data
+#[derive(Deserialize, JsonSchema)]
+pub struct DataLdapLogin {
+    /// Directory login name
+    pub username: String,
+    /// Directory password
+    pub password: String,
+    /// Friendly name used for the session
+    pub friendly_name: Option<String>,
+}
+
+/// Attempt a simple bind against the directory
+async fn bind(url: &str, dn: &str, password: &str) -> Result<bool> {
+    let (conn, mut ldap) = LdapConnAsync::new(url)
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    ldap3::drive!(conn);
+
+    let result = ldap
+        .simple_bind(dn, password)
+        .await
+        .and_then(|result| result.success());
+
+    ldap.unbind().await.ok();
+    Ok(result.is_ok())
+}
+
+/// # Login with LDAP
+///
+/// Authenticate against the configured directory and create a session.
+///
+/// Accounts are created on first login and are matched by email address afterwards.
+/// Existing accounts which weren't created through provisioning can't be signed in to.
+#[openapi(tag = "Session")]
+#[post("/login", data = "<data>")]
+pub async fn login(
+    db: &State<Database>,
+    authifier: &State<Authifier>,
+    data: Json<DataLdapLogin>,
+) -> Result<Json<Session>> {
+    let config = config().await;
+    let ldap = &config.api.provisioning.ldap;
+    if ldap.url.is_empty() {
+        return Err(create_error!(FeatureDisabled {
+            feature: "ldap".to_string()
+        }));
+    }
+
+    let data = data.into_inner();
+    let username = data.username.trim().to_lowercase();
+
+    // Directories accept anonymous binds with an empty password
+    if username.is_empty() || data.password.is_empty() {
+        return Err(create_error!(InvalidCredentials));
+    }
+
+    let dn = ldap.bind_dn.replace("{username}", &dn_escape(&username));
+    if !bind(&ldap.url, &dn, &data.password).await? {
+        return Err(create_error!(InvalidCredentials));
+    }
+
+    let email = format!("{username}@{}", ldap.email_domain);
+    let account = find_or_create_account(db, authifier, email, &username).await?;
+
+    if account.disabled {
+        return Err(create_error!(InvalidCredentials));
+    }
+
+    account
+        .create_session(
+            authifier,
+            data.friendly_name.unwrap_or_else(|| "LDAP".to_string()),
+        )
+        .await
+        .map(Json)
+        .map_err(|_| create_error!(InternalError))
+}
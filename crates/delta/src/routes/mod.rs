@@ -2,22 +2,47 @@ use guilderia_config::Settings;
 use guilderia_rocket_okapi::{revolt_okapi::openapi3::OpenApi, settings::OpenApiSettings};
 pub use rocket::http::Status;
 pub use rocket::response::Redirect;
-use rocket::{Build, Rocket};
+use rocket::{Build, Rocket, Route};
 
+use crate::util::{backup_codes::track_backup_codes, trusted_device::require_trusted_device};
+
+mod account_recovery;
+mod admin;
 mod bots;
 mod channels;
 mod customisation;
+mod devices;
+mod federation;
+mod interactions;
 mod invites;
+mod ldap;
+mod oidc;
 mod onboard;
 mod policy;
 mod push;
 mod root;
 mod safety;
+mod scim;
 mod servers;
 mod sync;
+mod tokens;
 mod users;
 mod webhooks;
 
+/// Strip password login and registration if only single sign-on is allowed
+fn password_login(
+    config: &Settings,
+    (mut routes, mut spec): (Vec<Route>, OpenApi),
+) -> (Vec<Route>, OpenApi) {
+    if config.api.provisioning.oidc.disable_password_login {
+        routes.retain(|route| !matches!(route.name.as_deref(), Some("login" | "create_account")));
+        spec.paths
+            .retain(|path, _| path != "/login" && path != "/create");
+    }
+
+    (routes, spec)
+}
+
 pub fn mount(config: Settings, mut rocket: Rocket<Build>) -> Rocket<Build> {
     let settings = OpenApiSettings::default();
 
@@ -25,20 +50,29 @@ pub fn mount(config: Settings, mut rocket: Rocket<Build>) -> Rocket<Build> {
         mount_endpoints_and_merged_docs! {
             rocket, "/".to_owned(), settings,
             "/" => (vec![], custom_openapi_spec()),
-            "" => openapi_get_routes_spec![root::root],
+            "" => openapi_get_routes_spec![root::root, root::errors],
             "/users" => users::routes(),
+            "/admin" => admin::routes(),
             "/bots" => bots::routes(),
+            "/interactions" => interactions::routes(),
             "/channels" => channels::routes(),
             "/servers" => servers::routes(),
             "/invites" => invites::routes(),
             "/custom" => customisation::routes(),
             "/safety" => safety::routes(),
-            "/auth/account" => rocket_authifier::routes::account::routes(),
-            "/auth/session" => rocket_authifier::routes::session::routes(),
-            "/auth/mfa" => rocket_authifier::routes::mfa::routes(),
+            "/auth/account" => require_trusted_device(password_login(&config, rocket_authifier::routes::account::routes())),
+            "/auth/session" => require_trusted_device(password_login(&config, rocket_authifier::routes::session::routes())),
+            "/auth/mfa" => require_trusted_device(track_backup_codes(rocket_authifier::routes::mfa::routes())),
+            "/auth/account/tokens" => require_trusted_device(tokens::routes()),
+            "/auth/session/devices" => require_trusted_device(devices::routes()),
+            "/auth/account/recovery" => require_trusted_device(account_recovery::routes()),
+            "/auth/ldap" => ldap::routes(),
+            "/auth/oidc" => oidc::routes(),
+            "/federation" => federation::routes(),
+            "/scim/v2" => scim::routes(),
             "/onboard" => onboard::routes(),
             "/policy" => policy::routes(),
-            "/push" => push::routes(),
+            "/push" => require_trusted_device(push::routes()),
             "/sync" => sync::routes(),
             "/webhooks" => webhooks::routes()
         };
@@ -46,20 +80,29 @@ pub fn mount(config: Settings, mut rocket: Rocket<Build>) -> Rocket<Build> {
         mount_endpoints_and_merged_docs! {
             rocket, "/".to_owned(), settings,
             "/" => (vec![], custom_openapi_spec()),
-            "" => openapi_get_routes_spec![root::root],
+            "" => openapi_get_routes_spec![root::root, root::errors],
             "/users" => users::routes(),
+            "/admin" => admin::routes(),
             "/bots" => bots::routes(),
+            "/interactions" => interactions::routes(),
             "/channels" => channels::routes(),
             "/servers" => servers::routes(),
             "/invites" => invites::routes(),
             "/custom" => customisation::routes(),
             "/safety" => safety::routes(),
-            "/auth/account" => rocket_authifier::routes::account::routes(),
-            "/auth/session" => rocket_authifier::routes::session::routes(),
-            "/auth/mfa" => rocket_authifier::routes::mfa::routes(),
+            "/auth/account" => require_trusted_device(password_login(&config, rocket_authifier::routes::account::routes())),
+            "/auth/session" => require_trusted_device(password_login(&config, rocket_authifier::routes::session::routes())),
+            "/auth/mfa" => require_trusted_device(track_backup_codes(rocket_authifier::routes::mfa::routes())),
+            "/auth/account/tokens" => require_trusted_device(tokens::routes()),
+            "/auth/session/devices" => require_trusted_device(devices::routes()),
+            "/auth/account/recovery" => require_trusted_device(account_recovery::routes()),
+            "/auth/ldap" => ldap::routes(),
+            "/auth/oidc" => oidc::routes(),
+            "/federation" => federation::routes(),
+            "/scim/v2" => scim::routes(),
             "/onboard" => onboard::routes(),
             "/policy" => policy::routes(),
-            "/push" => push::routes(),
+            "/push" => require_trusted_device(push::routes()),
             "/sync" => sync::routes()
         };
     }
@@ -68,19 +111,28 @@ pub fn mount(config: Settings, mut rocket: Rocket<Build>) -> Rocket<Build> {
         mount_endpoints_and_merged_docs! {
             rocket, "/0.8".to_owned(), settings,
             "/" => (vec![], custom_openapi_spec()),
-            "" => openapi_get_routes_spec![root::root],
+            "" => openapi_get_routes_spec![root::root, root::errors],
             "/users" => users::routes(),
+            "/admin" => admin::routes(),
             "/bots" => bots::routes(),
+            "/interactions" => interactions::routes(),
             "/channels" => channels::routes(),
             "/servers" => servers::routes(),
             "/invites" => invites::routes(),
             "/custom" => customisation::routes(),
             "/safety" => safety::routes(),
-            "/auth/account" => rocket_authifier::routes::account::routes(),
-            "/auth/session" => rocket_authifier::routes::session::routes(),
-            "/auth/mfa" => rocket_authifier::routes::mfa::routes(),
+            "/auth/account" => require_trusted_device(password_login(&config, rocket_authifier::routes::account::routes())),
+            "/auth/session" => require_trusted_device(password_login(&config, rocket_authifier::routes::session::routes())),
+            "/auth/mfa" => require_trusted_device(track_backup_codes(rocket_authifier::routes::mfa::routes())),
+            "/auth/account/tokens" => require_trusted_device(tokens::routes()),
+            "/auth/session/devices" => require_trusted_device(devices::routes()),
+            "/auth/account/recovery" => require_trusted_device(account_recovery::routes()),
+            "/auth/ldap" => ldap::routes(),
+            "/auth/oidc" => oidc::routes(),
+            "/federation" => federation::routes(),
+            "/scim/v2" => scim::routes(),
             "/onboard" => onboard::routes(),
-            "/push" => push::routes(),
+            "/push" => require_trusted_device(push::routes()),
             "/sync" => sync::routes(),
             "/webhooks" => webhooks::routes()
         };
@@ -88,19 +140,28 @@ pub fn mount(config: Settings, mut rocket: Rocket<Build>) -> Rocket<Build> {
         mount_endpoints_and_merged_docs! {
             rocket, "/0.8".to_owned(), settings,
             "/" => (vec![], custom_openapi_spec()),
-            "" => openapi_get_routes_spec![root::root],
+            "" => openapi_get_routes_spec![root::root, root::errors],
             "/users" => users::routes(),
+            "/admin" => admin::routes(),
             "/bots" => bots::routes(),
+            "/interactions" => interactions::routes(),
             "/channels" => channels::routes(),
             "/servers" => servers::routes(),
             "/invites" => invites::routes(),
             "/custom" => customisation::routes(),
             "/safety" => safety::routes(),
-            "/auth/account" => rocket_authifier::routes::account::routes(),
-            "/auth/session" => rocket_authifier::routes::session::routes(),
-            "/auth/mfa" => rocket_authifier::routes::mfa::routes(),
+            "/auth/account" => require_trusted_device(password_login(&config, rocket_authifier::routes::account::routes())),
+            "/auth/session" => require_trusted_device(password_login(&config, rocket_authifier::routes::session::routes())),
+            "/auth/mfa" => require_trusted_device(track_backup_codes(rocket_authifier::routes::mfa::routes())),
+            "/auth/account/tokens" => require_trusted_device(tokens::routes()),
+            "/auth/session/devices" => require_trusted_device(devices::routes()),
+            "/auth/account/recovery" => require_trusted_device(account_recovery::routes()),
+            "/auth/ldap" => ldap::routes(),
+            "/auth/oidc" => oidc::routes(),
+            "/federation" => federation::routes(),
+            "/scim/v2" => scim::routes(),
             "/onboard" => onboard::routes(),
-            "/push" => push::routes(),
+            "/push" => require_trusted_device(push::routes()),
             "/sync" => sync::routes()
         };
     }
@@ -150,6 +211,7 @@ fn custom_openapi_spec() -> OpenApi {
               "Channel Invites",
               "Channel Permissions",
               "Messaging",
+              "Threads",
               "Interactions",
               "Groups",
               "Voice",
@@ -161,7 +223,8 @@ fn custom_openapi_spec() -> OpenApi {
             "tags": [
               "Server Information",
               "Server Members",
-              "Server Permissions"
+              "Server Permissions",
+              "Server Badges"
             ]
           },
           {
@@ -196,7 +259,8 @@ fn custom_openapi_spec() -> OpenApi {
             "name": "Miscellaneous",
             "tags": [
               "Sync",
-              "Web Push"
+              "Web Push",
+              "Federation"
             ]
           }
         ]),
@@ -301,6 +365,11 @@ fn custom_openapi_spec() -> OpenApi {
                 description: Some("Send and manipulate messages".to_owned()),
                 ..Default::default()
             },
+            Tag {
+                name: "Threads".to_owned(),
+                description: Some("Branch conversations off messages".to_owned()),
+                ..Default::default()
+            },
             Tag {
                 name: "Groups".to_owned(),
                 description: Some("Create, invite users and manipulate groups".to_owned()),
@@ -326,11 +395,21 @@ fn custom_openapi_spec() -> OpenApi {
                 description: Some("Manage permissions for servers".to_owned()),
                 ..Default::default()
             },
+            Tag {
+                name: "Server Badges".to_owned(),
+                description: Some("Manage badges given to server members".to_owned()),
+                ..Default::default()
+            },
             Tag {
                 name: "Invites".to_owned(),
                 description: Some("View, join and delete invites".to_owned()),
                 ..Default::default()
             },
+            Tag {
+                name: "Admin".to_owned(),
+                description: Some("Inspect and manage the platform".to_owned()),
+                ..Default::default()
+            },
             Tag {
                 name: "Account".to_owned(),
                 description: Some("Manage your account".to_owned()),
@@ -353,6 +432,11 @@ fn custom_openapi_spec() -> OpenApi {
                 ),
                 ..Default::default()
             },
+            Tag {
+                name: "Federation".to_owned(),
+                description: Some("Resolve invites and profiles from other instances".to_owned()),
+                ..Default::default()
+            },
             Tag {
                 name: "Sync".to_owned(),
                 description: Some("Upload and retrieve any JSON data between clients".to_owned()),
@@ -0,0 +1,215 @@
+//! OpenID Connect single sign-on
+//!
+//! Clients are sent to the identity provider using the authorization URL and
+//! complete the login by exchanging the returned code for a session.
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use guilderia_config::{ApiProvisioningOidc, OidcRoleMapping};
+use guilderia_database::{Database, Member, PartialMember, User};
+use guilderia_result::{create_error, Result};
+use guilderia_rocket_okapi::revolt_okapi::openapi3::OpenApi;
+use redis_kiss::{get_connection, AsyncCommands};
+use rocket::Route;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+mod oidc_authorize;
+mod oidc_login;
+
+pub fn routes() -> (Vec<Route>, OpenApi) {
+    openapi_get_routes_spec![oidc_authorize::authorize, oidc_login::login]
+}
+
+/// How long a login attempt remains valid for, in seconds
+const STATE_TTL: usize = 600;
+
+/// Key under which a login attempt is stored
+fn key(state: &str) -> String {
+    format!("oidc_state:{state}")
+}
+
+/// Endpoints advertised by the identity provider
+#[derive(Deserialize)]
+pub struct ProviderMetadata {
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: String,
+}
+
+/// Ensure OIDC is configured
+pub fn enabled(oidc: &ApiProvisioningOidc) -> Result<()> {
+    if oidc.issuer.is_empty() {
+        Err(create_error!(FeatureDisabled {
+            feature: "oidc".to_string()
+        }))
+    } else {
+        Ok(())
+    }
+}
+
+/// Fetch the identity provider's endpoints
+pub async fn discover(oidc: &ApiProvisioningOidc) -> Result<ProviderMetadata> {
+    reqwest::get(format!(
+        "{}/.well-known/openid-configuration",
+        oidc.issuer.trim_end_matches('/')
+    ))
+    .await
+    .map_err(|_| create_error!(InternalError))?
+    .json()
+    .await
+    .map_err(|_| create_error!(InternalError))
+}
+
+/// Login attempt awaiting the identity provider's response
+#[derive(Serialize, Deserialize)]
+pub struct LoginAttempt {
+    /// PKCE challenge derived from the verifier held by the client
+    pub code_challenge: String,
+    /// Nonce which the identity provider must include in the ID token
+    pub nonce: String,
+}
+
+/// Derive the PKCE challenge for a verifier
+pub fn code_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// Issue a new single-use state for a login attempt
+///
+/// Only the client which started the login knows the verifier, so codes
+/// can't be redeemed by anyone else.
+pub async fn issue_state(attempt: &LoginAttempt) -> Result<String> {
+    let mut conn = get_connection()
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    let state = nanoid::nanoid!(32);
+    let value = serde_json::to_string(attempt).map_err(|_| create_error!(InternalError))?;
+    conn.set_ex::<_, _, ()>(key(&state), value, STATE_TTL)
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    Ok(state)
+}
+
+/// Consume the state of a login attempt, checking it was started by this client
+pub async fn consume_state(state: &str, code_verifier: &str) -> Result<LoginAttempt> {
+    let mut conn = get_connection()
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    let value: Option<String> = conn.get(key(state)).await.unwrap_or_default();
+    let removed: usize = conn.del(key(state)).await.unwrap_or_default();
+    if removed == 0 {
+        return Err(create_error!(InvalidCredentials));
+    }
+
+    let attempt: LoginAttempt = value
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .ok_or_else(|| create_error!(InvalidCredentials))?;
+
+    if code_challenge(code_verifier) != attempt.code_challenge {
+        return Err(create_error!(InvalidCredentials));
+    }
+
+    Ok(attempt)
+}
+
+/// Check the nonce of an ID token returned by the token endpoint
+///
+/// The token was received directly from the identity provider over TLS, so
+/// its signature doesn't need to be checked to trust its claims.
+pub fn verify_nonce(id_token: &str, nonce: &str) -> Result<()> {
+    let claims: Value = id_token
+        .split('.')
+        .nth(1)
+        .and_then(|payload| URL_SAFE_NO_PAD.decode(payload).ok())
+        .and_then(|payload| serde_json::from_slice(&payload).ok())
+        .ok_or_else(|| create_error!(InvalidCredentials))?;
+
+    if claims["nonce"].as_str() == Some(nonce) {
+        Ok(())
+    } else {
+        Err(create_error!(InvalidCredentials))
+    }
+}
+
+/// Grant or revoke mapped server roles based on the user's groups
+pub async fn apply_role_mappings(
+    db: &Database,
+    user: &User,
+    groups: &[String],
+    mappings: &[OidcRoleMapping],
+) -> Result<()> {
+    for mapping in mappings {
+        // A role is granted if any group mapped onto it matches
+        let granted = mappings.iter().any(|other| {
+            other.server == mapping.server
+                && other.role == mapping.role
+                && groups.contains(&other.group)
+        });
+
+        let mut member = match db.fetch_member(&mapping.server, &user.id).await {
+            Ok(member) => member,
+            Err(_) if granted => {
+                let server = db.fetch_server(&mapping.server).await?;
+                match Member::create(db, &server, user, None).await {
+                    Ok((member, _)) => member,
+                    // Banned users are not let back in
+                    Err(_) => continue,
+                }
+            }
+            Err(_) => continue,
+        };
+
+        if member.roles.contains(&mapping.role) == granted {
+            continue;
+        }
+
+        let mut roles = member.roles.clone();
+        if granted {
+            roles.push(mapping.role.clone());
+        } else {
+            roles.retain(|role| role != &mapping.role);
+        }
+
+        member
+            .update(
+                db,
+                PartialMember {
+                    roles: Some(roles),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+    use super::{code_challenge, verify_nonce};
+
+    #[test]
+    fn derives_code_challenge() {
+        // Example from RFC 7636, appendix B
+        assert_eq!(
+            code_challenge("dBjftJeZ4CVP-mJ92K9qnaBjiTQA4w8arpuGqvhHaho"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn checks_id_token_nonce() {
+        let payload = URL_SAFE_NO_PAD.encode(json!({ "nonce": "expected" }).to_string());
+        let id_token = format!("header.{payload}.signature");
+
+        assert!(verify_nonce(&id_token, "expected").is_ok());
+        assert!(verify_nonce(&id_token, "other").is_err());
+        assert!(verify_nonce("garbage", "expected").is_err());
+    }
+}
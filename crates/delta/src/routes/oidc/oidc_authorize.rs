@@ -0,0 +1,57 @@
+use guilderia_config::config;
+use guilderia_result::{create_error, Result};
+use rocket::serde::json::Json;
+use serde::Serialize;
+use url::Url;
+
+use super::{code_challenge, discover, enabled, issue_state, LoginAttempt};
+
+/// # OIDC Authorization
+#[derive(Serialize, JsonSchema)]
+pub struct OidcAuthorization {
+    /// URL to send the user to
+    pub url: String,
+    /// Secret which must be sent back when completing the login
+    pub code_verifier: String,
+}
+
+/// # Begin OIDC Login
+///
+/// Generate the URL used to sign in with the configured identity provider.
+///
+/// The returned code verifier must be kept by the client and sent along with the code.
+#[openapi(tag = "Session")]
+#[get("/authorize")]
+pub async fn authorize() -> Result<Json<OidcAuthorization>> {
+    let config = config().await;
+    let oidc = &config.api.provisioning.oidc;
+    enabled(oidc)?;
+
+    let metadata = discover(oidc).await?;
+    let code_verifier = nanoid::nanoid!(64);
+    let attempt = LoginAttempt {
+        code_challenge: code_challenge(&code_verifier),
+        nonce: nanoid::nanoid!(32),
+    };
+
+    let state = issue_state(&attempt).await?;
+    let url = Url::parse_with_params(
+        &metadata.authorization_endpoint,
+        &[
+            ("response_type", "code"),
+            ("client_id", &oidc.client_id),
+            ("redirect_uri", &oidc.redirect_uri),
+            ("scope", &oidc.scopes),
+            ("state", &state),
+            ("nonce", &attempt.nonce),
+            ("code_challenge", &attempt.code_challenge),
+            ("code_challenge_method", "S256"),
+        ],
+    )
+    .map_err(|_| create_error!(InternalError))?;
+
+    Ok(Json(OidcAuthorization {
+        url: url.to_string(),
+        code_verifier,
+    }))
+}
@@ -0,0 +1,125 @@
+use authifier::{models::Session, Authifier};
+use guilderia_config::config;
+use guilderia_database::Database;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::util::provisioning::find_or_create_account;
+
+use super::{apply_role_mappings, consume_state, discover, enabled, verify_nonce};
+
+/// # OIDC Login Data
+#[derive(Deserialize, JsonSchema)]
+pub struct DataOidcLogin {
+    /// Authorization code returned by the identity provider
+    pub code: String,
+    /// State returned by the identity provider
+    pub state: String,
+    /// Code verifier returned when the login was started
+    pub code_verifier: String,
+    /// Friendly name used for the session
+    pub friendly_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    id_token: String,
+}
+
+/// # Complete OIDC Login
+///
+/// Exchange the code returned by the identity provider for a session.
+///
+/// Accounts are created on first login and are matched by verified email address afterwards.
+/// Existing accounts which weren't created through provisioning can't be signed in to.
+#[openapi(tag = "Session")]
+#[post("/login", data = "<data>")]
+pub async fn login(
+    db: &State<Database>,
+    authifier: &State<Authifier>,
+    data: Json<DataOidcLogin>,
+) -> Result<Json<Session>> {
+    let config = config().await;
+    let oidc = &config.api.provisioning.oidc;
+    enabled(oidc)?;
+
+    let data = data.into_inner();
+    let attempt = consume_state(&data.state, &data.code_verifier).await?;
+
+    let metadata = discover(oidc).await?;
+    let client = reqwest::Client::new();
+    let token: TokenResponse = client
+        .post(&metadata.token_endpoint)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", &data.code),
+            ("redirect_uri", &oidc.redirect_uri),
+            ("client_id", &oidc.client_id),
+            ("client_secret", &oidc.client_secret),
+            ("code_verifier", &data.code_verifier),
+        ])
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|_| create_error!(InvalidCredentials))?
+        .json()
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    verify_nonce(&token.id_token, &attempt.nonce)?;
+
+    // Claims are fetched directly from the identity provider over TLS
+    let claims: Value = client
+        .get(&metadata.userinfo_endpoint)
+        .bearer_auth(&token.access_token)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|_| create_error!(InvalidCredentials))?
+        .json()
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    let verified = match &claims["email_verified"] {
+        Value::Bool(verified) => *verified,
+        Value::String(verified) => verified == "true",
+        _ => false,
+    };
+
+    let Some(email) = claims["email"].as_str().filter(|_| verified) else {
+        return Err(create_error!(InvalidCredentials));
+    };
+
+    let username = claims["preferred_username"].as_str().unwrap_or(email);
+    let account = find_or_create_account(db, authifier, email.to_string(), username).await?;
+    if account.disabled {
+        return Err(create_error!(InvalidCredentials));
+    }
+
+    if !oidc.role_mappings.is_empty() {
+        let groups: Vec<String> = claims[&oidc.groups_claim]
+            .as_array()
+            .map(|groups| {
+                groups
+                    .iter()
+                    .filter_map(|group| group.as_str().map(ToString::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let user = db.fetch_user(&account.id).await?;
+        apply_role_mappings(db, &user, &groups, &oidc.role_mappings).await?;
+    }
+
+    account
+        .create_session(
+            authifier,
+            data.friendly_name.unwrap_or_else(|| "OIDC".to_string()),
+        )
+        .await
+        .map(Json)
+        .map_err(|_| create_error!(InternalError))
+}
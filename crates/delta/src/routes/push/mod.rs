@@ -2,8 +2,15 @@ use guilderia_rocket_okapi::revolt_okapi::openapi3::OpenApi;
 use rocket::Route;
 
 mod subscribe;
+mod subscription_delete;
+mod subscription_list;
 mod unsubscribe;
 
 pub fn routes() -> (Vec<Route>, OpenApi) {
-    openapi_get_routes_spec![subscribe::subscribe, unsubscribe::unsubscribe]
+    openapi_get_routes_spec![
+        subscribe::subscribe,
+        subscription_list::list,
+        subscription_delete::delete,
+        unsubscribe::unsubscribe
+    ]
 }
@@ -2,6 +2,7 @@ use authifier::{
     models::{Session, WebPushSubscription},
     Authifier,
 };
+use guilderia_database::Database;
 use guilderia_result::{create_database_error, Result};
 use rocket::{serde::json::Json, State};
 use rocket_empty::EmptyResponse;
@@ -11,17 +12,25 @@ use rocket_empty::EmptyResponse;
 /// Create a new Web Push subscription.
 ///
 /// If an existing subscription exists on this session, it will be removed.
+/// The endpoint is also removed from any other session it was attached to.
 #[openapi(tag = "Web Push")]
 #[post("/subscribe", data = "<data>")]
 pub async fn subscribe(
     authifier: &State<Authifier>,
+    db: &State<Database>,
     mut session: Session,
     data: Json<WebPushSubscription>,
 ) -> Result<EmptyResponse> {
-    session.subscription = Some(data.into_inner());
+    let data = data.into_inner();
+    let endpoint = data.endpoint.clone();
+
+    session.subscription = Some(data);
     session
         .save(authifier)
+        .await
+        .map_err(|_| create_database_error!("save", "session"))?;
+
+    db.remove_push_subscription_by_endpoint(&endpoint, &session.id)
         .await
         .map(|_| EmptyResponse)
-        .map_err(|_| create_database_error!("save", "session"))
 }
@@ -0,0 +1,32 @@
+use authifier::{models::Session, Authifier};
+use guilderia_result::{create_database_error, create_error, Result};
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Delete Subscription
+///
+/// Remove the Web Push subscription from one of your sessions.
+#[openapi(tag = "Web Push")]
+#[delete("/subscriptions/<session_id>")]
+pub async fn delete(
+    authifier: &State<Authifier>,
+    session: Session,
+    session_id: String,
+) -> Result<EmptyResponse> {
+    let mut target = authifier
+        .database
+        .find_session(&session_id)
+        .await
+        .map_err(|_| create_error!(NotFound))?;
+
+    if target.user_id != session.user_id {
+        return Err(create_error!(NotFound));
+    }
+
+    target.subscription = None;
+    target
+        .save(authifier)
+        .await
+        .map(|_| EmptyResponse)
+        .map_err(|_| create_database_error!("save", "session"))
+}
@@ -0,0 +1,35 @@
+use authifier::{models::Session, Authifier};
+use guilderia_models::v0;
+use guilderia_result::{create_database_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Subscriptions
+///
+/// Fetch the Web Push subscriptions of all sessions on your account.
+#[openapi(tag = "Web Push")]
+#[get("/subscriptions")]
+pub async fn list(
+    authifier: &State<Authifier>,
+    session: Session,
+) -> Result<Json<Vec<v0::PushSubscription>>> {
+    let sessions = authifier
+        .database
+        .find_sessions(&session.user_id)
+        .await
+        .map_err(|_| create_database_error!("find", "sessions"))?;
+
+    Ok(Json(
+        sessions
+            .into_iter()
+            .filter_map(|session| {
+                session
+                    .subscription
+                    .map(|subscription| v0::PushSubscription {
+                        session_id: session.id,
+                        session_name: session.name,
+                        endpoint: subscription.endpoint,
+                    })
+            })
+            .collect(),
+    ))
+}
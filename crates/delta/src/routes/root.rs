@@ -1,5 +1,6 @@
 use guilderia_config::config;
-use guilderia_result::Result;
+use guilderia_database::tasks::interactions;
+use guilderia_result::{ErrorInfo, Result, ERRORS};
 use rocket::serde::json::Json;
 use serde::Serialize;
 
@@ -32,6 +33,15 @@ pub struct VoiceFeature {
     pub ws: String,
 }
 
+/// # HTTP Interactions Configuration
+#[derive(Serialize, JsonSchema, Debug)]
+pub struct InteractionsFeature {
+    /// Whether bots may receive interactions over HTTP
+    pub enabled: bool,
+    /// Base64 encoded Ed25519 public key interactions are signed with
+    pub public_key: String,
+}
+
 /// # Feature Configuration
 #[derive(Serialize, JsonSchema, Debug)]
 pub struct GuilderiaFeatures {
@@ -47,6 +57,8 @@ pub struct GuilderiaFeatures {
     pub january: Feature,
     /// Voice server configuration
     pub voso: VoiceFeature,
+    /// HTTP interactions configuration
+    pub interactions: InteractionsFeature,
 }
 
 /// # Build Information
@@ -88,6 +100,7 @@ pub struct GuilderiaConfig {
 #[get("/")]
 pub async fn root() -> Result<Json<GuilderiaConfig>> {
     let config = config().await;
+    let interactions_key = interactions::public_key().await;
 
     Ok(Json(GuilderiaConfig {
         revolt: env!("CARGO_PKG_VERSION").to_string(),
@@ -111,6 +124,10 @@ pub async fn root() -> Result<Json<GuilderiaConfig>> {
                 url: config.hosts.voso_legacy,
                 ws: config.hosts.voso_legacy_ws,
             },
+            interactions: InteractionsFeature {
+                enabled: interactions_key.is_some(),
+                public_key: interactions_key.unwrap_or_default(),
+            },
         },
         ws: config.hosts.events,
         app: config.hosts.app,
@@ -135,6 +152,15 @@ pub async fn root() -> Result<Json<GuilderiaConfig>> {
     }))
 }
 
+/// # Error Catalogue
+///
+/// Fetch every error type this node may return, with its stable code and localization key.
+#[openapi(tag = "Core")]
+#[get("/errors")]
+pub async fn errors() -> Json<&'static [ErrorInfo]> {
+    Json(ERRORS)
+}
+
 #[cfg(test)]
 #[cfg(feature = "FIXME: THIS TEST CAUSES cargo test TO SEG FAULT, I HAVE NO CLUE HOW")]
 mod test {
@@ -0,0 +1,34 @@
+use guilderia_database::Database;
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+use super::{Provisioner, ScimGroup, ScimMember, ScimMeta, SCHEMA_GROUP};
+
+/// # Fetch Group
+///
+/// Fetch a server and its members as a group.
+#[openapi(skip)]
+#[get("/Groups/<id>")]
+pub async fn fetch_group(
+    db: &State<Database>,
+    _provisioner: Provisioner,
+    id: String,
+) -> Result<Json<ScimGroup>> {
+    let server = db.fetch_server(&id).await?;
+    let members = db.fetch_all_members(&server.id).await?;
+
+    Ok(Json(ScimGroup {
+        schemas: vec![SCHEMA_GROUP],
+        id: server.id,
+        display_name: server.name,
+        members: members
+            .into_iter()
+            .map(|member| ScimMember {
+                value: member.id.user,
+            })
+            .collect(),
+        meta: ScimMeta {
+            resource_type: "Group",
+        },
+    }))
+}
@@ -0,0 +1,86 @@
+use guilderia_database::{Database, Member, RemovalIntention};
+use guilderia_result::{create_error, ErrorType, Result};
+use rocket::{serde::json::Json, State};
+use rocket_empty::EmptyResponse;
+use serde_json::Value;
+
+use super::{DataScimPatch, Provisioner, ScimMember};
+
+/// # Patch Group
+///
+/// Add users to or remove users from a server.
+///
+/// Groups cannot be created or renamed through provisioning, only membership is managed.
+#[openapi(skip)]
+#[patch("/Groups/<id>", data = "<data>")]
+pub async fn patch_group(
+    db: &State<Database>,
+    _provisioner: Provisioner,
+    id: String,
+    data: Json<DataScimPatch>,
+) -> Result<EmptyResponse> {
+    let server = db.fetch_server(&id).await?;
+
+    for operation in data.into_inner().operations {
+        let Some(path) = operation.path else {
+            continue;
+        };
+
+        // Removals may target a single member, e.g. `members[value eq "id"]`
+        let mut users: Vec<String> = match operation.value {
+            Some(Value::Array(members)) => members
+                .into_iter()
+                .filter_map(|member| serde_json::from_value::<ScimMember>(member).ok())
+                .map(|member| member.value)
+                .collect(),
+            _ => vec![],
+        };
+
+        if let Some(user) = path
+            .strip_prefix("members[value eq \"")
+            .and_then(|rest| rest.strip_suffix("\"]"))
+        {
+            users.push(user.to_string());
+        } else if path != "members" {
+            // Attributes other than membership are ignored
+            continue;
+        }
+
+        match operation.op.to_lowercase().as_str() {
+            "add" => {
+                for user in users {
+                    let user = db.fetch_user(&user).await?;
+                    if !user.provisioned {
+                        return Err(create_error!(NotFound));
+                    }
+
+                    match Member::create(db, &server, &user, None).await {
+                        Ok(_) => {}
+                        Err(error) if matches!(error.error_type, ErrorType::AlreadyInServer) => {}
+                        Err(error) => return Err(error),
+                    }
+                }
+            }
+            "remove" => {
+                for user in users {
+                    if user == server.owner {
+                        return Err(create_error!(InvalidOperation));
+                    }
+
+                    if !db.fetch_user(&user).await?.provisioned {
+                        return Err(create_error!(NotFound));
+                    }
+
+                    if let Ok(member) = db.fetch_member(&server.id, &user).await {
+                        member
+                            .remove(db, &server, RemovalIntention::Kick, false)
+                            .await?;
+                    }
+                }
+            }
+            _ => return Err(create_error!(InvalidOperation)),
+        }
+    }
+
+    Ok(EmptyResponse)
+}
@@ -0,0 +1,420 @@
+//! SCIM 2.0 provisioning API
+//!
+//! Identity providers manage accounts through the `Users` resource, which
+//! maps onto an account and its user, and manage server membership through
+//! the `Groups` resource, which maps onto existing servers.
+use authifier::{models::Account, util::normalise_email, Authifier};
+use guilderia_config::config;
+use guilderia_database::{Database, FieldsUser, PartialUser, User};
+use guilderia_result::{create_database_error, create_error, Error, Result};
+use guilderia_rocket_okapi::revolt_okapi::openapi3::OpenApi;
+use rocket::{
+    http::Status,
+    request::{FromRequest, Outcome},
+    Request, Route,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::util::provisioning::find_account_by_email;
+
+mod group_fetch;
+mod group_patch;
+mod user_create;
+mod user_delete;
+mod user_fetch;
+mod user_list;
+mod user_patch;
+mod user_replace;
+
+pub fn routes() -> (Vec<Route>, OpenApi) {
+    openapi_get_routes_spec![
+        // Users
+        user_list::list_users,
+        user_fetch::fetch_user,
+        user_create::create_user,
+        user_replace::replace_user,
+        user_patch::patch_user,
+        user_delete::delete_user,
+        // Groups
+        group_fetch::fetch_group,
+        group_patch::patch_group,
+    ]
+}
+
+pub const SCHEMA_USER: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+pub const SCHEMA_GROUP: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+pub const SCHEMA_LIST: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+
+/// Compare two secrets without leaking where they differ
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Identity provider authenticated using the configured SCIM bearer token
+pub struct Provisioner;
+
+#[async_trait]
+impl<'r> FromRequest<'r> for Provisioner {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let config = config().await;
+        let expected = &config.api.provisioning.scim_token;
+        if expected.is_empty() {
+            return Outcome::Error((
+                Status::NotFound,
+                create_error!(FeatureDisabled {
+                    feature: "scim".to_string()
+                }),
+            ));
+        }
+
+        match request
+            .headers()
+            .get_one("Authorization")
+            .and_then(|header| header.strip_prefix("Bearer "))
+        {
+            Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => {
+                Outcome::Success(Provisioner)
+            }
+            _ => Outcome::Error((Status::Unauthorized, create_error!(NotAuthenticated))),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScimEmail {
+    pub value: String,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimMeta {
+    pub resource_type: &'static str,
+}
+
+/// SCIM user resource
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUser {
+    pub schemas: Vec<&'static str>,
+    pub id: String,
+    /// Email address of the account, this is what identity providers match on
+    pub user_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    pub emails: Vec<ScimEmail>,
+    pub active: bool,
+    pub meta: ScimMeta,
+}
+
+impl ScimUser {
+    pub fn new(account: &Account, user: &User) -> ScimUser {
+        ScimUser {
+            schemas: vec![SCHEMA_USER],
+            id: account.id.clone(),
+            user_name: account.email.clone(),
+            display_name: user.display_name.clone(),
+            emails: vec![ScimEmail {
+                value: account.email.clone(),
+                primary: true,
+            }],
+            active: !account.disabled,
+            meta: ScimMeta {
+                resource_type: "User",
+            },
+        }
+    }
+}
+
+/// Member reference within a SCIM group
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ScimMember {
+    pub value: String,
+}
+
+/// SCIM group resource
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroup {
+    pub schemas: Vec<&'static str>,
+    pub id: String,
+    pub display_name: String,
+    pub members: Vec<ScimMember>,
+    pub meta: ScimMeta,
+}
+
+/// SCIM list response
+#[derive(Serialize, Debug)]
+pub struct ScimListResponse<T> {
+    pub schemas: Vec<&'static str>,
+    #[serde(rename = "totalResults")]
+    pub total_results: usize,
+    #[serde(rename = "startIndex")]
+    pub start_index: usize,
+    #[serde(rename = "itemsPerPage")]
+    pub items_per_page: usize,
+    #[serde(rename = "Resources")]
+    pub resources: Vec<T>,
+}
+
+impl<T> ScimListResponse<T> {
+    pub fn new(resources: Vec<T>) -> ScimListResponse<T> {
+        ScimListResponse {
+            schemas: vec![SCHEMA_LIST],
+            total_results: resources.len(),
+            start_index: 1,
+            items_per_page: resources.len(),
+            resources,
+        }
+    }
+}
+
+/// User resource as sent by the identity provider
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DataScimUser {
+    pub user_name: String,
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub emails: Vec<ScimEmail>,
+    pub active: Option<bool>,
+    pub password: Option<String>,
+}
+
+impl DataScimUser {
+    /// Email address to use for the account
+    pub fn email(&self) -> Result<String> {
+        self.emails
+            .iter()
+            .find(|email| email.primary)
+            .or_else(|| self.emails.first())
+            .map(|email| email.value.clone())
+            .or_else(|| Some(self.user_name.clone()))
+            .filter(|email| email.contains('@'))
+            .ok_or_else(|| create_error!(InvalidOperation))
+    }
+}
+
+/// Single operation of a SCIM patch request
+#[derive(Deserialize, Debug)]
+pub struct ScimPatchOperation {
+    pub op: String,
+    pub path: Option<String>,
+    pub value: Option<Value>,
+}
+
+/// SCIM patch request
+#[derive(Deserialize, Debug)]
+pub struct DataScimPatch {
+    #[serde(rename = "Operations")]
+    pub operations: Vec<ScimPatchOperation>,
+}
+
+/// Changes to apply to a provisioned user
+#[derive(Default, Debug)]
+pub struct ScimUserChanges {
+    pub email: Option<String>,
+    pub display_name: Option<Option<String>>,
+    pub active: Option<bool>,
+}
+
+impl ScimUserChanges {
+    /// Collect changes from a full replacement of the user
+    pub fn from_replace(data: DataScimUser) -> Result<ScimUserChanges> {
+        Ok(ScimUserChanges {
+            email: Some(data.email()?),
+            display_name: Some(data.display_name),
+            active: data.active,
+        })
+    }
+
+    /// Collect changes from patch operations
+    pub fn from_patch(data: DataScimPatch) -> Result<ScimUserChanges> {
+        let mut changes = ScimUserChanges::default();
+        for operation in data.operations {
+            let remove = operation.op.eq_ignore_ascii_case("remove");
+            match (operation.path, operation.value) {
+                (Some(path), value) => changes.set(&path, value, remove)?,
+                (None, Some(Value::Object(values))) => {
+                    for (path, value) in values {
+                        changes.set(&path, Some(value), remove)?;
+                    }
+                }
+                _ => return Err(create_error!(InvalidOperation)),
+            }
+        }
+
+        Ok(changes)
+    }
+
+    fn set(&mut self, path: &str, value: Option<Value>, remove: bool) -> Result<()> {
+        match path.to_lowercase().as_str() {
+            "active" => {
+                self.active = Some(match value {
+                    Some(Value::Bool(active)) => active,
+                    // Some identity providers send booleans as strings
+                    Some(Value::String(active)) => active.eq_ignore_ascii_case("true"),
+                    _ => return Err(create_error!(InvalidOperation)),
+                })
+            }
+            "displayname" => {
+                self.display_name = Some(if remove {
+                    None
+                } else {
+                    value.and_then(|value| value.as_str().map(ToString::to_string))
+                })
+            }
+            "username" | "emails" | "emails.value" | "emails[primary eq true].value" => {
+                self.email = match value {
+                    Some(Value::String(email)) => Some(email),
+                    Some(Value::Array(emails)) => emails
+                        .into_iter()
+                        .find_map(|email| email["value"].as_str().map(ToString::to_string)),
+                    _ => None,
+                }
+                .filter(|email| email.contains('@'));
+            }
+            // Attributes we have no equivalent for are ignored
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Apply changes to the account and user
+    pub async fn apply(
+        self,
+        db: &Database,
+        authifier: &Authifier,
+        account: &mut Account,
+        user: &mut User,
+    ) -> Result<()> {
+        if let Some(email) = self.email {
+            let normalised = normalise_email(email.clone());
+            if normalised != account.email_normalised {
+                if find_account_by_email(authifier, &email).await?.is_some() {
+                    return Err(create_error!(EmailInUse));
+                }
+
+                account.email = email;
+                account.email_normalised = normalised;
+                account
+                    .save(authifier)
+                    .await
+                    .map_err(|_| create_database_error!("save", "accounts"))?;
+            }
+        }
+
+        if let Some(display_name) = self.display_name {
+            match display_name {
+                Some(display_name) => {
+                    user.update(
+                        db,
+                        PartialUser {
+                            display_name: Some(display_name),
+                            ..Default::default()
+                        },
+                        vec![],
+                    )
+                    .await?
+                }
+                None => {
+                    user.update(db, Default::default(), vec![FieldsUser::DisplayName])
+                        .await?
+                }
+            }
+        }
+
+        if let Some(active) = self.active {
+            set_active(authifier, account, active).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Fetch a provisioned account alongside its user
+///
+/// Local accounts are hidden from the provisioner, so it can't change or
+/// delete them.
+pub async fn fetch_account(
+    db: &Database,
+    authifier: &Authifier,
+    id: &str,
+) -> Result<(Account, User)> {
+    let account = authifier
+        .database
+        .find_account(id)
+        .await
+        .map_err(|_| create_error!(NotFound))?;
+
+    let user = db.fetch_user(id).await?;
+    if !user.provisioned {
+        return Err(create_error!(NotFound));
+    }
+
+    Ok((account, user))
+}
+
+/// Activate or deactivate an account, deactivation signs out all sessions
+pub async fn set_active(authifier: &Authifier, account: &mut Account, active: bool) -> Result<()> {
+    if active == !account.disabled {
+        return Ok(());
+    }
+
+    if active {
+        account.disabled = false;
+        account
+            .save(authifier)
+            .await
+            .map_err(|_| create_database_error!("save", "accounts"))
+    } else {
+        account
+            .disable(authifier)
+            .await
+            .map_err(|_| create_error!(InternalError))?;
+
+        account
+            .delete_all_sessions(authifier, None)
+            .await
+            .map_err(|_| create_error!(InternalError))
+    }
+}
+
+/// Parse a simple `attribute eq "value"` filter
+pub fn parse_filter(filter: &str) -> Option<(String, String)> {
+    let mut parts = filter.splitn(3, ' ');
+    let attribute = parts.next()?;
+    let operator = parts.next()?;
+    let value = parts.next()?.trim().strip_prefix('"')?.strip_suffix('"')?;
+
+    if !operator.eq_ignore_ascii_case("eq") {
+        return None;
+    }
+
+    Some((attribute.to_string(), value.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use guilderia_result::ErrorType;
+
+    use super::fetch_account;
+
+    #[rocket::async_test]
+    async fn hides_local_accounts() {
+        let harness = TestHarness::new().await;
+        let authifier = harness.db.clone().to_authifier().await;
+        let (account, _, _) = harness.new_user().await;
+
+        let err = fetch_account(&harness.db, &authifier, &account.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err.error_type, ErrorType::NotFound));
+    }
+}
@@ -0,0 +1,53 @@
+use authifier::{models::Account, Authifier};
+use guilderia_database::{Database, PartialUser, User};
+use guilderia_result::{create_error, Result};
+use rocket::{http::Status, serde::json::Json, State};
+
+use crate::util::provisioning::{derive_username, find_account_by_email};
+
+use super::{set_active, DataScimUser, Provisioner, ScimUser};
+
+/// # Create User
+///
+/// Provision a new account and user.
+///
+/// Users without a password must sign in through the identity provider or reset their password.
+#[openapi(skip)]
+#[post("/Users", data = "<data>")]
+pub async fn create_user(
+    db: &State<Database>,
+    authifier: &State<Authifier>,
+    _provisioner: Provisioner,
+    data: Json<DataScimUser>,
+) -> Result<(Status, Json<ScimUser>)> {
+    let data = data.into_inner();
+    let email = data.email()?;
+
+    if find_account_by_email(authifier, &email).await?.is_some() {
+        return Err(create_error!(EmailInUse));
+    }
+
+    let password = data.password.clone().unwrap_or_else(|| nanoid::nanoid!(64));
+
+    let mut account = Account::new(authifier, email, password, false)
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    let user = User::create(
+        db,
+        derive_username(&data.user_name),
+        account.id.clone(),
+        PartialUser {
+            display_name: data.display_name,
+            provisioned: Some(true),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    if let Some(active) = data.active {
+        set_active(authifier, &mut account, active).await?;
+    }
+
+    Ok((Status::Created, Json(ScimUser::new(&account, &user))))
+}
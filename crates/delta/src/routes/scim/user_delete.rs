@@ -0,0 +1,23 @@
+use authifier::Authifier;
+use guilderia_database::Database;
+use guilderia_result::Result;
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+use super::{fetch_account, set_active, Provisioner};
+
+/// # Delete User
+///
+/// Deprovision a user, disabling their account and marking the user as deleted.
+#[openapi(skip)]
+#[delete("/Users/<id>")]
+pub async fn delete_user(
+    db: &State<Database>,
+    authifier: &State<Authifier>,
+    _provisioner: Provisioner,
+    id: String,
+) -> Result<EmptyResponse> {
+    let (mut account, mut user) = fetch_account(db, authifier, &id).await?;
+    set_active(authifier, &mut account, false).await?;
+    user.mark_deleted(db).await.map(|_| EmptyResponse)
+}
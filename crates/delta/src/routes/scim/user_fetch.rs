@@ -0,0 +1,21 @@
+use authifier::Authifier;
+use guilderia_database::Database;
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+use super::{fetch_account, Provisioner, ScimUser};
+
+/// # Fetch User
+///
+/// Fetch a provisioned user by its id.
+#[openapi(skip)]
+#[get("/Users/<id>")]
+pub async fn fetch_user(
+    db: &State<Database>,
+    authifier: &State<Authifier>,
+    _provisioner: Provisioner,
+    id: String,
+) -> Result<Json<ScimUser>> {
+    let (account, user) = fetch_account(db, authifier, &id).await?;
+    Ok(Json(ScimUser::new(&account, &user)))
+}
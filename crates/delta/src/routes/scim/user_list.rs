@@ -0,0 +1,41 @@
+use authifier::Authifier;
+use guilderia_database::Database;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+use crate::util::provisioning::find_account_by_email;
+
+use super::{parse_filter, Provisioner, ScimListResponse, ScimUser};
+
+/// # List Users
+///
+/// Find provisioned users, only `userName eq` and `emails eq` filters are supported.
+#[openapi(skip)]
+#[get("/Users?<filter>")]
+pub async fn list_users(
+    db: &State<Database>,
+    authifier: &State<Authifier>,
+    _provisioner: Provisioner,
+    filter: Option<String>,
+) -> Result<Json<ScimListResponse<ScimUser>>> {
+    let (attribute, value) = filter
+        .as_deref()
+        .and_then(parse_filter)
+        .ok_or_else(|| create_error!(InvalidOperation))?;
+
+    let mut resources = vec![];
+    if matches!(
+        attribute.to_lowercase().as_str(),
+        "username" | "emails" | "emails.value"
+    ) {
+        if let Some(account) = find_account_by_email(authifier, &value).await? {
+            if let Ok(user) = db.fetch_user(&account.id).await {
+                if user.provisioned {
+                    resources.push(ScimUser::new(&account, &user));
+                }
+            }
+        }
+    }
+
+    Ok(Json(ScimListResponse::new(resources)))
+}
@@ -0,0 +1,26 @@
+use authifier::Authifier;
+use guilderia_database::Database;
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+use super::{fetch_account, DataScimPatch, Provisioner, ScimUser, ScimUserChanges};
+
+/// # Patch User
+///
+/// Update a provisioned user, used by identity providers to deactivate accounts.
+#[openapi(skip)]
+#[patch("/Users/<id>", data = "<data>")]
+pub async fn patch_user(
+    db: &State<Database>,
+    authifier: &State<Authifier>,
+    _provisioner: Provisioner,
+    id: String,
+    data: Json<DataScimPatch>,
+) -> Result<Json<ScimUser>> {
+    let (mut account, mut user) = fetch_account(db, authifier, &id).await?;
+    ScimUserChanges::from_patch(data.into_inner())?
+        .apply(db, authifier, &mut account, &mut user)
+        .await?;
+
+    Ok(Json(ScimUser::new(&account, &user)))
+}
@@ -0,0 +1,26 @@
+use authifier::Authifier;
+use guilderia_database::Database;
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+use super::{fetch_account, DataScimUser, Provisioner, ScimUser, ScimUserChanges};
+
+/// # Replace User
+///
+/// Replace the attributes of a provisioned user.
+#[openapi(skip)]
+#[put("/Users/<id>", data = "<data>")]
+pub async fn replace_user(
+    db: &State<Database>,
+    authifier: &State<Authifier>,
+    _provisioner: Provisioner,
+    id: String,
+    data: Json<DataScimUser>,
+) -> Result<Json<ScimUser>> {
+    let (mut account, mut user) = fetch_account(db, authifier, &id).await?;
+    ScimUserChanges::from_replace(data.into_inner())?
+        .apply(db, authifier, &mut account, &mut user)
+        .await?;
+
+    Ok(Json(ScimUser::new(&account, &user)))
+}
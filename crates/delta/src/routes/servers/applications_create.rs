@@ -0,0 +1,68 @@
+use iso8601_timestamp::Timestamp;
+use guilderia_database::{
+    util::reference::Reference, Database, ServerApplication, ServerApplicationStatus,
+    ServerProgram, User,
+};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use ulid::Ulid;
+use validator::Validate;
+
+/// # Apply for Recognition
+///
+/// Apply for the server to be verified or join the partner program.
+///
+/// Applications are reviewed by the instance's admins. Only the server
+/// owner may apply and only one application per program may be pending.
+#[openapi(tag = "Server Information")]
+#[post("/<target>/applications", data = "<data>")]
+pub async fn apply(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataCreateServerApplication>,
+) -> Result<Json<v0::ServerApplication>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let server = target.as_server(db).await?;
+    if server.owner != user.id {
+        return Err(create_error!(NotOwner));
+    }
+
+    let program: ServerProgram = data.program.into();
+    if server.flags.unwrap_or_default() & program.flag() as i32 != 0 {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    if db
+        .fetch_server_applications_by_server(&server.id)
+        .await?
+        .iter()
+        .any(|application| {
+            application.program == program && application.status == ServerApplicationStatus::Pending
+        })
+    {
+        return Err(create_error!(ApplicationPending));
+    }
+
+    let application = ServerApplication {
+        id: Ulid::new().to_string(),
+        server: server.id,
+        applicant: user.id,
+        program,
+        reason: data.reason,
+        url: data.url,
+        created_at: Timestamp::now_utc(),
+        status: ServerApplicationStatus::Pending,
+        review: None,
+    };
+
+    db.insert_server_application(&application).await?;
+    Ok(Json(application.into()))
+}
@@ -0,0 +1,27 @@
+use guilderia_database::{util::reference::Reference, Database, User};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Applications
+///
+/// Fetch the server's recognition applications and their outcomes.
+///
+/// Only the server owner may see applications.
+#[openapi(tag = "Server Information")]
+#[get("/<target>/applications")]
+pub async fn list(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<Json<Vec<v0::ServerApplication>>> {
+    let server = target.as_server(db).await?;
+    if server.owner != user.id {
+        return Err(create_error!(NotOwner));
+    }
+
+    let mut applications = db.fetch_server_applications_by_server(&server.id).await?;
+    applications.sort_by(|a, b| b.id.cmp(&a.id));
+
+    Ok(Json(applications.into_iter().map(Into::into).collect()))
+}
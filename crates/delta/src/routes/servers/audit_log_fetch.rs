@@ -0,0 +1,35 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Audit Log
+///
+/// Fetch a server's audit log, newest entries first.
+#[openapi(tag = "Server Information")]
+#[get("/<target>/audit_log?<options..>")]
+pub async fn fetch(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    options: v0::OptionsFetchAuditLog,
+) -> Result<Json<Vec<v0::AuditLogEntry>>> {
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+
+    let limit = options.limit.unwrap_or(50).clamp(1, 100);
+    Ok(Json(
+        db.fetch_audit_log(&server.id, options.before.as_deref(), limit)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+    ))
+}
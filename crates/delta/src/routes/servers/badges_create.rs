@@ -0,0 +1,60 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, File, ServerBadge, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Create Badge
+///
+/// Creates a new server badge.
+#[openapi(tag = "Server Badges")]
+#[post("/<target>/badges", data = "<data>")]
+pub async fn create(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataCreateServerBadge>,
+) -> Result<Json<v0::NewServerBadgeResponse>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+
+    // Badge limits follow the server owner
+    let max = db
+        .fetch_user(&server.owner)
+        .await?
+        .limits()
+        .await
+        .server_badges;
+    if server.badges.len() >= max {
+        return Err(create_error!(TooManyBadges { max }));
+    }
+
+    let badge = ServerBadge {
+        name: data.name,
+        icon: if let Some(icon) = data.icon {
+            Some(File::use_server_badge(db, &icon, &server.id, &user.id).await?)
+        } else {
+            None
+        },
+        colour: data.colour,
+    };
+
+    Ok(Json(v0::NewServerBadgeResponse {
+        id: badge.create(db, &server.id).await?,
+        badge: badge.into(),
+    }))
+}
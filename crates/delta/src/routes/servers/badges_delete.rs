@@ -0,0 +1,39 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Delete Badge
+///
+/// Delete a server badge by its id, removing it from all members.
+#[openapi(tag = "Server Badges")]
+#[delete("/<target>/badges/<badge_id>")]
+pub async fn delete(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    badge_id: String,
+) -> Result<EmptyResponse> {
+    let mut server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+
+    let Some(badge) = server.badges.remove(&badge_id) else {
+        return Err(create_error!(NotFound));
+    };
+
+    if let Some(icon) = &badge.icon {
+        db.mark_attachment_as_deleted(&icon.id).await?;
+    }
+
+    badge
+        .delete(db, &server.id, &badge_id)
+        .await
+        .map(|_| EmptyResponse)
+}
@@ -0,0 +1,77 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, File, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Edit Badge
+///
+/// Edit a server badge by its id.
+#[openapi(tag = "Server Badges")]
+#[patch("/<target>/badges/<badge_id>", data = "<data>")]
+pub async fn edit(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    badge_id: String,
+    data: Json<v0::DataEditServerBadge>,
+) -> Result<Json<v0::ServerBadge>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let mut server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+
+    let Some(mut badge) = server.badges.remove(&badge_id) else {
+        return Err(create_error!(NotFound));
+    };
+
+    let v0::DataEditServerBadge {
+        name,
+        icon,
+        colour,
+        remove,
+    } = data;
+
+    // 1. Remove fields from object
+    for field in remove.unwrap_or_default() {
+        match field {
+            v0::FieldsServerBadge::Icon => {
+                if let Some(icon) = badge.icon.take() {
+                    db.mark_attachment_as_deleted(&icon.id).await?;
+                }
+            }
+            v0::FieldsServerBadge::Colour => badge.colour = None,
+        }
+    }
+
+    // 2. Apply new values
+    if let Some(name) = name {
+        badge.name = name;
+    }
+
+    if let Some(colour) = colour {
+        badge.colour = Some(colour);
+    }
+
+    if let Some(icon) = icon {
+        let icon = File::use_server_badge(db, &icon, &server.id, &user.id).await?;
+        if let Some(previous) = badge.icon.replace(icon) {
+            db.mark_attachment_as_deleted(&previous.id).await?;
+        }
+    }
+
+    badge.save(db, &server.id, &badge_id).await?;
+    Ok(Json(badge.into()))
+}
@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::PermissionQuery;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Badges
+///
+/// Fetch all badges in a server.
+#[openapi(tag = "Server Badges")]
+#[get("/<target>/badges")]
+pub async fn list(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<Json<HashMap<String, v0::ServerBadge>>> {
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    if !query.are_we_a_member().await {
+        return Err(create_error!(NotFound));
+    }
+
+    Ok(Json(
+        server
+            .badges
+            .into_iter()
+            .map(|(id, badge)| (id, badge.into()))
+            .collect(),
+    ))
+}
@@ -0,0 +1,80 @@
+use guilderia_database::{
+    tasks::server_jobs::{self, Job},
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, PendingAction, PendingActionKind, User,
+};
+use guilderia_models::v0;
+
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Bulk Ban Users
+///
+/// Ban many users by their ids in the background.
+///
+/// Progress is reported through `ServerJobProgress` events.
+///
+/// If the server requires approval for destructive actions, this creates
+/// a pending action which another administrator has to approve.
+#[openapi(tag = "Server Members")]
+#[post("/<server>/bans/bulk", data = "<data>")]
+pub async fn bulk_ban(
+    db: &State<Database>,
+    user: User,
+    server: Reference,
+    data: Json<v0::DataBanBulk>,
+) -> Result<Json<v0::ServerJob>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let server = server.as_server(db).await?;
+
+    if data.ids.contains(&user.id) {
+        return Err(create_error!(CannotRemoveYourself));
+    }
+
+    if data.ids.contains(&server.owner) {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::BanMembers)?;
+
+    if server.require_action_approval {
+        let pending = PendingAction::create(
+            db,
+            &server,
+            &user.id,
+            PendingActionKind::BanMembers {
+                ids: data.ids,
+                reason: data.reason,
+            },
+        )
+        .await?;
+
+        return Err(create_error!(ApprovalRequired { id: pending.id }));
+    }
+
+    let rank = query.get_member_rank().unwrap_or(i64::MIN);
+    let id = server_jobs::start(
+        db,
+        &server,
+        user.id,
+        rank,
+        Job::Ban {
+            ids: data.ids,
+            reason: data.reason,
+        },
+    )
+    .await?;
+
+    Ok(Json(v0::ServerJob { id }))
+}
@@ -0,0 +1,37 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    BanSubscription, Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Subscribe to Bans
+///
+/// Follow the bans of another server which shares them.
+///
+/// New bans on the source are either applied here or flagged to
+/// moderators in the moderation alerts channel as they are synced.
+#[openapi(tag = "Server Members")]
+#[post("/<target>/ban_subscriptions", data = "<data>")]
+pub async fn subscribe(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataCreateBanSubscription>,
+) -> Result<Json<v0::BanSubscription>> {
+    let data = data.into_inner();
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    let permissions = calculate_server_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::BanMembers)?;
+
+    let source = db.fetch_server(&data.source).await?;
+
+    BanSubscription::create(db, &server, &source, &user.id, data.mode.into())
+        .await
+        .map(Into::into)
+        .map(Json)
+}
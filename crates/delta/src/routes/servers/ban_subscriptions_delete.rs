@@ -0,0 +1,40 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Unsubscribe from Bans
+///
+/// Stop following the bans of another server.
+///
+/// With `rollback` set, every ban the subscription applied is lifted.
+#[openapi(tag = "Server Members")]
+#[delete("/<target>/ban_subscriptions/<subscription_id>?<options..>")]
+pub async fn unsubscribe(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    subscription_id: String,
+    options: v0::OptionsDeleteBanSubscription,
+) -> Result<EmptyResponse> {
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::BanMembers)?;
+
+    let subscription = db.fetch_ban_subscription(&subscription_id).await?;
+    if subscription.server != server.id {
+        return Err(create_error!(NotFound));
+    }
+
+    subscription
+        .delete(db, options.rollback.unwrap_or_default())
+        .await
+        .map(|_| EmptyResponse)
+}
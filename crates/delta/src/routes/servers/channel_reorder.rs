@@ -0,0 +1,69 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Channel, Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Reorder Channels
+///
+/// Reorder channels and move them between categories in a single operation.
+///
+/// Listed channels are reordered among the positions they currently occupy, so only the channels being moved need to be sent.
+#[openapi(tag = "Server Information")]
+#[patch("/<target>/channels/order", data = "<data>")]
+pub async fn reorder(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataReorderChannels>,
+) -> Result<Json<v0::Server>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let mut server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageChannel)?;
+
+    let positions: Vec<(String, Option<String>)> = data
+        .channels
+        .into_iter()
+        .map(|position| (position.id, position.category))
+        .collect();
+
+    server.reorder_channels(db, &positions).await?;
+
+    // Bring synced channels which moved category in line with their new category
+    for category in server.categories.iter().flatten() {
+        let moved: Vec<String> = positions
+            .iter()
+            .filter(|(_, target)| target.as_ref() == Some(&category.id))
+            .map(|(id, _)| id.to_string())
+            .collect();
+
+        if moved.is_empty() {
+            continue;
+        }
+
+        for mut channel in db.fetch_channels(&moved).await? {
+            if let Channel::TextChannel { synced: true, .. }
+            | Channel::VoiceChannel { synced: true, .. } = channel
+            {
+                if !channel.matches_category(category) {
+                    channel.sync_permissions(db, category).await?;
+                }
+            }
+        }
+    }
+
+    Ok(Json(server.into()))
+}
@@ -0,0 +1,61 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission, PermissionQuery};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Search Channels
+///
+/// Search channels you can see in a server by the start of their name, used to autocomplete channel mentions.
+#[openapi(tag = "Server Information")]
+#[get("/<target>/channels/search?<options..>")]
+pub async fn search(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    options: v0::OptionsSearchChannels,
+) -> Result<Json<Vec<v0::Channel>>> {
+    if options.query.len() > 32 {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    if !query.are_we_a_member().await {
+        return Err(create_error!(NotFound));
+    }
+
+    let limit = options.limit.unwrap_or(10).clamp(1, 25) as usize;
+    let mut channels = db
+        .search_server_channels(&server.id, &options.query)
+        .await?;
+
+    // Present channels in the order they appear in the server
+    channels.sort_by_key(|channel| {
+        server
+            .channels
+            .iter()
+            .position(|id| id == channel.id())
+            .unwrap_or(usize::MAX)
+    });
+
+    let mut visible_channels: Vec<v0::Channel> = vec![];
+    for channel in channels {
+        if visible_channels.len() >= limit {
+            break;
+        }
+
+        let mut channel_query = query.clone().channel(&channel);
+        if calculate_channel_permissions(&mut channel_query)
+            .await
+            .has_channel_permission(ChannelPermission::ViewChannel)
+        {
+            visible_channels.push(channel.into());
+        }
+    }
+
+    Ok(Json(visible_channels))
+}
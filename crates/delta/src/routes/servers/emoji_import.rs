@@ -0,0 +1,117 @@
+use guilderia_config::config;
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_files::fetch_from_s3;
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+use crate::util::emoji_pack::{self, UploadCredentials};
+
+/// # Import Emoji Pack
+///
+/// Create emoji in bulk from a zip archive uploaded to Autumn as an attachment.
+/// Each image is uploaded to Autumn as an emoji on behalf of the importing user.
+///
+/// The archive must contain a `manifest.json` listing the name and image path
+/// of each emoji. Emoji which cannot be imported are skipped and reported back.
+///
+/// Large packs are imported in the background, progress is reported through
+/// `ServerJobProgress` events and failures through an `EmojiImportComplete` event.
+#[openapi(tag = "Server Customisation")]
+#[post("/<target>/emojis/import", data = "<data>")]
+pub async fn import_emoji(
+    db: &State<Database>,
+    user: User,
+    credentials: UploadCredentials,
+    target: Reference,
+    data: Json<v0::DataImportEmojiPack>,
+) -> Result<Json<v0::EmojiImportResult>> {
+    let config = config().await;
+    let server = target.as_server(db).await?;
+
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageCustomisation)?;
+
+    // Find the uploaded pack, it must not have been used for anything yet
+    let file = db.fetch_attachment("attachments", &data.file).await?;
+    if file.uploader_id.as_ref() != Some(&user.id)
+        || file.used_for.is_some()
+        || file.deleted.is_some_and(|v| v)
+        || file.quarantined.is_some_and(|v| v)
+    {
+        return Err(create_error!(NotFound));
+    }
+
+    // Read the images with the same size limit as emoji uploads
+    let mut size_limit = user
+        .limits()
+        .await
+        .file_upload_size_limit
+        .get("emojis")
+        .copied()
+        .unwrap_or_default();
+
+    let pipeline = config.files.pipeline("emojis");
+    if pipeline.max_size > 0 {
+        size_limit = size_limit.min(pipeline.max_size);
+    }
+
+    let hash = file.as_hash(db).await?;
+    let archive = fetch_from_s3(&hash.bucket_id, &hash.path, &hash.iv).await?;
+    let pack = emoji_pack::open(archive)?;
+    if pack.entries.is_empty() {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    // Check that the whole pack fits within the emoji limit before decompressing it
+    let max = config.features.limits.global.server_emoji;
+    let emojis = db.fetch_emoji_by_parent_id(&server.id).await?;
+    if emojis.len() + pack.entries.len() > max {
+        return Err(create_error!(TooManyEmoji { max }));
+    }
+
+    let images = pack.read(size_limit);
+
+    // The pack itself is no longer needed
+    db.mark_attachment_as_deleted(&file.id).await?;
+
+    let pending = config.features.require_emoji_approval || server.require_emoji_approval;
+    if images.len() > config.features.emoji_import.background_threshold {
+        let job = emoji_pack::spawn(
+            db.inner().clone(),
+            credentials,
+            server.id,
+            user.id,
+            pending,
+            images,
+        );
+        return Ok(Json(v0::EmojiImportResult {
+            emojis: vec![],
+            failed: vec![],
+            job: Some(job),
+        }));
+    }
+
+    let (emojis, failed) = emoji_pack::import(
+        db,
+        &credentials,
+        &server.id,
+        &user.id,
+        pending,
+        images,
+        None,
+    )
+    .await;
+
+    Ok(Json(v0::EmojiImportResult {
+        emojis: emojis.into_iter().map(Into::into).collect(),
+        failed,
+        job: None,
+    }))
+}
@@ -91,6 +91,34 @@ pub async fn edit(
         permissions.throw_if_lacking_channel_permission(ChannelPermission::TimeoutMembers)?;
     }
 
+    if data.badges.is_some()
+        || data
+            .remove
+            .as_ref()
+            .map(|x| x.contains(&v0::FieldsMember::Badges))
+            .unwrap_or_default()
+    {
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+    }
+
+    if data.role_mention_optouts.is_some()
+        || data
+            .remove
+            .as_ref()
+            .map(|x| x.contains(&v0::FieldsMember::RoleMentionOptouts))
+            .unwrap_or_default()
+    {
+        // Only the member themselves may choose which roles ping them
+        if user.id != member.id.user {
+            return Err(create_error!(InvalidOperation));
+        }
+    }
+
+    // Only the member themselves may choose to display the server tag
+    if data.show_server_tag.is_some() && user.id != member.id.user {
+        return Err(create_error!(InvalidOperation));
+    }
+
     // Resolve our ranking
     let our_ranking = query.get_member_rank().unwrap_or(i64::MIN);
 
@@ -119,12 +147,33 @@ pub async fn edit(
         }
     }
 
+    // Check that given badges exist
+    if let Some(badges) = &data.badges {
+        if badges
+            .iter()
+            .any(|badge_id| !server.badges.contains_key(badge_id))
+        {
+            return Err(create_error!(NotFound));
+        }
+    }
+
+    // Check that opted out roles are held by the member
+    if let Some(optouts) = &data.role_mention_optouts {
+        let roles = data.roles.as_ref().unwrap_or(&member.roles);
+        if optouts.iter().any(|role_id| !roles.contains(role_id)) {
+            return Err(create_error!(InvalidRole));
+        }
+    }
+
     // Apply edits to the member object
     let v0::DataMemberEdit {
         nickname,
         avatar,
         roles,
         timeout,
+        badges,
+        role_mention_optouts,
+        show_server_tag,
         remove,
     } = data;
 
@@ -132,9 +181,30 @@ pub async fn edit(
         nickname,
         roles,
         timeout,
+        badges,
+        role_mention_optouts,
+        show_server_tag,
         ..Default::default()
     };
 
+    // Roles taken away from the member no longer need to expire
+    if let Some(roles) = &partial.roles {
+        if member
+            .role_expiries
+            .iter()
+            .any(|expiry| !roles.contains(&expiry.role))
+        {
+            partial.role_expiries = Some(
+                member
+                    .role_expiries
+                    .iter()
+                    .filter(|expiry| roles.contains(&expiry.role))
+                    .cloned()
+                    .collect(),
+            );
+        }
+    }
+
     // 1. Remove fields from object
     if let Some(fields) = &remove {
         if fields.contains(&v0::FieldsMember::Avatar) {
@@ -159,5 +229,5 @@ pub async fn edit(
         )
         .await?;
 
-    Ok(Json(member.into()))
+    Ok(Json(member.into_model_with_server(&server)))
 }
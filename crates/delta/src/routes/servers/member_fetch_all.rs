@@ -47,5 +47,6 @@ pub async fn fetch_all(
     Ok(Json(v0::AllMemberResponse {
         members: members.into_iter().map(Into::into).collect(),
         users,
+        hoisted_roles: server.hoisted_roles(),
     }))
 }
@@ -0,0 +1,60 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use iso8601_timestamp::{Duration, Timestamp};
+use rocket::{serde::json::Json, State};
+
+/// # Assign Role
+///
+/// Assign a role to a member, optionally removing it again after `expires_in` seconds.
+#[openapi(tag = "Server Members")]
+#[put("/<target>/members/<member>/roles/<role_id>?<expires_in>")]
+pub async fn assign_role(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    member: Reference,
+    role_id: String,
+    expires_in: Option<u64>,
+) -> Result<Json<v0::Member>> {
+    let server = target.as_server(db).await?;
+    let mut member = member.as_member(db, &server.id).await?;
+
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::AssignRoles)?;
+
+    // Check that we can act against this member and role
+    let our_ranking = query.get_member_rank().unwrap_or(i64::MIN);
+    if member.id.user != user.id && member.get_ranking(&server) <= our_ranking {
+        return Err(create_error!(NotElevated));
+    }
+
+    let role = server
+        .roles
+        .get(&role_id)
+        .ok_or_else(|| create_error!(InvalidRole))?;
+
+    if role.rank <= our_ranking {
+        return Err(create_error!(NotElevated));
+    }
+
+    let expires_at = match expires_in {
+        Some(0) => return Err(create_error!(InvalidOperation)),
+        Some(seconds) => Some(
+            i64::try_from(seconds)
+                .ok()
+                .and_then(|seconds| Timestamp::now_utc().checked_add(Duration::seconds(seconds)))
+                .ok_or_else(|| create_error!(InvalidOperation))?,
+        ),
+        None => None,
+    };
+
+    member.assign_role(db, &role_id, expires_at).await?;
+    Ok(Json(member.into_model_with_server(&server)))
+}
@@ -0,0 +1,44 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Edit Bot Scope
+///
+/// Change which of its requested permissions a bot member is granted.
+#[openapi(tag = "Server Members")]
+#[patch("/<server>/members/<member>/scope", data = "<data>")]
+pub async fn edit_scope(
+    db: &State<Database>,
+    user: User,
+    server: Reference,
+    member: Reference,
+    data: Json<v0::DataEditBotScope>,
+) -> Result<Json<v0::Member>> {
+    let data = data.into_inner();
+
+    let server = server.as_server(db).await?;
+    let mut member = member.as_member(db, &server.id).await?;
+    let bot = db.fetch_bot(&member.id.user).await?;
+
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    let permissions = calculate_server_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageRole)?;
+
+    // Ensure we have access to grant these permissions forwards
+    if !permissions.has(data.permissions) {
+        return Err(create_error!(CannotGiveMissingPermissions));
+    }
+
+    let name = db.fetch_user(&bot.id).await?.username;
+    member
+        .set_bot_scope(db, &server, &bot, &name, data.permissions)
+        .await?;
+
+    Ok(Json(member.into()))
+}
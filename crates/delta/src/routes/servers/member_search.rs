@@ -0,0 +1,45 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::PermissionQuery;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Search Members
+///
+/// Search members of a server by the start of their nickname or username, used to autocomplete user mentions.
+#[openapi(tag = "Server Members")]
+#[get("/<target>/members/search?<options..>")]
+pub async fn search(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    options: v0::OptionsSearchMembers,
+) -> Result<Json<v0::MemberSearchResponse>> {
+    if options.query.len() > 32 {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    if !query.are_we_a_member().await {
+        return Err(create_error!(NotFound));
+    }
+
+    let limit = options.limit.unwrap_or(10).clamp(1, 25) as usize;
+    let members = db.search_members(&server.id, &options.query, limit).await?;
+
+    let user_ids: Vec<String> = members
+        .iter()
+        .map(|member| member.id.user.clone())
+        .collect();
+
+    let users = User::fetch_many_ids_as_mutuals(db, &user, &user_ids).await?;
+
+    Ok(Json(v0::MemberSearchResponse {
+        members: members.into_iter().map(Into::into).collect(),
+        users,
+    }))
+}
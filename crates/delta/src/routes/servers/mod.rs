@@ -1,10 +1,24 @@
 use guilderia_rocket_okapi::guilderia_okapi::openapi3::OpenApi;
 use rocket::Route;
 
+mod applications_create;
+mod applications_list;
+mod audit_log_fetch;
+mod badges_create;
+mod badges_delete;
+mod badges_edit;
+mod badges_list;
+mod ban_bulk;
 mod ban_create;
 mod ban_list;
 mod ban_remove;
+mod ban_subscriptions_create;
+mod ban_subscriptions_delete;
+mod ban_subscriptions_list;
 mod channel_create;
+mod channel_reorder;
+mod channel_search;
+mod emoji_import;
 mod emoji_list;
 mod invites_fetch;
 mod member_edit;
@@ -12,17 +26,28 @@ mod member_experimental_query;
 mod member_fetch;
 mod member_fetch_all;
 mod member_remove;
+mod member_role_assign;
+mod member_scope_edit;
+mod member_search;
+mod pending_actions_approve;
+mod pending_actions_cancel;
+mod pending_actions_list;
 mod permissions_set;
 mod permissions_set_default;
 mod roles_create;
 mod roles_delete;
 mod roles_edit;
 mod roles_fetch;
+mod roles_reorder;
+mod screening_fetch;
+mod screening_list;
 mod server_ack;
 mod server_create;
 mod server_delete;
 mod server_edit;
+mod server_export;
 mod server_fetch;
+mod server_prune;
 
 pub fn routes() -> (Vec<Route>, OpenApi) {
     openapi_get_routes_spec![
@@ -31,22 +56,47 @@ pub fn routes() -> (Vec<Route>, OpenApi) {
         server_fetch::fetch,
         server_edit::edit,
         server_ack::ack,
+        server_export::export,
+        applications_list::list,
+        applications_create::apply,
+        audit_log_fetch::fetch,
+        pending_actions_list::list,
+        pending_actions_approve::approve,
+        pending_actions_cancel::cancel,
         channel_create::create_server_channel,
+        channel_search::search,
+        channel_reorder::reorder,
         member_fetch_all::fetch_all,
+        member_search::search,
         member_remove::kick,
+        server_prune::prune,
         member_fetch::fetch,
         member_edit::edit,
+        member_role_assign::assign_role,
+        screening_fetch::fetch,
+        screening_list::list,
+        member_scope_edit::edit_scope,
         member_experimental_query::member_experimental_query,
         ban_create::ban,
+        ban_bulk::bulk_ban,
         ban_remove::unban,
         ban_list::list,
+        ban_subscriptions_list::list,
+        ban_subscriptions_create::subscribe,
+        ban_subscriptions_delete::unsubscribe,
         invites_fetch::invites,
         roles_create::create,
         roles_edit::edit,
+        roles_reorder::reorder,
         roles_fetch::fetch,
         roles_delete::delete,
+        badges_list::list,
+        badges_create::create,
+        badges_edit::edit,
+        badges_delete::delete,
         permissions_set::set_role_permission,
         permissions_set_default::set_default_permissions,
-        emoji_list::list_emoji
+        emoji_list::list_emoji,
+        emoji_import::import_emoji
     ]
 }
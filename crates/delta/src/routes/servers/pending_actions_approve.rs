@@ -0,0 +1,100 @@
+use guilderia_database::{
+    tasks::server_jobs::{self, Job},
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, Message, PendingActionKind, PurgeSchedule, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{
+    calculate_channel_permissions, calculate_server_permissions, ChannelPermission,
+};
+use guilderia_result::{create_error, Result};
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Approve Pending Action
+///
+/// Approve and carry out a destructive action requested by another administrator.
+///
+/// The user who requested the action must still be allowed to perform it.
+#[openapi(tag = "Server Information")]
+#[post("/<target>/pending_actions/<action_id>/approve")]
+pub async fn approve(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    action_id: String,
+) -> Result<EmptyResponse> {
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+
+    let pending = db.fetch_pending_action(&action_id).await?;
+    if pending.server != server.id || pending.is_expired() {
+        return Err(create_error!(NotFound));
+    }
+
+    // The whole point is that someone else has to agree
+    if pending.initiator == user.id {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    db.delete_pending_action(&pending.id).await?;
+    let initiator = db.fetch_user(&pending.initiator).await?;
+
+    match pending.action {
+        PendingActionKind::DeleteServer => {
+            if server.owner != initiator.id {
+                return Err(create_error!(NotOwner));
+            }
+
+            server.delete(db).await?;
+        }
+        PendingActionKind::BanMembers { ids, reason } => {
+            let mut query = DatabasePermissionQuery::new(db, &initiator).server(&server);
+            calculate_server_permissions(&mut query)
+                .await
+                .throw_if_lacking_channel_permission(ChannelPermission::BanMembers)?;
+
+            let rank = query.get_member_rank().unwrap_or(i64::MIN);
+            server_jobs::start(db, &server, initiator.id, rank, Job::Ban { ids, reason }).await?;
+        }
+        PendingActionKind::PurgeMessages { channel, ids } => {
+            let channel = db.fetch_channel(&channel).await?;
+            let mut query = DatabasePermissionQuery::new(db, &initiator).channel(&channel);
+            calculate_channel_permissions(&mut query)
+                .await
+                .throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+
+            Message::bulk_delete(db, channel.id(), ids).await?;
+        }
+        PendingActionKind::SchedulePurge {
+            channel,
+            hour,
+            minute,
+            interval_days,
+        } => {
+            let channel = db.fetch_channel(&channel).await?;
+            let mut query = DatabasePermissionQuery::new(db, &initiator).channel(&channel);
+            calculate_channel_permissions(&mut query)
+                .await
+                .throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+
+            let schedule = PurgeSchedule::new(
+                channel.id(),
+                &server.id,
+                &initiator.id,
+                v0::DataEditPurgeSchedule {
+                    hour,
+                    minute,
+                    interval_days,
+                },
+            );
+
+            db.save_purge_schedule(&schedule).await?;
+        }
+    }
+
+    Ok(EmptyResponse)
+}
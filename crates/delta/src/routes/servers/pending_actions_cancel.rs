@@ -0,0 +1,39 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Cancel Pending Action
+///
+/// Cancel or reject a destructive action waiting for approval.
+///
+/// The user who requested the action may always cancel it.
+#[openapi(tag = "Server Information")]
+#[delete("/<target>/pending_actions/<action_id>")]
+pub async fn cancel(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    action_id: String,
+) -> Result<EmptyResponse> {
+    let server = target.as_server(db).await?;
+    let pending = db.fetch_pending_action(&action_id).await?;
+    if pending.server != server.id {
+        return Err(create_error!(NotFound));
+    }
+
+    if pending.initiator != user.id {
+        let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+        calculate_server_permissions(&mut query)
+            .await
+            .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+    }
+
+    db.delete_pending_action(&pending.id)
+        .await
+        .map(|_| EmptyResponse)
+}
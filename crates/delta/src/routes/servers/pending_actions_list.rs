@@ -0,0 +1,34 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Pending Actions
+///
+/// Fetch destructive actions waiting for approval in a server.
+#[openapi(tag = "Server Information")]
+#[get("/<target>/pending_actions")]
+pub async fn list(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<Json<Vec<v0::PendingAction>>> {
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+
+    Ok(Json(
+        db.fetch_pending_actions(&server.id)
+            .await?
+            .into_iter()
+            .filter(|action| !action.is_expired())
+            .map(Into::into)
+            .collect(),
+    ))
+}
@@ -41,7 +41,7 @@ pub async fn set_role_permission(
             .await?;
 
         server
-            .set_role_permission(db, &role_id, data.permissions.into())
+            .set_role_permission(db, &role_id, data.permissions.into(), &user.id)
             .await?;
 
         Ok(Json(server.into()))
@@ -1,10 +1,10 @@
 use guilderia_database::{
     util::{permissions::DatabasePermissionQuery, reference::Reference},
-    Database, PartialServer, User,
+    AuditLogAction, AuditLogEntry, Database, PartialServer, User,
 };
 use guilderia_models::v0;
 use guilderia_permissions::{
-    calculate_server_permissions, ChannelPermission, DataPermissionsValue, Override,
+    calculate_server_permissions, ChannelPermission, DataPermissionsValue, Override, OverrideField,
 };
 use guilderia_result::Result;
 use rocket::{serde::json::Json, State};
@@ -39,6 +39,7 @@ pub async fn set_default_permissions(
         )
         .await?;
 
+    let before = server.default_permissions;
     server
         .update(
             db,
@@ -50,5 +51,21 @@ pub async fn set_default_permissions(
         )
         .await?;
 
+    AuditLogEntry::create(
+        db,
+        &server.id,
+        &user.id,
+        AuditLogAction::PermissionOverrideEdit {
+            channel: None,
+            role: None,
+            before: OverrideField { a: before, d: 0 },
+            after: OverrideField {
+                a: data.permissions as i64,
+                d: 0,
+            },
+        },
+    )
+    .await?;
+
     Ok(Json(server.into()))
 }
@@ -56,6 +56,7 @@ pub async fn create(
         rank,
         colour: None,
         hoist: false,
+        group: None,
         permissions: Default::default(),
     };
 
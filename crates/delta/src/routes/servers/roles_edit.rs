@@ -12,7 +12,7 @@ use validator::Validate;
 ///
 /// Edit a role by its id.
 #[openapi(tag = "Server Permissions")]
-#[patch("/<target>/roles/<role_id>", data = "<data>")]
+#[patch("/<target>/roles/<role_id>", data = "<data>", rank = 2)]
 pub async fn edit(
     db: &State<Database>,
     user: User,
@@ -45,6 +45,7 @@ pub async fn edit(
             name,
             colour,
             hoist,
+            group,
             rank,
             remove,
         } = data;
@@ -60,6 +61,7 @@ pub async fn edit(
             name,
             colour,
             hoist,
+            group,
             rank,
             ..Default::default()
         };
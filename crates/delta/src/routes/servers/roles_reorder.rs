@@ -0,0 +1,50 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Reorder Roles
+///
+/// Reorder roles in a single operation.
+///
+/// Listed roles are reordered among the ranks they currently hold, so only the roles being moved need to be sent.
+#[openapi(tag = "Server Permissions")]
+#[patch("/<target>/roles/order", data = "<data>", rank = 1)]
+pub async fn reorder(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataReorderRoles>,
+) -> Result<Json<v0::Server>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let mut server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageRole)?;
+
+    let member_rank = query.get_member_rank().unwrap_or(i64::MIN);
+
+    // Prevent us from moving roles at or above our own
+    for role_id in &data.roles {
+        if let Some(role) = server.roles.get(role_id) {
+            if role.rank <= member_rank {
+                return Err(create_error!(NotElevated));
+            }
+        }
+    }
+
+    server.reorder_roles(db, &data.roles).await?;
+    Ok(Json(server.into()))
+}
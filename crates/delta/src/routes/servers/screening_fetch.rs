@@ -0,0 +1,31 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Member Screening Response
+///
+/// Fetch the answers a member gave to the server's screening questions.
+#[openapi(tag = "Server Members")]
+#[get("/<target>/members/<member>/screening")]
+pub async fn fetch(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    member: Reference,
+) -> Result<Json<v0::ScreeningResponse>> {
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::KickMembers)?;
+
+    db.fetch_screening_response(&server.id, &member.id)
+        .await
+        .map(Into::into)
+        .map(Json)
+}
@@ -1,6 +1,8 @@
-use guilderia_database::{util::reference::Reference, Database, RemovalIntention, User};
+use guilderia_database::{
+    util::reference::Reference, Database, PendingAction, PendingActionKind, RemovalIntention, User,
+};
 use guilderia_models::v0;
-use guilderia_result::Result;
+use guilderia_result::{create_error, Result};
 use rocket::State;
 
 use rocket_empty::EmptyResponse;
@@ -8,6 +10,9 @@ use rocket_empty::EmptyResponse;
 /// # Delete / Leave Server
 ///
 /// Deletes a server if owner otherwise leaves.
+///
+/// If the server requires approval for destructive actions, deleting it
+/// creates a pending action which another administrator has to approve.
 #[openapi(tag = "Server Information")]
 #[delete("/<target>?<options..>")]
 pub async fn delete(
@@ -20,6 +25,14 @@ pub async fn delete(
     let member = db.fetch_member(&target.id, &user.id).await?;
 
     if server.owner == user.id {
+        if server.require_action_approval {
+            let pending =
+                PendingAction::create(db, &server, &user.id, PendingActionKind::DeleteServer)
+                    .await?;
+
+            return Err(create_error!(ApprovalRequired { id: pending.id }));
+        }
+
         server.delete(db).await
     } else {
         member
@@ -2,11 +2,11 @@ use std::collections::HashSet;
 
 use guilderia_database::{
     util::{permissions::DatabasePermissionQuery, reference::Reference},
-    Database, File, PartialServer, User,
+    Channel, Database, File, PartialServer, User,
 };
 use guilderia_models::v0;
 use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
-use guilderia_result::{create_error, Result};
+use guilderia_result::{create_error, ErrorType, Result};
 use rocket::{serde::json::Json, State};
 use validator::Validate;
 
@@ -35,63 +35,143 @@ pub async fn edit(
     // Check permissions
     if data.name.is_none()
         && data.description.is_none()
+        && data.tag.is_none()
         && data.icon.is_none()
         && data.banner.is_none()
         && data.system_messages.is_none()
+        && data.starboard.is_none()
+        && data.new_member_restrictions.is_none()
+        && data.mention_limit.is_none()
+        && data.content_filter.is_none()
         && data.categories.is_none()
         // && data.nsfw.is_none()
         && data.flags.is_none()
         && data.analytics.is_none()
         && data.discoverable.is_none()
+        && data.default_notifications.is_none()
+        && data.require_emoji_approval.is_none()
+        && data.require_rules_acceptance.is_none()
+        && data.screening_questions.is_none()
+        && data.disable_tts.is_none()
+        && data.require_action_approval.is_none()
+        && data.share_bans.is_none()
         && data.remove.is_none()
     {
         return Ok(Json(server.into()));
     } else if data.name.is_some()
         || data.description.is_some()
+        || data.tag.is_some()
         || data.icon.is_some()
         || data.banner.is_some()
         || data.system_messages.is_some()
+        || data.starboard.is_some()
+        || data.new_member_restrictions.is_some()
+        || data.mention_limit.is_some()
+        || data.content_filter.is_some()
         || data.analytics.is_some()
+        || data.default_notifications.is_some()
+        || data.require_emoji_approval.is_some()
+        || data.require_rules_acceptance.is_some()
+        || data.screening_questions.is_some()
+        || data.disable_tts.is_some()
+        || data.share_bans.is_some()
         || data.remove.is_some()
     {
         permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
     }
 
     // Check we are privileged if changing sensitive fields
-    if (data.flags.is_some() /*|| data.nsfw.is_some()*/ || data.discoverable.is_some())
+    if (data.flags.is_some() /*|| data.nsfw.is_some()*/
+        || data.discoverable.is_some()
+        || data
+            .remove
+            .as_ref()
+            .is_some_and(|fields| fields.contains(&v0::FieldsServer::Recognition)))
         && !user.privileged
     {
         return Err(create_error!(NotPrivileged));
     }
 
+    // Only the owner may change who has to approve destructive actions
+    if data.require_action_approval.is_some() && server.owner != user.id {
+        return Err(create_error!(NotOwner));
+    }
+
     // Changing categories requires manage channel
-    if data.categories.is_some() {
+    if let Some(categories) = &data.categories {
         permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageChannel)?;
+
+        // Changing category permissions also requires manage permissions
+        let permissions_changed = categories.iter().any(|category| {
+            match server
+                .categories
+                .iter()
+                .flatten()
+                .find(|existing| existing.id == category.id)
+            {
+                Some(existing) => {
+                    existing.default_permissions != category.default_permissions
+                        || existing.role_permissions != category.role_permissions
+                }
+                None => {
+                    category.default_permissions.is_some() || !category.role_permissions.is_empty()
+                }
+            }
+        });
+
+        if permissions_changed {
+            permissions
+                .throw_if_lacking_channel_permission(ChannelPermission::ManagePermissions)?;
+        }
     }
 
     let v0::DataEditServer {
         name,
         description,
+        tag,
         icon,
         banner,
         categories,
         system_messages,
+        starboard,
+        new_member_restrictions,
+        mention_limit,
+        content_filter,
         flags,
         // nsfw,
         discoverable,
         analytics,
+        default_notifications,
+        require_emoji_approval,
+        require_rules_acceptance,
+        screening_questions,
+        disable_tts,
+        require_action_approval,
+        share_bans,
         remove,
     } = data;
 
     let mut partial = PartialServer {
         name,
         description,
+        tag: tag.map(|tag| tag.to_uppercase()),
         categories: categories.map(|v| v.into_iter().map(Into::into).collect()),
         system_messages: system_messages.map(Into::into),
+        starboard: starboard.map(Into::into),
+        new_member_restrictions: new_member_restrictions.map(Into::into),
+        mention_limit: mention_limit.map(Into::into),
+        content_filter: content_filter.map(Into::into),
         flags,
         // nsfw,
         discoverable,
         analytics,
+        default_notifications: default_notifications.map(Into::into),
+        require_emoji_approval,
+        require_rules_acceptance,
+        screening_questions,
+        disable_tts,
+        require_action_approval,
+        share_bans,
         ..Default::default()
     };
 
@@ -117,6 +197,36 @@ pub async fn edit(
                 return Err(create_error!(NotFound));
             }
         }
+
+        for templates in [
+            &system_messages.user_joined_templates,
+            &system_messages.user_left_templates,
+        ] {
+            if templates.len() > 10
+                || templates
+                    .iter()
+                    .any(|template| template.is_empty() || template.len() > 2000)
+            {
+                return Err(create_error!(FailedValidation {
+                    error: "system message templates must be 1-2000 characters, at most 10"
+                        .to_string()
+                }));
+            }
+        }
+    }
+
+    if let Some(starboard) = &partial.starboard {
+        if !server.channels.contains(&starboard.channel) {
+            return Err(create_error!(NotFound));
+        }
+    }
+
+    if let Some(tag) = &partial.tag {
+        match db.fetch_server_by_tag(tag).await {
+            Ok(existing) if existing.id != server.id => return Err(create_error!(ServerTagTaken)),
+            Err(err) if !matches!(err.error_type, ErrorType::NotFound) => return Err(err),
+            _ => {}
+        }
     }
 
     if let Some(categories) = &mut partial.categories {
@@ -148,6 +258,7 @@ pub async fn edit(
         server.banner = partial.banner.clone();
     }
 
+    let categories_changed = partial.categories.is_some();
     server
         .update(
             db,
@@ -158,5 +269,20 @@ pub async fn edit(
         )
         .await?;
 
+    // 5. Bring synced channels in line with their categories
+    if categories_changed {
+        for category in server.categories.iter().flatten() {
+            for mut channel in db.fetch_channels(&category.channels).await? {
+                if let Channel::TextChannel { synced: true, .. }
+                | Channel::VoiceChannel { synced: true, .. } = channel
+                {
+                    if !channel.matches_category(category) {
+                        channel.sync_permissions(db, category).await?;
+                    }
+                }
+            }
+        }
+    }
+
     Ok(Json(server.into()))
 }
@@ -0,0 +1,35 @@
+use guilderia_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+use crate::util::server_export;
+
+/// # Export Server Data
+///
+/// Export screening answers, role membership or bot command usage as CSV or JSON in the background.
+///
+/// The file is uploaded to Autumn under the `exports` tag, where only the
+/// requesting user may download it, and sent through a `ServerExportComplete` event.
+#[openapi(tag = "Server Information")]
+#[post("/<target>/exports", data = "<data>")]
+pub async fn export(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataCreateServerExport>,
+) -> Result<Json<v0::ServerJob>> {
+    let server = target.as_server(db).await?;
+
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+
+    let id = server_export::spawn(db.inner().clone(), server, user.id, data.into_inner());
+    Ok(Json(v0::ServerJob { id }))
+}
@@ -0,0 +1,61 @@
+use guilderia_database::{
+    tasks::server_jobs::{self, Job},
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use guilderia_models::v0;
+
+use guilderia_permissions::{calculate_server_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Prune Inactive Members
+///
+/// Kick members who have been inactive for a number of days in the background.
+///
+/// Progress is reported through `ServerJobProgress` events.
+#[openapi(tag = "Server Members")]
+#[post("/<server>/prune", data = "<data>")]
+pub async fn prune(
+    db: &State<Database>,
+    user: User,
+    server: Reference,
+    data: Json<v0::DataServerPrune>,
+) -> Result<Json<v0::ServerJob>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let server = server.as_server(db).await?;
+    if data
+        .exempt_roles
+        .iter()
+        .any(|role| !server.roles.contains_key(role))
+    {
+        return Err(create_error!(InvalidRole));
+    }
+
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::KickMembers)?;
+
+    let rank = query.get_member_rank().unwrap_or(i64::MIN);
+    let id = server_jobs::start(
+        db,
+        &server,
+        user.id,
+        rank,
+        Job::Prune {
+            days: data.days,
+            exempt_roles: data.exempt_roles,
+        },
+    )
+    .await?;
+
+    Ok(Json(v0::ServerJob { id }))
+}
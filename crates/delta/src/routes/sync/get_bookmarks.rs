@@ -0,0 +1,17 @@
+use guilderia_database::{Database, User};
+use guilderia_models::v0;
+use guilderia_result::Result;
+use rocket::serde::json::Json;
+use rocket::State;
+
+/// # Fetch Bookmarks
+///
+/// Fetch all of your bookmarks across channels.
+#[openapi(tag = "Sync")]
+#[get("/bookmarks")]
+pub async fn bookmarks(db: &State<Database>, user: User) -> Result<Json<Vec<v0::Bookmark>>> {
+    db.fetch_bookmarks(&user.id)
+        .await
+        .map(|v| v.into_iter().map(|b| b.into()).collect())
+        .map(Json)
+}
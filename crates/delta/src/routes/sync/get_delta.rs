@@ -0,0 +1,150 @@
+use chrono::Utc;
+use guilderia_database::{Channel, Database, User};
+use guilderia_models::v0;
+use guilderia_result::Result;
+use rocket::serde::json::Json;
+use rocket::State;
+use ulid::Ulid;
+
+/// Whether an id was generated after the given timestamp
+fn created_since(id: &str, since: i64) -> bool {
+    Ulid::from_string(id).is_ok_and(|id| id.timestamp_ms() as i64 > since)
+}
+
+/// # Fetch Sync Delta
+///
+/// Fetch what changed since the last sync in a single request.
+///
+/// Intended for clients waking up in the background, which can use this instead of reconnecting to the events server.
+/// Pass the returned `timestamp` as `since` on the next sync.
+#[openapi(tag = "Sync")]
+#[get("/delta?<options..>")]
+pub async fn delta(
+    db: &State<Database>,
+    user: User,
+    options: v0::OptionsSyncDelta,
+) -> Result<Json<v0::SyncDelta>> {
+    let timestamp = Utc::now().timestamp_millis();
+    let since = options.since;
+
+    let channels = db
+        .find_direct_messages(&user.id)
+        .await?
+        .into_iter()
+        .filter(|channel| match channel {
+            Channel::DirectMessage {
+                id,
+                last_message_id,
+                ..
+            }
+            | Channel::Group {
+                id,
+                last_message_id,
+                ..
+            } => {
+                created_since(id, since)
+                    || last_message_id
+                        .as_deref()
+                        .is_some_and(|id| created_since(id, since))
+            }
+            _ => false,
+        })
+        .map(Into::into)
+        .collect();
+
+    let unreads = db
+        .fetch_unreads(&user.id)
+        .await?
+        .into_iter()
+        .filter(|unread| {
+            unread.unread_count != Some(0)
+                || unread
+                    .mentions
+                    .as_ref()
+                    .is_some_and(|mentions| !mentions.is_empty())
+                || unread
+                    .last_id
+                    .as_deref()
+                    .is_some_and(|id| created_since(id, since))
+        })
+        .map(Into::into)
+        .collect();
+
+    let settings = db
+        .fetch_all_user_settings(&user.id)
+        .await?
+        .into_iter()
+        .filter(|(_, (revision, _))| *revision > since)
+        .collect();
+
+    Ok(Json(v0::SyncDelta {
+        timestamp,
+        channels,
+        unreads,
+        relationships: user
+            .relations
+            .unwrap_or_default()
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+        settings,
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use chrono::Utc;
+    use guilderia_database::{Channel, UserSettingsImpl};
+    use guilderia_models::v0::{self, DataCreateGroup};
+    use rocket::http::{Header, Status};
+    use std::collections::HashMap;
+
+    #[rocket::async_test]
+    async fn success_sync_delta() {
+        let mut harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let group = Channel::create_group(
+            &harness.db,
+            DataCreateGroup {
+                ..Default::default()
+            },
+            user.id.clone(),
+        )
+        .await
+        .expect("`Channel`");
+
+        let now = Utc::now().timestamp_millis();
+        let mut settings = HashMap::new();
+        settings.insert("theme".to_string(), (now, "{}".to_string()));
+        settings.set(&harness.db, &user.id).await.unwrap();
+
+        let response = harness
+            .client
+            .get(format!("/sync/delta?since={}", now - 60_000))
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+        let delta: v0::SyncDelta = response.into_json().await.expect("`SyncDelta`");
+        assert!(delta
+            .channels
+            .iter()
+            .any(|channel| channel.id() == group.id()));
+        assert!(delta.settings.contains_key("theme"));
+
+        let response = harness
+            .client
+            .get(format!("/sync/delta?since={}", delta.timestamp))
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+        let delta: v0::SyncDelta = response.into_json().await.expect("`SyncDelta`");
+        assert!(delta.channels.is_empty());
+        assert!(delta.settings.is_empty());
+    }
+}
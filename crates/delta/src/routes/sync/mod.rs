@@ -1,10 +1,18 @@
 use guilderia_rocket_okapi::revolt_okapi::openapi3::OpenApi;
 use rocket::Route;
 
+mod get_bookmarks;
+mod get_delta;
 mod get_settings;
 mod get_unreads;
 mod set_settings;
 
 pub fn routes() -> (Vec<Route>, OpenApi) {
-    openapi_get_routes_spec![get_settings::fetch, set_settings::set, get_unreads::unreads]
+    openapi_get_routes_spec![
+        get_settings::fetch,
+        set_settings::set,
+        get_unreads::unreads,
+        get_bookmarks::bookmarks,
+        get_delta::delta
+    ]
 }
@@ -1,23 +1,34 @@
-use guilderia_database::{Database, User, UserSettingsImpl};
+use guilderia_config::config;
+use guilderia_database::{
+    util::locale::Locale, BotDmPreferences, DataMinimisation, Database, HighlightKeywords,
+    QuietHours, User, UserSettingsImpl, BOT_DMS_KEY, DATA_MINIMISATION_KEY,
+    DATA_MINIMISATION_STATUS_KEY, HIGHLIGHTS_KEY, LOCALE_KEY, QUIET_HOURS_KEY, TIMEZONE_KEY,
+};
 use guilderia_models::v0;
 
 use chrono::prelude::*;
-use guilderia_result::Result;
-use rocket::{serde::json::Json, State};
+use guilderia_result::{create_error, Result};
+use rocket::State;
 use rocket_empty::EmptyResponse;
 use std::collections::HashMap;
 
+use crate::util::body_limits::LimitedJson;
+
 type Data = HashMap<String, String>;
 
 /// # Set Settings
 ///
 /// Upload data to save to settings.
+///
+/// All keys are committed together under the same revision and announced
+/// in a single event. Nothing is written if any key already holds a later
+/// revision, so clients should fetch the latest settings and try again.
 #[openapi(tag = "Sync")]
 #[post("/settings/set?<options..>", data = "<data>")]
 pub async fn set(
     db: &State<Database>,
     user: User,
-    data: Json<Data>,
+    data: LimitedJson<Data>,
     options: v0::OptionsSetSettings,
 ) -> Result<EmptyResponse> {
     let data = data.into_inner();
@@ -32,6 +43,75 @@ pub async fn set(
         current_time
     };
 
+    // Settings consumed by the server must be well-formed
+    if let Some(value) = data.get(QUIET_HOURS_KEY) {
+        if QuietHours::parse(value).is_none() {
+            return Err(create_error!(FailedValidation {
+                error: "invalid quiet hours".to_string()
+            }));
+        }
+    }
+
+    if let Some(value) = data.get(TIMEZONE_KEY) {
+        if QuietHours::parse_timezone(value).is_none() {
+            return Err(create_error!(FailedValidation {
+                error: "invalid timezone".to_string()
+            }));
+        }
+    }
+
+    if let Some(value) = data.get(LOCALE_KEY) {
+        if Locale::parse(value).is_none() {
+            return Err(create_error!(FailedValidation {
+                error: "invalid locale".to_string()
+            }));
+        }
+    }
+
+    if let Some(value) = data.get(HIGHLIGHTS_KEY) {
+        let Some(keywords) = HighlightKeywords::parse(value) else {
+            return Err(create_error!(FailedValidation {
+                error: "invalid highlight keywords".to_string()
+            }));
+        };
+
+        let limits = config().await.features.highlights;
+        if keywords.keyword_count() > limits.max_keywords {
+            return Err(create_error!(FailedValidation {
+                error: format!("at most {} highlight keywords", limits.max_keywords)
+            }));
+        }
+
+        if keywords.keywords().any(|keyword| {
+            keyword.trim().is_empty() || keyword.chars().count() > limits.max_keyword_length
+        }) {
+            return Err(create_error!(FailedValidation {
+                error: "invalid highlight keyword".to_string()
+            }));
+        }
+    }
+
+    if let Some(value) = data.get(DATA_MINIMISATION_KEY) {
+        if DataMinimisation::parse(value).is_none() {
+            return Err(create_error!(FailedValidation {
+                error: "invalid data minimisation settings".to_string()
+            }));
+        }
+    }
+
+    if let Some(value) = data.get(BOT_DMS_KEY) {
+        if BotDmPreferences::parse(value).is_none() {
+            return Err(create_error!(FailedValidation {
+                error: "invalid bot direct message preferences".to_string()
+            }));
+        }
+    }
+
+    // Progress of data minimisation is only ever reported by the server
+    if data.contains_key(DATA_MINIMISATION_STATUS_KEY) {
+        return Err(create_error!(InvalidOperation));
+    }
+
     let mut settings = HashMap::new();
     for (key, data) in data {
         settings.insert(key, (timestamp, data));
@@ -39,3 +119,49 @@ pub async fn set(
 
     settings.set(db, &user.id).await.map(|_| EmptyResponse)
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use rocket::http::{ContentType, Header, Status};
+
+    #[rocket::async_test]
+    async fn rejects_outdated_settings() {
+        let harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let response = harness
+            .client
+            .post("/sync/settings/set?timestamp=2000")
+            .header(ContentType::JSON)
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .body(json!({ "a": "1", "b": "2" }).to_string())
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NoContent);
+        drop(response);
+
+        let response = harness
+            .client
+            .post("/sync/settings/set?timestamp=1000")
+            .header(ContentType::JSON)
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .body(json!({ "a": "3", "c": "4" }).to_string())
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Conflict);
+        drop(response);
+
+        let settings = harness
+            .db
+            .fetch_all_user_settings(&user.id)
+            .await
+            .expect("`UserSettings`");
+
+        assert_eq!(settings.get("a"), Some(&(2000, "1".to_string())));
+        assert_eq!(settings.get("b"), Some(&(2000, "2".to_string())));
+        assert!(!settings.contains_key("c"));
+    }
+}
@@ -0,0 +1,14 @@
+use guilderia_rocket_okapi::revolt_okapi::openapi3::OpenApi;
+use rocket::Route;
+
+mod token_create;
+mod token_list;
+mod token_revoke;
+
+pub fn routes() -> (Vec<Route>, OpenApi) {
+    openapi_get_routes_spec![
+        token_create::create_token,
+        token_list::list_tokens,
+        token_revoke::revoke_token,
+    ]
+}
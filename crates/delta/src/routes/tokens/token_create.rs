@@ -0,0 +1,37 @@
+use authifier::models::Session;
+use guilderia_database::{ApiToken, Database, Device};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Create API Token
+///
+/// Create a personal access token for scripts to act as your account.
+///
+/// Tokens can only be managed using a session.
+#[openapi(tag = "Account")]
+#[post("/", data = "<data>")]
+pub async fn create_token(
+    db: &State<Database>,
+    session: Session,
+    data: Json<v0::DataCreateApiToken>,
+) -> Result<Json<v0::CreatedApiToken>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    // Tokens would otherwise let a session sidestep login approval
+    let user = db.fetch_user(&session.user_id).await?;
+    Device::verify(db, &user, &session.id, &session.name).await?;
+
+    let (token, secret) =
+        ApiToken::create(db, &session.user_id, data.name, data.scope.into()).await?;
+    Ok(Json(v0::CreatedApiToken {
+        token: secret,
+        info: token.into(),
+    }))
+}
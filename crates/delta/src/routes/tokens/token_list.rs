@@ -0,0 +1,20 @@
+use authifier::models::Session;
+use guilderia_database::Database;
+use guilderia_models::v0;
+use guilderia_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch API Tokens
+///
+/// Fetch all personal access tokens on your account.
+#[openapi(tag = "Account")]
+#[get("/")]
+pub async fn list_tokens(
+    db: &State<Database>,
+    session: Session,
+) -> Result<Json<Vec<v0::ApiToken>>> {
+    db.fetch_api_tokens(&session.user_id)
+        .await
+        .map(|tokens| tokens.into_iter().map(Into::into).collect())
+        .map(Json)
+}
@@ -0,0 +1,20 @@
+use authifier::models::Session;
+use guilderia_database::Database;
+use guilderia_result::Result;
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Revoke API Token
+///
+/// Revoke a personal access token, it can no longer be used.
+#[openapi(tag = "Account")]
+#[delete("/<id>")]
+pub async fn revoke_token(
+    db: &State<Database>,
+    session: Session,
+    id: String,
+) -> Result<EmptyResponse> {
+    db.delete_api_token(&session.user_id, &id)
+        .await
+        .map(|_| EmptyResponse)
+}
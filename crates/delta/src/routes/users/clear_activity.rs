@@ -0,0 +1,13 @@
+use guilderia_database::{Database, User};
+use guilderia_result::Result;
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Clear Activity
+///
+/// Stop showing an activity on your presence.
+#[openapi(tag = "User Information")]
+#[delete("/@me/activity")]
+pub async fn clear_activity(db: &State<Database>, mut user: User) -> Result<EmptyResponse> {
+    user.set_activity(db, None).await.map(|_| EmptyResponse)
+}
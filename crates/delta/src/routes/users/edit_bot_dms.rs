@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use guilderia_database::{BotDmPreferences, Database, User, UserSettingsImpl, BOT_DMS_KEY};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Edit Bot DM Preferences
+///
+/// Choose which bots may send you direct messages, or refuse messages from
+/// any bot not on your allowlist. Bots you own may always message you.
+///
+/// Preferences are stored in your settings, so other clients are kept in sync.
+#[openapi(tag = "Direct Messaging")]
+#[put("/@me/bot_dms", data = "<data>")]
+pub async fn edit_bot_dms(
+    db: &State<Database>,
+    user: User,
+    data: Json<v0::BotDmPreferences>,
+) -> Result<Json<v0::BotDmPreferences>> {
+    if user.bot.is_some() {
+        return Err(create_error!(IsBot));
+    }
+
+    let mut data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    data.allowed.sort();
+    data.allowed.dedup();
+
+    let preferences: BotDmPreferences = data.into();
+    let value = serde_json::to_string(&preferences).map_err(|_| create_error!(InternalError))?;
+
+    let mut settings = HashMap::new();
+    settings.insert(
+        BOT_DMS_KEY.to_string(),
+        (Utc::now().timestamp_millis(), value),
+    );
+    settings.set(db, &user.id).await?;
+
+    Ok(Json(preferences.into()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use guilderia_database::{Bot, Channel};
+    use guilderia_models::v0;
+    use rocket::http::{ContentType, Header, Status};
+
+    #[rocket::async_test]
+    async fn refuses_bots_outside_allowlist() {
+        let mut harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+        let (_, _, owner) = harness.new_user().await;
+
+        let response = harness
+            .client
+            .put("/users/@me/bot_dms")
+            .header(ContentType::JSON)
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .body(
+                json!(v0::BotDmPreferences {
+                    allow_all: false,
+                    allowed: vec![],
+                })
+                .to_string(),
+            )
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+        drop(response);
+
+        let (_, bot_user) = Bot::create(&harness.db, TestHarness::rand_string(), &owner, None)
+            .await
+            .expect("`Bot`");
+
+        assert!(Channel::create_dm(&harness.db, &bot_user, &user)
+            .await
+            .is_err());
+        assert!(Channel::create_dm(&harness.db, &bot_user, &owner)
+            .await
+            .is_ok());
+    }
+}
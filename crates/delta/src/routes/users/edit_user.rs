@@ -1,5 +1,7 @@
 use guilderia_database::FieldsUser;
-use guilderia_database::{util::reference::Reference, Database, File, PartialUser, User};
+use guilderia_database::{
+    util::reference::Reference, Database, File, NotificationSummary, PartialUser, User, AMQP,
+};
 use guilderia_models::v0;
 use guilderia_result::{create_error, Result};
 use rocket::serde::json::Json;
@@ -13,6 +15,7 @@ use validator::Validate;
 #[patch("/<target>", data = "<data>")]
 pub async fn edit(
     db: &State<Database>,
+    amqp: &State<AMQP>,
     mut user: User,
     target: Reference,
     data: Json<v0::DataEditUser>,
@@ -58,6 +61,9 @@ pub async fn edit(
         return Ok(Json(user.into_self(false).await));
     }
 
+    let was_busy = user.is_busy();
+    let was_invisible = user.is_invisible();
+
     // 1. Remove fields from object
     if let Some(fields) = &data.remove {
         if fields.contains(&v0::FieldsUser::Avatar) {
@@ -130,5 +136,13 @@ pub async fn edit(
     )
     .await?;
 
+    // Invisible users are counted as offline
+    user.update_online_counts(db, was_invisible).await.ok();
+
+    // Catch the user up on what they missed while in do not disturb
+    if was_busy && !user.is_busy() {
+        NotificationSummary::deliver(db, amqp, &user).await.ok();
+    }
+
     Ok(Json(user.into_self(false).await))
 }
@@ -0,0 +1,21 @@
+use guilderia_database::{BotDmPreferences, Database, User, BOT_DMS_KEY};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Bot DM Preferences
+///
+/// Fetch which bots may send you direct messages.
+#[openapi(tag = "Direct Messaging")]
+#[get("/@me/bot_dms")]
+pub async fn fetch_bot_dms(db: &State<Database>, user: User) -> Result<Json<v0::BotDmPreferences>> {
+    if user.bot.is_some() {
+        return Err(create_error!(IsBot));
+    }
+
+    let settings = db
+        .fetch_user_settings(&user.id, &[BOT_DMS_KEY.to_string()])
+        .await?;
+
+    Ok(Json(BotDmPreferences::from_settings(&settings).into()))
+}
@@ -4,7 +4,10 @@ use rocket::Route;
 mod add_friend;
 mod block_user;
 mod change_username;
+mod clear_activity;
+mod edit_bot_dms;
 mod edit_user;
+mod fetch_bot_dms;
 mod fetch_dms;
 mod fetch_profile;
 mod fetch_self;
@@ -14,7 +17,9 @@ mod find_mutual;
 mod get_default_avatar;
 mod open_dm;
 mod remove_friend;
+mod search_messages;
 mod send_friend_request;
+mod set_activity;
 mod unblock_user;
 
 pub fn routes() -> (Vec<Route>, OpenApi) {
@@ -27,9 +32,14 @@ pub fn routes() -> (Vec<Route>, OpenApi) {
         change_username::change_username,
         get_default_avatar::default_avatar,
         fetch_profile::profile,
+        search_messages::search_messages,
+        set_activity::set_activity,
+        clear_activity::clear_activity,
         // Direct Messaging
         fetch_dms::direct_messages,
         open_dm::open_dm,
+        fetch_bot_dms::fetch_bot_dms,
+        edit_bot_dms::edit_bot_dms,
         // Relationships
         find_mutual::mutual,
         add_friend::add,
@@ -0,0 +1,201 @@
+use std::collections::{HashMap, HashSet};
+
+use guilderia_database::{
+    search, util::permissions::DatabasePermissionQuery, Channel, Database, MessageFilter,
+    MessageQuery, MessageTimePeriod, User,
+};
+use guilderia_models::v0;
+use guilderia_permissions::{calculate_channel_permissions, ChannelPermission};
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Search All Messages
+///
+/// Search for messages in every channel you can read, across all servers and conversations.
+///
+/// Results include a breakdown by server and channel which can be used to narrow the search.
+#[openapi(tag = "Messaging")]
+#[post("/@me/search", data = "<options>")]
+pub async fn search_messages(
+    db: &State<Database>,
+    user: User,
+    options: Json<v0::DataGlobalMessageSearch>,
+) -> Result<Json<v0::GlobalSearchResponse>> {
+    if user.bot.is_some() {
+        return Err(create_error!(IsBot));
+    }
+
+    let options = options.into_inner();
+    options.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let v0::DataGlobalMessageSearch {
+        query,
+        server,
+        channel,
+        limit,
+        before,
+        after,
+        sort,
+        include_users,
+    } = options;
+
+    // Collect every channel which could be searched
+    let members: Vec<_> = db
+        .fetch_all_memberships(&user.id)
+        .await?
+        .into_iter()
+        .filter(|member| match &server {
+            Some(id) => &member.id.server == id,
+            None => true,
+        })
+        .collect();
+
+    let server_ids: Vec<String> = members.iter().map(|x| x.id.server.clone()).collect();
+    let servers = db.fetch_servers(&server_ids).await?;
+
+    let mut channels = if server.is_some() {
+        vec![]
+    } else {
+        db.find_direct_messages(&user.id).await?
+    };
+
+    let channel_ids: Vec<String> = servers
+        .iter()
+        .flat_map(|server| server.channels.clone())
+        .collect();
+    channels.append(&mut db.fetch_channels(&channel_ids).await?);
+
+    if let Some(channel) = &channel {
+        channels.retain(|x| x.id() == channel);
+    }
+
+    // Only keep channels the user can read history in
+    let mut readable: HashMap<String, Option<String>> = HashMap::new();
+    for channel in &channels {
+        let server_id = match channel {
+            Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } => {
+                Some(server.clone())
+            }
+            _ => None,
+        };
+
+        let mut query = DatabasePermissionQuery::new(db, &user).channel(channel);
+        if let Some(server_id) = &server_id {
+            if let (Some(server), Some(member)) = (
+                servers.iter().find(|x| &x.id == server_id),
+                members.iter().find(|x| &x.id.server == server_id),
+            ) {
+                query = query.server(server).member(member);
+            }
+        }
+
+        if calculate_channel_permissions(&mut query)
+            .await
+            .has_channel_permission(ChannelPermission::ReadMessageHistory)
+        {
+            readable.insert(channel.id().to_string(), server_id);
+        }
+    }
+
+    if readable.is_empty() {
+        return Ok(Json(v0::GlobalSearchResponse {
+            messages: vec![],
+            users: vec![],
+            members: vec![],
+            facets: v0::SearchFacets {
+                servers: vec![],
+                channels: vec![],
+            },
+        }));
+    }
+
+    let query = MessageQuery {
+        filter: MessageFilter {
+            channels: Some(readable.keys().cloned().collect()),
+            query: Some(query),
+            ..Default::default()
+        },
+        time_period: MessageTimePeriod::Absolute {
+            before,
+            after,
+            sort: Some(sort),
+        },
+        limit,
+    };
+
+    // Count every result per server and channel, not just this page
+    let channel_counts = search::count_messages_by_channel(db, query.clone()).await?;
+    let messages = search::fetch_messages(db, query).await?;
+
+    let mut server_counts: HashMap<String, usize> = HashMap::new();
+    for (channel, count) in &channel_counts {
+        if let Some(Some(server_id)) = readable.get(channel) {
+            *server_counts.entry(server_id.clone()).or_default() += count;
+        }
+    }
+
+    let facets = v0::SearchFacets {
+        servers: into_facets(server_counts),
+        channels: into_facets(channel_counts),
+    };
+
+    let (users, members) = if let Some(true) = include_users {
+        let author_ids: Vec<String> = messages
+            .iter()
+            .map(|message| message.author.clone())
+            .collect::<HashSet<String>>()
+            .into_iter()
+            .collect();
+
+        let users = User::fetch_many_ids_as_mutuals(db, &user, &author_ids).await?;
+
+        let mut result_members = vec![];
+        for facet in &facets.servers {
+            let ids: Vec<String> = messages
+                .iter()
+                .filter(|message| readable.get(&message.channel) == Some(&Some(facet.id.clone())))
+                .map(|message| message.author.clone())
+                .collect::<HashSet<String>>()
+                .into_iter()
+                .collect();
+
+            let server = servers.iter().find(|x| x.id == facet.id);
+            for member in db.fetch_members(&facet.id, &ids).await? {
+                result_members.push(match server {
+                    Some(server) if member.show_server_tag => member.into_model_with_server(server),
+                    _ => member.into(),
+                });
+            }
+        }
+
+        (users, result_members)
+    } else {
+        (vec![], vec![])
+    };
+
+    Ok(Json(v0::GlobalSearchResponse {
+        messages: messages
+            .into_iter()
+            .map(|message| message.into_model(None, None))
+            .collect(),
+        users,
+        members,
+        facets,
+    }))
+}
+
+/// Convert result counts into facets, largest first
+fn into_facets(counts: HashMap<String, usize>) -> Vec<v0::SearchFacet> {
+    let mut facets: Vec<v0::SearchFacet> = counts
+        .into_iter()
+        .map(|(id, count)| v0::SearchFacet { id, count })
+        .collect();
+
+    facets.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.id.cmp(&b.id)));
+    facets
+}
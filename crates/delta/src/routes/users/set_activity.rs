@@ -0,0 +1,36 @@
+use guilderia_database::{Activity, Database, User};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use rocket_empty::EmptyResponse;
+use validator::Validate;
+
+/// # Set Activity
+///
+/// Show what you are currently doing on your presence, such as the game
+/// you are playing or the song you are listening to.
+///
+/// API tokens with the `Activity` scope may use this route, so integrations
+/// can keep your activity up to date.
+#[openapi(tag = "User Information")]
+#[put("/@me/activity", data = "<data>")]
+pub async fn set_activity(
+    db: &State<Database>,
+    mut user: User,
+    data: Json<v0::Activity>,
+) -> Result<EmptyResponse> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let mut activity: Activity = data.into();
+    activity.check()?;
+    activity.application = user.bot.as_ref().map(|_| user.id.clone());
+
+    user.set_activity(db, Some(activity))
+        .await
+        .map(|_| EmptyResponse)
+}
@@ -8,6 +8,7 @@ mod webhook_edit_token;
 mod webhook_execute;
 mod webhook_fetch_token;
 mod webhook_fetch;
+mod webhook_fetch_message;
 mod webhook_execute_github;
 
 pub fn routes() -> (Vec<Route>, OpenApi) {
@@ -20,5 +21,6 @@ pub fn routes() -> (Vec<Route>, OpenApi) {
         webhook_execute::webhook_execute,
         webhook_fetch_token::webhook_fetch_token,
         webhook_fetch::webhook_fetch,
+        webhook_fetch_message::webhook_fetch_message,
     ]
 }
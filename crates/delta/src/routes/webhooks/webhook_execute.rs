@@ -10,19 +10,25 @@ use rocket::{serde::json::Json, State};
 
 use validator::Validate;
 
+use crate::util::body_limits::LimitedJson;
+
 /// # Executes a webhook
 ///
-/// Executes a webhook and sends a message
+/// Executes a webhook and sends a message.
+///
+/// The created message is returned unless `wait` is false, in which case
+/// the message is sent in the background and `null` is returned.
 #[openapi(tag = "Webhooks")]
-#[post("/<webhook_id>/<token>", data = "<data>")]
+#[post("/<webhook_id>/<token>?<options..>", data = "<data>")]
 pub async fn webhook_execute(
     db: &State<Database>,
     amqp: &State<AMQP>,
     webhook_id: Reference,
     token: String,
-    data: Json<v0::DataMessageSend>,
+    options: v0::OptionsWebhookExecute,
+    data: LimitedJson<v0::DataMessageSend>,
     idempotency: IdempotencyKey,
-) -> Result<Json<v0::Message>> {
+) -> Result<Json<Option<v0::Message>>> {
     let data = data.into_inner();
     data.validate().map_err(|error| {
         create_error!(FailedValidation {
@@ -52,12 +58,18 @@ pub async fn webhook_execute(
         permissions.throw_if_lacking_channel_permission(ChannelPermission::React)?;
     }
 
+    if data.tts == Some(true) {
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::SendTTSMessages)?;
+    }
+
     let channel = db.fetch_channel(&webhook.channel_id).await?;
+    let db = db.inner().clone();
+    let amqp = amqp.inner().clone();
 
-    Ok(Json(
+    let send = async move {
         Message::create_from_api(
-            db,
-            Some(amqp),
+            &db,
+            Some(&amqp),
             channel,
             data,
             v0::MessageAuthor::Webhook(&webhook.into()),
@@ -66,9 +78,23 @@ pub async fn webhook_execute(
             config().await.features.limits.default,
             idempotency,
             true,
+            None,
             true,
+            None,
         )
-        .await?
-        .into_model(None, None),
-    ))
+        .await
+        .map(|message| message.into_model(None, None))
+    };
+
+    if options.wait.unwrap_or(true) {
+        send.await.map(Some).map(Json)
+    } else {
+        async_std::task::spawn(async move {
+            if let Err(err) = send.await {
+                guilderia_config::capture_error(&err);
+            }
+        });
+
+        Ok(Json(None))
+    }
 }
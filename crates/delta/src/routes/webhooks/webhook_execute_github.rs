@@ -13,6 +13,8 @@ use serde_json::Value;
 use ulid::Ulid;
 use validator::Validate;
 
+use crate::util::body_limits::LimitedString;
+
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct GithubUser {
     name: Option<String>,
@@ -755,13 +757,13 @@ pub async fn webhook_execute_github(
     webhook_id: Reference,
     token: String,
     event: EventHeader<'_>,
-    data: String,
+    data: LimitedString,
 ) -> Result<()> {
     let webhook = webhook_id.as_webhook(db).await?;
     webhook.assert_token(&token)?;
 
     let channel = db.fetch_channel(&webhook.channel_id).await?;
-    let event = convert_event(&data, &event)?;
+    let event = convert_event(&data.0, &event)?;
 
     let sendable_embed = match event.event {
         BaseEvent::Star(_) => {
@@ -0,0 +1,29 @@
+use guilderia_database::{util::reference::Reference, Database};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Webhook Message
+///
+/// Fetch a message previously sent by this webhook.
+#[openapi(tag = "Webhooks")]
+#[get("/<webhook_id>/<token>/messages/<message_id>")]
+pub async fn webhook_fetch_message(
+    db: &State<Database>,
+    webhook_id: Reference,
+    token: String,
+    message_id: Reference,
+) -> Result<Json<v0::Message>> {
+    let webhook = webhook_id.as_webhook(db).await?;
+    webhook.assert_token(&token)?;
+
+    let message = message_id
+        .as_message_in_channel(db, &webhook.channel_id)
+        .await?;
+
+    if message.author != webhook.id {
+        return Err(create_error!(NotFound));
+    }
+
+    Ok(Json(message.into_model(None, None)))
+}
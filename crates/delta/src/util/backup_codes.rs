@@ -0,0 +1,62 @@
+use authifier::{models::Session, Authifier};
+use guilderia_database::{BackupCodes, Database};
+use guilderia_rocket_okapi::revolt_okapi::openapi3::OpenApi;
+use rocket::{
+    http::Status,
+    request::Outcome,
+    route::{self, Handler},
+    Data, Request, Route,
+};
+
+/// Authifier's route which generates new MFA recovery codes
+const GENERATE_RECOVERY: &str = "generate_recovery";
+
+/// Limit and record regenerations of backup codes made through Authifier
+///
+/// Authifier itself requires a validated MFA ticket to generate new codes,
+/// this only keeps track of how often it happens.
+pub fn track_backup_codes((routes, spec): (Vec<Route>, OpenApi)) -> (Vec<Route>, OpenApi) {
+    let routes = routes
+        .into_iter()
+        .map(|mut route| {
+            if route.name.as_deref() == Some(GENERATE_RECOVERY) {
+                route.handler = Box::new(TrackBackupCodes(route.handler));
+            }
+
+            route
+        })
+        .collect();
+
+    (routes, spec)
+}
+
+/// Handler which counts successful regenerations of backup codes
+#[derive(Clone)]
+struct TrackBackupCodes(Box<dyn Handler>);
+
+#[rocket::async_trait]
+impl Handler for TrackBackupCodes {
+    async fn handle<'r>(&self, request: &'r Request<'_>, data: Data<'r>) -> route::Outcome<'r> {
+        let Outcome::Success(session) = request.guard::<Session>().await else {
+            return self.0.handle(request, data).await;
+        };
+
+        let db = request.rocket().state::<Database>().expect("`Database`");
+        if let Err(err) = BackupCodes::check_regeneration(db, &session.user_id).await {
+            return route::Outcome::from(request, err);
+        }
+
+        let outcome = self.0.handle(request, data).await;
+        if matches!(&outcome, route::Outcome::Success(response) if response.status() == Status::Ok)
+        {
+            let authifier = request.rocket().state::<Authifier>().expect("`Authifier`");
+            if let Ok(account) = authifier.database.find_account(&session.user_id).await {
+                BackupCodes::record(db, &account.id, account.mfa.recovery_codes.len())
+                    .await
+                    .ok();
+            }
+        }
+
+        outcome
+    }
+}
@@ -0,0 +1,146 @@
+use guilderia_config::{config, ApiBodyLimits};
+use guilderia_result::{create_error, Error};
+use rocket::data::{self, FromData, ToByteUnit};
+use rocket::http::{Method, Status};
+use rocket::outcome::Outcome;
+use rocket::serde::json::Json;
+use rocket::{Data, Request};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+
+use guilderia_rocket_okapi::gen::OpenApiGenerator;
+use guilderia_rocket_okapi::request::OpenApiFromData;
+use guilderia_rocket_okapi::revolt_okapi::openapi3::RequestBody;
+
+/// Find the body size limit for a given request
+fn resolve_limit(request: &Request<'_>, limits: &ApiBodyLimits) -> usize {
+    let mut segments = request.uri().path().segments();
+    let mut segment = segments.next();
+    if segment == Some("0.8") {
+        segment = segments.next();
+    }
+
+    let resource = segments.next();
+    let extra = segments.next();
+    let method = request.method();
+
+    match (segment, resource, extra, method) {
+        (Some("channels"), Some(_), Some("messages"), Method::Post | Method::Patch) => {
+            limits.messages
+        }
+        (Some("sync"), Some("settings"), Some("set"), Method::Post) => limits.settings,
+        (Some("webhooks"), Some(_), Some(_), Method::Post) => limits.webhooks,
+        _ => limits.default,
+    }
+}
+
+/// Read a request body, refusing to read past the limit for its route
+///
+/// The limit is enforced on the body itself, so requests without a
+/// `Content-Length` header or using chunked encoding are limited too.
+async fn read_limited<'r>(
+    request: &'r Request<'_>,
+    data: Data<'r>,
+) -> Result<String, (Status, Error)> {
+    let limit = resolve_limit(request, &config().await.api.body_limits);
+    let body = data
+        .open(limit.bytes())
+        .into_string()
+        .await
+        .map_err(|_| (Status::BadRequest, create_error!(InvalidOperation)))?;
+
+    if body.is_complete() {
+        Ok(body.into_inner())
+    } else {
+        info!("Rejected body on route {} (limit = {limit})", request.uri());
+        Err((
+            Status::PayloadTooLarge,
+            create_error!(PayloadTooLarge { max: limit }),
+        ))
+    }
+}
+
+/// JSON body limited to the size allowed for its route
+pub struct LimitedJson<T>(pub T);
+
+impl<T> LimitedJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, T: DeserializeOwned> FromData<'r> for LimitedJson<T> {
+    type Error = Error;
+
+    async fn from_data(request: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        let body = match read_limited(request, data).await {
+            Ok(body) => body,
+            Err(error) => return Outcome::Error(error),
+        };
+
+        match serde_json::from_str(&body) {
+            Ok(value) => Outcome::Success(LimitedJson(value)),
+            Err(error) => Outcome::Error((
+                Status::UnprocessableEntity,
+                create_error!(FailedValidation {
+                    error: error.to_string()
+                }),
+            )),
+        }
+    }
+}
+
+impl<'r, T: JsonSchema + DeserializeOwned> OpenApiFromData<'r> for LimitedJson<T> {
+    fn request_body(gen: &mut OpenApiGenerator) -> guilderia_rocket_okapi::Result<RequestBody> {
+        Json::<T>::request_body(gen)
+    }
+}
+
+/// Raw body limited to the size allowed for its route
+pub struct LimitedString(pub String);
+
+#[rocket::async_trait]
+impl<'r> FromData<'r> for LimitedString {
+    type Error = Error;
+
+    async fn from_data(request: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        match read_limited(request, data).await {
+            Ok(body) => Outcome::Success(LimitedString(body)),
+            Err(error) => Outcome::Error(error),
+        }
+    }
+}
+
+impl<'r> OpenApiFromData<'r> for LimitedString {
+    fn request_body(gen: &mut OpenApiGenerator) -> guilderia_rocket_okapi::Result<RequestBody> {
+        String::request_body(gen)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use guilderia_config::config;
+    use rocket::http::{ContentType, Header, Status};
+
+    #[rocket::async_test]
+    async fn rejects_bodies_over_route_limit() {
+        let harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+        let (_, channels) = harness.new_server(&user).await;
+
+        // No `Content-Length` header is sent, the body itself is measured
+        let limit = config().await.api.body_limits.messages;
+        let response = harness
+            .client
+            .post(format!("/channels/{}/messages", channels[0].id()))
+            .header(ContentType::JSON)
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .body(json!({ "content": "a".repeat(limit) }).to_string())
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::PayloadTooLarge);
+    }
+}
@@ -0,0 +1,34 @@
+use std::net::SocketAddr;
+
+use guilderia_config::{config, proxy::ForwardingHeaders};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::{Data, Request};
+
+/// Replace the remote address of requests from trusted proxies with the client's
+///
+/// Anything reading the remote address afterwards, such as the ratelimiter
+/// and Authifier's sessions and shield, sees the client's address.
+pub struct ClientIpFairing;
+
+#[rocket::async_trait]
+impl Fairing for ClientIpFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Client IP",
+            kind: Kind::Request,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        let Some(peer) = request.remote() else {
+            return;
+        };
+
+        let ip = config().await.client_ip(
+            peer.ip(),
+            &ForwardingHeaders::from_fn(|name| request.headers().get_one(name)),
+        );
+
+        request.set_remote(SocketAddr::new(ip, peer.port()));
+    }
+}
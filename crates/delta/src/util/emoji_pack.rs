@@ -0,0 +1,354 @@
+//! Import emoji in bulk from pack archives
+use std::collections::HashSet;
+use std::io::{Cursor, Read};
+
+use guilderia_config::{config, report_internal_error};
+use guilderia_database::{events::client::EventV1, Database, Emoji, File};
+use guilderia_models::v0;
+use guilderia_result::{create_error, Error, Result};
+use reqwest::multipart::{Form, Part};
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use serde::Deserialize;
+use ulid::Ulid;
+use validator::Validate;
+use zip::ZipArchive;
+
+/// Path of the manifest within a pack archive
+const MANIFEST: &str = "manifest.json";
+
+/// Maximum size of the manifest (in bytes)
+const MANIFEST_SIZE_LIMIT: u64 = 1_000_000;
+
+/// Number of emoji to import between progress reports
+const PROGRESS_INTERVAL: usize = 10;
+
+/// Image read from a pack archive
+pub struct PackImage {
+    entry: v0::EmojiPackEntry,
+    data: Result<Vec<u8>>,
+}
+
+/// Pack archive whose manifest has been read
+pub struct Pack {
+    archive: ZipArchive<Cursor<Vec<u8>>>,
+    /// Emoji listed in the manifest, without duplicates
+    pub entries: Vec<v0::EmojiPackEntry>,
+}
+
+/// Open a pack archive and read its manifest
+///
+/// Entries which repeat an earlier name or image are dropped, so that the
+/// number of entries can be checked before any image is decompressed.
+pub fn open(data: Vec<u8>) -> Result<Pack> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(data)).map_err(|_| create_error!(FileTypeNotAllowed))?;
+
+    let manifest: v0::EmojiPackManifest = {
+        let mut manifest = String::new();
+        archive
+            .by_name(MANIFEST)
+            .map_err(|_| create_error!(NotFound))?
+            .take(MANIFEST_SIZE_LIMIT)
+            .read_to_string(&mut manifest)
+            .map_err(|_| create_error!(InvalidOperation))?;
+
+        serde_json::from_str(&manifest).map_err(|error| {
+            create_error!(FailedValidation {
+                error: error.to_string()
+            })
+        })?
+    };
+
+    let mut names = HashSet::new();
+    let mut files = HashSet::new();
+    let entries = manifest
+        .emojis
+        .into_iter()
+        .filter(|entry| names.insert(entry.name.clone()) && files.insert(entry.file.clone()))
+        .collect();
+
+    Ok(Pack { archive, entries })
+}
+
+impl Pack {
+    /// Read the images listed in the manifest
+    ///
+    /// Images which cannot be read are kept so they are reported as failures
+    /// when importing, images larger than `size_limit` are not decompressed.
+    pub fn read(mut self, size_limit: usize) -> Vec<PackImage> {
+        self.entries
+            .into_iter()
+            .map(|entry| {
+                let data = self
+                    .archive
+                    .by_name(&entry.file)
+                    .map_err(|_| create_error!(NotFound))
+                    .and_then(|file| {
+                        let mut buf = Vec::new();
+                        report_internal_error!(file
+                            .take(size_limit as u64 + 1)
+                            .read_to_end(&mut buf))?;
+
+                        if buf.len() > size_limit {
+                            Err(create_error!(FileTooLarge { max: size_limit }))
+                        } else {
+                            Ok(buf)
+                        }
+                    });
+
+                PackImage { entry, data }
+            })
+            .collect()
+    }
+}
+
+/// Credentials of the importing user, used to upload images to Autumn
+///
+/// Images go through Autumn's upload pipeline like any other emoji, so they
+/// are scanned, stripped and checked against the hash list.
+#[derive(Clone)]
+pub struct UploadCredentials {
+    header: &'static str,
+    value: String,
+}
+
+#[async_trait]
+impl<'r> FromRequest<'r> for UploadCredentials {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        for header in ["x-session-token", "x-bot-token", "x-api-token"] {
+            if let Some(value) = request.headers().get_one(header) {
+                return Outcome::Success(UploadCredentials {
+                    header,
+                    value: value.to_owned(),
+                });
+            }
+        }
+
+        Outcome::Error((Status::Unauthorized, create_error!(NotAuthenticated)))
+    }
+}
+
+#[derive(Deserialize)]
+struct UploadResponse {
+    id: String,
+}
+
+/// Upload an image from a pack to Autumn as an emoji and return the file
+async fn store(
+    db: &Database,
+    credentials: &UploadCredentials,
+    user: &str,
+    path: &str,
+    data: Vec<u8>,
+) -> Result<File> {
+    let config = config().await;
+    let filename = path.rsplit('/').next().unwrap_or(path).to_owned();
+    let form = Form::new().part("file", Part::bytes(data).file_name(filename));
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/emojis", config.hosts.autumn))
+        .header(credentials.header, &credentials.value)
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    if !response.status().is_success() {
+        return Err(response
+            .json::<Error>()
+            .await
+            .unwrap_or_else(|_| create_error!(InternalError)));
+    }
+
+    let UploadResponse { id } = response
+        .json()
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    File::use_emoji(db, &id, &id, user).await
+}
+
+/// Create a single emoji from a pack
+async fn import_one(
+    db: &Database,
+    credentials: &UploadCredentials,
+    server: &str,
+    user: &str,
+    pending: bool,
+    image: PackImage,
+) -> Result<Emoji> {
+    let data = v0::DataCreateEmoji {
+        name: image.entry.name,
+        parent: v0::EmojiParent::Server {
+            id: server.to_owned(),
+        },
+        nsfw: image.entry.nsfw,
+    };
+
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let attachment = store(db, credentials, user, &image.entry.file, image.data?).await?;
+    let emoji = Emoji {
+        id: attachment.id,
+        parent: data.parent.into(),
+        creator_id: user.to_owned(),
+        name: data.name,
+        animated: "image/gif" == attachment.content_type,
+        nsfw: data.nsfw,
+        pending,
+    };
+
+    emoji.create(db).await?;
+    Ok(emoji)
+}
+
+/// Create emoji from the images of a pack
+///
+/// Emoji which fail to import are skipped and reported back, progress
+/// is sent to the importing user if this is running as a job.
+pub async fn import(
+    db: &Database,
+    credentials: &UploadCredentials,
+    server: &str,
+    user: &str,
+    pending: bool,
+    images: Vec<PackImage>,
+    job: Option<&str>,
+) -> (Vec<Emoji>, Vec<v0::EmojiImportFailure>) {
+    let total = images.len();
+    let mut emojis = vec![];
+    let mut failed = vec![];
+
+    for (index, image) in images.into_iter().enumerate() {
+        let name = image.entry.name.clone();
+        match import_one(db, credentials, server, user, pending, image).await {
+            Ok(emoji) => emojis.push(emoji),
+            Err(error) => failed.push(v0::EmojiImportFailure {
+                name,
+                error: format!("{:?}", error.error_type),
+            }),
+        }
+
+        let processed = index + 1;
+        if let Some(id) = job {
+            if processed % PROGRESS_INTERVAL == 0 || processed == total {
+                EventV1::ServerJobProgress {
+                    id: id.to_owned(),
+                    server: server.to_owned(),
+                    processed,
+                    failed: failed.len(),
+                    total,
+                }
+                .private(user.to_owned())
+                .await;
+            }
+        }
+    }
+
+    (emojis, failed)
+}
+
+/// Import a pack in the background and return the job id
+///
+/// Failures are sent to the importing user once the job completes.
+pub fn spawn(
+    db: Database,
+    credentials: UploadCredentials,
+    server: String,
+    user: String,
+    pending: bool,
+    images: Vec<PackImage>,
+) -> String {
+    let id = Ulid::new().to_string();
+    let job = id.clone();
+
+    async_std::task::spawn(async move {
+        let (_, failed) = import(
+            &db,
+            &credentials,
+            &server,
+            &user,
+            pending,
+            images,
+            Some(&job),
+        )
+        .await;
+
+        EventV1::EmojiImportComplete {
+            id: job,
+            server,
+            failed,
+        }
+        .private(user)
+        .await;
+    });
+
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    use super::open;
+
+    fn pack(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, data) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(data).unwrap();
+        }
+
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn reads_images_listed_in_manifest() {
+        let data = pack(&[
+            (
+                "manifest.json",
+                br#"{"emojis":[{"name":"small","file":"small.png"},{"name":"large","file":"large.png"},{"name":"missing","file":"missing.png"}]}"#,
+            ),
+            ("small.png", &[0; 4]),
+            ("large.png", &[0; 16]),
+        ]);
+
+        let images = open(data).unwrap().read(8);
+        assert_eq!(images.len(), 3);
+        assert!(images[0].data.is_ok());
+        assert!(images[1].data.is_err());
+        assert!(images[2].data.is_err());
+    }
+
+    #[test]
+    fn drops_duplicate_entries() {
+        let data = pack(&[
+            (
+                "manifest.json",
+                br#"{"emojis":[{"name":"wave","file":"wave.png"},{"name":"wave","file":"other.png"},{"name":"again","file":"wave.png"}]}"#,
+            ),
+            ("wave.png", &[0; 4]),
+        ]);
+
+        let pack = open(data).unwrap();
+        assert_eq!(pack.entries.len(), 1);
+        assert_eq!(pack.entries[0].name, "wave");
+    }
+
+    #[test]
+    fn requires_manifest() {
+        assert!(open(pack(&[("small.png", &[0; 4])])).is_err());
+        assert!(open(b"not a zip".to_vec()).is_err());
+    }
+}
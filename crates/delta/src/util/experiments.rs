@@ -0,0 +1,67 @@
+use guilderia_config::config;
+use guilderia_database::User;
+use guilderia_result::{create_error, Result};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::uri::Origin;
+use rocket::http::Method;
+use rocket::request::Outcome;
+use rocket::{Data, Request};
+
+/// Hide routes from users outside of the experiments gating them
+pub struct ExperimentFairing;
+
+#[rocket::async_trait]
+impl Fairing for ExperimentFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Experiments",
+            kind: Kind::Request,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        let config = config().await;
+        if config.experiments.is_empty() {
+            return;
+        }
+
+        let path = request.uri().path().as_str();
+        let path = path.strip_prefix("/0.8").unwrap_or(path);
+        let method = request.method().as_str();
+        let gates: Vec<&String> = config
+            .experiments
+            .iter()
+            .filter(|(_, experiment)| experiment.gates(method, path))
+            .map(|(name, _)| name)
+            .collect();
+
+        if gates.is_empty() {
+            return;
+        }
+
+        if let Outcome::Success(user) = request.guard::<User>().await {
+            if gates
+                .iter()
+                .all(|name| config.experiment_enabled(name, &user.id))
+            {
+                return;
+            }
+        }
+
+        info!(
+            "Hid route {} outside of experiments {gates:?}",
+            request.uri()
+        );
+        request.set_method(Method::Get);
+        request.set_uri(Origin::parse("/experiment_disabled").unwrap())
+    }
+}
+
+#[rocket::get("/experiment_disabled")]
+fn experiment_disabled() -> Result<()> {
+    Err(create_error!(NotFound))
+}
+
+pub fn routes() -> Vec<rocket::Route> {
+    rocket::routes![experiment_disabled]
+}
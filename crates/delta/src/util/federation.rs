@@ -0,0 +1,197 @@
+//! Signed requests between federated instances
+//!
+//! Requests carry the name of the requesting instance, a timestamp and an
+//! HMAC-SHA256 signature over both and the requested path, keyed with the
+//! secret shared between the two instances.
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use guilderia_config::{config, FederationPeer};
+use guilderia_result::{create_error, Error, Result};
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use rocket::{
+    http::Status,
+    request::{FromRequest, Outcome},
+    Request,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Maximum difference between the request timestamp and our clock, in seconds
+const MAX_CLOCK_SKEW: i64 = 300;
+
+/// Number of cached responses after which expired entries are pruned
+const CACHE_PRUNE_THRESHOLD: usize = 10_000;
+
+/// Responses resolved from peers
+static CACHE: Lazy<DashMap<String, (Instant, Value)>> = Lazy::new(DashMap::new);
+
+fn mac(secret: &str, origin: &str, timestamp: i64, path: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key");
+    mac.update(format!("{origin}\n{timestamp}\n{path}").as_bytes());
+    mac
+}
+
+/// Sign a request to a peer
+pub fn sign(secret: &str, origin: &str, timestamp: i64, path: &str) -> String {
+    hex::encode(mac(secret, origin, timestamp, path).finalize().into_bytes())
+}
+
+/// Verify a request from a peer
+pub fn verify(secret: &str, origin: &str, timestamp: i64, path: &str, signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+
+    (chrono::Utc::now().timestamp() - timestamp).abs() <= MAX_CLOCK_SKEW
+        && mac(secret, origin, timestamp, path)
+            .verify_slice(&signature)
+            .is_ok()
+}
+
+/// Allowed instance which signed the current request
+pub struct FederatedPeer(pub FederationPeer);
+
+#[async_trait]
+impl<'r> FromRequest<'r> for FederatedPeer {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let config = config().await;
+        if !config.federation.enabled {
+            return Outcome::Error((
+                Status::NotFound,
+                create_error!(FeatureDisabled {
+                    feature: "federation".to_string()
+                }),
+            ));
+        }
+
+        let headers = request.headers();
+        let origin = headers.get_one("x-federation-origin");
+        let timestamp = headers
+            .get_one("x-federation-timestamp")
+            .and_then(|timestamp| timestamp.parse().ok());
+        let signature = headers.get_one("x-federation-signature");
+
+        if let (Some(origin), Some(timestamp), Some(signature)) = (origin, timestamp, signature) {
+            if let Some(peer) = config.federation.peer(origin) {
+                let path = request.uri().path().as_str();
+                if verify(&peer.secret, origin, timestamp, path, signature) {
+                    return Outcome::Success(FederatedPeer(peer.clone()));
+                }
+            }
+        }
+
+        Outcome::Error((Status::Unauthorized, create_error!(NotAuthenticated)))
+    }
+}
+
+/// Resolve a resource from a peer, using a cached copy if it is recent enough
+pub async fn fetch<T: DeserializeOwned>(host: &str, path: &str) -> Result<T> {
+    let config = config().await;
+    if !config.federation.enabled {
+        return Err(create_error!(FeatureDisabled {
+            feature: "federation".to_string()
+        }));
+    }
+
+    let peer = config
+        .federation
+        .peer(host)
+        .ok_or_else(|| create_error!(NotFound))?;
+
+    let ttl = Duration::from_secs(config.federation.cache_ttl_seconds);
+    let key = format!("{host}{path}");
+    if let Some(entry) = CACHE.get(&key) {
+        if entry.0.elapsed() < ttl {
+            return serde_json::from_value(entry.1.clone())
+                .map_err(|_| create_error!(InternalError));
+        }
+    }
+
+    let timestamp = chrono::Utc::now().timestamp();
+    let response = reqwest::Client::new()
+        .get(format!("{}{path}", peer.url.trim_end_matches('/')))
+        .header("x-federation-origin", &config.federation.host)
+        .header("x-federation-timestamp", timestamp.to_string())
+        .header(
+            "x-federation-signature",
+            sign(&peer.secret, &config.federation.host, timestamp, path),
+        )
+        .send()
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(create_error!(NotFound));
+    }
+
+    let value: Value = response
+        .error_for_status()
+        .map_err(|_| create_error!(InternalError))?
+        .json()
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    if CACHE.len() >= CACHE_PRUNE_THRESHOLD {
+        CACHE.retain(|_, (fetched_at, _)| fetched_at.elapsed() < ttl);
+    }
+
+    CACHE.insert(key, (Instant::now(), value.clone()));
+    serde_json::from_value(value).map_err(|_| create_error!(InternalError))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{sign, verify};
+
+    #[test]
+    fn signatures_are_bound_to_request() {
+        let now = chrono::Utc::now().timestamp();
+        let signature = sign("secret", "a.example.com", now, "/federation/users/01");
+
+        assert!(verify(
+            "secret",
+            "a.example.com",
+            now,
+            "/federation/users/01",
+            &signature
+        ));
+        assert!(!verify(
+            "other",
+            "a.example.com",
+            now,
+            "/federation/users/01",
+            &signature
+        ));
+        assert!(!verify(
+            "secret",
+            "b.example.com",
+            now,
+            "/federation/users/01",
+            &signature
+        ));
+        assert!(!verify(
+            "secret",
+            "a.example.com",
+            now,
+            "/federation/users/02",
+            &signature
+        ));
+
+        let stale = now - 3600;
+        let signature = sign("secret", "a.example.com", stale, "/federation/users/01");
+        assert!(!verify(
+            "secret",
+            "a.example.com",
+            stale,
+            "/federation/users/01",
+            &signature
+        ));
+    }
+}
@@ -1,2 +1,12 @@
+pub mod backup_codes;
+pub mod body_limits;
+pub mod client_ip;
+pub mod emoji_pack;
+pub mod experiments;
+pub mod federation;
+pub mod provisioning;
 pub mod ratelimiter;
+pub mod server_export;
+pub mod task_metrics;
 pub mod test;
+pub mod trusted_device;
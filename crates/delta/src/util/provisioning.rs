@@ -0,0 +1,104 @@
+//! Accounts managed by an external identity provider
+use authifier::{models::Account, util::normalise_email, Authifier};
+use guilderia_database::{Database, PartialUser, User};
+use guilderia_result::{create_database_error, create_error, Result};
+
+/// Find an account by its email address
+pub async fn find_account_by_email(authifier: &Authifier, email: &str) -> Result<Option<Account>> {
+    authifier
+        .database
+        .find_account_by_normalised_email(&normalise_email(email.to_string()))
+        .await
+        .map_err(|_| create_database_error!("find_one", "accounts"))
+}
+
+/// Find the account linked to an email address, creating it and its user if necessary
+///
+/// Accounts created this way never use their local password. Only accounts
+/// which were provisioned are signed in to, since sessions created here skip
+/// MFA and anyone able to claim the email address at the identity provider
+/// could otherwise take over a local account.
+pub async fn find_or_create_account(
+    db: &Database,
+    authifier: &Authifier,
+    email: String,
+    username: &str,
+) -> Result<Account> {
+    if let Some(account) = find_account_by_email(authifier, &email).await? {
+        let provisioned = db
+            .fetch_user(&account.id)
+            .await
+            .map(|user| user.provisioned)
+            .unwrap_or_default();
+
+        if !provisioned || account.mfa.is_active() {
+            return Err(create_error!(EmailInUse));
+        }
+
+        return Ok(account);
+    }
+
+    let account = Account::new(authifier, email, nanoid::nanoid!(64), false)
+        .await
+        .map_err(|_| create_error!(InternalError))?;
+
+    User::create(
+        db,
+        derive_username(username),
+        account.id.clone(),
+        PartialUser {
+            provisioned: Some(true),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    Ok(account)
+}
+
+/// Derive a valid username from the identity provider's user name
+pub fn derive_username(user_name: &str) -> String {
+    let username: String = user_name
+        .split('@')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        .take(32)
+        .collect();
+
+    if username.len() < 2 {
+        "user".to_string()
+    } else {
+        username
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use guilderia_result::ErrorType;
+
+    use super::find_or_create_account;
+
+    #[rocket::async_test]
+    async fn only_links_provisioned_accounts() {
+        let harness = TestHarness::new().await;
+        let authifier = harness.db.clone().to_authifier().await;
+        let (account, _, _) = harness.new_user().await;
+
+        let err = find_or_create_account(&harness.db, &authifier, account.email, "local")
+            .await
+            .unwrap_err();
+        assert!(matches!(err.error_type, ErrorType::EmailInUse));
+
+        let email = format!("{}@revolt.chat", TestHarness::rand_string());
+        let created = find_or_create_account(&harness.db, &authifier, email.clone(), "sso")
+            .await
+            .unwrap();
+        let linked = find_or_create_account(&harness.db, &authifier, email, "sso")
+            .await
+            .unwrap();
+        assert_eq!(created.id, linked.id);
+    }
+}
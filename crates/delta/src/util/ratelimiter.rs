@@ -114,6 +114,7 @@ fn resolve_bucket<'r>(request: &'r rocket::Request<'_>) -> (&'r str, Option<&'r
         let method = request.method();
         match (segment, resource, method) {
             ("users", target, Method::Patch) => ("user_edit", target),
+            ("users", Some("@me"), _) if extra == Some("activity") => ("user_activity", None),
             ("users", _, _) => {
                 if let Some("default_avatar") = extra {
                     return ("default_avatar", None);
@@ -153,6 +154,7 @@ fn resolve_bucket<'r>(request: &'r rocket::Request<'_>) -> (&'r str, Option<&'r
 fn resolve_bucket_limit(bucket: &str) -> u8 {
     match bucket {
         "user_edit" => 2,
+        "user_activity" => 5,
         "users" => 20,
         "bots" => 10,
         "messaging" => 10,
@@ -168,7 +170,10 @@ fn resolve_bucket_limit(bucket: &str) -> u8 {
     }
 }
 
-/// Find the remote IP of the client
+/// Find the IP of the client
+///
+/// Requests from trusted proxies have had their remote address replaced by
+/// [`ClientIpFairing`](super::client_ip::ClientIpFairing) already.
 fn to_ip(request: &'_ rocket::Request<'_>) -> String {
     request
         .remote()
@@ -176,19 +181,6 @@ fn to_ip(request: &'_ rocket::Request<'_>) -> String {
         .unwrap_or_default()
 }
 
-/// Find the actual IP of the client
-fn to_real_ip(request: &'_ rocket::Request<'_>) -> String {
-    if let Ok(true) = std::env::var("TRUST_CLOUDFLARE").map(|x| x == "1") {
-        request
-            .headers()
-            .get_one("CF-Connecting-IP")
-            .map(|x| x.to_string())
-            .unwrap_or_else(|| to_ip(request))
-    } else {
-        to_ip(request)
-    }
-}
-
 impl Ratelimiter {
     /// Generate guard from identifier and target bucket
     pub fn from(
@@ -240,7 +232,7 @@ impl<'r> FromRequest<'r> for Ratelimiter {
                 {
                     session.id
                 } else {
-                    to_real_ip(request)
+                    to_ip(request)
                 };
 
                 Ratelimiter::from(&identifier, resolve_bucket(request))
@@ -282,7 +274,7 @@ impl Fairing for RatelimitFairing {
             info!(
                 "User rate-limited on route {}! (IP = {:?})",
                 request.uri(),
-                to_real_ip(request)
+                to_ip(request)
             );
 
             request.set_method(Method::Get);
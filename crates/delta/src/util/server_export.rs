@@ -0,0 +1,280 @@
+//! Export server data for reporting
+use std::collections::{HashMap, HashSet};
+
+use guilderia_database::{events::client::EventV1, Database, File, Metadata, Server};
+use guilderia_models::v0;
+use guilderia_result::Result;
+use serde_json::{Map, Value};
+use ulid::Ulid;
+
+/// Rows of exported data sharing a set of columns
+struct Table {
+    columns: &'static [&'static str],
+    rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    /// Render the table as CSV with a header row
+    fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        let mut write_row = |cells: Vec<String>| {
+            csv.push_str(&cells.join(","));
+            csv.push_str("\r\n");
+        };
+
+        write_row(self.columns.iter().map(|column| escape(column)).collect());
+        for row in &self.rows {
+            write_row(
+                row.iter()
+                    .map(|value| match value {
+                        Value::Null => String::new(),
+                        Value::String(value) => escape(value),
+                        value => escape(&value.to_string()),
+                    })
+                    .collect(),
+            );
+        }
+
+        csv
+    }
+
+    /// Render the table as a JSON array of objects keyed by column
+    fn to_json(&self) -> String {
+        Value::Array(
+            self.rows
+                .iter()
+                .map(|row| {
+                    Value::Object(
+                        self.columns
+                            .iter()
+                            .map(|column| column.to_string())
+                            .zip(row.iter().cloned())
+                            .collect::<Map<String, Value>>(),
+                    )
+                })
+                .collect(),
+        )
+        .to_string()
+    }
+}
+
+/// Quote a CSV field if it contains separators, quotes or line breaks
+///
+/// Fields which a spreadsheet would evaluate as a formula are prefixed
+/// with an apostrophe, as answers are written by members.
+fn escape(field: &str) -> String {
+    let field = if field.starts_with(['=', '+', '-', '@']) {
+        format!("'{field}")
+    } else {
+        field.to_owned()
+    };
+
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// Collect the answers given to the server's screening questions
+async fn screening(db: &Database, server: &Server) -> Result<Table> {
+    let mut rows = vec![];
+    for response in db.fetch_screening_responses(&server.id).await? {
+        for answer in response.answers {
+            rows.push(vec![
+                Value::String(response.id.user.clone()),
+                Value::String(answer.question),
+                Value::String(answer.answer),
+                serde_json::to_value(response.submitted_at).unwrap_or_default(),
+                serde_json::to_value(response.left_at).unwrap_or_default(),
+            ]);
+        }
+    }
+
+    Ok(Table {
+        columns: &["user", "question", "answer", "submitted_at", "left_at"],
+        rows,
+    })
+}
+
+/// Collect the server's roles and how many members hold each of them
+async fn roles(db: &Database, server: &Server) -> Result<Table> {
+    let mut members: HashMap<&str, usize> = HashMap::new();
+    for member in db.fetch_all_members(&server.id).await? {
+        for role in &member.roles {
+            if let Some((id, _)) = server.roles.get_key_value(role) {
+                *members.entry(id.as_str()).or_default() += 1;
+            }
+        }
+    }
+
+    let mut roles: Vec<_> = server.roles.iter().collect();
+    roles.sort_by_key(|(_, role)| role.rank);
+
+    Ok(Table {
+        columns: &["id", "name", "rank", "members"],
+        rows: roles
+            .into_iter()
+            .map(|(id, role)| {
+                vec![
+                    Value::String(id.clone()),
+                    Value::String(role.name.clone()),
+                    Value::from(role.rank),
+                    Value::from(members.get(id.as_str()).copied().unwrap_or_default()),
+                ]
+            })
+            .collect(),
+    })
+}
+
+/// Collect how often each bot command was used in the server's channels
+async fn interactions(db: &Database, server: &Server) -> Result<Table> {
+    let mut usage: HashMap<(String, String), (usize, HashSet<String>)> = HashMap::new();
+    for interaction in db.fetch_interactions_in_channels(&server.channels).await? {
+        let (uses, users) = usage
+            .entry((interaction.bot, interaction.command))
+            .or_default();
+
+        *uses += 1;
+        users.insert(interaction.user);
+    }
+
+    let mut usage: Vec<_> = usage.into_iter().collect();
+    usage.sort_by(|(a, (a_uses, _)), (b, (b_uses, _))| b_uses.cmp(a_uses).then(a.cmp(b)));
+
+    Ok(Table {
+        columns: &["bot", "command", "uses", "users"],
+        rows: usage
+            .into_iter()
+            .map(|((bot, command), (uses, users))| {
+                vec![
+                    Value::String(bot),
+                    Value::String(command),
+                    Value::from(uses),
+                    Value::from(users.len()),
+                ]
+            })
+            .collect(),
+    })
+}
+
+/// Collect the data for an export
+async fn collect(db: &Database, server: &Server, kind: &v0::ServerExportKind) -> Result<Table> {
+    match kind {
+        v0::ServerExportKind::Screening => screening(db, server).await,
+        v0::ServerExportKind::Roles => roles(db, server).await,
+        v0::ServerExportKind::Interactions => interactions(db, server).await,
+    }
+}
+
+/// Generate an export and upload it to Autumn
+async fn export(
+    db: &Database,
+    server: &Server,
+    user: &str,
+    data: &v0::DataCreateServerExport,
+) -> Result<File> {
+    let table = collect(db, server, &data.kind).await?;
+    let name = match data.kind {
+        v0::ServerExportKind::Screening => "screening",
+        v0::ServerExportKind::Roles => "roles",
+        v0::ServerExportKind::Interactions => "interactions",
+    };
+
+    let (extension, content_type, contents) = match data.format {
+        v0::ServerExportFormat::Csv => ("csv", "text/csv", table.to_csv()),
+        v0::ServerExportFormat::Json => ("json", "application/json", table.to_json()),
+    };
+
+    // Re-uses the stored file if an identical export was made before
+    let file = File::store(
+        db,
+        "exports",
+        format!("{name}-{}.{extension}", server.id),
+        content_type,
+        Metadata::File,
+        contents.as_bytes(),
+        user,
+    )
+    .await?;
+
+    File::use_server_export(db, &file.id, &server.id, user).await
+}
+
+/// Generate an export in the background and return the job id
+///
+/// The file is sent to the requesting user once the job completes.
+pub fn spawn(
+    db: Database,
+    server: Server,
+    user: String,
+    data: v0::DataCreateServerExport,
+) -> String {
+    let id = Ulid::new().to_string();
+    let job = id.clone();
+
+    async_std::task::spawn(async move {
+        let result = export(&db, &server, &user, &data).await;
+        if let Err(err) = &result {
+            log::error!("Failed to export data from {}: {err:?}", server.id);
+        }
+
+        EventV1::ServerJobProgress {
+            id: job.clone(),
+            server: server.id.clone(),
+            processed: 1,
+            failed: result.is_err() as usize,
+            total: 1,
+        }
+        .private(user.clone())
+        .await;
+
+        if let Ok(file) = result {
+            EventV1::ServerExportComplete {
+                id: job,
+                server: server.id,
+                file: file.into(),
+            }
+            .private(user)
+            .await;
+        }
+    });
+
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::{escape, Table};
+
+    #[test]
+    fn escapes_csv_fields() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape("a, b"), "\"a, b\"");
+        assert_eq!(escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(escape("=SUM(A1)"), "'=SUM(A1)");
+    }
+
+    #[test]
+    fn renders_tables() {
+        let table = Table {
+            columns: &["name", "members"],
+            rows: vec![
+                vec![Value::from("Moderators"), Value::from(2)],
+                vec![Value::from("Everyone, really"), Value::Null],
+            ],
+        };
+
+        assert_eq!(
+            table.to_csv(),
+            "name,members\r\nModerators,2\r\n\"Everyone, really\",\r\n"
+        );
+        let json: Value = serde_json::from_str(&table.to_json()).unwrap();
+        assert_eq!(json[0]["name"], "Moderators");
+        assert_eq!(json[0]["members"], 2);
+        assert_eq!(json[1]["members"], Value::Null);
+    }
+}
@@ -0,0 +1,104 @@
+use guilderia_database::tasks::{self, persistent::QueueStats};
+use rocket_prometheus::prometheus::{
+    core::{Collector, Desc},
+    proto::MetricFamily,
+    IntGaugeVec, Opts,
+};
+
+/// Read a value out of a queue snapshot
+type Reader = fn(&QueueStats) -> u64;
+
+/// Exposes background task queue counters, read on every scrape
+pub struct TaskQueueCollector {
+    gauges: Vec<(IntGaugeVec, Reader)>,
+}
+
+impl Default for TaskQueueCollector {
+    fn default() -> Self {
+        let gauge = |name: &str, help: &str, reader: Reader| {
+            (
+                IntGaugeVec::new(Opts::new(name, help), &["queue"])
+                    .expect("Failed to create task queue metric."),
+                reader,
+            )
+        };
+
+        TaskQueueCollector {
+            gauges: vec![
+                gauge(
+                    "task_queue_pending",
+                    "Items waiting to be processed",
+                    |stats| stats.pending,
+                ),
+                gauge(
+                    "task_queue_dead",
+                    "Items which failed too many times and were set aside",
+                    |stats| stats.dead,
+                ),
+                gauge("task_queue_pushed", "Items queued by this node", |stats| {
+                    stats.pushed
+                }),
+                gauge(
+                    "task_queue_completed",
+                    "Items completed by this node",
+                    |stats| stats.completed,
+                ),
+                gauge(
+                    "task_queue_retried",
+                    "Items retried by this node",
+                    |stats| stats.retried,
+                ),
+                gauge(
+                    "task_queue_dead_lettered",
+                    "Items set aside by this node",
+                    |stats| stats.dead_lettered,
+                ),
+                gauge(
+                    "task_queue_latency_milliseconds_sum",
+                    "Total time between queueing and completion of completed items",
+                    |stats| stats.latency_ms,
+                ),
+                gauge(
+                    "task_queue_batches",
+                    "Batches committed for a single channel",
+                    |stats| stats.batches,
+                ),
+                gauge(
+                    "task_queue_batched_items",
+                    "Items across all committed batches",
+                    |stats| stats.batched,
+                ),
+                gauge(
+                    "task_queue_largest_batch",
+                    "Largest batch committed for a single channel",
+                    |stats| stats.largest_batch,
+                ),
+            ],
+        }
+    }
+}
+
+impl Collector for TaskQueueCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        self.gauges
+            .iter()
+            .flat_map(|(gauge, _)| gauge.desc())
+            .collect()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        let stats = tasks::queue_stats();
+        self.gauges
+            .iter()
+            .flat_map(|(gauge, reader)| {
+                for queue in &stats {
+                    gauge
+                        .with_label_values(&[queue.name])
+                        .set(reader(queue) as i64);
+                }
+
+                gauge.collect()
+            })
+            .collect()
+    }
+}
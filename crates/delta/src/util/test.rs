@@ -135,6 +135,7 @@ impl TestHarness {
             rank,
             colour: None,
             hoist: false,
+            group: None,
         };
 
         let id = role
@@ -181,9 +182,15 @@ impl TestHarness {
                 attachments: None,
                 replies: None,
                 embeds: None,
+                alt_text: None,
+                mention_confirmation: None,
+                provenance: None,
+                thread: None,
+                effect: None,
                 masquerade: None,
                 interactions: None,
                 flags: None,
+                tts: None,
             },
             v0::MessageAuthor::User(&user.clone().into(&self.db, Some(user)).await),
             Some(user.clone().into(&self.db, Some(user)).await),
@@ -191,7 +198,9 @@ impl TestHarness {
             user.limits().await,
             IdempotencyKey::unchecked_from_string("0".to_string()),
             false,
+            None,
             false,
+            None,
         )
         .await
         .expect("Failed to create message");
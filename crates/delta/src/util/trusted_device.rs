@@ -0,0 +1,164 @@
+use authifier::models::Session;
+use guilderia_database::{Database, Device, User};
+use guilderia_result::{create_error, Error};
+use guilderia_rocket_okapi::{
+    gen::OpenApiGenerator,
+    request::{OpenApiFromRequest, RequestHeaderInput},
+    revolt_okapi::openapi3::OpenApi,
+};
+use rocket::{
+    http::Status,
+    request::{FromRequest, Outcome},
+    route::{self, Handler},
+    Data, Request, Route,
+};
+
+/// Routes which may be used by sessions waiting for approval
+const ALLOWED_WHILE_PENDING: [&str; 2] = ["logout", "fetch_current_device"];
+
+/// Refuse requests from sessions on devices which are waiting for approval
+///
+/// Routes which only authenticate the session, such as Authifier's, would
+/// otherwise let an unapproved session change the account's email, password
+/// or MFA and take it over.
+pub fn require_trusted_device((routes, spec): (Vec<Route>, OpenApi)) -> (Vec<Route>, OpenApi) {
+    let routes = routes
+        .into_iter()
+        .map(|mut route| {
+            if !matches!(route.name.as_deref(), Some(name) if ALLOWED_WHILE_PENDING.contains(&name))
+            {
+                route.handler = Box::new(TrustedDevice(route.handler));
+            }
+
+            route
+        })
+        .collect();
+
+    (routes, spec)
+}
+
+/// Handler which checks the session's device before running the route
+#[derive(Clone)]
+struct TrustedDevice(Box<dyn Handler>);
+
+#[rocket::async_trait]
+impl Handler for TrustedDevice {
+    async fn handle<'r>(&self, request: &'r Request<'_>, data: Data<'r>) -> route::Outcome<'r> {
+        if let Outcome::Success(session) = request.guard::<Session>().await {
+            let db = request.rocket().state::<Database>().expect("`Database`");
+            // Accounts which haven't finished onboarding have no devices to approve
+            if let Ok(user) = db.fetch_user(&session.user_id).await {
+                if let Err(err) = Device::verify(db, &user, &session.id, &session.name).await {
+                    return route::Outcome::from(request, err);
+                }
+            }
+        }
+
+        self.0.handle(request, data).await
+    }
+}
+
+/// Session on a trusted device, along with its user
+///
+/// Unlike [`User`], this refuses API and impersonation tokens, so that only
+/// the account holder can approve or revoke devices.
+pub struct TrustedSession {
+    pub session: Session,
+    pub user: User,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for TrustedSession {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let headers = request.headers();
+        if headers.contains("x-api-token") || headers.contains("x-impersonation-token") {
+            return Outcome::Error((Status::Forbidden, create_error!(NotPrivileged)));
+        }
+
+        let Outcome::Success(session) = request.guard::<Session>().await else {
+            return Outcome::Error((Status::Unauthorized, create_error!(InvalidSession)));
+        };
+
+        let db = request.rocket().state::<Database>().expect("`Database`");
+        let user = match db.fetch_user(&session.user_id).await {
+            Ok(user) => user,
+            Err(err) => return Outcome::Error((Status::Unauthorized, err)),
+        };
+
+        match Device::verify(db, &user, &session.id, &session.name).await {
+            Ok(()) => Outcome::Success(TrustedSession { session, user }),
+            Err(err) => Outcome::Error((Status::Forbidden, err)),
+        }
+    }
+}
+
+impl<'r> OpenApiFromRequest<'r> for TrustedSession {
+    fn from_request_input(
+        gen: &mut OpenApiGenerator,
+        name: String,
+        required: bool,
+    ) -> guilderia_rocket_okapi::Result<RequestHeaderInput> {
+        User::from_request_input(gen, name, required)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use guilderia_database::PartialUser;
+    use rocket::http::{ContentType, Header, Status};
+
+    #[rocket::async_test]
+    async fn refuses_pending_devices() {
+        let harness = TestHarness::new().await;
+        let (account, _, user) = harness.new_user().await;
+
+        harness
+            .db
+            .update_user(
+                &user.id,
+                &PartialUser {
+                    require_device_approval: Some(true),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        let authifier = harness.db.clone().to_authifier().await;
+        let session = account
+            .create_session(&authifier, "Firefox".to_string())
+            .await
+            .expect("`Session`");
+
+        let response = harness
+            .client
+            .patch("/auth/account/change/email")
+            .header(ContentType::JSON)
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .body(
+                json!({
+                    "email": format!("{}@revolt.chat", TestHarness::rand_string()),
+                    "current_password": "password"
+                })
+                .to_string(),
+            )
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Forbidden);
+        drop(response);
+
+        let response = harness
+            .client
+            .get("/auth/session/devices/current")
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+}
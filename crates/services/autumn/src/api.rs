@@ -4,16 +4,17 @@ use std::{
 };
 
 use axum::{
-    extract::{DefaultBodyLimit, Path, State},
-    http::{header, Method},
+    extract::{DefaultBodyLimit, Extension, Path, Query, State},
+    http::{header, HeaderMap, Method},
+    middleware,
     response::{IntoResponse, Redirect, Response},
     routing::{get, post},
     Json, Router,
 };
 use axum_typed_multipart::{FieldData, TryFromMultipart, TypedMultipart};
 use lazy_static::lazy_static;
-use guilderia_config::{config, report_internal_error};
-use guilderia_database::{iso8601_timestamp::Timestamp, Database, FileHash, Metadata, User};
+use guilderia_config::{config, proxy::ClientIp, report_internal_error, FilesProcessor};
+use guilderia_database::{iso8601_timestamp::Timestamp, Database, File, FileHash, Metadata, User};
 use guilderia_files::{
     create_thumbnail, decode_image, fetch_from_s3, upload_to_s3, AUTHENTICATION_TAG_SIZE_BYTES,
 };
@@ -25,7 +26,14 @@ use tokio::time::Instant;
 use tower_http::cors::{AllowHeaders, Any, CorsLayer};
 use utoipa::ToSchema;
 
-use crate::{exif::strip_metadata, metadata::generate_metadata, mime_type::determine_mime_type};
+use crate::{
+    archive::inspect_archive,
+    cdn,
+    exif::strip_metadata,
+    hash_matching::{self, Upload},
+    metadata::{generate_blurhash, generate_metadata, generate_text_preview, generate_waveform},
+    mime_type::determine_mime_type,
+};
 
 /// Build the API router
 pub async fn router() -> Router<Database> {
@@ -46,8 +54,21 @@ pub async fn router() -> Router<Database> {
                     config.features.limits.global.body_limit_size,
                 )),
         )
-        .route("/:tag/:file_id", get(fetch_preview))
-        .route("/:tag/:file_id/:file_name", get(fetch_file))
+        .route("/files/:file_id/rescan", post(rescan_file))
+        // Exports are private to the user who requested them and must not be cached by the CDN
+        .route("/exports/:file_id/:file_name", get(fetch_export))
+        .route("/cdn/credentials", get(cdn::fetch_credentials))
+        .merge(
+            // Files may only be fetched through the CDN if it authenticates itself
+            Router::new()
+                .route("/:tag/:file_id", get(fetch_preview))
+                .route("/:tag/:file_id/:file_name", get(fetch_file))
+                .route(
+                    "/attachments/:file_id/preview_text",
+                    get(fetch_text_preview),
+                )
+                .route_layer(middleware::from_fn(cdn::require_origin_auth)),
+        )
         .layer(cors)
 }
 
@@ -130,6 +151,14 @@ pub struct UploadPayload {
     file: FieldData<NamedTempFile>,
 }
 
+/// Options for upload
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct UploadOptions {
+    /// Whether this file is a recorded voice message
+    voice_note: bool,
+}
+
 /// Successful upload response
 #[derive(Serialize, Debug, ToSchema)]
 pub struct UploadResponse {
@@ -139,7 +168,7 @@ pub struct UploadResponse {
 
 /// Upload a file
 ///
-/// Available tags and restrictions:
+/// Default tags and restrictions, these can be changed by the instance:
 ///
 /// | Tag | Size | Resolution | Type |
 /// | :-: | --: | :-- | :-: |
@@ -156,7 +185,8 @@ pub struct UploadResponse {
         (status = 200, description = "Upload was successful", body = UploadResponse)
     ),
     params(
-        ("tag" = Tag, Path, description = "Tag to upload to (e.g. attachments, icons, ...)")
+        ("tag" = Tag, Path, description = "Tag to upload to (e.g. attachments, icons, ...)"),
+        ("voice_note" = Option<bool>, Query, description = "Whether this file is a voice message, only audio attachments may be")
     ),
     request_body(content_type = "multipart/form-data", content = UploadPayload),
     security(
@@ -168,6 +198,9 @@ async fn upload_file(
     State(db): State<Database>,
     user: User,
     Path(tag): Path<Tag>,
+    headers: HeaderMap,
+    Extension(ClientIp(ip)): Extension<ClientIp>,
+    Query(UploadOptions { voice_note }): Query<UploadOptions>,
     TypedMultipart(UploadPayload { mut file }): TypedMultipart<UploadPayload>,
 ) -> Result<Json<UploadResponse>> {
     // Fetch configuration
@@ -191,13 +224,21 @@ async fn upload_file(
         return Err(create_error!(FileTooSmall));
     }
 
+    // Get the upload pipeline for this tag
+    let pipeline = config.files.pipeline(tag.clone().into());
+
     // Get user's file upload limits
     let limits = user.limits().await;
-    let size_limit = *limits
+    let mut size_limit = *limits
         .file_upload_size_limit
         .get(tag.clone().into())
         .expect("size limit");
 
+    // Apply the tag's own limit, if any
+    if pipeline.max_size > 0 {
+        size_limit = size_limit.min(pipeline.max_size);
+    }
+
     if original_file_size > size_limit {
         return Err(create_error!(FileTooLarge { max: size_limit }));
     }
@@ -219,24 +260,58 @@ async fn upload_file(
     // Determine the mime type for the file
     let mime_type = determine_mime_type(&mut file.contents, &buf, &filename);
 
-    // Check blocklist for mime type
+    // Check blocklist and tag's allowlist for mime type
     if config
         .files
         .blocked_mime_types
         .iter()
         .any(|m| m == mime_type)
+        || !pipeline.allows_mime_type(mime_type)
     {
         return Err(create_error!(FileTypeNotAllowed));
     }
 
+    // Voice messages may only be sent as attachments
+    if voice_note && !matches!(tag, Tag::attachments) {
+        return Err(create_error!(InvalidOperation));
+    }
+
     // Determine metadata for the file
-    let metadata = generate_metadata(&file.contents, mime_type);
+    let classify = pipeline.runs(FilesProcessor::Classify);
+    let metadata = if classify {
+        generate_metadata(&file.contents, mime_type)
+    } else {
+        Metadata::File
+    };
 
-    // Block non-images for non-attachment uploads
-    if !matches!(tag, Tag::attachments) && !matches!(metadata, Metadata::Image { .. }) {
+    // Block invalid images for tags restricted to certain types
+    if classify
+        && !pipeline.allowed_mime_types.is_empty()
+        && mime_type.starts_with("image/")
+        && !matches!(metadata, Metadata::Image { .. })
+    {
         return Err(create_error!(FileTypeNotAllowed));
     }
 
+    // Match images against the hash list, including copies of files uploaded before
+    if pipeline.runs(FilesProcessor::HashMatch) && mime_type.starts_with("image/") {
+        let tag: &'static str = tag.clone().into();
+        hash_matching::check(
+            &db,
+            Upload {
+                user: &user.id,
+                tag,
+                filename: &filename,
+                mime_type,
+                hash: &format!("{original_hash:02x}"),
+                buf: &buf,
+                ip,
+            },
+            &headers,
+        )
+        .await?;
+    }
+
     // Find an existing hash and use that if possible
     let file_hash_exists = if let Ok(file_hash) = db
         .fetch_attachment_hash(&format!("{original_hash:02x}"))
@@ -244,14 +319,22 @@ async fn upload_file(
     {
         if !file_hash.iv.is_empty() {
             let tag: &'static str = tag.into();
-            db.insert_attachment(&file_hash.into_file(
-                id.clone(),
-                tag.to_owned(),
-                filename,
-                user.id,
-            ))
-            .await?;
+            let mut file = file_hash.into_file(id.clone(), tag.to_owned(), filename, user.id);
+
+            if voice_note {
+                // Audio uploaded before waveforms were generated has yet to be measured
+                if !is_voice_note(&file.metadata) {
+                    file.metadata = generate_waveform(&buf, file.metadata).await;
+                }
+
+                if !is_voice_note(&file.metadata) {
+                    return Err(create_error!(FileTypeNotAllowed));
+                }
 
+                file.voice_note = Some(true);
+            }
+
+            db.insert_attachment(&file).await?;
             return Ok(Json(UploadResponse { id }));
         }
 
@@ -261,17 +344,59 @@ async fn upload_file(
     };
 
     // Strip metadata
-    let (buf, metadata) = strip_metadata(file.contents, buf, metadata, mime_type).await?;
+    let (buf, metadata) = if pipeline.runs(FilesProcessor::ExifStrip) {
+        strip_metadata(
+            file.contents,
+            buf,
+            metadata,
+            mime_type,
+            config.files.exif_policy(tag.clone().into()),
+        )
+        .await?
+    } else {
+        (buf, metadata)
+    };
+
+    let metadata = if classify {
+        // Generate placeholder for images and videos
+        let metadata = generate_blurhash(&buf, metadata, mime_type).await;
+
+        // Generate preview for text files
+        let metadata = generate_text_preview(&buf, &filename, metadata).await;
+
+        // Measure audio so it can be shown as a waveform
+        let metadata = generate_waveform(&buf, metadata).await;
+
+        // List the contents of archives
+        if matches!(metadata, Metadata::File) {
+            inspect_archive(&buf, mime_type).await?.unwrap_or(metadata)
+        } else {
+            metadata
+        }
+    } else {
+        metadata
+    };
 
     // Virus scan files if ClamAV is configured
-    if matches!(metadata, Metadata::File)
+    let scan = if pipeline.runs(FilesProcessor::Scan)
+        && matches!(metadata, Metadata::File | Metadata::Archive { .. })
         && (config.files.scan_mime_types.is_empty()
             || config.files.scan_mime_types.iter().any(|v| v == mime_type))
-        && crate::clamav::is_malware(&buf).await?
     {
+        crate::clamav::scan(&buf).await?
+    } else {
+        None
+    };
+
+    if scan.as_ref().is_some_and(|scan| !scan.is_clean()) {
         return Err(create_error!(InternalError));
     }
 
+    // Voice messages must be audio which could be decoded
+    if voice_note && !is_voice_note(&metadata) {
+        return Err(create_error!(FileTypeNotAllowed));
+    }
+
     // Print file information for debug purposes
     let new_file_size = buf.len() + AUTHENTICATION_TAG_SIZE_BYTES;
     let processed_hash = {
@@ -298,6 +423,8 @@ async fn upload_file(
         metadata,
         content_type: mime_type.to_owned(),
         size: new_file_size as isize,
+
+        scan,
     };
 
     // Add attachment hash if it doesn't exist
@@ -316,14 +443,23 @@ async fn upload_file(
 
     // Finally, create the file and return its ID
     let tag: &'static str = tag.into();
-    db.insert_attachment(&file_hash.into_file(id.clone(), tag.to_owned(), filename, user.id))
-        .await?;
+    let mut file = file_hash.into_file(id.clone(), tag.to_owned(), filename, user.id);
+    file.voice_note = voice_note.then_some(true);
+    db.insert_attachment(&file).await?;
 
     Ok(Json(UploadResponse { id }))
 }
 
-/// Header value used for cache control
-pub static CACHE_CONTROL: &str = "public, max-age=604800, must-revalidate";
+/// Whether metadata describes audio which can be played back as a voice message
+fn is_voice_note(metadata: &Metadata) -> bool {
+    matches!(
+        metadata,
+        Metadata::Audio {
+            duration: Some(_),
+            waveform: Some(_),
+        }
+    )
+}
 
 /// Fetch preview of file
 ///
@@ -372,12 +508,23 @@ async fn fetch_preview(
         return Err(create_error!(NotFound));
     }
 
+    // Ignore files held in quarantine
+    if file.quarantined.is_some_and(|v| v) {
+        return Err(create_error!(NotFound));
+    }
+
     let hash = file.as_hash(&db).await?;
 
     let is_animated = hash.content_type == "image/gif"; // TODO: extract this data from files
+    let resize = config()
+        .await
+        .files
+        .pipeline(tag_str)
+        .runs(FilesProcessor::Resize);
 
-    // Only process image files and don't process GIFs if not avatar or icon
-    if !matches!(hash.metadata, Metadata::Image { .. })
+    // Only process image files if the tag resizes them and don't process GIFs if not avatar or icon
+    if !resize
+        || !matches!(hash.metadata, Metadata::Image { .. })
         || (is_animated && !matches!(tag, Tag::avatars | Tag::icons))
     {
         return Ok(
@@ -395,11 +542,12 @@ async fn fetch_preview(
     )
     .await;
 
+    let cache_control = config().await.files.cdn.cache_control(tag_str);
     Ok((
         [
-            (header::CONTENT_TYPE, "image/webp"),
-            (header::CONTENT_DISPOSITION, "inline"),
-            (header::CACHE_CONTROL, CACHE_CONTROL),
+            (header::CONTENT_TYPE, "image/webp".to_owned()),
+            (header::CONTENT_DISPOSITION, "inline".to_owned()),
+            (header::CACHE_CONTROL, cache_control),
         ],
         data,
     )
@@ -440,6 +588,11 @@ async fn fetch_file(
         return Err(create_error!(NotFound));
     }
 
+    // Ignore files held in quarantine
+    if file.quarantined.is_some_and(|v| v) {
+        return Err(create_error!(NotFound));
+    }
+
     // Ensure filename is correct
     if file_name != file.filename {
         if file_name == "original" {
@@ -451,16 +604,190 @@ async fn fetch_file(
         return Err(create_error!(NotFound));
     }
 
+    let hash = file.as_hash(&db).await?;
+    let cache_control = config().await.files.cdn.cache_control(tag);
+    retrieve_file_by_hash(&hash).await.map(|data| {
+        (
+            [
+                (header::CONTENT_TYPE, hash.content_type),
+                (header::CONTENT_DISPOSITION, "attachment".to_owned()),
+                (header::CACHE_CONTROL, cache_control),
+            ],
+            data,
+        )
+            .into_response()
+    })
+}
+
+/// Fetch server export
+///
+/// Server exports may only be downloaded by the user who requested them.
+#[utoipa::path(
+    get,
+    path = "/exports/{file_id}/{file_name}",
+    responses(
+        (status = 200, description = "Exported data", body = Vec<u8>)
+    ),
+    params(
+        ("file_id" = String, Path, description = "File identifier"),
+        ("file_name" = String, Path, description = "File name")
+    ),
+    security(("session_token" = []), ("bot_token" = []))
+)]
+async fn fetch_export(
+    State(db): State<Database>,
+    user: User,
+    Path((file_id, file_name)): Path<(String, String)>,
+) -> Result<Response> {
+    let file = db.fetch_attachment("exports", &file_id).await?;
+
+    if file.deleted.is_some_and(|v| v)
+        || file.uploader_id.as_ref() != Some(&user.id)
+        || file_name != file.filename
+    {
+        return Err(create_error!(NotFound));
+    }
+
     let hash = file.as_hash(&db).await?;
     retrieve_file_by_hash(&hash).await.map(|data| {
         (
             [
                 (header::CONTENT_TYPE, hash.content_type),
                 (header::CONTENT_DISPOSITION, "attachment".to_owned()),
-                (header::CACHE_CONTROL, CACHE_CONTROL.to_owned()),
+                (header::CACHE_CONTROL, "private, no-store".to_owned()),
             ],
             data,
         )
             .into_response()
     })
 }
+
+/// Text preview response
+#[derive(Serialize, Debug, ToSchema)]
+pub struct TextPreviewResponse {
+    /// Language detected from the file name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    /// Number of lines in the file
+    lines: usize,
+    /// First few lines of the file
+    preview: String,
+}
+
+/// Fetch text preview
+///
+/// Returns the first few lines of a text attachment so it can be rendered inline.
+#[utoipa::path(
+    get,
+    path = "/attachments/{file_id}/preview_text",
+    responses(
+        (status = 200, description = "Text preview", body = TextPreviewResponse)
+    ),
+    params(
+        ("file_id" = String, Path, description = "File identifier")
+    ),
+)]
+async fn fetch_text_preview(
+    State(db): State<Database>,
+    Path(file_id): Path<String>,
+) -> Result<Json<TextPreviewResponse>> {
+    let file = db.fetch_attachment("attachments", &file_id).await?;
+
+    // Ignore deleted files
+    if file.deleted.is_some_and(|v| v) {
+        return Err(create_error!(NotFound));
+    }
+
+    // Ignore files that haven't been attached
+    if file.used_for.is_none() {
+        return Err(create_error!(NotFound));
+    }
+
+    // Ignore files held in quarantine
+    if file.quarantined.is_some_and(|v| v) {
+        return Err(create_error!(NotFound));
+    }
+
+    match file.as_hash(&db).await?.metadata {
+        Metadata::Text {
+            language,
+            lines: Some(lines),
+            preview: Some(preview),
+        } => Ok(Json(TextPreviewResponse {
+            language,
+            lines: lines as usize,
+            preview,
+        })),
+        _ => Err(create_error!(NotFound)),
+    }
+}
+
+/// Successful rescan response
+#[derive(Serialize, Debug, ToSchema)]
+pub struct RescanResponse {
+    /// Whether the file was found to be clean
+    clean: bool,
+    /// Name of the matched signature, if the file is infected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+    /// Engine version used to scan the file
+    engine: String,
+    /// Version of the signature database used to scan the file
+    signatures: u32,
+}
+
+/// Rescan a file
+///
+/// Scans the file again using the currently loaded signature database and stores the verdict on every file sharing the same content.
+///
+/// Files found to be infected will no longer be served.
+///
+/// Only available to privileged users.
+#[utoipa::path(
+    post,
+    path = "/files/{file_id}/rescan",
+    responses(
+        (status = 200, description = "Scan result", body = RescanResponse)
+    ),
+    params(
+        ("file_id" = String, Path, description = "File identifier")
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn rescan_file(
+    State(db): State<Database>,
+    user: User,
+    Path(file_id): Path<String>,
+) -> Result<Json<RescanResponse>> {
+    if !user.privileged {
+        return Err(create_error!(NotPrivileged));
+    }
+
+    let file = db.fetch_attachment_by_id(&file_id).await?;
+    if file.hash.is_none() {
+        return Err(create_error!(NotFound));
+    }
+
+    let hash = file.as_hash(&db).await?;
+    let data = retrieve_file_by_hash(&hash).await?;
+    let scan = crate::clamav::scan(&data).await?.ok_or_else(|| {
+        create_error!(FeatureDisabled {
+            feature: "clamav".to_owned()
+        })
+    })?;
+
+    db.set_attachment_hash_scan(&hash.id, &scan).await?;
+    db.set_attachments_scan(&hash.id, &scan).await?;
+    if !scan.is_clean() {
+        File::quarantine_hash(&db, &hash.id).await?;
+    }
+
+    Ok(Json(RescanResponse {
+        clean: scan.is_clean(),
+        signature: scan.signature,
+        engine: scan.engine,
+        signatures: scan.signatures,
+    }))
+}
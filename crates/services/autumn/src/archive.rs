@@ -0,0 +1,161 @@
+use std::io::{self, Cursor, Read};
+
+use flate2::read::GzDecoder;
+use guilderia_config::{config, report_internal_error, FilesArchive};
+use guilderia_database::{ArchiveEntry, Metadata};
+use guilderia_result::{create_error, Result};
+use zip::ZipArchive;
+
+/// Mime types we can list the contents of
+static SUPPORTED_ARCHIVE_MIME: [&str; 3] =
+    ["application/zip", "application/x-tar", "application/gzip"];
+
+/// Running tally of an archive's contents
+struct Listing<'a> {
+    limits: &'a FilesArchive,
+    compressed_size: usize,
+    files: Vec<ArchiveEntry>,
+    count: usize,
+    size: usize,
+    contents: Vec<Vec<u8>>,
+}
+
+impl Listing<'_> {
+    /// Read a file from the archive, enforcing decompression limits as we go
+    fn add(&mut self, name: String, reader: impl Read) -> Result<()> {
+        self.count += 1;
+        if self.count > self.limits.max_entries {
+            return Err(create_error!(ArchiveTooLarge));
+        }
+
+        // Never trust the sizes the archive claims, only read up to the limit
+        let remaining = (self.limits.max_uncompressed_size - self.size) as u64;
+        let mut reader = reader.take(remaining + 1);
+        let size = if self.limits.scan_contents {
+            let mut buf = Vec::new();
+            let size = report_internal_error!(reader.read_to_end(&mut buf))?;
+            self.contents.push(buf);
+            size
+        } else {
+            report_internal_error!(io::copy(&mut reader, &mut io::sink()))? as usize
+        };
+
+        self.size += size;
+        if self.size > self.limits.max_uncompressed_size
+            || self.size > self.compressed_size * self.limits.max_compression_ratio
+        {
+            return Err(create_error!(ArchiveTooLarge));
+        }
+
+        if self.files.len() < self.limits.listing_limit {
+            self.files.push(ArchiveEntry {
+                name,
+                size: size as isize,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Walk through a zip archive
+    fn read_zip(&mut self, buf: &[u8]) -> Option<Result<()>> {
+        let mut archive = ZipArchive::new(Cursor::new(buf)).ok()?;
+        for i in 0..archive.len() {
+            let file = match archive.by_index(i) {
+                Ok(file) => file,
+                Err(_) => return Some(Err(create_error!(FileTypeNotAllowed))),
+            };
+
+            if file.is_dir() {
+                continue;
+            }
+
+            let name = file.name().to_owned();
+            if let Err(err) = self.add(name, file) {
+                return Some(Err(err));
+            }
+        }
+
+        Some(Ok(()))
+    }
+
+    /// Walk through a tar archive
+    fn read_tar(&mut self, reader: impl Read) -> Option<Result<()>> {
+        let mut archive = tar::Archive::new(reader);
+        let mut entries = archive.entries().ok()?.peekable();
+
+        // Not a tar archive if we can't read the first header
+        if entries.peek().is_some_and(|entry| entry.is_err()) {
+            return None;
+        }
+
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(_) => return Some(Err(create_error!(FileTypeNotAllowed))),
+            };
+
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+
+            let name = entry
+                .path()
+                .map(|path| path.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            if let Err(err) = self.add(name, entry) {
+                return Some(Err(err));
+            }
+        }
+
+        Some(Ok(()))
+    }
+}
+
+/// Inspect an archive, producing a listing of its contents
+///
+/// Returns None if the file is not an archive we can read
+pub async fn inspect_archive(buf: &[u8], mime_type: &str) -> Result<Option<Metadata>> {
+    if !SUPPORTED_ARCHIVE_MIME.contains(&mime_type) {
+        return Ok(None);
+    }
+
+    let config = config().await;
+    let mut listing = Listing {
+        limits: &config.files.archive,
+        compressed_size: buf.len(),
+        files: vec![],
+        count: 0,
+        size: 0,
+        contents: vec![],
+    };
+
+    let result = match mime_type {
+        "application/zip" => listing.read_zip(buf),
+        "application/x-tar" => listing.read_tar(buf),
+        // Only gzip compressed tarballs are inspected
+        _ => listing.read_tar(GzDecoder::new(buf)),
+    };
+
+    match result {
+        Some(result) => result?,
+        None => return Ok(None),
+    }
+
+    // Scan each file individually if configured to
+    for content in &listing.contents {
+        if crate::clamav::scan(content)
+            .await?
+            .is_some_and(|scan| !scan.is_clean())
+        {
+            return Err(create_error!(InternalError));
+        }
+    }
+
+    Ok(Some(Metadata::Archive {
+        files: listing.files,
+        count: listing.count as isize,
+        size: listing.size as isize,
+    }))
+}
@@ -0,0 +1,219 @@
+//! CDN integration
+//!
+//! Autumn can sit behind a CDN which authenticates itself with a shared
+//! header, so files cannot be fetched around it, and which checks signed
+//! credentials issued here before serving files, so only signed in users
+//! can download them even when they are cached at the edge.
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    extract::{Query, Request},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use guilderia_config::{config, report_internal_error, CdnSigning, FilesCdn};
+use guilderia_database::User;
+use guilderia_result::{create_error, Result};
+use hmac::{Hmac, Mac};
+use rsa::{
+    pkcs1::DecodeRsaPrivateKey, pkcs1v15::SigningKey, pkcs8::DecodePrivateKey,
+    signature::SignatureEncoding, signature::Signer, RsaPrivateKey,
+};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::Sha256;
+use utoipa::{IntoParams, ToSchema};
+
+/// Compare two secrets without leaking where they differ
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Reject requests which did not come through the CDN
+pub async fn require_origin_auth(request: Request, next: Next) -> Response {
+    let cdn = config().await.files.cdn;
+    if !cdn.origin_secret.is_empty() {
+        let authenticated = request
+            .headers()
+            .get(cdn.origin_header.as_str())
+            .is_some_and(|value| constant_time_eq(value.as_bytes(), cdn.origin_secret.as_bytes()));
+
+        if !authenticated {
+            return StatusCode::FORBIDDEN.into_response();
+        }
+    }
+
+    next.run(request).await
+}
+
+/// Sign a CloudFront custom policy granting access to everything under the CDN
+///
+/// Returns the `CloudFront-Policy`, `CloudFront-Signature` and
+/// `CloudFront-Key-Pair-Id` cookies.
+fn cloudfront_cookies(cdn: &FilesCdn, expires_at: u64) -> Result<HashMap<String, String>> {
+    let policy = serde_json::json!({
+        "Statement": [{
+            "Resource": format!("{}/*", cdn.url.trim_end_matches('/')),
+            "Condition": {
+                "DateLessThan": {
+                    "AWS:EpochTime": expires_at
+                }
+            }
+        }]
+    })
+    .to_string();
+
+    let key = RsaPrivateKey::from_pkcs1_pem(&cdn.cloudfront_private_key)
+        .or_else(|_| RsaPrivateKey::from_pkcs8_pem(&cdn.cloudfront_private_key));
+    let key = report_internal_error!(key)?;
+    let signature = SigningKey::<Sha1>::new(key).sign(policy.as_bytes());
+
+    // CloudFront uses its own URL-safe variant of base64
+    let encode = |data: &[u8]| {
+        STANDARD
+            .encode(data)
+            .replace('+', "-")
+            .replace('=', "_")
+            .replace('/', "~")
+    };
+
+    Ok(HashMap::from([
+        ("CloudFront-Policy".to_string(), encode(policy.as_bytes())),
+        (
+            "CloudFront-Signature".to_string(),
+            encode(&signature.to_vec()),
+        ),
+        (
+            "CloudFront-Key-Pair-Id".to_string(),
+            cdn.cloudfront_key_pair_id.clone(),
+        ),
+    ]))
+}
+
+/// Create a token for Cloudflare's `is_timed_hmac_valid_v0` rule
+///
+/// The token is sent as the `verify` query parameter of the signed path.
+fn cloudflare_token(secret: &str, path: &str, issued_at: u64) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key");
+    mac.update(format!("{path}{issued_at}").as_bytes());
+
+    let signature = STANDARD.encode(mac.finalize().into_bytes());
+    format!(
+        "{issued_at}-{}",
+        signature
+            .replace('+', "%2B")
+            .replace('/', "%2F")
+            .replace('=', "%3D")
+    )
+}
+
+/// Options for fetching CDN credentials
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct CredentialsQuery {
+    /// Path of the file to sign, required by token based CDNs
+    path: Option<String>,
+}
+
+/// Signed credentials for fetching files through the CDN
+#[derive(Serialize, Debug, ToSchema)]
+pub struct CdnCredentials {
+    /// Cookies to send with requests to the CDN, also set on the response
+    cookies: HashMap<String, String>,
+    /// Query string to append to the requested path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query: Option<String>,
+    /// Unix timestamp at which these credentials expire
+    expires_at: u64,
+}
+
+/// Fetch CDN credentials
+///
+/// Issue signed credentials which let the current user fetch files through the CDN.
+#[utoipa::path(
+    get,
+    path = "/cdn/credentials",
+    responses(
+        (status = 200, description = "Signed credentials", body = CdnCredentials)
+    ),
+    params(CredentialsQuery),
+    security(("session_token" = []), ("bot_token" = []))
+)]
+pub async fn fetch_credentials(
+    _user: User,
+    Query(query): Query<CredentialsQuery>,
+) -> Result<Response> {
+    let cdn = config().await.files.cdn;
+    let now = report_internal_error!(SystemTime::now().duration_since(UNIX_EPOCH))?.as_secs();
+    let expires_at = now + cdn.credentials_ttl_seconds;
+
+    let credentials = match cdn.signing {
+        CdnSigning::None => return Err(create_error!(InvalidOperation)),
+        CdnSigning::Cloudfront => CdnCredentials {
+            cookies: cloudfront_cookies(&cdn, expires_at)?,
+            query: None,
+            expires_at,
+        },
+        CdnSigning::Cloudflare => {
+            let path = query
+                .path
+                .filter(|path| path.starts_with('/'))
+                .ok_or_else(|| create_error!(InvalidOperation))?;
+
+            CdnCredentials {
+                cookies: HashMap::new(),
+                query: Some(format!(
+                    "verify={}",
+                    cloudflare_token(&cdn.cloudflare_secret, &path, now)
+                )),
+                expires_at,
+            }
+        }
+    };
+
+    let mut response = Json(&credentials).into_response();
+    for (name, value) in &credentials.cookies {
+        let mut cookie = format!(
+            "{name}={value}; Path=/; Max-Age={}; Secure; HttpOnly; SameSite=None",
+            cdn.credentials_ttl_seconds
+        );
+
+        if !cdn.cookie_domain.is_empty() {
+            cookie.push_str(&format!("; Domain={}", cdn.cookie_domain));
+        }
+
+        if let Ok(cookie) = cookie.parse() {
+            response.headers_mut().append(header::SET_COOKIE, cookie);
+        }
+    }
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cloudflare_token, constant_time_eq};
+
+    #[test]
+    fn compares_secrets() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secrets"));
+    }
+
+    #[test]
+    fn signs_cloudflare_tokens() {
+        let token = cloudflare_token("secret", "/attachments/id/file.png", 1484063787);
+        assert!(token.starts_with("1484063787-"));
+        assert!(!token.contains('+') && !token.contains('/') && !token.contains('='));
+        assert_ne!(
+            token,
+            cloudflare_token("secret", "/attachments/other/file.png", 1484063787)
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::time::Duration;
 
-use guilderia_config::{config, report_internal_error};
+use guilderia_config::config;
+use guilderia_database::{iso8601_timestamp::Timestamp, FileScan};
 use guilderia_result::Result;
 
 /// Initialise ClamAV
@@ -33,17 +34,15 @@ pub async fn init() {
 }
 
 /// Scan for malware
-pub async fn is_malware(buf: &[u8]) -> Result<bool> {
-    let config = config().await;
-    if config.files.clamd_host.is_empty() {
-        Ok(false)
-    } else {
-        let scan_response = report_internal_error!(revolt_clamav_client::scan_buffer_tcp(
-            buf,
-            config.files.clamd_host,
-            None
-        ))?;
-
-        report_internal_error!(revolt_clamav_client::clean(&scan_response)).map(|v| !v)
-    }
+///
+/// Returns None if ClamAV is not configured
+pub async fn scan(buf: &[u8]) -> Result<Option<FileScan>> {
+    Ok(guilderia_files::clamav::scan(buf)
+        .await?
+        .map(|scan| FileScan {
+            signature: scan.signature,
+            engine: scan.version.engine,
+            signatures: scan.version.signatures,
+            scanned_at: Timestamp::now_utc(),
+        }))
 }
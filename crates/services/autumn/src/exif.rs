@@ -1,22 +1,44 @@
 use std::io::{Cursor, Read};
 
 use exif::Reader;
-use image::{ImageFormat, ImageReader};
-use guilderia_config::report_internal_error;
-use guilderia_database::Metadata;
+use guilderia_config::{report_internal_error, ExifPolicy};
+use guilderia_database::{Metadata, StrippedMetadata};
 use guilderia_result::{create_error, Result};
+use image::{
+    codecs::{avif::AvifEncoder, jpeg::JpegEncoder, png::PngEncoder, tiff::TiffEncoder},
+    DynamicImage, ImageDecoder, ImageEncoder, ImageReader,
+};
 use tempfile::NamedTempFile;
 use tokio::process::Command;
 
+/// Encode an image, embedding the given colour profile if the format supports it
+///
+/// Returns whether the colour profile was kept
+fn encode_image(
+    image: &DynamicImage,
+    mut encoder: impl ImageEncoder,
+    icc_profile: Option<Vec<u8>>,
+) -> Result<bool> {
+    let kept_profile = icc_profile
+        .map(|icc_profile| encoder.set_icc_profile(icc_profile).is_ok())
+        .unwrap_or_default();
+
+    report_internal_error!(image.write_with_encoder(encoder))?;
+    Ok(kept_profile)
+}
+
 /// Strip EXIF data from given file and produce new file and metadata
+///
+/// What is kept is decided by the given policy, anything removed is recorded in the metadata
 pub async fn strip_metadata(
     file: NamedTempFile,
     buf: Vec<u8>,
     metadata: Metadata,
     mime: &str,
+    policy: ExifPolicy,
 ) -> Result<(Vec<u8>, Metadata)> {
     match &metadata {
-        Metadata::Image { width, height } => match mime {
+        Metadata::Image { .. } => match mime {
             // // little_exif does not appear to parse JPEGs correctly? had 2/2 files fail
             // "image/jpeg" | "image/png" => {
             //     // use little_exif to strip metadata except for orientation and colour profile
@@ -40,60 +62,90 @@ pub async fn strip_metadata(
                 // Create a reader
                 let mut cursor = Cursor::new(buf);
 
-                // Decode the image
-                let image = report_internal_error!(report_internal_error!(ImageReader::new(
-                    &mut cursor
-                )
-                .with_guessed_format())?
-                .decode());
+                // Extract EXIF data
+                let exif = Reader::new().read_from_container(&mut cursor).ok();
+                let rotation = exif
+                    .as_ref()
+                    .and_then(|exif| exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY))
+                    .and_then(|orientation| orientation.value.get_uint(0))
+                    .unwrap_or_default();
 
                 // Reset read position
                 cursor.set_position(0);
 
-                // Extract orientation data
-                let exif_reader = Reader::new();
-                let rotation = match exif_reader.read_from_container(&mut cursor) {
-                    Ok(exif) => match exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY) {
-                        Some(orientation) => orientation.value.get_uint(0).unwrap_or_default(),
-                        _ => 0,
-                    },
-                    _ => 0,
+                // Decode the image and its colour profile
+                let mut decoder = report_internal_error!(report_internal_error!(
+                    ImageReader::new(&mut cursor).with_guessed_format()
+                )?
+                .into_decoder())?;
+                let icc_profile = decoder.icc_profile().ok().flatten();
+                let image = report_internal_error!(DynamicImage::from_decoder(decoder))?;
+
+                // Keep track of what we remove
+                let mut stripped = vec![];
+                if exif.as_ref().is_some_and(|exif| {
+                    exif.fields()
+                        .any(|field| field.tag != exif::Tag::Orientation)
+                }) {
+                    stripped.push(StrippedMetadata::Exif);
+                }
+
+                // Apply the EXIF rotation
+                // See https://jdhao.github.io/2019/07/31/image_rotation_exif_info/
+                let image = if policy == ExifPolicy::StripAll {
+                    if rotation > 1 {
+                        stripped.push(StrippedMetadata::Orientation);
+                    }
+
+                    image
+                } else {
+                    match &rotation {
+                        2 => image.fliph(),
+                        3 => image.rotate180(),
+                        4 => image.rotate180().fliph(),
+                        5 => image.rotate90().fliph(),
+                        6 => image.rotate90(),
+                        7 => image.rotate270().fliph(),
+                        8 => image.rotate270(),
+                        _ => image,
+                    }
                 };
 
+                // Only carry the colour profile over if requested
+                let had_profile = icc_profile.is_some();
+                let icc_profile = icc_profile.filter(|_| policy == ExifPolicy::KeepColorProfile);
+
                 // Create a buffer to write to
                 let mut bytes: Vec<u8> = Vec::new();
                 let mut writer = Cursor::new(&mut bytes);
 
-                // Apply the EXIF rotation
-                // See https://jdhao.github.io/2019/07/31/image_rotation_exif_info/
-                report_internal_error!(match &rotation {
-                    2 => image?.fliph(),
-                    3 => image?.rotate180(),
-                    4 => image?.rotate180().fliph(),
-                    5 => image?.rotate90().fliph(),
-                    6 => image?.rotate90(),
-                    7 => image?.rotate270().fliph(),
-                    8 => image?.rotate270(),
-                    _ => image?,
-                }
-                .write_to(
-                    &mut writer,
-                    match mime {
-                        "image/jpeg" => ImageFormat::Jpeg,
-                        "image/png" => ImageFormat::Png,
-                        "image/avif" => ImageFormat::Avif,
-                        "image/tiff" => ImageFormat::Tiff,
-                        _ => todo!(),
-                    },
-                ))?;
+                let kept_profile = match mime {
+                    "image/jpeg" => {
+                        encode_image(&image, JpegEncoder::new(&mut writer), icc_profile)
+                    }
+                    "image/png" => encode_image(&image, PngEncoder::new(&mut writer), icc_profile),
+                    "image/avif" => {
+                        encode_image(&image, AvifEncoder::new(&mut writer), icc_profile)
+                    }
+                    "image/tiff" => {
+                        encode_image(&image, TiffEncoder::new(&mut writer), icc_profile)
+                    }
+                    _ => unreachable!(),
+                }?;
 
-                // Calculate dimensions after rotation.
-                let (width, height) = match &rotation {
-                    2 | 4 | 5 | 7 => (*height, *width),
-                    _ => (*width, *height),
-                };
+                if had_profile && !kept_profile {
+                    stripped.push(StrippedMetadata::ColorProfile);
+                }
 
-                Ok((bytes, Metadata::Image { width, height }))
+                Ok((
+                    bytes,
+                    Metadata::Image {
+                        width: image.width() as isize,
+                        height: image.height() as isize,
+                        blurhash: None,
+                        stripped,
+                    },
+                ))
             }
             // JXLs store EXIF data but we don't have the ability to write them
             "image/jxl" => Ok((buf, metadata)),
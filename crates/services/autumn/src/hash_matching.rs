@@ -0,0 +1,362 @@
+//! Perceptual hash matching
+//!
+//! Images are reduced to a 256-bit hash of their low frequencies in the same
+//! manner as PDQ, so resized, recompressed or lightly edited copies of an
+//! image land within a few bits of each other. Uploads close enough to an
+//! entry on the configured hash list are refused and escalated.
+use std::{io::Cursor, net::IpAddr, sync::OnceLock};
+
+use axum::http::HeaderMap;
+use guilderia_config::{config, FilesHashMatching};
+use guilderia_database::{iso8601_timestamp::Timestamp, Database, HashMatch};
+use guilderia_files::{decode_image, upload_to_s3};
+use guilderia_result::{create_error, Result};
+use hmac::{Hmac, Mac};
+use image::{imageops::FilterType, DynamicImage};
+use sha2::Sha256;
+
+/// Side length images are reduced to before hashing
+const SAMPLE_SIZE: usize = 64;
+
+/// Side length of the block of frequencies the hash is taken from
+const DCT_SIZE: usize = 16;
+
+/// Perceptual hash of an image
+pub type Hash = [u8; DCT_SIZE * DCT_SIZE / 8];
+
+/// Entry on the hash list
+pub struct ListedHash {
+    pub hash: Hash,
+    pub label: Option<String>,
+}
+
+/// Hash list loaded on start up
+static HASH_LIST: OnceLock<Vec<ListedHash>> = OnceLock::new();
+
+/// Load the hash list if hash matching is enabled
+pub async fn init() {
+    let config = config().await;
+    if !config.files.hash_matching.enabled {
+        return;
+    }
+
+    let list = std::fs::read_to_string(&config.files.hash_matching.hash_list)
+        .expect("Failed to read hash list");
+
+    let hashes = parse_hash_list(&list);
+    tracing::info!("Loaded {} hashes, hash matching enabled!", hashes.len());
+    HASH_LIST.set(hashes).ok();
+}
+
+/// Parse a hash list, skipping comments and malformed lines
+fn parse_hash_list(list: &str) -> Vec<ListedHash> {
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (hash, label) = line
+                .split_once(char::is_whitespace)
+                .map(|(hash, label)| (hash, Some(label.trim().to_owned())))
+                .unwrap_or((line, None));
+
+            match from_hex(hash) {
+                Some(hash) => Some(ListedHash { hash, label }),
+                None => {
+                    tracing::warn!("Skipping malformed entry on hash list: {line}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Encode a hash as hex
+pub fn to_hex(hash: &Hash) -> String {
+    hash.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Decode a hash from hex
+pub fn from_hex(hex: &str) -> Option<Hash> {
+    if hex.len() != 2 * std::mem::size_of::<Hash>() || !hex.is_ascii() {
+        return None;
+    }
+
+    let mut hash = Hash::default();
+    for (byte, pair) in hash.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+
+    Some(hash)
+}
+
+/// Number of bits by which two hashes differ
+pub fn distance(a: &Hash, b: &Hash) -> u32 {
+    a.iter().zip(b).map(|(a, b)| (a ^ b).count_ones()).sum()
+}
+
+/// Compute the perceptual hash of an image
+///
+/// The image is reduced to greyscale, transformed into frequencies and each
+/// of the lowest frequencies, other than the average, is compared against
+/// their median.
+pub fn perceptual_hash(image: &DynamicImage) -> Hash {
+    let luma = image
+        .resize_exact(SAMPLE_SIZE as u32, SAMPLE_SIZE as u32, FilterType::Triangle)
+        .to_luma8();
+
+    let basis: Vec<[f32; SAMPLE_SIZE]> = (1..=DCT_SIZE)
+        .map(|frequency| {
+            let mut row = [0.0; SAMPLE_SIZE];
+            for (index, value) in row.iter_mut().enumerate() {
+                *value = (std::f32::consts::PI / (2 * SAMPLE_SIZE) as f32
+                    * frequency as f32
+                    * (2 * index + 1) as f32)
+                    .cos();
+            }
+
+            row
+        })
+        .collect();
+
+    // Transform each row, then each column of the result
+    let mut rows = [[0.0; DCT_SIZE]; SAMPLE_SIZE];
+    for (y, row) in rows.iter_mut().enumerate() {
+        for (u, value) in row.iter_mut().enumerate() {
+            *value = (0..SAMPLE_SIZE)
+                .map(|x| basis[u][x] * luma.get_pixel(x as u32, y as u32).0[0] as f32)
+                .sum();
+        }
+    }
+
+    let mut coefficients = Vec::with_capacity(DCT_SIZE * DCT_SIZE);
+    for frequencies in &basis {
+        for u in 0..DCT_SIZE {
+            coefficients.push(
+                (0..SAMPLE_SIZE)
+                    .map(|y| frequencies[y] * rows[y][u])
+                    .sum::<f32>(),
+            );
+        }
+    }
+
+    let mut sorted = coefficients.clone();
+    sorted.sort_by(f32::total_cmp);
+    let median = sorted[sorted.len() / 2];
+
+    let mut hash = Hash::default();
+    for (index, coefficient) in coefficients.into_iter().enumerate() {
+        if coefficient > median {
+            hash[index / 8] |= 1 << (index % 8);
+        }
+    }
+
+    hash
+}
+
+/// Find the closest entry on a hash list within the maximum distance
+fn find_match<'a>(
+    list: &'a [ListedHash],
+    hash: &Hash,
+    max_distance: u32,
+) -> Option<(&'a ListedHash, u32)> {
+    list.iter()
+        .map(|entry| (entry, distance(&entry.hash, hash)))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .min_by_key(|(_, distance)| *distance)
+}
+
+/// Determine the jurisdiction an upload was made from
+fn jurisdiction(config: &FilesHashMatching, headers: &HeaderMap) -> Option<String> {
+    Some(config.country_header.as_str())
+        .filter(|header| !header.is_empty())
+        .and_then(|header| headers.get(header))
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        // Cloudflare uses XX and T1 for unknown countries and Tor
+        .filter(|country| country.len() == 2 && !matches!(*country, "XX" | "T1"))
+        .or(Some(config.default_jurisdiction.as_str()).filter(|x| !x.is_empty()))
+        .map(str::to_ascii_uppercase)
+}
+
+/// Send a match to the reporting hooks for its jurisdiction
+async fn notify_hooks(db: &Database, config: &FilesHashMatching, hash_match: &HashMatch) {
+    let body = serde_json::json!({
+        "id": hash_match.id,
+        "user": hash_match.user,
+        "filename": hash_match.filename,
+        "content_type": hash_match.content_type,
+        "size": hash_match.size,
+        "hash": hash_match.hash,
+        "perceptual_hash": hash_match.perceptual_hash,
+        "matched": hash_match.matched,
+        "label": hash_match.label,
+        "distance": hash_match.distance,
+        "jurisdiction": hash_match.jurisdiction,
+        "ip": hash_match.ip,
+        "report_id": hash_match.report_id,
+        "created_at": hash_match.created_at,
+    })
+    .to_string();
+
+    let client = reqwest::Client::new();
+    for hook in config.hooks_for(hash_match.jurisdiction.as_deref()) {
+        let mut request = client
+            .post(&hook.url)
+            .header("content-type", "application/json");
+
+        if !hook.secret.is_empty() {
+            let mut mac = Hmac::<Sha256>::new_from_slice(hook.secret.as_bytes())
+                .expect("HMAC accepts any key");
+            mac.update(body.as_bytes());
+            request = request.header(
+                "x-signature",
+                format!("{:02x}", mac.finalize().into_bytes()),
+            );
+        }
+
+        match request
+            .body(body.clone())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+        {
+            Ok(_) => {
+                db.add_hash_match_recipient(&hash_match.id, &hook.url)
+                    .await
+                    .ok();
+            }
+            Err(error) => tracing::error!(
+                "Failed to report hash match {} to {}: {error}",
+                hash_match.id,
+                hook.url
+            ),
+        }
+    }
+}
+
+/// Uploaded file being checked against the hash list
+pub struct Upload<'a> {
+    pub user: &'a str,
+    pub tag: &'a str,
+    pub filename: &'a str,
+    pub mime_type: &'a str,
+    pub hash: &'a str,
+    pub buf: &'a [u8],
+    pub ip: IpAddr,
+}
+
+/// Match an uploaded image against the hash list
+///
+/// Matching uploads are kept as evidence, reported to the moderation team
+/// and the reporting hooks, and refused.
+pub async fn check(db: &Database, upload: Upload<'_>, headers: &HeaderMap) -> Result<()> {
+    let Some(list) = HASH_LIST.get() else {
+        return Ok(());
+    };
+
+    let Ok(image) = decode_image(&mut Cursor::new(upload.buf), upload.mime_type) else {
+        return Ok(());
+    };
+
+    let config = config().await;
+    let hash = perceptual_hash(&image);
+    let Some((entry, distance)) = find_match(list, &hash, config.files.hash_matching.max_distance)
+    else {
+        return Ok(());
+    };
+
+    let id = ulid::Ulid::new().to_string();
+    let bucket_id = Some(config.files.hash_matching.evidence_bucket.clone())
+        .filter(|bucket| !bucket.is_empty())
+        .unwrap_or_else(|| config.files.s3.default_bucket.clone());
+    let path = format!("hash_matches/{id}");
+    let iv = upload_to_s3(&bucket_id, &path, upload.buf).await?;
+
+    let mut hash_match = HashMatch {
+        id,
+        user: upload.user.to_owned(),
+        tag: upload.tag.to_owned(),
+        filename: upload.filename.to_owned(),
+        content_type: upload.mime_type.to_owned(),
+        size: upload.buf.len() as isize,
+        hash: upload.hash.to_owned(),
+        perceptual_hash: to_hex(&hash),
+        matched: to_hex(&entry.hash),
+        label: entry.label.clone(),
+        distance,
+        bucket_id,
+        path,
+        iv,
+        jurisdiction: jurisdiction(&config.files.hash_matching, headers),
+        ip: Some(upload.ip.to_string()),
+        report_id: None,
+        reported_to: vec![],
+        created_at: Timestamp::now_utc(),
+    };
+
+    db.insert_hash_match(&hash_match).await?;
+    tracing::warn!(
+        "Blocked upload {} by {} from {} matching the hash list, recorded as hash match {}",
+        hash_match.hash,
+        hash_match.user,
+        upload.ip,
+        hash_match.id
+    );
+
+    if let Err(error) = hash_match.report(db).await {
+        tracing::error!("Failed to report hash match {}: {error:?}", hash_match.id);
+    }
+
+    let db = db.clone();
+    tokio::spawn(async move {
+        notify_hooks(&db, &config.files.hash_matching, &hash_match).await;
+    });
+
+    Err(create_error!(InternalError))
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, GrayImage, Luma};
+
+    use super::{distance, find_match, from_hex, parse_hash_list, perceptual_hash, to_hex};
+
+    /// Render the same grid of grey cells at a given size
+    fn pattern(size: u32, offset: u8, invert: bool) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(size, size, |x, y| {
+            let (x, y) = (x * 8 / size, y * 8 / size);
+            let value = 48 + ((x * 37 + y * 91 + x * y * 13) % 160) as u8;
+            let value = if invert { 255 - value } else { value };
+            Luma([value + offset])
+        }))
+    }
+
+    #[test]
+    fn hashes_survive_edits() {
+        let original = perceptual_hash(&pattern(256, 0, false));
+        assert!(distance(&original, &perceptual_hash(&pattern(128, 0, false))) <= 31);
+        assert!(distance(&original, &perceptual_hash(&pattern(256, 20, false))) <= 31);
+        assert!(distance(&original, &perceptual_hash(&pattern(256, 0, true))) > 128);
+    }
+
+    #[test]
+    fn matches_hash_list() {
+        let hash = perceptual_hash(&pattern(256, 0, false));
+        let mut near = hash;
+        near[0] ^= 0b111;
+
+        let list = parse_hash_list(&format!(
+            "# known images\n\n{} example image\nnot a hash\n{}\n",
+            to_hex(&near),
+            "0".repeat(64)
+        ));
+
+        assert_eq!(list.len(), 2);
+        assert_eq!(from_hex(&to_hex(&hash)), Some(hash));
+
+        let (entry, distance) = find_match(&list, &hash, 31).unwrap();
+        assert_eq!(entry.label.as_deref(), Some("example image"));
+        assert_eq!(distance, 3);
+        assert!(find_match(&list, &hash, 2).is_none());
+    }
+}
@@ -1,9 +1,13 @@
 use std::net::{Ipv4Addr, SocketAddr};
 
-use axum::Router;
+use axum::{middleware, Router};
 
 use guildera_database::DatabaseInfo;
-use tokio::net::TcpListener;
+use guilderia_config::doctor;
+use tokio::{
+    net::TcpListener,
+    signal::unix::{signal, SignalKind},
+};
 use utoipa::{
     openapi::security::{ApiKey, ApiKeyValue, SecurityScheme},
     Modify, OpenApi,
@@ -11,19 +15,30 @@ use utoipa::{
 use utoipa_scalar::{Scalar, Servable as ScalarServable};
 
 mod api;
+pub mod archive;
+pub mod cdn;
 pub mod clamav;
 pub mod exif;
+pub mod hash_matching;
 pub mod metadata;
 pub mod mime_type;
 
 #[tokio::main]
 async fn main() -> Result<(), std::io::Error> {
+    // Check the configuration and exit if asked to
+    if doctor::requested() {
+        std::process::exit(doctor::run(doctor::Service::Files).await);
+    }
+
     // Configure logging and environment
     guilderia_config::configure!(files);
 
     // Wait for ClamAV
     clamav::init().await;
 
+    // Load the hash list
+    hash_matching::init().await;
+
     // Configure API schema
     #[derive(OpenApi)]
     #[openapi(
@@ -32,7 +47,11 @@ async fn main() -> Result<(), std::io::Error> {
             api::root,
             api::upload_file,
             api::fetch_preview,
-            api::fetch_file
+            api::fetch_file,
+            api::fetch_text_preview,
+            api::fetch_export,
+            api::rescan_file,
+            cdn::fetch_credentials
         ),
         components(
             schemas(
@@ -41,7 +60,10 @@ async fn main() -> Result<(), std::io::Error> {
                 api::RootResponse,
                 api::Tag,
                 api::UploadPayload,
-                api::UploadResponse
+                api::UploadResponse,
+                api::TextPreviewResponse,
+                api::RescanResponse,
+                cdn::CdnCredentials
             )
         ),
         tags(
@@ -74,10 +96,41 @@ async fn main() -> Result<(), std::io::Error> {
     let app = Router::new()
         .merge(Scalar::with_url("/scalar", ApiDoc::openapi()))
         .nest("/", api::router().await)
-        .with_state(db);
+        .layer(middleware::from_fn(
+            guilderia_config::proxy::resolve_client_ip,
+        ))
+        .with_state(db.clone());
 
     // Configure TCP listener and bind
     let address = SocketAddr::from((Ipv4Addr::UNSPECIFIED, 14704));
     let listener = TcpListener::bind(&address).await?;
-    axum::serve(listener, app.into_make_service()).await
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal());
+
+    // Give in-flight uploads until the deadline to complete once asked to stop
+    let deadline = async {
+        shutdown_signal().await;
+        tokio::time::sleep(guilderia_config::config().await.shutdown.deadline()).await;
+    };
+
+    tokio::select! {
+        result = server => result?,
+        _ = deadline => tracing::warn!("Shutdown deadline elapsed with requests still in flight"),
+    }
+
+    db.shutdown().await;
+    Ok(())
+}
+
+/// Wait until the process is asked to stop
+async fn shutdown_signal() {
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {},
+        _ = terminate.recv() => {},
+    }
 }
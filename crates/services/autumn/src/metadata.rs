@@ -1,8 +1,65 @@
-use std::io::Cursor;
+use std::io::{Cursor, Write};
 
+use guilderia_config::config;
 use guilderia_database::Metadata;
-use guilderia_files::{image_size, video_size};
+use guilderia_files::{decode_image, image_size, video_size};
+use image::{DynamicImage, ImageFormat};
 use tempfile::NamedTempFile;
+use tokio::process::Command;
+
+/// Languages to detect from file extensions
+static LANGUAGE_EXTENSIONS: [(&str, &str); 38] = [
+    ("bash", "bash"),
+    ("c", "c"),
+    ("cc", "cpp"),
+    ("cpp", "cpp"),
+    ("cs", "csharp"),
+    ("css", "css"),
+    ("dart", "dart"),
+    ("diff", "diff"),
+    ("go", "go"),
+    ("h", "c"),
+    ("hpp", "cpp"),
+    ("html", "html"),
+    ("ini", "ini"),
+    ("java", "java"),
+    ("js", "javascript"),
+    ("json", "json"),
+    ("jsx", "javascript"),
+    ("kt", "kotlin"),
+    ("lua", "lua"),
+    ("md", "markdown"),
+    ("php", "php"),
+    ("ps1", "powershell"),
+    ("py", "python"),
+    ("rb", "ruby"),
+    ("rs", "rust"),
+    ("scss", "scss"),
+    ("sh", "bash"),
+    ("sql", "sql"),
+    ("svelte", "svelte"),
+    ("swift", "swift"),
+    ("toml", "toml"),
+    ("ts", "typescript"),
+    ("tsx", "typescript"),
+    ("vue", "vue"),
+    ("xml", "xml"),
+    ("yaml", "yaml"),
+    ("yml", "yaml"),
+    ("zig", "zig"),
+];
+
+/// Number of blurhash components along each axis
+const BLURHASH_COMPONENTS: (u32, u32) = (4, 3);
+
+/// Size to shrink images down to before computing their blurhash
+const BLURHASH_SAMPLE_SIZE: u32 = 64;
+
+/// Number of slices making up the waveform of audio
+const WAVEFORM_LENGTH: usize = 64;
+
+/// Rate to resample audio to before measuring it
+const WAVEFORM_SAMPLE_RATE: usize = 8000;
 
 /// Intersection of what infer can detect and what image-rs supports
 ///
@@ -26,6 +83,8 @@ pub fn generate_metadata(f: &NamedTempFile, mime_type: &str) -> Metadata {
             .map(|(width, height)| Metadata::Image {
                 width: width as isize,
                 height: height as isize,
+                blurhash: None,
+                stripped: vec![],
             })
             .unwrap_or_default()
     } else if mime_type.starts_with("video/") {
@@ -33,12 +92,20 @@ pub fn generate_metadata(f: &NamedTempFile, mime_type: &str) -> Metadata {
             .map(|(width, height)| Metadata::Video {
                 width: width as isize,
                 height: height as isize,
+                blurhash: None,
             })
             .unwrap_or_default()
     } else if mime_type.starts_with("audio/") {
-        Metadata::Audio
+        Metadata::Audio {
+            duration: None,
+            waveform: None,
+        }
     } else if mime_type == "plain/text" {
-        Metadata::Text
+        Metadata::Text {
+            language: None,
+            lines: None,
+            preview: None,
+        }
     } else {
         Metadata::File
     }
@@ -74,3 +141,210 @@ pub fn validate_from_metadata(
 
     metadata
 }
+
+/// Compute a blurhash placeholder for images and videos
+pub async fn generate_blurhash(buf: &[u8], metadata: Metadata, mime_type: &str) -> Metadata {
+    match metadata {
+        Metadata::Image {
+            width,
+            height,
+            stripped,
+            ..
+        } => Metadata::Image {
+            width,
+            height,
+            blurhash: decode_image(&mut Cursor::new(buf), mime_type)
+                .ok()
+                .and_then(encode_blurhash),
+            stripped,
+        },
+        Metadata::Video { width, height, .. } => Metadata::Video {
+            width,
+            height,
+            blurhash: extract_first_frame(buf).await.and_then(encode_blurhash),
+        },
+        metadata => metadata,
+    }
+}
+
+/// Encode a blurhash from a downscaled copy of an image
+fn encode_blurhash(image: DynamicImage) -> Option<String> {
+    let image = image
+        .thumbnail(BLURHASH_SAMPLE_SIZE, BLURHASH_SAMPLE_SIZE)
+        .to_rgba8();
+
+    blurhash::encode(
+        BLURHASH_COMPONENTS.0,
+        BLURHASH_COMPONENTS.1,
+        image.width(),
+        image.height(),
+        image.as_raw(),
+    )
+    .inspect_err(|err| tracing::error!("Failed to generate blurhash! {err:?}"))
+    .ok()
+}
+
+/// Use ffmpeg to grab the first frame of a video
+async fn extract_first_frame(buf: &[u8]) -> Option<DynamicImage> {
+    let mut file = NamedTempFile::new().ok()?;
+    file.write_all(buf).ok()?;
+
+    let output = Command::new("ffmpeg")
+        .args([
+            "-i",
+            file.path().to_str()?,
+            // Only take the first frame
+            "-frames:v",
+            "1",
+            // Write it to stdout as a PNG
+            "-f",
+            "image2pipe",
+            "-vcodec",
+            "png",
+            "-",
+        ])
+        .output()
+        .await
+        .inspect_err(|err| tracing::error!("Failed to run ffmpeg! {err:?}"))
+        .ok()?;
+
+    image::load_from_memory_with_format(&output.stdout, ImageFormat::Png).ok()
+}
+
+/// Measure the length and waveform of audio
+pub async fn generate_waveform(buf: &[u8], metadata: Metadata) -> Metadata {
+    match metadata {
+        Metadata::Audio { .. } => {
+            let Some(samples) = decode_audio(buf).await.filter(|s| !s.is_empty()) else {
+                return metadata;
+            };
+
+            Metadata::Audio {
+                duration: Some((samples.len() * 1000 / WAVEFORM_SAMPLE_RATE) as isize),
+                waveform: Some(measure_waveform(&samples)),
+            }
+        }
+        metadata => metadata,
+    }
+}
+
+/// Take the peak loudness of evenly spaced slices of samples
+fn measure_waveform(samples: &[i16]) -> Vec<u8> {
+    samples
+        .chunks(samples.len().div_ceil(WAVEFORM_LENGTH).max(1))
+        .map(|slice| {
+            let peak = slice.iter().map(|s| s.unsigned_abs()).max().unwrap_or(0);
+            (peak as u32 * u8::MAX as u32 / i16::MIN.unsigned_abs() as u32) as u8
+        })
+        .collect()
+}
+
+/// Use ffmpeg to decode audio into mono samples
+async fn decode_audio(buf: &[u8]) -> Option<Vec<i16>> {
+    let mut file = NamedTempFile::new().ok()?;
+    file.write_all(buf).ok()?;
+
+    let output = Command::new("ffmpeg")
+        .args([
+            "-i",
+            file.path().to_str()?,
+            // Mix down to a single channel at a low sample rate
+            "-vn",
+            "-ac",
+            "1",
+            "-ar",
+            &WAVEFORM_SAMPLE_RATE.to_string(),
+            // Write raw 16-bit samples to stdout
+            "-f",
+            "s16le",
+            "-",
+        ])
+        .output()
+        .await
+        .inspect_err(|err| tracing::error!("Failed to run ffmpeg! {err:?}"))
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(
+        output
+            .stdout
+            .chunks_exact(2)
+            .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+            .collect(),
+    )
+}
+
+/// Detect the language of a text file from its name
+fn detect_language(file_name: &str) -> Option<String> {
+    let file_name = file_name.to_lowercase();
+    match file_name.as_str() {
+        "dockerfile" => return Some("dockerfile".to_owned()),
+        "makefile" => return Some("makefile".to_owned()),
+        _ => {}
+    }
+
+    let (_, extension) = file_name.rsplit_once('.')?;
+    LANGUAGE_EXTENSIONS
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, language)| (*language).to_owned())
+}
+
+/// Generate a preview of the first few lines for text files
+pub async fn generate_text_preview(buf: &[u8], file_name: &str, metadata: Metadata) -> Metadata {
+    let config = config().await;
+    let limits = config.files.text_preview;
+
+    match metadata {
+        Metadata::Text { .. } if buf.len() <= limits.max_file_size => {
+            let Ok(text) = std::str::from_utf8(buf) else {
+                return metadata;
+            };
+
+            let mut preview = text
+                .split_inclusive('\n')
+                .take(limits.max_lines)
+                .collect::<String>();
+
+            if preview.len() > limits.max_length {
+                let mut end = limits.max_length;
+                while !preview.is_char_boundary(end) {
+                    end -= 1;
+                }
+
+                preview.truncate(end);
+            }
+
+            Metadata::Text {
+                language: detect_language(file_name),
+                lines: Some(text.lines().count() as isize),
+                preview: Some(preview),
+            }
+        }
+        metadata => metadata,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{measure_waveform, WAVEFORM_LENGTH};
+
+    #[test]
+    fn measures_waveform_peaks() {
+        let mut samples = vec![0i16; WAVEFORM_LENGTH * 100];
+        samples[0] = i16::MIN;
+        samples[150] = i16::MAX / 2;
+
+        let waveform = measure_waveform(&samples);
+        assert_eq!(waveform.len(), WAVEFORM_LENGTH);
+        assert_eq!(waveform[0], 255);
+        assert_eq!(waveform[1], 127);
+        assert_eq!(waveform[2], 0);
+
+        // Short clips have fewer slices than usual
+        assert_eq!(measure_waveform(&[100; 10]).len(), 10);
+    }
+}
@@ -1,5 +1,15 @@
-use axum::{extract::Query, response::IntoResponse, routing::get, Json, Router};
+use axum::{
+    extract::{Extension, Query},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
 use reqwest::header;
+use guilderia_config::{config, proxy::ClientIp};
 use guilderia_models::v0::Embed;
 use guilderia_result::{create_error, Result};
 use serde::{Deserialize, Serialize};
@@ -46,6 +56,12 @@ struct UrlQuery {
     url: String,
 }
 
+#[derive(Deserialize)]
+struct EmbedQuery {
+    url: String,
+    lang: Option<String>,
+}
+
 /// Proxy a given URL and load media
 #[utoipa::path(
     get,
@@ -78,17 +94,31 @@ async fn proxy(Query(UrlQuery { url }): Query<UrlQuery>) -> Result<impl IntoResp
         (status = 200, description = "Generated embed information", body = Embed)
     ),
     params(
-        ("url" = String, Query, description = "URL to fetch")
+        ("url" = String, Query, description = "URL to fetch"),
+        ("lang" = Option<String>, Query, description = "Preferred language to fetch metadata in")
     ),
     security(
         ("api_key" = [])
     )
 )]
 async fn embed(
-    Query(UrlQuery { url }): Query<UrlQuery>,
-    // TypedHeader(Authorization(_bearer)): TypedHeader<Authorization<Bearer>>,
+    Query(EmbedQuery { url, lang }): Query<EmbedQuery>,
+    authorization: Option<TypedHeader<Authorization<Bearer>>>,
+    Extension(ClientIp(ip)): Extension<ClientIp>,
 ) -> Result<impl IntoResponse> {
-    match Request::generate_embed(url).await {
+    // Only allow trusted services to generate embeds if a key is configured
+    let key = config().await.api.security.january_key;
+    let authenticated = match &authorization {
+        Some(TypedHeader(Authorization(bearer))) => bearer.token() == key,
+        None => false,
+    };
+
+    if !key.is_empty() && !authenticated {
+        tracing::warn!("Refused unauthenticated embed request from {ip}");
+        return Err(create_error!(NotAuthenticated));
+    }
+
+    match Request::generate_embed(url, lang).await {
         Ok(Embed::None) => Err(create_error!(NoEmbedData)),
         result => result,
     }
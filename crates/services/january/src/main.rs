@@ -1,8 +1,12 @@
 use std::net::{Ipv4Addr, SocketAddr};
 
-use axum::Router;
+use axum::{middleware, Router};
 
-use tokio::net::TcpListener;
+use revolt_config::doctor;
+use tokio::{
+    net::TcpListener,
+    signal::unix::{signal, SignalKind},
+};
 use utoipa::{
     openapi::security::{Http, HttpAuthScheme, SecurityScheme},
     Modify, OpenApi,
@@ -11,10 +15,17 @@ use utoipa_scalar::{Scalar, Servable as ScalarServable};
 
 mod api;
 pub mod requests;
+pub mod sanitize;
+pub mod social;
 pub mod website_embed;
 
 #[tokio::main]
 async fn main() -> Result<(), std::io::Error> {
+    // Check the configuration and exit if asked to
+    if doctor::requested() {
+        std::process::exit(doctor::run(doctor::Service::Proxy).await);
+    }
+
     // Configure logging and environment
     revolt_config::configure!(proxy);
 
@@ -38,6 +49,11 @@ async fn main() -> Result<(), std::io::Error> {
                 guilderia_models::v0::TwitchType,
                 guilderia_models::v0::LightspeedType,
                 guilderia_models::v0::BandcampType,
+                guilderia_models::v0::YouTubeType,
+                guilderia_models::v0::YouTubePlaylist,
+                guilderia_models::v0::Post,
+                guilderia_models::v0::PostAuthor,
+                guilderia_models::v0::PostStats,
                 guilderia_models::v0::Special,
                 guilderia_models::v0::WebsiteMetadata,
                 guilderia_models::v0::Text,
@@ -63,12 +79,41 @@ async fn main() -> Result<(), std::io::Error> {
     // Configure Axum and router
     let app = Router::new()
         .merge(Scalar::with_url("/scalar", ApiDoc::openapi()))
-        .nest("/", api::router().await);
+        .nest("/", api::router().await)
+        .layer(middleware::from_fn(revolt_config::proxy::resolve_client_ip));
 
     // Configure TCP listener and bind
     tracing::info!("Listening on 0.0.0.0:14705");
     tracing::info!("Play around with the API: http://localhost:14705/scalar");
     let address = SocketAddr::from((Ipv4Addr::UNSPECIFIED, 14705));
     let listener = TcpListener::bind(&address).await?;
-    axum::serve(listener, app.into_make_service()).await
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal());
+
+    // Give in-flight requests until the deadline to complete once asked to stop
+    let deadline = async {
+        shutdown_signal().await;
+        tokio::time::sleep(revolt_config::config().await.shutdown.deadline()).await;
+    };
+
+    tokio::select! {
+        result = server => result,
+        _ = deadline => {
+            tracing::warn!("Shutdown deadline elapsed with requests still in flight");
+            Ok(())
+        }
+    }
+}
+
+/// Wait until the process is asked to stop
+async fn shutdown_signal() {
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {},
+        _ = terminate.recv() => {},
+    }
 }
@@ -4,17 +4,21 @@ use mime::Mime;
 use regex::Regex;
 use reqwest::{
     header::{self, CONTENT_TYPE},
-    redirect, Client, Response,
+    redirect, Client, Response, Url,
 };
-use guilderia_config::report_internal_error;
+use guilderia_config::{config, report_internal_error};
 use guilderia_files::{create_thumbnail, decode_image, image_size_vec, is_valid_image, video_size};
 use guilderia_models::v0::{Embed, Image, ImageSize, Video};
 use guilderia_result::{create_error, Error, Result};
+use serde::de::DeserializeOwned;
 use std::{
     io::{Cursor, Write},
     time::Duration,
 };
 
+/// End of the document head
+const HEAD_END: &[u8] = b"</head";
+
 lazy_static! {
     /// Request client
     static ref CLIENT: Client = reqwest::Client::builder()
@@ -53,6 +57,16 @@ lazy_static! {
         .time_to_live(Duration::from_secs(60)) // For up to 1 minute
         .build();
 
+    /// Regex for matching a single language tag
+    static ref RE_LANGUAGE_TAG: Regex = Regex::new("^[a-zA-Z]{2,8}(?:-[a-zA-Z0-9]{1,8}){0,3}$").expect("valid regex");
+
+    /// Hosts which have been seen varying their response by language
+    static ref LOCALISED_HOSTS: moka::future::Cache<String, ()> = moka::future::Cache::builder()
+        // TODO config
+        .max_capacity(10_000)
+        .time_to_live(Duration::from_secs(60 * 60 * 24)) // For up to a day
+        .build();
+
     /// Cache for embed results
     static ref EMBED_CACHE: moka::future::Cache<String, Embed> = moka::future::Cache::builder()
         // TODO config
@@ -198,8 +212,28 @@ impl Request {
         }
     }
 
+    /// Key to cache an embed under, embeds for hosts that vary by language are cached per language
+    fn embed_cache_key(url: &str, locale: Option<&str>) -> String {
+        if let (Some(locale), Some(host)) = (locale, Request::host(url)) {
+            if LOCALISED_HOSTS.contains_key(&host) {
+                return format!("{locale}:{url}");
+            }
+        }
+
+        url.to_owned()
+    }
+
+    /// Extract the host from a URL
+    fn host(url: &str) -> Option<String> {
+        Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(ToOwned::to_owned))
+    }
+
     /// Generate embed for a given URL
-    pub async fn generate_embed(mut url: String) -> Result<Embed> {
+    pub async fn generate_embed(mut url: String, locale: Option<String>) -> Result<Embed> {
+        let locale = locale.filter(|locale| RE_LANGUAGE_TAG.is_match(locale));
+
         // Re-map certain links for better metadata generation
         if RE_URL_NEW_REDDIT.is_match(&url) {
             url = RE_URL_NEW_REDDIT
@@ -209,10 +243,33 @@ impl Request {
         }
 
         // Generate the actual embed
-        if let Some(hit) = EMBED_CACHE.get(&url).await {
+        let cache_key = Request::embed_cache_key(&url, locale.as_deref());
+        if let Some(hit) = EMBED_CACHE.get(&cache_key).await {
             Ok(hit)
+        } else if let Some(metadata) = crate::social::create_social_embed(&url).await {
+            let embed = Embed::Website(metadata);
+            EMBED_CACHE.insert(url.to_owned(), embed.clone()).await;
+            Ok(embed)
         } else {
-            let request = Request::new(&url).await?;
+            let request = Request::new_with_locale(&url, locale.as_deref()).await?;
+
+            // Remember hosts that serve different content depending on language
+            let localised = request
+                .response
+                .headers()
+                .get_all(header::VARY)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .any(|value| value.to_ascii_lowercase().contains("accept-language"));
+
+            let cache_key = match (localised, &locale, Request::host(&url)) {
+                (true, Some(locale), Some(host)) => {
+                    LOCALISED_HOSTS.insert(host, ()).await;
+                    format!("{locale}:{url}")
+                }
+                _ => cache_key,
+            };
+
             let embed = match (request.mime.type_(), request.mime.subtype()) {
                 (_, mime::HTML) => {
                     let content_type = request
@@ -230,7 +287,12 @@ impl Request {
                     let encoding =
                         Encoding::for_label(encoding_name.as_bytes()).unwrap_or(&UTF_8_INIT);
 
-                    let bytes = report_internal_error!(request.response.bytes().await)?;
+                    let bytes = Request::read_document(
+                        request.response,
+                        config().await.embeds.max_document_size,
+                    )
+                    .await?;
+
                     let (text, _, _) = encoding.decode(&bytes);
 
                     crate::website_embed::create_website_embed(&url, &text)
@@ -249,13 +311,18 @@ impl Request {
                 _ => Embed::None,
             };
 
-            EMBED_CACHE.insert(url.to_owned(), embed.clone()).await;
+            EMBED_CACHE.insert(cache_key, embed.clone()).await;
             Ok(embed)
         }
     }
 
     /// Send a new request to a service
     pub async fn new(url: &str) -> Result<Request> {
+        Request::new_with_locale(url, None).await
+    }
+
+    /// Send a new request to a service, preferring content in the given language
+    pub async fn new_with_locale(url: &str, locale: Option<&str>) -> Result<Request> {
         let response = CLIENT
             .get(url)
             .header(
@@ -266,7 +333,13 @@ impl Request {
                     "Mozilla/5.0 (compatible; January/2.0; +https://github.com/guilderia/backend)"
                 },
             )
-            .header("Accept-Language", "en-US,en;q=0.5")
+            .header(
+                "Accept-Language",
+                match locale {
+                    Some(locale) => format!("{locale},en-US;q=0.7,en;q=0.5"),
+                    None => "en-US,en;q=0.5".to_owned(),
+                },
+            )
             .send()
             .await
             .map_err(|_| create_error!(ProxyError))?;
@@ -290,6 +363,56 @@ impl Request {
         Ok(Request { response, mime })
     }
 
+    /// Fetch JSON from an API, optionally authenticating with a bearer token
+    pub async fn fetch_json<T: DeserializeOwned>(
+        url: &str,
+        bearer_token: Option<&str>,
+    ) -> Result<T> {
+        let mut request = CLIENT.get(url).header(
+            "User-Agent",
+            "Mozilla/5.0 (compatible; January/2.0; +https://github.com/guilderia/backend)",
+        );
+
+        if let Some(token) = bearer_token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|_| create_error!(ProxyError))?;
+
+        if !response.status().is_success() {
+            tracing::error!("{:?}", response);
+            return Err(create_error!(ProxyError));
+        }
+
+        response.json().await.map_err(|_| create_error!(ProxyError))
+    }
+
+    /// Read an HTML document up to a maximum size
+    ///
+    /// Stops early once the end of the head has been reached as all
+    /// metadata we care about lives there.
+    async fn read_document(mut response: Response, limit: usize) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        while let Some(chunk) = report_internal_error!(response.chunk().await)? {
+            let start = bytes.len().saturating_sub(HEAD_END.len());
+            let remaining = limit.saturating_sub(bytes.len());
+            bytes.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+
+            if bytes.len() >= limit
+                || bytes[start..]
+                    .windows(HEAD_END.len())
+                    .any(|window| window.eq_ignore_ascii_case(HEAD_END))
+            {
+                break;
+            }
+        }
+
+        Ok(bytes)
+    }
+
     /// Check if something exists
     pub async fn exists(url: &str) -> bool {
         if let Ok(response) = CLIENT.head(url).send().await {
@@ -0,0 +1,204 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use guilderia_models::v0::WebsiteMetadata;
+use reqwest::Url;
+
+/// Maximum length of a URL we are willing to embed
+const MAX_URL_LENGTH: usize = 2048;
+
+lazy_static! {
+    /// Regex for matching elements whose content is never useful for metadata
+    static ref RE_NON_CONTENT: Regex = Regex::new("(?is)<script\\b.*?</script\\s*>|<style\\b.*?</style\\s*>|<noscript\\b.*?</noscript\\s*>|<template\\b.*?</template\\s*>|<!--.*?-->").expect("valid regex");
+
+    /// Regex for matching any remaining markup in text fields
+    static ref RE_TAG: Regex = Regex::new("(?s)</?[a-zA-Z!][^>]*>").expect("valid regex");
+
+    /// Regex for matching runs of whitespace
+    static ref RE_WHITESPACE: Regex = Regex::new("[ \\t\\r\\f\\v]+").expect("valid regex");
+
+    /// Regex for matching CSS colours we allow through
+    static ref RE_COLOUR: Regex = Regex::new("^(?:#[0-9a-fA-F]{3,8}|[a-zA-Z]{1,20}|(?:rgb|rgba|hsl|hsla)\\([0-9.,%\\s/]{1,32}\\))$").expect("valid regex");
+}
+
+/// Remove scripts, styles and comments from a document before it is parsed
+pub fn strip_non_content(document: &str) -> String {
+    RE_NON_CONTENT.replace_all(document, "").into_owned()
+}
+
+/// Clean up a text field
+///
+/// Entities have already been decoded exactly once by the HTML parser, they
+/// must not be decoded again as that would let escaped markup through.
+pub fn sanitize_text(text: &str, multiline: bool) -> Option<String> {
+    let text = RE_TAG.replace_all(text, "");
+    let text: String = text
+        .chars()
+        .filter(|c| !c.is_control() || (multiline && *c == '\n'))
+        .collect();
+
+    let text = if multiline {
+        text.lines()
+            .map(|line| RE_WHITESPACE.replace_all(line, " ").trim().to_owned())
+            .collect::<Vec<String>>()
+            .join("\n")
+    } else {
+        RE_WHITESPACE.replace_all(&text, " ").into_owned()
+    };
+
+    let text = text.trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_owned())
+    }
+}
+
+/// Resolve a URL against the page it was found on, only allowing http(s) URLs
+pub fn sanitize_url(url: &str, base: Option<&Url>) -> Option<String> {
+    let url = url.trim();
+    if url.is_empty() || url.len() > MAX_URL_LENGTH {
+        return None;
+    }
+
+    let url = match base {
+        Some(base) => base.join(url).ok()?,
+        None => Url::parse(url).ok()?,
+    };
+
+    if matches!(url.scheme(), "http" | "https") {
+        Some(url.to_string()).filter(|url| url.len() <= MAX_URL_LENGTH)
+    } else {
+        None
+    }
+}
+
+/// Only allow simple CSS colours
+pub fn sanitize_colour(colour: &str) -> Option<String> {
+    let colour = colour.trim();
+    if RE_COLOUR.is_match(colour) {
+        Some(colour.to_owned())
+    } else {
+        None
+    }
+}
+
+/// Sanitize all user-controlled fields of website metadata
+pub fn sanitize_metadata(metadata: &mut WebsiteMetadata) {
+    let base = metadata
+        .original_url
+        .as_deref()
+        .and_then(|url| Url::parse(url).ok());
+
+    let base = base.as_ref();
+    metadata.url = metadata
+        .url
+        .as_deref()
+        .and_then(|url| sanitize_url(url, base));
+
+    metadata.original_url = metadata
+        .original_url
+        .as_deref()
+        .and_then(|url| sanitize_url(url, None));
+
+    metadata.title = metadata
+        .title
+        .as_deref()
+        .and_then(|title| sanitize_text(title, false));
+
+    metadata.description = metadata
+        .description
+        .as_deref()
+        .and_then(|description| sanitize_text(description, true));
+
+    metadata.site_name = metadata
+        .site_name
+        .as_deref()
+        .and_then(|site_name| sanitize_text(site_name, false));
+
+    metadata.icon_url = metadata
+        .icon_url
+        .as_deref()
+        .and_then(|url| sanitize_url(url, base));
+
+    metadata.colour = metadata.colour.as_deref().and_then(sanitize_colour);
+
+    if let Some(image) = metadata.image.take() {
+        metadata.image = sanitize_url(&image.url, base).map(|url| {
+            let mut image = image;
+            image.url = url;
+            image
+        });
+    }
+
+    if let Some(video) = metadata.video.take() {
+        metadata.video = sanitize_url(&video.url, base).map(|url| {
+            let mut video = video;
+            video.url = url;
+            video
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Url;
+
+    use super::{sanitize_colour, sanitize_text, sanitize_url, strip_non_content};
+
+    #[test]
+    fn strips_scripts_and_styles() {
+        let document =
+            "<head><SCRIPT>alert(1)</script ><style>a{}</style><!-- x --><title>Hi</title></head>";
+        assert_eq!(
+            strip_non_content(document),
+            "<head><title>Hi</title></head>"
+        );
+    }
+
+    #[test]
+    fn sanitizes_text() {
+        assert_eq!(
+            sanitize_text("  <script>alert(1)</script> Hello\u{0}   world ", false),
+            Some("alert(1) Hello world".to_owned())
+        );
+
+        assert_eq!(
+            sanitize_text("line  one\n\n line two", true),
+            Some("line one\n\nline two".to_owned())
+        );
+
+        assert_eq!(
+            sanitize_text("&lt;b&gt;", false),
+            Some("&lt;b&gt;".to_owned())
+        );
+        assert_eq!(sanitize_text(" \u{7} ", false), None);
+    }
+
+    #[test]
+    fn sanitizes_urls() {
+        let base = Url::parse("https://example.com/page/").unwrap();
+
+        assert_eq!(
+            sanitize_url("/image.png", Some(&base)),
+            Some("https://example.com/image.png".to_owned())
+        );
+
+        assert_eq!(sanitize_url("javascript:alert(1)", Some(&base)), None);
+        assert_eq!(sanitize_url("data:text/html,hi", None), None);
+        assert_eq!(sanitize_url(&"a".repeat(4096), Some(&base)), None);
+    }
+
+    #[test]
+    fn sanitizes_colours() {
+        assert_eq!(sanitize_colour(" #FF424F "), Some("#FF424F".to_owned()));
+        assert_eq!(
+            sanitize_colour("rebeccapurple"),
+            Some("rebeccapurple".to_owned())
+        );
+        assert_eq!(
+            sanitize_colour("rgb(1, 2, 3)"),
+            Some("rgb(1, 2, 3)".to_owned())
+        );
+        assert_eq!(sanitize_colour("red;background:url(x)"), None);
+    }
+}
@@ -0,0 +1,471 @@
+use iso8601_timestamp::{Duration, Timestamp};
+use lazy_static::lazy_static;
+use regex::Regex;
+use guilderia_config::config;
+use guilderia_models::v0::{
+    Image, ImageSize, Post, PostAuthor, PostStats, Special, Video, WebsiteMetadata,
+};
+use guilderia_result::{create_error, Result};
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::requests::Request;
+
+lazy_static! {
+    /// Regex for matching posts on X (formerly Twitter)
+    static ref RE_X_POST: Regex = Regex::new("^(?:https?://)?(?:www\\.|mobile\\.)?(?:twitter|x)\\.com/(?:\\w+)/status/(\\d+)").expect("valid regex");
+
+    /// Regex for matching posts on Bluesky
+    static ref RE_BLUESKY_POST: Regex = Regex::new("^(?:https?://)?(?:www\\.)?bsky\\.app/profile/([\\w.:-]+)/post/([a-z0-9]+)").expect("valid regex");
+}
+
+/// Post as returned by the X API
+#[derive(Deserialize)]
+struct XResponse {
+    data: XPost,
+    #[serde(default)]
+    includes: XIncludes,
+}
+
+#[derive(Deserialize)]
+struct XPost {
+    text: String,
+    author_id: String,
+    created_at: Option<String>,
+    public_metrics: Option<XMetrics>,
+    attachments: Option<XAttachments>,
+}
+
+#[derive(Deserialize)]
+struct XMetrics {
+    reply_count: usize,
+    retweet_count: usize,
+    quote_count: usize,
+    like_count: usize,
+    impression_count: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct XAttachments {
+    #[serde(default)]
+    media_keys: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct XIncludes {
+    #[serde(default)]
+    users: Vec<XUser>,
+    #[serde(default)]
+    media: Vec<XMedia>,
+}
+
+#[derive(Deserialize)]
+struct XUser {
+    id: String,
+    name: String,
+    username: String,
+    profile_image_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct XMedia {
+    media_key: String,
+    #[serde(rename = "type")]
+    kind: String,
+    url: Option<String>,
+    width: Option<usize>,
+    height: Option<usize>,
+    #[serde(default)]
+    variants: Vec<XMediaVariant>,
+}
+
+#[derive(Deserialize)]
+struct XMediaVariant {
+    content_type: String,
+    url: String,
+    bit_rate: Option<usize>,
+}
+
+/// Post as returned by an FxTwitter compatible API
+#[derive(Deserialize)]
+struct FxResponse {
+    tweet: FxPost,
+}
+
+#[derive(Deserialize)]
+struct FxPost {
+    text: String,
+    created_timestamp: Option<i64>,
+    author: FxAuthor,
+    replies: Option<usize>,
+    retweets: Option<usize>,
+    likes: Option<usize>,
+    views: Option<usize>,
+    media: Option<FxMedia>,
+}
+
+#[derive(Deserialize)]
+struct FxAuthor {
+    name: String,
+    screen_name: String,
+    avatar_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FxMedia {
+    #[serde(default)]
+    photos: Vec<FxMediaItem>,
+    #[serde(default)]
+    videos: Vec<FxMediaItem>,
+}
+
+#[derive(Deserialize)]
+struct FxMediaItem {
+    url: String,
+    width: usize,
+    height: usize,
+}
+
+/// Post thread as returned by the Bluesky AppView API
+#[derive(Deserialize)]
+struct BlueskyThreadResponse {
+    thread: BlueskyThread,
+}
+
+#[derive(Deserialize)]
+struct BlueskyThread {
+    post: BlueskyPost,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlueskyPost {
+    author: BlueskyAuthor,
+    record: BlueskyRecord,
+    embed: Option<BlueskyEmbed>,
+    reply_count: Option<usize>,
+    repost_count: Option<usize>,
+    quote_count: Option<usize>,
+    like_count: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlueskyAuthor {
+    handle: String,
+    display_name: Option<String>,
+    avatar: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlueskyRecord {
+    text: String,
+    created_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlueskyEmbed {
+    #[serde(default)]
+    images: Vec<BlueskyImage>,
+    /// Thumbnail of an attached video
+    thumbnail: Option<String>,
+    aspect_ratio: Option<BlueskyAspectRatio>,
+    /// Media attached alongside a quoted post
+    media: Option<Box<BlueskyEmbed>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlueskyImage {
+    fullsize: String,
+    aspect_ratio: Option<BlueskyAspectRatio>,
+}
+
+#[derive(Deserialize)]
+struct BlueskyAspectRatio {
+    width: usize,
+    height: usize,
+}
+
+impl BlueskyEmbed {
+    /// Collect images attached to the post, using the thumbnail for videos
+    fn into_images(self) -> Vec<Image> {
+        if let Some(media) = self.media {
+            return media.into_images();
+        }
+
+        let image = |url: String, aspect_ratio: Option<BlueskyAspectRatio>| Image {
+            url,
+            width: aspect_ratio
+                .as_ref()
+                .map(|ratio| ratio.width)
+                .unwrap_or_default(),
+            height: aspect_ratio.map(|ratio| ratio.height).unwrap_or_default(),
+            size: ImageSize::Large,
+        };
+
+        match self.thumbnail {
+            Some(thumbnail) => vec![image(thumbnail, self.aspect_ratio)],
+            None => self
+                .images
+                .into_iter()
+                .map(|img| image(img.fullsize, img.aspect_ratio))
+                .collect(),
+        }
+    }
+}
+
+/// Fetch a post using the official X API
+async fn fetch_x_post(id: &str, bearer_token: &str) -> Result<Post> {
+    let XResponse { data, includes } = Request::fetch_json(
+        &format!("https://api.twitter.com/2/tweets/{id}?expansions=author_id,attachments.media_keys&tweet.fields=created_at,public_metrics&user.fields=name,username,profile_image_url&media.fields=url,width,height,variants"),
+        Some(bearer_token),
+    )
+    .await?;
+
+    let author = includes
+        .users
+        .into_iter()
+        .find(|user| user.id == data.author_id);
+
+    let media_keys = data
+        .attachments
+        .map(|attachments| attachments.media_keys)
+        .unwrap_or_default();
+
+    let media: Vec<XMedia> = includes
+        .media
+        .into_iter()
+        .filter(|media| media_keys.contains(&media.media_key))
+        .collect();
+
+    let video = media
+        .iter()
+        .filter(|media| media.kind == "video" || media.kind == "animated_gif")
+        .find_map(|media| {
+            media
+                .variants
+                .iter()
+                .filter(|variant| variant.content_type == "video/mp4")
+                .max_by_key(|variant| variant.bit_rate.unwrap_or_default())
+                .map(|variant| Video {
+                    url: variant.url.clone(),
+                    width: media.width.unwrap_or_default(),
+                    height: media.height.unwrap_or_default(),
+                })
+        });
+
+    let images = media
+        .into_iter()
+        .filter(|media| media.kind == "photo")
+        .filter_map(|media| {
+            Some(Image {
+                url: media.url?,
+                width: media.width.unwrap_or_default(),
+                height: media.height.unwrap_or_default(),
+                size: ImageSize::Large,
+            })
+        })
+        .collect();
+
+    Ok(Post {
+        author: author
+            .map(|user| PostAuthor {
+                name: user.name,
+                handle: user.username,
+                avatar: user.profile_image_url,
+            })
+            .unwrap_or_else(|| PostAuthor {
+                name: String::new(),
+                handle: String::new(),
+                avatar: None,
+            }),
+        text: data.text,
+        created_at: data.created_at.as_deref().and_then(Timestamp::parse),
+        images,
+        video,
+        stats: data
+            .public_metrics
+            .map(|metrics| PostStats {
+                replies: Some(metrics.reply_count),
+                reposts: Some(metrics.retweet_count),
+                quotes: Some(metrics.quote_count),
+                likes: Some(metrics.like_count),
+                views: metrics.impression_count,
+            })
+            .unwrap_or_else(|| PostStats {
+                replies: None,
+                reposts: None,
+                quotes: None,
+                likes: None,
+                views: None,
+            }),
+    })
+}
+
+/// Fetch a post using an FxTwitter compatible API
+async fn fetch_fx_post(id: &str, api: &str) -> Result<Post> {
+    let FxResponse { tweet } = Request::fetch_json(&format!("{api}/status/{id}"), None).await?;
+    let (photos, videos) = tweet
+        .media
+        .map(|media| (media.photos, media.videos))
+        .unwrap_or_default();
+
+    Ok(Post {
+        author: PostAuthor {
+            name: tweet.author.name,
+            handle: tweet.author.screen_name,
+            avatar: tweet.author.avatar_url,
+        },
+        text: tweet.text,
+        created_at: tweet
+            .created_timestamp
+            .and_then(|seconds| Timestamp::UNIX_EPOCH.checked_add(Duration::seconds(seconds))),
+        images: photos
+            .into_iter()
+            .map(|photo| Image {
+                url: photo.url,
+                width: photo.width,
+                height: photo.height,
+                size: ImageSize::Large,
+            })
+            .collect(),
+        video: videos.into_iter().next().map(|video| Video {
+            url: video.url,
+            width: video.width,
+            height: video.height,
+        }),
+        stats: PostStats {
+            replies: tweet.replies,
+            reposts: tweet.retweets,
+            quotes: None,
+            likes: tweet.likes,
+            views: tweet.views,
+        },
+    })
+}
+
+/// Fetch a post using the Bluesky AppView API
+async fn fetch_bluesky_post(actor: &str, id: &str, api: &str) -> Result<Post> {
+    let url = Url::parse_with_params(
+        &format!("{api}/xrpc/app.bsky.feed.getPostThread"),
+        &[
+            ("uri", format!("at://{actor}/app.bsky.feed.post/{id}")),
+            ("depth", "0".to_owned()),
+            ("parentHeight", "0".to_owned()),
+        ],
+    )
+    .map_err(|_| create_error!(ProxyError))?;
+
+    let BlueskyThreadResponse {
+        thread: BlueskyThread { post },
+    } = Request::fetch_json(url.as_str(), None).await?;
+
+    Ok(Post {
+        author: PostAuthor {
+            name: post
+                .author
+                .display_name
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| post.author.handle.clone()),
+            handle: post.author.handle,
+            avatar: post.author.avatar,
+        },
+        text: post.record.text,
+        created_at: post.record.created_at.as_deref().and_then(Timestamp::parse),
+        images: post
+            .embed
+            .map(BlueskyEmbed::into_images)
+            .unwrap_or_default(),
+        video: None,
+        stats: PostStats {
+            replies: post.reply_count,
+            reposts: post.repost_count,
+            quotes: post.quote_count,
+            likes: post.like_count,
+            views: None,
+        },
+    })
+}
+
+/// Build website metadata around a post
+fn into_metadata(
+    url: &str,
+    site_name: &str,
+    colour: &str,
+    special: Special,
+    post: &Post,
+) -> WebsiteMetadata {
+    let mut metadata = WebsiteMetadata {
+        url: Some(url.to_owned()),
+        original_url: Some(url.to_owned()),
+        special: Some(special),
+        title: Some(format!("{} (@{})", post.author.name, post.author.handle)),
+        description: Some(post.text.clone()),
+        image: post.images.first().cloned(),
+        video: post.video.clone(),
+        site_name: Some(site_name.to_owned()),
+        icon_url: post.author.avatar.clone(),
+        colour: Some(colour.to_owned()),
+    };
+
+    crate::sanitize::sanitize_metadata(&mut metadata);
+    metadata.truncate();
+    metadata
+}
+
+/// Generate an embed for posts on X or Bluesky using their APIs
+///
+/// Returns None if the URL is not a supported post or the API is not configured
+pub async fn create_social_embed(url: &str) -> Option<WebsiteMetadata> {
+    let config = config().await.embeds;
+
+    if let Some(captures) = RE_X_POST.captures(url) {
+        let x = config.x;
+        if !x.enabled {
+            return None;
+        }
+
+        let id = &captures[1];
+        let result = if !x.bearer_token.is_empty() {
+            fetch_x_post(id, &x.bearer_token).await
+        } else if !x.fallback_api.is_empty() {
+            fetch_fx_post(id, &x.fallback_api).await
+        } else {
+            return None;
+        };
+
+        let post = result
+            .inspect_err(|err| tracing::error!("Failed to fetch X post {id}: {err:?}"))
+            .ok()?;
+
+        let special = Special::Twitter {
+            id: id.to_owned(),
+            post: post.clone(),
+        };
+
+        Some(into_metadata(url, "X", "#1DA1F2", special, &post))
+    } else if let Some(captures) = RE_BLUESKY_POST.captures(url) {
+        let bluesky = config.bluesky;
+        if !bluesky.enabled || bluesky.api.is_empty() {
+            return None;
+        }
+
+        let id = &captures[2];
+        let post = fetch_bluesky_post(&captures[1], id, &bluesky.api)
+            .await
+            .inspect_err(|err| tracing::error!("Failed to fetch Bluesky post {id}: {err:?}"))
+            .ok()?;
+
+        let special = Special::Bluesky {
+            id: id.to_owned(),
+            post: post.clone(),
+        };
+
+        Some(into_metadata(url, "Bluesky", "#1185FE", special, &post))
+    } else {
+        None
+    }
+}
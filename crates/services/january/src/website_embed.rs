@@ -4,13 +4,15 @@ use lazy_static::lazy_static;
 use regex::Regex;
 use guilderia_models::v0::{
     BandcampType, Image, ImageSize, LightspeedType, Special, TwitchType, Video, WebsiteMetadata,
+    YouTubePlaylist, YouTubeType,
 };
+use reqwest::Url;
 use scraper::{Html, Selector};
 
 /// Create website metadata from URL and document
 pub async fn create_website_embed(original_url: &str, document: &str) -> Option<WebsiteMetadata> {
     let (mut meta, mut link) = {
-        let document = Html::parse_document(document);
+        let document = Html::parse_document(&crate::sanitize::strip_non_content(document));
 
         // create selectors
         let meta_selector = Selector::parse("meta").ok()?;
@@ -140,6 +142,9 @@ pub async fn create_website_embed(original_url: &str, document: &str) -> Option<
         special: None,
     };
 
+    // remove unsafe values before anything else touches them
+    crate::sanitize::sanitize_metadata(&mut metadata);
+
     // populate extra metadata for popular websites
     populate_special(original_url.to_owned(), &mut metadata).await;
 
@@ -188,10 +193,89 @@ pub async fn create_website_embed(original_url: &str, document: &str) -> Option<
     }
 }
 
+/// Parse a YouTube start time such as `90`, `90s` or `1h2m3s` into seconds
+fn parse_youtube_timestamp(timestamp: &str) -> Option<usize> {
+    if let Ok(seconds) = timestamp.parse() {
+        return Some(seconds);
+    }
+
+    let mut seconds = 0;
+    let mut value = String::new();
+    for c in timestamp.chars() {
+        if c.is_ascii_digit() {
+            value.push(c);
+        } else {
+            let unit = match c {
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => return None,
+            };
+
+            seconds += value.parse::<usize>().ok()? * unit;
+            value.clear();
+        }
+    }
+
+    value.is_empty().then_some(seconds)
+}
+
+/// Parse a YouTube link into its video, start time and playlist context
+fn parse_youtube(url: &str) -> Option<Special> {
+    let url = Url::parse(url)
+        .or_else(|_| Url::parse(&format!("https://{url}")))
+        .ok()?;
+
+    let host = url.host_str()?;
+    let host = host
+        .strip_prefix("www.")
+        .or_else(|| host.strip_prefix("m."))
+        .unwrap_or(host);
+
+    let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    let mut segments = url.path_segments()?.filter(|segment| !segment.is_empty());
+
+    let (id, content_type) = match host {
+        "youtu.be" => (segments.next()?.to_owned(), YouTubeType::Video),
+        "youtube.com" | "music.youtube.com" | "youtube-nocookie.com" => match segments.next()? {
+            "watch" => (query.get("v")?.to_owned(), YouTubeType::Video),
+            "shorts" => (segments.next()?.to_owned(), YouTubeType::Short),
+            "live" => (segments.next()?.to_owned(), YouTubeType::Live),
+            "embed" | "v" => (segments.next()?.to_owned(), YouTubeType::Video),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return None;
+    }
+
+    // Start time may also be given in the fragment (e.g. #t=90)
+    let timestamp = query
+        .get("t")
+        .or_else(|| query.get("start"))
+        .cloned()
+        .or_else(|| url.fragment()?.strip_prefix("t=").map(ToOwned::to_owned));
+
+    Some(Special::YouTube {
+        id,
+        start: timestamp.as_deref().and_then(parse_youtube_timestamp),
+        timestamp,
+        playlist: query.get("list").map(|list| YouTubePlaylist {
+            id: list.to_owned(),
+            index: query.get("index").and_then(|index| index.parse().ok()),
+        }),
+        content_type,
+    })
+}
+
 pub async fn populate_special(original_url: String, metadata: &mut WebsiteMetadata) {
     lazy_static! {
-        static ref RE_YOUTUBE: Regex = Regex::new("^(?:(?:https?:)?//)?(?:(?:www|m)\\.)?(?:(?:youtube\\.com|youtu.be))(?:/(?:[\\w\\-]+\\?v=|embed/|v/)?)([\\w\\-]+)(?:\\S+)?$").unwrap();
-
         static ref RE_LIGHTSPEED: Regex = Regex::new("^(?:https?://)?(?:[\\w]+\\.)?lightspeed\\.tv/([a-z0-9_]{4,25})").unwrap();
 
         static ref RE_TWITCH: Regex = Regex::new("^(?:https?://)?(?:www\\.|go\\.)?twitch\\.tv/([a-z0-9_]+)($|\\?)").unwrap();
@@ -219,16 +303,10 @@ pub async fn populate_special(original_url: String, metadata: &mut WebsiteMetada
         Some(Special::Streamable {
             id: captures[1].to_string(),
         })
-    } else if let Some(captures) = RE_YOUTUBE.captures_iter(url).next() {
-        let id = captures[1].to_string();
-
-        lazy_static! {
-            static ref RE_TIMESTAMP: Regex = Regex::new("(?:\\?|&)(?:t|start)=([\\w]+)").unwrap();
-        }
-
+    } else if let Some(special) = parse_youtube(url) {
         // YouTube now blocks datacentre IPs from fetching information
         // This is a fallback to prevent the embed from looking weird
-        if metadata.video.is_none() {
+        if let (Special::YouTube { id, .. }, None) = (&special, &metadata.video) {
             metadata.title.replace("YouTube".to_owned());
             metadata.description.take();
             metadata.colour.take();
@@ -246,17 +324,7 @@ pub async fn populate_special(original_url: String, metadata: &mut WebsiteMetada
             }
         }
 
-        if let Some(timestamp_captures) = RE_TIMESTAMP.captures_iter(url).next() {
-            Some(Special::YouTube {
-                id,
-                timestamp: Some(timestamp_captures[1].to_string()),
-            })
-        } else {
-            Some(Special::YouTube {
-                id,
-                timestamp: None,
-            })
-        }
+        Some(special)
     } else if let Some(captures) = RE_LIGHTSPEED.captures_iter(url).next() {
         Some(Special::Lightspeed {
             id: captures[1].to_string(),
@@ -333,3 +401,112 @@ pub async fn populate_special(original_url: String, metadata: &mut WebsiteMetada
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use guilderia_models::v0::{Special, YouTubePlaylist, YouTubeType};
+
+    use super::{parse_youtube, parse_youtube_timestamp};
+
+    fn youtube(
+        id: &str,
+        timestamp: Option<&str>,
+        start: Option<usize>,
+        playlist: Option<(&str, Option<usize>)>,
+        content_type: YouTubeType,
+    ) -> Option<Special> {
+        Some(Special::YouTube {
+            id: id.to_owned(),
+            timestamp: timestamp.map(ToOwned::to_owned),
+            start,
+            playlist: playlist.map(|(id, index)| YouTubePlaylist {
+                id: id.to_owned(),
+                index,
+            }),
+            content_type,
+        })
+    }
+
+    #[test]
+    fn parses_youtube_timestamps() {
+        assert_eq!(parse_youtube_timestamp("90"), Some(90));
+        assert_eq!(parse_youtube_timestamp("90s"), Some(90));
+        assert_eq!(parse_youtube_timestamp("1m30s"), Some(90));
+        assert_eq!(parse_youtube_timestamp("1h2m3s"), Some(3723));
+        assert_eq!(parse_youtube_timestamp("1m30"), None);
+        assert_eq!(parse_youtube_timestamp("abc"), None);
+    }
+
+    #[test]
+    fn parses_youtube_links() {
+        assert_eq!(
+            parse_youtube("https://www.youtube.com/watch?v=dQw4w9WgXcQ"),
+            youtube("dQw4w9WgXcQ", None, None, None, YouTubeType::Video)
+        );
+        assert_eq!(
+            parse_youtube("https://youtu.be/dQw4w9WgXcQ?t=1m30s"),
+            youtube(
+                "dQw4w9WgXcQ",
+                Some("1m30s"),
+                Some(90),
+                None,
+                YouTubeType::Video
+            )
+        );
+        assert_eq!(
+            parse_youtube("https://m.youtube.com/watch?v=dQw4w9WgXcQ&start=42"),
+            youtube(
+                "dQw4w9WgXcQ",
+                Some("42"),
+                Some(42),
+                None,
+                YouTubeType::Video
+            )
+        );
+        assert_eq!(
+            parse_youtube("youtube.com/watch?v=dQw4w9WgXcQ#t=15"),
+            youtube(
+                "dQw4w9WgXcQ",
+                Some("15"),
+                Some(15),
+                None,
+                YouTubeType::Video
+            )
+        );
+        assert_eq!(
+            parse_youtube(
+                "https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI&index=3"
+            ),
+            youtube(
+                "dQw4w9WgXcQ",
+                None,
+                None,
+                Some(("PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI", Some(3))),
+                YouTubeType::Video
+            )
+        );
+        assert_eq!(
+            parse_youtube("https://www.youtube.com/shorts/aqz-KE-bpKQ"),
+            youtube("aqz-KE-bpKQ", None, None, None, YouTubeType::Short)
+        );
+        assert_eq!(
+            parse_youtube("https://www.youtube.com/live/jfKfPfyJRdk?si=abc"),
+            youtube("jfKfPfyJRdk", None, None, None, YouTubeType::Live)
+        );
+        assert_eq!(
+            parse_youtube("https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ"),
+            youtube("dQw4w9WgXcQ", None, None, None, YouTubeType::Video)
+        );
+        assert_eq!(
+            parse_youtube(
+                "https://www.youtube.com/playlist?list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI"
+            ),
+            None
+        );
+        assert_eq!(parse_youtube("https://www.youtube.com/@channel"), None);
+        assert_eq!(
+            parse_youtube("https://example.com/watch?v=dQw4w9WgXcQ"),
+            None
+        );
+    }
+}